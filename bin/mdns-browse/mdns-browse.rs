@@ -13,55 +13,118 @@
 // limitations under the License.
 
 use std::env;
+#[cfg(feature = "capture")]
+use std::fs::File;
 use std::io::Error;
-use std::{thread, time};
+use std::time::{Duration, Instant};
 
 use cybergarage::log::Logger;
-use mdns::{Client, Query};
+#[cfg(feature = "capture")]
+use mdns::PcapWriter;
+use mdns::{BrowseFormat, BrowseFormatter, Client, Query, ServiceEvent, LOCAL_DOMAIN};
 
 fn usages() {
     println!("Usage: mdns-browse");
     println!(" -h : Print this message");
     println!(" -v : Enable debug output");
+    println!(" -f <dns-sd|avahi|avahi-parsable> : Print events in the format of the browse tool");
+    println!(" -H <host> : Print only the services offered by the host such as printer.local");
+    println!(" -s : Print the numbers of the services by type and interface at the end");
+    println!(" --dump-packets <file> : Write the received packets to the file in the pcap format");
 }
 
 fn main() -> Result<(), Error> {
-    for arg in env::args() {
+    let mut formatter: Option<BrowseFormatter> = None;
+    let mut host: Option<String> = None;
+    let mut dump_file: Option<String> = None;
+    let mut summary = false;
+    let mut args = env::args().skip(1);
+    while let Some(arg) = args.next() {
         match arg.as_str() {
             "-v" => {
                 Logger::init();
             }
+            "-s" => {
+                summary = true;
+            }
             "-h" => {
                 usages();
                 return Ok(());
             }
+            "-f" => match args.next().as_deref().and_then(BrowseFormat::from_name) {
+                Some(format) => formatter = Some(BrowseFormatter::new(format)),
+                None => {
+                    usages();
+                    return Ok(());
+                }
+            },
+            "-H" => match args.next() {
+                Some(name) => host = Some(name),
+                None => {
+                    usages();
+                    return Ok(());
+                }
+            },
+            "--dump-packets" => match args.next() {
+                Some(file) => dump_file = Some(file),
+                None => {
+                    usages();
+                    return Ok(());
+                }
+            },
             &_ => {}
         }
     }
 
     let mut client = Client::new();
-    let ret = client.start();
-    if ret.is_err() {
-        return ret;
+    let events = client.subscribe();
+    if let Some(file) = &dump_file {
+        #[cfg(feature = "capture")]
+        client.set_capture(Box::new(PcapWriter::new(File::create(file)?)?));
+        #[cfg(not(feature = "capture"))]
+        {
+            eprintln!("--dump-packets {} requires the capture feature", file);
+            return Ok(());
+        }
     }
-    let queries = vec![Query::with("_services._dns-sd._udp", "local")];
+    client.start()?;
+    let queries = vec![Query::service_enumeration()];
     for query in &queries {
-        let res = client.search(query);
-        if res.is_err() {
-            return res;
+        client.search(query)?;
+    }
+
+    if let Some(header) = formatter.as_ref().and_then(|f| f.header()) {
+        println!("{}", header);
+    }
+    let deadline = Instant::now() + Duration::from_secs(10);
+    while let Ok(event) = events.recv_timeout(deadline.saturating_duration_since(Instant::now())) {
+        match (&formatter, &event) {
+            (_, ServiceEvent::ConflictDetected(conflict)) => eprintln!("Conflict : {}", conflict),
+            (Some(formatter), _) => {
+                if let Some(line) = formatter.format_event(&event) {
+                    println!("{}", line);
+                }
+            }
+            (None, _) => {}
         }
     }
 
-    let ten_secs = time::Duration::from_secs(10);
-    thread::sleep(ten_secs);
+    client.stop()?;
 
-    let ret = client.stop();
-    if ret.is_err() {
-        return ret;
+    if let Some(host) = &host {
+        for service in client.services_on_host(host) {
+            println!("Service : {}", service.name());
+        }
+    } else if formatter.is_none() {
+        for service_type in client.service_types(LOCAL_DOMAIN) {
+            println!("Service Type : {}", service_type);
+        }
+        for service in client.services() {
+            println!("Service : {}", service);
+        }
     }
-
-    for service in client.services() {
-        println!("Service : {}", service);
+    if summary {
+        print!("{}", client.summary());
     }
 
     Ok(())
@@ -0,0 +1,86 @@
+// Copyright (C) 2024 Satoshi Konno All rights reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//    http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::env;
+use std::fs::File;
+use std::io::{stdout, Error};
+use std::thread;
+use std::time::Duration;
+
+use cybergarage::log::Logger;
+use mdns::{Client, HexWriter, PcapWriter, Query, QueryHistory};
+
+fn usages() {
+    println!("Usage: mdns-dump");
+    println!(" -h : Print this message");
+    println!(" -v : Enable debug output");
+    println!(" -t <seconds> : Dump the packets for the seconds (default 10)");
+    println!(" --dump-packets <file> : Write the packets to the file in the pcap format instead of the hex lines");
+    println!(" --queries : Print the questions asked by the other hosts in the time");
+}
+
+fn main() -> Result<(), Error> {
+    let mut secs = 10;
+    let mut dump_file: Option<String> = None;
+    let mut queries = false;
+    let mut args = env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "-v" => {
+                Logger::init();
+            }
+            "-h" => {
+                usages();
+                return Ok(());
+            }
+            "-t" => match args.next().and_then(|s| s.parse::<u64>().ok()) {
+                Some(n) => secs = n,
+                None => {
+                    usages();
+                    return Ok(());
+                }
+            },
+            "--dump-packets" => match args.next() {
+                Some(file) => dump_file = Some(file),
+                None => {
+                    usages();
+                    return Ok(());
+                }
+            },
+            "--queries" => {
+                queries = true;
+            }
+            &_ => {}
+        }
+    }
+
+    let mut client = Client::new();
+    match &dump_file {
+        Some(file) => client.set_capture(Box::new(PcapWriter::new(File::create(file)?)?)),
+        None => client.set_capture(Box::new(HexWriter::new(stdout()))),
+    }
+    if queries {
+        client.set_query_history(QueryHistory::new());
+    }
+    client.start()?;
+    client.search(&Query::service_enumeration())?;
+    thread::sleep(Duration::from_secs(secs));
+    client.stop()?;
+
+    for question in client.query_history() {
+        println!("{}", question);
+    }
+
+    Ok(())
+}
@@ -0,0 +1,63 @@
+// Copyright (C) 2024 Satoshi Konno All rights reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//    http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::env;
+use std::error::Error;
+use std::time::Duration;
+
+use mdns::{AsyncClient, Query, ServiceEvent};
+
+const BROWSE_TIMEOUT: Duration = Duration::from_secs(10);
+
+fn usages() {
+    println!("Usage: browse_async [-h] [<service type>]");
+    println!(" -h : Print this message");
+}
+
+// browse_async browses the specified service type on a tokio runtime, and prints the service events as they arrive.
+fn main() -> Result<(), Box<dyn Error>> {
+    let args: Vec<String> = env::args().skip(1).collect();
+    if args.iter().any(|arg| arg == "-h") {
+        usages();
+        return Ok(());
+    }
+    let service_type = args.first().map(String::as_str).unwrap_or("_http._tcp");
+
+    let runtime = tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()?;
+    runtime.block_on(async {
+        let mut client = AsyncClient::new();
+        let mut events = client.subscribe();
+        client.start().await?;
+        client.search(&Query::with(service_type, "local")).await?;
+
+        let browse = async {
+            while let Some(event) = events.recv().await {
+                match event {
+                    ServiceEvent::Added(service) => println!("Added : {}", service),
+                    ServiceEvent::Updated(service) => println!("Updated : {}", service),
+                    ServiceEvent::Removed(service) => println!("Removed : {}", service.name()),
+                    ServiceEvent::ConflictDetected(conflict) => {
+                        eprintln!("Conflict : {}", conflict)
+                    }
+                }
+            }
+        };
+        let _ = tokio::time::timeout(BROWSE_TIMEOUT, browse).await;
+
+        client.stop().await?;
+        Ok(())
+    })
+}
@@ -0,0 +1,62 @@
+// Copyright (C) 2024 Satoshi Konno All rights reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//    http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::env;
+use std::error::Error;
+use std::net::IpAddr;
+use std::thread;
+use std::time::Duration;
+
+use mdns::{Hostname, HostnamePolicy, Responder, ResponderConfig, ServiceBuilder};
+
+fn usages() {
+    println!("Usage: proxy [-h] <host name> <address> <service type> <port>");
+    println!(" -h : Print this message");
+    println!(" e.g. proxy printer 192.168.1.20 _ipp._tcp 631");
+}
+
+// proxy advertises a service on behalf of a device which has no responder of its own, such as a legacy printer,
+// by answering for the host name and the address of the device.
+fn main() -> Result<(), Box<dyn Error>> {
+    let args: Vec<String> = env::args().skip(1).collect();
+    if args.len() < 4 || args.iter().any(|arg| arg == "-h") {
+        usages();
+        return Ok(());
+    }
+    let host = &args[0];
+    let addr = args[1].parse::<IpAddr>()?;
+    let service_type = &args[2];
+    let port = args[3].parse::<u16>()?;
+
+    let mut config = ResponderConfig::new();
+    config.set_advertised_addrs(&[addr]);
+    let mut responder = Responder::with_config(config);
+    responder.set_hostname(Hostname::new(HostnamePolicy::Fixed(host.clone())));
+
+    let service = ServiceBuilder::new(host, service_type).port(port).build()?;
+    responder.start()?;
+    let id = responder.register(service)?;
+    println!(
+        "Proxying : {}.{} at {} ({})",
+        host,
+        service_type,
+        responder.hostname(),
+        addr
+    );
+    thread::sleep(Duration::from_secs(60));
+    responder.unregister(id)?;
+    responder.stop()?;
+
+    Ok(())
+}
@@ -0,0 +1,60 @@
+// Copyright (C) 2024 Satoshi Konno All rights reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//    http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::env;
+use std::error::Error;
+use std::thread;
+use std::time::Duration;
+
+use mdns::{Responder, ServiceBuilder};
+
+fn usages() {
+    println!("Usage: register_service [-h] [<instance name>] [<port>]");
+    println!(" -h : Print this message");
+}
+
+// register_service advertises a web service until the program is stopped after a minute.
+fn main() -> Result<(), Box<dyn Error>> {
+    let args: Vec<String> = env::args().skip(1).collect();
+    if args.iter().any(|arg| arg == "-h") {
+        usages();
+        return Ok(());
+    }
+    let instance_name = args.first().map(String::as_str).unwrap_or("My Web");
+    let port = match args.get(1) {
+        Some(port) => port.parse::<u16>()?,
+        None => 8080,
+    };
+
+    let service = ServiceBuilder::new(instance_name, "_http._tcp")
+        .port(port)
+        .attribute("path", "/")
+        .txtvers(1)
+        .build()?;
+
+    let mut responder = Responder::new();
+    responder.start()?;
+    let id = responder.register(service)?;
+    println!("Registered : {}.{}", instance_name, responder.hostname());
+    for _ in 0..60 {
+        thread::sleep(Duration::from_secs(1));
+        if let Some(state) = responder.state(id) {
+            println!("State : {:?}", state);
+        }
+    }
+    responder.unregister(id)?;
+    responder.stop()?;
+
+    Ok(())
+}
@@ -0,0 +1,81 @@
+// Copyright (C) 2024 Satoshi Konno All rights reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//    http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::env;
+use std::error::Error;
+use std::time::{Duration, Instant};
+
+use mdns::{Client, Query, ServiceEvent};
+
+const BROWSE_TIMEOUT: Duration = Duration::from_secs(5);
+const RESOLVE_TIMEOUT: Duration = Duration::from_secs(3);
+
+fn usages() {
+    println!("Usage: resolve_instance [-h] <service type> [<instance name>]");
+    println!(" -h : Print this message");
+    println!(" e.g. resolve_instance _http._tcp \"My Web\"");
+}
+
+// resolve_instance browses the specified service type, and resolves the instance again
+// to verify its host, port and addresses right before connecting to it.
+fn main() -> Result<(), Box<dyn Error>> {
+    let args: Vec<String> = env::args().skip(1).collect();
+    let service_type = match args.first() {
+        Some(arg) if arg != "-h" => arg.clone(),
+        _ => {
+            usages();
+            return Ok(());
+        }
+    };
+    let instance_name = args.get(1).cloned();
+
+    let mut client = Client::new();
+    let events = client.subscribe();
+    client.start()?;
+    client.search(&Query::with(&service_type, "local"))?;
+
+    let deadline = Instant::now() + BROWSE_TIMEOUT;
+    let mut found = None;
+    while let Ok(event) = events.recv_timeout(deadline.saturating_duration_since(Instant::now())) {
+        if let ServiceEvent::Added(service) = event {
+            let matched = match &instance_name {
+                Some(name) => service.instance_name().eq_ignore_ascii_case(name),
+                None => true,
+            };
+            if matched {
+                found = Some(service);
+                break;
+            }
+        }
+    }
+
+    match found {
+        Some(service) => {
+            let service = client.resolve(&service, RESOLVE_TIMEOUT)?;
+            println!("Service : {}", service.name());
+            println!("Host : {}:{}", service.host(), service.port());
+            for addr in service.ipaddrs() {
+                println!("Address : {}", addr);
+            }
+            for (key, value) in service.attributes() {
+                println!("TXT : {}={}", key, value);
+            }
+        }
+        None => println!("No instance of {} found", service_type),
+    }
+
+    client.stop()?;
+
+    Ok(())
+}
@@ -0,0 +1,78 @@
+// Copyright (C) 2024 Satoshi Konno All rights reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//    http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::dns::{Record, Type};
+
+/// additional_records returns the records of the specified known records which a querier will predictably need
+/// to use the specified answers, excluding the answers themselves.
+/// RFC 6763: 12. Populating the DNS Additional Record Section
+/// PTR answers are followed by the SRV and TXT records of the instances and the addresses of their targets,
+/// and SRV answers are followed by the addresses of their targets.
+/// RFC 6762: 6.2. Responding to Address Queries
+/// Address answers are followed by the addresses of the other family.
+pub fn additional_records(answers: &[Record], known_records: &[Record]) -> Vec<Record> {
+    let mut additionals: Vec<Record> = Vec::new();
+    let mut add = |name: &str, types: &[Type]| -> Vec<Record> {
+        let mut added = Vec::new();
+        for record in known_records {
+            if !types.contains(&record.typ()) || !record.name().eq_ignore_ascii_case(name) {
+                continue;
+            }
+            if contains(answers, record) || contains(&additionals, record) {
+                continue;
+            }
+            additionals.push(record.clone());
+            added.push(record.clone());
+        }
+        added
+    };
+
+    for answer in answers {
+        match answer.typ() {
+            Type::PTR => {
+                let instance = match answer.as_ptr() {
+                    Some(ptr) => ptr.domain_name().to_string(),
+                    None => continue,
+                };
+                add(&instance, &[Type::TXT]);
+                for srv in add(&instance, &[Type::SRV]) {
+                    if let Some(srv) = srv.as_srv() {
+                        add(srv.target(), &[Type::A, Type::AAAA]);
+                    }
+                }
+            }
+            Type::SRV => {
+                if let Some(srv) = answer.as_srv() {
+                    add(srv.target(), &[Type::A, Type::AAAA]);
+                }
+            }
+            Type::A => {
+                add(answer.name(), &[Type::AAAA]);
+            }
+            Type::AAAA => {
+                add(answer.name(), &[Type::A]);
+            }
+            _ => {}
+        }
+    }
+    additionals
+}
+
+fn contains(records: &[Record], record: &Record) -> bool {
+    records.iter().any(|r| {
+        r.typ() == record.typ()
+            && r.name().eq_ignore_ascii_case(record.name())
+            && r.data() == record.data()
+    })
+}
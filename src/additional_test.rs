@@ -0,0 +1,100 @@
+// Copyright (C) 2024 Satoshi Konno All rights reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//    http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+#[cfg(test)]
+mod tests {
+
+    use crate::dns::{Record, Type};
+    use crate::fixture_test::{name_data, record, srv_data};
+    use crate::ResponderConfig;
+
+    fn known_records() -> Vec<Record> {
+        vec![
+            record(
+                "_http._tcp.local",
+                Type::PTR,
+                name_data("web._http._tcp.local"),
+            ),
+            record(
+                "web._http._tcp.local",
+                Type::SRV,
+                srv_data(80, "host.local"),
+            ),
+            record("web._http._tcp.local", Type::TXT, vec![0]),
+            record("host.local", Type::A, vec![192, 168, 1, 10]),
+            record(
+                "host.local",
+                Type::AAAA,
+                vec![0xfe, 0x80, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1],
+            ),
+            record("other.local", Type::A, vec![192, 168, 1, 20]),
+        ]
+    }
+
+    #[test]
+    fn additional_records() {
+        struct Test {
+            answer: usize,
+            expected: Vec<(&'static str, Type)>,
+        }
+
+        let tests = vec![
+            Test {
+                answer: 0,
+                expected: vec![
+                    ("web._http._tcp.local", Type::TXT),
+                    ("web._http._tcp.local", Type::SRV),
+                    ("host.local", Type::A),
+                    ("host.local", Type::AAAA),
+                ],
+            },
+            Test {
+                answer: 1,
+                expected: vec![("host.local", Type::A), ("host.local", Type::AAAA)],
+            },
+            Test {
+                answer: 2,
+                expected: vec![],
+            },
+            Test {
+                answer: 3,
+                expected: vec![("host.local", Type::AAAA)],
+            },
+        ];
+
+        let known = known_records();
+        let config = ResponderConfig::new();
+        for test in tests {
+            let answers = vec![known[test.answer].clone()];
+            let additionals = config.additional_records(&answers, &known);
+            let additionals: Vec<(&str, Type)> =
+                additionals.iter().map(|r| (r.name(), r.typ())).collect();
+            assert_eq!(additionals, test.expected);
+        }
+    }
+
+    #[test]
+    fn additional_records_disabled() {
+        let known = known_records();
+        let mut config = ResponderConfig::new();
+        config.set_additional_records_enabled(false);
+        assert!(config.additional_records(&known[0..1], &known).is_empty());
+
+        // The answers are never repeated in the additional section.
+        config.set_additional_records_enabled(true);
+        let additionals = config.additional_records(&known[0..2], &known);
+        assert_eq!(additionals.len(), 3);
+        assert!(additionals.iter().all(|r| r.typ() != Type::SRV));
+    }
+}
@@ -0,0 +1,1074 @@
+// Copyright (C) 2024 Satoshi Konno All rights reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//    http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr, UdpSocket};
+use std::sync::atomic::AtomicBool;
+use std::sync::Arc;
+use std::sync::Mutex;
+use std::sync::Weak;
+use std::time::{Duration, Instant};
+
+use cybergarage::net::{Observer, ObserverObject, Packet};
+
+use crate::default::PORT;
+use crate::dns::{Message, Question, Record, Type, QR};
+use crate::error::{Error, Result};
+use crate::family::AddressFamily;
+use crate::hostname::{system_hostname, Hostname, HostnamePolicy};
+use crate::loopback::LoopbackFilter;
+use crate::name_conflict::{ConflictCallback, ConflictDecision, NameConflict};
+use crate::net::{interface_of, is_on_link, mdns_interfaces, Interface};
+use crate::normalize::NameComparison;
+use crate::outgoing::{OutgoingHook, OutgoingKind, OutgoingPacket};
+use crate::rate_limit::{question_key, RateLimiter, StormDetector};
+use crate::received_message::ReceivedMessage;
+use crate::record_index::{RecordIndex, RegistrationId};
+use crate::responder_config::ResponderConfig;
+use crate::response_strategy::{QuestionContext, ResponseDecision};
+use crate::service_builder::ServiceInfo;
+use crate::shutdown::{wait_tick, ShutdownReport, TimerWorker};
+use crate::stats::Stats;
+use crate::supervisor::{lock_recovered, Supervisor, TaskStatus};
+use crate::transport::{MulticastTransport, Route};
+
+/// PROBE_INTERVAL is the interval between the probes of a registration.
+/// RFC 6762: 8.1. Probing
+pub const PROBE_INTERVAL: Duration = Duration::from_millis(250);
+
+/// PROBE_COUNT is the number of the probes sent before a registration is announced.
+pub const PROBE_COUNT: u32 = 3;
+
+/// PROBE_DEFER is how long a registration waits before probing again after losing a simultaneous probe tiebreak.
+/// RFC 6762: 8.2. Simultaneous Probe Tiebreaking
+pub const PROBE_DEFER: Duration = Duration::from_secs(1);
+
+/// ANNOUNCE_INTERVAL is the interval between the first two announcements, which doubles for each later one.
+/// RFC 6762: 8.3. Announcing
+pub const ANNOUNCE_INTERVAL: Duration = Duration::from_secs(1);
+
+/// ANNOUNCE_COUNT is the number of the announcements of a registration.
+pub const ANNOUNCE_COUNT: u32 = 2;
+
+/// HOST_REGISTRATION is the registration of the address records of the host.
+pub const HOST_REGISTRATION: RegistrationId = 0;
+
+// RFC 6762: 6.7. Legacy Unicast Responses
+const LEGACY_UNICAST_MAX_TTL: u32 = 10;
+
+/// TIMER_TASK is the name of the task which sends the due probes, announcements and delayed responses.
+pub const TIMER_TASK: &str = "responder-timer";
+
+const TIMER_TICK: Duration = Duration::from_millis(50);
+
+/// RegistrationState represents how far the records of a registration have been established on the network.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum RegistrationState {
+    /// Probing verifies that no other host claims the unique records.
+    Probing,
+    /// Announcing sends the unsolicited responses of the records, which are answered from now on.
+    Announcing,
+    /// Announced answers the records until they are unregistered.
+    Announced,
+}
+
+struct Registration {
+    id: RegistrationId,
+    service: Option<ServiceInfo>,
+    records: Vec<Record>,
+    state: RegistrationState,
+    sent: u32,
+    due: Instant,
+}
+
+// Destination represents where a packet is sent.
+enum Destination {
+    // All is the multicast groups of every family on every interface, such as for the announcements.
+    All,
+    // Route is the multicast group of the family on the interface a query arrived on.
+    Route(Route),
+    // Unicast is the address of a legacy querier.
+    Unicast(SocketAddr),
+}
+
+impl Registration {
+    fn new(id: RegistrationId, service: Option<ServiceInfo>, records: Vec<Record>) -> Registration {
+        Registration {
+            id,
+            service,
+            records,
+            state: RegistrationState::Probing,
+            sent: 0,
+            due: Instant::now(),
+        }
+    }
+
+    // unique_records returns the records which no other host may assert, which are probed and defended.
+    fn unique_records(&self) -> Vec<Record> {
+        self.records
+            .iter()
+            .filter(|r| r.cache_flush())
+            .cloned()
+            .collect()
+    }
+
+    // restart probes the records again from the specified time.
+    fn restart(&mut self, due: Instant) {
+        self.state = RegistrationState::Probing;
+        self.sent = 0;
+        self.due = due;
+    }
+
+    fn is_answered(&self) -> bool {
+        self.state != RegistrationState::Probing
+    }
+}
+
+/// Advertiser represents an advertiser, which probes, announces and answers the records of the registered services.
+pub struct Advertiser {
+    self_ref: Weak<Mutex<Advertiser>>,
+    transport: MulticastTransport,
+    interfaces: Vec<Interface>,
+    config: ResponderConfig,
+    hostname: Hostname,
+    detected_addrs: Vec<IpAddr>,
+    registrations: Vec<Registration>,
+    index: RecordIndex,
+    name_comparison: NameComparison,
+    next_id: RegistrationId,
+    delayed_responses: Vec<(Instant, Vec<Record>, Route)>,
+    last_multicast: HashMap<String, Instant>,
+    rate_limiter: RateLimiter,
+    storm_detector: StormDetector,
+    on_link_check: bool,
+    outgoing_hook: Option<OutgoingHook>,
+    loopback_filter: Option<Arc<Mutex<LoopbackFilter>>>,
+    conflict_callback: Option<ConflictCallback>,
+    stats: Stats,
+    timer: Option<TimerWorker>,
+    supervisor: Supervisor,
+}
+
+impl Advertiser {
+    /// new creates a new advertiser of the system host name, which is renamed with an increasing number on conflicts.
+    pub fn new() -> Arc<Mutex<Advertiser>> {
+        Arc::new_cyclic(|self_ref| {
+            Mutex::new(Advertiser {
+                self_ref: self_ref.clone(),
+                transport: MulticastTransport::new(),
+                interfaces: Vec::new(),
+                config: ResponderConfig::new(),
+                hostname: Hostname::new(HostnamePolicy::AutoIncrement(system_hostname())),
+                detected_addrs: Vec::new(),
+                registrations: Vec::new(),
+                index: RecordIndex::new(),
+                name_comparison: NameComparison::default(),
+                next_id: HOST_REGISTRATION + 1,
+                delayed_responses: Vec::new(),
+                last_multicast: HashMap::new(),
+                rate_limiter: RateLimiter::new(),
+                storm_detector: StormDetector::new(),
+                on_link_check: true,
+                outgoing_hook: None,
+                loopback_filter: None,
+                conflict_callback: None,
+                stats: Stats::new(),
+                timer: None,
+                supervisor: Supervisor::new(),
+            })
+        })
+    }
+
+    /// set_config sets the configuration of the responses, and the registered services are announced again with it.
+    pub fn set_config(&mut self, config: ResponderConfig) {
+        self.config = config;
+        self.refresh_records();
+    }
+
+    /// config returns the configuration of the responses.
+    pub fn config(&self) -> &ResponderConfig {
+        &self.config
+    }
+
+    /// set_hostname sets the host name which the services are advertised on, and the host is probed again.
+    pub fn set_hostname(&mut self, hostname: Hostname) {
+        self.hostname = hostname;
+        self.refresh_records();
+    }
+
+    /// hostname returns the current host name in the local domain.
+    pub fn hostname(&self) -> String {
+        self.hostname.fqdn()
+    }
+
+    /// set_name_comparison sets how the question names are matched with the registered names.
+    pub fn set_name_comparison(&mut self, name_comparison: NameComparison) {
+        self.name_comparison = name_comparison;
+        self.index.set_name_comparison(name_comparison);
+    }
+
+    /// on_name_conflict registers the callback called when a registered service loses a name conflict,
+    /// which may veto the rename and deregister the service instead. The host name is renamed by its HostnamePolicy.
+    pub fn on_name_conflict(&mut self, callback: ConflictCallback) {
+        self.conflict_callback = Some(callback);
+    }
+
+    /// clear_name_conflict removes the callback of the name conflicts, and the conflicted services are always renamed.
+    pub fn clear_name_conflict(&mut self) {
+        self.conflict_callback = None;
+    }
+
+    /// set_loopback_filter sets the filter which the multicast transmissions are remembered in,
+    /// such as the filter of a discoverer in the same process so it does not discover the advertised services.
+    pub fn set_loopback_filter(&mut self, filter: Arc<Mutex<LoopbackFilter>>) {
+        self.loopback_filter = Some(filter);
+    }
+
+    /// set_outgoing_hook sets the hook invoked with every packet about to be transmitted, which may rewrite or veto the packet.
+    pub fn set_outgoing_hook(&mut self, hook: OutgoingHook) {
+        self.outgoing_hook = Some(hook);
+    }
+
+    /// clear_outgoing_hook removes the outgoing hook.
+    pub fn clear_outgoing_hook(&mut self) {
+        self.outgoing_hook = None;
+    }
+
+    /// set_rate_limiter sets the limiter of the multicast responses.
+    pub fn set_rate_limiter(&mut self, rate_limiter: RateLimiter) {
+        self.rate_limiter = rate_limiter;
+    }
+
+    /// set_storm_detector sets the detector of the inbound query storms, whose suppressed questions are not answered.
+    pub fn set_storm_detector(&mut self, storm_detector: StormDetector) {
+        self.storm_detector = storm_detector;
+    }
+
+    /// storm_detector returns the detector of the inbound query storms.
+    pub fn storm_detector(&self) -> &StormDetector {
+        &self.storm_detector
+    }
+
+    /// set_on_link_check sets whether the packets from the sources off the directly attached networks are dropped, which is enabled by default.
+    /// The check is skipped while the interfaces are not known, and may be disabled for the environments such as the routed or tunneled networks.
+    /// RFC 6762: 11. Source Address Check
+    pub fn set_on_link_check(&mut self, enabled: bool) {
+        self.on_link_check = enabled;
+    }
+
+    /// is_on_link_check returns true if the packets from the sources off the directly attached networks are dropped.
+    pub fn is_on_link_check(&self) -> bool {
+        self.on_link_check
+    }
+
+    /// stats returns the statistics of the advertiser.
+    pub fn stats(&self) -> &Stats {
+        &self.stats
+    }
+
+    /// reset_stats clears the statistics of the advertiser.
+    pub fn reset_stats(&mut self) {
+        self.stats.reset();
+    }
+
+    /// register registers the specified service, which is probed and then announced.
+    /// It returns an error if the attributes violate the TXT schemas of the configuration.
+    pub fn register(&mut self, service: ServiceInfo) -> Result<RegistrationId> {
+        let attrs: HashMap<String, String> = service.attributes().iter().cloned().collect();
+        self.config
+            .check_attributes(service.service_type(), &attrs)?;
+        if !self.registrations.iter().any(|r| r.id == HOST_REGISTRATION) {
+            self.register_host();
+        }
+        let id = self.next_id;
+        self.next_id += 1;
+        let records = service.records(&self.hostname.fqdn(), self.config.record_ttls());
+        self.registrations
+            .push(Registration::new(id, Some(service), records));
+        self.poll_at(Instant::now());
+        self.spawn_timer();
+        Ok(id)
+    }
+
+    /// unregister sends the goodbye of the specified service, and stops answering its records.
+    pub fn unregister(&mut self, id: RegistrationId) -> Result<()> {
+        let pos = match self
+            .registrations
+            .iter()
+            .position(|r| r.id == id && r.service.is_some())
+        {
+            Some(pos) => pos,
+            None => return Err(Error::from_string(&format!("Unknown registration: {}", id))),
+        };
+        let registration = self.registrations.remove(pos);
+        self.index.remove(id);
+        if registration.is_answered() {
+            self.send_goodbye(&registration.records);
+        }
+        Ok(())
+    }
+
+    /// services returns the registered services, which may have been renamed on conflicts.
+    pub fn services(&self) -> Vec<ServiceInfo> {
+        self.registrations
+            .iter()
+            .filter_map(|r| r.service.clone())
+            .collect()
+    }
+
+    /// service returns the specified registered service.
+    pub fn service(&self, id: RegistrationId) -> Option<&ServiceInfo> {
+        self.registrations
+            .iter()
+            .find(|r| r.id == id)
+            .and_then(|r| r.service.as_ref())
+    }
+
+    /// state returns the state of the specified registration.
+    pub fn state(&self, id: RegistrationId) -> Option<RegistrationState> {
+        self.registrations
+            .iter()
+            .find(|r| r.id == id)
+            .map(|r| r.state)
+    }
+
+    /// index returns the records answered by the advertiser.
+    pub fn index(&self) -> &RecordIndex {
+        &self.index
+    }
+
+    /// set_interfaces sets the local interfaces instead of enumerating them at the start, such as for a fixed configuration.
+    /// The addresses of the interfaces are advertised unless the configuration has its own addresses,
+    /// and the queries from the networks of the interfaces are answered on them.
+    pub fn set_interfaces(&mut self, ifaces: Vec<Interface>) {
+        self.detected_addrs = ifaces.iter().flat_map(|i| i.ipaddrs()).collect();
+        self.interfaces = ifaces;
+    }
+
+    /// interfaces returns the local interfaces the advertiser answers on.
+    pub fn interfaces(&self) -> &Vec<Interface> {
+        &self.interfaces
+    }
+
+    /// routes returns the families and interfaces of the joined multicast groups.
+    pub fn routes(&self) -> Vec<Route> {
+        self.transport.routes()
+    }
+
+    // register_host registers the address records of the host.
+    fn register_host(&mut self) {
+        let records = self.host_records();
+        if records.is_empty() {
+            log::warn!("no address to advertise for {}", self.hostname.fqdn());
+        }
+        self.registrations
+            .push(Registration::new(HOST_REGISTRATION, None, records));
+    }
+
+    // host_records returns the unique address records of the host.
+    fn host_records(&self) -> Vec<Record> {
+        self.config.address_records(
+            &self.hostname.fqdn(),
+            self.config.record_ttls().host_ttl(),
+            &self.detected_addrs,
+        )
+    }
+
+    // refresh_records builds the records of all registrations again, and probes them again.
+    fn refresh_records(&mut self) {
+        let now = Instant::now();
+        let host_records = self.host_records();
+        let host = self.hostname.fqdn();
+        for registration in &mut self.registrations {
+            registration.records = match &registration.service {
+                Some(service) => service.records(&host, self.config.record_ttls()),
+                None => host_records.clone(),
+            };
+            self.index.remove(registration.id);
+            registration.restart(now);
+        }
+        self.poll_at(now);
+        self.spawn_timer();
+    }
+
+    /// poll_at sends the probes, announcements and delayed responses which are due at the specified time.
+    pub fn poll_at(&mut self, now: Instant) {
+        let due: Vec<RegistrationId> = self
+            .registrations
+            .iter()
+            .filter(|r| r.state != RegistrationState::Announced && r.due <= now)
+            .map(|r| r.id)
+            .collect();
+        for id in due {
+            self.step(id, now);
+        }
+        let (due, delayed): (Vec<_>, Vec<_>) = self
+            .delayed_responses
+            .drain(..)
+            .partition(|(due, _, _)| *due <= now);
+        self.delayed_responses = delayed;
+        let mut routes: Vec<(Route, Vec<Record>)> = Vec::new();
+        for (_, records, route) in due {
+            let n = match routes.iter().position(|(r, _)| *r == route) {
+                Some(n) => n,
+                None => {
+                    routes.push((route, Vec::new()));
+                    routes.len() - 1
+                }
+            };
+            let answers = &mut routes[n].1;
+            for record in records {
+                if !answers.iter().any(|a| is_same_record(a, &record)) {
+                    answers.push(record);
+                }
+            }
+        }
+        for (route, answers) in routes {
+            self.multicast_answers(answers, &route, now);
+        }
+    }
+
+    // step advances the specified registration by one probe or announcement.
+    fn step(&mut self, id: RegistrationId, now: Instant) {
+        let registration = match self.registrations.iter_mut().find(|r| r.id == id) {
+            Some(registration) => registration,
+            None => return,
+        };
+        if registration.state == RegistrationState::Probing && PROBE_COUNT <= registration.sent {
+            registration.state = RegistrationState::Announcing;
+            registration.sent = 0;
+            let records = registration.records.clone();
+            self.index.add(id, &records);
+        }
+        let registration = match self.registrations.iter_mut().find(|r| r.id == id) {
+            Some(registration) => registration,
+            None => return,
+        };
+        match registration.state {
+            RegistrationState::Probing => {
+                let first = registration.sent == 0;
+                registration.sent += 1;
+                registration.due = now + PROBE_INTERVAL;
+                let records = registration.unique_records();
+                self.send_probe(&records, first);
+            }
+            RegistrationState::Announcing => {
+                registration.due = now + ANNOUNCE_INTERVAL * 2u32.pow(registration.sent);
+                registration.sent += 1;
+                if ANNOUNCE_COUNT <= registration.sent {
+                    registration.state = RegistrationState::Announced;
+                }
+                let announced = registration.state == RegistrationState::Announced;
+                let records = registration.records.clone();
+                self.send_announcement(&records, now);
+                if announced && id == HOST_REGISTRATION {
+                    if let Err(e) = self.hostname.defended() {
+                        log::warn!("host name not stored: {}", e);
+                    }
+                }
+            }
+            RegistrationState::Announced => {}
+        }
+    }
+
+    // send_probe sends the query probing the names of the specified unique records.
+    // RFC 6762: 8.1. Probing
+    fn send_probe(&mut self, records: &[Record], unicast_response: bool) {
+        if records.is_empty() {
+            return;
+        }
+        let mut msg = Message::new();
+        let mut names: Vec<&str> = Vec::new();
+        for record in records {
+            if names
+                .iter()
+                .any(|name| self.name_comparison.eq(name, record.name()))
+            {
+                continue;
+            }
+            names.push(record.name());
+            let mut question = Question::new(record.name(), Type::ANY);
+            question.set_unicast_response(unicast_response);
+            msg.add_question(question);
+        }
+        for record in records {
+            msg.add_authority(record.clone());
+        }
+        if let Err(e) = self.send(OutgoingKind::Probe, &msg, Destination::All) {
+            log::warn!("probe not sent: {}", e);
+        }
+    }
+
+    // send_announcement multicasts the unsolicited responses of the specified records.
+    // RFC 6762: 8.3. Announcing
+    fn send_announcement(&mut self, records: &[Record], now: Instant) {
+        let msgs = match self.response_messages(records, &[]) {
+            Ok(msgs) => msgs,
+            Err(e) => {
+                log::warn!("announcement not sent: {}", e);
+                return;
+            }
+        };
+        for msg in msgs {
+            match self.send(OutgoingKind::Announcement, &msg, Destination::All) {
+                Ok(_) => {
+                    self.stats.add_announcement_sent();
+                    self.add_last_multicast(&msg, now);
+                }
+                Err(e) => log::warn!("announcement not sent: {}", e),
+            }
+        }
+    }
+
+    // send_goodbye multicasts the specified records with a zero TTL, and returns the number of the sent packets.
+    // RFC 6762: 10.1. Goodbye Packets
+    fn send_goodbye(&mut self, records: &[Record]) -> usize {
+        let records: Vec<Record> = records
+            .iter()
+            .map(|record| {
+                let mut record = record.clone();
+                record.set_ttl(0);
+                record
+            })
+            .collect();
+        let msgs = match self.config.pack_response(&records, &[]) {
+            Ok(msgs) => msgs,
+            Err(e) => {
+                log::warn!("goodbye not sent: {}", e);
+                return 0;
+            }
+        };
+        let mut sent = 0;
+        for msg in msgs {
+            match self.send(OutgoingKind::Announcement, &msg, Destination::All) {
+                Ok(_) => sent += 1,
+                Err(e) => log::warn!("goodbye not sent: {}", e),
+            }
+        }
+        sent
+    }
+
+    // response_messages returns the signed and packed responses of the specified answers with their additional records.
+    fn response_messages(
+        &self,
+        answers: &[Record],
+        known_records: &[Record],
+    ) -> Result<Vec<Message>> {
+        let answers = self.config.sign_answers(answers)?;
+        let msgs = self.config.pack_response(&answers, known_records)?;
+        for msg in &msgs {
+            self.config.check_response(msg)?;
+        }
+        Ok(msgs)
+    }
+
+    // answered_records returns all records which the advertiser answers.
+    fn answered_records(&self) -> Vec<Record> {
+        self.registrations
+            .iter()
+            .filter(|r| r.is_answered())
+            .flat_map(|r| r.records.iter().cloned())
+            .collect()
+    }
+
+    // multicast_answers multicasts the specified answers over the specified route except the record sets multicast over it within the last second.
+    // The limiter is consulted once per record set, so all records of an allowed set, such as the PTR records of the services
+    // of a type or the addresses of the host, are answered together.
+    // RFC 6762: 6.2. Responding to Address Queries
+    fn multicast_answers(&mut self, answers: Vec<Record>, route: &Route, now: Instant) {
+        let mut allowed: HashMap<String, bool> = HashMap::new();
+        let answers: Vec<Record> = answers
+            .into_iter()
+            .filter(|a| {
+                let key = format!("{}/{}", route.family(), record_key(a));
+                *allowed
+                    .entry(key)
+                    .or_insert_with_key(|key| self.rate_limiter.allow_response_at(key, now))
+            })
+            .collect();
+        if answers.is_empty() {
+            return;
+        }
+        let msgs = match self.response_messages(&answers, &self.answered_records()) {
+            Ok(msgs) => msgs,
+            Err(e) => {
+                log::warn!("response not sent: {}", e);
+                return;
+            }
+        };
+        for msg in msgs {
+            match self.send(
+                OutgoingKind::Response,
+                &msg,
+                Destination::Route(route.clone()),
+            ) {
+                Ok(_) => self.add_last_multicast(&msg, now),
+                Err(e) => log::warn!("response not sent: {}", e),
+            }
+        }
+    }
+
+    // unicast_answers answers a legacy querier directly, echoing its query ID and questions with the TTLs capped.
+    // RFC 6762: 6.7. Legacy Unicast Responses
+    fn unicast_answers(&mut self, query: &Message, answers: Vec<Record>, to: SocketAddr) {
+        let answers: Vec<Record> = answers
+            .into_iter()
+            .map(|mut record| {
+                record.set_ttl(record.ttl().min(LEGACY_UNICAST_MAX_TTL));
+                record.set_cache_flush(false);
+                record
+            })
+            .collect();
+        let msgs = match self.response_messages(&answers, &[]) {
+            Ok(msgs) => msgs,
+            Err(e) => {
+                log::warn!("unicast response not sent: {}", e);
+                return;
+            }
+        };
+        for packed in msgs {
+            let mut msg = Message::new();
+            msg.set_id(query.id());
+            msg.set_qr(QR::Response);
+            msg.set_aa(true);
+            for question in query.questions() {
+                msg.add_question(question.clone());
+            }
+            for answer in packed.answers() {
+                msg.add_answer(answer.clone());
+            }
+            for additional in packed.additionals() {
+                msg.add_additional(additional.clone());
+            }
+            if let Err(e) = self.send(OutgoingKind::Response, &msg, Destination::Unicast(to)) {
+                log::warn!("unicast response not sent: {}", e);
+            }
+        }
+    }
+
+    // add_last_multicast records when the answers of the specified message were multicast.
+    fn add_last_multicast(&mut self, msg: &Message, now: Instant) {
+        for answer in msg.answers() {
+            self.last_multicast.insert(record_key(answer), now);
+        }
+    }
+
+    // send applies the outgoing hook to the specified message, and sends it to the specified destination.
+    fn send(
+        &self,
+        kind: OutgoingKind,
+        msg: &Message,
+        dest: Destination,
+    ) -> std::result::Result<(), std::io::Error> {
+        let bytes = msg
+            .to_bytes()
+            .map_err(|e| std::io::Error::other(e.message()))?;
+        let bytes = match &self.outgoing_hook {
+            Some(hook) => {
+                let mut pkt = OutgoingPacket::new(kind, bytes);
+                if let Destination::Route(route) = &dest {
+                    pkt.set_route(Some(route.clone()));
+                }
+                if !hook(&mut pkt) {
+                    return Err(std::io::Error::new(
+                        std::io::ErrorKind::PermissionDenied,
+                        format!("{} vetoed by the outgoing hook", kind),
+                    ));
+                }
+                pkt.into_bytes()
+            }
+            None => bytes,
+        };
+        match dest {
+            Destination::Unicast(to) => {
+                let local: SocketAddr = match to {
+                    SocketAddr::V4(_) => (Ipv4Addr::UNSPECIFIED, 0).into(),
+                    SocketAddr::V6(_) => (Ipv6Addr::UNSPECIFIED, 0).into(),
+                };
+                UdpSocket::bind(local)?.send_to(&bytes, to)?;
+                return Ok(());
+            }
+            Destination::Route(route) => self
+                .transport
+                .notify_route(&Packet::from_bytes(&bytes), &route)?,
+            Destination::All => self.transport.notify(&Packet::from_bytes(&bytes))?,
+        }
+        if let Some(filter) = &self.loopback_filter {
+            lock_recovered(filter).add_sent(&bytes);
+        }
+        Ok(())
+    }
+
+    // route_of returns the route of the family and interface the packet from the specified address arrived on.
+    fn route_of(&self, from: SocketAddr) -> Route {
+        let interface = interface_of(&self.interfaces, &from.ip()).map(|i| i.name());
+        Route::new(AddressFamily::from_socketaddr(&from), interface)
+    }
+
+    // answer answers the questions of the specified query from the announced records,
+    // over the family and on the interface the query arrived on unless the family is disabled.
+    // RFC 6762: 6. Responding
+    fn answer(&mut self, query: &Message, from: SocketAddr, now: Instant) {
+        let route = self.route_of(from);
+        if !self.config.is_family_enabled(route.family()) {
+            return;
+        }
+        let mut multicast: Vec<Record> = Vec::new();
+        let mut unicast: Vec<Record> = Vec::new();
+        for question in query.questions() {
+            let mut answers = self.index.answers(question);
+            answers.extend(self.config.dnskey_answers(question));
+            if answers.is_empty() {
+                continue;
+            }
+            // RFC 6762: 7.1. Known-Answer Suppression
+            let known = answers.len();
+            answers.retain(|a| !is_known_answer(query, a, self.name_comparison));
+            let ttl = answers.iter().map(|a| a.ttl()).min().unwrap_or(0);
+            let last_multicast = answers
+                .iter()
+                .filter_map(|a| self.last_multicast.get(&record_key(a)).copied())
+                .min();
+            let mut ctx = QuestionContext::new(question, from);
+            ctx.set_unique(answers.iter().all(|a| a.cache_flush()))
+                .set_truncated(query.tc())
+                .set_known_answer(answers.is_empty() && 0 < known)
+                .set_last_multicast(ttl, last_multicast)
+                .set_now(now);
+            match self.config.decide(&ctx) {
+                ResponseDecision::Immediate => multicast.extend(answers),
+                ResponseDecision::Delayed(delay) => {
+                    self.delayed_responses
+                        .push((now + delay, answers, route.clone()));
+                }
+                // A querier only accepts the unicast responses sent from the mDNS port, which the multicast transport owns,
+                // so only the legacy queriers are answered directly and the others by multicast.
+                ResponseDecision::Unicast if from.port() != PORT => unicast.extend(answers),
+                ResponseDecision::Unicast => multicast.extend(answers),
+                ResponseDecision::Ignore => {}
+            }
+        }
+        if !multicast.is_empty() {
+            self.multicast_answers(multicast, &route, now);
+        }
+        if !unicast.is_empty() {
+            self.unicast_answers(query, unicast, from);
+        }
+        self.spawn_timer();
+    }
+
+    // resolve_probe_tiebreaks defers the probing registrations which lose the tiebreak with the specified probe of another host.
+    // RFC 6762: 8.2. Simultaneous Probe Tiebreaking
+    fn resolve_probe_tiebreaks(&mut self, probe: &Message, now: Instant) {
+        let name_comparison = self.name_comparison;
+        for registration in &mut self.registrations {
+            if registration.state != RegistrationState::Probing {
+                continue;
+            }
+            let ours = registration.unique_records();
+            let theirs: Vec<Record> = probe
+                .authorities()
+                .iter()
+                .filter(|a| ours.iter().any(|r| name_comparison.eq(r.name(), a.name())))
+                .cloned()
+                .collect();
+            if theirs.is_empty() {
+                continue;
+            }
+            let ours: Vec<Record> = ours
+                .into_iter()
+                .filter(|r| {
+                    theirs
+                        .iter()
+                        .any(|a| name_comparison.eq(r.name(), a.name()))
+                })
+                .collect();
+            if compare_records(&ours, &theirs) == Ordering::Less {
+                log::info!("probe tiebreak lost, probing again in {:?}", PROBE_DEFER);
+                registration.restart(now + PROBE_DEFER);
+            }
+        }
+    }
+
+    // detect_conflicts renames the registrations whose unique records conflict with the records of the specified response.
+    // RFC 6762: 9. Conflict Resolution
+    fn detect_conflicts(&mut self, response: &Message, from: SocketAddr, now: Instant) {
+        let name_comparison = self.name_comparison;
+        let records: Vec<&Record> = response
+            .answers()
+            .iter()
+            .chain(response.additionals().iter())
+            .collect();
+        let conflicted: Vec<RegistrationId> = self
+            .registrations
+            .iter()
+            .filter(|registration| {
+                let ours = registration.unique_records();
+                records.iter().any(|theirs| {
+                    let same_name: Vec<&Record> = ours
+                        .iter()
+                        .filter(|r| name_comparison.eq(r.name(), theirs.name()))
+                        .collect();
+                    if same_name.iter().any(|r| is_same_record(r, theirs)) {
+                        return false;
+                    }
+                    match registration.state {
+                        // Any record of a probed name is a conflict while probing.
+                        RegistrationState::Probing => !same_name.is_empty(),
+                        _ => same_name.iter().any(|r| r.typ() == theirs.typ()),
+                    }
+                })
+            })
+            .map(|r| r.id)
+            .collect();
+        for id in conflicted {
+            self.resolve_conflict(id, from, now);
+        }
+    }
+
+    // resolve_conflict renames the specified registration and probes it again,
+    // or deregisters it if the callback of the name conflicts vetoes the rename.
+    fn resolve_conflict(&mut self, id: RegistrationId, from: SocketAddr, now: Instant) {
+        self.index.remove(id);
+        if id == HOST_REGISTRATION {
+            if let Err(e) = self.hostname.conflict() {
+                log::error!("{}", e);
+                self.registrations.retain(|r| r.id != HOST_REGISTRATION);
+                return;
+            }
+            self.refresh_records();
+            return;
+        }
+        let host = self.hostname.fqdn();
+        let registration = match self.registrations.iter_mut().find(|r| r.id == id) {
+            Some(registration) => registration,
+            None => return,
+        };
+        if let Some(service) = &mut registration.service {
+            let name = next_instance_name(service.instance_name());
+            let conflict = NameConflict::new(id, service.instance_name(), &name, from);
+            let decision = match &self.conflict_callback {
+                Some(callback) => callback(&conflict),
+                None => ConflictDecision::Rename,
+            };
+            if decision == ConflictDecision::Deregister {
+                log::info!(
+                    "service {} conflicted with {}, deregistered",
+                    conflict.old_name(),
+                    from
+                );
+                if let Err(e) = self.unregister(id) {
+                    log::error!("{}", e);
+                }
+                return;
+            }
+            log::info!("service {}", conflict);
+            service.set_instance_name(&name);
+            registration.records = service.records(&host, self.config.record_ttls());
+        }
+        registration.restart(now);
+        self.poll_at(now);
+    }
+
+    // has_timer_work returns true if any registration is not announced yet or any response is delayed.
+    fn has_timer_work(&self) -> bool {
+        !self.delayed_responses.is_empty()
+            || self
+                .registrations
+                .iter()
+                .any(|r| r.state != RegistrationState::Announced)
+    }
+
+    // spawn_timer starts the thread which sends the due probes, announcements and delayed responses,
+    // which ends when the transport stops or no work is left.
+    fn spawn_timer(&mut self) {
+        if self.timer.is_some() || !self.transport.is_running() || !self.has_timer_work() {
+            return;
+        }
+        let self_ref = self.self_ref.clone();
+        let cancelled = Arc::new(AtomicBool::new(false));
+        let timer_cancelled = cancelled.clone();
+        let worker = self.supervisor.spawn(TIMER_TASK, move || loop {
+            if !wait_tick(&timer_cancelled, TIMER_TICK) {
+                return;
+            }
+            let advertiser = match self_ref.upgrade() {
+                Some(advertiser) => advertiser,
+                None => return,
+            };
+            let mut advertiser = lock_recovered(&advertiser);
+            advertiser.poll_at(Instant::now());
+            if !advertiser.transport.is_running() || !advertiser.has_timer_work() {
+                advertiser.timer = None;
+                return;
+            }
+        });
+        self.timer = Some(TimerWorker::new(cancelled, worker));
+    }
+
+    /// tasks returns the statuses of the background tasks of the advertiser.
+    pub fn tasks(&self) -> Vec<TaskStatus> {
+        self.supervisor.tasks()
+    }
+
+    /// start joins the multicast groups, and probes the registered services again with the detected addresses.
+    pub fn start(&mut self) -> std::result::Result<(), std::io::Error> {
+        if self.transport.is_running() {
+            return Ok(());
+        }
+        match mdns_interfaces() {
+            Ok(ifaces) => self.set_interfaces(ifaces),
+            Err(e) => log::warn!("addresses not detected: {}", e),
+        }
+        let observer: ObserverObject = match self.self_ref.upgrade() {
+            Some(advertiser) => advertiser,
+            None => return Err(std::io::Error::other("advertiser dropped")),
+        };
+        let families = self.config.families().clone();
+        self.transport
+            .start(&self.interfaces, &families, PORT, observer)?;
+        self.refresh_records();
+        Ok(())
+    }
+
+    /// stop leaves the multicast groups without sending the goodbyes.
+    pub fn stop(&mut self) -> std::result::Result<(), std::io::Error> {
+        self.transport.stop()
+    }
+
+    /// shutdown sends the goodbyes of the announced services, and stops the advertiser.
+    /// The timer is waited for at most the specified timeout.
+    pub fn shutdown(
+        &mut self,
+        timeout: Duration,
+    ) -> std::result::Result<ShutdownReport, std::io::Error> {
+        let started = Instant::now();
+        let mut report = ShutdownReport::new();
+        let records = self.answered_records();
+        report.add_goodbyes_sent(self.send_goodbye(&records));
+        self.registrations.clear();
+        report.add_replies_flushed(self.delayed_responses.len());
+        self.delayed_responses.clear();
+        self.index = RecordIndex::new();
+        self.index.set_name_comparison(self.name_comparison);
+        if self.transport.is_running() {
+            self.transport.stop()?;
+            report.set_transport_stopped(true);
+        }
+        if let Some(timer) = self.timer.take() {
+            report.add_schedulers_cancelled(1);
+            report.add_worker(timer.cancel(started + timeout));
+        }
+        if !report.is_complete() {
+            log::warn!("shutdown exceeded the timeout ({:?})", timeout);
+        }
+        report.set_elapsed(started.elapsed());
+        Ok(report)
+    }
+
+    fn receive(&mut self, pkt: &Packet) {
+        self.stats.add_packet_received();
+        self.stats.add_packet_size(pkt.bytes().len());
+        let now = Instant::now();
+        self.poll_at(now);
+        // A source can not be verified while the interfaces are not known.
+        if self.on_link_check
+            && !self.interfaces.is_empty()
+            && !is_on_link(&self.interfaces, &pkt.from().ip())
+        {
+            log::debug!("packet from off-link source ignored ({})", pkt.from());
+            self.stats.add_off_link_dropped();
+            return;
+        }
+        let parse_start = Instant::now();
+        let parsed = ReceivedMessage::from_packet(pkt, None);
+        self.stats.add_parse_time(parse_start.elapsed());
+        let received = match parsed {
+            Ok(received) => received,
+            Err(_) => {
+                self.stats.add_parse_failure();
+                return;
+            }
+        };
+        let msg = received.message();
+        if msg.is_query() && !self.storm_detector.observe(&question_key(msg)) {
+            return;
+        }
+        if msg.is_response() {
+            self.stats.add_answers_per_response(msg.answers().len());
+            self.detect_conflicts(msg, received.source(), now);
+            return;
+        }
+        if !msg.authorities().is_empty() {
+            self.resolve_probe_tiebreaks(msg, now);
+        }
+        self.answer(msg, received.source(), now);
+    }
+}
+
+impl Observer for Advertiser {
+    fn packet_received(&mut self, pkt: &Packet) {
+        self.receive(pkt);
+    }
+}
+
+impl Drop for Advertiser {
+    fn drop(&mut self) {
+        let _ = self.stop();
+    }
+}
+
+// record_key returns the key of the record set of the specified record.
+fn record_key(record: &Record) -> String {
+    format!("{}/{}", record.name().to_lowercase(), record.typ())
+}
+
+fn is_same_record(a: &Record, b: &Record) -> bool {
+    a.typ() == b.typ() && a.class() == b.class() && a.data() == b.data()
+}
+
+// is_known_answer returns true if the specified query already holds the answer with at least half of its TTL.
+fn is_known_answer(query: &Message, answer: &Record, name_comparison: NameComparison) -> bool {
+    query.answers().iter().any(|known| {
+        name_comparison.eq(known.name(), answer.name())
+            && is_same_record(known, answer)
+            && answer.ttl() / 2 <= known.ttl()
+    })
+}
+
+// compare_records compares the records of two probes sorted by the class, type and data, as a tiebreak does.
+fn compare_records(ours: &[Record], theirs: &[Record]) -> Ordering {
+    let sorted = |records: &[Record]| {
+        let mut keys: Vec<(u16, u16, Vec<u8>)> = records
+            .iter()
+            .map(|r| (r.class().to_value(), r.typ().to_value(), r.data().to_vec()))
+            .collect();
+        keys.sort();
+        keys
+    };
+    sorted(ours).cmp(&sorted(theirs))
+}
+
+// next_instance_name returns `name (2)` for `name`, and `name (3)` for `name (2)`.
+fn next_instance_name(name: &str) -> String {
+    if let Some(base) = name.strip_suffix(')') {
+        if let Some((base, n)) = base.rsplit_once(" (") {
+            if let Ok(n) = n.parse::<u32>() {
+                return format!("{} ({})", base, n + 1);
+            }
+        }
+    }
+    format!("{} (2)", name)
+}
@@ -0,0 +1,543 @@
+// Copyright (C) 2024 Satoshi Konno All rights reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//    http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+#[cfg(test)]
+mod tests {
+
+    use std::net::IpAddr;
+    use std::sync::{Arc, Mutex};
+    use std::time::{Duration, Instant};
+
+    use cybergarage::net::{Observer, Packet};
+
+    use crate::dns::{Message, Question, Record, Type, Writer};
+    use crate::net::{Interface, InterfaceAddr};
+    use crate::rate_limit::StormDetector;
+    use crate::{
+        AddressFamily, Advertiser, ConflictDecision, Hostname, HostnamePolicy, NameConflict,
+        OutgoingKind, RegistrationState, ResponderConfig, ServiceBuilder,
+    };
+
+    type Sent = Arc<Mutex<Vec<(OutgoingKind, Message)>>>;
+
+    fn test_advertiser() -> (Arc<Mutex<Advertiser>>, Sent) {
+        let advertiser = Advertiser::new();
+        let sent: Sent = Arc::new(Mutex::new(Vec::new()));
+        let hook_sent = sent.clone();
+        let mut config = ResponderConfig::new();
+        config.set_advertised_addrs(&["192.168.1.2".parse::<IpAddr>().unwrap()]);
+        {
+            let mut advertiser = advertiser.lock().unwrap();
+            advertiser.set_config(config);
+            advertiser.set_hostname(Hostname::new(HostnamePolicy::AutoIncrement(
+                "myhost".to_string(),
+            )));
+            advertiser.set_outgoing_hook(Box::new(move |pkt| {
+                hook_sent
+                    .lock()
+                    .unwrap()
+                    .push((pkt.kind(), pkt.message().unwrap()));
+                true
+            }));
+        }
+        (advertiser, sent)
+    }
+
+    fn query_packet(name: &str, typ: Type, known_answers: &[Record]) -> Packet {
+        let mut msg = Message::new();
+        msg.add_question(Question::new(name, typ));
+        for answer in known_answers {
+            msg.add_answer(answer.clone());
+        }
+        let mut pkt = Packet::from_bytes(&msg.to_bytes().unwrap());
+        pkt.set_from("192.168.1.10:5353".parse().unwrap());
+        pkt
+    }
+
+    fn count(sent: &Sent, kind: OutgoingKind) -> usize {
+        sent.lock()
+            .unwrap()
+            .iter()
+            .filter(|(k, _)| *k == kind)
+            .count()
+    }
+
+    fn announce(advertiser: &Arc<Mutex<Advertiser>>) -> u64 {
+        let service = ServiceBuilder::new("My Web", "_http._tcp")
+            .port(80)
+            .attribute("path", "/")
+            .build()
+            .unwrap();
+        let id = advertiser.lock().unwrap().register(service).unwrap();
+        let started = Instant::now();
+        for ms in [250, 500, 750, 1750] {
+            advertiser
+                .lock()
+                .unwrap()
+                .poll_at(started + Duration::from_millis(ms));
+        }
+        id
+    }
+
+    #[test]
+    fn advertiser_probe_announce() {
+        let (advertiser, sent) = test_advertiser();
+        let service = ServiceBuilder::new("My Web", "_http._tcp")
+            .port(80)
+            .build()
+            .unwrap();
+        let id = advertiser.lock().unwrap().register(service).unwrap();
+        let started = Instant::now();
+        assert_eq!(count(&sent, OutgoingKind::Probe), 2);
+        {
+            let sent = sent.lock().unwrap();
+            let probe = &sent[1].1;
+            assert!(probe.questions()[0].unicast_response());
+            assert_eq!(probe.questions()[0].typ(), Type::ANY);
+            assert!(probe.authorities().iter().any(|r| r.typ() == Type::SRV));
+        }
+
+        for ms in [250, 500] {
+            advertiser
+                .lock()
+                .unwrap()
+                .poll_at(started + Duration::from_millis(ms));
+        }
+        assert_eq!(count(&sent, OutgoingKind::Probe), 6);
+        assert_eq!(
+            advertiser.lock().unwrap().state(id),
+            Some(RegistrationState::Probing)
+        );
+
+        advertiser
+            .lock()
+            .unwrap()
+            .poll_at(started + Duration::from_millis(750));
+        assert_eq!(count(&sent, OutgoingKind::Announcement), 2);
+        assert_eq!(
+            advertiser.lock().unwrap().state(id),
+            Some(RegistrationState::Announcing)
+        );
+        advertiser
+            .lock()
+            .unwrap()
+            .poll_at(started + Duration::from_millis(1750));
+        assert_eq!(count(&sent, OutgoingKind::Announcement), 4);
+        let advertiser = advertiser.lock().unwrap();
+        assert_eq!(advertiser.state(id), Some(RegistrationState::Announced));
+        assert_eq!(advertiser.stats().announcements_sent(), 4);
+        assert!(advertiser.index().has_name("My Web._http._tcp.local"));
+        assert!(advertiser.index().has_name("myhost.local"));
+    }
+
+    #[test]
+    fn advertiser_answer() {
+        let (advertiser, sent) = test_advertiser();
+        announce(&advertiser);
+        sent.lock().unwrap().clear();
+
+        let pkt = query_packet("My Web._http._tcp.local", Type::SRV, &[]);
+        advertiser.lock().unwrap().packet_received(&pkt);
+        {
+            let sent = sent.lock().unwrap();
+            assert_eq!(sent.len(), 1);
+            let (kind, msg) = &sent[0];
+            assert_eq!(*kind, OutgoingKind::Response);
+            assert!(msg.is_response());
+            assert_eq!(msg.answers()[0].typ(), Type::SRV);
+            assert!(msg.additionals().iter().any(|r| r.typ() == Type::A));
+        }
+
+        // A shared record set is answered after a random delay.
+        let pkt = query_packet("_http._tcp.local", Type::PTR, &[]);
+        advertiser.lock().unwrap().packet_received(&pkt);
+        assert_eq!(sent.lock().unwrap().len(), 1);
+        advertiser
+            .lock()
+            .unwrap()
+            .poll_at(Instant::now() + Duration::from_millis(200));
+        {
+            let sent = sent.lock().unwrap();
+            assert_eq!(sent.len(), 2);
+            assert_eq!(sent[1].1.answers()[0].typ(), Type::PTR);
+        }
+
+        // A known answer with at least half of its TTL is not answered again.
+        let known = advertiser
+            .lock()
+            .unwrap()
+            .index()
+            .answers(&Question::new("_http._tcp.local", Type::PTR));
+        let pkt = query_packet("_http._tcp.local", Type::PTR, &known);
+        advertiser.lock().unwrap().packet_received(&pkt);
+        advertiser
+            .lock()
+            .unwrap()
+            .poll_at(Instant::now() + Duration::from_millis(200));
+        assert_eq!(sent.lock().unwrap().len(), 2);
+
+        let pkt = query_packet("other.local", Type::A, &[]);
+        advertiser.lock().unwrap().packet_received(&pkt);
+        assert_eq!(sent.lock().unwrap().len(), 2);
+    }
+
+    #[test]
+    fn advertiser_conflict() {
+        let (advertiser, _) = test_advertiser();
+        let service = ServiceBuilder::new("My Web", "_http._tcp")
+            .port(80)
+            .build()
+            .unwrap();
+        let id = advertiser.lock().unwrap().register(service).unwrap();
+
+        let mut w = Writer::new();
+        assert!(w.write_u16(0).is_ok());
+        assert!(w.write_u16(0).is_ok());
+        assert!(w.write_u16(8080).is_ok());
+        assert!(w.write_name("other.local").is_ok());
+        let mut srv = Record::new();
+        srv.set_name("My Web._http._tcp.local");
+        srv.set_typ(Type::SRV);
+        srv.set_ttl(120);
+        srv.set_data(w.to_bytes());
+        let mut msg = Message::new();
+        msg.set_qr(crate::dns::QR::Response);
+        msg.add_answer(srv.clone());
+        let pkt = Packet::from_bytes(&msg.to_bytes().unwrap());
+        advertiser.lock().unwrap().packet_received(&pkt);
+        assert_eq!(
+            advertiser
+                .lock()
+                .unwrap()
+                .service(id)
+                .unwrap()
+                .instance_name(),
+            "My Web (2)"
+        );
+
+        srv.set_name("My Web (2)._http._tcp.local");
+        let mut msg = Message::new();
+        msg.set_qr(crate::dns::QR::Response);
+        msg.add_answer(srv);
+        let pkt = Packet::from_bytes(&msg.to_bytes().unwrap());
+        advertiser.lock().unwrap().packet_received(&pkt);
+        let advertiser = advertiser.lock().unwrap();
+        assert_eq!(
+            advertiser.service(id).unwrap().instance_name(),
+            "My Web (3)"
+        );
+        assert_eq!(advertiser.state(id), Some(RegistrationState::Probing));
+    }
+
+    #[test]
+    fn advertiser_goodbye() {
+        let (advertiser, sent) = test_advertiser();
+        let id = announce(&advertiser);
+        sent.lock().unwrap().clear();
+
+        assert!(advertiser.lock().unwrap().unregister(id).is_ok());
+        assert!(advertiser.lock().unwrap().unregister(id).is_err());
+        {
+            let sent = sent.lock().unwrap();
+            assert_eq!(sent.len(), 1);
+            assert!(sent[0].1.answers().iter().all(|r| r.ttl() == 0));
+            assert!(sent[0].1.answers().iter().any(|r| r.typ() == Type::SRV));
+        }
+        assert!(!advertiser
+            .lock()
+            .unwrap()
+            .index()
+            .has_name("My Web._http._tcp.local"));
+
+        let report = advertiser
+            .lock()
+            .unwrap()
+            .shutdown(Duration::from_secs(1))
+            .unwrap();
+        assert_eq!(report.goodbyes_sent(), 1);
+        assert!(!report.transport_stopped());
+    }
+
+    #[test]
+    fn advertiser_shutdown() {
+        let (advertiser, sent) = test_advertiser();
+        announce(&advertiser);
+        sent.lock().unwrap().clear();
+
+        // The delayed response of a shared record set is flushed instead of being sent.
+        let pkt = query_packet("_http._tcp.local", Type::PTR, &[]);
+        advertiser.lock().unwrap().packet_received(&pkt);
+        let report = advertiser
+            .lock()
+            .unwrap()
+            .shutdown(Duration::from_secs(1))
+            .unwrap();
+        assert_eq!(report.replies_flushed(), 1);
+        assert_eq!(report.goodbyes_sent(), 1);
+        assert!(report.is_complete());
+        advertiser
+            .lock()
+            .unwrap()
+            .poll_at(Instant::now() + Duration::from_millis(200));
+        assert_eq!(count(&sent, OutgoingKind::Response), 0);
+    }
+
+    #[test]
+    fn advertiser_name_conflict_callback() {
+        let (advertiser, _) = test_advertiser();
+        let conflicts: Arc<Mutex<Vec<NameConflict>>> = Arc::new(Mutex::new(Vec::new()));
+        let callback_conflicts = conflicts.clone();
+        advertiser
+            .lock()
+            .unwrap()
+            .on_name_conflict(Box::new(move |conflict| {
+                callback_conflicts.lock().unwrap().push(conflict.clone());
+                match conflict.old_name() {
+                    "Printer" => ConflictDecision::Deregister,
+                    _ => ConflictDecision::Rename,
+                }
+            }));
+        let mut ids = Vec::new();
+        for name in ["My Web", "Printer"] {
+            let service = ServiceBuilder::new(name, "_http._tcp")
+                .port(80)
+                .build()
+                .unwrap();
+            ids.push(advertiser.lock().unwrap().register(service).unwrap());
+        }
+
+        let mut msg = Message::new();
+        msg.set_qr(crate::dns::QR::Response);
+        for name in ["My Web._http._tcp.local", "Printer._http._tcp.local"] {
+            let mut srv = "0 0 8080 other.local."
+                .parse::<crate::dns::SRVRecord>()
+                .unwrap();
+            srv.set_name(name);
+            let mut record = srv.to_record().unwrap();
+            record.set_ttl(120);
+            msg.add_answer(record);
+        }
+        let mut pkt = Packet::from_bytes(&msg.to_bytes().unwrap());
+        pkt.set_from("192.168.1.20:5353".parse().unwrap());
+        advertiser.lock().unwrap().packet_received(&pkt);
+
+        let conflicts = conflicts.lock().unwrap();
+        assert_eq!(conflicts.len(), 2);
+        assert_eq!(conflicts[0].id(), ids[0]);
+        assert_eq!(conflicts[0].old_name(), "My Web");
+        assert_eq!(conflicts[0].new_name(), "My Web (2)");
+        assert_eq!(conflicts[0].source(), "192.168.1.20:5353".parse().unwrap());
+        assert_eq!(conflicts[1].new_name(), "Printer (2)");
+        let advertiser = advertiser.lock().unwrap();
+        assert_eq!(
+            advertiser.service(ids[0]).unwrap().instance_name(),
+            "My Web (2)"
+        );
+        // The vetoed service keeps its provisioned name by being withdrawn.
+        assert!(advertiser.service(ids[1]).is_none());
+        assert_eq!(advertiser.state(ids[1]), None);
+        assert!(!advertiser.index().has_name("Printer._http._tcp.local"));
+    }
+
+    #[test]
+    fn advertiser_answer_routes() {
+        let (advertiser, sent) = test_advertiser();
+        let routes = Arc::new(Mutex::new(Vec::new()));
+        let hook_routes = routes.clone();
+        advertiser.lock().unwrap().set_interfaces(vec![
+            Interface::new(
+                "eth0",
+                2,
+                &[InterfaceAddr::new("192.168.1.2".parse().unwrap(), 24)],
+            ),
+            Interface::new(
+                "wlan0",
+                3,
+                &[InterfaceAddr::new("fe80::2".parse().unwrap(), 64)],
+            ),
+        ]);
+        announce(&advertiser);
+        sent.lock().unwrap().clear();
+        advertiser
+            .lock()
+            .unwrap()
+            .set_outgoing_hook(Box::new(move |pkt| {
+                hook_routes.lock().unwrap().push((
+                    pkt.kind(),
+                    pkt.family(),
+                    pkt.interface().map(|i| i.to_string()),
+                ));
+                true
+            }));
+
+        let mut pkt = query_packet("My Web._http._tcp.local", Type::SRV, &[]);
+        advertiser.lock().unwrap().packet_received(&pkt);
+        pkt.set_from("[fe80::10]:5353".parse().unwrap());
+        advertiser.lock().unwrap().packet_received(&pkt);
+        // The answer was multicast over IPv4 within the last second.
+        pkt.set_from("10.0.0.10:5353".parse().unwrap());
+        advertiser.lock().unwrap().packet_received(&pkt);
+        assert_eq!(
+            *routes.lock().unwrap(),
+            vec![
+                (
+                    OutgoingKind::Response,
+                    Some(AddressFamily::IPv4),
+                    Some("eth0".to_string())
+                ),
+                (
+                    OutgoingKind::Response,
+                    Some(AddressFamily::IPv6),
+                    Some("wlan0".to_string())
+                ),
+            ]
+        );
+
+        // A disabled family is not answered over.
+        let (advertiser, sent) = test_advertiser();
+        let mut config = ResponderConfig::new();
+        config.set_advertised_addrs(&["192.168.1.2".parse::<IpAddr>().unwrap()]);
+        config.set_family_enabled(AddressFamily::IPv6, false);
+        assert!(!config.is_family_enabled(AddressFamily::IPv6));
+        assert_eq!(config.families(), &vec![AddressFamily::IPv4]);
+        advertiser.lock().unwrap().set_config(config);
+        announce(&advertiser);
+        sent.lock().unwrap().clear();
+        pkt.set_from("[fe80::10]:5353".parse().unwrap());
+        advertiser.lock().unwrap().packet_received(&pkt);
+        assert_eq!(count(&sent, OutgoingKind::Response), 0);
+        pkt.set_from("192.168.1.10:5353".parse().unwrap());
+        advertiser.lock().unwrap().packet_received(&pkt);
+        assert_eq!(count(&sent, OutgoingKind::Response), 1);
+    }
+
+    #[test]
+    fn advertiser_answer_record_sets() {
+        let (advertiser, sent) = test_advertiser();
+        let mut config = ResponderConfig::new();
+        config.set_advertised_addrs(&[
+            "192.168.1.2".parse::<IpAddr>().unwrap(),
+            "192.168.1.3".parse::<IpAddr>().unwrap(),
+        ]);
+        advertiser.lock().unwrap().set_config(config);
+        for name in ["My Web", "Your Web", "Our Web"] {
+            let service = ServiceBuilder::new(name, "_http._tcp")
+                .port(80)
+                .build()
+                .unwrap();
+            advertiser.lock().unwrap().register(service).unwrap();
+        }
+        let started = Instant::now();
+        for ms in [250, 500, 750, 1750] {
+            advertiser
+                .lock()
+                .unwrap()
+                .poll_at(started + Duration::from_millis(ms));
+        }
+        sent.lock().unwrap().clear();
+
+        // All the PTR records of the services are answered together.
+        let pkt = query_packet("_http._tcp.local", Type::PTR, &[]);
+        advertiser.lock().unwrap().packet_received(&pkt);
+        advertiser
+            .lock()
+            .unwrap()
+            .poll_at(Instant::now() + Duration::from_millis(200));
+        {
+            let sent = sent.lock().unwrap();
+            assert_eq!(sent.len(), 1);
+            let ptrs = sent[0]
+                .1
+                .answers()
+                .iter()
+                .filter(|r| r.typ() == Type::PTR)
+                .count();
+            assert_eq!(ptrs, 3);
+        }
+
+        // All the addresses of the host are answered together.
+        let pkt = query_packet("myhost.local", Type::A, &[]);
+        advertiser.lock().unwrap().packet_received(&pkt);
+        let sent = sent.lock().unwrap();
+        assert_eq!(sent.len(), 2);
+        let addrs = sent[1]
+            .1
+            .answers()
+            .iter()
+            .filter(|r| r.typ() == Type::A)
+            .count();
+        assert_eq!(addrs, 2);
+    }
+
+    #[test]
+    fn advertiser_query_storm() {
+        let (advertiser, sent) = test_advertiser();
+        advertiser
+            .lock()
+            .unwrap()
+            .set_storm_detector(StormDetector::with_threshold(
+                2,
+                Duration::from_secs(1),
+                Duration::from_secs(10),
+            ));
+        announce(&advertiser);
+        sent.lock().unwrap().clear();
+
+        let key = "my web._http._tcp.local/SRV";
+        let mut pkt = query_packet("My Web._http._tcp.local", Type::SRV, &[]);
+        for n in 0..3 {
+            assert!(!advertiser
+                .lock()
+                .unwrap()
+                .storm_detector()
+                .is_suppressed(key));
+            pkt.set_from(format!("192.168.1.{}:5353", 10 + n).parse().unwrap());
+            advertiser.lock().unwrap().packet_received(&pkt);
+        }
+        assert!(advertiser
+            .lock()
+            .unwrap()
+            .storm_detector()
+            .is_suppressed(key));
+        assert_eq!(count(&sent, OutgoingKind::Response), 1);
+    }
+
+    #[test]
+    fn advertiser_on_link_check() {
+        let (advertiser, sent) = test_advertiser();
+        advertiser
+            .lock()
+            .unwrap()
+            .set_interfaces(vec![Interface::new(
+                "eth0",
+                2,
+                &[InterfaceAddr::new("192.168.1.2".parse().unwrap(), 24)],
+            )]);
+        announce(&advertiser);
+        sent.lock().unwrap().clear();
+
+        // The query from an off-link source is not answered.
+        let mut pkt = query_packet("My Web._http._tcp.local", Type::SRV, &[]);
+        pkt.set_from("203.0.113.1:5353".parse().unwrap());
+        advertiser.lock().unwrap().packet_received(&pkt);
+        assert_eq!(count(&sent, OutgoingKind::Response), 0);
+        assert_eq!(advertiser.lock().unwrap().stats().off_link_dropped(), 1);
+
+        advertiser.lock().unwrap().set_on_link_check(false);
+        assert!(!advertiser.lock().unwrap().is_on_link_check());
+        advertiser.lock().unwrap().packet_received(&pkt);
+        assert_eq!(count(&sent, OutgoingKind::Response), 1);
+        assert_eq!(advertiser.lock().unwrap().stats().off_link_dropped(), 1);
+    }
+}
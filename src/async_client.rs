@@ -0,0 +1,259 @@
+// Copyright (C) 2024 Satoshi Konno All rights reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//    http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::io;
+use std::net::{Ipv4Addr, Ipv6Addr, SocketAddr};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use cybergarage::net::{Observer, Packet};
+use socket2::{Domain, Protocol, SockAddr, Socket, Type};
+use tokio::net::UdpSocket;
+use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
+
+use crate::default::PORT;
+use crate::discoverer::Discoverer;
+use crate::event::ServiceEvent;
+use crate::outgoing::OutgoingHook;
+use crate::query::Query;
+use crate::service::Service;
+use crate::stats::Stats;
+use crate::supervisor::{lock_recovered, Supervisor, TaskStatus};
+
+const MULTICAST_V4_GROUP: Ipv4Addr = Ipv4Addr::new(224, 0, 0, 251);
+const MULTICAST_V6_GROUP: Ipv6Addr = Ipv6Addr::new(0xff02, 0, 0, 0, 0, 0, 0, 0xfb);
+const MDNS_HOP_LIMIT: u32 = 255;
+const RECV_BUFFER_SIZE: usize = 9000;
+const TICK: Duration = Duration::from_millis(100);
+
+/// RECEIVE_V4_TASK is the name of the task which receives the IPv4 packets.
+pub const RECEIVE_V4_TASK: &str = "async-receive-v4";
+/// RECEIVE_V6_TASK is the name of the task which receives the IPv6 packets.
+pub const RECEIVE_V6_TASK: &str = "async-receive-v6";
+/// SEND_TASK is the name of the task which sends the packets.
+pub const SEND_TASK: &str = "async-send";
+/// TICK_TASK is the name of the task which runs the timers of the discoverer.
+pub const TICK_TASK: &str = "async-tick";
+
+/// AsyncClient represents a client running on the tokio runtime, whose sockets are driven by tasks instead of threads.
+pub struct AsyncClient {
+    discoverer: Arc<Mutex<Discoverer>>,
+    outbound_rx: Option<mpsc::UnboundedReceiver<Vec<u8>>>,
+    supervisor: Supervisor,
+    tasks: Vec<(&'static str, JoinHandle<()>)>,
+}
+
+impl AsyncClient {
+    /// new creates a new client, which can be used before it is started.
+    pub fn new() -> AsyncClient {
+        let discoverer = Discoverer::new();
+        let (outbound_tx, outbound_rx) = mpsc::unbounded_channel();
+        discoverer
+            .lock()
+            .unwrap()
+            .set_outbound(Box::new(move |bytes| {
+                outbound_tx
+                    .send(bytes.to_vec())
+                    .map_err(|_| io::Error::new(io::ErrorKind::NotConnected, "client stopped"))
+            }));
+        let supervisor = discoverer.lock().unwrap().supervisor().clone();
+        AsyncClient {
+            discoverer,
+            outbound_rx: Some(outbound_rx),
+            supervisor,
+            tasks: Vec::new(),
+        }
+    }
+
+    /// search queries the specified service, and the answers are delivered as service events.
+    /// The query is sent once the client is started.
+    pub async fn search(&self, query: &Query) -> io::Result<()> {
+        self.discoverer.lock().unwrap().search(query)
+    }
+
+    /// subscribe returns an awaitable receiver of the service events.
+    pub fn subscribe(&self) -> mpsc::UnboundedReceiver<ServiceEvent> {
+        self.discoverer.lock().unwrap().subscribe_tokio()
+    }
+
+    /// services returns the discovered services.
+    pub fn services(&self) -> Vec<Service> {
+        self.discoverer.lock().unwrap().services().clone()
+    }
+
+    /// stats returns the statistics of the client.
+    pub fn stats(&self) -> Stats {
+        self.discoverer.lock().unwrap().stats().clone()
+    }
+
+    /// set_outgoing_hook sets the hook invoked with every packet about to be transmitted, which may rewrite or veto the packet.
+    pub fn set_outgoing_hook(&self, hook: OutgoingHook) {
+        self.discoverer.lock().unwrap().set_outgoing_hook(hook);
+    }
+
+    /// packet_received handles the specified packet received by another transport.
+    pub fn packet_received(&self, pkt: &Packet) {
+        self.discoverer.lock().unwrap().packet_received(pkt);
+    }
+
+    /// tasks returns the statuses of the tasks of the client, and a panicked task is restarted.
+    pub fn tasks(&self) -> Vec<TaskStatus> {
+        self.supervisor.tasks()
+    }
+
+    /// is_running returns true if the client is started.
+    pub fn is_running(&self) -> bool {
+        !self.tasks.is_empty()
+    }
+
+    /// start joins the multicast groups, and spawns the tasks receiving and sending the packets on the current runtime.
+    /// IPv6 is skipped with a warning when it is unavailable.
+    pub async fn start(&mut self) -> io::Result<()> {
+        if self.is_running() {
+            return Ok(());
+        }
+        let outbound_rx = match self.outbound_rx.take() {
+            Some(outbound_rx) => outbound_rx,
+            None => return Err(io::Error::other("client can not be started again")),
+        };
+        let mut sockets = vec![(RECEIVE_V4_TASK, Arc::new(bind_v4()?))];
+        match bind_v6() {
+            Ok(socket) => sockets.push((RECEIVE_V6_TASK, Arc::new(socket))),
+            Err(e) => log::warn!("IPv6 multicast not joined: {}", e),
+        }
+        for (name, socket) in &sockets {
+            let socket = socket.clone();
+            let discoverer = self.discoverer.clone();
+            let task = self.supervisor.spawn_async(name, move || {
+                receive_loop(socket.clone(), discoverer.clone())
+            });
+            self.tasks.push((name, task));
+        }
+        // The receiver is shared, so a restarted send task continues with the queued packets.
+        let sockets: Vec<Arc<UdpSocket>> = sockets.into_iter().map(|(_, socket)| socket).collect();
+        let outbound_rx = Arc::new(tokio::sync::Mutex::new(outbound_rx));
+        let task = self.supervisor.spawn_async(SEND_TASK, move || {
+            send_loop(sockets.clone(), outbound_rx.clone())
+        });
+        self.tasks.push((SEND_TASK, task));
+        let discoverer = self.discoverer.clone();
+        let task = self
+            .supervisor
+            .spawn_async(TICK_TASK, move || tick_loop(discoverer.clone()));
+        self.tasks.push((TICK_TASK, task));
+        Ok(())
+    }
+
+    /// stop aborts the tasks of the client, which leaves the multicast groups.
+    pub async fn stop(&mut self) -> io::Result<()> {
+        for (name, task) in self.tasks.drain(..) {
+            task.abort();
+            // A task ends with a cancelled error once aborted.
+            let _ = task.await;
+            self.supervisor.set_finished(name);
+        }
+        Ok(())
+    }
+}
+
+impl Default for AsyncClient {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Drop for AsyncClient {
+    fn drop(&mut self) {
+        for (_, task) in &self.tasks {
+            task.abort();
+        }
+    }
+}
+
+fn bind_v4() -> io::Result<UdpSocket> {
+    let socket = Socket::new(Domain::IPV4, Type::DGRAM, Some(Protocol::UDP))?;
+    socket.set_reuse_address(true)?;
+    #[cfg(unix)]
+    socket.set_reuse_port(true)?;
+    socket.set_nonblocking(true)?;
+    socket.bind(&SockAddr::from(SocketAddr::from((
+        Ipv4Addr::UNSPECIFIED,
+        PORT,
+    ))))?;
+    socket.join_multicast_v4(&MULTICAST_V4_GROUP, &Ipv4Addr::UNSPECIFIED)?;
+    socket.set_multicast_ttl_v4(MDNS_HOP_LIMIT)?;
+    UdpSocket::from_std(socket.into())
+}
+
+fn bind_v6() -> io::Result<UdpSocket> {
+    let socket = Socket::new(Domain::IPV6, Type::DGRAM, Some(Protocol::UDP))?;
+    socket.set_only_v6(true)?;
+    socket.set_reuse_address(true)?;
+    #[cfg(unix)]
+    socket.set_reuse_port(true)?;
+    socket.set_nonblocking(true)?;
+    socket.bind(&SockAddr::from(SocketAddr::from((
+        Ipv6Addr::UNSPECIFIED,
+        PORT,
+    ))))?;
+    socket.join_multicast_v6(&MULTICAST_V6_GROUP, 0)?;
+    socket.set_multicast_hops_v6(MDNS_HOP_LIMIT)?;
+    UdpSocket::from_std(socket.into())
+}
+
+async fn receive_loop(socket: Arc<UdpSocket>, discoverer: Arc<Mutex<Discoverer>>) {
+    let mut buf = vec![0; RECV_BUFFER_SIZE];
+    loop {
+        let (n, from) = match socket.recv_from(&mut buf).await {
+            Ok(received) => received,
+            // A persistent error is retried after a tick, so it does not spin the runtime nor flood the log.
+            Err(e) => {
+                log::warn!("receive failed: {}", e);
+                tokio::time::sleep(TICK).await;
+                continue;
+            }
+        };
+        let mut pkt = Packet::from_bytes(&buf[..n].to_vec());
+        pkt.set_from(from);
+        lock_recovered(&discoverer).packet_received(&pkt);
+    }
+}
+
+async fn send_loop(
+    sockets: Vec<Arc<UdpSocket>>,
+    outbound_rx: Arc<tokio::sync::Mutex<mpsc::UnboundedReceiver<Vec<u8>>>>,
+) {
+    let mut outbound_rx = outbound_rx.lock().await;
+    while let Some(bytes) = outbound_rx.recv().await {
+        for socket in &sockets {
+            let to: SocketAddr = match socket.local_addr() {
+                Ok(SocketAddr::V4(_)) => (MULTICAST_V4_GROUP, PORT).into(),
+                Ok(SocketAddr::V6(_)) => (MULTICAST_V6_GROUP, PORT).into(),
+                Err(_) => continue,
+            };
+            if let Err(e) = socket.send_to(&bytes, to).await {
+                log::warn!("send to {} failed: {}", to, e);
+            }
+        }
+    }
+}
+
+async fn tick_loop(discoverer: Arc<Mutex<Discoverer>>) {
+    let mut interval = tokio::time::interval(TICK);
+    loop {
+        interval.tick().await;
+        lock_recovered(&discoverer).tick(Instant::now());
+    }
+}
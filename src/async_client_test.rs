@@ -0,0 +1,54 @@
+// Copyright (C) 2024 Satoshi Konno All rights reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//    http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+#[cfg(test)]
+mod tests {
+
+    use std::sync::{Arc, Mutex};
+
+    use crate::fixture_test::srv_response_packet;
+    use crate::{AsyncClient, OutgoingKind, Query, ServiceEvent};
+
+    #[test]
+    fn async_client() {
+        let rt = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .unwrap();
+        rt.block_on(async {
+            let client = AsyncClient::new();
+            let mut events = client.subscribe();
+            let sent = Arc::new(Mutex::new(Vec::new()));
+            let hook_sent = sent.clone();
+            client.set_outgoing_hook(Box::new(move |pkt| {
+                hook_sent.lock().unwrap().push(pkt.kind());
+                true
+            }));
+
+            let query = Query::with("_http._tcp", "local");
+            assert!(client.search(&query).await.is_ok());
+            assert_eq!(*sent.lock().unwrap(), vec![OutgoingKind::Query]);
+            assert_eq!(client.stats().queries_sent(), 1);
+            assert!(!client.is_running());
+
+            client.packet_received(&srv_response_packet("test._http._tcp.local", 80));
+
+            match events.recv().await {
+                Some(ServiceEvent::Added(service)) => assert_eq!(service.port(), 80),
+                _ => panic!("expected an added event"),
+            }
+            assert_eq!(client.services().len(), 1);
+        });
+    }
+}
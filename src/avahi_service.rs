@@ -0,0 +1,421 @@
+// Copyright (C) 2024 Satoshi Konno All rights reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//    http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::fs;
+use std::path::Path;
+
+use crate::error::{Error, Result};
+use crate::family::AddressFamily;
+use crate::service_builder::{ServiceBuilder, ServiceInfo};
+use crate::txt;
+
+/// AVAHI_SERVICES_DIR is the directory of the static service definitions of Avahi.
+pub const AVAHI_SERVICES_DIR: &str = "/etc/avahi/services";
+
+const HOST_NAME_WILDCARD: &str = "%h";
+
+/// parse_service_group returns the services of the specified Avahi `.service` XML document,
+/// where the `%h` wildcard of a name with `replace-wildcards="yes"` is replaced with the specified host name.
+/// The text and `binary-hex` TXT value formats are supported.
+pub fn parse_service_group(xml: &str, hostname: &str) -> Result<Vec<ServiceBuilder>> {
+    let root = Parser::new(xml).parse_document()?;
+    if root.name != "service-group" {
+        return Err(Error::from_string(&format!(
+            "Invalid service group: <{}>",
+            root.name
+        )));
+    }
+    let name = root.child("name").ok_or(missing("name"))?;
+    let mut instance_name = name.text.trim().to_string();
+    if name.attr("replace-wildcards") == Some("yes") {
+        instance_name = instance_name.replace(HOST_NAME_WILDCARD, hostname);
+    }
+
+    let mut builders = Vec::new();
+    for service in root.children.iter().filter(|e| e.name == "service") {
+        let service_type = service.child("type").ok_or(missing("type"))?;
+        let mut builder = ServiceBuilder::new(&instance_name, service_type.text.trim());
+        builder = match service.attr("protocol") {
+            None | Some("any") => builder,
+            Some("ipv4") => builder.family(AddressFamily::IPv4),
+            Some("ipv6") => builder.family(AddressFamily::IPv6),
+            Some(protocol) => {
+                return Err(Error::from_string(&format!(
+                    "Invalid protocol: {:?}",
+                    protocol
+                )))
+            }
+        };
+        let port = service.child("port").ok_or(missing("port"))?;
+        let port = port
+            .text
+            .trim()
+            .parse::<u16>()
+            .map_err(|_| Error::from_string(&format!("Invalid port: {:?}", port.text)))?;
+        builder = builder.port(port);
+        if let Some(domain) = service.child("domain-name") {
+            builder = builder.domain(domain.text.trim());
+        }
+        if let Some(host) = service.child("host-name") {
+            builder = builder.host(host.text.trim());
+        }
+        for element in &service.children {
+            match element.name.as_str() {
+                "subtype" => builder = builder.subtype(element.text.trim()),
+                "txt-record" => {
+                    let value = txt_value(element)?;
+                    let (key, value) = txt::parse_attribute(&value);
+                    builder = builder.attribute(&key, &value);
+                }
+                _ => {}
+            }
+        }
+        builders.push(builder);
+    }
+    if builders.is_empty() {
+        return Err(missing("service"));
+    }
+    Ok(builders)
+}
+
+/// read_service_file returns the services of the specified Avahi `.service` file.
+pub fn read_service_file(path: &Path, hostname: &str) -> Result<Vec<ServiceBuilder>> {
+    let xml = fs::read_to_string(path)
+        .map_err(|e| Error::from_string(&format!("{}: {}", path.display(), e)))?;
+    parse_service_group(&xml, hostname)
+        .map_err(|e| Error::from_string(&format!("{}: {}", path.display(), e)))
+}
+
+/// read_services_dir returns the services of all `.service` files in the specified directory such as AVAHI_SERVICES_DIR
+/// in the file name order.
+pub fn read_services_dir(dir: &Path, hostname: &str) -> Result<Vec<ServiceBuilder>> {
+    let entries =
+        fs::read_dir(dir).map_err(|e| Error::from_string(&format!("{}: {}", dir.display(), e)))?;
+    let mut paths = entries
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| path.extension().is_some_and(|ext| ext == "service"))
+        .collect::<Vec<_>>();
+    paths.sort();
+    let mut builders = Vec::new();
+    for path in paths {
+        builders.extend(read_service_file(&path, hostname)?);
+    }
+    Ok(builders)
+}
+
+/// format_service_group returns the Avahi `.service` XML document of the specified services, which must share the instance name.
+pub fn format_service_group(services: &[ServiceInfo]) -> Result<String> {
+    let first = services.first().ok_or(missing("service"))?;
+    if let Some(other) = services
+        .iter()
+        .find(|s| s.instance_name() != first.instance_name())
+    {
+        return Err(Error::from_string(&format!(
+            "Instance names differ in a service group: {:?} and {:?}",
+            first.instance_name(),
+            other.instance_name()
+        )));
+    }
+
+    let mut xml = String::new();
+    xml.push_str("<?xml version=\"1.0\" standalone='no'?>\n");
+    xml.push_str("<!DOCTYPE service-group SYSTEM \"avahi-service.dtd\">\n");
+    xml.push_str("<service-group>\n");
+    xml.push_str(&format!(
+        "  <name>{}</name>\n",
+        escape(first.instance_name())
+    ));
+    for service in services {
+        match service.family() {
+            Some(AddressFamily::IPv4) => xml.push_str("  <service protocol=\"ipv4\">\n"),
+            Some(AddressFamily::IPv6) => xml.push_str("  <service protocol=\"ipv6\">\n"),
+            None => xml.push_str("  <service>\n"),
+        }
+        xml.push_str(&format!(
+            "    <type>{}</type>\n",
+            escape(service.service_type())
+        ));
+        for subtype in service.subtypes() {
+            xml.push_str(&format!(
+                "    <subtype>{}._sub.{}</subtype>\n",
+                escape(subtype),
+                escape(service.service_type())
+            ));
+        }
+        xml.push_str(&format!(
+            "    <domain-name>{}</domain-name>\n",
+            escape(service.domain())
+        ));
+        if let Some(host) = service.host() {
+            xml.push_str(&format!("    <host-name>{}</host-name>\n", escape(host)));
+        }
+        xml.push_str(&format!("    <port>{}</port>\n", service.port()));
+        for (key, value) in service.attributes() {
+            xml.push_str(&format!(
+                "    <txt-record>{}</txt-record>\n",
+                escape(&txt::format_attribute(key, value))
+            ));
+        }
+        xml.push_str("  </service>\n");
+    }
+    xml.push_str("</service-group>\n");
+    Ok(xml)
+}
+
+fn missing(name: &str) -> Error {
+    Error::from_string(&format!("Missing <{}> in the service group", name))
+}
+
+// txt_value returns the TXT string of the specified txt-record element decoded by its value format.
+fn txt_value(element: &Element) -> Result<String> {
+    match element.attr("value-format") {
+        None | Some("text") => Ok(element.text.clone()),
+        Some("binary-hex") => {
+            let digits = element.text.split_whitespace().collect::<String>();
+            let bytes = hex::decode(digits).map_err(|e| {
+                Error::from_string(&format!("Invalid binary-hex TXT record: {}", e))
+            })?;
+            Ok(String::from_utf8_lossy(&bytes).to_string())
+        }
+        Some(format) => Err(Error::from_string(&format!(
+            "Unsupported TXT value format: {:?}",
+            format
+        ))),
+    }
+}
+
+fn escape(s: &str) -> String {
+    let mut escaped = String::new();
+    for c in s.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+// Element is an XML element, which keeps only the attributes, the child elements and the concatenated text.
+struct Element {
+    name: String,
+    attrs: Vec<(String, String)>,
+    children: Vec<Element>,
+    text: String,
+}
+
+impl Element {
+    fn attr(&self, name: &str) -> Option<&str> {
+        self.attrs
+            .iter()
+            .find(|(n, _)| n == name)
+            .map(|(_, value)| value.as_str())
+    }
+
+    fn child(&self, name: &str) -> Option<&Element> {
+        self.children.iter().find(|e| e.name == name)
+    }
+}
+
+// Parser is a minimal XML parser for the service definitions, which skips the declarations, comments and DOCTYPE.
+struct Parser<'a> {
+    s: &'a str,
+    pos: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn new(s: &'a str) -> Parser<'a> {
+        Parser { s, pos: 0 }
+    }
+
+    fn rest(&self) -> &'a str {
+        &self.s[self.pos..]
+    }
+
+    fn error(&self, msg: &str) -> Error {
+        Error::from_string(&format!("Invalid XML at {}: {}", self.pos, msg))
+    }
+
+    fn skip_whitespace(&mut self) {
+        let rest = self.rest();
+        self.pos += rest.len() - rest.trim_start().len();
+    }
+
+    fn skip_past(&mut self, end: &str) -> Result<()> {
+        match self.rest().find(end) {
+            Some(n) => {
+                self.pos += n + end.len();
+                Ok(())
+            }
+            None => Err(self.error(&format!("{:?} is not closed", end))),
+        }
+    }
+
+    // skip_misc skips the whitespaces, declarations, comments and DOCTYPE before an element.
+    fn skip_misc(&mut self) -> Result<()> {
+        loop {
+            self.skip_whitespace();
+            if self.rest().starts_with("<?") {
+                self.skip_past("?>")?;
+            } else if self.rest().starts_with("<!--") {
+                self.skip_past("-->")?;
+            } else if self.rest().starts_with("<!") {
+                self.skip_past(">")?;
+            } else {
+                return Ok(());
+            }
+        }
+    }
+
+    fn parse_document(&mut self) -> Result<Element> {
+        self.skip_misc()?;
+        let root = self.parse_element()?;
+        self.skip_misc()?;
+        if !self.rest().is_empty() {
+            return Err(self.error("trailing content"));
+        }
+        Ok(root)
+    }
+
+    fn parse_name(&mut self) -> Result<String> {
+        let rest = self.rest();
+        let len = rest
+            .find(|c: char| c.is_whitespace() || c == '>' || c == '/' || c == '=')
+            .unwrap_or(rest.len());
+        if len == 0 {
+            return Err(self.error("expected a name"));
+        }
+        self.pos += len;
+        Ok(rest[..len].to_string())
+    }
+
+    fn parse_element(&mut self) -> Result<Element> {
+        if !self.rest().starts_with('<') {
+            return Err(self.error("expected an element"));
+        }
+        self.pos += 1;
+        let mut element = Element {
+            name: self.parse_name()?,
+            attrs: Vec::new(),
+            children: Vec::new(),
+            text: String::new(),
+        };
+
+        loop {
+            self.skip_whitespace();
+            if self.rest().starts_with("/>") {
+                self.pos += 2;
+                return Ok(element);
+            }
+            if self.rest().starts_with('>') {
+                self.pos += 1;
+                break;
+            }
+            let name = self.parse_name()?;
+            self.skip_whitespace();
+            if !self.rest().starts_with('=') {
+                return Err(self.error("expected '='"));
+            }
+            self.pos += 1;
+            self.skip_whitespace();
+            let quote = match self.rest().chars().next() {
+                Some(c) if c == '"' || c == '\'' => c,
+                _ => return Err(self.error("expected a quoted value")),
+            };
+            self.pos += 1;
+            let len = self
+                .rest()
+                .find(quote)
+                .ok_or_else(|| self.error("attribute value is not closed"))?;
+            let value = unescape(&self.rest()[..len]).map_err(|e| self.error(&e))?;
+            self.pos += len + 1;
+            element.attrs.push((name, value));
+        }
+
+        loop {
+            let rest = self.rest();
+            if rest.starts_with("</") {
+                self.pos += 2;
+                let name = self.parse_name()?;
+                if name != element.name {
+                    return Err(self.error(&format!("<{}> is closed by </{}>", element.name, name)));
+                }
+                self.skip_whitespace();
+                if !self.rest().starts_with('>') {
+                    return Err(self.error("expected '>'"));
+                }
+                self.pos += 1;
+                return Ok(element);
+            } else if rest.starts_with("<!--") {
+                self.skip_past("-->")?;
+            } else if let Some(cdata) = rest.strip_prefix("<![CDATA[") {
+                let len = cdata
+                    .find("]]>")
+                    .ok_or_else(|| self.error("CDATA is not closed"))?;
+                element.text.push_str(&cdata[..len]);
+                self.pos += "<![CDATA[".len() + len + "]]>".len();
+            } else if rest.starts_with('<') {
+                element.children.push(self.parse_element()?);
+            } else if rest.is_empty() {
+                return Err(self.error(&format!("<{}> is not closed", element.name)));
+            } else {
+                let len = rest.find('<').unwrap_or(rest.len());
+                element
+                    .text
+                    .push_str(&unescape(&rest[..len]).map_err(|e| self.error(&e))?);
+                self.pos += len;
+            }
+        }
+    }
+}
+
+// unescape replaces the predefined entities and the character references.
+fn unescape(s: &str) -> std::result::Result<String, String> {
+    let mut unescaped = String::new();
+    let mut rest = s;
+    while let Some(n) = rest.find('&') {
+        unescaped.push_str(&rest[..n]);
+        rest = &rest[n + 1..];
+        let end = rest
+            .find(';')
+            .ok_or_else(|| format!("entity is not closed: {:?}", rest))?;
+        let entity = &rest[..end];
+        let c = match entity {
+            "lt" => Some('<'),
+            "gt" => Some('>'),
+            "amp" => Some('&'),
+            "quot" => Some('"'),
+            "apos" => Some('\''),
+            _ => match entity
+                .strip_prefix("#x")
+                .or_else(|| entity.strip_prefix("#X"))
+            {
+                Some(hex) => u32::from_str_radix(hex, 16).ok().and_then(char::from_u32),
+                None => entity
+                    .strip_prefix('#')
+                    .and_then(|dec| dec.parse::<u32>().ok())
+                    .and_then(char::from_u32),
+            },
+        };
+        match c {
+            Some(c) => unescaped.push(c),
+            None => return Err(format!("unknown entity: &{};", entity)),
+        }
+        rest = &rest[end + 1..];
+    }
+    unescaped.push_str(rest);
+    Ok(unescaped)
+}
@@ -0,0 +1,121 @@
+// Copyright (C) 2024 Satoshi Konno All rights reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//    http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+#[cfg(test)]
+mod tests {
+
+    use crate::avahi_service::{format_service_group, parse_service_group};
+    use crate::{AddressFamily, ServiceBuilder, ServiceInfo};
+
+    const SERVICE_GROUP: &str = r#"<?xml version="1.0" standalone='no'?><!--*-nxml-*-->
+<!DOCTYPE service-group SYSTEM "avahi-service.dtd">
+<!-- See avahi.service(5) for more information about this configuration file -->
+<service-group>
+  <name replace-wildcards="yes">Printer on %h</name>
+  <service protocol="ipv4">
+    <type>_ipp._tcp</type>
+    <subtype>_universal._sub._ipp._tcp</subtype>
+    <port>631</port>
+    <txt-record>txtvers=1</txt-record>
+    <txt-record>rp=printers/Office &amp; Lab</txt-record>
+    <txt-record value-format="binary-hex">54 4c 53 3d 31 2e 32</txt-record>
+  </service>
+  <service>
+    <type>_http._tcp</type>
+    <domain-name>example.com</domain-name>
+    <host-name>printer.local</host-name>
+    <port>80</port>
+  </service>
+</service-group>
+"#;
+
+    #[test]
+    fn service_builder_build() {
+        let service = ServiceBuilder::new("My Web", "_http._tcp.")
+            .port(8080)
+            .subtype("_printer")
+            .attribute("path", "/")
+            .attribute("txtvers", "1")
+            .build()
+            .unwrap();
+        assert_eq!(service.name(), "My Web._http._tcp.local");
+        assert_eq!(
+            service.subtype_names(),
+            vec!["_printer._sub._http._tcp.local"]
+        );
+        assert_eq!(service.attributes()[0].0, "txtvers");
+        assert_eq!(service.attribute("PATH"), Some("/"));
+        assert_eq!(service.host(), None);
+        assert_eq!(service.txt_data(), b"\x09txtvers=1\x06path=/");
+        let empty = ServiceBuilder::new("My Web", "_http._tcp").build().unwrap();
+        assert_eq!(empty.txt_data(), vec![0]);
+
+        let invalids = vec![
+            ServiceBuilder::new("", "_http._tcp"),
+            ServiceBuilder::new(&"x".repeat(64), "_http._tcp"),
+            ServiceBuilder::new("My Web", "http._tcp"),
+            ServiceBuilder::new("My Web", "_http._sctp"),
+            ServiceBuilder::new("My Web", "_http._tcp").attribute("a=b", ""),
+            ServiceBuilder::new("My Web", "_http._tcp").attribute("a", &"x".repeat(254)),
+        ];
+        for builder in invalids {
+            assert!(builder.clone().build().is_err(), "{:?}", builder);
+        }
+    }
+
+    #[test]
+    fn avahi_service_group() {
+        let services = parse_service_group(SERVICE_GROUP, "myhost")
+            .unwrap()
+            .into_iter()
+            .map(|builder| builder.build().unwrap())
+            .collect::<Vec<ServiceInfo>>();
+        assert_eq!(services.len(), 2);
+
+        let ipp = &services[0];
+        assert_eq!(ipp.name(), "Printer on myhost._ipp._tcp.local");
+        assert_eq!(ipp.family(), Some(AddressFamily::IPv4));
+        assert_eq!(ipp.port(), 631);
+        assert_eq!(ipp.subtypes(), &vec!["_universal".to_string()]);
+        assert_eq!(ipp.attribute("rp"), Some("printers/Office & Lab"));
+        assert_eq!(ipp.attribute("TLS"), Some("1.2"));
+
+        let http = &services[1];
+        assert_eq!(http.name(), "Printer on myhost._http._tcp.example.com");
+        assert_eq!(http.family(), None);
+        assert_eq!(http.host(), Some("printer.local"));
+        assert!(http.attributes().is_empty());
+
+        let xml = format_service_group(&services).unwrap();
+        let reparsed = parse_service_group(&xml, "otherhost")
+            .unwrap()
+            .into_iter()
+            .map(|builder| builder.build().unwrap())
+            .collect::<Vec<ServiceInfo>>();
+        assert_eq!(reparsed, services);
+
+        let other = ServiceBuilder::new("Other", "_http._tcp").build().unwrap();
+        assert!(format_service_group(&[services[0].clone(), other]).is_err());
+
+        let invalids = vec![
+            "<service-group><name>x</name></service-group>",
+            "<service-group><name>x</name><service><type>_http._tcp</type></service></service-group>",
+            "<service-group><name>x</name><service><type>_http._tcp</type><port>80</port></service>",
+            "<group><name>x</name></group>",
+        ];
+        for xml in invalids {
+            assert!(parse_service_group(xml, "myhost").is_err(), "{}", xml);
+        }
+    }
+}
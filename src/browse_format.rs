@@ -0,0 +1,165 @@
+// Copyright (C) 2024 Satoshi Konno All rights reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//    http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::event::ServiceEvent;
+use crate::service::Service;
+
+// dns_sd.h: kDNSServiceFlagsAdd
+const DNS_SD_FLAGS_ADD: u32 = 0x2;
+
+const UNKNOWN_INTERFACE: &str = "*";
+
+/// BrowseFormat represents an output format of the browse results.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum BrowseFormat {
+    /// DnsSd is the output format of `dns-sd -B`.
+    DnsSd,
+    /// Avahi is the human readable output format of `avahi-browse`.
+    Avahi,
+    /// AvahiParsable is the output format of `avahi-browse -p`.
+    AvahiParsable,
+}
+
+impl BrowseFormat {
+    /// from_name returns the format of the specified name.
+    pub fn from_name(name: &str) -> Option<BrowseFormat> {
+        match name {
+            "dns-sd" => Some(BrowseFormat::DnsSd),
+            "avahi" => Some(BrowseFormat::Avahi),
+            "avahi-parsable" => Some(BrowseFormat::AvahiParsable),
+            _ => None,
+        }
+    }
+}
+
+/// BrowseFormatter formats service events into the text lines of the existing browse tools,
+/// so scripts written for `dns-sd -B` or `avahi-browse` can consume the output unchanged.
+pub struct BrowseFormatter {
+    format: BrowseFormat,
+}
+
+impl BrowseFormatter {
+    /// new creates a new formatter of the specified format.
+    pub fn new(format: BrowseFormat) -> BrowseFormatter {
+        BrowseFormatter { format }
+    }
+
+    /// format returns the format of the formatter.
+    pub fn format(&self) -> BrowseFormat {
+        self.format
+    }
+
+    /// header returns the column header line, if the format has one.
+    pub fn header(&self) -> Option<String> {
+        match self.format {
+            BrowseFormat::DnsSd => Some(format!(
+                "{:<13} {:<6} {:>5} {:>3} {:<20} {:<20} {}",
+                "Timestamp", "A/R", "Flags", "if", "Domain", "Service Type", "Instance Name"
+            )),
+            BrowseFormat::Avahi | BrowseFormat::AvahiParsable => None,
+        }
+    }
+
+    /// format_event returns the line of the specified event stamped with the current time,
+    /// or None if the event has no line in the format such as a conflict.
+    pub fn format_event(&self, event: &ServiceEvent) -> Option<String> {
+        self.format_event_at(event, SystemTime::now())
+    }
+
+    /// format_event_at returns the line of the specified event stamped with the specified time.
+    pub fn format_event_at(&self, event: &ServiceEvent, time: SystemTime) -> Option<String> {
+        let service = event.service()?;
+        let line = match self.format {
+            BrowseFormat::DnsSd => {
+                let (ar, flags) = match event {
+                    ServiceEvent::Removed(_) => ("Rmv", 0),
+                    _ => ("Add", DNS_SD_FLAGS_ADD),
+                };
+                format!(
+                    "{:<13} {:<6} {:>5X} {:>3} {:<20} {:<20} {}",
+                    timestamp(time),
+                    ar,
+                    flags,
+                    0,
+                    format!("{}.", service.domain()),
+                    format!("{}.", service.service_type()),
+                    service.instance_name()
+                )
+            }
+            BrowseFormat::Avahi => format!(
+                "{} {:>6} {:<4} {:<40} {:<20} {}",
+                event_mark(event),
+                UNKNOWN_INTERFACE,
+                protocol(service),
+                service.instance_name(),
+                service.service_type(),
+                service.domain()
+            ),
+            BrowseFormat::AvahiParsable => [
+                event_mark(event).to_string(),
+                UNKNOWN_INTERFACE.to_string(),
+                protocol(service).to_string(),
+                avahi_escape(service.instance_name()),
+                service.service_type().to_string(),
+                service.domain().to_string(),
+            ]
+            .join(";"),
+        };
+        Some(line)
+    }
+}
+
+fn event_mark(event: &ServiceEvent) -> &'static str {
+    match event {
+        ServiceEvent::Added(_) => "+",
+        ServiceEvent::Updated(_) => "=",
+        ServiceEvent::Removed(_) => "-",
+        ServiceEvent::ConflictDetected(_) => "!",
+    }
+}
+
+fn protocol(service: &Service) -> &'static str {
+    match service.source() {
+        Some(addr) if addr.is_ipv4() => "IPv4",
+        Some(_) => "IPv6",
+        None => "*",
+    }
+}
+
+// timestamp returns the time of day in UTC as `HH:MM:SS.mmm`.
+fn timestamp(time: SystemTime) -> String {
+    let since_epoch = time.duration_since(UNIX_EPOCH).unwrap_or_default();
+    let secs = since_epoch.as_secs() % 86400;
+    format!(
+        "{:2}:{:02}:{:02}.{:03}",
+        secs / 3600,
+        (secs / 60) % 60,
+        secs % 60,
+        since_epoch.subsec_millis()
+    )
+}
+
+// avahi_escape escapes the characters which avahi-browse escapes in parsable instance names as `\DDD`.
+fn avahi_escape(s: &str) -> String {
+    let mut escaped = String::new();
+    for c in s.chars() {
+        match c {
+            ' ' | ';' | '\\' | '.' => escaped.push_str(&format!("\\{:03}", c as u32)),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
@@ -0,0 +1,67 @@
+// Copyright (C) 2024 Satoshi Konno All rights reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//    http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+#[cfg(test)]
+mod tests {
+
+    use std::time::{Duration, UNIX_EPOCH};
+
+    use crate::dns::Message;
+    use crate::fixture_test::srv_record;
+    use crate::service::split_service_name;
+    use crate::{BrowseFormat, BrowseFormatter, Service, ServiceEvent};
+
+    fn test_service() -> Service {
+        let mut msg = Message::new();
+        msg.add_answer(srv_record("My Server._http._tcp.local", 80, "host.local"));
+        let mut service = Service::from_message(&msg);
+        service.set_source("192.168.0.2:5353".parse().unwrap());
+        service
+    }
+
+    #[test]
+    fn service_name_split() {
+        assert_eq!(
+            split_service_name("My Server._http._tcp.local"),
+            ("My Server", "_http._tcp", "local")
+        );
+        assert_eq!(
+            split_service_name("_services._dns-sd._udp.local"),
+            ("_services", "_dns-sd._udp", "local")
+        );
+        assert_eq!(split_service_name("host.local"), ("host.local", "", ""));
+    }
+
+    #[test]
+    fn browse_format() {
+        let event = ServiceEvent::Added(test_service());
+        let time = UNIX_EPOCH + Duration::from_millis(3_723_004);
+
+        let formatter = BrowseFormatter::new(BrowseFormat::DnsSd);
+        assert!(formatter.header().unwrap().starts_with("Timestamp"));
+        assert_eq!(
+            formatter.format_event_at(&event, time).unwrap(),
+            " 1:02:03.004  Add        2   0 local.               _http._tcp.          My Server"
+        );
+
+        let formatter = BrowseFormatter::new(BrowseFormat::AvahiParsable);
+        assert!(formatter.header().is_none());
+        assert_eq!(
+            formatter
+                .format_event(&ServiceEvent::Removed(test_service()))
+                .unwrap(),
+            "-;*;IPv4;My\\032Server;_http._tcp;local"
+        );
+    }
+}
@@ -0,0 +1,159 @@
+// Copyright (C) 2024 Satoshi Konno All rights reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//    http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::mpsc;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use crate::discoverer::Discoverer;
+use crate::event::ServiceEvent;
+use crate::query::Query;
+use crate::query_pacing::QueryPacing;
+use crate::service::Service;
+
+/// BrowseSession represents a set of related queries which are browsed as one unit,
+/// such as the service types of a device picker, with a shared view of the results and a combined event stream.
+pub struct BrowseSession {
+    discoverer: Arc<Mutex<Discoverer>>,
+    queries: Vec<Query>,
+    pacing: QueryPacing,
+    events: Option<mpsc::Receiver<ServiceEvent>>,
+}
+
+impl BrowseSession {
+    /// new creates a new stopped session of the specified queries on the specified discoverer.
+    pub fn new(discoverer: Arc<Mutex<Discoverer>>, queries: &[Query]) -> BrowseSession {
+        BrowseSession {
+            discoverer,
+            queries: queries.to_vec(),
+            pacing: QueryPacing::Standard,
+            events: None,
+        }
+    }
+
+    /// add_query adds the specified query to the session, which is searched immediately if the session is started.
+    pub fn add_query(&mut self, query: &Query) -> Result<(), std::io::Error> {
+        if self.queries.contains(query) {
+            return Ok(());
+        }
+        self.queries.push(query.clone());
+        let mut discoverer = self.discoverer.lock().unwrap();
+        discoverer.set_query_pacing(query, self.pacing);
+        if self.is_started() {
+            discoverer.search(query)?;
+        }
+        Ok(())
+    }
+
+    /// remove_query removes the specified query from the session, so its services are no longer part of the results.
+    pub fn remove_query(&mut self, query: &Query) {
+        self.queries.retain(|q| q != query);
+        self.discoverer
+            .lock()
+            .unwrap()
+            .set_query_pacing(query, QueryPacing::Standard);
+    }
+
+    /// set_pacing sets the pacing of the queries of the session, such as the low-power pacing for battery-powered devices.
+    pub fn set_pacing(&mut self, pacing: QueryPacing) {
+        self.pacing = pacing;
+        let mut discoverer = self.discoverer.lock().unwrap();
+        for query in &self.queries {
+            discoverer.set_query_pacing(query, pacing);
+        }
+    }
+
+    /// pacing returns the pacing of the queries of the session.
+    pub fn pacing(&self) -> QueryPacing {
+        self.pacing
+    }
+
+    /// queries returns the queries of the session.
+    pub fn queries(&self) -> &[Query] {
+        &self.queries
+    }
+
+    /// start subscribes to the service events and searches all queries of the session.
+    /// Every query is searched even if another one fails, and the first error is returned.
+    pub fn start(&mut self) -> Result<(), std::io::Error> {
+        let mut discoverer = self.discoverer.lock().unwrap();
+        if self.events.is_none() {
+            self.events = Some(discoverer.subscribe());
+        }
+        discoverer.search_all(&self.queries)
+    }
+
+    /// stop stops delivering the events of the session, and the pending events are dropped.
+    pub fn stop(&mut self) {
+        self.events = None;
+    }
+
+    /// is_started returns true if the session has been started and not stopped.
+    pub fn is_started(&self) -> bool {
+        self.events.is_some()
+    }
+
+    /// matches returns true if the specified service is a result of any query of the session.
+    pub fn matches(&self, service: &Service) -> bool {
+        let name = service.name().trim_end_matches('.').to_ascii_lowercase();
+        self.queries.iter().any(|query| {
+            let type_name = query.to_string();
+            name.ends_with(&format!(
+                ".{}",
+                type_name.trim_end_matches('.').to_ascii_lowercase()
+            ))
+        })
+    }
+
+    /// services returns the known services of all queries of the session.
+    pub fn services(&self) -> Vec<Service> {
+        self.discoverer
+            .lock()
+            .unwrap()
+            .merged_services()
+            .into_iter()
+            .filter(|service| self.matches(service))
+            .collect()
+    }
+
+    /// try_recv returns the next pending event of the services of the session, or None if there is no event or the session is stopped.
+    pub fn try_recv(&self) -> Option<ServiceEvent> {
+        let events = self.events.as_ref()?;
+        while let Ok(event) = events.try_recv() {
+            if self.is_session_event(&event) {
+                return Some(event);
+            }
+        }
+        None
+    }
+
+    /// recv_timeout waits for the next event of the services of the session until the specified timeout,
+    /// and returns None if the timeout elapses or the session is stopped.
+    pub fn recv_timeout(&self, timeout: Duration) -> Option<ServiceEvent> {
+        let events = self.events.as_ref()?;
+        let deadline = Instant::now() + timeout;
+        loop {
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            let event = events.recv_timeout(remaining).ok()?;
+            if self.is_session_event(&event) {
+                return Some(event);
+            }
+        }
+    }
+
+    // is_session_event returns true if the specified event is about a service of the session.
+    fn is_session_event(&self, event: &ServiceEvent) -> bool {
+        event.service().is_some_and(|service| self.matches(service))
+    }
+}
@@ -0,0 +1,187 @@
+// Copyright (C) 2024 Satoshi Konno All rights reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//    http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+#[cfg(test)]
+mod tests {
+
+    use std::sync::{Arc, Mutex};
+
+    use cybergarage::net::{Observer, Packet};
+
+    use crate::dns::{Message, PTRRecord};
+    use crate::fixture_test::srv_response_packet;
+    use crate::{BrowseSession, Discoverer, Query, QueryPacing, ServiceEvent};
+
+    #[test]
+    fn browse_session() {
+        let discoverer = Discoverer::new();
+        let sent = Arc::new(Mutex::new(Vec::new()));
+        let hook_sent = sent.clone();
+        discoverer
+            .lock()
+            .unwrap()
+            .set_outgoing_hook(Box::new(move |pkt| {
+                let msg = pkt.message().unwrap();
+                hook_sent
+                    .lock()
+                    .unwrap()
+                    .push(msg.questions()[0].name().to_string());
+                false
+            }));
+
+        let queries = [
+            Query::with("_http._tcp", "local"),
+            Query::with("_ipp._tcp", "local"),
+        ];
+        let mut session = BrowseSession::new(discoverer.clone(), &queries);
+        assert!(!session.is_started());
+        assert!(session.try_recv().is_none());
+
+        // The vetoed queries fail, but every query of the session is tried.
+        assert!(session.start().is_err());
+        assert!(session.is_started());
+        assert_eq!(
+            *sent.lock().unwrap(),
+            vec!["_http._tcp.local", "_ipp._tcp.local"]
+        );
+
+        for name in [
+            "web._http._tcp.local",
+            "printer._IPP._tcp.local",
+            "speaker._raop._tcp.local",
+        ] {
+            discoverer
+                .lock()
+                .unwrap()
+                .packet_received(&srv_response_packet(name, 80));
+        }
+        let mut names: Vec<String> = session
+            .services()
+            .iter()
+            .map(|s| s.name().to_string())
+            .collect();
+        names.sort();
+        assert_eq!(
+            names,
+            vec!["printer._IPP._tcp.local", "web._http._tcp.local"]
+        );
+
+        let mut added = Vec::new();
+        while let Some(event) = session.try_recv() {
+            match event {
+                ServiceEvent::Added(service) => added.push(service.name().to_string()),
+                _ => panic!("expected an added event"),
+            }
+        }
+        assert_eq!(
+            added,
+            vec!["web._http._tcp.local", "printer._IPP._tcp.local"]
+        );
+
+        // A query added to a started session is searched at once.
+        assert!(session
+            .add_query(&Query::with("_raop._tcp", "local"))
+            .is_err());
+        assert_eq!(sent.lock().unwrap().len(), 3);
+        assert_eq!(session.services().len(), 3);
+        session.remove_query(&Query::with("_http._tcp", "local"));
+        assert_eq!(session.queries().len(), 2);
+        assert_eq!(session.services().len(), 2);
+
+        session.stop();
+        assert!(!session.is_started());
+        discoverer
+            .lock()
+            .unwrap()
+            .packet_received(&srv_response_packet("other._ipp._tcp.local", 80));
+        assert!(session.try_recv().is_none());
+    }
+
+    #[test]
+    fn browse_session_low_power() {
+        let discoverer = Discoverer::new();
+        let sent = Arc::new(Mutex::new(Vec::new()));
+        let hook_sent = sent.clone();
+        discoverer
+            .lock()
+            .unwrap()
+            .set_outgoing_hook(Box::new(move |pkt| {
+                let msg = pkt.message().unwrap();
+                let names: Vec<String> = msg
+                    .questions()
+                    .iter()
+                    .map(|q| q.name().to_string())
+                    .collect();
+                hook_sent.lock().unwrap().push(names);
+                true
+            }));
+
+        let queries = [
+            Query::with("_http._tcp", "local"),
+            Query::with("_ipp._tcp", "local"),
+        ];
+        let mut session = BrowseSession::new(discoverer.clone(), &queries);
+        assert_eq!(session.pacing(), QueryPacing::Standard);
+        session.set_pacing(QueryPacing::LowPower);
+        assert_eq!(session.pacing(), QueryPacing::LowPower);
+        assert!(!QueryPacing::LowPower.refreshes_cache());
+        assert!(QueryPacing::Standard.min_interval() < QueryPacing::LowPower.min_interval());
+
+        // The questions of the session are batched into a single query.
+        assert!(session.start().is_ok());
+        assert_eq!(
+            *sent.lock().unwrap(),
+            vec![vec!["_http._tcp.local", "_ipp._tcp.local"]]
+        );
+
+        // The same queries are not sent again within the low-power interval.
+        assert!(session.start().is_ok());
+        assert!(discoverer
+            .lock()
+            .unwrap()
+            .search(&Query::with("_ipp._tcp", "local"))
+            .is_ok());
+        assert_eq!(sent.lock().unwrap().len(), 1);
+
+        // A query added to a started session follows the pacing of the session.
+        let raop = Query::with("_raop._tcp", "local");
+        assert!(session.add_query(&raop).is_ok());
+        assert_eq!(
+            discoverer.lock().unwrap().query_pacing(&raop),
+            QueryPacing::LowPower
+        );
+        assert_eq!(sent.lock().unwrap()[1], vec!["_raop._tcp.local"]);
+
+        // The fresh cached records answer the search, and are not queried again before they expire.
+        let mut ptr = PTRRecord::new("_printer._tcp.local", "office");
+        ptr.set_ttl(120);
+        let mut msg = Message::new();
+        msg.add_answer(ptr.to_record().unwrap());
+        discoverer
+            .lock()
+            .unwrap()
+            .packet_received(&Packet::from_bytes(&msg.to_bytes().unwrap()));
+        let printer = Query::with("_printer._tcp", "local");
+        assert!(session.add_query(&printer).is_ok());
+        assert_eq!(sent.lock().unwrap().len(), 2);
+        assert!(discoverer.lock().unwrap().deferred_queries().is_empty());
+
+        // A removed query is paced as standard again.
+        session.remove_query(&raop);
+        assert_eq!(
+            discoverer.lock().unwrap().query_pacing(&raop),
+            QueryPacing::Standard
+        );
+    }
+}
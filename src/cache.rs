@@ -0,0 +1,787 @@
+// Copyright (C) 2024 Satoshi Konno All rights reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//    http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::HashMap;
+use std::sync::mpsc;
+use std::time::{Duration, Instant};
+
+use crate::conflict::{Conflict, Reconfirmation, ReconfirmationOutcome};
+use crate::dns::{NSECRecord, PTRRecord, Question, Record, SRVRecord, Type};
+use crate::eviction::{Eviction, EvictionReason};
+use crate::normalize::NameComparison;
+use crate::provenance::Provenance;
+use crate::service_builder::SERVICE_TYPE_ENUMERATION;
+
+/// CACHE_FLUSH_GRACE is how long a record must have been cached before a cache-flush record replaces it.
+/// RFC 6762: 10.2. Announcements to Flush Outdated Cache Entries
+pub const CACHE_FLUSH_GRACE: Duration = Duration::from_secs(1);
+
+/// GOODBYE_GRACE is how long a record withdrawn by a goodbye packet is kept before it is removed,
+/// so a device which re-announces the record right away, such as when it reboots, does not disappear.
+/// RFC 6762: 10.1. Goodbye Packets
+pub const GOODBYE_GRACE: Duration = Duration::from_secs(1);
+
+/// RECONFIRM_TIMEOUT is how long the owner of a cached record has to announce it again after a conflicting record
+/// from another host is received, before the conflicting record replaces it.
+/// RFC 6762: 10.4. Cache Flush on Failure Indication
+pub const RECONFIRM_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// RECONFIRM_QUERIES is the number of the queries sent to reconfirm a conflicted record within RECONFIRM_TIMEOUT.
+pub const RECONFIRM_QUERIES: u32 = 2;
+
+// Absence represents the types asserted by a NSEC record, and when the assertion expires.
+struct Absence {
+    types: Vec<Type>,
+    expires: Instant,
+}
+
+/// CachedRecord represents a cached resource record with where it came from.
+#[derive(Clone)]
+pub struct CachedRecord {
+    record: Record,
+    provenance: Provenance,
+    ttl: u32,
+    received: Instant,
+    expires: Instant,
+    goodbye: bool,
+}
+
+impl CachedRecord {
+    /// ttl returns the effective TTL of the record bounded by the cache, while the record keeps the announced TTL.
+    pub fn ttl(&self) -> u32 {
+        self.ttl
+    }
+
+    /// record returns the cached record.
+    pub fn record(&self) -> &Record {
+        &self.record
+    }
+
+    /// provenance returns where and when the record was received.
+    pub fn provenance(&self) -> &Provenance {
+        &self.provenance
+    }
+
+    /// cache_flush returns true if the record was received with the cache-flush bit.
+    pub fn cache_flush(&self) -> bool {
+        self.record.cache_flush()
+    }
+
+    /// expires returns the time the record expires.
+    pub fn expires(&self) -> Instant {
+        self.expires
+    }
+
+    /// is_goodbye returns true if the record was withdrawn by a goodbye packet, and is kept only for GOODBYE_GRACE.
+    pub fn is_goodbye(&self) -> bool {
+        self.goodbye
+    }
+
+    /// remaining_ttl_at returns the TTL remaining at the specified time, decremented from the time the record was received.
+    /// The remaining TTL never exceeds what remains of the announced TTL, even if the effective TTL was raised.
+    pub fn remaining_ttl_at(&self, now: Instant) -> u32 {
+        let elapsed = now.saturating_duration_since(self.received).as_secs();
+        let ttl = self.ttl.min(self.record.ttl()) as u64;
+        ttl.saturating_sub(elapsed) as u32
+    }
+
+    /// stale_at returns the time the remaining TTL falls below half of the announced TTL,
+    /// after which the record is neither a known answer nor satisfies a query.
+    pub fn stale_at(&self) -> Instant {
+        let ttl = Duration::from_secs(self.ttl.min(self.record.ttl()) as u64);
+        let half = Duration::from_secs(self.record.ttl() as u64) / 2;
+        self.received + ttl.saturating_sub(half)
+    }
+
+    // conflicts_with returns true if the specified record from the specified provenance conflicts with the cached record.
+    // A host may legitimately announce several unique records of the same name and type, such as the addresses of its interfaces,
+    // so only records from another host over the same family are regarded as a conflict.
+    // The asserted data are of the records of the same name and type received together, and a record set which also asserts
+    // the cached data is consistent with it, such as a multihomed responder answering the same set from another interface.
+    fn conflicts_with(
+        &self,
+        record: &Record,
+        provenance: &Provenance,
+        asserted: &[&[u8]],
+        now: Instant,
+    ) -> bool {
+        if !is_unique_type(record.typ()) || !record.cache_flush() || !self.record.cache_flush() {
+            return false;
+        }
+        if self.expires <= now || self.record.typ() != record.typ() {
+            return false;
+        }
+        if self.provenance.family() != provenance.family() {
+            return false;
+        }
+        if asserted.contains(&self.record.data()) {
+            return false;
+        }
+        self.provenance.source().ip() != provenance.source().ip()
+            && self.record.data() != record.data()
+    }
+
+    // is_same_rdata returns true if the specified record of the same name has the same data.
+    fn is_same_rdata(&self, record: &Record) -> bool {
+        self.record.typ() == record.typ() && self.record.data() == record.data()
+    }
+}
+
+// PendingConflict represents a conflicting record held until the conflicted record is reconfirmed or times out.
+struct PendingConflict {
+    key: String,
+    conflict: Conflict,
+    record: CachedRecord,
+    deadline: Instant,
+    next_query: Instant,
+    queries: u32,
+}
+
+impl PendingConflict {
+    // is_existing returns true if the specified record is the conflicted record announced again by its owner.
+    fn is_existing(&self, record: &Record, provenance: &Provenance) -> bool {
+        self.conflict.typ() == record.typ()
+            && self.conflict.existing().source().ip() == provenance.source().ip()
+            && self.conflict.existing_data() == record.data()
+    }
+
+    // is_conflicting returns true if the specified record is the held conflicting record announced again.
+    fn is_conflicting(&self, record: &Record, provenance: &Provenance) -> bool {
+        self.conflict.typ() == record.typ()
+            && self.conflict.conflicting().source().ip() == provenance.source().ip()
+            && self.conflict.conflicting_data() == record.data()
+    }
+}
+
+/// Cache represents the records learned from the received responses.
+pub struct Cache {
+    records: HashMap<String, Vec<CachedRecord>>,
+    absences: HashMap<String, Absence>,
+    pending: Vec<PendingConflict>,
+    min_ttl: Option<u32>,
+    max_ttl: Option<u32>,
+    capacity: Option<usize>,
+    subscribers: Vec<mpsc::Sender<Eviction>>,
+    reconfirmation_subscribers: Vec<mpsc::Sender<Reconfirmation>>,
+    name_comparison: NameComparison,
+}
+
+impl Cache {
+    /// new creates an empty cache.
+    pub fn new() -> Cache {
+        Cache {
+            records: HashMap::new(),
+            absences: HashMap::new(),
+            pending: Vec::new(),
+            min_ttl: None,
+            max_ttl: None,
+            capacity: None,
+            subscribers: Vec::new(),
+            reconfirmation_subscribers: Vec::new(),
+            name_comparison: NameComparison::default(),
+        }
+    }
+
+    /// set_name_comparison sets how the names of the records are compared, which should be set before any record is added.
+    pub fn set_name_comparison(&mut self, name_comparison: NameComparison) {
+        self.name_comparison = name_comparison;
+    }
+
+    /// name_comparison returns how the names of the records are compared.
+    pub fn name_comparison(&self) -> NameComparison {
+        self.name_comparison
+    }
+
+    /// subscribe_evictions returns a receiver of the records leaving the cache with the reasons.
+    pub fn subscribe_evictions(&mut self) -> mpsc::Receiver<Eviction> {
+        let (tx, rx) = mpsc::channel();
+        self.subscribers.push(tx);
+        rx
+    }
+
+    /// subscribe_reconfirmations returns a receiver of the conflicts of the cached records with how they were resolved.
+    pub fn subscribe_reconfirmations(&mut self) -> mpsc::Receiver<Reconfirmation> {
+        let (tx, rx) = mpsc::channel();
+        self.reconfirmation_subscribers.push(tx);
+        rx
+    }
+
+    /// pending_conflicts returns the conflicts whose cached records are being reconfirmed.
+    pub fn pending_conflicts(&self) -> Vec<&Conflict> {
+        self.pending.iter().map(|p| &p.conflict).collect()
+    }
+
+    /// set_capacity sets the maximum number of the cached records, and the records expiring first are evicted beyond it.
+    pub fn set_capacity(&mut self, capacity: Option<usize>) {
+        self.capacity = capacity;
+        self.evict_over_capacity();
+    }
+
+    /// capacity returns the maximum number of the cached records.
+    pub fn capacity(&self) -> Option<usize> {
+        self.capacity
+    }
+
+    /// len returns the number of the cached records including the expired ones not removed yet.
+    pub fn len(&self) -> usize {
+        self.records.values().map(|entries| entries.len()).sum()
+    }
+
+    /// is_empty returns true if no record is cached.
+    pub fn is_empty(&self) -> bool {
+        self.records.is_empty()
+    }
+
+    /// set_min_ttl sets the minimum effective TTL of the cached records, against devices announcing pathologically short TTLs.
+    pub fn set_min_ttl(&mut self, ttl: Option<u32>) {
+        self.min_ttl = ttl;
+    }
+
+    /// min_ttl returns the minimum effective TTL of the cached records.
+    pub fn min_ttl(&self) -> Option<u32> {
+        self.min_ttl
+    }
+
+    /// set_max_ttl sets the maximum effective TTL of the cached records, against devices announcing absurdly long TTLs.
+    pub fn set_max_ttl(&mut self, ttl: Option<u32>) {
+        self.max_ttl = ttl;
+    }
+
+    /// max_ttl returns the maximum effective TTL of the cached records.
+    pub fn max_ttl(&self) -> Option<u32> {
+        self.max_ttl
+    }
+
+    /// effective_ttl returns the specified announced TTL bounded by the minimum and maximum TTLs.
+    /// A zero TTL is a goodbye, and is never raised to the minimum.
+    /// RFC 6762: 10.1. Goodbye Packets
+    pub fn effective_ttl(&self, ttl: u32) -> u32 {
+        if ttl == 0 {
+            return 0;
+        }
+        let ttl = match self.max_ttl {
+            Some(max_ttl) => ttl.min(max_ttl),
+            None => ttl,
+        };
+        match self.min_ttl {
+            Some(min_ttl) => ttl.max(min_ttl),
+            None => ttl,
+        }
+    }
+
+    /// add_record adds the specified record received now, and returns the conflict if the record conflicts with a cached one.
+    pub fn add_record(&mut self, record: &Record, provenance: &Provenance) -> Option<Conflict> {
+        self.add_record_at(record, provenance, Instant::now())
+    }
+
+    /// add_records adds the specified records received together in a message now, and returns the conflicts.
+    pub fn add_records<'a, I>(&mut self, records: I, provenance: &Provenance) -> Vec<Conflict>
+    where
+        I: IntoIterator<Item = &'a Record>,
+    {
+        self.add_records_at(records, provenance, Instant::now())
+    }
+
+    /// add_records_at adds the specified records received together in a message at the specified time.
+    /// RFC 6762: 9. Conflict Resolution
+    /// The records are compared as record sets, so a set which also asserts the cached data of another host
+    /// is not regarded as a conflict.
+    pub fn add_records_at<'a, I>(
+        &mut self,
+        records: I,
+        provenance: &Provenance,
+        now: Instant,
+    ) -> Vec<Conflict>
+    where
+        I: IntoIterator<Item = &'a Record>,
+    {
+        let records: Vec<&Record> = records.into_iter().collect();
+        let name_comparison = self.name_comparison;
+        let mut conflicts = Vec::new();
+        for record in &records {
+            let asserted: Vec<&[u8]> = records
+                .iter()
+                .filter(|r| r.typ() == record.typ() && name_comparison.eq(r.name(), record.name()))
+                .map(|r| r.data())
+                .collect();
+            if let Some(conflict) = self.add_record_with(record, provenance, &asserted, now) {
+                conflicts.push(conflict);
+            }
+        }
+        conflicts
+    }
+
+    /// add_record_at adds the specified record received at the specified time.
+    /// RFC 6762: 6.1. Negative Responses
+    /// A NSEC record asserts that the types absent from its bitmap do not exist for the name
+    /// for the TTL of the record, and a record of such a type withdraws the assertion for that type.
+    /// A unique record with the cache-flush bit which differs from the one cached from another host is reported as a conflict,
+    /// only when it is received for the first time.
+    /// RFC 6762: 9. Conflict Resolution
+    /// The conflicting record is held instead of replacing the cached one, which is reconfirmed with its owner for RECONFIRM_TIMEOUT.
+    /// The conflicting record is discarded if the owner announces the cached record again, and replaces it otherwise.
+    /// A record with zero TTL expires the same cached record GOODBYE_GRACE later as a goodbye unless it is announced again,
+    /// and a record with the cache-flush bit
+    /// evicts the other records of the same name and type cached more than CACHE_FLUSH_GRACE ago.
+    pub fn add_record_at(
+        &mut self,
+        record: &Record,
+        provenance: &Provenance,
+        now: Instant,
+    ) -> Option<Conflict> {
+        self.add_record_with(record, provenance, &[record.data()], now)
+    }
+
+    // add_record_with adds the specified record which was received with the records of the specified asserted data.
+    fn add_record_with(
+        &mut self,
+        record: &Record,
+        provenance: &Provenance,
+        asserted: &[&[u8]],
+        now: Instant,
+    ) -> Option<Conflict> {
+        let key = self.name_comparison.key(record.name());
+        if record.typ() == Type::NSEC {
+            self.add_absence_at(&key, record, now);
+        } else if let Some(absence) = self.absences.get_mut(&key) {
+            if !absence.types.contains(&record.typ()) {
+                absence.types.push(record.typ());
+            }
+        }
+        if record.ttl() == 0 {
+            self.add_goodbye_at(&key, record, now);
+            return None;
+        }
+        self.resolve_confirmed(&key, record, provenance);
+        let ttl = self.effective_ttl(record.ttl());
+        let entry = CachedRecord {
+            record: record.clone(),
+            provenance: provenance.clone(),
+            ttl,
+            received: now,
+            expires: now + Duration::from_secs(ttl as u64),
+            goodbye: false,
+        };
+        if let Some(pending) = self
+            .pending
+            .iter_mut()
+            .find(|p| p.key == key && p.is_conflicting(record, provenance))
+        {
+            pending.record = entry;
+            return None;
+        }
+        let entries = self.records.entry(key.clone()).or_default();
+        let conflict = match entries.iter_mut().find(|e| e.is_same_rdata(record)) {
+            Some(known) => {
+                *known = entry;
+                None
+            }
+            None => {
+                let conflict = entries
+                    .iter()
+                    .find(|e| e.conflicts_with(record, provenance, asserted, now))
+                    .map(|e| {
+                        Conflict::new(
+                            record.name(),
+                            record.typ(),
+                            (&e.provenance, e.record.data()),
+                            (provenance, record.data()),
+                        )
+                    });
+                match &conflict {
+                    Some(conflict) => self.pending.push(PendingConflict {
+                        key: key.clone(),
+                        conflict: conflict.clone(),
+                        record: entry,
+                        deadline: now + RECONFIRM_TIMEOUT,
+                        next_query: now,
+                        queries: 0,
+                    }),
+                    None => entries.push(entry),
+                }
+                conflict
+            }
+        };
+        if conflict.is_some() {
+            return conflict;
+        }
+        if record.cache_flush() {
+            self.remove_where(&key, EvictionReason::CacheFlush, |e| {
+                e.record.typ() == record.typ() && e.received + CACHE_FLUSH_GRACE <= now
+            });
+        }
+        self.evict_over_capacity();
+        conflict
+    }
+
+    // resolve_confirmed discards the conflicting records held for the specified record which its owner announced again.
+    fn resolve_confirmed(&mut self, key: &str, record: &Record, provenance: &Provenance) {
+        let mut confirmed = Vec::new();
+        self.pending.retain(|p| {
+            if p.key == key && p.is_existing(record, provenance) {
+                confirmed.push(p.conflict.clone());
+                return false;
+            }
+            true
+        });
+        for conflict in confirmed {
+            self.publish_reconfirmation(conflict, ReconfirmationOutcome::Confirmed);
+        }
+    }
+
+    /// due_reconfirmations returns the conflicts whose cached records should be queried now to reconfirm them.
+    pub fn due_reconfirmations(&mut self) -> Vec<Conflict> {
+        self.due_reconfirmations_at(Instant::now())
+    }
+
+    /// due_reconfirmations_at returns the conflicts whose cached records should be queried at the specified time,
+    /// where each record is queried RECONFIRM_QUERIES times evenly within RECONFIRM_TIMEOUT.
+    pub fn due_reconfirmations_at(&mut self, now: Instant) -> Vec<Conflict> {
+        let interval = RECONFIRM_TIMEOUT / RECONFIRM_QUERIES;
+        let mut due = Vec::new();
+        for pending in self.pending.iter_mut() {
+            if RECONFIRM_QUERIES <= pending.queries || now < pending.next_query {
+                continue;
+            }
+            pending.queries += 1;
+            pending.next_query = now + interval;
+            due.push(pending.conflict.clone());
+        }
+        due
+    }
+
+    // replace_unconfirmed replaces the cached records which were not reconfirmed by the deadlines with the conflicting records.
+    fn replace_unconfirmed(&mut self, now: Instant) {
+        let (replaced, pending): (Vec<PendingConflict>, Vec<PendingConflict>) =
+            self.pending.drain(..).partition(|p| p.deadline <= now);
+        self.pending = pending;
+        for pending in replaced {
+            let existing = pending.conflict.existing().source().ip();
+            let typ = pending.conflict.typ();
+            self.remove_where(&pending.key, EvictionReason::Conflict, |e| {
+                e.record.typ() == typ && e.provenance.source().ip() == existing
+            });
+            self.records
+                .entry(pending.key.clone())
+                .or_default()
+                .push(pending.record);
+            self.publish_reconfirmation(pending.conflict, ReconfirmationOutcome::Replaced);
+        }
+    }
+
+    fn publish_reconfirmation(&mut self, conflict: Conflict, outcome: ReconfirmationOutcome) {
+        let reconfirmation = Reconfirmation::new(conflict, outcome);
+        self.reconfirmation_subscribers
+            .retain(|subscriber| subscriber.send(reconfirmation.clone()).is_ok());
+    }
+
+    // add_goodbye_at sets the TTL of the same cached record to one second as RFC 6762: 10.1. Goodbye Packets describes.
+    fn add_goodbye_at(&mut self, key: &str, record: &Record, now: Instant) {
+        let entries = match self.records.get_mut(key) {
+            Some(entries) => entries,
+            None => return,
+        };
+        let expires = now + GOODBYE_GRACE;
+        for entry in entries.iter_mut().filter(|e| e.is_same_rdata(record)) {
+            entry.ttl = GOODBYE_GRACE.as_secs() as u32;
+            entry.expires = entry.expires.min(expires);
+            entry.goodbye = true;
+        }
+    }
+
+    fn add_absence_at(&mut self, key: &str, record: &Record, now: Instant) {
+        let nsec = match NSECRecord::from_record(record) {
+            Ok(nsec) => nsec,
+            Err(e) => {
+                log::debug!("invalid NSEC record ({}): {}", record.name(), e.message());
+                return;
+            }
+        };
+        if nsec.ttl() == 0 {
+            self.absences.remove(key);
+            return;
+        }
+        let absence = Absence {
+            types: nsec.types().clone(),
+            expires: now + Duration::from_secs(self.effective_ttl(nsec.ttl()) as u64),
+        };
+        self.absences.insert(key.to_string(), absence);
+    }
+
+    /// records returns the unexpired records of the specified name.
+    pub fn records(&self, name: &str) -> Vec<&CachedRecord> {
+        self.records_at(name, Instant::now())
+    }
+
+    /// records_at returns the records of the specified name which are unexpired at the specified time.
+    pub fn records_at(&self, name: &str, now: Instant) -> Vec<&CachedRecord> {
+        match self.records.get(&self.name_comparison.key(name)) {
+            Some(entries) => entries.iter().filter(|e| now < e.expires).collect(),
+            None => Vec::new(),
+        }
+    }
+
+    /// fresh_records_at returns the records of the specified name and type whose remaining TTLs at the specified time
+    /// are at least half of the announced TTLs.
+    pub fn fresh_records_at(&self, name: &str, typ: Type, now: Instant) -> Vec<&CachedRecord> {
+        self.records_at(name, now)
+            .into_iter()
+            .filter(|e| e.record.typ() == typ && now < e.stale_at())
+            .collect()
+    }
+
+    /// known_answers returns the known answers to attach to a query of the specified questions sent now.
+    pub fn known_answers(&self, questions: &[Question]) -> Vec<Record> {
+        self.known_answers_at(questions, Instant::now())
+    }
+
+    /// known_answers_at returns the known answers to attach to a query of the specified questions sent at the specified time.
+    /// RFC 6762: 7.1. Known-Answer Suppression
+    /// The known answers carry the remaining TTLs rather than the announced ones,
+    /// and the records whose remaining TTLs are less than half of the announced TTLs are not included.
+    pub fn known_answers_at(&self, questions: &[Question], now: Instant) -> Vec<Record> {
+        let mut answers: Vec<Record> = Vec::new();
+        for question in questions {
+            for entry in self.records_at(question.name(), now) {
+                if !question.matches(&entry.record) {
+                    continue;
+                }
+                let ttl = entry.remaining_ttl_at(now);
+                if ttl == 0 || (ttl as u64) * 2 < entry.record.ttl() as u64 {
+                    continue;
+                }
+                let mut record = entry.record.clone();
+                record.set_ttl(ttl);
+                record.set_cache_flush(false);
+                if !answers.iter().any(|a| {
+                    a.typ() == record.typ()
+                        && self.name_comparison.eq(a.name(), record.name())
+                        && a.data() == record.data()
+                }) {
+                    answers.push(record);
+                }
+            }
+        }
+        answers
+    }
+
+    /// services_on_host returns the instance names of the unexpired SRV records targeting the specified host.
+    pub fn services_on_host(&self, host: &str) -> Vec<String> {
+        self.services_on_host_at(host, Instant::now())
+    }
+
+    /// services_on_host_at returns the instance names of the SRV records targeting the specified host which are unexpired at the specified time.
+    pub fn services_on_host_at(&self, host: &str, now: Instant) -> Vec<String> {
+        let host = host.trim_end_matches('.');
+        let mut names: Vec<String> = Vec::new();
+        for entry in self.records.values().flatten() {
+            if entry.record.typ() != Type::SRV || entry.expires <= now {
+                continue;
+            }
+            let srv = match SRVRecord::from_record(&entry.record) {
+                Ok(srv) => srv,
+                Err(_) => continue,
+            };
+            if !srv
+                .target()
+                .trim_end_matches('.')
+                .eq_ignore_ascii_case(host)
+            {
+                continue;
+            }
+            if !names.iter().any(|n| self.name_comparison.eq(n, srv.name())) {
+                names.push(srv.name().to_string());
+            }
+        }
+        names.sort();
+        names
+    }
+
+    /// service_types returns the service types such as `_http._tcp.local` of the unexpired service type enumeration PTR records
+    /// of the specified domain.
+    pub fn service_types(&self, domain: &str) -> Vec<String> {
+        self.service_types_at(domain, Instant::now())
+    }
+
+    /// service_types_at returns the service types of the service type enumeration PTR records of the specified domain
+    /// which are unexpired at the specified time.
+    /// RFC 6763: 9. Service Type Enumeration
+    /// The PTR records of the enumeration point to the service types, not to the service instances.
+    pub fn service_types_at(&self, domain: &str, now: Instant) -> Vec<String> {
+        let name = format!(
+            "{}.{}",
+            SERVICE_TYPE_ENUMERATION,
+            domain.trim_end_matches('.')
+        );
+        let mut types: Vec<String> = Vec::new();
+        for entry in self.records_at(&name, now) {
+            if entry.record.typ() != Type::PTR || entry.is_goodbye() {
+                continue;
+            }
+            let ptr = match PTRRecord::from_record(&entry.record) {
+                Ok(ptr) => ptr,
+                Err(_) => continue,
+            };
+            let typ = ptr.domain_name().trim_end_matches('.');
+            if !types.iter().any(|t| self.name_comparison.eq(t, typ)) {
+                types.push(typ.to_string());
+            }
+        }
+        types.sort();
+        types
+    }
+
+    /// is_known_absent returns true if a NSEC record asserts that the specified type does not exist for the name.
+    pub fn is_known_absent(&self, name: &str, typ: Type) -> bool {
+        self.is_known_absent_at(name, typ, Instant::now())
+    }
+
+    /// is_known_absent_at returns true if the negative assertion for the specified type is still valid at the specified time.
+    pub fn is_known_absent_at(&self, name: &str, typ: Type, now: Instant) -> bool {
+        if typ == Type::ANY {
+            return false;
+        }
+        match self.absences.get(&self.name_comparison.key(name)) {
+            Some(absence) => now < absence.expires && !absence.types.contains(&typ),
+            None => false,
+        }
+    }
+
+    /// remove_expired_at removes the records and assertions which have expired at the specified time,
+    /// and the records withdrawn by goodbye packets are evicted as goodbyes.
+    /// The conflicted records which were not reconfirmed in time are replaced with the conflicting records.
+    pub fn remove_expired_at(&mut self, now: Instant) {
+        self.replace_unconfirmed(now);
+        self.absences.retain(|_, absence| now < absence.expires);
+        let keys: Vec<String> = self.records.keys().cloned().collect();
+        for key in keys {
+            self.remove_where(&key, EvictionReason::Goodbye, |e| {
+                e.goodbye && e.expires <= now
+            });
+            self.remove_where(&key, EvictionReason::Expired, |e| e.expires <= now);
+        }
+    }
+
+    /// poof removes the records of the specified name and type which were expected in the responses to the observed queries
+    /// but not seen in any of them, even though their TTLs have not elapsed.
+    /// RFC 6762: 10.5. Passive Observation Of Failures (POOF)
+    pub fn poof(&mut self, name: &str, typ: Type) {
+        let key = self.name_comparison.key(name);
+        self.remove_where(&key, EvictionReason::Poof, |e| e.record.typ() == typ);
+    }
+
+    /// flush removes all records and assertions.
+    pub fn flush(&mut self) {
+        self.absences.clear();
+        self.pending.clear();
+        let keys: Vec<String> = self.records.keys().cloned().collect();
+        for key in keys {
+            self.remove_where(&key, EvictionReason::Flush, |_| true);
+        }
+    }
+
+    /// flush_name removes the records of the specified name, such as the records of a service instance known to be stale.
+    pub fn flush_name(&mut self, name: &str) {
+        let key = self.name_comparison.key(name);
+        self.absences.remove(&key);
+        self.pending.retain(|p| p.key != key);
+        self.remove_where(&key, EvictionReason::Flush, |_| true);
+    }
+
+    /// flush_domain removes the records of the specified name and of all names under it,
+    /// such as the PTR records of a service type and the records of its instances and subtypes.
+    pub fn flush_domain(&mut self, domain: &str) {
+        let domain = self.name_comparison.key(domain);
+        let suffix = format!(".{}", domain);
+        let keys: Vec<String> = self
+            .records
+            .keys()
+            .chain(self.absences.keys())
+            .filter(|key| **key == domain || key.ends_with(&suffix))
+            .cloned()
+            .collect();
+        for key in keys {
+            self.absences.remove(&key);
+            self.pending.retain(|p| p.key != key);
+            self.remove_where(&key, EvictionReason::Flush, |_| true);
+        }
+    }
+
+    // evict_over_capacity evicts the records expiring first until the cache is within the capacity.
+    fn evict_over_capacity(&mut self) {
+        let capacity = match self.capacity {
+            Some(capacity) => capacity,
+            None => return,
+        };
+        while capacity < self.len() {
+            let first = self
+                .records
+                .iter()
+                .flat_map(|(key, entries)| entries.iter().map(move |e| (key, e)))
+                .min_by_key(|(_, e)| e.expires)
+                .map(|(key, e)| (key.clone(), e.expires));
+            let (key, expires) = match first {
+                Some(first) => first,
+                None => return,
+            };
+            let mut evicted = false;
+            self.remove_where(&key, EvictionReason::Limit, |e| {
+                if evicted || e.expires != expires {
+                    return false;
+                }
+                evicted = true;
+                true
+            });
+        }
+    }
+
+    // remove_where removes the records of the specified key matching the predicate, and delivers them to the subscribers.
+    fn remove_where<F>(&mut self, key: &str, reason: EvictionReason, mut pred: F)
+    where
+        F: FnMut(&CachedRecord) -> bool,
+    {
+        let entries = match self.records.get_mut(key) {
+            Some(entries) => entries,
+            None => return,
+        };
+        let mut evicted = Vec::new();
+        entries.retain(|e| {
+            if pred(e) {
+                evicted.push(e.clone());
+                return false;
+            }
+            true
+        });
+        if entries.is_empty() {
+            self.records.remove(key);
+        }
+        for record in evicted {
+            let eviction = Eviction::new(record, reason);
+            self.subscribers
+                .retain(|subscriber| subscriber.send(eviction.clone()).is_ok());
+        }
+    }
+}
+
+// is_unique_type returns true if the records of the specified type are unique records, which only one host may own.
+// RFC 6762: 2. Conventions and Terminology Used in This Document
+fn is_unique_type(typ: Type) -> bool {
+    matches!(typ, Type::A | Type::AAAA | Type::SRV | Type::TXT)
+}
+
+impl Default for Cache {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,492 @@
+// Copyright (C) 2024 Satoshi Konno All rights reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//    http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+#[cfg(test)]
+mod tests {
+
+    use std::time::{Duration, Instant, SystemTime};
+
+    use crate::cache::{GOODBYE_GRACE, RECONFIRM_TIMEOUT};
+    use crate::dns::{NSECRecord, Question, Record, Type};
+    use crate::fixture_test::{a_record, name_data, record, unique, with_ttl};
+    use crate::{AddressFamily, Cache, EvictionReason, Provenance, ReconfirmationOutcome};
+
+    fn nsec_record(name: &str, ttl: u32, bitmap: &[u8]) -> Record {
+        let mut data = name_data(name);
+        data.extend_from_slice(bitmap);
+        with_ttl(record(name, Type::NSEC, data), ttl)
+    }
+
+    #[test]
+    fn nsec_types() {
+        // Window 0: A (1), TXT (16), AAAA (28), SRV (33)
+        let bitmap = [0x00, 0x05, 0x40, 0x00, 0x80, 0x08, 0x40];
+        let record = nsec_record("test._http._tcp.local", 120, &bitmap);
+        let nsec = NSECRecord::from_record(&record).unwrap();
+        assert_eq!(nsec.ttl(), 120);
+        assert_eq!(
+            nsec.types(),
+            &vec![Type::A, Type::TXT, Type::AAAA, Type::SRV]
+        );
+        assert!(!nsec.has_type(Type::PTR));
+
+        let record = nsec_record("test._http._tcp.local", 120, &[0x00, 0x00]);
+        assert!(NSECRecord::from_record(&record).is_err());
+    }
+
+    #[test]
+    fn cache_known_absent() {
+        struct Test {
+            typ: Type,
+            elapsed: u64,
+            expected: bool,
+        }
+
+        let now = Instant::now();
+        let provenance = Provenance::new("192.168.1.10:5353".parse().unwrap());
+        let mut cache = Cache::new();
+        // Window 0: TXT (16), SRV (33)
+        let bitmap = [0x00, 0x05, 0x00, 0x00, 0x80, 0x00, 0x40];
+        cache.add_record_at(&nsec_record("Test.local", 120, &bitmap), &provenance, now);
+
+        let tests = vec![
+            Test {
+                typ: Type::A,
+                elapsed: 0,
+                expected: true,
+            },
+            Test {
+                typ: Type::AAAA,
+                elapsed: 119,
+                expected: true,
+            },
+            Test {
+                typ: Type::AAAA,
+                elapsed: 120,
+                expected: false,
+            },
+            Test {
+                typ: Type::SRV,
+                elapsed: 0,
+                expected: false,
+            },
+            Test {
+                typ: Type::ANY,
+                elapsed: 0,
+                expected: false,
+            },
+        ];
+
+        for test in tests {
+            let at = now + Duration::from_secs(test.elapsed);
+            assert_eq!(
+                cache.is_known_absent_at("test.local", test.typ, at),
+                test.expected,
+                "{} after {}s",
+                test.typ,
+                test.elapsed
+            );
+        }
+        assert!(!cache.is_known_absent_at("other.local", Type::A, now));
+
+        let a = a_record("test.local", [192, 168, 1, 10]);
+        cache.add_record_at(&a, &provenance, now);
+        assert!(!cache.is_known_absent_at("test.local", Type::A, now));
+        assert!(cache.is_known_absent_at("test.local", Type::AAAA, now));
+
+        cache.add_record_at(&nsec_record("test.local", 0, &bitmap), &provenance, now);
+        assert!(!cache.is_known_absent_at("test.local", Type::AAAA, now));
+    }
+
+    #[test]
+    fn cache_provenance() {
+        let now = Instant::now();
+        let mut cache = Cache::new();
+        let received = SystemTime::now();
+        let mut from_v4 =
+            Provenance::with_timestamp("192.168.1.10:5353".parse().unwrap(), received);
+        from_v4.set_interface("eth0");
+        let from_v6 = Provenance::with_timestamp("[fe80::20]:5353".parse().unwrap(), received);
+
+        let mut a = unique(a_record("host.local", [192, 168, 1, 10]));
+        cache.add_record_at(&a, &from_v4, now);
+
+        a.set_data(vec![192, 168, 1, 20]);
+        a.set_cache_flush(false);
+        cache.add_record_at(&a, &from_v6, now);
+
+        let records = cache.records_at("Host.local", now);
+        assert_eq!(records.len(), 2);
+        assert!(records[0].cache_flush());
+        assert_eq!(records[0].provenance(), &from_v4);
+        assert_eq!(records[0].provenance().interface(), Some("eth0"));
+        assert!(!records[1].cache_flush());
+        assert_eq!(records[1].provenance().family(), AddressFamily::IPv6);
+        assert_eq!(records[1].provenance().timestamp(), received);
+
+        a.set_ttl(10);
+        cache.add_record_at(&a, &from_v4, now);
+        let records = cache.records_at("host.local", now);
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[1].provenance(), &from_v4);
+        assert_eq!(
+            cache
+                .records_at("host.local", now + Duration::from_secs(10))
+                .len(),
+            1
+        );
+
+        cache.remove_expired_at(now + Duration::from_secs(120));
+        assert!(cache.records_at("host.local", now).is_empty());
+    }
+
+    #[test]
+    fn cache_conflict() {
+        let now = Instant::now();
+        let mut cache = Cache::new();
+        let host1 = Provenance::new("192.168.1.10:5353".parse().unwrap());
+        let host2 = Provenance::new("192.168.1.20:5353".parse().unwrap());
+
+        let mut a = unique(a_record("host.local", [192, 168, 1, 10]));
+        assert!(cache.add_record_at(&a, &host1, now).is_none());
+
+        // The same host announces the address of another interface.
+        a.set_data(vec![10, 0, 0, 10]);
+        assert!(cache.add_record_at(&a, &host1, now).is_none());
+
+        a.set_data(vec![192, 168, 1, 20]);
+        let conflict = cache.add_record_at(&a, &host2, now).unwrap();
+        assert_eq!(conflict.name(), "host.local");
+        assert_eq!(conflict.typ(), Type::A);
+        assert_eq!(conflict.existing(), &host1);
+        assert_eq!(conflict.conflicting(), &host2);
+        assert_eq!(conflict.conflicting_data(), &[192, 168, 1, 20]);
+        assert!(cache.add_record_at(&a, &host2, now).is_none());
+
+        // Shared records such as PTR never conflict.
+        let mut ptr = record("_http._tcp.local", Type::PTR, vec![1]);
+        assert!(cache.add_record_at(&ptr, &host1, now).is_none());
+        ptr.set_data(vec![2]);
+        assert!(cache.add_record_at(&ptr, &host2, now).is_none());
+    }
+
+    #[test]
+    fn cache_ttl_bounds() {
+        struct Test {
+            ttl: u32,
+            expected: u32,
+        }
+
+        let mut cache = Cache::new();
+        cache.set_min_ttl(Some(10));
+        cache.set_max_ttl(Some(4500));
+        let tests = vec![
+            Test {
+                ttl: 0,
+                expected: 0,
+            },
+            Test {
+                ttl: 1,
+                expected: 10,
+            },
+            Test {
+                ttl: 120,
+                expected: 120,
+            },
+            Test {
+                ttl: 86400,
+                expected: 4500,
+            },
+        ];
+        for test in tests {
+            assert_eq!(cache.effective_ttl(test.ttl), test.expected);
+        }
+
+        let now = Instant::now();
+        let provenance = Provenance::new("192.168.1.10:5353".parse().unwrap());
+        let a = with_ttl(a_record("host.local", [192, 168, 1, 10]), 1);
+        cache.add_record_at(&a, &provenance, now);
+
+        let records = cache.records_at("host.local", now + Duration::from_secs(5));
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0].ttl(), 10);
+        assert_eq!(records[0].record().ttl(), 1);
+        assert!(cache
+            .records_at("host.local", now + Duration::from_secs(10))
+            .is_empty());
+    }
+
+    #[test]
+    fn cache_evictions() {
+        let now = Instant::now();
+        let mut cache = Cache::new();
+        let evictions = cache.subscribe_evictions();
+        let provenance = Provenance::new("192.168.1.10:5353".parse().unwrap());
+
+        let host = |addr, ttl| with_ttl(a_record("host.local", addr), ttl);
+
+        // Goodbye, after the grace period.
+        cache.add_record_at(&host([192, 168, 1, 10], 120), &provenance, now);
+        cache.add_record_at(&host([192, 168, 1, 10], 0), &provenance, now);
+        assert!(evictions.try_recv().is_err());
+        cache.remove_expired_at(now + GOODBYE_GRACE);
+        let eviction = evictions.try_recv().unwrap();
+        assert_eq!(eviction.reason(), EvictionReason::Goodbye);
+        assert_eq!(eviction.record().record().data(), &[192, 168, 1, 10]);
+        assert!(cache.is_empty());
+
+        // Cache-flush replacement, only after the grace period.
+        cache.add_record_at(&unique(host([192, 168, 1, 10], 120)), &provenance, now);
+        cache.add_record_at(&unique(host([10, 0, 0, 10], 120)), &provenance, now);
+        assert!(evictions.try_recv().is_err());
+        let later = now + Duration::from_secs(2);
+        cache.add_record_at(&unique(host([192, 168, 1, 20], 120)), &provenance, later);
+        assert_eq!(
+            evictions.try_recv().unwrap().reason(),
+            EvictionReason::CacheFlush
+        );
+        assert_eq!(
+            evictions.try_recv().unwrap().reason(),
+            EvictionReason::CacheFlush
+        );
+        assert_eq!(cache.len(), 1);
+
+        // POOF and manual flush
+        cache.poof("Host.local", Type::A);
+        assert_eq!(evictions.try_recv().unwrap().reason(), EvictionReason::Poof);
+        cache.add_record_at(&host([192, 168, 1, 10], 120), &provenance, now);
+        cache.flush();
+        assert_eq!(
+            evictions.try_recv().unwrap().reason(),
+            EvictionReason::Flush
+        );
+        assert!(cache.is_empty());
+
+        // Eviction by limit, of the record expiring first
+        cache.set_capacity(Some(1));
+        cache.add_record_at(&host([192, 168, 1, 10], 120), &provenance, now);
+        cache.add_record_at(&host([192, 168, 1, 20], 60), &provenance, now);
+        let eviction = evictions.try_recv().unwrap();
+        assert_eq!(eviction.reason(), EvictionReason::Limit);
+        assert_eq!(eviction.record().ttl(), 60);
+        assert_eq!(cache.len(), 1);
+
+        // TTL expiry
+        cache.remove_expired_at(now + Duration::from_secs(120));
+        assert_eq!(
+            evictions.try_recv().unwrap().reason(),
+            EvictionReason::Expired
+        );
+        assert!(cache.is_empty());
+        assert!(evictions.try_recv().is_err());
+    }
+
+    #[test]
+    fn cache_known_answers() {
+        struct Test {
+            elapsed: u64,
+            expected: Option<u32>,
+        }
+
+        let now = Instant::now();
+        let mut cache = Cache::new();
+        let provenance = Provenance::new("192.168.1.10:5353".parse().unwrap());
+        let ptr = unique(record("_http._tcp.local", Type::PTR, vec![1]));
+        cache.add_record_at(&ptr, &provenance, now);
+
+        let questions = vec![
+            Question::new("_HTTP._tcp.local", Type::PTR),
+            Question::new("_http._tcp.local", Type::ANY),
+            Question::new("_ipp._tcp.local", Type::PTR),
+        ];
+        let tests = vec![
+            Test {
+                elapsed: 0,
+                expected: Some(120),
+            },
+            Test {
+                elapsed: 30,
+                expected: Some(90),
+            },
+            Test {
+                elapsed: 60,
+                expected: Some(60),
+            },
+            Test {
+                elapsed: 61,
+                expected: None,
+            },
+            Test {
+                elapsed: 120,
+                expected: None,
+            },
+        ];
+        for test in tests {
+            let at = now + Duration::from_secs(test.elapsed);
+            let answers = cache.known_answers_at(&questions, at);
+            assert_eq!(
+                answers.first().map(|a| a.ttl()),
+                test.expected,
+                "after {}s",
+                test.elapsed
+            );
+            assert!(answers.len() <= 1);
+            assert!(answers.iter().all(|a| !a.cache_flush()));
+        }
+    }
+
+    #[test]
+    fn cache_flush_domain() {
+        let mut cache = Cache::new();
+        let provenance = Provenance::new("192.168.1.10:5353".parse().unwrap());
+        for name in [
+            "_ipp._tcp.local",
+            "Printer._ipp._tcp.local",
+            "_color._sub._ipp._tcp.local",
+            "_http._tcp.local",
+            "x_ipp._tcp.local",
+        ] {
+            cache.add_record(&record(name, Type::TXT, vec![0]), &provenance);
+        }
+
+        cache.flush_domain("_IPP._tcp.local");
+        assert_eq!(cache.len(), 2);
+        assert!(cache.records("printer._ipp._tcp.local").is_empty());
+        assert_eq!(cache.records("x_ipp._tcp.local").len(), 1);
+
+        cache.flush_name("_http._tcp.local");
+        assert_eq!(cache.len(), 1);
+    }
+
+    #[test]
+    fn cache_goodbye_grace() {
+        let now = Instant::now();
+        let mut cache = Cache::new();
+        let evictions = cache.subscribe_evictions();
+        let provenance = Provenance::new("192.168.1.10:5353".parse().unwrap());
+        let printer = |ttl| with_ttl(a_record("printer.local", [192, 168, 1, 10]), ttl);
+
+        // The record is kept for one second after the goodbye, but no longer a known answer.
+        cache.add_record_at(&printer(120), &provenance, now);
+        cache.add_record_at(&printer(0), &provenance, now);
+        let records = cache.records_at("printer.local", now);
+        assert_eq!(records.len(), 1);
+        assert!(records[0].is_goodbye());
+        assert_eq!(records[0].expires(), now + GOODBYE_GRACE);
+        let question = Question::new("printer.local", Type::A);
+        assert!(cache.known_answers_at(&[question], now).is_empty());
+
+        // A device rebooting quickly re-announces the record within the grace period, which keeps it cached.
+        let rebooted = now + Duration::from_millis(500);
+        cache.add_record_at(&printer(120), &provenance, rebooted);
+        cache.remove_expired_at(now + GOODBYE_GRACE);
+        let records = cache.records_at("printer.local", now + GOODBYE_GRACE);
+        assert_eq!(records.len(), 1);
+        assert!(!records[0].is_goodbye());
+        assert!(evictions.try_recv().is_err());
+
+        // A device which does not come back is evicted as a goodbye once the grace period has elapsed.
+        let later = now + Duration::from_secs(10);
+        cache.add_record_at(&printer(0), &provenance, later);
+        cache.remove_expired_at(later + Duration::from_millis(999));
+        assert_eq!(cache.len(), 1);
+        cache.remove_expired_at(later + GOODBYE_GRACE);
+        assert_eq!(
+            evictions.try_recv().unwrap().reason(),
+            EvictionReason::Goodbye
+        );
+        assert!(cache.is_empty());
+
+        // A goodbye of a record which is not cached is ignored.
+        cache.add_record_at(&printer(0), &provenance, later);
+        assert!(cache.is_empty());
+    }
+
+    #[test]
+    fn cache_conflict_reconfirmation() {
+        let now = Instant::now();
+        let mut cache = Cache::new();
+        let evictions = cache.subscribe_evictions();
+        let reconfirmations = cache.subscribe_reconfirmations();
+        let owner = Provenance::new("192.168.1.10:5353".parse().unwrap());
+        let other = Provenance::new("192.168.1.20:5353".parse().unwrap());
+        let host = |addr| unique(a_record("host.local", addr));
+
+        // The owner announces the record again, so the conflicting record is discarded.
+        cache.add_record_at(&host([192, 168, 1, 10]), &owner, now);
+        let later = now + Duration::from_secs(2);
+        assert!(cache
+            .add_record_at(&host([192, 168, 1, 20]), &other, later)
+            .is_some());
+        assert_eq!(cache.records_at("host.local", later).len(), 1);
+        assert_eq!(cache.pending_conflicts().len(), 1);
+        assert!(evictions.try_recv().is_err());
+        assert_eq!(cache.due_reconfirmations_at(later).len(), 1);
+        assert!(cache.due_reconfirmations_at(later).is_empty());
+        let half = later + RECONFIRM_TIMEOUT / 2;
+        assert_eq!(cache.due_reconfirmations_at(half).len(), 1);
+        assert!(cache.due_reconfirmations_at(half).is_empty());
+        cache.add_record_at(&host([192, 168, 1, 10]), &owner, half);
+        let reconfirmation = reconfirmations.try_recv().unwrap();
+        assert_eq!(reconfirmation.outcome(), ReconfirmationOutcome::Confirmed);
+        assert_eq!(reconfirmation.conflict().existing(), &owner);
+        assert!(cache.pending_conflicts().is_empty());
+        cache.remove_expired_at(later + RECONFIRM_TIMEOUT);
+        let records = cache.records_at("host.local", later + RECONFIRM_TIMEOUT);
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0].record().data(), &[192, 168, 1, 10]);
+
+        // The owner is silent, so the conflicting record replaces the cached record.
+        assert!(cache
+            .add_record_at(&host([192, 168, 1, 20]), &other, later)
+            .is_some());
+        assert!(cache
+            .add_record_at(&host([192, 168, 1, 20]), &other, later)
+            .is_none());
+        cache.remove_expired_at(later + RECONFIRM_TIMEOUT - Duration::from_secs(1));
+        assert!(reconfirmations.try_recv().is_err());
+        cache.remove_expired_at(later + RECONFIRM_TIMEOUT);
+        let reconfirmation = reconfirmations.try_recv().unwrap();
+        assert_eq!(reconfirmation.outcome(), ReconfirmationOutcome::Replaced);
+        assert_eq!(
+            evictions.try_recv().unwrap().reason(),
+            EvictionReason::Conflict
+        );
+        let records = cache.records_at("host.local", later + RECONFIRM_TIMEOUT);
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0].provenance(), &other);
+        assert_eq!(records[0].record().data(), &[192, 168, 1, 20]);
+    }
+
+    #[test]
+    fn cache_conflict_multihomed() {
+        let now = Instant::now();
+        let mut cache = Cache::new();
+        let first = Provenance::new("192.168.1.10:5353".parse().unwrap());
+        let second = Provenance::new("192.168.1.20:5353".parse().unwrap());
+        let other = Provenance::new("192.168.1.30:5353".parse().unwrap());
+        let host = |addr| unique(a_record("host.local", addr));
+
+        // A multihomed responder answers the same record set from another interface.
+        cache.add_record_at(&host([192, 168, 1, 10]), &first, now);
+        let records = [host([192, 168, 1, 20]), host([192, 168, 1, 10])];
+        assert!(cache.add_records_at(&records, &second, now).is_empty());
+        assert!(cache.pending_conflicts().is_empty());
+        assert_eq!(cache.records_at("host.local", now).len(), 2);
+
+        // Another host asserts a record set without the cached data.
+        let records = [host([192, 168, 1, 30])];
+        assert_eq!(cache.add_records_at(&records, &other, now).len(), 1);
+    }
+}
@@ -0,0 +1,209 @@
+// Copyright (C) 2024 Satoshi Konno All rights reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//    http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::io::{self, Write};
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::default::PORT;
+
+// pcap-linktype(7): LINKTYPE_RAW
+const LINKTYPE_RAW: u32 = 101;
+const PCAP_MAGIC: u32 = 0xa1b2c3d4;
+const PCAP_SNAPLEN: u32 = 65535;
+const IPPROTO_UDP: u8 = 17;
+const UDP_HEADER_LEN: usize = 8;
+// RFC 6762: 11. Source Address Check
+const MDNS_HOP_LIMIT: u8 = 255;
+const MULTICAST_V4_GROUP: Ipv4Addr = Ipv4Addr::new(224, 0, 0, 251);
+const MULTICAST_V6_GROUP: Ipv6Addr = Ipv6Addr::new(0xff02, 0, 0, 0, 0, 0, 0, 0xfb);
+
+/// PacketSink represents a destination of the captured packets.
+pub trait PacketSink: Send {
+    /// write_packet writes the specified UDP payload received at the specified time from the specified source.
+    fn write_packet(
+        &mut self,
+        time: SystemTime,
+        from: SocketAddr,
+        payload: &[u8],
+    ) -> io::Result<()>;
+}
+
+/// PcapWriter writes the captured packets in the pcap format, which Wireshark and tcpdump can read.
+/// The packets are wrapped in the synthesized IP and UDP headers addressed to the mDNS multicast group.
+pub struct PcapWriter<W: Write + Send> {
+    w: W,
+}
+
+impl<W: Write + Send> PcapWriter<W> {
+    /// new creates a new writer, and writes the pcap file header to the specified writer.
+    pub fn new(mut w: W) -> io::Result<PcapWriter<W>> {
+        w.write_all(&PCAP_MAGIC.to_le_bytes())?;
+        w.write_all(&2u16.to_le_bytes())?;
+        w.write_all(&4u16.to_le_bytes())?;
+        w.write_all(&0i32.to_le_bytes())?;
+        w.write_all(&0u32.to_le_bytes())?;
+        w.write_all(&PCAP_SNAPLEN.to_le_bytes())?;
+        w.write_all(&LINKTYPE_RAW.to_le_bytes())?;
+        Ok(PcapWriter { w })
+    }
+
+    /// into_inner returns the underlying writer.
+    pub fn into_inner(self) -> W {
+        self.w
+    }
+}
+
+impl<W: Write + Send> PacketSink for PcapWriter<W> {
+    fn write_packet(
+        &mut self,
+        time: SystemTime,
+        from: SocketAddr,
+        payload: &[u8],
+    ) -> io::Result<()> {
+        let datagram = ip_datagram(from, payload)?;
+        let since_epoch = time.duration_since(UNIX_EPOCH).unwrap_or_default();
+        self.w
+            .write_all(&(since_epoch.as_secs() as u32).to_le_bytes())?;
+        self.w
+            .write_all(&since_epoch.subsec_micros().to_le_bytes())?;
+        self.w.write_all(&(datagram.len() as u32).to_le_bytes())?;
+        self.w.write_all(&(datagram.len() as u32).to_le_bytes())?;
+        self.w.write_all(&datagram)?;
+        self.w.flush()
+    }
+}
+
+/// HexWriter writes the captured packets as text lines of the time, the source and the hex dump of the payload.
+pub struct HexWriter<W: Write + Send> {
+    w: W,
+}
+
+impl<W: Write + Send> HexWriter<W> {
+    /// new creates a new writer to the specified writer.
+    pub fn new(w: W) -> HexWriter<W> {
+        HexWriter { w }
+    }
+
+    /// into_inner returns the underlying writer.
+    pub fn into_inner(self) -> W {
+        self.w
+    }
+}
+
+impl<W: Write + Send> PacketSink for HexWriter<W> {
+    fn write_packet(
+        &mut self,
+        time: SystemTime,
+        from: SocketAddr,
+        payload: &[u8],
+    ) -> io::Result<()> {
+        let since_epoch = time.duration_since(UNIX_EPOCH).unwrap_or_default();
+        writeln!(
+            self.w,
+            "{}.{:06} {} {}",
+            since_epoch.as_secs(),
+            since_epoch.subsec_micros(),
+            from,
+            hex::encode(payload)
+        )?;
+        self.w.flush()
+    }
+}
+
+// ip_datagram returns the IP datagram carrying the specified payload from the specified source to the mDNS group.
+fn ip_datagram(from: SocketAddr, payload: &[u8]) -> io::Result<Vec<u8>> {
+    let udp_len = UDP_HEADER_LEN + payload.len();
+    if (u16::MAX as usize) < udp_len {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("payload too large: {}", payload.len()),
+        ));
+    }
+    let src = match from.ip() {
+        IpAddr::V6(v6) => match v6.to_ipv4_mapped() {
+            Some(v4) => IpAddr::V4(v4),
+            None => IpAddr::V6(v6),
+        },
+        addr => addr,
+    };
+    let (mut datagram, pseudo_header) = match src {
+        IpAddr::V4(src) => {
+            let dst = MULTICAST_V4_GROUP;
+            let total_len = (20 + udp_len) as u16;
+            let mut header = vec![0x45, 0];
+            header.extend_from_slice(&total_len.to_be_bytes());
+            header.extend_from_slice(&[0, 0, 0, 0, MDNS_HOP_LIMIT, IPPROTO_UDP, 0, 0]);
+            header.extend_from_slice(&src.octets());
+            header.extend_from_slice(&dst.octets());
+            let checksum = checksum(&header);
+            header[10..12].copy_from_slice(&checksum.to_be_bytes());
+
+            let mut pseudo_header = Vec::new();
+            pseudo_header.extend_from_slice(&src.octets());
+            pseudo_header.extend_from_slice(&dst.octets());
+            pseudo_header.extend_from_slice(&[0, IPPROTO_UDP]);
+            pseudo_header.extend_from_slice(&(udp_len as u16).to_be_bytes());
+            (header, pseudo_header)
+        }
+        IpAddr::V6(src) => {
+            let dst = MULTICAST_V6_GROUP;
+            let mut header = vec![0x60, 0, 0, 0];
+            header.extend_from_slice(&(udp_len as u16).to_be_bytes());
+            header.extend_from_slice(&[IPPROTO_UDP, MDNS_HOP_LIMIT]);
+            header.extend_from_slice(&src.octets());
+            header.extend_from_slice(&dst.octets());
+
+            let mut pseudo_header = Vec::new();
+            pseudo_header.extend_from_slice(&src.octets());
+            pseudo_header.extend_from_slice(&dst.octets());
+            pseudo_header.extend_from_slice(&(udp_len as u32).to_be_bytes());
+            pseudo_header.extend_from_slice(&[0, 0, 0, IPPROTO_UDP]);
+            (header, pseudo_header)
+        }
+    };
+
+    let mut udp = Vec::with_capacity(udp_len);
+    udp.extend_from_slice(&from.port().to_be_bytes());
+    udp.extend_from_slice(&PORT.to_be_bytes());
+    udp.extend_from_slice(&(udp_len as u16).to_be_bytes());
+    udp.extend_from_slice(&[0, 0]);
+    udp.extend_from_slice(payload);
+    // RFC 768: If the computed checksum is zero, it is transmitted as all ones.
+    let checksum = match checksum(&[pseudo_header.as_slice(), udp.as_slice()].concat()) {
+        0 => 0xffff,
+        checksum => checksum,
+    };
+    udp[6..8].copy_from_slice(&checksum.to_be_bytes());
+
+    datagram.extend_from_slice(&udp);
+    Ok(datagram)
+}
+
+// checksum returns the internet checksum of the specified bytes.
+// RFC 1071: Computing the Internet Checksum
+fn checksum(bytes: &[u8]) -> u16 {
+    let mut sum: u32 = bytes
+        .chunks(2)
+        .map(|pair| match pair {
+            [hi, lo] => u16::from_be_bytes([*hi, *lo]) as u32,
+            [hi] => u16::from_be_bytes([*hi, 0]) as u32,
+            _ => 0,
+        })
+        .sum();
+    while 0xffff < sum {
+        sum = (sum & 0xffff) + (sum >> 16);
+    }
+    !(sum as u16)
+}
@@ -0,0 +1,133 @@
+// Copyright (C) 2024 Satoshi Konno All rights reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//    http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+#[cfg(test)]
+mod tests {
+
+    use std::io::{self, Write};
+    use std::sync::{Arc, Mutex};
+    use std::time::{Duration, UNIX_EPOCH};
+
+    use cybergarage::net::{Observer, Packet};
+
+    use crate::{Discoverer, HexWriter, PacketSink, PcapWriter};
+
+    const PCAP_HEADER_LEN: usize = 24;
+    const RECORD_HEADER_LEN: usize = 16;
+
+    #[derive(Clone)]
+    struct SharedBuffer(Arc<Mutex<Vec<u8>>>);
+
+    impl Write for SharedBuffer {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.0.lock().unwrap().write(buf)
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    fn sum(bytes: &[u8]) -> u16 {
+        let mut sum: u32 = bytes
+            .chunks(2)
+            .map(|pair| u16::from_be_bytes([pair[0], *pair.get(1).unwrap_or(&0)]) as u32)
+            .sum();
+        while 0xffff < sum {
+            sum = (sum & 0xffff) + (sum >> 16);
+        }
+        sum as u16
+    }
+
+    #[test]
+    fn pcap_writer() {
+        struct Test {
+            from: &'static str,
+            header_len: usize,
+        }
+
+        let tests = vec![
+            Test {
+                from: "192.168.1.10:5353",
+                header_len: 20,
+            },
+            Test {
+                from: "[fe80::10]:5353",
+                header_len: 40,
+            },
+        ];
+        let payload = [0u8, 0, 0x84, 0, 0, 0, 0, 1, 0, 0, 0, 0, 0xab];
+        let time = UNIX_EPOCH + Duration::from_micros(1_700_000_000_123_456);
+        for test in tests {
+            let mut w = PcapWriter::new(Vec::new()).unwrap();
+            w.write_packet(time, test.from.parse().unwrap(), &payload)
+                .unwrap();
+            let bytes = w.into_inner();
+            assert_eq!(bytes[..4], [0xd4, 0xc3, 0xb2, 0xa1]);
+            assert_eq!(bytes[20..24], 101u32.to_le_bytes());
+
+            let record = &bytes[PCAP_HEADER_LEN..];
+            assert_eq!(record[..4], 1_700_000_000u32.to_le_bytes());
+            assert_eq!(record[4..8], 123_456u32.to_le_bytes());
+            let len = test.header_len + 8 + payload.len();
+            assert_eq!(record[8..12], (len as u32).to_le_bytes());
+            let datagram = &record[RECORD_HEADER_LEN..];
+            assert_eq!(datagram.len(), len, "{}", test.from);
+            assert_eq!(datagram[test.header_len + 8..], payload);
+
+            let udp = &datagram[test.header_len..];
+            assert_eq!(udp[2..4], 5353u16.to_be_bytes());
+            let pseudo_header = if test.header_len == 20 {
+                assert_eq!(sum(&datagram[..20]), 0xffff);
+                assert_eq!(datagram[16..20], [224, 0, 0, 251]);
+                [&datagram[12..20], &[0, 17], &udp[4..6]].concat()
+            } else {
+                assert_eq!(datagram[24..26], [0xff, 0x02]);
+                [
+                    &datagram[8..40],
+                    &(udp.len() as u32).to_be_bytes()[..],
+                    &[0, 0, 0, 17],
+                ]
+                .concat()
+            };
+            assert_eq!(sum(&[pseudo_header.as_slice(), udp].concat()), 0xffff);
+        }
+    }
+
+    #[test]
+    fn discoverer_capture() {
+        let buffer = SharedBuffer(Arc::new(Mutex::new(Vec::new())));
+        let discoverer = Discoverer::new();
+        discoverer
+            .lock()
+            .unwrap()
+            .set_capture(Box::new(HexWriter::new(buffer.clone())));
+
+        let mut pkt = Packet::from_bytes(&vec![0x12, 0x34]);
+        pkt.set_from("192.168.1.10:5353".parse().unwrap());
+        discoverer.lock().unwrap().packet_received(&pkt);
+        discoverer.lock().unwrap().packet_received(&pkt);
+        discoverer.lock().unwrap().clear_capture();
+        discoverer.lock().unwrap().packet_received(&pkt);
+
+        let text = String::from_utf8(buffer.0.lock().unwrap().clone()).unwrap();
+        let lines = text.lines().collect::<Vec<&str>>();
+        assert_eq!(lines.len(), 2);
+        assert!(
+            lines[0].ends_with(" 192.168.1.10:5353 1234"),
+            "{}",
+            lines[0]
+        );
+    }
+}
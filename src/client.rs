@@ -12,16 +12,43 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::io::ErrorKind;
+use std::net::SocketAddr;
+use std::sync::mpsc;
 use std::sync::Arc;
 use std::sync::Mutex;
+use std::time::{Duration, Instant};
 
+use crate::browse_session::BrowseSession;
+#[cfg(feature = "capture")]
+use crate::capture::PacketSink;
+use crate::coalesce::EventCoalescer;
+use crate::conflict::Reconfirmation;
+use crate::device_info::DeviceInfo;
 use crate::discoverer::Discoverer;
+use crate::dns::Type;
+use crate::event::{ServiceCallback, ServiceEvent};
+use crate::eviction::Eviction;
+use crate::filter::Filter;
+use crate::llq::LlqClient;
+use crate::loopback::LoopbackFilter;
+use crate::normalize::NameComparison;
+use crate::outgoing::OutgoingHook;
 use crate::query::Query;
+use crate::query_history::{ObservedQuestion, QueryHistory};
 use crate::service::Service;
+use crate::shutdown::{ShutdownReport, DEFAULT_SHUTDOWN_TIMEOUT};
+use crate::stats::Stats;
+use crate::summary::ServiceSummary;
+use crate::supervisor::TaskStatus;
+use crate::trust_policy::TrustPolicy;
+use crate::txt_schema::TxtSchemas;
+use crate::unicast::UnicastResolver;
 
 /// Client represents a client.
 pub struct Client {
     discoverer: Arc<Mutex<Discoverer>>,
+    llq_clients: Vec<LlqClient>,
 }
 
 impl Client {
@@ -29,36 +56,371 @@ impl Client {
     pub fn new() -> Client {
         Client {
             discoverer: Discoverer::new(),
+            llq_clients: Vec::new(),
         }
     }
 
-    ///search queries the client.
+    ///search queries the client, and returns a NotConnected error unless the client is started.
     pub fn search(&mut self, query: &Query) -> Result<(), std::io::Error> {
+        self.check_started()?;
         self.discoverer.lock().unwrap().search(query)
     }
 
-    // services returns the services of the client.
+    /// browse_session returns a new stopped session which browses the specified queries as one unit on the client.
+    pub fn browse_session(&self, queries: &[Query]) -> BrowseSession {
+        BrowseSession::new(self.discoverer.clone(), queries)
+    }
+
+    /// search_llq browses the specified query as a long-lived query at the specified unicast DNS server,
+    /// such as a legacy wide-area Bonjour server, and the pushed changes are delivered as the service events.
+    pub fn search_llq(
+        &mut self,
+        server: SocketAddr,
+        query: &Query,
+        timeout: Duration,
+    ) -> Result<(), std::io::Error> {
+        let mut llq = LlqClient::new(server, &query.to_string());
+        llq.start(self.discoverer.clone(), timeout)?;
+        self.llq_clients.push(llq);
+        Ok(())
+    }
+
+    /// search_unicast browses the specified query at the specified unicast DNS server, such as a wide-area DNS-SD server,
+    /// and the answers are delivered as the service events.
+    pub fn search_unicast(
+        &mut self,
+        server: SocketAddr,
+        query: &Query,
+        timeout: Duration,
+    ) -> Result<(), std::io::Error> {
+        let mut resolver = UnicastResolver::new(server);
+        resolver.set_timeout(timeout);
+        resolver.search(&self.discoverer, query)
+    }
+
+    /// resolve queries the records of the specified service again and waits for the answer,
+    /// so that a cached entry can be verified immediately before connecting to it.
+    pub fn resolve(
+        &mut self,
+        service: &Service,
+        timeout: Duration,
+    ) -> Result<Service, std::io::Error> {
+        self.check_started()?;
+        let events = {
+            let mut discoverer = self.discoverer.lock().unwrap();
+            let events = discoverer.subscribe_live();
+            discoverer.resolve(service)?;
+            events
+        };
+        wait_resolved(&events, service, timeout)
+    }
+
+    /// query_device_info queries the device information of the specified host, such as `MyMac.local`.
+    pub fn query_device_info(&mut self, host: &str) -> Result<(), std::io::Error> {
+        self.check_started()?;
+        self.discoverer.lock().unwrap().query_device_info(host)
+    }
+
+    /// flush_cache removes all cached records and services, so the next responses build a fresh view.
+    pub fn flush_cache(&mut self) {
+        self.discoverer.lock().unwrap().flush_cache();
+    }
+
+    /// flush_type removes the cached records and services of the specified service type such as `_ipp._tcp`.
+    pub fn flush_type(&mut self, service_type: &str) {
+        self.discoverer.lock().unwrap().flush_type(service_type);
+    }
+
+    /// invalidate removes the cached records of the specified service instance, and queries them again to reconfirm it.
+    pub fn invalidate(&mut self, instance: &str) -> Result<(), std::io::Error> {
+        self.check_started()?;
+        self.discoverer.lock().unwrap().invalidate(instance)
+    }
+
+    /// device_info returns the device information of the specified host if it has been received.
+    pub fn device_info(&self, host: &str) -> Option<DeviceInfo> {
+        self.discoverer.lock().unwrap().device_info(host)
+    }
+
+    /// services returns the services of the client, merging the ones of the same name received on the different interfaces.
     pub fn services(&self) -> Vec<Service> {
+        self.discoverer.lock().unwrap().merged_services()
+    }
+
+    /// summary returns the counts of the discovered services grouped by the service type and the interface.
+    pub fn summary(&self) -> ServiceSummary {
+        self.discoverer.lock().unwrap().summary()
+    }
+
+    /// service_types returns the service types such as `_http._tcp.local` enumerated in the specified domain,
+    /// which are answered to the query of Query::service_enumeration.
+    pub fn service_types(&self, domain: &str) -> Vec<String> {
+        self.discoverer.lock().unwrap().service_types(domain)
+    }
+
+    /// services_on_host returns the services offered by the specified host, such as `printer.local`.
+    pub fn services_on_host(&self, host: &str) -> Vec<Service> {
         let mut services = Vec::new();
-        for service in self.discoverer.lock().unwrap().services() {
+        for service in self.discoverer.lock().unwrap().services_on_host(host) {
             services.push(service.clone());
         }
         services
     }
 
+    /// services_on_interface returns the services received on the specified interface.
+    pub fn services_on_interface(&self, interface: &str) -> Vec<Service> {
+        let mut services = Vec::new();
+        for service in self
+            .discoverer
+            .lock()
+            .unwrap()
+            .services_on_interface(interface)
+        {
+            services.push(service.clone());
+        }
+        services
+    }
+
+    /// set_name_comparison sets how the names of the services and the records are compared.
+    pub fn set_name_comparison(&mut self, name_comparison: NameComparison) {
+        self.discoverer
+            .lock()
+            .unwrap()
+            .set_name_comparison(name_comparison);
+    }
+
+    /// set_interface_scoped sets whether a service is identified by the pair of the instance name and the interface.
+    pub fn set_interface_scoped(&mut self, scoped: bool) {
+        self.discoverer.lock().unwrap().set_interface_scoped(scoped);
+    }
+
+    /// subscribe returns a receiver of the service events.
+    pub fn subscribe(&mut self) -> mpsc::Receiver<ServiceEvent> {
+        self.discoverer.lock().unwrap().subscribe()
+    }
+
+    /// on_service_added registers the callback called with each discovered service.
+    /// The callbacks are called under the client lock, so they must not call the client.
+    pub fn on_service_added(&mut self, callback: ServiceCallback) {
+        self.discoverer.lock().unwrap().on_service_added(callback);
+    }
+
+    /// on_service_updated registers the callback called with each known service announced again.
+    pub fn on_service_updated(&mut self, callback: ServiceCallback) {
+        self.discoverer.lock().unwrap().on_service_updated(callback);
+    }
+
+    /// on_service_removed registers the callback called with each service which is no longer available.
+    pub fn on_service_removed(&mut self, callback: ServiceCallback) {
+        self.discoverer.lock().unwrap().on_service_removed(callback);
+    }
+
+    /// subscribe_evictions returns a receiver of the records leaving the cache with the reasons.
+    pub fn subscribe_evictions(&mut self) -> mpsc::Receiver<Eviction> {
+        self.discoverer
+            .lock()
+            .unwrap()
+            .cache_mut()
+            .subscribe_evictions()
+    }
+
+    /// subscribe_reconfirmations returns a receiver of the conflicts of the cached records with how they were resolved.
+    pub fn subscribe_reconfirmations(&mut self) -> mpsc::Receiver<Reconfirmation> {
+        self.discoverer
+            .lock()
+            .unwrap()
+            .cache_mut()
+            .subscribe_reconfirmations()
+    }
+
+    /// is_known_absent returns true if the specified type is known not to exist for the name.
+    pub fn is_known_absent(&self, name: &str, typ: Type) -> bool {
+        self.discoverer
+            .lock()
+            .unwrap()
+            .cache()
+            .is_known_absent(name, typ)
+    }
+
+    /// set_ttl_bounds sets the minimum and maximum effective TTLs of the cached records.
+    pub fn set_ttl_bounds(&mut self, min_ttl: Option<u32>, max_ttl: Option<u32>) {
+        let mut discoverer = self.discoverer.lock().unwrap();
+        discoverer.cache_mut().set_min_ttl(min_ttl);
+        discoverer.cache_mut().set_max_ttl(max_ttl);
+    }
+
+    /// set_allowed_interfaces restricts the interfaces whose packets are handled to the specified names.
+    pub fn set_allowed_interfaces(&mut self, names: &[&str]) {
+        self.discoverer
+            .lock()
+            .unwrap()
+            .set_allowed_interfaces(names);
+    }
+
+    /// set_outgoing_hook sets the hook invoked with every packet about to be transmitted, which may rewrite or veto the packet.
+    pub fn set_outgoing_hook(&mut self, hook: OutgoingHook) {
+        self.discoverer.lock().unwrap().set_outgoing_hook(hook);
+    }
+
+    /// set_capture sets the sink which every received packet is written to, such as a PcapWriter.
+    #[cfg(feature = "capture")]
+    pub fn set_capture(&mut self, sink: Box<dyn PacketSink>) {
+        self.discoverer.lock().unwrap().set_capture(sink);
+    }
+
+    /// set_event_coalescer sets the coalescer which merges the bursts of the events of the same instance.
+    pub fn set_event_coalescer(&mut self, coalescer: EventCoalescer) {
+        self.discoverer
+            .lock()
+            .unwrap()
+            .set_event_coalescer(coalescer);
+    }
+
+    /// flush_events delivers all events pending in the coalescer without waiting for their windows.
+    pub fn flush_events(&mut self) {
+        self.discoverer.lock().unwrap().flush_events();
+    }
+
+    /// set_txt_schemas sets the TXT schemas of the service types, and the services whose attributes violate them are ignored.
+    pub fn set_txt_schemas(&mut self, schemas: TxtSchemas) {
+        self.discoverer.lock().unwrap().set_txt_schemas(schemas);
+    }
+
+    /// set_query_history sets the history which every question received from the network is added to.
+    pub fn set_query_history(&mut self, history: QueryHistory) {
+        self.discoverer.lock().unwrap().set_query_history(history);
+    }
+
+    /// query_history returns the kept questions from the oldest, or no questions if no history is set.
+    pub fn query_history(&self) -> Vec<ObservedQuestion> {
+        match self.discoverer.lock().unwrap().query_history() {
+            Some(history) => history.questions().into_iter().cloned().collect(),
+            None => Vec::new(),
+        }
+    }
+
+    /// set_self_suppression sets whether our own transmissions looped back by the kernel are ignored. It is enabled by default.
+    pub fn set_self_suppression(&mut self, enabled: bool) {
+        self.discoverer
+            .lock()
+            .unwrap()
+            .set_self_suppression(enabled);
+    }
+
+    /// loopback_filter returns the filter of our own transmissions, to be set to a responder in the same process.
+    pub fn loopback_filter(&self) -> Arc<Mutex<LoopbackFilter>> {
+        self.discoverer.lock().unwrap().loopback_filter()
+    }
+
+    /// set_on_link_check sets whether the packets from the sources off the directly attached networks are dropped.
+    pub fn set_on_link_check(&mut self, enabled: bool) {
+        self.discoverer.lock().unwrap().set_on_link_check(enabled);
+    }
+
+    /// set_trust_policy sets the checks which the additional records of the received messages must pass before they are cached.
+    pub fn set_trust_policy(&mut self, policy: TrustPolicy) {
+        self.discoverer.lock().unwrap().set_trust_policy(policy);
+    }
+
+    /// set_filter sets the filter of the services, and only the matching services are cached and generate events.
+    pub fn set_filter(&mut self, filter: Filter) {
+        self.discoverer.lock().unwrap().set_filter(filter);
+    }
+
+    /// clear_filter removes the filter of the services.
+    pub fn clear_filter(&mut self) {
+        self.discoverer.lock().unwrap().clear_filter();
+    }
+
+    /// stats returns the statistics of the client.
+    pub fn stats(&self) -> Stats {
+        self.discoverer.lock().unwrap().stats().clone()
+    }
+
+    /// tasks returns the statuses of the background tasks of the client.
+    pub fn tasks(&self) -> Vec<TaskStatus> {
+        self.discoverer.lock().unwrap().tasks()
+    }
+
     /// start starts the client.
     pub fn start(&mut self) -> Result<(), std::io::Error> {
         self.discoverer.lock().unwrap().start()
     }
 
+    /// is_running returns true if the client is started and not stopped.
+    pub fn is_running(&self) -> bool {
+        self.discoverer.lock().unwrap().is_running()
+    }
+
+    // check_started returns a NotConnected error unless the client is started, since a query of a stopped client would be dropped silently.
+    fn check_started(&self) -> Result<(), std::io::Error> {
+        if self.is_running() {
+            return Ok(());
+        }
+        Err(std::io::Error::new(
+            ErrorKind::NotConnected,
+            "client not started",
+        ))
+    }
+
     /// stop stops the client.
     pub fn stop(&mut self) -> Result<(), std::io::Error> {
         self.discoverer.lock().unwrap().stop()
     }
+
+    /// shutdown stops the client, closes its event subscribers and reports what was cleaned up.
+    pub fn shutdown(&mut self, timeout: Duration) -> Result<ShutdownReport, std::io::Error> {
+        let started = Instant::now();
+        let mut report = ShutdownReport::new();
+        for mut llq in self.llq_clients.drain(..) {
+            report.add_worker(llq.stop(timeout.saturating_sub(started.elapsed())));
+        }
+        let discoverer_report = self
+            .discoverer
+            .lock()
+            .unwrap()
+            .shutdown(timeout.saturating_sub(started.elapsed()))?;
+        report.merge(&discoverer_report);
+        report.set_elapsed(started.elapsed());
+        Ok(report)
+    }
+}
+
+impl Default for Client {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl Drop for Client {
     fn drop(&mut self) {
-        let _ = self.stop();
+        let _ = self.shutdown(DEFAULT_SHUTDOWN_TIMEOUT);
+    }
+}
+
+// wait_resolved waits for the event of the specified service received within the specified timeout,
+// which answers the query sent after the specified receiver subscribed.
+pub(crate) fn wait_resolved(
+    events: &mpsc::Receiver<ServiceEvent>,
+    service: &Service,
+    timeout: Duration,
+) -> Result<Service, std::io::Error> {
+    let deadline = Instant::now() + timeout;
+    loop {
+        let remaining = deadline.saturating_duration_since(Instant::now());
+        match events.recv_timeout(remaining) {
+            Ok(ServiceEvent::Added(resolved)) | Ok(ServiceEvent::Updated(resolved)) => {
+                if resolved.name() == service.name() {
+                    return Ok(resolved);
+                }
+            }
+            Ok(_) => {}
+            Err(_) => {
+                return Err(std::io::Error::new(
+                    ErrorKind::TimedOut,
+                    format!("{} was not resolved", service.name()),
+                ));
+            }
+        }
     }
 }
@@ -15,9 +15,15 @@
 #[cfg(test)]
 mod tests {
 
+    use std::io::ErrorKind;
+    use std::time::Duration;
+
     use cybergarage::log::Logger;
+    use cybergarage::net::Observer;
 
-    use crate::{Client, Query};
+    use crate::client::wait_resolved;
+    use crate::fixture_test::srv_response_packet;
+    use crate::{Client, Discoverer, Query};
 
     #[test]
     fn client() {
@@ -33,4 +39,47 @@ mod tests {
         }
         assert!(client.stop().is_ok());
     }
+
+    #[test]
+    fn client_not_started() {
+        let mut client = Client::new();
+        assert!(!client.is_running());
+        let err = client
+            .search(&Query::with("_http._tcp", "local"))
+            .unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::NotConnected);
+        let err = client.query_device_info("host.local").unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::NotConnected);
+        let err = client.invalidate("web._http._tcp.local").unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::NotConnected);
+        assert_eq!(client.stats().queries_sent(), 0);
+        assert!(client.shutdown(Duration::from_secs(1)).is_ok());
+    }
+
+    #[test]
+    fn client_resolve_cached() {
+        let discoverer = Discoverer::new();
+        let pkt = srv_response_packet("test._http._tcp.local", 80);
+        discoverer.lock().unwrap().packet_received(&pkt);
+        let cached = discoverer.lock().unwrap().services()[0].clone();
+
+        // The cached service is not replayed as the answer of the resolution.
+        let events = {
+            let mut discoverer = discoverer.lock().unwrap();
+            let events = discoverer.subscribe_live();
+            assert!(discoverer.resolve(&cached).is_ok());
+            events
+        };
+        match wait_resolved(&events, &cached, Duration::ZERO) {
+            Err(e) => assert_eq!(e.kind(), ErrorKind::TimedOut),
+            Ok(_) => panic!("expected a timeout"),
+        }
+
+        let pkt = srv_response_packet("test._http._tcp.local", 8080);
+        discoverer.lock().unwrap().packet_received(&pkt);
+        match wait_resolved(&events, &cached, Duration::from_secs(1)) {
+            Ok(resolved) => assert_eq!(resolved.port(), 8080),
+            Err(e) => panic!("{}", e),
+        }
+    }
 }
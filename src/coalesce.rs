@@ -0,0 +1,157 @@
+// Copyright (C) 2024 Satoshi Konno All rights reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//    http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::time::{Duration, Instant};
+
+use crate::event::ServiceEvent;
+use crate::service::Service;
+
+/// DEFAULT_COALESCING_WINDOW is the default time in which the events of the same instance are merged.
+pub const DEFAULT_COALESCING_WINDOW: Duration = Duration::from_millis(250);
+
+struct PendingEvent {
+    key: String,
+    event: ServiceEvent,
+    deadline: Instant,
+}
+
+/// EventCoalescer merges the bursts of the added and updated events of the same instance into one event,
+/// which is delivered when the window of the first event ends.
+/// In the debounce mode, every merged event restarts the window instead.
+pub struct EventCoalescer {
+    window: Duration,
+    debounce: bool,
+    pending: Vec<PendingEvent>,
+}
+
+impl EventCoalescer {
+    /// new creates a new coalescer of the specified window.
+    pub fn new(window: Duration) -> EventCoalescer {
+        EventCoalescer {
+            window,
+            debounce: false,
+            pending: Vec::new(),
+        }
+    }
+
+    /// set_debounce sets whether every merged event restarts the window.
+    pub fn set_debounce(&mut self, debounce: bool) {
+        self.debounce = debounce;
+    }
+
+    /// is_debounce returns true if every merged event restarts the window.
+    pub fn is_debounce(&self) -> bool {
+        self.debounce
+    }
+
+    /// window returns the time in which the events of the same instance are merged.
+    pub fn window(&self) -> Duration {
+        self.window
+    }
+
+    /// len returns the number of the pending events.
+    pub fn len(&self) -> usize {
+        self.pending.len()
+    }
+
+    /// is_empty returns true if no event is pending.
+    pub fn is_empty(&self) -> bool {
+        self.pending.is_empty()
+    }
+
+    /// next_deadline returns the time when the next pending event is delivered.
+    pub fn next_deadline(&self) -> Option<Instant> {
+        self.pending.iter().map(|p| p.deadline).min()
+    }
+
+    /// push returns the events to deliver now after adding the specified event.
+    pub fn push(&mut self, event: ServiceEvent) -> Vec<ServiceEvent> {
+        self.push_at(event, Instant::now())
+    }
+
+    /// push_at returns the events to deliver at the specified time after adding the specified event.
+    /// An added event followed by a removed one in the same window cancel each other,
+    /// and the conflicts are never delayed.
+    pub fn push_at(&mut self, event: ServiceEvent, now: Instant) -> Vec<ServiceEvent> {
+        let mut events = self.flush_due_at(now);
+        let key = match event.service() {
+            Some(service) if !self.window.is_zero() => pending_key(service),
+            _ => {
+                events.push(event);
+                return events;
+            }
+        };
+        let pending = self.pending.iter().position(|p| p.key == key);
+        match (event, pending) {
+            (ServiceEvent::Removed(service), pending) => {
+                let cancelled = match pending {
+                    Some(n) => matches!(self.pending.remove(n).event, ServiceEvent::Added(_)),
+                    None => false,
+                };
+                if !cancelled {
+                    events.push(ServiceEvent::Removed(service));
+                }
+            }
+            (ServiceEvent::Added(service) | ServiceEvent::Updated(service), Some(n)) => {
+                let pending = &mut self.pending[n];
+                pending.event = match pending.event {
+                    ServiceEvent::Added(_) => ServiceEvent::Added(service),
+                    _ => ServiceEvent::Updated(service),
+                };
+                if self.debounce {
+                    pending.deadline = now + self.window;
+                }
+            }
+            (event, _) => self.pending.push(PendingEvent {
+                key,
+                event,
+                deadline: now + self.window,
+            }),
+        }
+        events
+    }
+
+    /// flush_due_at returns the pending events whose window has ended at the specified time in the order they were first added.
+    pub fn flush_due_at(&mut self, now: Instant) -> Vec<ServiceEvent> {
+        let mut events = Vec::new();
+        let mut n = 0;
+        while n < self.pending.len() {
+            if self.pending[n].deadline <= now {
+                events.push(self.pending.remove(n).event);
+            } else {
+                n += 1;
+            }
+        }
+        events
+    }
+
+    /// flush returns all pending events regardless of their windows.
+    pub fn flush(&mut self) -> Vec<ServiceEvent> {
+        self.pending.drain(..).map(|p| p.event).collect()
+    }
+}
+
+impl Default for EventCoalescer {
+    fn default() -> Self {
+        Self::new(DEFAULT_COALESCING_WINDOW)
+    }
+}
+
+// pending_key returns the key of the instance of the specified service, which is distinct per interface when it is attributed.
+fn pending_key(service: &Service) -> String {
+    match service.interface() {
+        Some(interface) => format!("{}%{}", service.name().to_lowercase(), interface),
+        None => service.name().to_lowercase(),
+    }
+}
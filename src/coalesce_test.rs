@@ -0,0 +1,133 @@
+// Copyright (C) 2024 Satoshi Konno All rights reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//    http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+#[cfg(test)]
+mod tests {
+
+    use std::time::{Duration, Instant};
+
+    use cybergarage::net::{Observer, Packet};
+
+    use crate::dns::Message;
+    use crate::fixture_test::srv_record;
+    use crate::{Discoverer, EventCoalescer, Service, ServiceEvent};
+
+    fn srv_response(name: &str, port: u16) -> Message {
+        let mut msg = Message::new();
+        msg.add_answer(srv_record(name, port, "host.local"));
+        msg
+    }
+
+    fn service(name: &str, port: u16) -> Service {
+        Service::from_message(&srv_response(name, port))
+    }
+
+    fn summary(events: &[ServiceEvent]) -> Vec<String> {
+        events
+            .iter()
+            .map(|event| {
+                let service = event.service().unwrap();
+                let kind = match event {
+                    ServiceEvent::Added(_) => "added",
+                    ServiceEvent::Updated(_) => "updated",
+                    ServiceEvent::Removed(_) => "removed",
+                    ServiceEvent::ConflictDetected(_) => "conflict",
+                };
+                format!("{} {}:{}", kind, service.instance_name(), service.port())
+            })
+            .collect()
+    }
+
+    #[test]
+    fn event_coalescer() {
+        let window = Duration::from_millis(100);
+        let start = Instant::now();
+        let at = |ms: u64| start + Duration::from_millis(ms);
+
+        let mut coalescer = EventCoalescer::new(window);
+        let a = "a._http._tcp.local";
+        let b = "b._http._tcp.local";
+        assert!(coalescer
+            .push_at(ServiceEvent::Added(service(a, 80)), at(0))
+            .is_empty());
+        assert!(coalescer
+            .push_at(ServiceEvent::Updated(service(a, 81)), at(10))
+            .is_empty());
+        assert!(coalescer
+            .push_at(ServiceEvent::Updated(service(b, 80)), at(20))
+            .is_empty());
+        assert_eq!(coalescer.len(), 2);
+        assert_eq!(coalescer.next_deadline(), Some(at(100)));
+        assert_eq!(
+            summary(&coalescer.flush_due_at(at(100))),
+            vec!["added a:81"]
+        );
+        assert_eq!(
+            summary(&coalescer.push_at(ServiceEvent::Removed(service(b, 80)), at(110))),
+            vec!["removed b:80"]
+        );
+        assert!(coalescer.is_empty());
+
+        // An instance which appears and disappears in a window is never delivered.
+        coalescer.push_at(ServiceEvent::Added(service(a, 80)), at(200));
+        assert!(coalescer
+            .push_at(ServiceEvent::Removed(service(a, 80)), at(210))
+            .is_empty());
+        assert!(coalescer.is_empty());
+
+        coalescer.set_debounce(true);
+        coalescer.push_at(ServiceEvent::Updated(service(a, 80)), at(300));
+        coalescer.push_at(ServiceEvent::Updated(service(a, 82)), at(380));
+        assert!(coalescer.flush_due_at(at(400)).is_empty());
+        assert_eq!(
+            summary(&coalescer.flush_due_at(at(480))),
+            vec!["updated a:82"]
+        );
+
+        let mut immediate = EventCoalescer::new(Duration::ZERO);
+        assert_eq!(
+            immediate
+                .push_at(ServiceEvent::Added(service(a, 80)), at(0))
+                .len(),
+            1
+        );
+    }
+
+    #[test]
+    fn discoverer_event_coalescer() {
+        let discoverer = Discoverer::new();
+        let events = discoverer.lock().unwrap().subscribe();
+        discoverer
+            .lock()
+            .unwrap()
+            .set_event_coalescer(EventCoalescer::new(Duration::from_secs(3600)));
+
+        for port in [80, 81, 82] {
+            let pkt = Packet::from_bytes(
+                &srv_response("test._http._tcp.local", port)
+                    .to_bytes()
+                    .unwrap(),
+            );
+            discoverer.lock().unwrap().packet_received(&pkt);
+        }
+        assert!(events.try_recv().is_err());
+
+        discoverer.lock().unwrap().flush_events();
+        match events.try_recv() {
+            Ok(ServiceEvent::Added(service)) => assert_eq!(service.port(), 82),
+            _ => panic!("expected an added event"),
+        }
+        assert!(events.try_recv().is_err());
+    }
+}
@@ -0,0 +1,141 @@
+// Copyright (C) 2024 Satoshi Konno All rights reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//    http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::fmt;
+
+use crate::dns::Type;
+use crate::provenance::Provenance;
+
+/// Conflict represents two different hosts asserting conflicting unique records of the same name.
+/// RFC 6762: 9. Conflict Resolution
+#[derive(Debug, Clone, PartialEq)]
+pub struct Conflict {
+    name: String,
+    typ: Type,
+    existing: Provenance,
+    existing_data: Vec<u8>,
+    conflicting: Provenance,
+    conflicting_data: Vec<u8>,
+}
+
+impl Conflict {
+    pub(crate) fn new(
+        name: &str,
+        typ: Type,
+        existing: (&Provenance, &[u8]),
+        conflicting: (&Provenance, &[u8]),
+    ) -> Conflict {
+        Conflict {
+            name: name.to_string(),
+            typ,
+            existing: existing.0.clone(),
+            existing_data: existing.1.to_vec(),
+            conflicting: conflicting.0.clone(),
+            conflicting_data: conflicting.1.to_vec(),
+        }
+    }
+
+    /// name returns the conflicted name.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// typ returns the type of the conflicted records.
+    pub fn typ(&self) -> Type {
+        self.typ
+    }
+
+    /// existing returns where the record cached first came from.
+    pub fn existing(&self) -> &Provenance {
+        &self.existing
+    }
+
+    /// existing_data returns the data of the record cached first.
+    pub fn existing_data(&self) -> &[u8] {
+        &self.existing_data
+    }
+
+    /// conflicting returns where the conflicting record came from.
+    pub fn conflicting(&self) -> &Provenance {
+        &self.conflicting
+    }
+
+    /// conflicting_data returns the data of the conflicting record.
+    pub fn conflicting_data(&self) -> &[u8] {
+        &self.conflicting_data
+    }
+}
+
+impl fmt::Display for Conflict {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{} {} is claimed by {} and {}",
+            self.name,
+            self.typ,
+            self.existing.source(),
+            self.conflicting.source()
+        )
+    }
+}
+
+/// ReconfirmationOutcome represents how a conflict of a cached record was resolved by the reconfirmation.
+/// RFC 6762: 10.4. Cache Flush on Failure Indication
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum ReconfirmationOutcome {
+    /// Confirmed is the outcome of a cached record which its owner announced again, so the conflicting record was discarded.
+    Confirmed,
+    /// Replaced is the outcome of a cached record which its owner did not announce again in time,
+    /// so the record was evicted and the conflicting record was cached instead.
+    Replaced,
+}
+
+impl fmt::Display for ReconfirmationOutcome {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let outcome = match self {
+            ReconfirmationOutcome::Confirmed => "confirmed",
+            ReconfirmationOutcome::Replaced => "replaced",
+        };
+        write!(f, "{}", outcome)
+    }
+}
+
+/// Reconfirmation represents a resolved conflict of a cached record with the outcome.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Reconfirmation {
+    conflict: Conflict,
+    outcome: ReconfirmationOutcome,
+}
+
+impl Reconfirmation {
+    pub(crate) fn new(conflict: Conflict, outcome: ReconfirmationOutcome) -> Reconfirmation {
+        Reconfirmation { conflict, outcome }
+    }
+
+    /// conflict returns the resolved conflict.
+    pub fn conflict(&self) -> &Conflict {
+        &self.conflict
+    }
+
+    /// outcome returns how the conflict was resolved.
+    pub fn outcome(&self) -> ReconfirmationOutcome {
+        self.outcome
+    }
+}
+
+impl fmt::Display for Reconfirmation {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}: {}", self.conflict, self.outcome)
+    }
+}
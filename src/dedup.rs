@@ -0,0 +1,124 @@
+// Copyright (C) 2024 Satoshi Konno All rights reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//    http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+use std::time::{Duration, Instant};
+
+/// DEFAULT_DEDUP_WINDOW is the default window in which byte-identical messages are regarded as duplicates.
+/// A repeated identical announcement carries nothing new, so the window may cover the one second interval of RFC 6762: 8.3.
+pub const DEFAULT_DEDUP_WINDOW: Duration = Duration::from_secs(1);
+
+// Seen represents a message received in the window.
+struct Seen {
+    received: Instant,
+    name: Option<String>,
+}
+
+/// Deduplicator represents a hash-based filter of the byte-identical messages, such as an announcement
+/// sent on several interfaces or arriving on both the IPv4 and IPv6 sockets, which is applied before parsing.
+pub struct Deduplicator {
+    window: Duration,
+    seen: HashMap<u64, Seen>,
+}
+
+impl Deduplicator {
+    /// new creates a new filter of the default window.
+    pub fn new() -> Deduplicator {
+        Deduplicator::with_window(DEFAULT_DEDUP_WINDOW)
+    }
+
+    /// with_window creates a new filter of the specified window.
+    pub fn with_window(window: Duration) -> Deduplicator {
+        Deduplicator {
+            window,
+            seen: HashMap::new(),
+        }
+    }
+
+    /// window returns the window of the filter.
+    pub fn window(&self) -> Duration {
+        self.window
+    }
+
+    /// is_duplicate returns true if the identical message was received within the window before now.
+    pub fn is_duplicate(&mut self, msg_bytes: &[u8]) -> bool {
+        self.is_duplicate_at(msg_bytes, Instant::now())
+    }
+
+    /// is_duplicate_at returns true if the identical message was received within the window before the specified time.
+    pub fn is_duplicate_at(&mut self, msg_bytes: &[u8], now: Instant) -> bool {
+        self.is_duplicate_key_at(hash(msg_bytes), now)
+    }
+
+    // is_duplicate_key_at returns true if the specified key was seen within the window, and remembers it otherwise.
+    // The fingerprint keys are inverted, so they do not collide with the byte hashes of the same value.
+    fn is_duplicate_key_at(&mut self, key: u64, now: Instant) -> bool {
+        let window = self.window;
+        self.seen
+            .retain(|_, seen| now.saturating_duration_since(seen.received) < window);
+        if self.seen.contains_key(&key) {
+            return true;
+        }
+        self.seen.insert(
+            key,
+            Seen {
+                received: now,
+                name: None,
+            },
+        );
+        false
+    }
+
+    /// is_duplicate_fingerprint returns true if a message of the specified fingerprint was received within the window before now.
+    pub fn is_duplicate_fingerprint(&mut self, fingerprint: u64) -> bool {
+        self.is_duplicate_fingerprint_at(fingerprint, Instant::now())
+    }
+
+    /// is_duplicate_fingerprint_at returns true if a message of the specified fingerprint was received within the window before the specified time,
+    /// which suppresses the equivalent messages differing only in the TTLs or the encoding such as the name compression.
+    pub fn is_duplicate_fingerprint_at(&mut self, fingerprint: u64, now: Instant) -> bool {
+        self.is_duplicate_key_at(!fingerprint, now)
+    }
+
+    /// set_name remembers the service name of the specified message, so the duplicates can be attributed without parsing.
+    pub fn set_name(&mut self, msg_bytes: &[u8], name: &str) {
+        if let Some(seen) = self.seen.get_mut(&hash(msg_bytes)) {
+            seen.name = Some(name.to_string());
+        }
+    }
+
+    /// clear forgets all received messages, so the next identical message is handled again.
+    pub fn clear(&mut self) {
+        self.seen.clear();
+    }
+
+    /// name returns the service name of the specified message if it was remembered.
+    pub fn name(&self, msg_bytes: &[u8]) -> Option<&str> {
+        self.seen.get(&hash(msg_bytes))?.name.as_deref()
+    }
+}
+
+impl Default for Deduplicator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn hash(msg_bytes: &[u8]) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    msg_bytes.hash(&mut hasher);
+    hasher.finish()
+}
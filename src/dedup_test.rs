@@ -0,0 +1,53 @@
+// Copyright (C) 2024 Satoshi Konno All rights reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//    http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+#[cfg(test)]
+mod tests {
+
+    use std::time::{Duration, Instant};
+
+    use crate::dedup::Deduplicator;
+    use crate::dns::{ARecord, Message};
+
+    #[test]
+    fn dedup_window() {
+        let now = Instant::now();
+        let mut dedup = Deduplicator::new();
+        assert_eq!(dedup.window(), Duration::from_secs(1));
+        assert!(!dedup.is_duplicate_at(&[1, 2, 3], now));
+        dedup.set_name(&[1, 2, 3], "test._http._tcp.local");
+        assert!(dedup.is_duplicate_at(&[1, 2, 3], now + Duration::from_millis(10)));
+        assert_eq!(dedup.name(&[1, 2, 3]), Some("test._http._tcp.local"));
+        assert!(!dedup.is_duplicate_at(&[1, 2, 4], now + Duration::from_millis(10)));
+        assert_eq!(dedup.name(&[1, 2, 4]), None);
+        assert!(dedup.is_duplicate_at(&[1, 2, 3], now + Duration::from_millis(999)));
+        assert!(!dedup.is_duplicate_at(&[1, 2, 3], now + Duration::from_secs(1)));
+    }
+
+    #[test]
+    fn dedup_fingerprint() {
+        let mut msg = Message::new();
+        msg.add_answer(ARecord::new("host.local", "192.168.1.10".parse().unwrap()).to_record());
+
+        let now = Instant::now();
+        let mut dedup = Deduplicator::new();
+        let fingerprint = msg.fingerprint();
+        assert!(!dedup.is_duplicate_fingerprint_at(fingerprint, now));
+        assert!(dedup.is_duplicate_fingerprint_at(fingerprint, now + Duration::from_millis(10)));
+        assert!(!dedup.is_duplicate_fingerprint_at(fingerprint, now + Duration::from_secs(1)));
+        // The fingerprints do not collide with the byte hashes.
+        let bytes = msg.to_bytes().unwrap();
+        assert!(!dedup.is_duplicate_at(&bytes, now + Duration::from_secs(1)));
+    }
+}
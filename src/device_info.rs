@@ -0,0 +1,86 @@
+// Copyright (C) 2024 Satoshi Konno All rights reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//    http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::HashMap;
+
+use crate::dns::{Record, TXTRecord, Type};
+use crate::error::{Error, Result};
+use crate::hostname::LOCAL_DOMAIN;
+use crate::service::split_service_name;
+use crate::txt;
+
+/// DEVICE_INFO_SERVICE is the pseudo service type whose TXT records describe the hardware of a host.
+/// It is not browsable, and the TXT record is attached to the host name rather than a service instance.
+pub const DEVICE_INFO_SERVICE: &str = "_device-info._tcp";
+
+/// MODEL_KEY is the key of the model identifier such as `MacBookPro18,3`.
+pub const MODEL_KEY: &str = "model";
+
+/// device_info_name returns the name of the device information TXT record of the specified host,
+/// such as `MyMac._device-info._tcp.local` for `MyMac.local`.
+pub fn device_info_name(host: &str) -> String {
+    let host = host.trim_end_matches('.');
+    let suffix = format!(".{}", LOCAL_DOMAIN);
+    let host = match host.len().checked_sub(suffix.len()) {
+        Some(n) if host[n..].eq_ignore_ascii_case(&suffix) => &host[..n],
+        _ => host,
+    };
+    format!("{}.{}.{}", host, DEVICE_INFO_SERVICE, LOCAL_DOMAIN)
+}
+
+/// DeviceInfo represents the device information published under the `_device-info._tcp` pseudo service.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DeviceInfo {
+    host: String,
+    attrs: HashMap<String, String>,
+}
+
+impl DeviceInfo {
+    /// from_record creates a new device information from the specified TXT record.
+    pub fn from_record(record: &Record) -> Result<DeviceInfo> {
+        let (host, service_type, domain) = split_service_name(record.name());
+        if record.typ() != Type::TXT || !service_type.eq_ignore_ascii_case(DEVICE_INFO_SERVICE) {
+            return Err(Error::from_string(&format!(
+                "Not a device information record: {} {}",
+                record.name(),
+                record.typ()
+            )));
+        }
+        let txt = TXTRecord::from_record(record).map_err(|e| Error::from_str(e.message()))?;
+        Ok(DeviceInfo {
+            host: format!("{}.{}", host, domain),
+            attrs: txt.attributes().clone(),
+        })
+    }
+
+    /// host returns the host name the device information belongs to.
+    pub fn host(&self) -> &str {
+        &self.host
+    }
+
+    /// model returns the model identifier of the device.
+    pub fn model(&self) -> Option<&str> {
+        self.attribute(MODEL_KEY)
+    }
+
+    /// attributes returns all attributes of the device information.
+    pub fn attributes(&self) -> &HashMap<String, String> {
+        &self.attrs
+    }
+
+    /// attribute returns the attribute of the specified key, which is compared case-insensitively.
+    pub fn attribute(&self, key: &str) -> Option<&str> {
+        txt::attribute(&self.attrs, key).map(|value| value.as_str())
+    }
+}
@@ -0,0 +1,94 @@
+// Copyright (C) 2024 Satoshi Konno All rights reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//    http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+#[cfg(test)]
+mod tests {
+
+    use cybergarage::net::{Observer, Packet};
+
+    use crate::device_info::device_info_name;
+    use crate::dns::{Message, Record, Type};
+    use crate::fixture_test::{record, with_ttl};
+    use crate::{DeviceInfo, Discoverer};
+
+    fn device_info_record(name: &str, strs: &[&str]) -> Record {
+        let mut data = Vec::new();
+        for s in strs {
+            data.push(s.len() as u8);
+            data.extend_from_slice(s.as_bytes());
+        }
+        with_ttl(record(name, Type::TXT, data), 4500)
+    }
+
+    #[test]
+    fn device_info_names() {
+        struct Test {
+            host: &'static str,
+            expected: &'static str,
+        }
+
+        let tests = vec![
+            Test {
+                host: "MyMac.local",
+                expected: "MyMac._device-info._tcp.local",
+            },
+            Test {
+                host: "MyMac.local.",
+                expected: "MyMac._device-info._tcp.local",
+            },
+            Test {
+                host: "MyMac",
+                expected: "MyMac._device-info._tcp.local",
+            },
+        ];
+        for test in tests {
+            assert_eq!(device_info_name(test.host), test.expected);
+        }
+    }
+
+    #[test]
+    fn device_info_decode() {
+        let record = device_info_record(
+            "MyMac._device-info._tcp.local",
+            &["model=MacBookPro18,3", "osxvers=21"],
+        );
+        let info = DeviceInfo::from_record(&record).unwrap();
+        assert_eq!(info.host(), "MyMac.local");
+        assert_eq!(info.model(), Some("MacBookPro18,3"));
+        assert_eq!(info.attribute("OSXVERS"), Some("21"));
+
+        let record = device_info_record("MyMac._http._tcp.local", &["model=x"]);
+        assert!(DeviceInfo::from_record(&record).is_err());
+
+        let discoverer = Discoverer::new();
+        let mut msg = Message::new();
+        msg.add_answer(device_info_record(
+            "MyMac._device-info._tcp.local",
+            &["model=MacBookPro18,3"],
+        ));
+        let pkt = Packet::from_bytes(&msg.to_bytes().unwrap());
+        discoverer.lock().unwrap().packet_received(&pkt);
+        let info = discoverer
+            .lock()
+            .unwrap()
+            .device_info("mymac.local")
+            .unwrap();
+        assert_eq!(info.model(), Some("MacBookPro18,3"));
+        assert!(discoverer
+            .lock()
+            .unwrap()
+            .device_info("other.local")
+            .is_none());
+    }
+}
@@ -12,51 +12,539 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+use std::sync::atomic::AtomicBool;
+use std::sync::mpsc;
 use std::sync::Arc;
 use std::sync::Mutex;
+use std::sync::Weak;
+#[cfg(feature = "capture")]
+use std::time::SystemTime;
+use std::time::{Duration, Instant};
 
 use cybergarage::net::{MulticastManager, Observer, Packet};
 
+use crate::cache::Cache;
+#[cfg(feature = "capture")]
+use crate::capture::PacketSink;
+use crate::coalesce::EventCoalescer;
+use crate::dedup::Deduplicator;
 use crate::default::{MULTICAST_V4_ADDR, MULTICAST_V6_ADDR, PORT};
+use crate::device_info::{device_info_name, DeviceInfo};
 use crate::dns::message::Message;
+use crate::dns::{PTRRecord, QuestionRecord, Record, Type};
+use crate::event::{EventSender, EventSenders, ServiceCallback, ServiceEvent};
+use crate::eviction::{Eviction, EvictionReason};
+use crate::family::AddressFamily;
+use crate::filter::Filter;
+use crate::hostname::LOCAL_DOMAIN;
+use crate::loopback::LoopbackFilter;
 use crate::message::QueryMessage;
+use crate::net::{interface_of, interfaces, is_on_link, Interface};
+use crate::normalize::NameComparison;
+use crate::outgoing::{OutgoingHook, OutgoingKind, OutgoingPacket};
+use crate::packing::{pack_query, DEFAULT_MAX_MESSAGE_SIZE};
 use crate::query::Query;
+use crate::query_history::QueryHistory;
+use crate::query_pacing::QueryPacing;
+use crate::rate_limit::{question_key, RateLimiter, StormDetector};
+use crate::received_message::ReceivedMessage;
 use crate::service::Service;
+use crate::service_builder::SERVICE_TYPE_ENUMERATION;
+use crate::shutdown::{wait_tick, ShutdownReport, TimerWorker};
+use crate::stats::Stats;
+use crate::summary::ServiceSummary;
+use crate::supervisor::{lock_recovered, Supervisor, TaskStatus};
+use crate::trust_policy::TrustPolicy;
+use crate::txt_schema::TxtSchemas;
+
+// Outbound transmits the packets instead of the multicast transport, such as the sockets of an async runtime.
+pub(crate) type Outbound = Box<dyn Fn(&[u8]) -> Result<(), std::io::Error> + Send>;
+
+/// TIMER_TASK is the name of the task which sends the scheduled queries and expires the cached records.
+pub const TIMER_TASK: &str = "discoverer-timer";
+
+const TIMER_MIN_TICK: Duration = Duration::from_millis(10);
+const TIMER_MAX_TICK: Duration = Duration::from_secs(1);
 
 /// Discoverer represents a discoverer.
 pub struct Discoverer {
+    self_ref: Weak<Mutex<Discoverer>>,
     services: Vec<Service>,
     transport_mgr: MulticastManager,
+    stats: Stats,
+    subscribers: EventSenders,
+    cache: Cache,
+    evictions: mpsc::Receiver<Eviction>,
+    rate_limiter: RateLimiter,
+    storm_detector: StormDetector,
+    dedup: Deduplicator,
+    filter: Option<Filter>,
+    interface_scoped: bool,
+    outgoing_hook: Option<OutgoingHook>,
+    txt_schemas: Option<TxtSchemas>,
+    interfaces: Vec<Interface>,
+    allowed_interfaces: Vec<String>,
+    on_link_check: bool,
+    coalescer: Option<EventCoalescer>,
+    query_suppression: bool,
+    deferred_queries: Vec<(Query, Instant)>,
+    low_power_queries: HashMap<String, Option<Instant>>,
+    max_message_size: usize,
+    timer: Option<TimerWorker>,
+    supervisor: Supervisor,
+    outbound: Option<Outbound>,
+    query_history: Option<QueryHistory>,
+    loopback_filter: Arc<Mutex<LoopbackFilter>>,
+    self_suppression: bool,
+    trust_policy: TrustPolicy,
+    #[cfg(feature = "capture")]
+    capture: Option<Box<dyn PacketSink>>,
 }
 
 impl Discoverer {
     /// new creates a new discoverer.
     pub fn new() -> Arc<Mutex<Discoverer>> {
-        let discoverer = Arc::new(Mutex::new(Discoverer {
-            transport_mgr: MulticastManager::new(),
-            services: Vec::new(),
-        }));
-        {
-            let mut discoverer_lock = discoverer.lock().unwrap();
-            discoverer_lock
-                .transport_mgr
-                .add_observer(discoverer.clone());
-        } // discoverer_lock is dropped here
-        discoverer
+        let mut cache = Cache::new();
+        let evictions = cache.subscribe_evictions();
+        Arc::new_cyclic(|self_ref| {
+            Mutex::new(Discoverer {
+                self_ref: self_ref.clone(),
+                transport_mgr: MulticastManager::new(),
+                services: Vec::new(),
+                stats: Stats::new(),
+                subscribers: EventSenders::new(),
+                cache,
+                evictions,
+                rate_limiter: RateLimiter::new(),
+                storm_detector: StormDetector::new(),
+                dedup: Deduplicator::new(),
+                filter: None,
+                interface_scoped: false,
+                outgoing_hook: None,
+                txt_schemas: None,
+                interfaces: Vec::new(),
+                allowed_interfaces: Vec::new(),
+                on_link_check: true,
+                coalescer: None,
+                query_suppression: true,
+                deferred_queries: Vec::new(),
+                low_power_queries: HashMap::new(),
+                max_message_size: DEFAULT_MAX_MESSAGE_SIZE,
+                timer: None,
+                supervisor: Supervisor::new(),
+                outbound: None,
+                query_history: None,
+                loopback_filter: Arc::new(Mutex::new(LoopbackFilter::new())),
+                self_suppression: true,
+                trust_policy: TrustPolicy::new(),
+                #[cfg(feature = "capture")]
+                capture: None,
+            })
+        })
     }
 
     ///search queries the discoverer.
     pub fn search(&mut self, query: &Query) -> Result<(), std::io::Error> {
+        if self.query_pacing(query) == QueryPacing::LowPower {
+            return self.search_all(std::slice::from_ref(query));
+        }
+        // RFC 6762: 6.1. Negative Responses
+        // Queries for a name whose records are known not to exist are suppressed.
+        if self.cache.is_known_absent(&query.to_string(), Type::PTR) {
+            return Ok(());
+        }
+        if self.query_suppression && self.answer_from_cache(query) {
+            return Ok(());
+        }
+        let name = query.to_string();
+        self.deferred_queries.retain(|(q, _)| q.to_string() != name);
         let q = QueryMessage::new(query);
-        match q.to_bytes() {
-            Ok(bytes) => {
-                let pkt = Packet::from_bytes(&bytes);
-                return self.transport_mgr.notify(&pkt);
+        self.send_query(q)
+    }
+
+    /// search_all queries the specified queries, and the questions of the low-power queries are batched into a single query.
+    /// Every query is searched even if another one fails, and the first error is returned.
+    pub fn search_all(&mut self, queries: &[Query]) -> Result<(), std::io::Error> {
+        let now = Instant::now();
+        let mut result = Ok(());
+        let mut batch = Message::new();
+        let mut batched = Vec::new();
+        for query in queries {
+            let pacing = self.query_pacing(query);
+            if !pacing.batches_questions() {
+                if let Err(e) = self.search(query) {
+                    log::warn!("query not sent ({}): {}", query, e);
+                    if result.is_ok() {
+                        result = Err(e);
+                    }
+                }
+                continue;
+            }
+            let name = query.to_string();
+            if batched.contains(&name) || self.cache.is_known_absent(&name, Type::PTR) {
+                continue;
+            }
+            let recently_sent = self
+                .low_power_queries
+                .get(&name)
+                .copied()
+                .flatten()
+                .is_some_and(|sent| now.saturating_duration_since(sent) < pacing.min_interval());
+            if recently_sent {
+                self.answer_from_cache(query);
+                continue;
+            }
+            if self.query_suppression && self.answer_from_cache(query) {
+                continue;
+            }
+            for question in QueryMessage::new(query).questions() {
+                batch.add_question(question.clone());
             }
-            Err(e) => {
-                return Err(std::io::Error::new(std::io::ErrorKind::Other, e.message()));
+            batched.push(name);
+        }
+        if batched.is_empty() {
+            return result;
+        }
+        if let Err(e) = self.send_query(batch) {
+            log::warn!("batched query not sent ({}): {}", batched.join(", "), e);
+            return result.and(Err(e));
+        }
+        for name in batched {
+            self.low_power_queries.insert(name, Some(now));
+        }
+        result
+    }
+
+    /// set_query_pacing sets the pacing of the specified query, which is the standard pacing by default.
+    pub fn set_query_pacing(&mut self, query: &Query, pacing: QueryPacing) {
+        let name = query.to_string();
+        match pacing {
+            QueryPacing::Standard => {
+                self.low_power_queries.remove(&name);
+            }
+            QueryPacing::LowPower => {
+                self.low_power_queries.entry(name.clone()).or_insert(None);
+                self.deferred_queries.retain(|(q, _)| q.to_string() != name);
+            }
+        }
+    }
+
+    /// query_pacing returns the pacing of the specified query.
+    pub fn query_pacing(&self, query: &Query) -> QueryPacing {
+        if self.low_power_queries.contains_key(&query.to_string()) {
+            QueryPacing::LowPower
+        } else {
+            QueryPacing::Standard
+        }
+    }
+
+    /// set_query_suppression sets whether a search satisfied by the fresh cached PTR records is answered from the cache,
+    /// and its query is deferred until the records are no longer fresh. It is enabled by default.
+    pub fn set_query_suppression(&mut self, enabled: bool) {
+        self.query_suppression = enabled;
+    }
+
+    /// is_query_suppression returns true if the searches satisfied by the cache are answered from the cache.
+    pub fn is_query_suppression(&self) -> bool {
+        self.query_suppression
+    }
+
+    /// deferred_queries returns the names of the queries deferred by the cache, and when they are sent.
+    pub fn deferred_queries(&self) -> Vec<(String, Instant)> {
+        self.deferred_queries
+            .iter()
+            .map(|(query, due)| (query.to_string(), *due))
+            .collect()
+    }
+
+    // answer_from_cache delivers the known services of the fresh cached PTR records of the specified query as added events,
+    // and defers the query until the first record is no longer fresh. It returns false if no fresh record is cached.
+    // RFC 6762: 5.2. Continuous Multicast DNS Querying
+    fn answer_from_cache(&mut self, query: &Query) -> bool {
+        // The PTR records of the service types deliver no service, so the query of the types is always sent.
+        if query.is_service_enumeration() {
+            return false;
+        }
+        let now = Instant::now();
+        let name = query.to_string();
+        let fresh = self.cache.fresh_records_at(&name, Type::PTR, now);
+        let due = match fresh.iter().map(|e| e.stale_at()).min() {
+            Some(due) => due,
+            None => return false,
+        };
+        let instances: Vec<String> = fresh
+            .iter()
+            .filter_map(|e| PTRRecord::from_record(e.record()).ok())
+            .map(|ptr| ptr.domain_name().to_string())
+            .collect();
+        let name_comparison = self.cache.name_comparison();
+        let services: Vec<Service> = self
+            .services
+            .iter()
+            .filter(|s| instances.iter().any(|i| name_comparison.eq(i, s.name())))
+            .cloned()
+            .collect();
+        for service in services {
+            self.publish(ServiceEvent::Added(service));
+        }
+        self.deferred_queries.retain(|(q, _)| q.to_string() != name);
+        self.stats.add_query_suppressed();
+        if !self.query_pacing(query).refreshes_cache() {
+            return true;
+        }
+        self.deferred_queries
+            .push((Query::with(query.service(), query.domain()), due));
+        self.spawn_timer();
+        true
+    }
+
+    // send_due_queries sends the deferred queries which are due at the specified time.
+    fn send_due_queries(&mut self, now: Instant) {
+        let (due, deferred): (Vec<_>, Vec<_>) = self
+            .deferred_queries
+            .drain(..)
+            .partition(|(_, due)| *due <= now);
+        self.deferred_queries = deferred;
+        for (query, _) in due {
+            if let Err(e) = self.send_query(QueryMessage::new(&query)) {
+                log::warn!("deferred query not sent ({}): {}", query, e);
+            }
+        }
+    }
+
+    /// resolve queries the SRV, TXT and address records of the specified service again.
+    /// The answers are delivered as service events like any other response.
+    pub fn resolve(&mut self, service: &Service) -> Result<(), std::io::Error> {
+        let mut msg = Message::new();
+        for typ in [Type::SRV, Type::TXT] {
+            if self.cache.is_known_absent(service.name(), typ) {
+                continue;
+            }
+            let mut question = QuestionRecord::new();
+            question.set_name(service.name());
+            question.set_typ(typ);
+            msg.add_question(question);
+        }
+        if !service.host().is_empty() {
+            for typ in [Type::A, Type::AAAA] {
+                if self.cache.is_known_absent(service.host(), typ) {
+                    continue;
+                }
+                let mut question = QuestionRecord::new();
+                question.set_name(service.host());
+                question.set_typ(typ);
+                msg.add_question(question);
+            }
+        }
+        if msg.questions().is_empty() {
+            return Ok(());
+        }
+        self.send_query(msg)
+    }
+
+    /// query_device_info queries the device information TXT record of the specified host.
+    /// The answer is cached, and returned by device_info.
+    pub fn query_device_info(&mut self, host: &str) -> Result<(), std::io::Error> {
+        let mut msg = Message::new();
+        let mut question = QuestionRecord::new();
+        question.set_name(&device_info_name(host));
+        question.set_typ(Type::TXT);
+        msg.add_question(question);
+        self.send_query(msg)
+    }
+
+    /// flush_cache removes all cached records and services, and delivers the removed services as removed events,
+    /// so the next responses build a fresh view such as after the network has changed.
+    pub fn flush_cache(&mut self) {
+        self.cache.flush();
+        self.dedup.clear();
+        self.deferred_queries.clear();
+        for sent in self.low_power_queries.values_mut() {
+            *sent = None;
+        }
+        let services: Vec<Service> = self.services.drain(..).collect();
+        self.stats.set_services_cached(0);
+        for service in services {
+            self.publish(ServiceEvent::Removed(service));
+        }
+    }
+
+    /// flush_type removes the cached records and services of the specified service type such as `_ipp._tcp`,
+    /// and delivers the removed services as removed events.
+    pub fn flush_type(&mut self, service_type: &str) {
+        let name_comparison = self.cache.name_comparison();
+        let mut domains = vec![LOCAL_DOMAIN.to_string()];
+        for service in &self.services {
+            if name_comparison.eq(service.service_type(), service_type)
+                && !domains
+                    .iter()
+                    .any(|d| name_comparison.eq(d, service.domain()))
+            {
+                domains.push(service.domain().to_string());
+            }
+        }
+        for domain in &domains {
+            let type_name = format!("{}.{}", service_type, domain);
+            self.cache.flush_domain(&type_name);
+            self.deferred_queries
+                .retain(|(q, _)| !name_comparison.eq(&q.to_string(), &type_name));
+        }
+        self.dedup.clear();
+        let (removed, kept): (Vec<Service>, Vec<Service>) = self
+            .services
+            .drain(..)
+            .partition(|s| name_comparison.eq(s.service_type(), service_type));
+        self.services = kept;
+        self.stats.set_services_cached(self.services.len());
+        for service in removed {
+            self.publish(ServiceEvent::Removed(service));
+        }
+    }
+
+    /// invalidate removes the cached records of the specified service instance such as `My Web._http._tcp.local`,
+    /// delivers it as a removed event, and queries its records again to reconfirm it.
+    /// The service is added again if its host still answers.
+    /// RFC 6762: 10.4. Cache Flush on Failure Indication
+    pub fn invalidate(&mut self, instance: &str) -> Result<(), std::io::Error> {
+        self.cache.flush_name(instance);
+        self.dedup.clear();
+        let name_comparison = self.cache.name_comparison();
+        let pos = match self
+            .services
+            .iter()
+            .position(|s| name_comparison.eq(s.name(), instance))
+        {
+            Some(pos) => pos,
+            None => return Ok(()),
+        };
+        let service = self.services.remove(pos);
+        self.stats.set_services_cached(self.services.len());
+        self.publish(ServiceEvent::Removed(service.clone()));
+        self.resolve(&service)
+    }
+
+    /// device_info returns the cached device information of the specified host.
+    pub fn device_info(&self, host: &str) -> Option<DeviceInfo> {
+        self.cache
+            .records(&device_info_name(host))
+            .iter()
+            .filter(|e| e.record().typ() == Type::TXT)
+            .find_map(|e| DeviceInfo::from_record(e.record()).ok())
+    }
+
+    // send_reconfirmations queries the conflicted records of the cache due at the specified time to reconfirm them with their owners.
+    // The queries have no known answers, so the owners answer even if they announced the records recently,
+    // and the duplicate packets are forgotten, so the answers are not suppressed as duplicates.
+    // RFC 6762: 10.4. Cache Flush on Failure Indication
+    fn send_reconfirmations(&mut self, now: Instant) {
+        let conflicts = self.cache.due_reconfirmations_at(now);
+        if conflicts.is_empty() {
+            return;
+        }
+        self.dedup.clear();
+        for conflict in conflicts {
+            let mut msg = Message::new();
+            let mut question = QuestionRecord::new();
+            question.set_name(conflict.name());
+            question.set_typ(conflict.typ());
+            msg.add_question(question);
+            if let Err(e) = self.transmit_query(msg) {
+                log::warn!("reconfirmation query not sent ({}): {}", conflict, e);
+            }
+        }
+        self.spawn_timer();
+    }
+
+    // send_query sends the specified query with the known answers in the cache.
+    fn send_query(&mut self, mut msg: Message) -> Result<(), std::io::Error> {
+        for answer in self.cache.known_answers(msg.questions()) {
+            msg.add_answer(answer);
+        }
+        self.transmit_query(msg)
+    }
+
+    // transmit_query sends the specified query unless the query is rate limited.
+    // The known answers which do not fit in a packet follow in the subsequent packets with the TC bit.
+    fn transmit_query(&mut self, msg: Message) -> Result<(), std::io::Error> {
+        let key = question_key(&msg);
+        if !self.rate_limiter.allow_query(&key) {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::WouldBlock,
+                format!("query rate limited ({})", key),
+            ));
+        }
+        let packets = pack_query(&msg, self.max_message_size)
+            .map_err(|e| std::io::Error::other(e.message()))?;
+        for packet in packets {
+            let bytes = packet
+                .to_bytes()
+                .map_err(|e| std::io::Error::other(e.message()))?;
+            let bytes = self.apply_outgoing_hook(OutgoingKind::Query, bytes)?;
+            match &self.outbound {
+                Some(outbound) => outbound(&bytes)?,
+                None => self.transport_mgr.notify(&Packet::from_bytes(&bytes))?,
             }
+            lock_recovered(&self.loopback_filter).add_sent(&bytes);
         }
+        self.stats.add_query_sent();
+        Ok(())
+    }
+
+    /// set_max_message_size sets the maximum size of a query packet, and the known answers which do not fit follow in the subsequent packets.
+    pub fn set_max_message_size(&mut self, size: usize) {
+        self.max_message_size = size;
+    }
+
+    /// max_message_size returns the maximum size of a query packet.
+    pub fn max_message_size(&self) -> usize {
+        self.max_message_size
+    }
+
+    // set_outbound transmits the packets by the specified function instead of the multicast transport.
+    #[cfg(feature = "tokio")]
+    pub(crate) fn set_outbound(&mut self, outbound: Outbound) {
+        self.outbound = Some(outbound);
+    }
+
+    // tick does the periodic work of the timer for a transport which drives the discoverer by itself.
+    pub(crate) fn tick(&mut self, now: Instant) {
+        self.deliver_due_events();
+        self.send_due_queries(now);
+        self.send_reconfirmations(now);
+        self.expire_services(now);
+    }
+
+    // apply_outgoing_hook passes the specified bytes to the outgoing hook, and returns the bytes to transmit unless vetoed.
+    fn apply_outgoing_hook(
+        &self,
+        kind: OutgoingKind,
+        bytes: Vec<u8>,
+    ) -> Result<Vec<u8>, std::io::Error> {
+        let hook = match &self.outgoing_hook {
+            Some(hook) => hook,
+            None => return Ok(bytes),
+        };
+        let mut pkt = OutgoingPacket::new(kind, bytes);
+        if !hook(&mut pkt) {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::PermissionDenied,
+                format!("{} vetoed by the outgoing hook", kind),
+            ));
+        }
+        Ok(pkt.into_bytes())
+    }
+
+    /// set_outgoing_hook sets the hook invoked with every packet about to be transmitted, which may rewrite or veto the packet.
+    pub fn set_outgoing_hook(&mut self, hook: OutgoingHook) {
+        self.outgoing_hook = Some(hook);
+    }
+
+    /// clear_outgoing_hook removes the outgoing hook.
+    pub fn clear_outgoing_hook(&mut self) {
+        self.outgoing_hook = None;
     }
 
     /// services returns the services of the discoverer.
@@ -64,40 +552,775 @@ impl Discoverer {
         &self.services
     }
 
+    /// summary returns the counts of the discovered services grouped by the service type and the interface.
+    pub fn summary(&self) -> ServiceSummary {
+        ServiceSummary::from_services(&self.services)
+    }
+
+    /// subscribe returns a receiver of the service events.
+    /// The events are sent without holding any callback under the discoverer lock,
+    /// so the receiver can be polled from the application's own event loop.
+    /// Every subscriber receives the Added events of the already discovered services first, followed by the live events.
+    pub fn subscribe(&mut self) -> mpsc::Receiver<ServiceEvent> {
+        let (tx, rx) = mpsc::channel();
+        self.add_subscriber(EventSender::Std(tx));
+        rx
+    }
+
+    // subscribe_live returns a receiver of the live service events only, without the replayed Added events of the discovered services,
+    // so a resolution waits for a fresh answer instead of the cached one.
+    pub(crate) fn subscribe_live(&mut self) -> mpsc::Receiver<ServiceEvent> {
+        let (tx, rx) = mpsc::channel();
+        self.subscribers.add(EventSender::Std(tx));
+        rx
+    }
+
+    /// on_service_added registers the callback called with each discovered service.
+    /// The callbacks are called under the discoverer lock, so they must not call the discoverer.
+    pub fn on_service_added(&mut self, callback: ServiceCallback) {
+        self.add_subscriber(EventSender::Added(callback));
+    }
+
+    /// on_service_updated registers the callback called with each known service announced again.
+    pub fn on_service_updated(&mut self, callback: ServiceCallback) {
+        self.add_subscriber(EventSender::Updated(callback));
+    }
+
+    /// on_service_removed registers the callback called with each service which is no longer available.
+    pub fn on_service_removed(&mut self, callback: ServiceCallback) {
+        self.add_subscriber(EventSender::Removed(callback));
+    }
+
+    /// subscribe_crossbeam returns a crossbeam receiver of the service events.
+    #[cfg(feature = "crossbeam")]
+    pub fn subscribe_crossbeam(&mut self) -> crossbeam_channel::Receiver<ServiceEvent> {
+        let (tx, rx) = crossbeam_channel::unbounded();
+        self.add_subscriber(EventSender::Crossbeam(tx));
+        rx
+    }
+
+    /// subscribe_tokio returns a tokio receiver of the service events, which can be awaited.
+    #[cfg(feature = "tokio")]
+    pub fn subscribe_tokio(&mut self) -> tokio::sync::mpsc::UnboundedReceiver<ServiceEvent> {
+        let (tx, rx) = tokio::sync::mpsc::unbounded_channel();
+        self.add_subscriber(EventSender::Tokio(tx));
+        rx
+    }
+
+    /// subscribe_flume returns a flume receiver of the service events.
+    #[cfg(feature = "flume")]
+    pub fn subscribe_flume(&mut self) -> flume::Receiver<ServiceEvent> {
+        let (tx, rx) = flume::unbounded();
+        self.add_subscriber(EventSender::Flume(tx));
+        rx
+    }
+
+    /// set_capture sets the sink which every received packet is written to, such as a PcapWriter.
+    #[cfg(feature = "capture")]
+    pub fn set_capture(&mut self, sink: Box<dyn PacketSink>) {
+        self.capture = Some(sink);
+    }
+
+    /// clear_capture stops writing the received packets.
+    #[cfg(feature = "capture")]
+    pub fn clear_capture(&mut self) {
+        self.capture = None;
+    }
+
+    // capture_packet writes the specified packet to the capture sink, and stops capturing when the sink fails.
+    #[cfg(feature = "capture")]
+    fn capture_packet(&mut self, pkt: &Packet) {
+        if let Some(sink) = &mut self.capture {
+            if let Err(e) = sink.write_packet(SystemTime::now(), pkt.from(), pkt.bytes()) {
+                log::warn!("packet capture stopped: {}", e);
+                self.capture = None;
+            }
+        }
+    }
+
+    // add_subscriber replays the Added events of the discovered services to the specified subscriber before adding it,
+    // so a late subscriber sees the same services as the earlier ones without merging a snapshot.
+    fn add_subscriber(&mut self, sender: EventSender) {
+        for service in &self.services {
+            if !sender.send(ServiceEvent::Added(service.clone())) {
+                return;
+            }
+        }
+        self.subscribers.add(sender);
+    }
+
+    fn add_service(&mut self, service: Service) {
+        if service.name().is_empty() {
+            return;
+        }
+        if let Some(filter) = &self.filter {
+            if !filter.matches(&service) {
+                return;
+            }
+        }
+        // The attributes are validated only when the service carries a TXT record.
+        if let Some(schemas) = &self.txt_schemas {
+            if !service.attributes().is_empty() {
+                let violations = schemas.validate(service.service_type(), service.attributes());
+                if let Some(violation) = violations.first() {
+                    log::debug!("invalid TXT attributes ({}): {}", service.name(), violation);
+                    return;
+                }
+            }
+        }
+        let interface_scoped = self.interface_scoped;
+        let name_comparison = self.cache.name_comparison();
+        let event = match self
+            .services
+            .iter_mut()
+            .find(|s| is_same_identity(s, &service, interface_scoped, name_comparison))
+        {
+            Some(known) => {
+                let mut service = service;
+                for family in known.families() {
+                    service.add_family(*family);
+                }
+                *known = service.clone();
+                ServiceEvent::Updated(service)
+            }
+            None => {
+                self.services.push(service.clone());
+                ServiceEvent::Added(service)
+            }
+        };
+        self.stats.set_services_cached(self.services.len());
+        self.publish(event);
+    }
+
+    /// set_event_coalescer sets the coalescer which merges the bursts of the events of the same instance.
+    pub fn set_event_coalescer(&mut self, coalescer: EventCoalescer) {
+        self.flush_events();
+        self.coalescer = Some(coalescer);
+    }
+
+    /// clear_event_coalescer delivers the pending events, and the events are delivered immediately again.
+    pub fn clear_event_coalescer(&mut self) {
+        self.flush_events();
+        self.coalescer = None;
+    }
+
+    /// flush_events delivers all events pending in the coalescer without waiting for their windows.
+    pub fn flush_events(&mut self) {
+        if let Some(coalescer) = &mut self.coalescer {
+            for event in coalescer.flush() {
+                self.subscribers.publish(&event);
+            }
+        }
+    }
+
+    // publish delivers the specified event to the subscribers through the coalescer if any.
+    fn publish(&mut self, event: ServiceEvent) {
+        let events = match &mut self.coalescer {
+            Some(coalescer) => coalescer.push(event),
+            None => vec![event],
+        };
+        for event in events {
+            self.subscribers.publish(&event);
+        }
+        self.spawn_timer();
+    }
+
+    // deliver_due_events delivers the pending events whose windows have ended.
+    fn deliver_due_events(&mut self) {
+        if let Some(coalescer) = &mut self.coalescer {
+            for event in coalescer.flush_due_at(Instant::now()) {
+                self.subscribers.publish(&event);
+            }
+        }
+    }
+
+    // has_timer_work returns true if any event is pending in the coalescer, any query is deferred, any conflict is being reconfirmed
+    // or any service may expire.
+    fn has_timer_work(&self) -> bool {
+        self.coalescer.as_ref().is_some_and(|c| !c.is_empty())
+            || !self.deferred_queries.is_empty()
+            || !self.cache.pending_conflicts().is_empty()
+            || !self.services.is_empty()
+    }
+
+    // expire_services removes the expired records from the cache, and the services which no unexpired record is left for
+    // or whose records withdrawn by the goodbye packets were not announced again within GOODBYE_GRACE.
+    fn expire_services(&mut self, now: Instant) {
+        self.cache.remove_expired_at(now);
+        let withdrawn: Vec<String> = self
+            .evictions
+            .try_iter()
+            .filter(|e| e.reason() == EvictionReason::Goodbye)
+            .filter_map(|e| withdrawn_name(e.record().record()))
+            .collect();
+        self.remove_services(&withdrawn);
+        let cache = &self.cache;
+        let (expired, alive): (Vec<Service>, Vec<Service>) = self
+            .services
+            .drain(..)
+            .partition(|s| cache.records_at(s.name(), now).is_empty());
+        self.services = alive;
+        if expired.is_empty() {
+            return;
+        }
+        self.stats.set_services_cached(self.services.len());
+        for service in expired {
+            self.publish(ServiceEvent::Removed(service));
+        }
+    }
+
+    // remove_services removes the services of the specified names, and delivers them as removed events.
+    fn remove_services(&mut self, names: &[String]) {
+        let name_comparison = self.cache.name_comparison();
+        let (removed, kept): (Vec<Service>, Vec<Service>) = self
+            .services
+            .drain(..)
+            .partition(|s| names.iter().any(|n| name_comparison.eq(n, s.name())));
+        self.services = kept;
+        if removed.is_empty() {
+            return;
+        }
+        self.stats.set_services_cached(self.services.len());
+        for service in removed {
+            self.publish(ServiceEvent::Removed(service));
+        }
+    }
+
+    // timer_tick returns how often the timer runs, which follows the coalescing window if any.
+    fn timer_tick(&self) -> Duration {
+        match &self.coalescer {
+            Some(coalescer) => (coalescer.window() / 4).clamp(TIMER_MIN_TICK, TIMER_MAX_TICK),
+            None => TIMER_MAX_TICK,
+        }
+    }
+
+    // spawn_timer starts the thread which delivers the pending events and sends the deferred queries when no packet arrives,
+    // which ends when the transport stops or no work is left.
+    fn spawn_timer(&mut self) {
+        if self.timer.is_some() || !self.transport_mgr.is_running() || !self.has_timer_work() {
+            return;
+        }
+        let self_ref = self.self_ref.clone();
+        let first_tick = self.timer_tick();
+        let cancelled = Arc::new(AtomicBool::new(false));
+        let timer_cancelled = cancelled.clone();
+        let worker = self.supervisor.spawn(TIMER_TASK, move || {
+            let mut tick = first_tick;
+            loop {
+                if !wait_tick(&timer_cancelled, tick) {
+                    return;
+                }
+                let discoverer = match self_ref.upgrade() {
+                    Some(discoverer) => discoverer,
+                    None => return,
+                };
+                let mut discoverer = lock_recovered(&discoverer);
+                discoverer.tick(Instant::now());
+                if !discoverer.transport_mgr.is_running() || !discoverer.has_timer_work() {
+                    discoverer.timer = None;
+                    return;
+                }
+                tick = discoverer.timer_tick();
+            }
+        });
+        self.timer = Some(TimerWorker::new(cancelled, worker));
+    }
+
+    /// tasks returns the statuses of the background tasks of the discoverer.
+    pub fn tasks(&self) -> Vec<TaskStatus> {
+        self.supervisor.tasks()
+    }
+
+    /// supervisor returns the supervisor of the background tasks.
+    #[cfg(feature = "tokio")]
+    pub(crate) fn supervisor(&self) -> &Supervisor {
+        &self.supervisor
+    }
+
+    /// set_query_history sets the history which every question received from the network is added to.
+    pub fn set_query_history(&mut self, history: QueryHistory) {
+        self.query_history = Some(history);
+    }
+
+    /// clear_query_history stops keeping the received questions, and drops the history.
+    pub fn clear_query_history(&mut self) {
+        self.query_history = None;
+    }
+
+    /// query_history returns the history of the received questions, if it is kept.
+    pub fn query_history(&self) -> Option<&QueryHistory> {
+        self.query_history.as_ref()
+    }
+
+    /// set_self_suppression sets whether our own transmissions looped back by the kernel are ignored,
+    /// which are recognized by the loopback filter. It is enabled by default.
+    pub fn set_self_suppression(&mut self, enabled: bool) {
+        self.self_suppression = enabled;
+    }
+
+    /// is_self_suppression returns true if our own transmissions looped back by the kernel are ignored.
+    pub fn is_self_suppression(&self) -> bool {
+        self.self_suppression
+    }
+
+    /// set_trust_policy sets the checks which the additional records of the received messages must pass before they are cached.
+    pub fn set_trust_policy(&mut self, policy: TrustPolicy) {
+        self.trust_policy = policy;
+    }
+
+    /// trust_policy returns the checks of the additional records of the received messages.
+    pub fn trust_policy(&self) -> &TrustPolicy {
+        &self.trust_policy
+    }
+
+    /// set_loopback_filter sets the filter which the transmitted queries are remembered in,
+    /// such as the filter of a responder so its announcements are not discovered as services.
+    pub fn set_loopback_filter(&mut self, filter: Arc<Mutex<LoopbackFilter>>) {
+        self.loopback_filter = filter;
+    }
+
+    /// loopback_filter returns the filter which the transmitted queries are remembered in, to be shared with a responder.
+    pub fn loopback_filter(&self) -> Arc<Mutex<LoopbackFilter>> {
+        self.loopback_filter.clone()
+    }
+
+    /// set_name_comparison sets how the names of the services and the records are compared.
+    pub fn set_name_comparison(&mut self, name_comparison: NameComparison) {
+        self.cache.set_name_comparison(name_comparison);
+    }
+
+    /// set_interface_scoped sets whether a service is identified by the pair of the instance name and the interface it was received on,
+    /// since the same service seen on several interfaces may have different addresses and reachability on each.
+    pub fn set_interface_scoped(&mut self, scoped: bool) {
+        self.interface_scoped = scoped;
+    }
+
+    /// is_interface_scoped returns true if a service is identified by the pair of the instance name and the interface.
+    pub fn is_interface_scoped(&self) -> bool {
+        self.interface_scoped
+    }
+
+    /// merged_services returns the services merging the ones of the same instance name received on the different interfaces.
+    pub fn merged_services(&self) -> Vec<Service> {
+        let name_comparison = self.cache.name_comparison();
+        let mut services: Vec<Service> = Vec::new();
+        for service in &self.services {
+            match services
+                .iter_mut()
+                .find(|s| name_comparison.eq(s.name(), service.name()))
+            {
+                Some(merged) => merged.merge(service),
+                None => services.push(service.clone()),
+            }
+        }
+        services
+    }
+
+    /// service_types returns the service types such as `_http._tcp.local` enumerated in the cache for the specified domain.
+    pub fn service_types(&self, domain: &str) -> Vec<String> {
+        self.cache.service_types(domain)
+    }
+
+    /// services_on_host returns the services whose SRV records in the cache target the specified host.
+    pub fn services_on_host(&self, host: &str) -> Vec<&Service> {
+        let names = self.cache.services_on_host(host);
+        self.services
+            .iter()
+            .filter(|s| {
+                names
+                    .iter()
+                    .any(|n| self.cache.name_comparison().eq(n, s.name()))
+            })
+            .collect()
+    }
+
+    /// services_on_interface returns the services received on the specified interface.
+    pub fn services_on_interface(&self, interface: &str) -> Vec<&Service> {
+        self.services
+            .iter()
+            .filter(|s| s.interface() == Some(interface))
+            .collect()
+    }
+
+    /// set_filter sets the filter of the services, and only the matching services are cached and generate events.
+    pub fn set_filter(&mut self, filter: Filter) {
+        self.filter = Some(filter);
+    }
+
+    /// clear_filter removes the filter of the services.
+    pub fn clear_filter(&mut self) {
+        self.filter = None;
+    }
+
+    /// set_txt_schemas sets the TXT schemas of the service types, and the services whose attributes violate them are ignored.
+    pub fn set_txt_schemas(&mut self, schemas: TxtSchemas) {
+        self.txt_schemas = Some(schemas);
+    }
+
+    /// clear_txt_schemas removes the TXT schemas, and the attributes of the services are not validated.
+    pub fn clear_txt_schemas(&mut self) {
+        self.txt_schemas = None;
+    }
+
+    /// filter returns the filter of the services.
+    pub fn filter(&self) -> Option<&Filter> {
+        self.filter.as_ref()
+    }
+
+    /// set_rate_limiter sets the limiter of the outgoing queries.
+    pub fn set_rate_limiter(&mut self, rate_limiter: RateLimiter) {
+        self.rate_limiter = rate_limiter;
+    }
+
+    /// set_storm_detector sets the detector of the inbound query storms.
+    pub fn set_storm_detector(&mut self, storm_detector: StormDetector) {
+        self.storm_detector = storm_detector;
+    }
+
+    /// storm_detector returns the detector of the inbound query storms.
+    pub fn storm_detector(&self) -> &StormDetector {
+        &self.storm_detector
+    }
+
+    /// cache returns the cache of the discoverer.
+    pub fn cache(&self) -> &Cache {
+        &self.cache
+    }
+
+    // add_duplicate_family records the family of a duplicate message without updating the service again.
+    fn add_duplicate_family(&mut self, pkt: &Packet, key: &[u8], interface: Option<&str>) {
+        let name = match self.dedup.name(key) {
+            Some(name) => name.to_string(),
+            None => return,
+        };
+        let family = AddressFamily::from_socketaddr(&pkt.from());
+        let interface_scoped = self.interface_scoped;
+        let name_comparison = self.cache.name_comparison();
+        if let Some(known) = self.services.iter_mut().find(|s| {
+            name_comparison.eq(s.name(), &name) && (!interface_scoped || s.interface() == interface)
+        }) {
+            known.add_family(family);
+        }
+    }
+
+    /// cache_mut returns the mutable cache of the discoverer, such as to configure the TTL bounds.
+    pub fn cache_mut(&mut self) -> &mut Cache {
+        &mut self.cache
+    }
+
+    /// stats returns the statistics of the discoverer.
+    pub fn stats(&self) -> &Stats {
+        &self.stats
+    }
+
+    /// reset_stats clears the statistics of the discoverer.
+    pub fn reset_stats(&mut self) {
+        self.stats.reset();
+    }
+
     /// start starts the discoverer.
     pub fn start(&mut self) -> Result<(), std::io::Error> {
         if self.transport_mgr.is_running() {
             return Ok(());
         }
+        if let Err(e) = self.refresh_interfaces() {
+            log::warn!("interfaces not enumerated: {}", e);
+        }
         let addrs = vec![MULTICAST_V6_ADDR, MULTICAST_V4_ADDR];
-        let ret = self.transport_mgr.start(&addrs, PORT);
-        if ret.is_err() {
-            return ret;
+        self.transport_mgr.start(&addrs, PORT)?;
+        // The observer can only be registered once the multicast servers exist.
+        if let Some(discoverer) = self.self_ref.upgrade() {
+            self.transport_mgr.add_observer(discoverer);
         }
+        self.spawn_timer();
+        Ok(())
+    }
+
+    /// is_running returns true if the packets are transmitted, by the multicast transport started or by another transport.
+    pub fn is_running(&self) -> bool {
+        self.outbound.is_some() || self.transport_mgr.is_running()
+    }
+
+    /// refresh_interfaces enumerates the local interfaces again, which attribute the received packets to the interfaces by the source addresses.
+    pub fn refresh_interfaces(&mut self) -> Result<(), std::io::Error> {
+        self.set_interfaces(interfaces()?);
         Ok(())
     }
 
+    /// set_interfaces sets the local interfaces instead of enumerating them, such as for a fixed configuration.
+    pub fn set_interfaces(&mut self, ifaces: Vec<Interface>) {
+        self.interfaces = ifaces;
+        let addrs: Vec<_> = self.interfaces.iter().flat_map(|i| i.ipaddrs()).collect();
+        lock_recovered(&self.loopback_filter).add_local_addrs(&addrs);
+    }
+
+    /// interfaces returns the local interfaces enumerated at the start.
+    pub fn interfaces(&self) -> &Vec<Interface> {
+        &self.interfaces
+    }
+
+    /// set_on_link_check sets whether the packets from the sources off the directly attached networks are dropped, which is enabled by default.
+    /// The check is skipped while the interfaces are not known, and may be disabled for the environments such as the routed or tunneled networks.
+    /// RFC 6762: 11. Source Address Check
+    pub fn set_on_link_check(&mut self, enabled: bool) {
+        self.on_link_check = enabled;
+    }
+
+    /// is_on_link_check returns true if the packets from the sources off the directly attached networks are dropped.
+    pub fn is_on_link_check(&self) -> bool {
+        self.on_link_check
+    }
+
+    /// set_allowed_interfaces restricts the interfaces whose packets are handled to the specified names,
+    /// and an empty list allows all interfaces. The packets which can not be attributed to any interface are always handled.
+    pub fn set_allowed_interfaces(&mut self, names: &[&str]) {
+        self.allowed_interfaces = names.iter().map(|name| name.to_string()).collect();
+    }
+
+    /// allowed_interfaces returns the names of the interfaces whose packets are handled.
+    pub fn allowed_interfaces(&self) -> &Vec<String> {
+        &self.allowed_interfaces
+    }
+
     /// stop stops the discoverer.
     pub fn stop(&mut self) -> Result<(), std::io::Error> {
         self.transport_mgr.stop()
     }
+
+    /// shutdown stops the discoverer and releases its resources, closing all event subscribers.
+    /// Background work is waited for at most the specified timeout.
+    pub fn shutdown(&mut self, timeout: Duration) -> Result<ShutdownReport, std::io::Error> {
+        let started = Instant::now();
+        let mut report = ShutdownReport::new();
+        if self.transport_mgr.is_running() {
+            self.transport_mgr.stop()?;
+            report.set_transport_stopped(true);
+        }
+        report.add_schedulers_cancelled(self.deferred_queries.len());
+        self.deferred_queries.clear();
+        if let Some(timer) = self.timer.take() {
+            report.add_schedulers_cancelled(1);
+            report.add_worker(timer.cancel(started + timeout));
+        }
+        self.flush_events();
+        report.add_subscribers_closed(self.subscribers.len());
+        self.subscribers.clear();
+        if !report.is_complete() {
+            log::warn!("shutdown exceeded the timeout ({:?})", timeout);
+        }
+        report.set_elapsed(started.elapsed());
+        Ok(report)
+    }
 }
 
 impl Observer for Discoverer {
     fn packet_received(&mut self, pkt: &Packet) {
-        let msg = Message::from_bytes(pkt.bytes());
-        match msg {
-            Ok(msg) => {
-                let service = Service::from_message(&msg);
-                self.services.push(service);
+        self.receive(pkt, None);
+    }
+}
+
+impl Discoverer {
+    /// packet_received_on handles the specified packet received on the specified interface,
+    /// for transports which know the receiving interface.
+    pub fn packet_received_on(&mut self, pkt: &Packet, interface: &str) {
+        self.receive(pkt, Some(interface));
+    }
+
+    /// unicast_received handles the specified packet received from a unicast DNS server such as an LLQ server,
+    /// which is off the local links by definition, so the loopback, on-link and interface filters are not applied.
+    /// The server reports the removals authoritatively, so the withdrawn services are removed without the goodbye grace.
+    pub(crate) fn unicast_received(&mut self, pkt: &Packet) {
+        self.start_receive(pkt);
+        self.handle_packet(pkt, None, true);
+        if let Ok(msg) = Message::from_bytes(pkt.bytes()) {
+            self.remove_services(&goodbye_names(&msg));
+        }
+    }
+
+    // start_receive updates the statistics and runs the due timers for the specified received packet.
+    fn start_receive(&mut self, pkt: &Packet) {
+        self.stats.add_packet_received();
+        self.stats.add_packet_size(pkt.bytes().len());
+        self.deliver_due_events();
+        self.send_due_queries(Instant::now());
+        self.expire_services(Instant::now());
+        #[cfg(feature = "capture")]
+        self.capture_packet(pkt);
+    }
+
+    fn receive(&mut self, pkt: &Packet, interface: Option<&str>) {
+        self.start_receive(pkt);
+        if self.self_suppression
+            && lock_recovered(&self.loopback_filter).is_own(pkt.bytes(), pkt.from())
+        {
+            log::trace!("looped back packet ignored ({})", pkt.from());
+            return;
+        }
+        // A source can not be verified while the interfaces are not known.
+        let on_link = self.interfaces.is_empty() || is_on_link(&self.interfaces, &pkt.from().ip());
+        if self.on_link_check && !on_link {
+            log::debug!("packet from off-link source ignored ({})", pkt.from());
+            self.stats.add_off_link_dropped();
+            return;
+        }
+        let interface = match interface {
+            Some(interface) => Some(interface.to_string()),
+            None => interface_of(&self.interfaces, &pkt.from().ip()).map(|i| i.name().to_string()),
+        };
+        if let Some(name) = &interface {
+            if !self.allowed_interfaces.is_empty() && !self.allowed_interfaces.contains(name) {
+                return;
+            }
+        }
+        self.handle_packet(pkt, interface.as_deref(), on_link);
+    }
+
+    // handle_packet parses the specified packet which passed the source filters, and merges it into the cache.
+    fn handle_packet(&mut self, pkt: &Packet, interface: Option<&str>, on_link: bool) {
+        // The identical messages received on the different interfaces are distinct when the services are interface scoped.
+        let mut key = match interface {
+            Some(interface) if self.interface_scoped => {
+                [pkt.bytes().as_slice(), interface.as_bytes()].join(&0)
+            }
+            _ => pkt.bytes().clone(),
+        };
+        // The identical questions of the different askers are distinct, so the query history keeps every asker.
+        // RFC 6762: 18.2. QR (Query/Response) Bit
+        let bytes = pkt.bytes();
+        let has_questions = bytes.len() > 5 && bytes[2] & 0x80 == 0 && (bytes[4] | bytes[5]) != 0;
+        if has_questions {
+            key.push(0);
+            key.extend_from_slice(pkt.from().to_string().as_bytes());
+        }
+        // The questions are parsed before the duplicates are suppressed, so the storm detector counts the repeated queries.
+        if !has_questions && self.dedup.is_duplicate(&key) {
+            self.stats.add_duplicate_suppressed();
+            self.add_duplicate_family(pkt, &key, interface);
+            return;
+        }
+        let parse_start = Instant::now();
+        let parsed = ReceivedMessage::from_packet(pkt, interface);
+        self.stats.add_parse_time(parse_start.elapsed());
+        match parsed {
+            Ok(mut received) => {
+                let msg = received.message();
+                if msg.is_query() {
+                    if let Some(history) = &mut self.query_history {
+                        history.add_query(msg, received.source());
+                    }
+                    if !self.storm_detector.observe(&question_key(msg)) {
+                        return;
+                    }
+                }
+                // The equivalent messages which differ only in the TTLs or the encoding are duplicates too.
+                let mut fingerprint = match interface {
+                    Some(interface) if self.interface_scoped => {
+                        scoped_fingerprint(received.message().fingerprint(), interface)
+                    }
+                    _ => received.message().fingerprint(),
+                };
+                if has_questions {
+                    fingerprint = scoped_fingerprint(fingerprint, received.source());
+                }
+                if self.dedup.is_duplicate_fingerprint(fingerprint) {
+                    self.stats.add_duplicate_suppressed();
+                    return;
+                }
+                let rejected = self.trust_policy.apply(received.message_mut(), on_link);
+                if 0 < rejected {
+                    log::debug!(
+                        "{} additional records from {} rejected",
+                        rejected,
+                        received.source()
+                    );
+                    self.stats.add_additionals_rejected(rejected);
+                }
+                let msg = received.message();
+                if msg.is_response() {
+                    self.stats.add_answers_per_response(msg.answers().len());
+                }
+                let conflicts = self.cache.add_records(
+                    received.records().map(|r| r.record()),
+                    received.provenance(),
+                );
+                let has_conflicts = !conflicts.is_empty();
+                for conflict in conflicts {
+                    log::warn!("conflict detected: {}", conflict);
+                    self.publish(ServiceEvent::ConflictDetected(conflict));
+                }
+                // RFC 6762: 9. Conflict Resolution
+                if has_conflicts {
+                    self.send_reconfirmations(Instant::now());
+                }
+                // RFC 6762: 10.1. Goodbye Packets
+                // The withdrawn services are removed when the cache evicts the goodbye records after GOODBYE_GRACE,
+                // so a service announced again within the grace is not removed.
+                let goodbyes = goodbye_names(msg);
+                let mut service = Service::from_message(msg);
+                let name_comparison = self.cache.name_comparison();
+                if goodbyes
+                    .iter()
+                    .any(|n| name_comparison.eq(n, service.name()))
+                {
+                    return;
+                }
+                service.set_provenance(received.provenance().clone());
+                self.dedup.set_name(&key, service.name());
+                self.add_service(service);
             }
             Err(_) => {
-                return;
+                self.stats.add_parse_failure();
             }
         }
     }
 }
 
+// scoped_fingerprint returns the fingerprint of a message distinct per the specified scope, such as the interface or the source.
+fn scoped_fingerprint<T: Hash>(fingerprint: u64, scope: T) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    (fingerprint, scope).hash(&mut hasher);
+    hasher.finish()
+}
+
+// goodbye_names returns the names of the service instances withdrawn by the PTR and SRV records with zero TTL of the specified message.
+// The service type enumeration PTR records withdraw the service types, not the service instances.
+fn goodbye_names(msg: &Message) -> Vec<String> {
+    msg.answers()
+        .iter()
+        .filter(|record| record.ttl() == 0)
+        .filter_map(withdrawn_name)
+        .collect()
+}
+
+// withdrawn_name returns the name of the service instance which the specified PTR or SRV record withdraws as a goodbye.
+fn withdrawn_name(record: &Record) -> Option<String> {
+    match record.typ() {
+        Type::PTR if is_service_enumeration_name(record.name()) => None,
+        Type::PTR => PTRRecord::from_record(record)
+            .ok()
+            .map(|ptr| ptr.domain_name().to_string()),
+        Type::SRV => Some(record.name().to_string()),
+        _ => None,
+    }
+}
+
+// is_service_enumeration_name returns true if the specified name is the service type enumeration name of a domain.
+fn is_service_enumeration_name(name: &str) -> bool {
+    name.to_ascii_lowercase()
+        .starts_with(&format!("{}.", SERVICE_TYPE_ENUMERATION))
+}
+
+// is_same_identity returns true if the specified services are the same one, which also requires the same interface when interface scoped.
+fn is_same_identity(
+    known: &Service,
+    service: &Service,
+    interface_scoped: bool,
+    name_comparison: NameComparison,
+) -> bool {
+    name_comparison.eq(known.name(), service.name())
+        && (!interface_scoped || known.interface() == service.interface())
+}
+
 impl Drop for Discoverer {
     fn drop(&mut self) {
         let _ = self.stop();
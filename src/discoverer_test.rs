@@ -0,0 +1,800 @@
+// Copyright (C) 2024 Satoshi Konno All rights reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//    http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+#[cfg(test)]
+mod tests {
+
+    use std::io::ErrorKind;
+    use std::sync::mpsc::TryRecvError;
+    use std::sync::{Arc, Mutex};
+    use std::time::{Duration, Instant};
+
+    use cybergarage::net::{Observer, Packet};
+
+    use crate::cache::{GOODBYE_GRACE, RECONFIRM_TIMEOUT};
+    use crate::dns::{Message, PTRRecord, Question, Type};
+    use crate::fixture_test::{a_record, ptr_record, srv_response_packet, with_ttl};
+    use crate::net::{Interface, InterfaceAddr};
+    use crate::rate_limit::StormDetector;
+    use crate::{
+        AddressFamily, Discoverer, Filter, OutgoingKind, Query, ReconfirmationOutcome,
+        ServiceEvent, TrustPolicy,
+    };
+
+    // announcement_packet returns an announcement of the PTR and SRV records of `test._http._tcp.local` on the specified port.
+    fn announcement_packet(port: u16) -> Packet {
+        let pkt = srv_response_packet("test._http._tcp.local", port);
+        let mut msg = Message::from_bytes(pkt.bytes()).unwrap();
+        let ptr = PTRRecord::new("_http._tcp.local", "test._http._tcp.local");
+        msg.add_answer(ptr.to_record().unwrap());
+        Packet::from_bytes(&msg.to_bytes().unwrap())
+    }
+
+    // goodbye_packet returns a goodbye of the PTR record of `test._http._tcp.local`.
+    fn goodbye_packet() -> Packet {
+        let ptr = ptr_record("_http._tcp.local", "test._http._tcp.local");
+        let mut msg = Message::new();
+        msg.add_answer(with_ttl(ptr, 0));
+        Packet::from_bytes(&msg.to_bytes().unwrap())
+    }
+
+    #[test]
+    fn discoverer_subscribe() {
+        let discoverer = Discoverer::new();
+        let events = discoverer.lock().unwrap().subscribe();
+
+        let pkt = srv_response_packet("test._http._tcp.local", 80);
+        discoverer.lock().unwrap().packet_received(&pkt);
+        let pkt = srv_response_packet("test._http._tcp.local", 8080);
+        discoverer.lock().unwrap().packet_received(&pkt);
+
+        match events.try_recv() {
+            Ok(ServiceEvent::Added(service)) => {
+                assert_eq!(service.name(), "test._http._tcp.local");
+                assert_eq!(service.port(), 80);
+            }
+            _ => panic!("expected an added event"),
+        }
+        assert!(matches!(events.try_recv(), Ok(ServiceEvent::Updated(_))));
+        assert!(events.try_recv().is_err());
+        assert_eq!(discoverer.lock().unwrap().services().len(), 1);
+    }
+
+    #[test]
+    fn discoverer_dedup_families() {
+        let discoverer = Discoverer::new();
+        let events = discoverer.lock().unwrap().subscribe();
+
+        let mut pkt = srv_response_packet("test._http._tcp.local", 80);
+        pkt.set_from("192.168.1.10:5353".parse().unwrap());
+        discoverer.lock().unwrap().packet_received(&pkt);
+        pkt.set_from("[fe80::10]:5353".parse().unwrap());
+        discoverer.lock().unwrap().packet_received(&pkt);
+
+        assert!(matches!(events.try_recv(), Ok(ServiceEvent::Added(_))));
+        assert!(events.try_recv().is_err());
+        let discoverer = discoverer.lock().unwrap();
+        let service = &discoverer.services()[0];
+        assert!(service.is_reachable_over(AddressFamily::IPv4));
+        assert!(service.is_reachable_over(AddressFamily::IPv6));
+        let provenance = service.provenance().unwrap();
+        assert_eq!(provenance.family(), AddressFamily::IPv4);
+        assert_eq!(discoverer.cache().records("test._http._tcp.local").len(), 1);
+        assert_eq!(discoverer.stats().packets_received(), 2);
+        assert_eq!(discoverer.stats().duplicates_suppressed(), 1);
+        assert_eq!(discoverer.stats().packet_size().count(), 2);
+        assert_eq!(discoverer.stats().parse_time().count(), 1);
+    }
+
+    #[test]
+    fn discoverer_filter() {
+        let discoverer = Discoverer::new();
+        let events = discoverer.lock().unwrap().subscribe();
+        discoverer
+            .lock()
+            .unwrap()
+            .set_filter(Filter::service_type("_http._tcp").port_range(1..1024));
+
+        let pkt = srv_response_packet("alt._http._tcp.local", 8080);
+        discoverer.lock().unwrap().packet_received(&pkt);
+        let pkt = srv_response_packet("test._ipp._tcp.local", 631);
+        discoverer.lock().unwrap().packet_received(&pkt);
+        assert!(events.try_recv().is_err());
+
+        let pkt = srv_response_packet("test._http._tcp.local", 80);
+        discoverer.lock().unwrap().packet_received(&pkt);
+        assert!(matches!(events.try_recv(), Ok(ServiceEvent::Added(_))));
+        assert_eq!(discoverer.lock().unwrap().services().len(), 1);
+    }
+
+    #[test]
+    fn discoverer_shutdown() {
+        let discoverer = Discoverer::new();
+        let events = discoverer.lock().unwrap().subscribe();
+        let report = discoverer
+            .lock()
+            .unwrap()
+            .shutdown(Duration::from_secs(1))
+            .unwrap();
+        assert_eq!(report.subscribers_closed(), 1);
+        assert!(!report.transport_stopped());
+        assert!(report.is_complete());
+        assert!(matches!(events.try_recv(), Err(TryRecvError::Disconnected)));
+    }
+
+    #[test]
+    fn discoverer_shutdown_deferred_queries() {
+        let discoverer = Discoverer::new();
+        let mut msg =
+            Message::from_bytes(srv_response_packet("test._http._tcp.local", 80).bytes()).unwrap();
+        msg.add_answer(
+            PTRRecord::new("_http._tcp.local", "test._http._tcp.local")
+                .to_record()
+                .unwrap(),
+        );
+        let pkt = Packet::from_bytes(&msg.to_bytes().unwrap());
+        discoverer.lock().unwrap().packet_received(&pkt);
+        let query = Query::with("_http._tcp", "local");
+        assert!(discoverer.lock().unwrap().search(&query).is_ok());
+        assert_eq!(discoverer.lock().unwrap().deferred_queries().len(), 1);
+
+        let report = discoverer
+            .lock()
+            .unwrap()
+            .shutdown(Duration::from_secs(1))
+            .unwrap();
+        assert_eq!(report.schedulers_cancelled(), 1);
+        assert!(report.is_complete());
+        assert!(discoverer.lock().unwrap().deferred_queries().is_empty());
+    }
+
+    #[test]
+    fn discoverer_interface_scoped() {
+        let discoverer = Discoverer::new();
+        let events = discoverer.lock().unwrap().subscribe();
+        discoverer.lock().unwrap().set_interface_scoped(true);
+
+        let mut pkt = srv_response_packet("test._http._tcp.local", 80);
+        pkt.set_from("192.168.1.10:5353".parse().unwrap());
+        discoverer.lock().unwrap().packet_received_on(&pkt, "en0");
+        pkt.set_from("[fe80::10]:5353".parse().unwrap());
+        discoverer.lock().unwrap().packet_received_on(&pkt, "en1");
+        discoverer.lock().unwrap().packet_received_on(&pkt, "en1");
+
+        assert!(matches!(events.try_recv(), Ok(ServiceEvent::Added(_))));
+        assert!(matches!(events.try_recv(), Ok(ServiceEvent::Added(_))));
+        assert!(events.try_recv().is_err());
+
+        let discoverer = discoverer.lock().unwrap();
+        assert_eq!(discoverer.services().len(), 2);
+        assert_eq!(discoverer.stats().duplicates_suppressed(), 1);
+        let on_en1 = discoverer.services_on_interface("en1");
+        assert_eq!(on_en1.len(), 1);
+        assert!(!on_en1[0].is_reachable_over(AddressFamily::IPv4));
+        assert!(on_en1[0].is_reachable_over(AddressFamily::IPv6));
+
+        let merged = discoverer.merged_services();
+        assert_eq!(merged.len(), 1);
+        assert!(merged[0].is_reachable_over(AddressFamily::IPv4));
+        assert!(merged[0].is_reachable_over(AddressFamily::IPv6));
+    }
+
+    #[test]
+    fn discoverer_outgoing_hook() {
+        let discoverer = Discoverer::new();
+        let sent = Arc::new(Mutex::new(Vec::new()));
+        let hook_sent = sent.clone();
+        discoverer
+            .lock()
+            .unwrap()
+            .set_outgoing_hook(Box::new(move |pkt| {
+                let msg = pkt.message().unwrap();
+                hook_sent
+                    .lock()
+                    .unwrap()
+                    .push((pkt.kind(), msg.questions()[0].to_string()));
+                false
+            }));
+
+        let query = Query::with("_http._tcp", "local");
+        let err = discoverer.lock().unwrap().search(&query).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::PermissionDenied);
+        assert_eq!(discoverer.lock().unwrap().stats().queries_sent(), 0);
+
+        let sent = sent.lock().unwrap();
+        assert_eq!(sent.len(), 1);
+        assert_eq!(sent[0].0, OutgoingKind::Query);
+        assert!(sent[0].1.starts_with("_http._tcp.local"));
+    }
+
+    #[test]
+    fn discoverer_services_on_host() {
+        let discoverer = Discoverer::new();
+        for (name, port) in [
+            ("web._http._tcp.local", 80),
+            ("printer._ipp._tcp.local", 631),
+        ] {
+            let pkt = srv_response_packet(name, port);
+            discoverer.lock().unwrap().packet_received(&pkt);
+        }
+
+        let discoverer = discoverer.lock().unwrap();
+        assert_eq!(
+            discoverer.cache().services_on_host("Host.local."),
+            vec!["printer._ipp._tcp.local", "web._http._tcp.local"]
+        );
+        assert_eq!(discoverer.services_on_host("host.local").len(), 2);
+        assert!(discoverer.services_on_host("other.local").is_empty());
+    }
+
+    #[test]
+    fn discoverer_query_suppression() {
+        let discoverer = Discoverer::new();
+        let events = discoverer.lock().unwrap().subscribe();
+
+        let ptr = with_ttl(
+            ptr_record("_http._tcp.local", "test._http._tcp.local"),
+            4500,
+        );
+        let mut msg =
+            Message::from_bytes(srv_response_packet("test._http._tcp.local", 80).bytes()).unwrap();
+        msg.add_answer(ptr);
+        let pkt = Packet::from_bytes(&msg.to_bytes().unwrap());
+        discoverer.lock().unwrap().packet_received(&pkt);
+        assert!(matches!(events.try_recv(), Ok(ServiceEvent::Added(_))));
+
+        let query = Query::with("_http._tcp", "local");
+        assert!(discoverer.lock().unwrap().search(&query).is_ok());
+        match events.try_recv() {
+            Ok(ServiceEvent::Added(service)) => assert_eq!(service.name(), "test._http._tcp.local"),
+            _ => panic!("expected an added event from the cache"),
+        }
+        let discoverer = discoverer.lock().unwrap();
+        assert_eq!(discoverer.stats().queries_suppressed(), 1);
+        assert_eq!(discoverer.stats().queries_sent(), 0);
+        let deferred = discoverer.deferred_queries();
+        assert_eq!(deferred.len(), 1);
+        assert_eq!(deferred[0].0, "_http._tcp.local");
+        assert!(Instant::now() + Duration::from_secs(2200) < deferred[0].1);
+
+        let fresh = discoverer.cache().fresh_records_at(
+            "_http._tcp.local",
+            Type::PTR,
+            Instant::now() + Duration::from_secs(2251),
+        );
+        assert!(fresh.is_empty());
+    }
+
+    #[test]
+    fn discoverer_flush() {
+        let discoverer = Discoverer::new();
+        let events = discoverer.lock().unwrap().subscribe();
+        for (name, port) in [
+            ("web._http._tcp.local", 80),
+            ("alt._http._tcp.local", 8080),
+            ("printer._ipp._tcp.local", 631),
+        ] {
+            let pkt = srv_response_packet(name, port);
+            discoverer.lock().unwrap().packet_received(&pkt);
+        }
+        while events.try_recv().is_ok() {}
+
+        discoverer.lock().unwrap().flush_type("_ipp._tcp");
+        match events.try_recv() {
+            Ok(ServiceEvent::Removed(service)) => {
+                assert_eq!(service.name(), "printer._ipp._tcp.local")
+            }
+            _ => panic!("expected a removed event"),
+        }
+        assert!(events.try_recv().is_err());
+        assert!(discoverer
+            .lock()
+            .unwrap()
+            .cache()
+            .records("printer._ipp._tcp.local")
+            .is_empty());
+        assert_eq!(discoverer.lock().unwrap().services().len(), 2);
+
+        let sent = Arc::new(Mutex::new(Vec::new()));
+        let hook_sent = sent.clone();
+        discoverer
+            .lock()
+            .unwrap()
+            .set_outgoing_hook(Box::new(move |pkt| {
+                let msg = pkt.message().unwrap();
+                let mut sent = hook_sent.lock().unwrap();
+                for question in msg.questions() {
+                    sent.push((question.name().to_string(), question.typ()));
+                }
+                true
+            }));
+        assert!(discoverer
+            .lock()
+            .unwrap()
+            .invalidate("Web._http._tcp.local")
+            .is_ok());
+        assert!(matches!(events.try_recv(), Ok(ServiceEvent::Removed(_))));
+        {
+            let sent = sent.lock().unwrap();
+            assert!(sent.contains(&("web._http._tcp.local".to_string(), Type::SRV)));
+            assert!(sent.contains(&("host.local".to_string(), Type::A)));
+        }
+        // The reconfirmed service is added again by the answer.
+        let pkt = srv_response_packet("web._http._tcp.local", 80);
+        discoverer.lock().unwrap().packet_received(&pkt);
+        assert!(matches!(events.try_recv(), Ok(ServiceEvent::Added(_))));
+
+        discoverer.lock().unwrap().flush_cache();
+        assert!(matches!(events.try_recv(), Ok(ServiceEvent::Removed(_))));
+        assert!(matches!(events.try_recv(), Ok(ServiceEvent::Removed(_))));
+        assert!(events.try_recv().is_err());
+        let discoverer = discoverer.lock().unwrap();
+        assert!(discoverer.services().is_empty());
+        assert!(discoverer.cache().is_empty());
+        assert_eq!(discoverer.stats().services_cached(), 0);
+    }
+
+    #[test]
+    fn discoverer_callbacks() {
+        let discoverer = Discoverer::new();
+        let calls = Arc::new(Mutex::new(Vec::new()));
+        {
+            let mut discoverer = discoverer.lock().unwrap();
+            let added = calls.clone();
+            discoverer.on_service_added(Box::new(move |service| {
+                added.lock().unwrap().push(format!("+{}", service.port()))
+            }));
+            let updated = calls.clone();
+            discoverer.on_service_updated(Box::new(move |service| {
+                updated.lock().unwrap().push(format!("={}", service.port()))
+            }));
+            let removed = calls.clone();
+            discoverer.on_service_removed(Box::new(move |service| {
+                removed.lock().unwrap().push(format!("-{}", service.port()))
+            }));
+        }
+
+        for port in [80, 8080] {
+            let pkt = srv_response_packet("test._http._tcp.local", port);
+            discoverer.lock().unwrap().packet_received(&pkt);
+        }
+        discoverer.lock().unwrap().flush_cache();
+        assert_eq!(*calls.lock().unwrap(), vec!["+80", "=8080", "-8080"]);
+    }
+
+    #[test]
+    fn discoverer_goodbye() {
+        let discoverer = Discoverer::new();
+        let events = discoverer.lock().unwrap().subscribe();
+
+        let pkt = announcement_packet(80);
+        discoverer.lock().unwrap().packet_received(&pkt);
+        assert!(matches!(events.try_recv(), Ok(ServiceEvent::Added(_))));
+
+        // The withdrawn service is removed when the cache evicts the goodbye record after the grace.
+        let pkt = goodbye_packet();
+        discoverer.lock().unwrap().packet_received(&pkt);
+        assert!(events.try_recv().is_err());
+        assert_eq!(discoverer.lock().unwrap().services().len(), 1);
+        discoverer
+            .lock()
+            .unwrap()
+            .tick(Instant::now() + GOODBYE_GRACE);
+        match events.try_recv() {
+            Ok(ServiceEvent::Removed(service)) => {
+                assert_eq!(service.name(), "test._http._tcp.local")
+            }
+            _ => panic!("expected a removed event"),
+        }
+        assert!(discoverer.lock().unwrap().services().is_empty());
+
+        // A zero TTL bound expires the records as soon as they are cached.
+        discoverer.lock().unwrap().cache_mut().set_max_ttl(Some(0));
+        let pkt = srv_response_packet("other._http._tcp.local", 80);
+        discoverer.lock().unwrap().packet_received(&pkt);
+        assert!(matches!(events.try_recv(), Ok(ServiceEvent::Added(_))));
+        let pkt = srv_response_packet("test._ipp._tcp.local", 631);
+        discoverer.lock().unwrap().packet_received(&pkt);
+        match events.try_recv() {
+            Ok(ServiceEvent::Removed(service)) => {
+                assert_eq!(service.name(), "other._http._tcp.local")
+            }
+            _ => panic!("expected a removed event of the expired service"),
+        }
+    }
+
+    #[test]
+    fn discoverer_goodbye_reannounced() {
+        let discoverer = Discoverer::new();
+        let events = discoverer.lock().unwrap().subscribe();
+
+        let pkt = announcement_packet(80);
+        discoverer.lock().unwrap().packet_received(&pkt);
+        assert!(matches!(events.try_recv(), Ok(ServiceEvent::Added(_))));
+
+        // The service announced again within the grace after rebooting is not removed.
+        let pkt = goodbye_packet();
+        discoverer.lock().unwrap().packet_received(&pkt);
+        let pkt = announcement_packet(8080);
+        discoverer.lock().unwrap().packet_received(&pkt);
+        match events.try_recv() {
+            Ok(ServiceEvent::Updated(service)) => assert_eq!(service.port(), 8080),
+            _ => panic!("expected an updated event"),
+        }
+        discoverer
+            .lock()
+            .unwrap()
+            .tick(Instant::now() + GOODBYE_GRACE);
+        assert!(events.try_recv().is_err());
+        assert_eq!(discoverer.lock().unwrap().services().len(), 1);
+    }
+
+    #[test]
+    fn discoverer_trust_policy() {
+        let discoverer = Discoverer::new();
+        let response_packet = |port: u16| {
+            let pkt = srv_response_packet("test._http._tcp.local", port);
+            let mut msg = Message::from_bytes(pkt.bytes()).unwrap();
+            msg.add_additional(a_record("host.local", [192, 168, 1, 10]));
+            msg.add_additional(a_record("victim.local", [192, 168, 1, 10]));
+            Packet::from_bytes(&msg.to_bytes().unwrap())
+        };
+
+        discoverer
+            .lock()
+            .unwrap()
+            .packet_received(&response_packet(80));
+        {
+            let discoverer = discoverer.lock().unwrap();
+            assert_eq!(discoverer.stats().additionals_rejected(), 1);
+            assert_eq!(discoverer.cache().records("host.local").len(), 1);
+            assert!(discoverer.cache().records("victim.local").is_empty());
+        }
+
+        discoverer
+            .lock()
+            .unwrap()
+            .set_trust_policy(TrustPolicy::permissive());
+        discoverer
+            .lock()
+            .unwrap()
+            .packet_received(&response_packet(8080));
+        let discoverer = discoverer.lock().unwrap();
+        assert_eq!(discoverer.stats().additionals_rejected(), 1);
+        assert_eq!(discoverer.cache().records("victim.local").len(), 1);
+    }
+
+    #[test]
+    fn discoverer_on_link_check() {
+        let discoverer = Discoverer::new();
+        let events = discoverer.lock().unwrap().subscribe();
+        let addr = InterfaceAddr::new("192.168.1.1".parse().unwrap(), 24);
+        discoverer
+            .lock()
+            .unwrap()
+            .set_interfaces(vec![Interface::new("eth0", 2, &[addr])]);
+        assert!(discoverer.lock().unwrap().is_on_link_check());
+
+        let mut pkt = srv_response_packet("test._http._tcp.local", 80);
+        pkt.set_from("10.0.0.1:5353".parse().unwrap());
+        discoverer.lock().unwrap().packet_received(&pkt);
+        assert!(events.try_recv().is_err());
+        assert_eq!(discoverer.lock().unwrap().stats().off_link_dropped(), 1);
+
+        pkt.set_from("192.168.1.10:5353".parse().unwrap());
+        discoverer.lock().unwrap().packet_received(&pkt);
+        match events.try_recv() {
+            Ok(ServiceEvent::Added(service)) => {
+                assert_eq!(service.provenance().unwrap().interface(), Some("eth0"))
+            }
+            _ => panic!("expected an added event"),
+        }
+
+        discoverer.lock().unwrap().set_on_link_check(false);
+        let mut pkt = srv_response_packet("other._http._tcp.local", 80);
+        pkt.set_from("10.0.0.1:5353".parse().unwrap());
+        discoverer.lock().unwrap().packet_received(&pkt);
+        assert!(matches!(events.try_recv(), Ok(ServiceEvent::Added(_))));
+        assert_eq!(discoverer.lock().unwrap().stats().off_link_dropped(), 1);
+    }
+
+    #[test]
+    fn discoverer_unicast_received() {
+        let discoverer = Discoverer::new();
+        let events = discoverer.lock().unwrap().subscribe();
+        {
+            let mut discoverer = discoverer.lock().unwrap();
+            let addr = InterfaceAddr::new("192.168.1.1".parse().unwrap(), 24);
+            discoverer.set_interfaces(vec![Interface::new("eth0", 2, &[addr])]);
+            discoverer.set_allowed_interfaces(&["eth0"]);
+        }
+
+        // The answers of an off-link unicast server such as an LLQ server are not filtered.
+        let mut pkt = srv_response_packet("test._http._tcp.example.com", 80);
+        pkt.set_from("203.0.113.1:5352".parse().unwrap());
+        discoverer.lock().unwrap().packet_received(&pkt);
+        assert!(events.try_recv().is_err());
+        discoverer.lock().unwrap().unicast_received(&pkt);
+        assert!(matches!(events.try_recv(), Ok(ServiceEvent::Added(_))));
+        assert_eq!(discoverer.lock().unwrap().stats().off_link_dropped(), 1);
+    }
+
+    #[test]
+    fn discoverer_fingerprint_dedup() {
+        let discoverer = Discoverer::new();
+        let events = discoverer.lock().unwrap().subscribe();
+
+        let pkt = srv_response_packet("test._http._tcp.local", 80);
+        discoverer.lock().unwrap().packet_received(&pkt);
+        assert!(matches!(events.try_recv(), Ok(ServiceEvent::Added(_))));
+
+        // The same records with a decayed TTL carry nothing new.
+        let parsed = Message::from_bytes(pkt.bytes()).unwrap();
+        let mut srv = parsed.answers()[0].clone();
+        srv.set_ttl(119);
+        let mut msg = Message::new();
+        msg.add_answer(srv);
+        let pkt = Packet::from_bytes(&msg.to_bytes().unwrap());
+        discoverer.lock().unwrap().packet_received(&pkt);
+        assert!(events.try_recv().is_err());
+        assert_eq!(
+            discoverer.lock().unwrap().stats().duplicates_suppressed(),
+            1
+        );
+    }
+
+    #[test]
+    fn discoverer_late_subscriber() {
+        let discoverer = Discoverer::new();
+        let pkt = srv_response_packet("test._http._tcp.local", 80);
+        discoverer.lock().unwrap().packet_received(&pkt);
+
+        let events = discoverer.lock().unwrap().subscribe();
+        match events.try_recv() {
+            Ok(ServiceEvent::Added(service)) => {
+                assert_eq!(service.name(), "test._http._tcp.local")
+            }
+            _ => panic!("expected a replayed added event"),
+        }
+        assert!(events.try_recv().is_err());
+
+        let added = Arc::new(Mutex::new(Vec::new()));
+        let names = added.clone();
+        discoverer
+            .lock()
+            .unwrap()
+            .on_service_added(Box::new(move |service| {
+                names.lock().unwrap().push(service.name().to_string())
+            }));
+        assert_eq!(*added.lock().unwrap(), vec!["test._http._tcp.local"]);
+
+        let pkt = srv_response_packet("other._http._tcp.local", 80);
+        discoverer.lock().unwrap().packet_received(&pkt);
+        assert!(matches!(events.try_recv(), Ok(ServiceEvent::Added(_))));
+        assert_eq!(added.lock().unwrap().len(), 2);
+    }
+
+    #[test]
+    fn discoverer_conflict_reconfirmation() {
+        let unique_srv_packet = |port: u16, from: &str| {
+            let mut msg =
+                Message::from_bytes(srv_response_packet("web._http._tcp.local", port).bytes())
+                    .unwrap();
+            let mut srv = msg.answers()[0].clone();
+            srv.set_cache_flush(true);
+            msg = Message::new();
+            msg.add_answer(srv);
+            let mut pkt = Packet::from_bytes(&msg.to_bytes().unwrap());
+            pkt.set_from(from.parse().unwrap());
+            pkt
+        };
+
+        let discoverer = Discoverer::new();
+        let events = discoverer.lock().unwrap().subscribe();
+        let reconfirmations = discoverer
+            .lock()
+            .unwrap()
+            .cache_mut()
+            .subscribe_reconfirmations();
+        let sent = Arc::new(Mutex::new(Vec::new()));
+        let hook_sent = sent.clone();
+        discoverer
+            .lock()
+            .unwrap()
+            .set_outgoing_hook(Box::new(move |pkt| {
+                let msg = pkt.message().unwrap();
+                let question = &msg.questions()[0];
+                hook_sent.lock().unwrap().push((
+                    question.name().to_string(),
+                    question.typ(),
+                    msg.answers().len(),
+                ));
+                false
+            }));
+
+        let pkt = unique_srv_packet(80, "192.168.1.10:5353");
+        discoverer.lock().unwrap().packet_received(&pkt);
+        assert!(matches!(events.try_recv(), Ok(ServiceEvent::Added(_))));
+        let pkt = unique_srv_packet(8080, "192.168.1.20:5353");
+        discoverer.lock().unwrap().packet_received(&pkt);
+        let mut conflicted = false;
+        while let Ok(event) = events.try_recv() {
+            conflicted |= matches!(event, ServiceEvent::ConflictDetected(_));
+        }
+        assert!(conflicted);
+
+        // The reconfirmation query has no known answer of the conflicted record.
+        assert_eq!(
+            *sent.lock().unwrap(),
+            vec![("web._http._tcp.local".to_string(), Type::SRV, 0)]
+        );
+        let cached = discoverer
+            .lock()
+            .unwrap()
+            .cache()
+            .records("web._http._tcp.local")
+            .len();
+        assert_eq!(cached, 1);
+
+        // The owner does not answer, so the conflicting record replaces the cached one.
+        discoverer
+            .lock()
+            .unwrap()
+            .tick(Instant::now() + RECONFIRM_TIMEOUT);
+        let reconfirmation = reconfirmations.try_recv().unwrap();
+        assert_eq!(reconfirmation.outcome(), ReconfirmationOutcome::Replaced);
+        assert_eq!(
+            reconfirmation.conflict().conflicting().source(),
+            "192.168.1.20:5353".parse().unwrap()
+        );
+        assert!(discoverer
+            .lock()
+            .unwrap()
+            .cache()
+            .pending_conflicts()
+            .is_empty());
+    }
+
+    #[test]
+    fn discoverer_service_enumeration() {
+        let query = Query::service_enumeration();
+        assert_eq!(query.to_string(), "_services._dns-sd._udp.local");
+        assert!(query.is_service_enumeration());
+        assert!(!Query::with("_http._tcp", "local").is_service_enumeration());
+
+        let discoverer = Discoverer::new();
+        let events = discoverer.lock().unwrap().subscribe();
+        let mut msg =
+            Message::from_bytes(srv_response_packet("web._http._tcp.local", 80).bytes()).unwrap();
+        msg.add_answer(with_ttl(
+            ptr_record("_http._tcp.local", "web._http._tcp.local"),
+            4500,
+        ));
+        for typ in ["_http._tcp.local", "_ipp._tcp.local"] {
+            msg.add_answer(with_ttl(
+                ptr_record("_services._dns-sd._udp.local", typ),
+                4500,
+            ));
+        }
+        let pkt = Packet::from_bytes(&msg.to_bytes().unwrap());
+        discoverer.lock().unwrap().packet_received(&pkt);
+        assert!(matches!(events.try_recv(), Ok(ServiceEvent::Added(_))));
+        assert!(events.try_recv().is_err());
+        assert_eq!(
+            discoverer.lock().unwrap().service_types("local"),
+            vec!["_http._tcp.local", "_ipp._tcp.local"]
+        );
+
+        // The enumerated types deliver no service, so the query of the types is not answered from the cache.
+        assert!(discoverer.lock().unwrap().search(&query).is_ok());
+        {
+            let discoverer = discoverer.lock().unwrap();
+            assert_eq!(discoverer.stats().queries_suppressed(), 0);
+            assert_eq!(discoverer.stats().queries_sent(), 1);
+            assert!(discoverer.deferred_queries().is_empty());
+        }
+
+        // The goodbye of a type withdraws the type, not the services of the type.
+        let mut msg = Message::new();
+        msg.add_answer(with_ttl(
+            ptr_record("_services._dns-sd._udp.local", "_http._tcp.local"),
+            0,
+        ));
+        let pkt = Packet::from_bytes(&msg.to_bytes().unwrap());
+        discoverer.lock().unwrap().packet_received(&pkt);
+        assert!(events.try_recv().is_err());
+        let discoverer = discoverer.lock().unwrap();
+        assert_eq!(discoverer.service_types("local."), vec!["_ipp._tcp.local"]);
+        assert_eq!(discoverer.services().len(), 1);
+    }
+
+    #[test]
+    fn discoverer_known_answer_packets() {
+        let discoverer = Discoverer::new();
+        let mut msg = Message::new();
+        for n in 0..40 {
+            let instance = format!("instance-{}._http._tcp.local", n);
+            msg.add_answer(with_ttl(ptr_record("_http._tcp.local", &instance), 4500));
+        }
+        let pkt = Packet::from_bytes(&msg.to_bytes().unwrap());
+        discoverer.lock().unwrap().packet_received(&pkt);
+
+        let sent = Arc::new(Mutex::new(Vec::new()));
+        let hook_sent = sent.clone();
+        {
+            let mut discoverer = discoverer.lock().unwrap();
+            discoverer.set_query_suppression(false);
+            discoverer.set_max_message_size(512);
+            assert_eq!(discoverer.max_message_size(), 512);
+            discoverer.set_outgoing_hook(Box::new(move |pkt| {
+                let msg = pkt.message().unwrap();
+                hook_sent
+                    .lock()
+                    .unwrap()
+                    .push((msg.qd_count(), msg.an_count(), msg.tc()));
+                true
+            }));
+        }
+        assert!(discoverer
+            .lock()
+            .unwrap()
+            .search(&Query::with("_http._tcp", "local"))
+            .is_ok());
+
+        // The known answers follow in the packets without questions, and the TC bit is set on all but the last.
+        let sent = sent.lock().unwrap();
+        assert!(2 <= sent.len());
+        assert_eq!(sent[0].0, 1);
+        assert!(sent[1..].iter().all(|(qd, _, _)| *qd == 0));
+        assert_eq!(sent.iter().map(|(_, an, _)| *an).sum::<u16>(), 40);
+        let last = sent.len() - 1;
+        for (n, (_, _, tc)) in sent.iter().enumerate() {
+            assert_eq!(*tc, n < last);
+        }
+        assert_eq!(discoverer.lock().unwrap().stats().queries_sent(), 1);
+    }
+
+    #[test]
+    fn discoverer_query_storm() {
+        let discoverer = Discoverer::new();
+        discoverer
+            .lock()
+            .unwrap()
+            .set_storm_detector(StormDetector::with_threshold(
+                3,
+                Duration::from_secs(1),
+                Duration::from_secs(10),
+            ));
+
+        // The identical queries are counted before the duplicates are suppressed.
+        let mut msg = Message::new();
+        msg.add_question(Question::new("_http._tcp.local", Type::PTR));
+        let mut pkt = Packet::from_bytes(&msg.to_bytes().unwrap());
+        for n in 0..4 {
+            let key = "_http._tcp.local/PTR";
+            assert!(!discoverer
+                .lock()
+                .unwrap()
+                .storm_detector()
+                .is_suppressed(key));
+            pkt.set_from(format!("192.168.1.{}:5353", 10 + n % 2).parse().unwrap());
+            discoverer.lock().unwrap().packet_received(&pkt);
+        }
+        let discoverer = discoverer.lock().unwrap();
+        assert!(discoverer
+            .storm_detector()
+            .is_suppressed("_http._tcp.local/PTR"));
+        assert_eq!(discoverer.stats().duplicates_suppressed(), 1);
+    }
+}
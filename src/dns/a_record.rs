@@ -12,21 +12,36 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use crate::dns::error::{Error, Result};
+use crate::dns::class::Class;
+use crate::dns::error::{Error, ErrorKind, Result};
+use crate::dns::presentation;
 use crate::dns::record::Record;
 use crate::dns::resource_record::ResourceRecord;
+use crate::dns::ttl::DEFAULT_HOST_RECORD_TTL;
 use crate::dns::typ::Type;
+use crate::dns::writer::Writer;
 use std::fmt;
 use std::net::{IpAddr, Ipv4Addr};
+use std::str::FromStr;
 
 /// ARecord represents an A record.
 pub struct ARecord {
     name: String,
 
     ipaddr: IpAddr,
+    ttl: u32,
 }
 
 impl ARecord {
+    /// new creates a new A record of the specified owner name and address with the default TTL of the host records.
+    pub fn new(name: &str, addr: Ipv4Addr) -> ARecord {
+        ARecord {
+            name: name.to_string(),
+            ipaddr: IpAddr::V4(addr),
+            ttl: DEFAULT_HOST_RECORD_TTL,
+        }
+    }
+
     // from_record creates a new A record from the specified record.
     pub fn from_record(record: &Record) -> Result<ARecord> {
         let data = record.data();
@@ -34,11 +49,16 @@ impl ARecord {
             let arr: [u8; 4] = data[0..4].try_into().unwrap();
             IpAddr::V4(Ipv4Addr::from(arr))
         } else {
-            return Err(Error::from_bytes(data, 0));
+            return Err(Error::from_bytes_with_kind(
+                ErrorKind::InvalidRdata,
+                data,
+                0,
+            ));
         };
         let a = ARecord {
             name: record.name().to_string(),
             ipaddr: addr,
+            ttl: record.ttl(),
         };
         Ok(a)
     }
@@ -47,6 +67,57 @@ impl ARecord {
     pub fn ipaddr(&self) -> &IpAddr {
         &self.ipaddr
     }
+
+    /// ttl returns the TTL of the A record.
+    pub fn ttl(&self) -> u32 {
+        self.ttl
+    }
+
+    /// set_name sets the owner name of the A record.
+    pub fn set_name(&mut self, name: &str) {
+        self.name = name.to_string();
+    }
+
+    /// set_ttl sets the TTL of the A record.
+    pub fn set_ttl(&mut self, ttl: u32) {
+        self.ttl = ttl;
+    }
+
+    /// to_record returns the record of the A record.
+    pub fn to_record(&self) -> Record {
+        let mut record = Record::new();
+        record.set_name(&self.name);
+        record.set_typ(Type::A);
+        record.set_class(Class::IN);
+        record.set_ttl(self.ttl);
+        if let IpAddr::V4(v4) = self.ipaddr {
+            record.set_data(v4.octets().to_vec());
+        }
+        record
+    }
+
+    /// to_bytes returns the wire format of the A record with the owner name, type, class, TTL and address.
+    pub fn to_bytes(&self) -> Result<Vec<u8>> {
+        let mut w = Writer::new();
+        w.write_response_record(&self.to_record())?;
+        Ok(w.to_bytes())
+    }
+}
+
+impl FromStr for ARecord {
+    type Err = Error;
+
+    /// from_str parses the RDATA of the zone file format such as `192.168.1.10`, and the owner name is empty.
+    fn from_str(rdata: &str) -> Result<ARecord> {
+        let fields = presentation::parse_fields(rdata, 1, "A")?;
+        let addr = fields[0].parse::<Ipv4Addr>().map_err(|_| {
+            Error::new(
+                ErrorKind::InvalidText,
+                &format!("Invalid IPv4 address: {:?}", fields[0]),
+            )
+        })?;
+        Ok(ARecord::new("", addr))
+    }
 }
 
 impl ResourceRecord for ARecord {
@@ -12,20 +12,35 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use crate::dns::error::{Error, Result};
+use crate::dns::class::Class;
+use crate::dns::error::{Error, ErrorKind, Result};
+use crate::dns::presentation;
 use crate::dns::record::Record;
 use crate::dns::resource_record::ResourceRecord;
+use crate::dns::ttl::DEFAULT_HOST_RECORD_TTL;
 use crate::dns::typ::Type;
+use crate::dns::writer::Writer;
 use std::fmt;
 use std::net::{IpAddr, Ipv6Addr};
+use std::str::FromStr;
 
 /// AAAARecord represents an AAAA record.
 pub struct AAAARecord {
     name: String,
     ipaddr: IpAddr,
+    ttl: u32,
 }
 
 impl AAAARecord {
+    /// new creates a new AAAA record of the specified owner name and address with the default TTL of the host records.
+    pub fn new(name: &str, addr: Ipv6Addr) -> AAAARecord {
+        AAAARecord {
+            name: name.to_string(),
+            ipaddr: IpAddr::V6(addr),
+            ttl: DEFAULT_HOST_RECORD_TTL,
+        }
+    }
+
     /// from_record creates a new AAAA record from the specified record.
     pub fn from_record(record: &Record) -> Result<AAAARecord> {
         let data = record.data();
@@ -33,11 +48,16 @@ impl AAAARecord {
             let arr: [u8; 16] = data[0..16].try_into().unwrap();
             IpAddr::V6(Ipv6Addr::from(arr))
         } else {
-            return Err(Error::from_bytes(data, 0));
+            return Err(Error::from_bytes_with_kind(
+                ErrorKind::InvalidRdata,
+                data,
+                0,
+            ));
         };
         let a = AAAARecord {
             name: record.name().to_string(),
             ipaddr: addr,
+            ttl: record.ttl(),
         };
         Ok(a)
     }
@@ -51,6 +71,57 @@ impl AAAARecord {
     pub fn ipaddr(&self) -> &IpAddr {
         &self.ipaddr
     }
+
+    /// ttl returns the TTL of the AAAA record.
+    pub fn ttl(&self) -> u32 {
+        self.ttl
+    }
+
+    /// set_name sets the owner name of the AAAA record.
+    pub fn set_name(&mut self, name: &str) {
+        self.name = name.to_string();
+    }
+
+    /// set_ttl sets the TTL of the AAAA record.
+    pub fn set_ttl(&mut self, ttl: u32) {
+        self.ttl = ttl;
+    }
+
+    /// to_record returns the record of the AAAA record.
+    pub fn to_record(&self) -> Record {
+        let mut record = Record::new();
+        record.set_name(&self.name);
+        record.set_typ(Type::AAAA);
+        record.set_class(Class::IN);
+        record.set_ttl(self.ttl);
+        if let IpAddr::V6(v6) = self.ipaddr {
+            record.set_data(v6.octets().to_vec());
+        }
+        record
+    }
+
+    /// to_bytes returns the wire format of the AAAA record with the owner name, type, class, TTL and address.
+    pub fn to_bytes(&self) -> Result<Vec<u8>> {
+        let mut w = Writer::new();
+        w.write_response_record(&self.to_record())?;
+        Ok(w.to_bytes())
+    }
+}
+
+impl FromStr for AAAARecord {
+    type Err = Error;
+
+    /// from_str parses the RDATA of the zone file format such as `fe80::1`, and the owner name is empty.
+    fn from_str(rdata: &str) -> Result<AAAARecord> {
+        let fields = presentation::parse_fields(rdata, 1, "AAAA")?;
+        let addr = fields[0].parse::<Ipv6Addr>().map_err(|_| {
+            Error::new(
+                ErrorKind::InvalidText,
+                &format!("Invalid IPv6 address: {:?}", fields[0]),
+            )
+        })?;
+        Ok(AAAARecord::new("", addr))
+    }
 }
 
 impl ResourceRecord for AAAARecord {
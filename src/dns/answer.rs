@@ -0,0 +1,154 @@
+// Copyright (C) 2024 Satoshi Konno All rights reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//    http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::dns::error::{Error, ErrorKind, Result};
+use crate::dns::message::Message;
+use crate::dns::question::Question;
+use crate::dns::reader::Reader;
+use crate::dns::record::Record;
+use crate::dns::typ::Type;
+
+/// MAX_CNAME_CHAIN is the maximum number of the aliases followed to answer a question.
+pub const MAX_CNAME_CHAIN: usize = 8;
+
+/// Answer represents the records of a response which legitimately answer a question.
+/// RFC 1034: 3.6.2. Aliases and canonical names
+/// When a name server fails to find a desired RR in the resource set associated with the domain name,
+/// it checks to see if the resource set consists of a CNAME record with a matching class.
+/// If so, the name server includes the CNAME record in the response and restarts the query at the domain name specified in the data field of the CNAME record.
+#[derive(Clone)]
+pub struct Answer<'a> {
+    question: &'a Question,
+    aliases: Vec<&'a Record>,
+    records: Vec<&'a Record>,
+}
+
+impl<'a> Answer<'a> {
+    /// from_response validates the specified response against the question which elicited it, and collects the records answering it.
+    /// An error is returned if the message is not a response or echoes another question, or if the CNAME chain loops or is too long.
+    /// A response without the answer records, such as a name error, is valid and has no records.
+    pub fn from_response(question: &'a Question, response: &'a Message) -> Result<Answer<'a>> {
+        if !response.is_response() {
+            return Err(Error::new(
+                ErrorKind::UnexpectedResponse,
+                "the message is not a response",
+            ));
+        }
+        // RFC 6762: 6. Responding
+        // Multicast DNS responses MUST NOT contain any questions in the Question Section,
+        // so only the unicast responses which echo the questions are checked.
+        let questions = response.questions();
+        if !questions.is_empty() && !questions.iter().any(|q| same_question(q, question)) {
+            return Err(Error::new(
+                ErrorKind::UnexpectedResponse,
+                &format!("the response does not echo the question {}", question),
+            ));
+        }
+        let mut answer = Answer {
+            question,
+            aliases: Vec::new(),
+            records: Vec::new(),
+        };
+        let mut name = question.name().to_string();
+        loop {
+            answer.records = response
+                .answers()
+                .iter()
+                .filter(|record| answer.matches(&name, record))
+                .collect();
+            if !answer.records.is_empty() || question.typ() == Type::CNAME {
+                break;
+            }
+            let alias = response.answers().iter().find(|record| {
+                record.typ() == Type::CNAME
+                    && record.class() == question.class()
+                    && same_name(record.name(), &name)
+            });
+            let alias = match alias {
+                Some(alias) => alias,
+                None => break,
+            };
+            let target = Reader::from_bytes(alias.data()).read_name()?;
+            if same_name(&target, question.name())
+                || answer.aliases.iter().any(|a| same_name(a.name(), &target))
+            {
+                return Err(Error::new(
+                    ErrorKind::UnexpectedResponse,
+                    &format!("the CNAME chain of {} loops", question.name()),
+                ));
+            }
+            if MAX_CNAME_CHAIN <= answer.aliases.len() {
+                return Err(Error::new(
+                    ErrorKind::UnexpectedResponse,
+                    &format!("the CNAME chain of {} is too long", question.name()),
+                ));
+            }
+            answer.aliases.push(alias);
+            name = target;
+        }
+        Ok(answer)
+    }
+
+    /// question returns the question of the answer.
+    pub fn question(&self) -> &Question {
+        self.question
+    }
+
+    /// aliases returns the CNAME records followed from the question name to the canonical name, in order.
+    pub fn aliases(&self) -> &[&'a Record] {
+        &self.aliases
+    }
+
+    /// canonical_name returns the name the records are owned by, which is the question name unless an alias was followed.
+    pub fn canonical_name(&self) -> String {
+        match self.aliases.last() {
+            Some(alias) => Reader::from_bytes(alias.data())
+                .read_name()
+                .unwrap_or_default(),
+            None => self.question.name().to_string(),
+        }
+    }
+
+    /// records returns the answer records of the question type owned by the canonical name.
+    pub fn records(&self) -> &[&'a Record] {
+        &self.records
+    }
+
+    /// is_empty returns true if the response has no records answering the question.
+    pub fn is_empty(&self) -> bool {
+        self.records.is_empty()
+    }
+
+    // matches returns true if the specified record answers the question at the specified name.
+    fn matches(&self, name: &str, record: &Record) -> bool {
+        if !same_name(record.name(), name) || record.class() != self.question.class() {
+            return false;
+        }
+        self.question.typ() == Type::ANY || self.question.typ() == record.typ()
+    }
+}
+
+// same_question returns true if the echoed question is the sent one, ignoring the case of the names.
+fn same_question(echoed: &Question, sent: &Question) -> bool {
+    same_name(echoed.name(), sent.name())
+        && echoed.typ() == sent.typ()
+        && echoed.class() == sent.class()
+}
+
+// same_name compares the domain names case-insensitively, ignoring the trailing root label.
+// RFC 4343: 3. Name Lookup, Label Types, and CLASS
+fn same_name(a: &str, b: &str) -> bool {
+    a.trim_end_matches('.')
+        .eq_ignore_ascii_case(b.trim_end_matches('.'))
+}
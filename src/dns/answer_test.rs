@@ -0,0 +1,153 @@
+// Copyright (C) 2024 Satoshi Konno All rights reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//    http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+#[cfg(test)]
+mod tests {
+
+    use crate::dns::{Answer, ErrorKind, Message, Question, Record, Type, MAX_CNAME_CHAIN, QR};
+    use crate::fixture_test::{a_record, name_data, record};
+
+    fn cname_record(name: &str, target: &str) -> Record {
+        record(name, Type::CNAME, name_data(target))
+    }
+
+    fn response(question: Option<&Question>, answers: Vec<Record>) -> Message {
+        let mut msg = Message::new();
+        msg.set_qr(QR::Response);
+        if let Some(question) = question {
+            msg.add_question(question.clone());
+        }
+        for answer in answers {
+            msg.add_answer(answer);
+        }
+        Message::from_bytes(&msg.to_bytes().unwrap()).unwrap()
+    }
+
+    #[test]
+    fn answer() {
+        let question = Question::new("host.example.com", Type::A);
+        let res = response(
+            Some(&Question::new("HOST.example.com", Type::A)),
+            vec![
+                a_record("Host.Example.com", [192, 168, 1, 10]),
+                a_record("other.example.com", [192, 168, 1, 11]),
+            ],
+        );
+        let answer = Answer::from_response(&question, &res).unwrap();
+        assert_eq!(answer.records().len(), 1);
+        assert!(answer.aliases().is_empty());
+        assert_eq!(answer.canonical_name(), "host.example.com");
+
+        // A multicast response has no questions.
+        let res = response(None, vec![a_record("host.example.com", [192, 168, 1, 10])]);
+        assert_eq!(
+            Answer::from_response(&question, &res)
+                .unwrap()
+                .records()
+                .len(),
+            1
+        );
+
+        // A name error has no records but answers the question.
+        let res = response(Some(&question), vec![]);
+        assert!(Answer::from_response(&question, &res).unwrap().is_empty());
+
+        let question = Question::new("host.example.com", Type::ANY);
+        let res = response(
+            Some(&question),
+            vec![a_record("host.example.com", [192, 168, 1, 10])],
+        );
+        assert_eq!(
+            Answer::from_response(&question, &res)
+                .unwrap()
+                .records()
+                .len(),
+            1
+        );
+    }
+
+    #[test]
+    fn answer_cname_chain() {
+        let question = Question::new("www.example.com", Type::A);
+        let res = response(
+            Some(&question),
+            vec![
+                cname_record("www.example.com", "web.example.com"),
+                cname_record("web.example.com", "host.example.com"),
+                a_record("host.example.com", [192, 168, 1, 10]),
+            ],
+        );
+        let answer = Answer::from_response(&question, &res).unwrap();
+        assert_eq!(answer.aliases().len(), 2);
+        assert_eq!(answer.canonical_name(), "host.example.com");
+        assert_eq!(answer.records().len(), 1);
+        assert_eq!(answer.records()[0].name(), "host.example.com");
+
+        // The CNAME itself is the answer of a CNAME question.
+        let question = Question::new("www.example.com", Type::CNAME);
+        let res = response(
+            Some(&question),
+            vec![
+                cname_record("www.example.com", "web.example.com"),
+                cname_record("web.example.com", "host.example.com"),
+            ],
+        );
+        let answer = Answer::from_response(&question, &res).unwrap();
+        assert!(answer.aliases().is_empty());
+        assert_eq!(answer.records().len(), 1);
+    }
+
+    #[test]
+    fn answer_errors() {
+        let question = Question::new("host.example.com", Type::A);
+
+        let mut query = response(Some(&question), vec![]);
+        query.set_qr(QR::Query);
+        let other_type = Question::new("host.example.com", Type::AAAA);
+        let mut chain = vec![];
+        for n in 0..=MAX_CNAME_CHAIN {
+            chain.push(cname_record(
+                &format!("{}.example.com", n),
+                &format!("{}.example.com", n + 1),
+            ));
+        }
+        let long_question = Question::new("0.example.com", Type::A);
+
+        let cases = [
+            (&question, query),
+            (&question, response(Some(&other_type), vec![])),
+            (
+                &question,
+                response(Some(&Question::new("other.example.com", Type::A)), vec![]),
+            ),
+            (
+                &question,
+                response(
+                    Some(&question),
+                    vec![
+                        cname_record("host.example.com", "web.example.com"),
+                        cname_record("web.example.com", "HOST.example.com"),
+                    ],
+                ),
+            ),
+            (&long_question, response(Some(&long_question), chain)),
+        ];
+        for (question, res) in cases.iter() {
+            match Answer::from_response(question, res) {
+                Ok(_) => panic!("{} was answered", question),
+                Err(err) => assert_eq!(err.kind(), ErrorKind::UnexpectedResponse),
+            }
+        }
+    }
+}
@@ -0,0 +1,310 @@
+// Copyright (C) 2024 Satoshi Konno All rights reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//    http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::dns::error::{Error, ErrorKind, Result};
+
+/// EDNS_OPTION_OWNER is the option code of the owner of a sleep proxy registration.
+/// draft-cheshire-edns0-owner-option: 3. Implementation
+pub const EDNS_OPTION_OWNER: u16 = 4;
+/// EDNS_OPTION_COOKIE is the option code of the DNS cookies.
+/// RFC 7873: 4. DNS Cookie Option
+pub const EDNS_OPTION_COOKIE: u16 = 10;
+/// EDNS_OPTION_PADDING is the option code of the padding.
+/// RFC 7830: 3. The 'Padding' Option
+pub const EDNS_OPTION_PADDING: u16 = 12;
+
+const MAC_LEN: usize = 6;
+const CLIENT_COOKIE_LEN: usize = 8;
+const SERVER_COOKIE_MIN_LEN: usize = 8;
+const SERVER_COOKIE_MAX_LEN: usize = 32;
+
+/// EdnsOption represents an option in the RDATA of an OPT record as the raw bytes.
+#[derive(Debug, Clone, PartialEq)]
+pub struct EdnsOption {
+    code: u16,
+    data: Vec<u8>,
+}
+
+impl EdnsOption {
+    /// new creates a new option of the specified code and data.
+    pub fn new(code: u16, data: Vec<u8>) -> EdnsOption {
+        EdnsOption { code, data }
+    }
+
+    /// from_typed creates a new option of the specified typed option.
+    pub fn from_typed<T: EdnsOptionCodec>(option: &T) -> EdnsOption {
+        EdnsOption::new(T::CODE, option.encode())
+    }
+
+    /// code returns the option code.
+    pub fn code(&self) -> u16 {
+        self.code
+    }
+
+    /// data returns the option data.
+    pub fn data(&self) -> &[u8] {
+        &self.data
+    }
+
+    /// decode returns the typed option of the registered codes, or the raw option for the other codes.
+    pub fn decode(&self) -> Result<EdnsOptionData> {
+        match self.code {
+            EDNS_OPTION_OWNER => Ok(EdnsOptionData::Owner(OwnerOption::decode(&self.data)?)),
+            EDNS_OPTION_COOKIE => Ok(EdnsOptionData::Cookie(CookieOption::decode(&self.data)?)),
+            EDNS_OPTION_PADDING => Ok(EdnsOptionData::Padding(PaddingOption::decode(&self.data)?)),
+            _ => Ok(EdnsOptionData::Unknown(self.clone())),
+        }
+    }
+}
+
+/// EdnsOptionCodec represents an option type which is encoded to and decoded from the option data.
+pub trait EdnsOptionCodec: Sized {
+    /// CODE is the option code of the type.
+    const CODE: u16;
+    /// decode decodes the option from the specified option data.
+    fn decode(data: &[u8]) -> Result<Self>;
+    /// encode returns the option data of the option.
+    fn encode(&self) -> Vec<u8>;
+}
+
+/// EdnsOptionData represents a decoded option, and an option of an unregistered code is kept as the raw bytes.
+#[derive(Debug, Clone, PartialEq)]
+pub enum EdnsOptionData {
+    /// Owner is the owner option of a sleep proxy registration.
+    Owner(OwnerOption),
+    /// Cookie is the DNS cookie option.
+    Cookie(CookieOption),
+    /// Padding is the padding option.
+    Padding(PaddingOption),
+    /// Unknown is an option of an unregistered code.
+    Unknown(EdnsOption),
+}
+
+impl EdnsOptionData {
+    /// to_option returns the raw option of the decoded option.
+    pub fn to_option(&self) -> EdnsOption {
+        match self {
+            EdnsOptionData::Owner(owner) => EdnsOption::from_typed(owner),
+            EdnsOptionData::Cookie(cookie) => EdnsOption::from_typed(cookie),
+            EdnsOptionData::Padding(padding) => EdnsOption::from_typed(padding),
+            EdnsOptionData::Unknown(option) => option.clone(),
+        }
+    }
+}
+
+/// OwnerOption represents the owner option, which identifies the host whose records a sleep proxy answers for,
+/// and how to wake it up.
+/// draft-cheshire-edns0-owner-option: 3. Implementation
+#[derive(Debug, Clone, PartialEq)]
+pub struct OwnerOption {
+    version: u8,
+    sequence: u8,
+    primary_mac: [u8; MAC_LEN],
+    wakeup_mac: Option<[u8; MAC_LEN]>,
+    password: Vec<u8>,
+}
+
+impl OwnerOption {
+    /// new creates a new owner option of the specified primary MAC address and sequence number.
+    pub fn new(primary_mac: [u8; MAC_LEN], sequence: u8) -> OwnerOption {
+        OwnerOption {
+            version: 0,
+            sequence,
+            primary_mac,
+            wakeup_mac: None,
+            password: Vec::new(),
+        }
+    }
+
+    /// version returns the version of the option.
+    pub fn version(&self) -> u8 {
+        self.version
+    }
+
+    /// sequence returns the sequence number, which increases every time the host wakes up.
+    pub fn sequence(&self) -> u8 {
+        self.sequence
+    }
+
+    /// primary_mac returns the MAC address of the primary interface of the host.
+    pub fn primary_mac(&self) -> &[u8; MAC_LEN] {
+        &self.primary_mac
+    }
+
+    /// set_wakeup_mac sets the MAC address where the wake-on-LAN packet is sent, if it differs from the primary one.
+    pub fn set_wakeup_mac(&mut self, mac: [u8; MAC_LEN]) {
+        self.wakeup_mac = Some(mac);
+    }
+
+    /// wakeup_mac returns the MAC address where the wake-on-LAN packet is sent.
+    pub fn wakeup_mac(&self) -> &[u8; MAC_LEN] {
+        self.wakeup_mac.as_ref().unwrap_or(&self.primary_mac)
+    }
+
+    /// set_password sets the wake-on-LAN password of 4 or 6 bytes, which requires the wake-up MAC address.
+    pub fn set_password(&mut self, password: &[u8]) -> Result<()> {
+        if !matches!(password.len(), 0 | 4 | 6) {
+            return Err(Error::new(
+                ErrorKind::InvalidRdata,
+                &format!("Invalid owner option password length: {}", password.len()),
+            ));
+        }
+        if !password.is_empty() && self.wakeup_mac.is_none() {
+            self.wakeup_mac = Some(self.primary_mac);
+        }
+        self.password = password.to_vec();
+        Ok(())
+    }
+
+    /// password returns the wake-on-LAN password, which is empty if none.
+    pub fn password(&self) -> &[u8] {
+        &self.password
+    }
+}
+
+impl EdnsOptionCodec for OwnerOption {
+    const CODE: u16 = EDNS_OPTION_OWNER;
+
+    fn decode(data: &[u8]) -> Result<OwnerOption> {
+        if !matches!(data.len(), 8 | 14 | 18 | 20) {
+            return Err(Error::new(
+                ErrorKind::InvalidRdata,
+                &format!("Invalid owner option length: {}", data.len()),
+            ));
+        }
+        let mac = |offset: usize| {
+            let mut mac = [0; MAC_LEN];
+            mac.copy_from_slice(&data[offset..offset + MAC_LEN]);
+            mac
+        };
+        Ok(OwnerOption {
+            version: data[0],
+            sequence: data[1],
+            primary_mac: mac(2),
+            wakeup_mac: (14 <= data.len()).then(|| mac(8)),
+            password: data.get(14..).unwrap_or_default().to_vec(),
+        })
+    }
+
+    fn encode(&self) -> Vec<u8> {
+        let mut data = vec![self.version, self.sequence];
+        data.extend_from_slice(&self.primary_mac);
+        if let Some(wakeup_mac) = &self.wakeup_mac {
+            data.extend_from_slice(wakeup_mac);
+            data.extend_from_slice(&self.password);
+        }
+        data
+    }
+}
+
+/// CookieOption represents the DNS cookie option of a client cookie and an optional server cookie.
+/// RFC 7873: 4. DNS Cookie Option
+#[derive(Debug, Clone, PartialEq)]
+pub struct CookieOption {
+    client: [u8; CLIENT_COOKIE_LEN],
+    server: Vec<u8>,
+}
+
+impl CookieOption {
+    /// new creates a new cookie option of the specified client cookie.
+    pub fn new(client: [u8; CLIENT_COOKIE_LEN]) -> CookieOption {
+        CookieOption {
+            client,
+            server: Vec::new(),
+        }
+    }
+
+    /// client returns the client cookie.
+    pub fn client(&self) -> &[u8; CLIENT_COOKIE_LEN] {
+        &self.client
+    }
+
+    /// set_server sets the server cookie of 8 to 32 bytes.
+    pub fn set_server(&mut self, server: &[u8]) -> Result<()> {
+        if !(SERVER_COOKIE_MIN_LEN..=SERVER_COOKIE_MAX_LEN).contains(&server.len()) {
+            return Err(Error::new(
+                ErrorKind::InvalidRdata,
+                &format!("Invalid server cookie length: {}", server.len()),
+            ));
+        }
+        self.server = server.to_vec();
+        Ok(())
+    }
+
+    /// server returns the server cookie, which is empty if none.
+    pub fn server(&self) -> &[u8] {
+        &self.server
+    }
+}
+
+impl EdnsOptionCodec for CookieOption {
+    const CODE: u16 = EDNS_OPTION_COOKIE;
+
+    fn decode(data: &[u8]) -> Result<CookieOption> {
+        if data.len() < CLIENT_COOKIE_LEN {
+            return Err(Error::new(
+                ErrorKind::InvalidRdata,
+                &format!("Invalid cookie option length: {}", data.len()),
+            ));
+        }
+        let mut client = [0; CLIENT_COOKIE_LEN];
+        client.copy_from_slice(&data[..CLIENT_COOKIE_LEN]);
+        let mut cookie = CookieOption::new(client);
+        if CLIENT_COOKIE_LEN < data.len() {
+            cookie.set_server(&data[CLIENT_COOKIE_LEN..])?;
+        }
+        Ok(cookie)
+    }
+
+    fn encode(&self) -> Vec<u8> {
+        [&self.client[..], &self.server].concat()
+    }
+}
+
+/// PaddingOption represents the padding option of zero bytes, which obscures the size of a message.
+/// RFC 7830: 3. The 'Padding' Option
+#[derive(Debug, Clone, PartialEq)]
+pub struct PaddingOption {
+    len: usize,
+}
+
+impl PaddingOption {
+    /// new creates a new padding option of the specified number of bytes.
+    pub fn new(len: usize) -> PaddingOption {
+        PaddingOption { len }
+    }
+
+    /// len returns the number of the padding bytes.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// is_empty returns true if the option has no padding bytes.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+}
+
+impl EdnsOptionCodec for PaddingOption {
+    const CODE: u16 = EDNS_OPTION_PADDING;
+
+    // The padding bytes SHOULD be zeros, but the receivers accept any bytes.
+    fn decode(data: &[u8]) -> Result<PaddingOption> {
+        Ok(PaddingOption::new(data.len()))
+    }
+
+    fn encode(&self) -> Vec<u8> {
+        vec![0; self.len]
+    }
+}
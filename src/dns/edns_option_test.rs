@@ -0,0 +1,100 @@
+// Copyright (C) 2024 Satoshi Konno All rights reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//    http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+#[cfg(test)]
+mod tests {
+
+    use crate::dns::{
+        CookieOption, EdnsOption, EdnsOptionCodec, EdnsOptionData, Message, OPTRecord, OwnerOption,
+        PaddingOption, EDNS_OPTION_COOKIE, EDNS_OPTION_OWNER,
+    };
+
+    const MAC: [u8; 6] = [0x00, 0x11, 0x22, 0x33, 0x44, 0x55];
+
+    #[test]
+    fn edns_owner_option() {
+        let owner = OwnerOption::new(MAC, 3);
+        let data = owner.encode();
+        assert_eq!(data.len(), 8);
+        let decoded = OwnerOption::decode(&data).unwrap();
+        assert_eq!(decoded.sequence(), 3);
+        assert_eq!(decoded.wakeup_mac(), &MAC);
+        assert!(decoded.password().is_empty());
+
+        let mut owner = OwnerOption::new(MAC, 4);
+        owner.set_wakeup_mac([0x66; 6]);
+        assert!(owner.set_password(&[1, 2, 3]).is_err());
+        assert!(owner.set_password(&[1, 2, 3, 4, 5, 6]).is_ok());
+        let data = owner.encode();
+        assert_eq!(data.len(), 20);
+        assert_eq!(OwnerOption::decode(&data).unwrap(), owner);
+        assert!(OwnerOption::decode(&data[..10]).is_err());
+    }
+
+    #[test]
+    fn edns_cookie_option() {
+        let mut cookie = CookieOption::new([1; 8]);
+        assert_eq!(CookieOption::decode(&cookie.encode()).unwrap(), cookie);
+        assert!(cookie.set_server(&[2; 4]).is_err());
+        assert!(cookie.set_server(&[2; 16]).is_ok());
+        let data = cookie.encode();
+        assert_eq!(data.len(), 24);
+        assert_eq!(CookieOption::decode(&data).unwrap().server(), &[2; 16]);
+        assert!(CookieOption::decode(&data[..12]).is_err());
+        assert!(CookieOption::decode(&[1; 4]).is_err());
+    }
+
+    #[test]
+    fn edns_option_registry() {
+        let mut opt = OPTRecord::new(1440);
+        opt.set_typed_option(&OwnerOption::new(MAC, 1));
+        opt.set_typed_option(&OwnerOption::new(MAC, 2));
+        opt.set_typed_option(&PaddingOption::new(16));
+        opt.add_option(EdnsOption::new(65001, vec![0xab, 0xcd]));
+        assert_eq!(opt.options().len(), 3);
+
+        let mut msg = Message::new();
+        assert!(msg.set_opt(&opt).is_ok());
+        let msg = Message::from_bytes(&msg.to_bytes().unwrap()).unwrap();
+        let opt = msg.opt().unwrap();
+        let owner = opt.typed_option::<OwnerOption>().unwrap().unwrap();
+        assert_eq!(owner.sequence(), 2);
+        assert_eq!(owner.primary_mac(), &MAC);
+        assert_eq!(
+            opt.typed_option::<PaddingOption>().unwrap().unwrap().len(),
+            16
+        );
+        assert!(opt.typed_option::<CookieOption>().is_none());
+
+        let decoded: Vec<EdnsOptionData> =
+            opt.options().iter().map(|o| o.decode().unwrap()).collect();
+        assert!(matches!(decoded[0], EdnsOptionData::Owner(_)));
+        assert!(matches!(decoded[1], EdnsOptionData::Padding(_)));
+        match &decoded[2] {
+            EdnsOptionData::Unknown(option) => {
+                assert_eq!(option.code(), 65001);
+                assert_eq!(option.data(), &[0xab, 0xcd]);
+            }
+            _ => panic!("expected an unknown option"),
+        }
+        for (data, option) in decoded.iter().zip(opt.options()) {
+            assert_eq!(&data.to_option(), option);
+        }
+
+        assert!(EdnsOption::new(EDNS_OPTION_COOKIE, vec![0; 3])
+            .decode()
+            .is_err());
+        assert_eq!(EdnsOption::from_typed(&owner).code(), EDNS_OPTION_OWNER);
+    }
+}
@@ -12,47 +12,114 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use hex;
+use std::fmt;
 
 pub type Result<T> = std::result::Result<T, Error>;
 
+/// ErrorKind represents the kind of an error, such as to tell a truncated packet from an unsupported record.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum ErrorKind {
+    /// Truncated is the kind of the data which ends before a field.
+    Truncated,
+    /// InvalidLabel is the kind of a malformed label or compression pointer of a name.
+    InvalidLabel,
+    /// UnsupportedType is the kind of a record type which can not be interpreted.
+    UnsupportedType,
+    /// InvalidRdata is the kind of a record data which is inconsistent with its type.
+    InvalidRdata,
+    /// InvalidMessage is the kind of a message whose sections or flags are inconsistent.
+    InvalidMessage,
+    /// InvalidText is the kind of a malformed zone file presentation format.
+    InvalidText,
+    /// UnexpectedResponse is the kind of a response which does not answer the question it was sent for.
+    UnexpectedResponse,
+    /// Io is the kind of an I/O failure.
+    Io,
+    /// Other is the kind of the other errors.
+    Other,
+}
+
+impl fmt::Display for ErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let kind = match self {
+            ErrorKind::Truncated => "truncated",
+            ErrorKind::InvalidLabel => "invalid label",
+            ErrorKind::UnsupportedType => "unsupported type",
+            ErrorKind::InvalidRdata => "invalid rdata",
+            ErrorKind::InvalidMessage => "invalid message",
+            ErrorKind::InvalidText => "invalid text",
+            ErrorKind::UnexpectedResponse => "unexpected response",
+            ErrorKind::Io => "I/O error",
+            ErrorKind::Other => "other",
+        };
+        write!(f, "{}", kind)
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct Error {
     pub msg: String,
+    kind: ErrorKind,
+    offset: Option<usize>,
 }
 
 impl Error {
-    /// from_str creates a new Error with the specified string.
-    pub fn from_str(str: &str) -> Error {
+    /// new creates a new Error of the specified kind with the specified string.
+    pub fn new(kind: ErrorKind, str: &str) -> Error {
         Error {
             msg: str.to_string(),
+            kind,
+            offset: None,
         }
     }
 
+    /// from_str creates a new Error with the specified string.
+    #[allow(clippy::should_implement_trait)]
+    pub fn from_str(str: &str) -> Error {
+        Error::new(ErrorKind::Other, str)
+    }
+
     /// from_string creates a new Error with the specified string.
-    pub fn from_string(str: &String) -> Error {
-        Error { msg: str.clone() }
+    pub fn from_string(str: &str) -> Error {
+        Error::new(ErrorKind::Other, str)
     }
 
-    /// from_bytes creates a new Error with the specified bytes.
+    /// from_bytes creates a new Error of the data which ends before the specified offset.
     pub fn from_bytes(msg_bytes: &[u8], offset: usize) -> Error {
+        Error::from_bytes_with_kind(ErrorKind::Truncated, msg_bytes, offset)
+    }
+
+    /// from_bytes_with_kind creates a new Error of the specified kind at the specified offset of the bytes.
+    pub fn from_bytes_with_kind(kind: ErrorKind, msg_bytes: &[u8], offset: usize) -> Error {
         Error {
             msg: format!(
                 "Invalid bytes {} (offset:{})",
                 hex::encode(msg_bytes),
                 offset
             ),
+            kind,
+            offset: Some(offset),
         }
     }
 
+    /// kind returns the kind of the error.
+    pub fn kind(&self) -> ErrorKind {
+        self.kind
+    }
+
+    /// offset returns the offset in the parsed bytes where the error was found, if known.
+    pub fn offset(&self) -> Option<usize> {
+        self.offset
+    }
+
     /// message returns the error message.
     pub fn message(&self) -> &str {
         &self.msg
     }
 }
 
-impl std::fmt::Display for Error {
-    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         write!(f, "{}", self.msg)
     }
 }
@@ -62,3 +129,9 @@ impl std::error::Error for Error {
         None
     }
 }
+
+impl From<std::io::Error> for Error {
+    fn from(e: std::io::Error) -> Error {
+        Error::new(ErrorKind::Io, &e.to_string())
+    }
+}
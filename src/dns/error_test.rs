@@ -0,0 +1,61 @@
+// Copyright (C) 2024 Satoshi Konno All rights reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//    http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+#[cfg(test)]
+mod tests {
+
+    use crate::dns::reader::Reader;
+    use crate::dns::{ARecord, ErrorKind, Message, Record, Type, Writer};
+
+    #[test]
+    fn error_kinds() {
+        let mut reader = Reader::from_bytes(&[0x00, 0x01]);
+        let mut buf = [0; 4];
+        let err = reader.read_bytes(&mut buf).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::Truncated);
+        assert_eq!(err.offset(), Some(0));
+        assert_eq!(err.to_string(), "Invalid bytes 0001 (offset:0)");
+
+        // A pointer to itself, and a reserved label type.
+        let mut reader = Reader::from_bytes(&[0xc0, 0x00]);
+        assert_eq!(
+            reader.read_name().unwrap_err().kind(),
+            ErrorKind::InvalidLabel
+        );
+        let mut reader = Reader::from_bytes(&[0x40, b'a', 0x00]);
+        assert_eq!(
+            reader.read_name().unwrap_err().kind(),
+            ErrorKind::InvalidLabel
+        );
+        let mut w = Writer::new();
+        let err = w.write_name(&"a".repeat(64)).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidLabel);
+        assert_eq!(err.offset(), None);
+
+        let mut record = Record::new();
+        record.set_typ(Type::A);
+        record.set_data(vec![192, 168]);
+        let err = ARecord::from_record(&record).err().unwrap();
+        assert_eq!(err.kind(), ErrorKind::InvalidRdata);
+        record.set_typ(Type::RRSIG);
+        let err = record.to_resource_record().err().unwrap();
+        assert_eq!(err.kind(), ErrorKind::UnsupportedType);
+
+        let err = "192.168.1".parse::<ARecord>().err().unwrap();
+        assert_eq!(err.kind(), ErrorKind::InvalidText);
+
+        let err = Message::from_bytes(&[0x00; 4]).err().unwrap();
+        assert_eq!(err.kind(), ErrorKind::Truncated);
+    }
+}
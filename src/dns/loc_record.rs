@@ -0,0 +1,395 @@
+// Copyright (C) 2024 Satoshi Konno All rights reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//    http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::dns::class::Class;
+use crate::dns::error::{Error, ErrorKind, Result};
+use crate::dns::reader::Reader;
+use crate::dns::record::Record;
+use crate::dns::resource_record::ResourceRecord;
+use crate::dns::typ::Type;
+use crate::dns::writer::Writer;
+use std::fmt;
+use std::str::FromStr;
+
+// RFC 1876: 2. RDATA Format
+const LOC_VERSION: u8 = 0;
+const LOC_DATA_LEN: usize = 16;
+// The equator and the prime meridian are 2^31 thousandths of an arc second.
+const LOC_EQUATOR: i64 = 1 << 31;
+// The altitude is in centimeters from a base 100,000 meters below the WGS 84 reference spheroid.
+const LOC_ALTITUDE_BASE: i64 = 10_000_000;
+const ARC_SECOND: i64 = 1000;
+const ARC_MINUTE: i64 = 60 * ARC_SECOND;
+const ARC_DEGREE: i64 = 60 * ARC_MINUTE;
+
+/// DEFAULT_LOC_SIZE is the default diameter of the sphere enclosing the located entity in centimeters.
+/// RFC 1876: 3. Master File Format
+pub const DEFAULT_LOC_SIZE: u64 = 100;
+/// DEFAULT_LOC_HORIZONTAL_PRECISION is the default horizontal precision in centimeters.
+pub const DEFAULT_LOC_HORIZONTAL_PRECISION: u64 = 1_000_000;
+/// DEFAULT_LOC_VERTICAL_PRECISION is the default vertical precision in centimeters.
+pub const DEFAULT_LOC_VERTICAL_PRECISION: u64 = 1_000;
+
+/// LOCRecord represents a LOC record, which tells the geographical location of the owner name.
+/// RFC 1876: 2. RDATA Format
+pub struct LOCRecord {
+    name: String,
+    size: u8,
+    horiz_pre: u8,
+    vert_pre: u8,
+    latitude: u32,
+    longitude: u32,
+    altitude: u32,
+    content: String,
+}
+
+impl LOCRecord {
+    /// new creates a new LOC record of the specified owner name, latitude and longitude in degrees, and altitude in meters,
+    /// where the north latitudes and the east longitudes are positive.
+    pub fn new(name: &str, latitude: f64, longitude: f64, altitude: f64) -> LOCRecord {
+        let mut loc = LOCRecord {
+            name: name.to_string(),
+            size: encode_precision(DEFAULT_LOC_SIZE),
+            horiz_pre: encode_precision(DEFAULT_LOC_HORIZONTAL_PRECISION),
+            vert_pre: encode_precision(DEFAULT_LOC_VERTICAL_PRECISION),
+            latitude: encode_angle((latitude * ARC_DEGREE as f64).round() as i64),
+            longitude: encode_angle((longitude * ARC_DEGREE as f64).round() as i64),
+            altitude: encode_altitude((altitude * 100.0).round() as i64),
+            content: "".to_string(),
+        };
+        loc.content = loc.to_string();
+        loc
+    }
+
+    /// from_record creates a new LOC record from the specified record.
+    pub fn from_record(record: &Record) -> Result<LOCRecord> {
+        let data = record.data();
+        let mut reader = Reader::from_bytes(data);
+        let version = reader.read_u8()?;
+        if version != LOC_VERSION || data.len() < LOC_DATA_LEN {
+            return Err(Error::from_bytes_with_kind(
+                ErrorKind::InvalidRdata,
+                data,
+                0,
+            ));
+        }
+        let size = reader.read_u8()?;
+        let horiz_pre = reader.read_u8()?;
+        let vert_pre = reader.read_u8()?;
+        for (n, precision) in [size, horiz_pre, vert_pre].iter().enumerate() {
+            if decode_precision(*precision).is_none() {
+                return Err(Error::from_bytes_with_kind(
+                    ErrorKind::InvalidRdata,
+                    data,
+                    1 + n,
+                ));
+            }
+        }
+        let mut loc = LOCRecord {
+            name: record.name().to_string(),
+            size,
+            horiz_pre,
+            vert_pre,
+            latitude: reader.read_u32()?,
+            longitude: reader.read_u32()?,
+            altitude: reader.read_u32()?,
+            content: "".to_string(),
+        };
+        loc.content = loc.to_string();
+        Ok(loc)
+    }
+
+    /// latitude returns the latitude in degrees, where the north latitudes are positive.
+    pub fn latitude(&self) -> f64 {
+        decode_angle(self.latitude) as f64 / ARC_DEGREE as f64
+    }
+
+    /// longitude returns the longitude in degrees, where the east longitudes are positive.
+    pub fn longitude(&self) -> f64 {
+        decode_angle(self.longitude) as f64 / ARC_DEGREE as f64
+    }
+
+    /// altitude returns the altitude in meters above the WGS 84 reference spheroid.
+    pub fn altitude(&self) -> f64 {
+        decode_altitude(self.altitude) as f64 / 100.0
+    }
+
+    /// size returns the diameter of the sphere enclosing the located entity in centimeters.
+    pub fn size(&self) -> u64 {
+        decode_precision(self.size).unwrap_or_default()
+    }
+
+    /// horizontal_precision returns the horizontal precision of the location in centimeters.
+    pub fn horizontal_precision(&self) -> u64 {
+        decode_precision(self.horiz_pre).unwrap_or_default()
+    }
+
+    /// vertical_precision returns the vertical precision of the location in centimeters.
+    pub fn vertical_precision(&self) -> u64 {
+        decode_precision(self.vert_pre).unwrap_or_default()
+    }
+
+    /// set_size sets the diameter of the enclosing sphere in centimeters, which is rounded to a single significant digit.
+    pub fn set_size(&mut self, cm: u64) {
+        self.size = encode_precision(cm);
+        self.content = self.to_string();
+    }
+
+    /// set_horizontal_precision sets the horizontal precision in centimeters, which is rounded to a single significant digit.
+    pub fn set_horizontal_precision(&mut self, cm: u64) {
+        self.horiz_pre = encode_precision(cm);
+        self.content = self.to_string();
+    }
+
+    /// set_vertical_precision sets the vertical precision in centimeters, which is rounded to a single significant digit.
+    pub fn set_vertical_precision(&mut self, cm: u64) {
+        self.vert_pre = encode_precision(cm);
+        self.content = self.to_string();
+    }
+
+    /// set_name sets the owner name of the LOC record.
+    pub fn set_name(&mut self, name: &str) {
+        self.name = name.to_string();
+    }
+
+    /// to_record returns the record of the LOC record.
+    pub fn to_record(&self) -> Result<Record> {
+        let mut w = Writer::new();
+        w.write_u8(LOC_VERSION)?;
+        w.write_u8(self.size)?;
+        w.write_u8(self.horiz_pre)?;
+        w.write_u8(self.vert_pre)?;
+        w.write_u32(self.latitude)?;
+        w.write_u32(self.longitude)?;
+        w.write_u32(self.altitude)?;
+        let mut record = Record::new();
+        record.set_name(&self.name);
+        record.set_typ(Type::LOC);
+        record.set_class(Class::IN);
+        record.set_data(w.to_bytes());
+        Ok(record)
+    }
+}
+
+// encode_precision returns the size or precision in centimeters as the base and the power of ten in the high and low nibbles.
+fn encode_precision(cm: u64) -> u8 {
+    let mut base = cm;
+    let mut exponent = 0;
+    while 9 < base && exponent < 9 {
+        base = (base + 5) / 10;
+        exponent += 1;
+    }
+    (base.min(9) as u8) << 4 | exponent
+}
+
+// decode_precision returns the size or precision in centimeters, or None if a nibble is larger than 9.
+fn decode_precision(value: u8) -> Option<u64> {
+    let base = (value >> 4) as u64;
+    let exponent = (value & 0x0f) as u32;
+    if 9 < base || 9 < exponent {
+        return None;
+    }
+    Some(base * 10u64.pow(exponent))
+}
+
+fn encode_angle(thousandths: i64) -> u32 {
+    (LOC_EQUATOR + thousandths).clamp(0, u32::MAX as i64) as u32
+}
+
+fn decode_angle(value: u32) -> i64 {
+    value as i64 - LOC_EQUATOR
+}
+
+fn encode_altitude(cm: i64) -> u32 {
+    (LOC_ALTITUDE_BASE + cm).clamp(0, u32::MAX as i64) as u32
+}
+
+fn decode_altitude(value: u32) -> i64 {
+    value as i64 - LOC_ALTITUDE_BASE
+}
+
+fn invalid_rdata(rdata: &str) -> Error {
+    Error::new(
+        ErrorKind::InvalidText,
+        &format!("Invalid LOC RDATA: {:?}", rdata),
+    )
+}
+
+// parse_angle parses the degrees, the optional minutes and seconds, and the hemisphere of a LOC coordinate as thousandths of an arc second.
+fn parse_angle<'a, I>(
+    fields: &mut I,
+    hemispheres: (&str, &str),
+    max: i64,
+    rdata: &str,
+) -> Result<i64>
+where
+    I: Iterator<Item = &'a str>,
+{
+    let mut units = [ARC_DEGREE, ARC_MINUTE, ARC_SECOND].iter();
+    let mut thousandths: i64 = 0;
+    loop {
+        let field = fields.next().ok_or_else(|| invalid_rdata(rdata))?;
+        let sign = if field.eq_ignore_ascii_case(hemispheres.0) {
+            1
+        } else if field.eq_ignore_ascii_case(hemispheres.1) {
+            -1
+        } else {
+            let unit = units.next().ok_or_else(|| invalid_rdata(rdata))?;
+            // Only the seconds may have the fractional digits, which are the thousandths.
+            let value = if *unit == ARC_SECOND {
+                parse_decimal(field, 3)
+            } else {
+                field.parse::<u32>().ok().map(|v| v as i64 * unit)
+            };
+            thousandths += value.ok_or_else(|| invalid_rdata(rdata))?;
+            continue;
+        };
+        if max < thousandths {
+            return Err(invalid_rdata(rdata));
+        }
+        return Ok(sign * thousandths);
+    }
+}
+
+// parse_decimal parses a non-negative decimal number scaled by the specified number of fractional digits.
+fn parse_decimal(field: &str, digits: usize) -> Option<i64> {
+    let (int, frac) = field.split_once('.').unwrap_or((field, ""));
+    if int.is_empty() || digits < frac.len() {
+        return None;
+    }
+    if !int.bytes().chain(frac.bytes()).all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    let int = int.parse::<i64>().ok()?;
+    let frac = format!("{:0<width$}", frac, width = digits)
+        .parse::<i64>()
+        .ok()?;
+    int.checked_mul(10i64.pow(digits as u32))?.checked_add(frac)
+}
+
+// parse_meters parses a length in meters with an optional `m` suffix as centimeters.
+fn parse_meters(field: &str) -> Option<i64> {
+    let field = field.strip_suffix(['m', 'M']).unwrap_or(field);
+    match field.strip_prefix('-') {
+        Some(field) => parse_decimal(field, 2).map(|cm| -cm),
+        None => parse_decimal(field, 2),
+    }
+}
+
+impl FromStr for LOCRecord {
+    type Err = Error;
+
+    /// from_str parses the RDATA of the zone file format such as `42 21 54 N 71 06 18 W -24m 30m`, and the owner name is empty.
+    /// RFC 1876: 3. Master File Format
+    fn from_str(rdata: &str) -> Result<LOCRecord> {
+        let mut fields = rdata.split_whitespace();
+        let latitude = parse_angle(&mut fields, ("N", "S"), 90 * ARC_DEGREE, rdata)?;
+        let longitude = parse_angle(&mut fields, ("E", "W"), 180 * ARC_DEGREE, rdata)?;
+        let altitude = fields
+            .next()
+            .and_then(parse_meters)
+            .ok_or_else(|| invalid_rdata(rdata))?;
+        let mut precisions = [
+            DEFAULT_LOC_SIZE,
+            DEFAULT_LOC_HORIZONTAL_PRECISION,
+            DEFAULT_LOC_VERTICAL_PRECISION,
+        ];
+        for precision in precisions.iter_mut() {
+            let Some(field) = fields.next() else {
+                break;
+            };
+            *precision = parse_meters(field)
+                .filter(|cm| 0 <= *cm)
+                .ok_or_else(|| invalid_rdata(rdata))? as u64;
+        }
+        if fields.next().is_some() {
+            return Err(invalid_rdata(rdata));
+        }
+        let mut loc = LOCRecord {
+            name: "".to_string(),
+            size: encode_precision(precisions[0]),
+            horiz_pre: encode_precision(precisions[1]),
+            vert_pre: encode_precision(precisions[2]),
+            latitude: encode_angle(latitude),
+            longitude: encode_angle(longitude),
+            altitude: encode_altitude(altitude),
+            content: "".to_string(),
+        };
+        loc.content = loc.to_string();
+        Ok(loc)
+    }
+}
+
+impl ResourceRecord for LOCRecord {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn typ(&self) -> Type {
+        Type::LOC
+    }
+
+    fn content(&self) -> &str {
+        &self.content
+    }
+}
+
+fn fmt_angle(f: &mut fmt::Formatter, value: u32, hemispheres: (&str, &str)) -> fmt::Result {
+    let thousandths = decode_angle(value);
+    let hemisphere = if 0 <= thousandths {
+        hemispheres.0
+    } else {
+        hemispheres.1
+    };
+    let thousandths = thousandths.abs();
+    write!(
+        f,
+        "{} {} {}.{:03} {}",
+        thousandths / ARC_DEGREE,
+        (thousandths % ARC_DEGREE) / ARC_MINUTE,
+        (thousandths % ARC_MINUTE) / ARC_SECOND,
+        thousandths % ARC_SECOND,
+        hemisphere
+    )
+}
+
+fn fmt_meters(f: &mut fmt::Formatter, cm: i64) -> fmt::Result {
+    let sign = if cm < 0 { "-" } else { "" };
+    let cm = cm.abs();
+    if cm % 100 == 0 {
+        write!(f, "{}{}m", sign, cm / 100)
+    } else {
+        write!(f, "{}{}.{:02}m", sign, cm / 100, cm % 100)
+    }
+}
+
+// RFC 1876: 3. Master File Format
+impl fmt::Display for LOCRecord {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt_angle(f, self.latitude, ("N", "S"))?;
+        write!(f, " ")?;
+        fmt_angle(f, self.longitude, ("E", "W"))?;
+        write!(f, " ")?;
+        fmt_meters(f, decode_altitude(self.altitude))?;
+        for precision in [
+            self.size(),
+            self.horizontal_precision(),
+            self.vertical_precision(),
+        ] {
+            write!(f, " ")?;
+            fmt_meters(f, precision as i64)?;
+        }
+        Ok(())
+    }
+}
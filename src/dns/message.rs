@@ -15,15 +15,23 @@
 use std::fmt;
 
 use crate::dns::error::{Error, Result};
+use crate::dns::message_builder::MessageBuilder;
+use crate::dns::opt_record::OPTRecord;
+use crate::dns::question::{Question, Questions};
 use crate::dns::reader::Reader;
 use crate::dns::record::Record;
 use crate::dns::records::Records;
 use crate::dns::resource_record::*;
 use crate::dns::resource_records::ResourceRecords;
+use crate::dns::typ::Type;
 use crate::dns::writer::Writer;
 
 const HEADER_SIZE: usize = 12;
 
+// FNV-1a: the 64-bit offset basis and prime, which hash the same on every platform and release unlike DefaultHasher.
+const FNV_OFFSET_BASIS: u64 = 0xcbf29ce484222325;
+const FNV_PRIME: u64 = 0x100000001b3;
+
 /// QR represents the query type.
 #[derive(PartialEq)]
 pub enum QR {
@@ -49,14 +57,21 @@ pub enum ResponseCode {
 }
 
 /// Message represents a DNS message.
+#[derive(Clone, PartialEq)]
 pub struct Message {
     header: [u8; HEADER_SIZE],
-    questions: Records,
+    questions: Questions,
     answers: Records,
     authorities: Records,
     additionals: Records,
 }
 
+impl Default for Message {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 /// Message represents a DNS message.
 impl Message {
     /// new creates a new message.
@@ -70,13 +85,15 @@ impl Message {
         }
     }
 
+    /// builder returns a new builder of a query, such as `Message::builder().response().answer(record).build()?`.
+    pub fn builder() -> MessageBuilder {
+        MessageBuilder::new()
+    }
+
     /// from_bytes creates a new message from the specified bytes.
     pub fn from_bytes(msg_bytes: &[u8]) -> Result<Message> {
         let mut msg = Message::new();
-        let ret = msg.parse_bytes(msg_bytes);
-        if ret.is_err() {
-            return Err(ret.err().unwrap());
-        };
+        msg.parse_bytes(msg_bytes)?;
         Ok(msg)
     }
 
@@ -135,6 +152,11 @@ impl Message {
         }
     }
 
+    /// set_opcode sets the kind of query.
+    pub fn set_opcode(&mut self, opcode: Opcode) {
+        self.header[2] = (self.header[2] & 0x87) | ((opcode as u8) << 3);
+    }
+
     /// aa returns the authoritative answer bit.
     /// RFC 6762: 18.4. AA (Authoritative Answer) Bit
     /// In query messages, the Authoritative Answer bit MUST be zero on transmission, and MUST be ignored on reception.
@@ -143,6 +165,15 @@ impl Message {
         (self.header[2] & 0x04) == 0x04
     }
 
+    /// set_aa sets the authoritative answer bit.
+    pub fn set_aa(&mut self, aa: bool) {
+        if aa {
+            self.header[2] |= 0x04;
+        } else {
+            self.header[2] &= !0x04;
+        }
+    }
+
     /// tc returns the truncated bit.
     /// RFC 6762: 18.5. TC (Truncated) Bit
     /// In query messages, if the TC bit is set, it means that additional Known-Answer records may be following shortly. A responder SHOULD record this fact, and wait for those additional Known-Answer records, before deciding whether to respond. If the TC bit is clear, it means that the querying host has no additional Known Answers.
@@ -151,6 +182,15 @@ impl Message {
         (self.header[2] & 0x02) == 0x02
     }
 
+    /// set_tc sets the truncated bit.
+    pub fn set_tc(&mut self, tc: bool) {
+        if tc {
+            self.header[2] |= 0x02;
+        } else {
+            self.header[2] &= !0x02;
+        }
+    }
+
     /// rd returns the recursion desired bit.
     /// RFC 6762: 18.6. RD (Recursion Desired) Bit
     /// In both multicast query and multicast response messages, the Recursion Desired bit SHOULD be zero on transmission, and MUST be ignored on reception.
@@ -158,6 +198,15 @@ impl Message {
         (self.header[2] & 0x01) == 0x01
     }
 
+    /// set_rd sets the recursion desired bit, such as for the unicast queries to a recursive resolver.
+    pub fn set_rd(&mut self, rd: bool) {
+        if rd {
+            self.header[2] |= 0x01;
+        } else {
+            self.header[2] &= !0x01;
+        }
+    }
+
     /// ra returns the recursion available bit.
     /// RFC 6762: 18.7. RA (Recursion Available) Bit
     /// In both multicast query and multicast response messages, the Recursion Available bit MUST be zero on transmission, and MUST be ignored on reception.
@@ -165,6 +214,15 @@ impl Message {
         (self.header[3] & 0x80) == 0x80
     }
 
+    /// set_ra sets the recursion available bit.
+    pub fn set_ra(&mut self, ra: bool) {
+        if ra {
+            self.header[3] |= 0x80;
+        } else {
+            self.header[3] &= !0x80;
+        }
+    }
+
     /// z returns the zero bit.
     /// RFC 6762: 18.8. Z (Zero) Bit
     /// In both query and response messages, the Zero bit MUST be zero on transmission, and MUST be ignored on reception.
@@ -172,6 +230,15 @@ impl Message {
         (self.header[3] & 0x40) == 0x40
     }
 
+    /// set_z sets the zero bit.
+    pub fn set_z(&mut self, z: bool) {
+        if z {
+            self.header[3] |= 0x40;
+        } else {
+            self.header[3] &= !0x40;
+        }
+    }
+
     /// ad returns the authentic data bit.
     /// RFC 6762: 18.9. AD (Authentic Data) Bit
     /// In both multicast query and multicast response messages, the Authentic Data bit [RFC2535] MUST be zero on transmission, and MUST be ignored on reception.
@@ -179,6 +246,15 @@ impl Message {
         (self.header[3] & 0x20) == 0x20
     }
 
+    /// set_ad sets the authentic data bit.
+    pub fn set_ad(&mut self, ad: bool) {
+        if ad {
+            self.header[3] |= 0x20;
+        } else {
+            self.header[3] &= !0x20;
+        }
+    }
+
     /// cd returns the checking disabled bit.
     /// RFC 6762: 18.10. CD (Checking Disabled) Bit
     /// In both multicast query and multicast response messages, the Checking Disabled bit [RFC2535] MUST be zero on transmission, and MUST be ignored on reception.
@@ -186,6 +262,15 @@ impl Message {
         (self.header[3] & 0x10) == 0x10
     }
 
+    /// set_cd sets the checking disabled bit.
+    pub fn set_cd(&mut self, cd: bool) {
+        if cd {
+            self.header[3] |= 0x10;
+        } else {
+            self.header[3] &= !0x10;
+        }
+    }
+
     /// response_code returns the checking disabled bit.
     /// RFC 6762: 18.11. RCODE (Response Code)
     /// In both multicast query and multicast response messages, the Response Code MUST be zero on transmission. Multicast DNS messages received with non-zero Response Codes MUST be silently ignored.
@@ -202,6 +287,33 @@ impl Message {
         }
     }
 
+    /// set_response_code sets the response code.
+    pub fn set_response_code(&mut self, code: ResponseCode) {
+        self.header[3] = (self.header[3] & 0xF0) | (code as u8);
+    }
+
+    /// flags returns the raw 16 bits of the header following the query identifier, which are QR, OPCODE, AA, TC, RD, RA, Z, AD, CD and RCODE.
+    pub fn flags(&self) -> u16 {
+        ((self.header[2] as u16) << 8) | (self.header[3] as u16)
+    }
+
+    /// set_flags sets the raw 16 bits of the header following the query identifier.
+    pub fn set_flags(&mut self, flags: u16) {
+        self.header[2] = ((flags >> 8) & 0xFF) as u8;
+        self.header[3] = (flags & 0xFF) as u8;
+    }
+
+    /// extended_response_code returns the 12-bit response code, whose upper 8 bits are carried by the OPT record if any.
+    /// RFC 6891: 6.1.3. OPT Record TTL Field Use
+    /// EXTENDED-RCODE: Forms the upper 8 bits of extended 12-bit RCODE (together with the 4 bits defined in [RFC1035]).
+    pub fn extended_response_code(&self) -> u16 {
+        let rcode = (self.header[3] & 0x0F) as u16;
+        match OPTRecord::from_message(self) {
+            Some(opt) => ((opt.extended_rcode() as u16) << 4) | rcode,
+            None => rcode,
+        }
+    }
+
     fn set_number_of_entries(&mut self, offset: usize, num: u16) {
         self.header[offset] = ((num >> 8) & 0xFF) as u8;
         self.header[offset + 1] = (num & 0xFF) as u8;
@@ -252,13 +364,13 @@ impl Message {
     }
 
     /// add_question adds the specified question.
-    pub fn add_question(&mut self, question: Record) {
+    pub fn add_question(&mut self, question: Question) {
         self.questions.push(question);
         self.set_qd_count(self.questions.len() as u16);
     }
 
     /// questions returns the questions.
-    pub fn questions(&self) -> &Records {
+    pub fn questions(&self) -> &Questions {
         &self.questions
     }
 
@@ -295,25 +407,45 @@ impl Message {
         &self.additionals
     }
 
+    /// retain_additionals keeps only the additionals for which the specified predicate returns true.
+    pub fn retain_additionals<F>(&mut self, f: F)
+    where
+        F: FnMut(&Record) -> bool,
+    {
+        self.additionals.retain(f);
+        self.set_ar_count(self.additionals.len() as u16);
+    }
+
+    /// set_opt sets the specified OPT record in the additional section, replacing the current one if any.
+    /// RFC 6891: 6.1.1. Basic Elements
+    pub fn set_opt(&mut self, opt: &OPTRecord) -> Result<()> {
+        let record = opt.to_record()?;
+        self.additionals.retain(|record| record.typ() != Type::OPT);
+        self.add_additional(record);
+        Ok(())
+    }
+
+    /// opt returns the OPT record in the additional section, if any.
+    pub fn opt(&self) -> Option<OPTRecord> {
+        OPTRecord::from_message(self)
+    }
+
     /// resource_records returns the all resource records.
     pub fn resource_records(&self) -> ResourceRecords {
         let mut resouce_records: Vec<Box<dyn ResourceRecord>> = Vec::new();
         for answer in self.answers() {
-            match answer.to_resource_record() {
-                Ok(resource_record) => resouce_records.push(resource_record),
-                Err(_) => {}
+            if let Ok(resource_record) = answer.to_resource_record() {
+                resouce_records.push(resource_record);
             }
         }
         for authority in self.authorities() {
-            match authority.to_resource_record() {
-                Ok(resource_record) => resouce_records.push(resource_record),
-                Err(_) => {}
+            if let Ok(resource_record) = authority.to_resource_record() {
+                resouce_records.push(resource_record);
             }
         }
         for additional in self.additionals() {
-            match additional.to_resource_record() {
-                Ok(resource_record) => resouce_records.push(resource_record),
-                Err(_) => {}
+            if let Ok(resource_record) = additional.to_resource_record() {
+                resouce_records.push(resource_record);
             }
         }
         resouce_records
@@ -331,8 +463,7 @@ impl Message {
         // Questions
         let qd_count = self.qd_count();
         for _ in 0..qd_count {
-            let mut question = Record::new();
-            question.parse_request_record(&mut reader)?;
+            let question = Question::from_reader(&mut reader)?;
             self.questions.push(question);
         }
 
@@ -363,85 +494,110 @@ impl Message {
         Ok(())
     }
 
-    /// find_record returns the record of the specified name.
+    /// find_record returns the resource record of the specified name.
     pub fn find_record(&self, name: &str) -> Option<&Record> {
-        for question in self.questions() {
-            if question.name() == name {
-                return Some(question);
-            }
-        }
-        for answer in self.answers() {
-            if answer.name() == name {
-                return Some(answer);
-            }
-        }
-        for authority in self.authorities() {
-            if authority.name() == name {
-                return Some(authority);
-            }
-        }
-        for additional in self.additionals() {
-            if additional.name() == name {
-                return Some(additional);
-            }
-        }
-        None
+        self.answers()
+            .iter()
+            .chain(self.authorities())
+            .chain(self.additionals())
+            .find(|record| record.name() == name)
     }
 
-    /// to_string returns the message as a string.
-    pub fn to_string(&self) -> String {
-        let mut msg_str = String::new();
-        struct Record(String, String, String);
-        let mut records = Vec::new();
-        for rrecord in self.resource_records() {
-            let record = Record(
-                rrecord.name().to_string(),
-                rrecord.typ().to_string(),
-                rrecord.content().to_string(),
-            );
-            records.push(record);
-        }
-        for record in records {
-            msg_str.push_str(&format!("{} {} {}\n", record.0, record.1, record.2));
-        }
-        msg_str
+    /// to_bytes returns the message as bytes, compressing the repeated names.
+    pub fn to_bytes(&self) -> Result<Vec<u8>> {
+        self.to_bytes_with_compression(true)
     }
 
-    /// to_bytes returns the message as bytes.
-    pub fn to_bytes(&self) -> Result<Vec<u8>> {
+    /// to_bytes_with_compression returns the message as bytes, compressing the repeated names only if specified,
+    /// such as for the peers which mishandle the pointers.
+    pub fn to_bytes_with_compression(&self, compress: bool) -> Result<Vec<u8>> {
         let mut w = Writer::new();
+        w.set_name_compression(compress);
         w.write_bytes(&self.header)?;
         for question in self.questions() {
-            w.write_request_record(&question)?;
+            w.write_question(question)?;
         }
         for answer in self.answers() {
-            w.write_response_record(&answer)?;
+            w.write_response_record(answer)?;
         }
         for authority in self.authorities() {
-            w.write_response_record(&authority)?;
+            w.write_response_record(authority)?;
         }
         for additional in self.additionals() {
-            w.write_response_record(&additional)?;
+            w.write_response_record(additional)?;
         }
         Ok(w.to_bytes())
     }
-}
 
-impl Clone for Message {
-    fn clone(&self) -> Message {
-        let mut msg = Message::new();
-        match msg.to_bytes() {
-            Ok(bytes) => {
-                let _ = msg.parse_bytes(&bytes);
+    /// fingerprint returns a stable hash of the content of the message, which is equal for the messages with the same questions and records
+    /// regardless of their order, the case of the names, the name compression, the query identifier and the TTLs except the goodbyes of zero TTL,
+    /// such as to detect the duplicate or looped messages.
+    pub fn fingerprint(&self) -> u64 {
+        let mut hash = fnv1a(FNV_OFFSET_BASIS, &[self.is_response() as u8]);
+        let mut questions: Vec<Vec<u8>> = self
+            .questions()
+            .iter()
+            .map(|q| canonical_entry(q.name(), q.typ(), q.class().to_value(), &[]))
+            .collect();
+        questions.sort();
+        for question in questions {
+            hash = fnv1a(hash, &question);
+        }
+        for (section, records) in [self.answers(), self.authorities(), self.additionals()]
+            .iter()
+            .enumerate()
+        {
+            hash = fnv1a(hash, &[0xff, section as u8]);
+            let mut entries: Vec<Vec<u8>> = records
+                .iter()
+                .map(|r| {
+                    let mut entry =
+                        canonical_entry(r.name(), r.typ(), r.class().to_value(), r.data());
+                    entry.push((r.ttl() == 0) as u8);
+                    entry
+                })
+                .collect();
+            entries.sort();
+            for entry in entries {
+                hash = fnv1a(hash, &entry);
             }
-            Err(_) => {}
         }
-        msg
+        hash
     }
 }
 
+// canonical_entry returns the length-prefixed fields of a question or record with the lowercased name.
+fn canonical_entry(name: &str, typ: Type, class: u16, data: &[u8]) -> Vec<u8> {
+    let name = name.trim_end_matches('.').to_ascii_lowercase();
+    let mut entry = Vec::with_capacity(name.len() + data.len() + 10);
+    entry.extend_from_slice(&(name.len() as u32).to_be_bytes());
+    entry.extend_from_slice(name.as_bytes());
+    entry.extend_from_slice(&typ.to_value().to_be_bytes());
+    entry.extend_from_slice(&class.to_be_bytes());
+    entry.extend_from_slice(&(data.len() as u32).to_be_bytes());
+    entry.extend_from_slice(data);
+    entry
+}
+
+fn fnv1a(mut hash: u64, bytes: &[u8]) -> u64 {
+    for b in bytes {
+        hash ^= *b as u64;
+        hash = hash.wrapping_mul(FNV_PRIME);
+    }
+    hash
+}
+
 impl fmt::Display for Message {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "{}", self.to_string())
+        for rrecord in self.resource_records() {
+            writeln!(
+                f,
+                "{} {} {}",
+                rrecord.name(),
+                rrecord.typ(),
+                rrecord.content()
+            )?;
+        }
+        Ok(())
     }
 }
@@ -0,0 +1,225 @@
+// Copyright (C) 2024 Satoshi Konno All rights reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//    http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::dns::error::{Error, ErrorKind, Result};
+use crate::dns::message::{Message, ResponseCode, QR};
+use crate::dns::opt_record::OPTRecord;
+use crate::dns::question::Question;
+use crate::dns::record::Record;
+use crate::dns::typ::Type;
+
+/// MessageBuilder represents a builder of a message, which checks the sections and the flags when the message is built.
+pub struct MessageBuilder {
+    qr: QR,
+    id: u16,
+    aa: bool,
+    tc: bool,
+    rd: bool,
+    rcode: ResponseCode,
+    questions: Vec<Question>,
+    answers: Vec<Record>,
+    authorities: Vec<Record>,
+    additionals: Vec<Record>,
+    opt: Option<OPTRecord>,
+}
+
+impl MessageBuilder {
+    /// new creates a new builder of a query.
+    pub fn new() -> MessageBuilder {
+        MessageBuilder {
+            qr: QR::Query,
+            id: 0,
+            aa: false,
+            tc: false,
+            rd: false,
+            rcode: ResponseCode::NoError,
+            questions: Vec::new(),
+            answers: Vec::new(),
+            authorities: Vec::new(),
+            additionals: Vec::new(),
+            opt: None,
+        }
+    }
+
+    /// query makes the message a query, which is not authoritative.
+    pub fn query(mut self) -> MessageBuilder {
+        self.qr = QR::Query;
+        self.aa = false;
+        self
+    }
+
+    /// response makes the message a response, which is authoritative as Multicast DNS responses are.
+    /// RFC 6762: 18.4. AA (Authoritative Answer) Bit
+    pub fn response(mut self) -> MessageBuilder {
+        self.qr = QR::Response;
+        self.aa = true;
+        self
+    }
+
+    /// id sets the query identifier.
+    pub fn id(mut self, id: u16) -> MessageBuilder {
+        self.id = id;
+        self
+    }
+
+    /// authoritative sets the authoritative answer bit.
+    pub fn authoritative(mut self, aa: bool) -> MessageBuilder {
+        self.aa = aa;
+        self
+    }
+
+    /// truncated sets the truncated bit.
+    pub fn truncated(mut self, tc: bool) -> MessageBuilder {
+        self.tc = tc;
+        self
+    }
+
+    /// recursion_desired sets the recursion desired bit.
+    pub fn recursion_desired(mut self, rd: bool) -> MessageBuilder {
+        self.rd = rd;
+        self
+    }
+
+    /// response_code sets the response code.
+    pub fn response_code(mut self, rcode: ResponseCode) -> MessageBuilder {
+        self.rcode = rcode;
+        self
+    }
+
+    /// question adds the specified question.
+    pub fn question(mut self, question: Question) -> MessageBuilder {
+        self.questions.push(question);
+        self
+    }
+
+    /// questions adds the specified questions.
+    pub fn questions<I: IntoIterator<Item = Question>>(mut self, questions: I) -> MessageBuilder {
+        self.questions.extend(questions);
+        self
+    }
+
+    /// answer adds the specified answer record.
+    pub fn answer(mut self, record: Record) -> MessageBuilder {
+        self.answers.push(record);
+        self
+    }
+
+    /// answers adds the specified answer records.
+    pub fn answers<I: IntoIterator<Item = Record>>(mut self, records: I) -> MessageBuilder {
+        self.answers.extend(records);
+        self
+    }
+
+    /// authority adds the specified authority record.
+    pub fn authority(mut self, record: Record) -> MessageBuilder {
+        self.authorities.push(record);
+        self
+    }
+
+    /// additional adds the specified additional record.
+    pub fn additional(mut self, record: Record) -> MessageBuilder {
+        self.additionals.push(record);
+        self
+    }
+
+    /// additionals adds the specified additional records.
+    pub fn additionals<I: IntoIterator<Item = Record>>(mut self, records: I) -> MessageBuilder {
+        self.additionals.extend(records);
+        self
+    }
+
+    /// edns attaches the specified OPT record to the additional section, replacing the one attached before.
+    /// RFC 6891: 6.1.1. Basic Elements
+    pub fn edns(mut self, opt: OPTRecord) -> MessageBuilder {
+        self.opt = Some(opt);
+        self
+    }
+
+    /// build returns the message, or an error if the sections or the flags are inconsistent.
+    pub fn build(mut self) -> Result<Message> {
+        if let Some(opt) = &self.opt {
+            self.additionals.push(opt.to_record()?);
+        }
+        for (section, n) in [
+            ("question", self.questions.len()),
+            ("answer", self.answers.len()),
+            ("authority", self.authorities.len()),
+            ("additional", self.additionals.len()),
+        ] {
+            if (u16::MAX as usize) < n {
+                return Err(invalid(&format!("Too many {} records: {}", section, n)));
+            }
+        }
+        if self.qr == QR::Query {
+            // RFC 6762: 18.4. AA (Authoritative Answer) Bit
+            // In query messages, the Authoritative Answer bit MUST be zero on transmission.
+            if self.aa {
+                return Err(invalid("Authoritative query"));
+            }
+            // RFC 6762: 7.1. Known-Answer Suppression
+            if self.questions.is_empty() && !self.answers.is_empty() {
+                return Err(invalid("Known answers without questions"));
+            }
+            if !matches!(self.rcode, ResponseCode::NoError) {
+                return Err(invalid("Response code in a query"));
+            }
+        }
+        // RFC 6891: 6.1.1. Basic Elements
+        // If a query message with more than one OPT RR is received, a FORMERR (RCODE=1) MUST be returned.
+        let misplaced_opt = self
+            .answers
+            .iter()
+            .chain(&self.authorities)
+            .any(|record| record.typ() == Type::OPT);
+        let opt_count = self
+            .additionals
+            .iter()
+            .filter(|record| record.typ() == Type::OPT)
+            .count();
+        if misplaced_opt || 1 < opt_count {
+            return Err(invalid("OPT record outside of a single additional record"));
+        }
+
+        let mut msg = Message::new();
+        msg.set_id(self.id);
+        msg.set_qr(self.qr);
+        msg.set_aa(self.aa);
+        msg.set_tc(self.tc);
+        msg.set_rd(self.rd);
+        msg.set_response_code(self.rcode);
+        for question in self.questions {
+            msg.add_question(question);
+        }
+        for record in self.answers {
+            msg.add_answer(record);
+        }
+        for record in self.authorities {
+            msg.add_authority(record);
+        }
+        for record in self.additionals {
+            msg.add_additional(record);
+        }
+        Ok(msg)
+    }
+}
+
+impl Default for MessageBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn invalid(msg: &str) -> Error {
+    Error::new(ErrorKind::InvalidMessage, msg)
+}
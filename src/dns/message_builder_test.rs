@@ -0,0 +1,101 @@
+// Copyright (C) 2024 Satoshi Konno All rights reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//    http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+#[cfg(test)]
+mod tests {
+
+    use crate::dns::{ARecord, ErrorKind, Message, OPTRecord, Question, ResponseCode, Type};
+
+    #[test]
+    fn message_builder() {
+        let mut a = "192.168.1.10".parse::<ARecord>().unwrap();
+        a.set_name("host.local");
+
+        let msg = Message::builder()
+            .response()
+            .id(0x1234)
+            .answer(a.to_record())
+            .additional(OPTRecord::new(1232).to_record().unwrap())
+            .build()
+            .unwrap();
+        assert!(msg.is_response());
+        assert!(msg.aa());
+        assert_eq!(msg.id(), 0x1234);
+        assert_eq!(msg.an_count(), 1);
+        assert_eq!(msg.ar_count(), 1);
+        let parsed = Message::from_bytes(&msg.to_bytes().unwrap()).unwrap();
+        assert!(parsed == msg);
+
+        let msg = Message::builder()
+            .question(Question::new("_http._tcp.local", Type::PTR))
+            .answer(a.to_record())
+            .truncated(true)
+            .build()
+            .unwrap();
+        assert!(msg.is_query());
+        assert!(!msg.aa());
+        assert!(msg.tc());
+        assert_eq!(msg.qd_count(), 1);
+    }
+
+    #[test]
+    fn message_builder_errors() {
+        let mut a = "192.168.1.10".parse::<ARecord>().unwrap();
+        a.set_name("host.local");
+        let opt = OPTRecord::new(1232).to_record().unwrap();
+
+        let builders = vec![
+            Message::builder().query().authoritative(true),
+            Message::builder().query().answer(a.to_record()),
+            Message::builder().response_code(ResponseCode::Refused),
+            Message::builder().response().answer(opt.clone()),
+            Message::builder()
+                .response()
+                .additional(opt.clone())
+                .additional(opt),
+        ];
+        for builder in builders {
+            let err = builder.build().err().unwrap();
+            assert_eq!(err.kind(), ErrorKind::InvalidMessage);
+        }
+    }
+
+    #[test]
+    fn message_builder_sections() {
+        let mut a = "192.168.1.10".parse::<ARecord>().unwrap();
+        a.set_name("host.local");
+        let mut b = "192.168.1.11".parse::<ARecord>().unwrap();
+        b.set_name("host.local");
+
+        let msg = Message::builder()
+            .questions(vec![
+                Question::new("host.local", Type::A),
+                Question::new("host.local", Type::AAAA),
+            ])
+            .answers(vec![a.to_record(), b.to_record()])
+            .build()
+            .unwrap();
+        assert_eq!(msg.qd_count(), 2);
+        assert_eq!(msg.an_count(), 2);
+
+        let msg = Message::builder()
+            .response()
+            .answer(a.to_record())
+            .additionals([b.to_record(), OPTRecord::new(1232).to_record().unwrap()])
+            .build()
+            .unwrap();
+        assert_eq!(msg.an_count(), 1);
+        assert_eq!(msg.ar_count(), 2);
+    }
+}
@@ -0,0 +1,429 @@
+// Copyright (C) 2024 Satoshi Konno All rights reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//    http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::borrow::Cow;
+use std::fmt;
+
+use crate::dns::class::{Class, CACHE_FLUSH_MASK, CLASS_MASK, UNICAST_RESPONSE_MASK};
+use crate::dns::error::{Error, ErrorKind, Result};
+use crate::dns::message::Message;
+use crate::dns::question::Question;
+use crate::dns::reader::{Reader, MAX_NAME_LEN, MAX_POINTER_HOPS};
+use crate::dns::record::{embedded_names, expand_names, Record};
+use crate::dns::typ::Type;
+
+const HEADER_SIZE: usize = 12;
+const POINTER_MASK: u8 = 0xc0;
+
+/// NameRef represents a domain name in a message buffer, which is decoded only when it is formatted.
+#[derive(Copy, Clone)]
+pub struct NameRef<'a> {
+    buffer: &'a [u8],
+    offset: usize,
+}
+
+impl<'a> NameRef<'a> {
+    // parse validates the name at the specified offset, and returns it with the offset following it.
+    fn parse(buffer: &'a [u8], offset: usize) -> Result<(NameRef<'a>, usize)> {
+        let mut labels = Labels::new(buffer, offset);
+        while labels.next().is_some() {}
+        match (labels.error, labels.end) {
+            (Some((kind, cursor)), _) => Err(Error::from_bytes_with_kind(kind, buffer, cursor)),
+            (None, Some(end)) => Ok((NameRef { buffer, offset }, end)),
+            (None, None) => Err(Error::from_bytes(buffer, offset)),
+        }
+    }
+
+    /// labels returns the labels of the name, following the compression pointers.
+    pub fn labels(&self) -> Labels<'a> {
+        Labels::new(self.buffer, self.offset)
+    }
+
+    /// eq_ignore_ascii_case returns true if the name equals the specified dotted name ignoring the ASCII case, without decoding the name.
+    pub fn eq_ignore_ascii_case(&self, name: &str) -> bool {
+        let mut labels = self.labels();
+        for label in name.split('.').filter(|label| !label.is_empty()) {
+            match labels.next() {
+                Some(l) if l.eq_ignore_ascii_case(label.as_bytes()) => {}
+                _ => return false,
+            }
+        }
+        labels.next().is_none()
+    }
+}
+
+impl fmt::Display for NameRef<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (n, label) in self.labels().enumerate() {
+            if 0 < n {
+                write!(f, ".")?;
+            }
+            write!(f, "{}", String::from_utf8_lossy(label))?;
+        }
+        Ok(())
+    }
+}
+
+/// Labels represents an iterator over the labels of a name.
+pub struct Labels<'a> {
+    buffer: &'a [u8],
+    cursor: usize,
+    segment: usize,
+    name_len: usize,
+    hops: usize,
+    end: Option<usize>,
+    error: Option<(ErrorKind, usize)>,
+}
+
+impl<'a> Labels<'a> {
+    fn new(buffer: &'a [u8], offset: usize) -> Labels<'a> {
+        Labels {
+            buffer,
+            cursor: offset,
+            segment: offset,
+            name_len: 1,
+            hops: 0,
+            end: None,
+            error: None,
+        }
+    }
+
+    // stop ends the iteration, and records why the name is malformed.
+    fn stop(&mut self, kind: ErrorKind) -> Option<&'a [u8]> {
+        self.error = Some((kind, self.cursor));
+        None
+    }
+}
+
+impl<'a> Iterator for Labels<'a> {
+    type Item = &'a [u8];
+
+    // next returns the next label with the same bounds as Reader::read_name, so a malformed name
+    // ends the iteration instead of looping.
+    fn next(&mut self) -> Option<&'a [u8]> {
+        loop {
+            if self.error.is_some() {
+                return None;
+            }
+            let Some(&label_len) = self.buffer.get(self.cursor) else {
+                return self.stop(ErrorKind::Truncated);
+            };
+            if label_len == 0 {
+                if self.end.is_none() {
+                    self.end = Some(self.cursor + 1);
+                }
+                return None;
+            }
+            if label_len & POINTER_MASK == POINTER_MASK {
+                let Some(&low) = self.buffer.get(self.cursor + 1) else {
+                    return self.stop(ErrorKind::Truncated);
+                };
+                // RFC 1035: 4.1.4. Message compression
+                // Each pointer has to precede the segment of the name it is in, so the targets are strictly decreasing.
+                let target = ((label_len & !POINTER_MASK) as usize) << 8 | low as usize;
+                self.hops += 1;
+                if self.segment <= target || MAX_POINTER_HOPS < self.hops {
+                    return self.stop(ErrorKind::InvalidLabel);
+                }
+                self.end.get_or_insert(self.cursor + 2);
+                self.cursor = target;
+                self.segment = target;
+                continue;
+            }
+            if label_len & POINTER_MASK != 0 {
+                return self.stop(ErrorKind::InvalidLabel);
+            }
+            self.name_len += 1 + label_len as usize;
+            if MAX_NAME_LEN < self.name_len {
+                return self.stop(ErrorKind::InvalidLabel);
+            }
+            let start = self.cursor + 1;
+            let Some(label) = self.buffer.get(start..start + label_len as usize) else {
+                return self.stop(ErrorKind::Truncated);
+            };
+            self.cursor = start + label_len as usize;
+            return Some(label);
+        }
+    }
+}
+
+/// QuestionRef represents a question which references the message buffer.
+#[derive(Copy, Clone)]
+pub struct QuestionRef<'a> {
+    name: NameRef<'a>,
+    typ: Type,
+    class_value: u16,
+}
+
+impl<'a> QuestionRef<'a> {
+    /// name returns the name of the question.
+    pub fn name(&self) -> NameRef<'a> {
+        self.name
+    }
+
+    /// typ returns the type of the question.
+    pub fn typ(&self) -> Type {
+        self.typ
+    }
+
+    /// class returns the class of the question.
+    pub fn class(&self) -> Class {
+        Class::from_value(self.class_value & CLASS_MASK)
+    }
+
+    /// unicast_response returns true if the question asks for a unicast response.
+    pub fn unicast_response(&self) -> bool {
+        (self.class_value & UNICAST_RESPONSE_MASK) != 0
+    }
+
+    /// to_question returns the owned question.
+    pub fn to_question(&self) -> Question {
+        let mut question = Question::new(&self.name.to_string(), self.typ);
+        question.set_class(self.class());
+        question.set_unicast_response(self.unicast_response());
+        question
+    }
+}
+
+/// RecordRef represents a resource record which references the message buffer.
+#[derive(Copy, Clone)]
+pub struct RecordRef<'a> {
+    buffer: &'a [u8],
+    name: NameRef<'a>,
+    typ: Type,
+    class_value: u16,
+    ttl: u32,
+    data_offset: usize,
+    data_len: usize,
+}
+
+impl<'a> RecordRef<'a> {
+    /// name returns the owner name of the record.
+    pub fn name(&self) -> NameRef<'a> {
+        self.name
+    }
+
+    /// typ returns the type of the record.
+    pub fn typ(&self) -> Type {
+        self.typ
+    }
+
+    /// class returns the class of the record.
+    pub fn class(&self) -> Class {
+        Class::from_value(self.class_value & CLASS_MASK)
+    }
+
+    /// cache_flush returns the cache-flush bit of the record.
+    pub fn cache_flush(&self) -> bool {
+        self.typ != Type::OPT && (self.class_value & CACHE_FLUSH_MASK) != 0
+    }
+
+    /// ttl returns the TTL of the record.
+    pub fn ttl(&self) -> u32 {
+        self.ttl
+    }
+
+    /// raw_data returns the data of the record as received, whose embedded names may point into the message.
+    pub fn raw_data(&self) -> &'a [u8] {
+        &self.buffer[self.data_offset..self.data_offset + self.data_len]
+    }
+
+    /// data returns the data of the record, which is borrowed unless an embedded name is compressed and has to be expanded.
+    pub fn data(&self) -> Cow<'a, [u8]> {
+        let raw = self.raw_data();
+        let (prefix_len, names) = match embedded_names(self.typ) {
+            Some(embedded) => embedded,
+            None => return Cow::Borrowed(raw),
+        };
+        if raw.len() <= prefix_len || !has_pointer(&raw[prefix_len..], names) {
+            return Cow::Borrowed(raw);
+        }
+        let reader = Reader::from_bytes(self.buffer);
+        let expanded = expand_names(&reader, self.typ, self.data_offset, raw);
+        match expanded {
+            Some(data) => Cow::Owned(data),
+            None => Cow::Borrowed(raw),
+        }
+    }
+
+    /// to_record returns the owned record.
+    pub fn to_record(&self) -> Record {
+        let mut record = Record::new();
+        record.set_name(&self.name.to_string());
+        record.set_typ(self.typ);
+        if self.typ == Type::OPT {
+            record.set_udp_payload_size(self.class_value);
+        } else {
+            record.set_class(self.class());
+            record.set_cache_flush(self.cache_flush());
+        }
+        record.set_ttl(self.ttl);
+        record.set_data(self.data().into_owned());
+        record
+    }
+}
+
+/// MessageRef represents a message parsed without copying, whose names and data reference the received buffer,
+/// such as to inspect the many announcements of a busy network and to copy only the interesting records.
+pub struct MessageRef<'a> {
+    buffer: &'a [u8],
+    questions: Vec<QuestionRef<'a>>,
+    answers: Vec<RecordRef<'a>>,
+    authorities: Vec<RecordRef<'a>>,
+    additionals: Vec<RecordRef<'a>>,
+}
+
+impl<'a> MessageRef<'a> {
+    /// from_bytes parses the specified message bytes without copying them.
+    pub fn from_bytes(buffer: &'a [u8]) -> Result<MessageRef<'a>> {
+        if buffer.len() < HEADER_SIZE {
+            return Err(Error::from_bytes(buffer, 0));
+        }
+        let mut msg = MessageRef {
+            buffer,
+            questions: Vec::new(),
+            answers: Vec::new(),
+            authorities: Vec::new(),
+            additionals: Vec::new(),
+        };
+        let mut reader = Reader::from_bytes(buffer);
+        reader.set_offset(HEADER_SIZE);
+        for _ in 0..msg.qd_count() {
+            let (name, offset) = NameRef::parse(buffer, reader.offset())?;
+            reader.set_offset(offset);
+            let typ = Type::from_value(reader.read_u16()?);
+            let class_value = reader.read_u16()?;
+            msg.questions.push(QuestionRef {
+                name,
+                typ,
+                class_value,
+            });
+        }
+        for n in 0..3 {
+            for _ in 0..msg.count(6 + n * 2) {
+                let record = parse_record(buffer, &mut reader)?;
+                match n {
+                    0 => msg.answers.push(record),
+                    1 => msg.authorities.push(record),
+                    _ => msg.additionals.push(record),
+                }
+            }
+        }
+        Ok(msg)
+    }
+
+    fn count(&self, offset: usize) -> u16 {
+        u16::from_be_bytes([self.buffer[offset], self.buffer[offset + 1]])
+    }
+
+    /// id returns the query identifier.
+    pub fn id(&self) -> u16 {
+        self.count(0)
+    }
+
+    /// is_query returns true if the message is a query.
+    pub fn is_query(&self) -> bool {
+        (self.buffer[2] & 0x80) == 0
+    }
+
+    /// is_response returns true if the message is a response.
+    pub fn is_response(&self) -> bool {
+        !self.is_query()
+    }
+
+    /// tc returns the truncated bit.
+    pub fn tc(&self) -> bool {
+        (self.buffer[2] & 0x02) == 0x02
+    }
+
+    /// qd_count returns the number of the questions.
+    pub fn qd_count(&self) -> u16 {
+        self.count(4)
+    }
+
+    /// questions returns the questions.
+    pub fn questions(&self) -> &[QuestionRef<'a>] {
+        &self.questions
+    }
+
+    /// answers returns the answer records.
+    pub fn answers(&self) -> &[RecordRef<'a>] {
+        &self.answers
+    }
+
+    /// authorities returns the authority records.
+    pub fn authorities(&self) -> &[RecordRef<'a>] {
+        &self.authorities
+    }
+
+    /// additionals returns the additional records.
+    pub fn additionals(&self) -> &[RecordRef<'a>] {
+        &self.additionals
+    }
+
+    /// records returns the records of all sections.
+    pub fn records(&self) -> impl Iterator<Item = &RecordRef<'a>> {
+        self.answers
+            .iter()
+            .chain(&self.authorities)
+            .chain(&self.additionals)
+    }
+
+    /// to_message returns the owned message.
+    pub fn to_message(&self) -> Result<Message> {
+        Message::from_bytes(self.buffer)
+    }
+}
+
+fn parse_record<'a>(buffer: &'a [u8], reader: &mut Reader<'a>) -> Result<RecordRef<'a>> {
+    let (name, offset) = NameRef::parse(buffer, reader.offset())?;
+    reader.set_offset(offset);
+    let typ = Type::from_value(reader.read_u16()?);
+    let class_value = reader.read_u16()?;
+    let ttl = reader.read_u32()?;
+    let data_len = reader.read_u16()? as usize;
+    let data_offset = reader.offset();
+    if buffer.len() < data_offset + data_len {
+        return Err(Error::from_bytes(buffer, data_offset));
+    }
+    reader.set_offset(data_offset + data_len);
+    Ok(RecordRef {
+        buffer,
+        name,
+        typ,
+        class_value,
+        ttl,
+        data_offset,
+        data_len,
+    })
+}
+
+// has_pointer returns true if any of the specified number of the names at the start of the bytes is compressed.
+fn has_pointer(bytes: &[u8], names: usize) -> bool {
+    let mut cursor = 0;
+    for _ in 0..names {
+        loop {
+            match bytes.get(cursor) {
+                None => return false,
+                Some(0) => {
+                    cursor += 1;
+                    break;
+                }
+                Some(&label_len) if label_len & POINTER_MASK == POINTER_MASK => return true,
+                Some(&label_len) => cursor += 1 + label_len as usize,
+            }
+        }
+    }
+    false
+}
@@ -0,0 +1,90 @@
+// Copyright (C) 2024 Satoshi Konno All rights reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//    http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+#[cfg(test)]
+mod tests {
+
+    use std::borrow::Cow;
+
+    use crate::dns::{ARecord, ErrorKind, Message, MessageRef, PTRRecord};
+
+    #[test]
+    fn message_ref() {
+        let bytes = include_bytes!("log/matter-spec-120-4.3.1.13-dns-sd.bin");
+        let msg = Message::from_bytes(bytes).unwrap();
+        let msg_ref = MessageRef::from_bytes(bytes).unwrap();
+
+        assert_eq!(msg_ref.id(), msg.id());
+        assert_eq!(msg_ref.is_response(), msg.is_response());
+        assert_eq!(msg_ref.questions().len(), msg.questions().len());
+        assert_eq!(msg_ref.answers().len(), msg.answers().len());
+        assert_eq!(msg_ref.additionals().len(), msg.additionals().len());
+        for (record_ref, record) in msg_ref.records().zip(
+            msg.answers()
+                .iter()
+                .chain(msg.authorities())
+                .chain(msg.additionals()),
+        ) {
+            assert_eq!(record_ref.name().to_string(), record.name());
+            assert!(record_ref
+                .name()
+                .eq_ignore_ascii_case(&record.name().to_uppercase()));
+            assert_eq!(record_ref.typ(), record.typ());
+            assert_eq!(record_ref.ttl(), record.ttl());
+            assert_eq!(record_ref.cache_flush(), record.cache_flush());
+            assert_eq!(*record_ref.data(), *record.data());
+            assert!(record_ref.to_record() == *record);
+        }
+        assert!(msg_ref.to_message().unwrap() == msg);
+    }
+
+    #[test]
+    fn message_ref_data() {
+        let mut ptr = "test._http._tcp.local.".parse::<PTRRecord>().unwrap();
+        ptr.set_name("_http._tcp.local");
+        let mut a = "192.168.1.10".parse::<ARecord>().unwrap();
+        a.set_name("host.local");
+        let mut msg = Message::new();
+        msg.add_answer(ptr.to_record().unwrap());
+        msg.add_answer(a.to_record());
+        let bytes = msg.to_bytes().unwrap();
+
+        let msg_ref = MessageRef::from_bytes(&bytes).unwrap();
+        let answers = msg_ref.answers();
+        // The PTR target is compressed against the owner name, so its data is expanded.
+        assert!(matches!(answers[0].data(), Cow::Owned(_)));
+        assert_eq!(*answers[0].data(), *msg.answers()[0].data());
+        assert!(answers[0].raw_data().len() < answers[0].data().len());
+        assert!(matches!(answers[1].data(), Cow::Borrowed(_)));
+        assert!(answers[1].name().eq_ignore_ascii_case("HOST.local."));
+        assert!(!answers[1].name().eq_ignore_ascii_case("host"));
+
+        assert!(MessageRef::from_bytes(&bytes[..bytes.len() - 1]).is_err());
+        let mut forward = bytes.clone();
+        forward[12] = 0xc0;
+        forward[13] = 0xff;
+        let err = MessageRef::from_bytes(&forward).err().unwrap();
+        assert_eq!(err.kind(), ErrorKind::InvalidLabel);
+    }
+
+    #[test]
+    fn message_ref_looping_name() {
+        let mut data = vec![
+            0x00, 0x00, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        ];
+        data.extend_from_slice(&[0x01, b'a', 0xc0, 0x0c, 0x00, 0x01, 0x00, 0x01]);
+        let err = MessageRef::from_bytes(&data).err().unwrap();
+        assert_eq!(err.kind(), ErrorKind::InvalidLabel);
+    }
+}
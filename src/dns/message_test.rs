@@ -15,7 +15,14 @@
 #[cfg(test)]
 mod tests {
 
-    use crate::dns::message::Message;
+    use crate::dns::message::{Message, Opcode, ResponseCode, QR};
+    use crate::dns::{
+        AAAARecord, ARecord, Class, LOCRecord, MXRecord, MessageRef, NAPTRRecord, NSECRecord,
+        NSRecord, PTRRecord, Record, SOARecord, SRVRecord, SVCBRecord, TXTRecord, Type, URIRecord,
+        DEFAULT_HOST_RECORD_TTL,
+    };
+    use crate::Service;
+    use std::collections::HashMap;
 
     #[test]
     fn parse_message() {
@@ -49,4 +56,720 @@ mod tests {
             assert_eq!(msg.ar_count(), test.expected.ar_count);
         }
     }
+
+    #[test]
+    fn clone_message() {
+        let mut msg = Message::new();
+        assert!(msg
+            .parse_bytes(include_bytes!("log/matter-spec-120-4.3.1.13-dns-sd.bin"))
+            .is_ok());
+        msg.set_id(0x1234);
+
+        let clone = msg.clone();
+        assert!(clone == msg);
+        assert_eq!(clone.id(), 0x1234);
+        assert_eq!(clone.an_count(), 7);
+        assert_eq!(clone.ar_count(), 5);
+        assert_eq!(clone.to_bytes().unwrap(), msg.to_bytes().unwrap());
+
+        let service = Service::from_message(&msg);
+        assert!(*service.message() == msg);
+    }
+
+    #[test]
+    fn unknown_type() {
+        // RFC 6895: 3.1. RRTYPE IANA Considerations (Private Use)
+        let mut https = Record::new();
+        https.set_name("host.local");
+        https.set_typ(Type::from_value(65280));
+        https.set_class(Class::IN);
+        https.set_ttl(120);
+        https.set_data(vec![0x00, 0x01, 0x00]);
+        let mut msg = Message::new();
+        msg.add_answer(https);
+
+        let parsed = Message::from_bytes(&msg.to_bytes().unwrap()).unwrap();
+        let typ = parsed.answers()[0].typ();
+        assert_eq!(typ, Type::Unknown(65280));
+        assert_eq!(typ.to_value(), 65280);
+        assert_eq!(typ.to_string(), "TYPE65280");
+        assert_eq!(parsed.to_bytes().unwrap(), msg.to_bytes().unwrap());
+        assert_eq!(Type::from_value(0), Type::NONE);
+        assert_eq!(Type::from_value(33), Type::SRV);
+    }
+
+    #[test]
+    fn soa_record() {
+        // A unicast response whose SOA names point to the owner name.
+        let mut msg_bytes = vec![
+            0x00, 0x00, 0x84, 0x00, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00, 0x00,
+        ];
+        msg_bytes.extend_from_slice(b"\x07example\x03com\x00");
+        msg_bytes.extend_from_slice(&[0x00, 0x06, 0x00, 0x01, 0x00, 0x00, 0x0e, 0x10]);
+        let mut rdata = b"\x02ns\xc0\x0c\x05admin\xc0\x0c".to_vec();
+        for value in [2024010101u32, 3600, 600, 86400, 60] {
+            rdata.extend_from_slice(&value.to_be_bytes());
+        }
+        msg_bytes.extend_from_slice(&(rdata.len() as u16).to_be_bytes());
+        msg_bytes.extend_from_slice(&rdata);
+
+        let msg = Message::from_bytes(&msg_bytes).unwrap();
+        let soa = SOARecord::from_record(&msg.answers()[0]).unwrap();
+        assert_eq!(soa.mname(), "ns.example.com");
+        assert_eq!(soa.rname(), "admin.example.com");
+        assert_eq!(soa.serial(), 2024010101);
+        assert_eq!(soa.refresh(), 3600);
+        assert_eq!(soa.retry(), 600);
+        assert_eq!(soa.expire(), 86400);
+        assert_eq!(soa.minimum(), 60);
+
+        let rr = msg.answers()[0].to_resource_record().unwrap();
+        assert_eq!(rr.typ(), Type::SOA);
+        assert_eq!(rr.name(), "example.com");
+        assert_eq!(
+            rr.content(),
+            "ns.example.com. admin.example.com. 2024010101 3600 600 86400 60"
+        );
+
+        let msg_ref = MessageRef::from_bytes(&msg_bytes).unwrap();
+        let record = msg_ref.answers()[0].to_record();
+        assert!(record == msg.answers()[0]);
+
+        let mut parsed: SOARecord = rr.content().parse().unwrap();
+        parsed.set_name("example.com");
+        assert_eq!(parsed.to_record().unwrap().data(), msg.answers()[0].data());
+        assert!("ns.example.com. admin.example.com. 1 2 3"
+            .parse::<SOARecord>()
+            .is_err());
+    }
+
+    #[test]
+    fn mx_record() {
+        // A unicast response whose exchange name points to the owner name.
+        let mut msg_bytes = vec![
+            0x00, 0x00, 0x84, 0x00, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00, 0x00,
+        ];
+        msg_bytes.extend_from_slice(b"\x07example\x03com\x00");
+        msg_bytes.extend_from_slice(&[0x00, 0x0f, 0x00, 0x01, 0x00, 0x00, 0x0e, 0x10]);
+        let rdata = b"\x00\x0a\x04mail\xc0\x0c";
+        msg_bytes.extend_from_slice(&(rdata.len() as u16).to_be_bytes());
+        msg_bytes.extend_from_slice(rdata);
+
+        let msg = Message::from_bytes(&msg_bytes).unwrap();
+        let mx = MXRecord::from_record(&msg.answers()[0]).unwrap();
+        assert_eq!(mx.preference(), 10);
+        assert_eq!(mx.exchange(), "mail.example.com");
+
+        let rr = msg.answers()[0].to_resource_record().unwrap();
+        assert_eq!(rr.typ(), Type::MX);
+        assert_eq!(rr.name(), "example.com");
+        assert_eq!(rr.content(), "10 mail.example.com.");
+
+        let msg_ref = MessageRef::from_bytes(&msg_bytes).unwrap();
+        let record = msg_ref.answers()[0].to_record();
+        assert!(record == msg.answers()[0]);
+
+        let mut parsed: MXRecord = rr.content().parse().unwrap();
+        parsed.set_name("example.com");
+        assert_eq!(parsed.to_record().unwrap().data(), msg.answers()[0].data());
+        assert!("mail.example.com.".parse::<MXRecord>().is_err());
+    }
+
+    #[test]
+    fn ns_record() {
+        // A unicast referral whose authority section names the servers under the owner name.
+        let mut msg_bytes = vec![
+            0x00, 0x00, 0x80, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x02, 0x00, 0x00,
+        ];
+        for ns in [&b"\x03ns1"[..], &b"\x03ns2"[..]] {
+            if msg_bytes.len() == 12 {
+                msg_bytes.extend_from_slice(b"\x07example\x03com\x00");
+            } else {
+                msg_bytes.extend_from_slice(&[0xc0, 0x0c]);
+            }
+            msg_bytes.extend_from_slice(&[0x00, 0x02, 0x00, 0x01, 0x00, 0x00, 0x0e, 0x10]);
+            let mut rdata = ns.to_vec();
+            rdata.extend_from_slice(&[0xc0, 0x0c]);
+            msg_bytes.extend_from_slice(&(rdata.len() as u16).to_be_bytes());
+            msg_bytes.extend_from_slice(&rdata);
+        }
+
+        let msg = Message::from_bytes(&msg_bytes).unwrap();
+        assert_eq!(msg.authorities().len(), 2);
+        let names: Vec<String> = msg
+            .authorities()
+            .iter()
+            .map(|record| NSRecord::from_record(record).unwrap().nsdname().to_string())
+            .collect();
+        assert_eq!(names, vec!["ns1.example.com", "ns2.example.com"]);
+
+        let rr = msg.authorities()[1].to_resource_record().unwrap();
+        assert_eq!(rr.typ(), Type::NS);
+        assert_eq!(rr.name(), "example.com");
+        assert_eq!(rr.content(), "ns2.example.com.");
+
+        let msg_ref = MessageRef::from_bytes(&msg_bytes).unwrap();
+        let record = msg_ref.authorities()[1].to_record();
+        assert!(record == msg.authorities()[1]);
+
+        let mut parsed: NSRecord = rr.content().parse().unwrap();
+        parsed.set_name("example.com");
+        assert_eq!(
+            parsed.to_record().unwrap().data(),
+            msg.authorities()[1].data()
+        );
+        assert!("ns1.example.com. ns2.example.com."
+            .parse::<NSRecord>()
+            .is_err());
+    }
+
+    #[test]
+    fn nsec_record() {
+        // A negative response of an Apple device, whose next domain name points to the owner name.
+        let mut msg_bytes = vec![
+            0x00, 0x00, 0x84, 0x00, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00, 0x00,
+        ];
+        msg_bytes.extend_from_slice(b"\x06iPhone\x05local\x00");
+        msg_bytes.extend_from_slice(&[0x00, 0x2f, 0x80, 0x01, 0x00, 0x00, 0x00, 0x78]);
+        let rdata = [0xc0, 0x0c, 0x00, 0x04, 0x40, 0x00, 0x00, 0x08];
+        msg_bytes.extend_from_slice(&(rdata.len() as u16).to_be_bytes());
+        msg_bytes.extend_from_slice(&rdata);
+
+        let msg = Message::from_bytes(&msg_bytes).unwrap();
+        let nsec = NSECRecord::from_record(&msg.answers()[0]).unwrap();
+        assert_eq!(nsec.name(), "iPhone.local");
+        assert_eq!(nsec.next_domain_name(), "iPhone.local");
+        assert_eq!(nsec.types(), &vec![Type::A, Type::AAAA]);
+        assert!(!nsec.has_type(Type::TXT));
+        let rr = msg.answers()[0].to_resource_record().unwrap();
+        assert_eq!(rr.content(), "iPhone.local. A AAAA");
+
+        let nsec = NSECRecord::new("host.local", &[Type::SRV, Type::TXT, Type::Unknown(1234)]);
+        let record = nsec.to_record().unwrap();
+        let parsed = NSECRecord::from_record(&record).unwrap();
+        assert_eq!(parsed.next_domain_name(), "host.local");
+        assert_eq!(
+            parsed.types(),
+            &vec![Type::TXT, Type::SRV, Type::Unknown(1234)]
+        );
+        assert_eq!(parsed.to_record().unwrap().data(), record.data());
+    }
+
+    #[test]
+    fn svcb_record() {
+        // RFC 9460: 2.2. RDATA Wire Format
+        let mut rdata = vec![0x00, 0x01, 0x00];
+        rdata.extend_from_slice(&[0x00, 0x01, 0x00, 0x06, 0x02, b'h', b'2', 0x02, b'h', b'3']);
+        rdata.extend_from_slice(&[0x00, 0x03, 0x00, 0x02, 0x20, 0xfb]);
+        rdata.extend_from_slice(&[0x00, 0x04, 0x00, 0x04, 192, 0, 2, 1]);
+        rdata.extend_from_slice(&[0x00, 0x06, 0x00, 0x10, 0x20, 0x01, 0x0d, 0xb8]);
+        rdata.extend_from_slice(&[0; 11]);
+        rdata.push(0x01);
+        let mut https = Record::new();
+        https.set_name("printer.local");
+        https.set_typ(Type::from_value(65));
+        https.set_class(Class::IN);
+        https.set_ttl(120);
+        https.set_data(rdata.clone());
+        let mut msg = Message::new();
+        msg.add_answer(https);
+
+        let parsed = Message::from_bytes(&msg.to_bytes().unwrap()).unwrap();
+        let record = &parsed.answers()[0];
+        assert_eq!(record.typ(), Type::HTTPS);
+        assert_eq!(Type::from_value(64), Type::SVCB);
+        assert_eq!(Type::HTTPS.to_string(), "HTTPS");
+        let svcb = SVCBRecord::from_record(record).unwrap();
+        assert_eq!(svcb.priority(), 1);
+        assert!(!svcb.is_alias());
+        assert_eq!(svcb.target(), "");
+        assert_eq!(svcb.alpn(), vec!["h2", "h3"]);
+        assert_eq!(svcb.port(), Some(8443));
+        assert_eq!(
+            svcb.ipv4hint(),
+            vec!["192.0.2.1".parse::<std::net::Ipv4Addr>().unwrap()]
+        );
+        assert_eq!(
+            svcb.ipv6hint(),
+            vec!["2001:db8::1".parse::<std::net::Ipv6Addr>().unwrap()]
+        );
+        let rr = record.to_resource_record().unwrap();
+        assert_eq!(rr.typ(), Type::HTTPS);
+        assert_eq!(
+            rr.content(),
+            "1 . alpn=h2,h3 port=8443 ipv4hint=192.0.2.1 ipv6hint=2001:db8::1"
+        );
+
+        let mut built = SVCBRecord::new(Type::HTTPS, "printer.local", 1, "");
+        built.set_ttl(120);
+        built.set_ipv6hint(&svcb.ipv6hint()).unwrap();
+        built.set_port(8443).unwrap();
+        built.set_alpn(&["h2", "h3"]).unwrap();
+        built.set_ipv4hint(&svcb.ipv4hint()).unwrap();
+        assert_eq!(built.to_record().unwrap().data(), rdata.as_slice());
+        assert!(built.set_alpn(&[]).is_err());
+        assert!(built.set_param(3, &[0x01]).is_err());
+
+        let mut svcb = SVCBRecord::new(Type::SVCB, "_printer._tcp.local", 0, "printer.local");
+        svcb.set_param(65000, b"x\\").unwrap();
+        assert!(svcb.is_alias());
+        assert_eq!(svcb.to_string(), "0 printer.local. key65000=\"x\\\\\"");
+
+        // The keys must be in the increasing order.
+        let mut unordered = Record::new();
+        unordered.set_typ(Type::SVCB);
+        unordered.set_data(vec![
+            0x00, 0x01, 0x00, 0x00, 0x03, 0x00, 0x02, 0x20, 0xfb, 0x00, 0x01, 0x00, 0x01, 0x00,
+        ]);
+        assert!(SVCBRecord::from_record(&unordered).is_err());
+    }
+
+    #[test]
+    fn uri_record() {
+        // RFC 7553: 4.5. URI RDATA Wire Format
+        let mut rdata = vec![0x00, 0x0a, 0x00, 0x01];
+        rdata.extend_from_slice(b"https://printer.local:631/ipp/print");
+        let mut uri = Record::new();
+        uri.set_name("_ipps._tcp.printer.local");
+        uri.set_typ(Type::from_value(256));
+        uri.set_class(Class::IN);
+        uri.set_ttl(120);
+        uri.set_data(rdata);
+        let mut msg = Message::new();
+        msg.add_answer(uri);
+
+        let parsed = Message::from_bytes(&msg.to_bytes().unwrap()).unwrap();
+        let record = &parsed.answers()[0];
+        assert_eq!(record.typ(), Type::URI);
+        assert_eq!(Type::URI.to_value(), 256);
+        let uri = URIRecord::from_record(record).unwrap();
+        assert_eq!(uri.priority(), 10);
+        assert_eq!(uri.weight(), 1);
+        assert_eq!(uri.target(), "https://printer.local:631/ipp/print");
+        let rr = record.to_resource_record().unwrap();
+        assert_eq!(rr.typ(), Type::URI);
+        assert_eq!(rr.content(), "10 1 \"https://printer.local:631/ipp/print\"");
+
+        let mut built: URIRecord = rr.content().parse().unwrap();
+        built.set_name("_ipps._tcp.printer.local");
+        assert_eq!(built.to_record().unwrap().data(), record.data());
+        assert!("10 1".parse::<URIRecord>().is_err());
+        assert!("10 1 \"\"".parse::<URIRecord>().is_err());
+
+        let mut empty = Record::new();
+        empty.set_typ(Type::URI);
+        empty.set_data(vec![0x00, 0x0a, 0x00, 0x01]);
+        assert!(URIRecord::from_record(&empty).is_err());
+    }
+
+    #[test]
+    fn naptr_record() {
+        // RFC 3403: 4.1 Packet Format
+        let mut rdata = vec![0x00, 0x64, 0x00, 0x0a];
+        rdata.extend_from_slice(b"\x01S\x07SIP+D2U\x00");
+        rdata.extend_from_slice(b"\x04_sip\x04_udp\x07example\x03com\x00");
+        let mut naptr = Record::new();
+        naptr.set_name("example.com");
+        naptr.set_typ(Type::from_value(35));
+        naptr.set_class(Class::IN);
+        naptr.set_ttl(120);
+        naptr.set_data(rdata);
+        let mut msg = Message::new();
+        msg.add_answer(naptr);
+
+        let parsed = Message::from_bytes(&msg.to_bytes().unwrap()).unwrap();
+        let record = &parsed.answers()[0];
+        assert_eq!(record.typ(), Type::NAPTR);
+        let naptr = NAPTRRecord::from_record(record).unwrap();
+        assert_eq!(naptr.order(), 100);
+        assert_eq!(naptr.preference(), 10);
+        assert_eq!(naptr.flags(), "S");
+        assert_eq!(naptr.services(), "SIP+D2U");
+        assert_eq!(naptr.regexp(), "");
+        assert_eq!(naptr.replacement(), "_sip._udp.example.com");
+        let rr = record.to_resource_record().unwrap();
+        assert_eq!(rr.typ(), Type::NAPTR);
+        assert_eq!(
+            rr.content(),
+            "100 10 \"S\" \"SIP+D2U\" \"\" _sip._udp.example.com."
+        );
+
+        let mut built: NAPTRRecord = rr.content().parse().unwrap();
+        built.set_name("example.com");
+        assert_eq!(built.to_record().unwrap().data(), record.data());
+
+        let regexp: NAPTRRecord = r#"100 50 "u" "E2U+sip" "!^.*$!sip:info@example.com!" ."#
+            .parse()
+            .unwrap();
+        assert_eq!(regexp.regexp(), "!^.*$!sip:info@example.com!");
+        assert_eq!(regexp.replacement(), "");
+        assert_eq!(
+            regexp.to_string(),
+            "100 50 \"u\" \"E2U+sip\" \"!^.*$!sip:info@example.com!\" ."
+        );
+        assert!("100 10 \"S\" \"SIP+D2U\" \"\""
+            .parse::<NAPTRRecord>()
+            .is_err());
+        let long = NAPTRRecord::new("", 0, 0, &"a".repeat(256), "", "", "");
+        assert!(long.to_record().is_err());
+
+        let mut truncated = Record::new();
+        truncated.set_typ(Type::NAPTR);
+        truncated.set_data(vec![0x00, 0x64, 0x00, 0x0a, 0x01]);
+        assert!(NAPTRRecord::from_record(&truncated).is_err());
+    }
+
+    #[test]
+    fn loc_record() {
+        // RFC 1876: 3. Master File Format
+        let mut rdata = vec![0x00, 0x33, 0x16, 0x13];
+        rdata.extend_from_slice(&2_299_997_648u32.to_be_bytes());
+        rdata.extend_from_slice(&1_891_505_648u32.to_be_bytes());
+        rdata.extend_from_slice(&9_997_600u32.to_be_bytes());
+        let mut loc = Record::new();
+        loc.set_name("cambridge-net.kei.com");
+        loc.set_typ(Type::from_value(29));
+        loc.set_class(Class::IN);
+        loc.set_ttl(120);
+        loc.set_data(rdata);
+        let mut msg = Message::new();
+        msg.add_answer(loc);
+
+        let parsed = Message::from_bytes(&msg.to_bytes().unwrap()).unwrap();
+        let record = &parsed.answers()[0];
+        assert_eq!(record.typ(), Type::LOC);
+        let loc = LOCRecord::from_record(record).unwrap();
+        assert!((loc.latitude() - 42.365).abs() < 1e-9);
+        assert!((loc.longitude() + 71.105).abs() < 1e-9);
+        assert!((loc.altitude() + 24.0).abs() < 1e-9);
+        assert_eq!(loc.size(), 3000);
+        assert_eq!(loc.horizontal_precision(), 1_000_000);
+        assert_eq!(loc.vertical_precision(), 1000);
+        let rr = record.to_resource_record().unwrap();
+        assert_eq!(rr.typ(), Type::LOC);
+        assert_eq!(
+            rr.content(),
+            "42 21 54.000 N 71 6 18.000 W -24m 30m 10000m 10m"
+        );
+
+        let mut built: LOCRecord = "42 21 54 N 71 06 18 W -24m 30m".parse().unwrap();
+        built.set_name("cambridge-net.kei.com");
+        assert_eq!(built.to_record().unwrap().data(), record.data());
+
+        let mut built = LOCRecord::new("", -33.8675, 151.207, 58.5);
+        built.set_size(25);
+        assert_eq!(
+            built.to_string(),
+            "33 52 3.000 S 151 12 25.200 E 58.50m 0.30m 10000m 10m"
+        );
+        let parsed: LOCRecord = built.to_string().parse().unwrap();
+        assert_eq!(parsed.to_string(), built.to_string());
+        assert!("42 N 71 W".parse::<LOCRecord>().is_err());
+        assert!("91 N 71 W 0m".parse::<LOCRecord>().is_err());
+        assert!("42 21 54 N 71 06 18 W -24m 1m 1m 1m 1m"
+            .parse::<LOCRecord>()
+            .is_err());
+
+        let mut invalid = Record::new();
+        invalid.set_typ(Type::LOC);
+        invalid.set_data(vec![0x01; 16]);
+        assert!(LOCRecord::from_record(&invalid).is_err());
+        invalid.set_data(vec![0x00, 0xa0, 0x16, 0x13]);
+        assert!(LOCRecord::from_record(&invalid).is_err());
+    }
+
+    #[test]
+    fn a_record() {
+        let mut a = ARecord::new("host.local", "192.168.1.10".parse().unwrap());
+        assert_eq!(a.ttl(), 120);
+        a.set_ttl(240);
+        let record = a.to_record();
+        assert_eq!(record.name(), "host.local");
+        assert_eq!(record.typ(), Type::A);
+        assert_eq!(record.class(), Class::IN);
+        assert_eq!(record.ttl(), 240);
+        assert_eq!(record.data(), &[192, 168, 1, 10]);
+
+        let mut expected = vec![0x04];
+        expected.extend_from_slice(b"host\x05local\x00");
+        expected.extend_from_slice(&[0x00, 0x01, 0x00, 0x01, 0x00, 0x00, 0x00, 0xf0]);
+        expected.extend_from_slice(&[0x00, 0x04, 192, 168, 1, 10]);
+        assert_eq!(a.to_bytes().unwrap(), expected);
+
+        let mut msg = Message::new();
+        msg.add_answer(record);
+        let parsed = Message::from_bytes(&msg.to_bytes().unwrap()).unwrap();
+        let parsed = ARecord::from_record(&parsed.answers()[0]).unwrap();
+        assert_eq!(parsed.ipaddr(), a.ipaddr());
+        assert_eq!(parsed.ttl(), 240);
+    }
+
+    #[test]
+    fn message_fingerprint() {
+        let a = |name: &str, addr: &str, ttl: u32| {
+            let mut a = ARecord::new(name, addr.parse().unwrap());
+            a.set_ttl(ttl);
+            a.to_record()
+        };
+        let mut msg = Message::new();
+        msg.set_qr(QR::Response);
+        msg.add_answer(a("host.local", "192.168.1.10", 120));
+        msg.add_answer(a("host.local", "192.168.1.11", 120));
+        let fingerprint = msg.fingerprint();
+        assert_eq!(fingerprint, msg.clone().fingerprint());
+
+        // The order, the case of the names, the query identifier and the TTLs are ignored.
+        let mut equivalent = Message::new();
+        equivalent.set_qr(QR::Response);
+        equivalent.set_id(0x1234);
+        equivalent.add_answer(a("HOST.local.", "192.168.1.11", 119));
+        equivalent.add_answer(a("host.local", "192.168.1.10", 4500));
+        assert_eq!(equivalent.fingerprint(), fingerprint);
+        let parsed =
+            Message::from_bytes(&equivalent.to_bytes_with_compression(false).unwrap()).unwrap();
+        assert_eq!(parsed.fingerprint(), fingerprint);
+
+        // The goodbyes, the sections, the records and the kind of the message are not.
+        let mut goodbye = Message::new();
+        goodbye.set_qr(QR::Response);
+        goodbye.add_answer(a("host.local", "192.168.1.10", 0));
+        goodbye.add_answer(a("host.local", "192.168.1.11", 120));
+        assert_ne!(goodbye.fingerprint(), fingerprint);
+        let mut additional = Message::new();
+        additional.set_qr(QR::Response);
+        additional.add_answer(a("host.local", "192.168.1.10", 120));
+        additional.add_additional(a("host.local", "192.168.1.11", 120));
+        assert_ne!(additional.fingerprint(), fingerprint);
+        let mut other = msg.clone();
+        other.add_answer(a("host.local", "192.168.1.12", 120));
+        assert_ne!(other.fingerprint(), fingerprint);
+        let mut query = msg.clone();
+        query.set_qr(QR::Query);
+        assert_ne!(query.fingerprint(), fingerprint);
+    }
+
+    #[test]
+    fn aaaa_record() {
+        let mut aaaa = AAAARecord::new("host.local", "fe80::1".parse().unwrap());
+        assert_eq!(aaaa.ttl(), 120);
+        aaaa.set_ttl(240);
+        let record = aaaa.to_record();
+        assert_eq!(record.name(), "host.local");
+        assert_eq!(record.typ(), Type::AAAA);
+        assert_eq!(record.class(), Class::IN);
+        assert_eq!(record.ttl(), 240);
+        let mut addr = vec![0xfe, 0x80];
+        addr.extend_from_slice(&[0x00; 13]);
+        addr.push(0x01);
+        assert_eq!(record.data(), addr.as_slice());
+
+        let mut expected = vec![0x04];
+        expected.extend_from_slice(b"host\x05local\x00");
+        expected.extend_from_slice(&[0x00, 0x1c, 0x00, 0x01, 0x00, 0x00, 0x00, 0xf0]);
+        expected.extend_from_slice(&[0x00, 0x10]);
+        expected.extend_from_slice(&addr);
+        assert_eq!(aaaa.to_bytes().unwrap(), expected);
+
+        let mut msg = Message::new();
+        msg.add_answer(record);
+        let parsed = Message::from_bytes(&msg.to_bytes().unwrap()).unwrap();
+        let parsed = AAAARecord::from_record(&parsed.answers()[0]).unwrap();
+        assert_eq!(parsed.ipaddr(), aaaa.ipaddr());
+        assert_eq!(parsed.ttl(), 240);
+    }
+
+    #[test]
+    fn srv_record() {
+        let mut srv = SRVRecord::new("My.Printer._ipp._tcp.local", 0, 5, 631, "printer.local");
+        assert_eq!(srv.service(), "_ipp");
+        assert_eq!(srv.proto(), "_tcp");
+        assert_eq!(srv.ttl(), DEFAULT_HOST_RECORD_TTL);
+        assert_eq!(srv.to_record().unwrap().ttl(), DEFAULT_HOST_RECORD_TTL);
+        srv.set_ttl(240);
+        let record = srv.to_record().unwrap();
+        assert_eq!(record.typ(), Type::SRV);
+        assert_eq!(record.class(), Class::IN);
+        assert_eq!(record.ttl(), 240);
+        let mut expected = vec![0x00, 0x00, 0x00, 0x05, 0x02, 0x77, 0x07];
+        expected.extend_from_slice(b"printer\x05local\x00");
+        assert_eq!(record.data(), expected.as_slice());
+
+        let mut msg = Message::new();
+        msg.add_answer(record);
+        let parsed = Message::from_bytes(&msg.to_bytes().unwrap()).unwrap();
+        let parsed = SRVRecord::from_record(&parsed.answers()[0]).unwrap();
+        assert_eq!(parsed.name(), "My.Printer._ipp._tcp.local");
+        assert_eq!(parsed.service(), "_ipp");
+        assert_eq!(parsed.proto(), "_tcp");
+        assert_eq!(parsed.priority(), 0);
+        assert_eq!(parsed.weight(), 5);
+        assert_eq!(parsed.port(), 631);
+        assert_eq!(parsed.target(), "printer.local");
+        assert_eq!(parsed.ttl(), 240);
+
+        let mut srv: SRVRecord = "10 60 5060 sip.example.com.".parse().unwrap();
+        assert_eq!(srv.service(), "");
+        srv.set_name("_sip._udp.example.com");
+        assert_eq!(srv.service(), "_sip");
+        assert_eq!(srv.proto(), "_udp");
+        srv.set_name("host.local");
+        assert_eq!(srv.service(), "");
+        assert_eq!(srv.proto(), "");
+    }
+
+    #[test]
+    fn txt_record() {
+        let attrs = vec![
+            ("txtvers", "1"),
+            ("path", "/"),
+            ("flag", ""),
+            ("PATH", "/x"),
+        ];
+        let txt = TXTRecord::with_attributes("My Web._http._tcp.local", attrs);
+        assert_eq!(txt.strings(), &vec!["txtvers=1", "path=/", "flag"]);
+        let record = txt.to_record().unwrap();
+        assert_eq!(record.typ(), Type::TXT);
+        assert_eq!(record.data(), b"\x09txtvers=1\x06path=/\x04flag");
+
+        let mut msg = Message::new();
+        msg.add_answer(record);
+        let parsed = Message::from_bytes(&msg.to_bytes().unwrap()).unwrap();
+        let parsed = TXTRecord::from_record(&parsed.answers()[0]).unwrap();
+        assert_eq!(parsed.name(), "My Web._http._tcp.local");
+        assert_eq!(parsed.attribute("Path"), Some(&"/".to_string()));
+        assert_eq!(parsed.attribute("flag"), Some(&"".to_string()));
+
+        let mut attrs = HashMap::new();
+        attrs.insert("id".to_string(), vec![0x00, 0xff]);
+        let record = TXTRecord::with_attributes("light.local", &attrs)
+            .to_record()
+            .unwrap();
+        assert_eq!(record.data(), b"\x05id=\x00\xff");
+
+        let txt = TXTRecord::with_attributes("empty.local", Vec::<(&str, &str)>::new());
+        assert_eq!(txt.to_record().unwrap().data(), &[0x00]);
+
+        let long = "x".repeat(255);
+        let txt = TXTRecord::with_attributes("long.local", [("k", long.as_str())]);
+        assert!(txt.to_record().is_err());
+    }
+
+    #[test]
+    fn ptr_record() {
+        let mut ptr = PTRRecord::new("_http._tcp.local", "My Web");
+        assert_eq!(ptr.domain_name(), "My Web._http._tcp.local");
+        assert_eq!(ptr.ttl(), 4500);
+        let full = PTRRecord::new("_http._tcp.local.", "My Web._HTTP._tcp.local.");
+        assert_eq!(full.domain_name(), "My Web._HTTP._tcp.local");
+
+        ptr.set_ttl(120);
+        let record = ptr.to_record().unwrap();
+        assert_eq!(record.name(), "_http._tcp.local");
+        assert_eq!(record.typ(), Type::PTR);
+        assert_eq!(record.class(), Class::IN);
+        assert_eq!(record.ttl(), 120);
+        let mut expected = vec![0x06];
+        expected.extend_from_slice(b"My Web\x05_http\x04_tcp\x05local\x00");
+        assert_eq!(record.data(), expected.as_slice());
+
+        let mut bytes = vec![0x05];
+        bytes.extend_from_slice(b"_http\x04_tcp\x05local\x00");
+        bytes.extend_from_slice(&[0x00, 0x0c, 0x00, 0x01, 0x00, 0x00, 0x00, 0x78]);
+        bytes.extend_from_slice(&[0x00, 0x09, 0x06]);
+        bytes.extend_from_slice(b"My Web\xc0\x00");
+        assert_eq!(ptr.to_bytes().unwrap(), bytes);
+
+        let mut msg = Message::new();
+        msg.add_answer(record);
+        let parsed = Message::from_bytes(&msg.to_bytes().unwrap()).unwrap();
+        let parsed = PTRRecord::from_record(&parsed.answers()[0]).unwrap();
+        assert_eq!(parsed.domain_name(), "My Web._http._tcp.local");
+        assert_eq!(parsed.ttl(), 120);
+    }
+
+    #[test]
+    fn message_flags() {
+        let mut msg = Message::new();
+        assert_eq!(msg.flags(), 0);
+        msg.set_qr(QR::Response);
+        msg.set_opcode(Opcode::Status);
+        msg.set_aa(true);
+        msg.set_tc(true);
+        msg.set_rd(true);
+        msg.set_ra(true);
+        msg.set_z(true);
+        msg.set_ad(true);
+        msg.set_cd(true);
+        msg.set_response_code(ResponseCode::Refused);
+        assert_eq!(msg.flags(), 0x97F5);
+        assert!(matches!(msg.opcode(), Opcode::Status));
+        assert!(msg.ra() && msg.z() && msg.ad() && msg.cd());
+
+        let parsed = Message::from_bytes(&msg.to_bytes().unwrap()).unwrap();
+        assert_eq!(parsed.flags(), 0x97F5);
+
+        msg.set_opcode(Opcode::Query);
+        msg.set_ra(false);
+        msg.set_z(false);
+        msg.set_ad(false);
+        msg.set_cd(false);
+        assert!(matches!(msg.opcode(), Opcode::Query));
+        assert_eq!(msg.flags(), 0x8705);
+
+        msg.set_flags(0x8400);
+        assert!(msg.is_response());
+        assert!(msg.aa());
+        assert!(!msg.tc());
+        assert!(matches!(msg.response_code(), ResponseCode::NoError));
+    }
+
+    #[test]
+    fn record_views() {
+        let mut srv = "0 0 80 host.local".parse::<SRVRecord>().unwrap();
+        srv.set_name("web._http._tcp.local");
+        let mut record = srv.to_record().unwrap();
+        let view = record.as_srv().unwrap();
+        assert_eq!(view.port(), 80);
+        assert_eq!(view.target(), "host.local");
+        assert!(record.as_a().is_none());
+        assert!(record.as_txt().is_none());
+
+        // The equality ignores whether the view has been parsed.
+        let parsed = record.clone();
+        assert!(parsed == record);
+
+        // The view is parsed again once the record is modified.
+        let mut data = record.data().to_vec();
+        data[4..6].copy_from_slice(&8080u16.to_be_bytes());
+        record.set_data(data);
+        assert_eq!(record.as_srv().unwrap().port(), 8080);
+
+        // A malformed record has no view, and the service ignores it.
+        record.set_data(vec![0, 0, 0]);
+        assert!(record.as_srv().is_none());
+        let mut msg = Message::new();
+        msg.add_answer(record);
+        let service = Service::from_message(&msg);
+        assert!(service.name().is_empty());
+
+        let mut a = "192.168.1.10".parse::<ARecord>().unwrap();
+        a.set_name("host.local");
+        let record = a.to_record();
+        assert_eq!(record.as_a().unwrap().ipaddr().to_string(), "192.168.1.10");
+        assert!(record.as_aaaa().is_none());
+        let mut aaaa = "fe80::1".parse::<AAAARecord>().unwrap();
+        aaaa.set_name("host.local");
+        assert!(aaaa.to_record().as_aaaa().is_some());
+        let record = PTRRecord::new("_http._tcp.local", "web")
+            .to_record()
+            .unwrap();
+        assert_eq!(
+            record.as_ptr().unwrap().domain_name(),
+            "web._http._tcp.local"
+        );
+        let record = TXTRecord::with_attributes("web._http._tcp.local", [("path", "/")])
+            .to_record()
+            .unwrap();
+        assert_eq!(record.as_txt().unwrap().attribute("path").unwrap(), "/");
+    }
 }
@@ -14,38 +14,76 @@
 
 pub use self::a_record::*;
 pub use self::aaaa_record::*;
+pub use self::answer::*;
 pub use self::class::*;
+pub use self::edns_option::*;
 pub use self::error::*;
+pub use self::loc_record::*;
 pub use self::message::*;
+pub use self::message_builder::*;
+pub use self::message_ref::*;
+pub use self::mx_record::*;
+pub use self::naptr_record::*;
+pub use self::ns_record::*;
 pub use self::nsec_record::*;
+pub use self::opt_record::*;
 pub use self::ptr_record::*;
+pub use self::question::*;
 pub use self::question_record::*;
 pub use self::record::*;
 pub use self::records::*;
 pub use self::resource_record::*;
 pub use self::resource_records::*;
+pub use self::soa_record::*;
 pub use self::srv_record::*;
+pub use self::svcb_record::*;
+pub use self::ttl::*;
 pub use self::txt_record::*;
 pub use self::typ::*;
+pub use self::uri_record::*;
 pub use self::writer::*;
 
 pub mod a_record;
 pub mod aaaa_record;
+pub mod answer;
 pub mod class;
+pub mod edns_option;
 pub mod error;
+pub mod loc_record;
 pub mod message;
+pub mod message_builder;
+pub mod message_ref;
+pub mod mx_record;
+pub mod naptr_record;
+pub mod ns_record;
 pub mod nsec_record;
+pub mod opt_record;
+pub mod presentation;
 pub mod ptr_record;
+pub mod question;
 pub mod question_record;
 pub mod reader;
 pub mod record;
 pub mod records;
 pub mod resource_record;
 pub mod resource_records;
+pub mod soa_record;
 pub mod srv_record;
+pub mod svcb_record;
+pub mod ttl;
 pub mod txt_record;
 pub mod typ;
+pub mod uri_record;
 pub mod writer;
 
+pub mod answer_test;
+pub mod edns_option_test;
+pub mod error_test;
+pub mod message_builder_test;
+pub mod message_ref_test;
 pub mod message_test;
+pub mod opt_record_test;
+pub mod presentation_test;
+pub mod question_test;
 pub mod reader_test;
+pub mod writer_test;
@@ -0,0 +1,117 @@
+// Copyright (C) 2024 Satoshi Konno All rights reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//    http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::dns::class::Class;
+use crate::dns::error::{Error, Result};
+use crate::dns::presentation;
+use crate::dns::reader::Reader;
+use crate::dns::record::Record;
+use crate::dns::resource_record::ResourceRecord;
+use crate::dns::typ::Type;
+use crate::dns::writer::Writer;
+use std::fmt;
+use std::str::FromStr;
+
+/// MXRecord represents a MX record, which names a mail exchange of the owner name.
+/// RFC 1035: 3.3.9. MX RDATA format
+pub struct MXRecord {
+    name: String,
+    preference: u16,
+    exchange: String,
+    content: String,
+}
+
+impl MXRecord {
+    /// new creates a new MX record of the specified owner name, preference and exchange.
+    pub fn new(name: &str, preference: u16, exchange: &str) -> MXRecord {
+        let mut mx = MXRecord {
+            name: name.to_string(),
+            preference,
+            exchange: exchange.to_string(),
+            content: "".to_string(),
+        };
+        mx.content = mx.to_string();
+        mx
+    }
+
+    /// from_record creates a new MX record from the specified record.
+    pub fn from_record(record: &Record) -> Result<MXRecord> {
+        let mut reader = Reader::from_bytes(record.data());
+        let preference = reader.read_u16()?;
+        let exchange = reader.read_name()?;
+        Ok(MXRecord::new(record.name(), preference, &exchange))
+    }
+
+    /// preference returns the preference of the exchange, where the lower values are preferred.
+    pub fn preference(&self) -> u16 {
+        self.preference
+    }
+
+    /// exchange returns the name of the host acting as the mail exchange.
+    pub fn exchange(&self) -> &str {
+        &self.exchange
+    }
+
+    /// set_name sets the owner name of the MX record.
+    pub fn set_name(&mut self, name: &str) {
+        self.name = name.to_string();
+    }
+
+    /// to_record returns the record of the MX record.
+    pub fn to_record(&self) -> Result<Record> {
+        let mut w = Writer::new();
+        w.write_u16(self.preference)?;
+        w.write_name(&self.exchange)?;
+        let mut record = Record::new();
+        record.set_name(&self.name);
+        record.set_typ(Type::MX);
+        record.set_class(Class::IN);
+        record.set_data(w.to_bytes());
+        Ok(record)
+    }
+}
+
+impl FromStr for MXRecord {
+    type Err = Error;
+
+    /// from_str parses the RDATA of the zone file format such as `10 mail.example.com.`, and the owner name is empty.
+    fn from_str(rdata: &str) -> Result<MXRecord> {
+        let fields = presentation::parse_fields(rdata, 2, "MX")?;
+        Ok(MXRecord::new(
+            "",
+            presentation::parse_u16(&fields[0])?,
+            &presentation::parse_name(&fields[1])?,
+        ))
+    }
+}
+
+impl ResourceRecord for MXRecord {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn typ(&self) -> Type {
+        Type::MX
+    }
+
+    fn content(&self) -> &str {
+        &self.content
+    }
+}
+
+impl fmt::Display for MXRecord {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} {}.", self.preference, self.exchange)
+    }
+}
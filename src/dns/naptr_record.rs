@@ -0,0 +1,198 @@
+// Copyright (C) 2024 Satoshi Konno All rights reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//    http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::dns::class::Class;
+use crate::dns::error::{Error, ErrorKind, Result};
+use crate::dns::presentation;
+use crate::dns::reader::Reader;
+use crate::dns::record::Record;
+use crate::dns::resource_record::ResourceRecord;
+use crate::dns::typ::Type;
+use crate::dns::writer::Writer;
+use std::fmt;
+use std::str::FromStr;
+
+/// NAPTRRecord represents a NAPTR record, which rewrites the owner name by a regular expression or replaces it with another name.
+/// RFC 3403: 4.1 Packet Format
+pub struct NAPTRRecord {
+    name: String,
+    order: u16,
+    preference: u16,
+    flags: String,
+    services: String,
+    regexp: String,
+    replacement: String,
+    content: String,
+}
+
+impl NAPTRRecord {
+    /// new creates a new NAPTR record of the specified owner name and fields, where the empty replacement is the root name.
+    pub fn new(
+        name: &str,
+        order: u16,
+        preference: u16,
+        flags: &str,
+        services: &str,
+        regexp: &str,
+        replacement: &str,
+    ) -> NAPTRRecord {
+        let mut naptr = NAPTRRecord {
+            name: name.to_string(),
+            order,
+            preference,
+            flags: flags.to_string(),
+            services: services.to_string(),
+            regexp: regexp.to_string(),
+            replacement: replacement.to_string(),
+            content: "".to_string(),
+        };
+        naptr.content = naptr.to_string();
+        naptr
+    }
+
+    /// from_record creates a new NAPTR record from the specified record.
+    pub fn from_record(record: &Record) -> Result<NAPTRRecord> {
+        let mut reader = Reader::from_bytes(record.data());
+        let order = reader.read_u16()?;
+        let preference = reader.read_u16()?;
+        let flags = reader.read_string()?;
+        let services = reader.read_string()?;
+        let regexp = reader.read_string()?;
+        let replacement = reader.read_name()?;
+        Ok(NAPTRRecord::new(
+            record.name(),
+            order,
+            preference,
+            &flags,
+            &services,
+            &regexp,
+            &replacement,
+        ))
+    }
+
+    /// order returns the order in which the records must be processed, where the lower values are processed first.
+    pub fn order(&self) -> u16 {
+        self.order
+    }
+
+    /// preference returns the preference among the records of the same order, where the lower values are preferred.
+    pub fn preference(&self) -> u16 {
+        self.preference
+    }
+
+    /// flags returns the flags which control the rewriting, such as `S`, `A`, `U` and `P`.
+    pub fn flags(&self) -> &str {
+        &self.flags
+    }
+
+    /// services returns the services available down the rewrite path, such as `SIP+D2U`.
+    pub fn services(&self) -> &str {
+        &self.services
+    }
+
+    /// regexp returns the substitution expression applied to the original string, or an empty string if the replacement is used.
+    pub fn regexp(&self) -> &str {
+        &self.regexp
+    }
+
+    /// replacement returns the name which replaces the owner name, or an empty string for the root name if the regexp is used.
+    pub fn replacement(&self) -> &str {
+        &self.replacement
+    }
+
+    /// set_name sets the owner name of the NAPTR record.
+    pub fn set_name(&mut self, name: &str) {
+        self.name = name.to_string();
+    }
+
+    /// to_record returns the record of the NAPTR record.
+    pub fn to_record(&self) -> Result<Record> {
+        let mut w = Writer::new();
+        w.write_u16(self.order)?;
+        w.write_u16(self.preference)?;
+        for s in [&self.flags, &self.services, &self.regexp] {
+            // RFC 1035: 3.3. Standard RRs
+            if (u8::MAX as usize) < s.len() {
+                return Err(Error::new(
+                    ErrorKind::InvalidRdata,
+                    &format!("Character string too long: {}", s),
+                ));
+            }
+            w.write_u8(s.len() as u8)?;
+            w.write_bytes(s.as_bytes())?;
+        }
+        // RFC 3403: 4.1 Packet Format
+        // The replacement must not be compressed.
+        w.write_name(&self.replacement)?;
+        let mut record = Record::new();
+        record.set_name(&self.name);
+        record.set_typ(Type::NAPTR);
+        record.set_class(Class::IN);
+        record.set_data(w.to_bytes());
+        Ok(record)
+    }
+}
+
+impl FromStr for NAPTRRecord {
+    type Err = Error;
+
+    /// from_str parses the RDATA of the zone file format such as `100 10 "S" "SIP+D2U" "" _sip._udp.example.com.`, and the owner name is empty.
+    fn from_str(rdata: &str) -> Result<NAPTRRecord> {
+        let fields = presentation::parse_fields(rdata, 6, "NAPTR")?;
+        let replacement = match fields[5].as_str() {
+            "." => "".to_string(),
+            field => presentation::parse_name(field)?,
+        };
+        Ok(NAPTRRecord::new(
+            "",
+            presentation::parse_u16(&fields[0])?,
+            presentation::parse_u16(&fields[1])?,
+            &fields[2],
+            &fields[3],
+            &fields[4],
+            &replacement,
+        ))
+    }
+}
+
+impl ResourceRecord for NAPTRRecord {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn typ(&self) -> Type {
+        Type::NAPTR
+    }
+
+    fn content(&self) -> &str {
+        &self.content
+    }
+}
+
+impl fmt::Display for NAPTRRecord {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} {}", self.order, self.preference)?;
+        for s in [&self.flags, &self.services, &self.regexp] {
+            write!(f, " \"")?;
+            for c in s.chars() {
+                if c == '"' || c == '\\' {
+                    write!(f, "\\")?;
+                }
+                write!(f, "{}", c)?;
+            }
+            write!(f, "\"")?;
+        }
+        write!(f, " {}.", self.replacement)
+    }
+}
@@ -0,0 +1,103 @@
+// Copyright (C) 2024 Satoshi Konno All rights reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//    http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::dns::class::Class;
+use crate::dns::error::{Error, Result};
+use crate::dns::presentation;
+use crate::dns::reader::Reader;
+use crate::dns::record::Record;
+use crate::dns::resource_record::ResourceRecord;
+use crate::dns::typ::Type;
+use crate::dns::writer::Writer;
+use std::fmt;
+use std::str::FromStr;
+
+/// NSRecord represents a NS record, which names an authoritative name server of the owner name.
+/// RFC 1035: 3.3.11. NS RDATA format
+pub struct NSRecord {
+    name: String,
+    nsdname: String,
+    content: String,
+}
+
+impl NSRecord {
+    /// new creates a new NS record of the specified owner name and name server.
+    pub fn new(name: &str, nsdname: &str) -> NSRecord {
+        let mut ns = NSRecord {
+            name: name.to_string(),
+            nsdname: nsdname.to_string(),
+            content: "".to_string(),
+        };
+        ns.content = ns.to_string();
+        ns
+    }
+
+    /// from_record creates a new NS record from the specified record.
+    pub fn from_record(record: &Record) -> Result<NSRecord> {
+        let nsdname = Reader::from_bytes(record.data()).read_name()?;
+        Ok(NSRecord::new(record.name(), &nsdname))
+    }
+
+    /// nsdname returns the name of the host acting as the name server.
+    pub fn nsdname(&self) -> &str {
+        &self.nsdname
+    }
+
+    /// set_name sets the owner name of the NS record.
+    pub fn set_name(&mut self, name: &str) {
+        self.name = name.to_string();
+    }
+
+    /// to_record returns the record of the NS record.
+    pub fn to_record(&self) -> Result<Record> {
+        let mut w = Writer::new();
+        w.write_name(&self.nsdname)?;
+        let mut record = Record::new();
+        record.set_name(&self.name);
+        record.set_typ(Type::NS);
+        record.set_class(Class::IN);
+        record.set_data(w.to_bytes());
+        Ok(record)
+    }
+}
+
+impl FromStr for NSRecord {
+    type Err = Error;
+
+    /// from_str parses the RDATA of the zone file format such as `ns1.example.com.`, and the owner name is empty.
+    fn from_str(rdata: &str) -> Result<NSRecord> {
+        let fields = presentation::parse_fields(rdata, 1, "NS")?;
+        Ok(NSRecord::new("", &presentation::parse_name(&fields[0])?))
+    }
+}
+
+impl ResourceRecord for NSRecord {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn typ(&self) -> Type {
+        Type::NS
+    }
+
+    fn content(&self) -> &str {
+        &self.content
+    }
+}
+
+impl fmt::Display for NSRecord {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}.", self.nsdname)
+    }
+}
@@ -12,29 +12,164 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use crate::dns::error::Result;
+use crate::dns::class::Class;
+use crate::dns::error::{Error, ErrorKind, Result};
+use crate::dns::reader::Reader;
 use crate::dns::record::Record;
 use crate::dns::resource_record::ResourceRecord;
 use crate::dns::typ::Type;
+use crate::dns::writer::Writer;
 use std::fmt;
 
 /// NSECRecord represents a NSEC record.
+/// RFC 4034: 4.1. NSEC RDATA Wire Format
 pub struct NSECRecord {
     name: String,
+    ttl: u32,
+    next_domain_name: String,
+    types: Vec<Type>,
+    content: String,
 }
 
 impl NSECRecord {
+    /// new creates a new NSEC record of the specified owner name asserting the specified types,
+    /// whose next domain name is the owner name itself as the Multicast DNS responders use.
+    /// RFC 6762: 6.1. Negative Responses
+    pub fn new(name: &str, types: &[Type]) -> NSECRecord {
+        let mut nsec = NSECRecord {
+            name: name.to_string(),
+            ttl: 0,
+            next_domain_name: name.to_string(),
+            types: types.to_vec(),
+            content: "".to_string(),
+        };
+        nsec.content = nsec.to_string();
+        nsec
+    }
+
     /// from_record creates a new NSEC record from the specified record.
     pub fn from_record(record: &Record) -> Result<NSECRecord> {
-        Ok(NSECRecord {
+        let mut nsec = NSECRecord {
             name: record.name().to_string(),
-        })
+            ttl: record.ttl(),
+            next_domain_name: "".to_string(),
+            types: Vec::new(),
+            content: "".to_string(),
+        };
+        let data = record.data();
+        if data.is_empty() {
+            return Ok(nsec);
+        }
+        // The names of the received records are expanded, but a pointer may be left in the data of a record built elsewhere,
+        // so the types are read even if the next domain name can not be.
+        nsec.next_domain_name = Reader::from_bytes(data).read_name().unwrap_or_default();
+        let mut reader = Reader::from_bytes(data);
+        reader.skip_name()?;
+        nsec.types = read_type_bitmaps(&mut reader)?;
+        nsec.content = nsec.to_string();
+        Ok(nsec)
+    }
+
+    /// name returns the owner name of the record.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// next_domain_name returns the next owner name in the canonical order of the zone,
+    /// which is the owner name itself in the Multicast DNS negative responses.
+    pub fn next_domain_name(&self) -> &str {
+        &self.next_domain_name
     }
 
     /// typ returns the type of the record.
     pub fn typ(&self) -> Type {
         Type::NSEC
     }
+
+    /// ttl returns the TTL of the record.
+    pub fn ttl(&self) -> u32 {
+        self.ttl
+    }
+
+    /// types returns the types which exist for the name.
+    /// RFC 6762: 6.1. Negative Responses
+    /// The types absent from the bitmap are asserted not to exist.
+    pub fn types(&self) -> &Vec<Type> {
+        &self.types
+    }
+
+    /// has_type returns true if the specified type exists for the name.
+    pub fn has_type(&self, typ: Type) -> bool {
+        self.types.contains(&typ)
+    }
+
+    /// to_record returns the record of the NSEC record, whose next domain name is never compressed.
+    /// RFC 4034: 4.1.1. The Next Domain Field
+    pub fn to_record(&self) -> Result<Record> {
+        let mut w = Writer::new();
+        w.write_name(&self.next_domain_name)?;
+        write_type_bitmaps(&mut w, &self.types)?;
+        let mut record = Record::new();
+        record.set_name(&self.name);
+        record.set_typ(Type::NSEC);
+        record.set_class(Class::IN);
+        record.set_ttl(self.ttl);
+        record.set_data(w.to_bytes());
+        Ok(record)
+    }
+}
+
+// RFC 4034: 4.1.2. The Type Bit Maps Field
+fn read_type_bitmaps(reader: &mut Reader) -> Result<Vec<Type>> {
+    let mut types = Vec::new();
+    while !reader.is_empty() {
+        let window = reader.read_u8()? as u16;
+        let bitmap_len = reader.read_u8()? as usize;
+        if bitmap_len == 0 || 32 < bitmap_len {
+            return Err(Error::new(
+                ErrorKind::InvalidRdata,
+                &format!("Invalid NSEC bitmap length: {}", bitmap_len),
+            ));
+        }
+        let mut bitmap = vec![0; bitmap_len];
+        reader.read_bytes(&mut bitmap)?;
+        for (n, bits) in bitmap.iter().enumerate() {
+            for bit in 0..8 {
+                if bits & (0x80 >> bit) != 0 {
+                    let value = (window << 8) | ((n as u16) * 8 + bit);
+                    types.push(Type::from_value(value));
+                }
+            }
+        }
+    }
+    Ok(types)
+}
+
+// write_type_bitmaps writes the bitmaps of the specified types, one for each window of 256 types in the ascending order.
+fn write_type_bitmaps(w: &mut Writer, types: &[Type]) -> Result<()> {
+    let mut values: Vec<u16> = types.iter().map(|typ| typ.to_value()).collect();
+    values.sort_unstable();
+    values.dedup();
+    let mut windows: Vec<(u8, Vec<u8>)> = Vec::new();
+    for value in values {
+        let window = (value >> 8) as u8;
+        let bit = (value & 0xFF) as usize;
+        if windows.last().map(|(w, _)| *w) != Some(window) {
+            windows.push((window, Vec::new()));
+        }
+        if let Some((_, bitmap)) = windows.last_mut() {
+            if bitmap.len() <= bit / 8 {
+                bitmap.resize(bit / 8 + 1, 0);
+            }
+            bitmap[bit / 8] |= 0x80 >> (bit % 8);
+        }
+    }
+    for (window, bitmap) in windows {
+        w.write_u8(window)?;
+        w.write_u8(bitmap.len() as u8)?;
+        w.write_bytes(&bitmap)?;
+    }
+    Ok(())
 }
 
 impl ResourceRecord for NSECRecord {
@@ -47,12 +182,16 @@ impl ResourceRecord for NSECRecord {
     }
 
     fn content(&self) -> &str {
-        ""
+        &self.content
     }
 }
 
 impl fmt::Display for NSECRecord {
-    fn fmt(&self, _f: &mut fmt::Formatter) -> fmt::Result {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}.", self.next_domain_name)?;
+        for typ in &self.types {
+            write!(f, " {}", typ)?;
+        }
         Ok(())
     }
 }
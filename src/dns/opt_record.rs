@@ -0,0 +1,203 @@
+// Copyright (C) 2024 Satoshi Konno All rights reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//    http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::dns::edns_option::{EdnsOption, EdnsOptionCodec};
+use crate::dns::error::{Error, ErrorKind, Result};
+use crate::dns::message::Message;
+use crate::dns::reader::Reader;
+use crate::dns::record::Record;
+use crate::dns::resource_record::ResourceRecord;
+use crate::dns::typ::Type;
+use crate::dns::writer::Writer;
+
+/// EDNS_VERSION is the EDNS version implemented.
+/// RFC 6891: 6.1.3. OPT Record TTL Field Use
+pub const EDNS_VERSION: u8 = 0;
+/// EDNS_MIN_UDP_PAYLOAD_SIZE is the smallest UDP payload size, which is assumed for the payload sizes less than it.
+/// RFC 6891: 6.2.3. Requestor's Payload Size
+pub const EDNS_MIN_UDP_PAYLOAD_SIZE: u16 = 512;
+/// EXTENDED_RCODE_BADVERS is the extended response code of the unsupported EDNS versions.
+/// RFC 6891: 9. IANA Considerations
+pub const EXTENDED_RCODE_BADVERS: u16 = 16;
+
+const DNSSEC_OK_MASK: u32 = 0x8000;
+
+/// OPTRecord represents an OPT pseudo-record of the extension mechanisms for DNS.
+/// RFC 6891: 6.1.2. Wire Format
+#[derive(Debug, Clone, PartialEq)]
+pub struct OPTRecord {
+    udp_payload_size: u16,
+    extended_rcode: u8,
+    version: u8,
+    dnssec_ok: bool,
+    options: Vec<EdnsOption>,
+}
+
+impl OPTRecord {
+    /// new creates a new OPT record advertising the specified UDP payload size.
+    pub fn new(udp_payload_size: u16) -> OPTRecord {
+        OPTRecord {
+            udp_payload_size,
+            extended_rcode: 0,
+            version: EDNS_VERSION,
+            dnssec_ok: false,
+            options: Vec::new(),
+        }
+    }
+
+    /// from_record creates a new OPT record from the specified record.
+    pub fn from_record(record: &Record) -> Result<OPTRecord> {
+        if record.typ() != Type::OPT {
+            return Err(Error::new(
+                ErrorKind::UnsupportedType,
+                &format!("{} is not an OPT record", record.typ()),
+            ));
+        }
+        let ttl = record.ttl();
+        let mut opt = OPTRecord {
+            udp_payload_size: record.udp_payload_size(),
+            extended_rcode: (ttl >> 24) as u8,
+            version: (ttl >> 16) as u8,
+            dnssec_ok: (ttl & DNSSEC_OK_MASK) != 0,
+            options: Vec::new(),
+        };
+        let mut reader = Reader::from_bytes(record.data());
+        while !reader.is_empty() {
+            let code = reader.read_u16()?;
+            let len = reader.read_u16()?;
+            let mut data = vec![0; len as usize];
+            reader.read_bytes(&mut data)?;
+            opt.options.push(EdnsOption::new(code, data));
+        }
+        Ok(opt)
+    }
+
+    /// from_message returns the OPT record in the additional section of the specified message, if any.
+    pub fn from_message(msg: &Message) -> Option<OPTRecord> {
+        msg.additionals()
+            .iter()
+            .filter(|record| record.typ() == Type::OPT)
+            .find_map(|record| OPTRecord::from_record(record).ok())
+    }
+
+    /// set_udp_payload_size sets the largest UDP payload the sender can reassemble.
+    pub fn set_udp_payload_size(&mut self, size: u16) {
+        self.udp_payload_size = size;
+    }
+
+    /// udp_payload_size returns the largest UDP payload the sender can reassemble, which is at least 512 bytes.
+    pub fn udp_payload_size(&self) -> u16 {
+        self.udp_payload_size.max(EDNS_MIN_UDP_PAYLOAD_SIZE)
+    }
+
+    /// set_extended_rcode sets the upper 8 bits of the extended response code.
+    pub fn set_extended_rcode(&mut self, extended_rcode: u8) {
+        self.extended_rcode = extended_rcode;
+    }
+
+    /// extended_rcode returns the upper 8 bits of the extended response code.
+    pub fn extended_rcode(&self) -> u8 {
+        self.extended_rcode
+    }
+
+    /// set_version sets the EDNS version.
+    pub fn set_version(&mut self, version: u8) {
+        self.version = version;
+    }
+
+    /// version returns the EDNS version.
+    pub fn version(&self) -> u8 {
+        self.version
+    }
+
+    /// set_dnssec_ok sets the DNSSEC OK bit.
+    /// RFC 3225: 3. Protocol Changes
+    pub fn set_dnssec_ok(&mut self, dnssec_ok: bool) {
+        self.dnssec_ok = dnssec_ok;
+    }
+
+    /// dnssec_ok returns the DNSSEC OK bit.
+    pub fn dnssec_ok(&self) -> bool {
+        self.dnssec_ok
+    }
+
+    /// add_option adds the specified option.
+    pub fn add_option(&mut self, option: EdnsOption) {
+        self.options.push(option);
+    }
+
+    /// options returns the options.
+    pub fn options(&self) -> &[EdnsOption] {
+        &self.options
+    }
+
+    /// option returns the data of the first option of the specified code, if any.
+    pub fn option(&self, code: u16) -> Option<&[u8]> {
+        self.options
+            .iter()
+            .find(|option| option.code() == code)
+            .map(|option| option.data())
+    }
+
+    /// typed_option returns the first option of the type of the specified codec, decoded, if any.
+    pub fn typed_option<T: EdnsOptionCodec>(&self) -> Option<Result<T>> {
+        self.option(T::CODE).map(T::decode)
+    }
+
+    /// set_typed_option replaces the options of the code of the specified option with it.
+    pub fn set_typed_option<T: EdnsOptionCodec>(&mut self, option: &T) {
+        self.remove_option(T::CODE);
+        self.options.push(EdnsOption::from_typed(option));
+    }
+
+    /// remove_option removes the options of the specified code.
+    pub fn remove_option(&mut self, code: u16) {
+        self.options.retain(|option| option.code() != code);
+    }
+
+    /// to_record returns the record of the OPT record, whose owner name is the root.
+    pub fn to_record(&self) -> Result<Record> {
+        let mut w = Writer::new();
+        for option in &self.options {
+            w.write_u16(option.code())?;
+            w.write_u16(option.data().len() as u16)?;
+            w.write_bytes(option.data())?;
+        }
+        let mut ttl = ((self.extended_rcode as u32) << 24) | ((self.version as u32) << 16);
+        if self.dnssec_ok {
+            ttl |= DNSSEC_OK_MASK;
+        }
+        let mut record = Record::new();
+        record.set_name("");
+        record.set_typ(Type::OPT);
+        record.set_udp_payload_size(self.udp_payload_size);
+        record.set_ttl(ttl);
+        record.set_data(w.to_bytes());
+        Ok(record)
+    }
+}
+
+impl ResourceRecord for OPTRecord {
+    fn name(&self) -> &str {
+        ""
+    }
+
+    fn typ(&self) -> Type {
+        Type::OPT
+    }
+
+    fn content(&self) -> &str {
+        ""
+    }
+}
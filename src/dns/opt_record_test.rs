@@ -0,0 +1,94 @@
+// Copyright (C) 2024 Satoshi Konno All rights reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//    http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+#[cfg(test)]
+mod tests {
+
+    use crate::dns::{
+        EdnsOption, Message, OPTRecord, Question, Record, ResponseCode, Type,
+        EDNS_MIN_UDP_PAYLOAD_SIZE, EXTENDED_RCODE_BADVERS,
+    };
+
+    #[test]
+    fn opt_record() {
+        let mut opt = OPTRecord::new(4096);
+        opt.set_dnssec_ok(true);
+        opt.add_option(EdnsOption::new(10, vec![1, 2, 3, 4, 5, 6, 7, 8]));
+
+        let mut msg = Message::new();
+        msg.add_additional(opt.to_record().unwrap());
+        let msg = Message::from_bytes(&msg.to_bytes().unwrap()).unwrap();
+        assert_eq!(msg.additionals()[0].name(), "");
+        assert_eq!(msg.additionals()[0].udp_payload_size(), 4096);
+        assert_eq!(OPTRecord::from_message(&msg), Some(opt));
+        assert_eq!(msg.extended_response_code(), 0);
+
+        let opt = OPTRecord::new(0);
+        assert_eq!(opt.udp_payload_size(), EDNS_MIN_UDP_PAYLOAD_SIZE);
+        assert!(OPTRecord::from_record(&Record::new()).is_err());
+        let mut msg = Message::new();
+        msg.set_rd(true);
+        assert!(msg.rd());
+        assert!(OPTRecord::from_message(&msg).is_none());
+    }
+
+    #[test]
+    fn extended_response_code() {
+        let mut opt = OPTRecord::new(1232);
+        opt.set_extended_rcode(1);
+        let mut msg = Message::new();
+        msg.set_response_code(ResponseCode::NoError);
+        msg.add_additional(opt.to_record().unwrap());
+        let msg = Message::from_bytes(&msg.to_bytes().unwrap()).unwrap();
+        assert_eq!(msg.extended_response_code(), EXTENDED_RCODE_BADVERS);
+
+        let mut msg = Message::new();
+        msg.set_response_code(ResponseCode::Refused);
+        assert_eq!(msg.extended_response_code(), ResponseCode::Refused as u16);
+    }
+
+    #[test]
+    fn opt_record_options() {
+        let mut opt = OPTRecord::new(1232);
+        opt.add_option(EdnsOption::new(10, vec![1, 2, 3, 4, 5, 6, 7, 8]));
+        opt.add_option(EdnsOption::new(12, vec![0; 4]));
+        assert_eq!(opt.option(10), Some(&[1, 2, 3, 4, 5, 6, 7, 8][..]));
+        assert_eq!(opt.option(4), None);
+        opt.remove_option(12);
+        assert_eq!(opt.options().len(), 1);
+
+        let mut msg = Message::new();
+        assert!(msg.set_opt(&OPTRecord::new(512)).is_ok());
+        assert!(msg.set_opt(&opt).is_ok());
+        assert_eq!(msg.ar_count(), 1);
+        let msg = Message::from_bytes(&msg.to_bytes().unwrap()).unwrap();
+        assert_eq!(msg.opt(), Some(opt.clone()));
+        let rr = msg.additionals()[0].to_resource_record().unwrap();
+        assert_eq!(rr.typ(), Type::OPT);
+
+        let msg = Message::builder()
+            .question(Question::new("host.example.com", Type::A))
+            .edns(OPTRecord::new(512))
+            .edns(opt.clone())
+            .build()
+            .unwrap();
+        assert_eq!(msg.opt(), Some(opt.clone()));
+        assert_eq!(msg.ar_count(), 1);
+        assert!(Message::builder()
+            .edns(opt.clone())
+            .additional(opt.to_record().unwrap())
+            .build()
+            .is_err());
+    }
+}
@@ -0,0 +1,133 @@
+// Copyright (C) 2024 Satoshi Konno All rights reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//    http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::dns::error::{Error, ErrorKind, Result};
+
+/// tokenize splits the specified RDATA in the zone file presentation format into its fields,
+/// where a quoted field may hold spaces and the escapes `\X` and `\DDD` of RFC 1035: 5.1. Format are decoded.
+pub fn tokenize(rdata: &str) -> Result<Vec<String>> {
+    let mut tokens = Vec::new();
+    let mut chars = rdata.chars().peekable();
+    loop {
+        while chars.peek().is_some_and(|c| c.is_whitespace()) {
+            chars.next();
+        }
+        let quoted = match chars.peek() {
+            Some('"') => {
+                chars.next();
+                true
+            }
+            Some(_) => false,
+            None => break,
+        };
+        let mut bytes: Vec<u8> = Vec::new();
+        let mut closed = !quoted;
+        while let Some(c) = chars.next() {
+            match c {
+                '"' if quoted => {
+                    closed = true;
+                    break;
+                }
+                c if c.is_whitespace() && !quoted => break,
+                '\\' => {
+                    let escaped = chars.next().ok_or_else(|| {
+                        Error::new(
+                            ErrorKind::InvalidText,
+                            &format!("Invalid escape: {}", rdata),
+                        )
+                    })?;
+                    if escaped.is_ascii_digit() {
+                        let digits: String = [Some(escaped), chars.next(), chars.next()]
+                            .iter()
+                            .flatten()
+                            .collect();
+                        let value = digits
+                            .parse::<u8>()
+                            .ok()
+                            .filter(|_| digits.len() == 3)
+                            .ok_or_else(|| {
+                                Error::new(
+                                    ErrorKind::InvalidText,
+                                    &format!("Invalid escape: \\{}", digits),
+                                )
+                            })?;
+                        bytes.push(value);
+                    } else {
+                        let mut buf = [0; 4];
+                        bytes.extend_from_slice(escaped.encode_utf8(&mut buf).as_bytes());
+                    }
+                }
+                c => {
+                    let mut buf = [0; 4];
+                    bytes.extend_from_slice(c.encode_utf8(&mut buf).as_bytes());
+                }
+            }
+        }
+        if !closed {
+            return Err(Error::new(
+                ErrorKind::InvalidText,
+                &format!("Unterminated quote: {}", rdata),
+            ));
+        }
+        let token = String::from_utf8(bytes).map_err(|_| {
+            Error::new(ErrorKind::InvalidText, &format!("Invalid UTF-8: {}", rdata))
+        })?;
+        tokens.push(token);
+    }
+    Ok(tokens)
+}
+
+/// parse_fields returns the fields of the specified RDATA, or an error if their number is not the specified one.
+pub fn parse_fields(rdata: &str, n: usize, typ: &str) -> Result<Vec<String>> {
+    let fields = tokenize(rdata)?;
+    if fields.len() != n {
+        return Err(Error::new(
+            ErrorKind::InvalidText,
+            &format!("Invalid {} RDATA: {:?}", typ, rdata),
+        ));
+    }
+    Ok(fields)
+}
+
+/// parse_name returns the domain name of the specified field without the trailing dot of the absolute names.
+pub fn parse_name(field: &str) -> Result<String> {
+    let name = field.strip_suffix('.').unwrap_or(field);
+    if name.is_empty() || name.contains("..") {
+        return Err(Error::new(
+            ErrorKind::InvalidText,
+            &format!("Invalid name: {:?}", field),
+        ));
+    }
+    Ok(name.to_string())
+}
+
+/// parse_u16 returns the integer of the specified field.
+pub fn parse_u16(field: &str) -> Result<u16> {
+    field.parse::<u16>().map_err(|_| {
+        Error::new(
+            ErrorKind::InvalidText,
+            &format!("Invalid number: {:?}", field),
+        )
+    })
+}
+
+/// parse_u32 returns the 32-bit integer of the specified field.
+pub fn parse_u32(field: &str) -> Result<u32> {
+    field.parse::<u32>().map_err(|_| {
+        Error::new(
+            ErrorKind::InvalidText,
+            &format!("Invalid number: {:?}", field),
+        )
+    })
+}
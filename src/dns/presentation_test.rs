@@ -0,0 +1,104 @@
+// Copyright (C) 2024 Satoshi Konno All rights reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//    http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+#[cfg(test)]
+mod tests {
+
+    use std::net::IpAddr;
+
+    use crate::dns::presentation::tokenize;
+    use crate::dns::{AAAARecord, ARecord, PTRRecord, SRVRecord, TXTRecord, Type};
+
+    #[test]
+    fn presentation_tokenize() {
+        struct Test {
+            rdata: &'static str,
+            expected: Option<Vec<&'static str>>,
+        }
+
+        let tests = vec![
+            Test {
+                rdata: "0 0 80 host.local.",
+                expected: Some(vec!["0", "0", "80", "host.local."]),
+            },
+            Test {
+                rdata: "  \"path=/a b\"\t\"v=1\" ",
+                expected: Some(vec!["path=/a b", "v=1"]),
+            },
+            Test {
+                rdata: r#"My\ Web._http._tcp.local. "q\"\065""#,
+                expected: Some(vec!["My Web._http._tcp.local.", "q\"A"]),
+            },
+            Test {
+                rdata: "\"open",
+                expected: None,
+            },
+            Test {
+                rdata: r"\25",
+                expected: None,
+            },
+            Test {
+                rdata: "",
+                expected: Some(vec![]),
+            },
+        ];
+
+        for test in tests {
+            let tokens = tokenize(test.rdata).ok();
+            let expected = test
+                .expected
+                .map(|tokens| tokens.iter().map(|t| t.to_string()).collect::<Vec<_>>());
+            assert_eq!(tokens, expected, "{}", test.rdata);
+        }
+    }
+
+    #[test]
+    fn presentation_from_str() {
+        let mut srv: SRVRecord = "1 2 8080 Host.local.".parse().unwrap();
+        assert_eq!(srv.priority(), 1);
+        assert_eq!(srv.weight(), 2);
+        assert_eq!(srv.port(), 8080);
+        assert_eq!(srv.target(), "Host.local");
+        srv.set_name("My Web._http._tcp.local");
+        let record = srv.to_record().unwrap();
+        assert_eq!(record.typ(), Type::SRV);
+        let srv = SRVRecord::from_record(&record).unwrap();
+        assert_eq!(srv.name(), "My Web._http._tcp.local");
+        assert_eq!(srv.target(), "Host.local");
+        assert!("1 2 host.local.".parse::<SRVRecord>().is_err());
+        assert!("1 2 70000 host.local.".parse::<SRVRecord>().is_err());
+
+        let ptr: PTRRecord = "\"My Web._http._tcp.local.\"".parse().unwrap();
+        assert_eq!(ptr.domain_name(), "My Web._http._tcp.local");
+        let ptr = PTRRecord::from_record(&ptr.to_record().unwrap()).unwrap();
+        assert_eq!(ptr.domain_name(), "My Web._http._tcp.local");
+
+        let txt: TXTRecord = "\"path=/\" version=1".parse().unwrap();
+        assert_eq!(txt.strings(), &vec!["path=/", "version=1"]);
+        assert_eq!(txt.attribute("version").unwrap(), "1");
+        let record = txt.to_record().unwrap();
+        assert_eq!(record.data(), b"\x06path=/\x09version=1");
+        let empty: TXTRecord = "".parse().unwrap();
+        assert_eq!(empty.to_record().unwrap().data(), &[0]);
+
+        let a: ARecord = "192.168.1.10".parse().unwrap();
+        assert_eq!(*a.ipaddr(), "192.168.1.10".parse::<IpAddr>().unwrap());
+        assert_eq!(a.to_record().data(), &[192, 168, 1, 10]);
+        assert!("fe80::1".parse::<ARecord>().is_err());
+
+        let aaaa: AAAARecord = "fe80::1".parse().unwrap();
+        assert_eq!(aaaa.to_record().data().len(), 16);
+        assert!("192.168.1.10".parse::<AAAARecord>().is_err());
+    }
+}
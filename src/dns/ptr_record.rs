@@ -12,21 +12,48 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use crate::dns::error::Result;
+use crate::dns::class::Class;
+use crate::dns::error::{Error, Result};
+use crate::dns::presentation;
 use crate::dns::reader::Reader;
 use crate::dns::record::Record;
 use crate::dns::resource_record::ResourceRecord;
+use crate::dns::ttl::DEFAULT_OTHER_RECORD_TTL;
 use crate::dns::typ::Type;
+use crate::dns::writer::Writer;
 use std::fmt;
+use std::str::FromStr;
 
 /// PTRRecord represents a PTR record.
 pub struct PTRRecord {
     name: String,
 
     domain_name: String,
+    ttl: u32,
 }
 
 impl PTRRecord {
+    /// new creates a new PTR record from the specified service type such as `_http._tcp.local` to the specified instance,
+    /// which is either a full instance name or an instance label such as `My Web` prefixed to the service type.
+    /// RFC 6763: 4.1. Structured Service Instance Names
+    pub fn new(service_type: &str, instance_name: &str) -> PTRRecord {
+        let service_type = service_type.trim_end_matches('.');
+        let instance_name = instance_name.trim_end_matches('.');
+        let suffix = format!(".{}", service_type);
+        let is_full_name = suffix.len() < instance_name.len()
+            && instance_name[instance_name.len() - suffix.len()..].eq_ignore_ascii_case(&suffix);
+        let domain_name = if is_full_name {
+            instance_name.to_string()
+        } else {
+            format!("{}{}", instance_name, suffix)
+        };
+        PTRRecord {
+            name: service_type.to_string(),
+            domain_name,
+            ttl: DEFAULT_OTHER_RECORD_TTL,
+        }
+    }
+
     /// from_record creates a new PTR record from the specified record.
     pub fn from_record(record: &Record) -> Result<PTRRecord> {
         let data = record.data();
@@ -35,14 +62,70 @@ impl PTRRecord {
         let ptr = PTRRecord {
             name: record.name().to_string(),
             domain_name: domain_name.to_string(),
+            ttl: record.ttl(),
         };
         Ok(ptr)
     }
 
-    ///
+    /// domain_name returns the domain name of the PTR record.
     pub fn domain_name(&self) -> &str {
         &self.domain_name
     }
+
+    /// ttl returns the TTL of the PTR record.
+    pub fn ttl(&self) -> u32 {
+        self.ttl
+    }
+
+    /// set_name sets the owner name of the PTR record.
+    pub fn set_name(&mut self, name: &str) {
+        self.name = name.to_string();
+    }
+
+    /// set_domain_name sets the domain name which the PTR record points to, such as a service type of the service type enumeration.
+    pub fn set_domain_name(&mut self, domain_name: &str) {
+        self.domain_name = domain_name.to_string();
+    }
+
+    /// set_ttl sets the TTL of the PTR record.
+    pub fn set_ttl(&mut self, ttl: u32) {
+        self.ttl = ttl;
+    }
+
+    /// to_record returns the record of the PTR record.
+    pub fn to_record(&self) -> Result<Record> {
+        let mut w = Writer::new();
+        w.write_name(&self.domain_name)?;
+        let mut record = Record::new();
+        record.set_name(&self.name);
+        record.set_typ(Type::PTR);
+        record.set_class(Class::IN);
+        record.set_ttl(self.ttl);
+        record.set_data(w.to_bytes());
+        Ok(record)
+    }
+
+    /// to_bytes returns the wire format of the PTR record, such as a known answer of a browse query.
+    pub fn to_bytes(&self) -> Result<Vec<u8>> {
+        let mut w = Writer::new();
+        w.write_response_record(&self.to_record()?)?;
+        Ok(w.to_bytes())
+    }
+}
+
+impl FromStr for PTRRecord {
+    type Err = Error;
+
+    /// from_str parses the RDATA of the zone file format such as `My Web._http._tcp.local.`, and the owner name is empty.
+    /// A name with spaces must be quoted or escaped as `My\ Web._http._tcp.local.`.
+    fn from_str(rdata: &str) -> Result<PTRRecord> {
+        let fields = presentation::parse_fields(rdata, 1, "PTR")?;
+        Ok(PTRRecord {
+            name: "".to_string(),
+            domain_name: presentation::parse_name(&fields[0])?,
+            ttl: DEFAULT_OTHER_RECORD_TTL,
+        })
+    }
 }
 
 impl ResourceRecord for PTRRecord {
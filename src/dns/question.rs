@@ -0,0 +1,154 @@
+// Copyright (C) 2024 Satoshi Konno All rights reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//    http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::fmt;
+use std::time::{Duration, Instant};
+
+use crate::default::PORT;
+use crate::dns::class::{Class, CLASS_MASK, UNICAST_RESPONSE_MASK};
+use crate::dns::error::Result;
+use crate::dns::reader::Reader;
+use crate::dns::record::Record;
+use crate::dns::typ::Type;
+
+/// ResponseMode represents how a question should be answered.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum ResponseMode {
+    /// Multicast answers the question to the multicast group.
+    Multicast,
+    /// Unicast answers the question directly to the querier.
+    Unicast,
+}
+
+/// Questions represents a question list.
+pub type Questions = Vec<Question>;
+
+/// Question represents an entry of the question section, which has no TTL nor data unlike the resource records.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Question {
+    name: String,
+    typ: Type,
+    class: Class,
+    unicast_response: bool,
+}
+
+impl Question {
+    /// new creates a new QM question of the specified name and type.
+    pub fn new(name: &str, typ: Type) -> Question {
+        Question {
+            name: name.to_string(),
+            typ,
+            class: Class::IN,
+            unicast_response: false,
+        }
+    }
+
+    /// from_reader parses a new question from the specified reader.
+    pub fn from_reader(reader: &mut Reader) -> Result<Question> {
+        let name = reader.read_name()?;
+        let typ = Type::from_value(reader.read_u16()?);
+        let cls = reader.read_u16()?;
+        Ok(Question {
+            name,
+            typ,
+            class: Class::from_value(cls & CLASS_MASK),
+            unicast_response: (cls & UNICAST_RESPONSE_MASK) != 0,
+        })
+    }
+
+    /// set_name sets the name of the question.
+    pub fn set_name(&mut self, name: &str) {
+        self.name = name.to_string();
+    }
+
+    /// name returns the name of the question.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// set_typ sets the type of the question.
+    pub fn set_typ(&mut self, typ: Type) {
+        self.typ = typ;
+    }
+
+    /// typ returns the type of the question.
+    pub fn typ(&self) -> Type {
+        self.typ
+    }
+
+    /// set_class sets the class of the question.
+    pub fn set_class(&mut self, class: Class) {
+        self.class = class;
+    }
+
+    /// class returns the class of the question.
+    pub fn class(&self) -> Class {
+        self.class
+    }
+
+    /// unicast_response returns true if the question is a QU question.
+    /// RFC 6762: 5.4. Questions Requesting Unicast Responses
+    pub fn unicast_response(&self) -> bool {
+        self.unicast_response
+    }
+
+    /// set_unicast_response sets the unicast response bit of the question.
+    pub fn set_unicast_response(&mut self, unicast_response: bool) {
+        self.unicast_response = unicast_response;
+    }
+
+    /// matches returns true if the specified record answers the question.
+    pub fn matches(&self, record: &Record) -> bool {
+        if !self.name.eq_ignore_ascii_case(record.name()) {
+            return false;
+        }
+        self.typ == Type::ANY || self.typ == record.typ()
+    }
+
+    /// response_mode_at returns how the question received from the specified port should be answered with a record of the specified TTL
+    /// which was last multicast at the specified time.
+    /// RFC 6762: 5.4. Questions Requesting Unicast Responses
+    /// If the responder has not multicast the record recently (within one quarter of its TTL),
+    /// then the responder SHOULD instead multicast the response.
+    /// RFC 6762: 6.7. Legacy Unicast Responses
+    pub fn response_mode_at(
+        &self,
+        src_port: u16,
+        ttl: u32,
+        last_multicast: Option<Instant>,
+        now: Instant,
+    ) -> ResponseMode {
+        if src_port != PORT {
+            return ResponseMode::Unicast;
+        }
+        if !self.unicast_response {
+            return ResponseMode::Multicast;
+        }
+        match last_multicast {
+            Some(last)
+                if now.saturating_duration_since(last) < Duration::from_secs(ttl as u64) / 4 =>
+            {
+                ResponseMode::Unicast
+            }
+            _ => ResponseMode::Multicast,
+        }
+    }
+}
+
+impl fmt::Display for Question {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mode = if self.unicast_response { "QU" } else { "QM" };
+        write!(f, "{} {} {}", self.name, self.typ, mode)
+    }
+}
@@ -0,0 +1,95 @@
+// Copyright (C) 2024 Satoshi Konno All rights reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//    http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+#[cfg(test)]
+mod tests {
+
+    use std::time::{Duration, Instant};
+
+    use crate::default::PORT;
+    use crate::dns::message::Message;
+    use crate::dns::question::{Question, ResponseMode};
+    use crate::dns::typ::Type;
+
+    #[test]
+    fn parse_questions() {
+        let mut qu = Question::new("_http._tcp.local", Type::PTR);
+        qu.set_unicast_response(true);
+        let qm = Question::new("host.local", Type::A);
+
+        let mut msg = Message::new();
+        msg.add_question(qu.clone());
+        msg.add_question(qm.clone());
+        let msg = Message::from_bytes(&msg.to_bytes().unwrap()).unwrap();
+
+        let questions = msg.questions();
+        assert_eq!(questions, &vec![qu, qm]);
+        assert!(questions[0].unicast_response());
+        assert!(!questions[1].unicast_response());
+    }
+
+    #[test]
+    fn question_response_mode() {
+        struct Test {
+            unicast_response: bool,
+            src_port: u16,
+            last_multicast: Option<u64>,
+            expected: ResponseMode,
+        }
+
+        let tests = vec![
+            Test {
+                unicast_response: false,
+                src_port: PORT,
+                last_multicast: Some(1),
+                expected: ResponseMode::Multicast,
+            },
+            Test {
+                unicast_response: true,
+                src_port: PORT,
+                last_multicast: Some(1),
+                expected: ResponseMode::Unicast,
+            },
+            Test {
+                unicast_response: true,
+                src_port: PORT,
+                last_multicast: Some(30),
+                expected: ResponseMode::Multicast,
+            },
+            Test {
+                unicast_response: true,
+                src_port: PORT,
+                last_multicast: None,
+                expected: ResponseMode::Multicast,
+            },
+            Test {
+                unicast_response: false,
+                src_port: 49152,
+                last_multicast: None,
+                expected: ResponseMode::Unicast,
+            },
+        ];
+
+        let now = Instant::now() + Duration::from_secs(60);
+        for test in tests {
+            let mut question = Question::new("host.local", Type::A);
+            question.set_unicast_response(test.unicast_response);
+            let last = test.last_multicast.map(|s| now - Duration::from_secs(s));
+            assert_eq!(
+                question.response_mode_at(test.src_port, 120, last, now),
+                test.expected
+            );
+        }
+    }
+}
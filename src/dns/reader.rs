@@ -12,7 +12,12 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use crate::dns::error::{Error, Result};
+use crate::dns::error::{Error, ErrorKind, Result};
+
+/// MAX_NAME_LEN is the maximum length of a name in the wire format.
+pub const MAX_NAME_LEN: usize = 255;
+/// MAX_POINTER_HOPS is the maximum number of the compression pointers followed in a name.
+pub const MAX_POINTER_HOPS: usize = MAX_NAME_LEN / 2;
 
 pub struct Reader<'a> {
     buffer: &'a [u8],
@@ -31,6 +36,14 @@ impl<'a> Reader<'a> {
         }
     }
 
+    /// reader_at returns a new reader of the same buffer from the specified offset,
+    /// such as to read a name in RDATA which may point into the enclosing message.
+    pub fn reader_at(&self, offset: usize) -> Reader<'a> {
+        let mut reader = Reader::from_bytes(self.buffer);
+        reader.set_offset(offset);
+        reader
+    }
+
     /// set_offset sets the offset of the buffer.
     pub fn set_offset(&mut self, offset: usize) {
         self.cursor = offset;
@@ -43,7 +56,7 @@ impl<'a> Reader<'a> {
 
     // read_u8 reads the next byte from the buffer.
     pub fn read_u8(&mut self) -> Result<u8> {
-        if self.buffer_len < self.cursor {
+        if self.buffer_len <= self.cursor {
             return Err(Error::from_bytes(self.buffer, self.cursor));
         }
         let v = self.buffer[self.cursor];
@@ -77,7 +90,7 @@ impl<'a> Reader<'a> {
 
     /// read_string_size reads the next string size from the buffer.
     pub fn read_string_size(&mut self) -> Result<usize> {
-        if self.buffer_len < self.cursor {
+        if self.buffer_len <= self.cursor {
             return Err(Error::from_bytes(self.buffer, self.cursor));
         }
         let str_len = self.buffer[self.cursor] as usize;
@@ -93,12 +106,23 @@ impl<'a> Reader<'a> {
         }
         let str_bytes = &self.buffer[self.cursor..self.cursor + str_len];
         self.cursor += str_len;
-        Ok(String::from_utf8(str_bytes.to_vec()).unwrap())
+        Ok(String::from_utf8_lossy(str_bytes).to_string())
     }
 
+    /// read_strings reads the next strings until the end of the buffer or an empty string.
     pub fn read_strings(&mut self) -> Result<Vec<String>> {
+        Ok(self
+            .read_byte_strings()?
+            .iter()
+            .map(|s| String::from_utf8_lossy(s).to_string())
+            .collect())
+    }
+
+    /// read_byte_strings reads the next strings as bytes until the end of the buffer or an empty string,
+    /// so the strings which are not UTF-8 such as binary TXT values are kept as they are.
+    pub fn read_byte_strings(&mut self) -> Result<Vec<Vec<u8>>> {
         let mut strs = Vec::new();
-        loop {
+        while self.cursor < self.buffer_len {
             let str_len = self.read_string_size()?;
             if str_len == 0 {
                 break;
@@ -108,7 +132,7 @@ impl<'a> Reader<'a> {
             }
             let str_bytes = &self.buffer[self.cursor..self.cursor + str_len];
             self.cursor += str_len;
-            strs.push(String::from_utf8(str_bytes.to_vec()).unwrap());
+            strs.push(str_bytes.to_vec());
         }
         Ok(strs)
     }
@@ -116,32 +140,95 @@ impl<'a> Reader<'a> {
     /// read_name reads the next name from the buffer.
     pub fn read_name(&mut self) -> Result<String> {
         let mut name = String::new();
+        let mut cursor = self.cursor;
+        let mut segment = self.cursor;
+        let mut end = None;
+        let mut name_len = 1;
+        let mut hops = 0;
         loop {
-            let label_len = self.buffer[self.cursor] as usize;
+            if self.buffer_len <= cursor {
+                return Err(Error::from_bytes(self.buffer, cursor));
+            }
+            let label_len = self.buffer[cursor] as usize;
             if label_len == 0 {
-                self.cursor += 1;
+                cursor += 1;
                 break;
             }
             if label_len & 0xc0 == 0xc0 {
-                let offset =
-                    ((label_len as usize) & 0x3f) << 8 | self.buffer[self.cursor + 1] as usize;
-                self.cursor += 2;
-                let mut reader = Reader::from_bytes(&self.buffer[offset..]);
-                let compressed_name = reader.read_name()?;
-                if 0 < name.len() {
-                    name.push('.');
+                if self.buffer_len <= cursor + 1 {
+                    return Err(Error::from_bytes(self.buffer, cursor));
                 }
-                name.push_str(&compressed_name);
-                break;
+                let offset = (label_len & 0x3f) << 8 | self.buffer[cursor + 1] as usize;
+                // RFC 1035: 4.1.4. Message compression
+                // A pointer refers to a prior occurrence, so each pointer has to precede the segment
+                // of the name it is in, which makes the targets strictly decreasing to avoid loops.
+                hops += 1;
+                if segment <= offset || MAX_POINTER_HOPS < hops {
+                    return Err(Error::from_bytes_with_kind(
+                        ErrorKind::InvalidLabel,
+                        self.buffer,
+                        cursor,
+                    ));
+                }
+                end.get_or_insert(cursor + 2);
+                cursor = offset;
+                segment = offset;
+                continue;
+            }
+            // RFC 6891: 5. Extended Label Types
+            // The label types 0b01 and 0b10 are not supported.
+            if label_len & 0xc0 != 0 {
+                return Err(Error::from_bytes_with_kind(
+                    ErrorKind::InvalidLabel,
+                    self.buffer,
+                    cursor,
+                ));
             }
-            self.cursor += 1;
-            if 0 < name.len() {
+            // RFC 1035: 3.1. Name space definitions
+            // To simplify implementations, the total length of a domain name is restricted to 255 octets or less.
+            name_len += 1 + label_len;
+            if MAX_NAME_LEN < name_len {
+                return Err(Error::from_bytes_with_kind(
+                    ErrorKind::InvalidLabel,
+                    self.buffer,
+                    cursor,
+                ));
+            }
+            cursor += 1;
+            if self.buffer_len < cursor + label_len {
+                return Err(Error::from_bytes(self.buffer, cursor));
+            }
+            if !name.is_empty() {
                 name.push('.');
             }
-            let label_bytes = &self.buffer[self.cursor..self.cursor + label_len];
-            name.push_str(&String::from_utf8(label_bytes.to_vec()).unwrap());
-            self.cursor += label_len;
+            let label_bytes = &self.buffer[cursor..cursor + label_len];
+            name.push_str(&String::from_utf8_lossy(label_bytes));
+            cursor += label_len;
         }
+        self.cursor = end.unwrap_or(cursor);
         Ok(name)
     }
+
+    /// skip_name skips the next name without following compression pointers.
+    pub fn skip_name(&mut self) -> Result<()> {
+        loop {
+            let label_len = self.read_u8()? as usize;
+            if label_len == 0 {
+                return Ok(());
+            }
+            if label_len & 0xc0 == 0xc0 {
+                self.read_u8()?;
+                return Ok(());
+            }
+            if self.buffer_len < self.cursor + label_len {
+                return Err(Error::from_bytes(self.buffer, self.cursor));
+            }
+            self.cursor += label_len;
+        }
+    }
+
+    /// is_empty returns true if all bytes have been read.
+    pub fn is_empty(&self) -> bool {
+        self.buffer_len <= self.cursor
+    }
 }
@@ -15,6 +15,7 @@
 #[cfg(test)]
 mod tests {
 
+    use crate::dns::message::Message;
     use crate::dns::reader::Reader;
 
     #[test]
@@ -32,16 +33,18 @@ mod tests {
 
     #[test]
     fn reader_read_string() {
-        let mut reader = Reader::from_bytes(&[0x03, 'a' as u8, 'b' as u8, 'c' as u8]);
+        let mut reader = Reader::from_bytes(&[0x03, b'a', b'b', b'c']);
         assert_eq!(reader.read_string().unwrap(), "abc");
     }
 
     #[test]
     fn reader_read_strings() {
-        let mut reader = Reader::from_bytes(&[
-            0x03, 'a' as u8, 'b' as u8, 'c' as u8, 0x03, 'd' as u8, 'e' as u8, 'f' as u8, 0x00,
-        ]);
+        let mut reader =
+            Reader::from_bytes(&[0x03, b'a', b'b', b'c', 0x03, b'd', b'e', b'f', 0x00]);
         assert_eq!(reader.read_strings().unwrap(), vec!["abc", "def"]);
+
+        let mut reader = Reader::from_bytes(&[0x03, b'a', b'b', b'c', 0x01, b'd']);
+        assert_eq!(reader.read_strings().unwrap(), vec!["abc", "d"]);
     }
 
     #[test]
@@ -53,14 +56,11 @@ mod tests {
 
         let tests = vec![
             Test {
-                data: vec![0x03, 'a' as u8, 'b' as u8, 'c' as u8, 0x00],
+                data: vec![0x03, b'a', b'b', b'c', 0x00],
                 name: "abc".to_string(),
             },
             Test {
-                data: vec![
-                    0x03, 'a' as u8, 'b' as u8, 'c' as u8, 0x03, 'd' as u8, 'e' as u8, 'f' as u8,
-                    0x00,
-                ],
+                data: vec![0x03, b'a', b'b', b'c', 0x03, b'd', b'e', b'f', 0x00],
                 name: "abc.def".to_string(),
             },
         ];
@@ -70,4 +70,45 @@ mod tests {
             assert_eq!(reader.read_name().unwrap(), test.name);
         }
     }
+
+    #[test]
+    fn reader_read_compressed_name() {
+        let data = vec![
+            0x03, b'a', b'b', b'c', 0x00, 0x03, b'd', b'e', b'f', 0xc0, 0x00, 0x01, b'g', 0xc0,
+            0x05,
+        ];
+        let mut reader = Reader::from_bytes(&data);
+        reader.set_offset(5);
+        assert_eq!(reader.read_name().unwrap(), "def.abc");
+        assert_eq!(reader.read_name().unwrap(), "g.def.abc");
+
+        // Forward pointers and truncated labels are rejected instead of panicking.
+        let mut reader = Reader::from_bytes(&[0xc0, 0x00]);
+        assert!(reader.read_name().is_err());
+        let mut reader = Reader::from_bytes(&[0x05, b'a', b'b']);
+        assert!(reader.read_name().is_err());
+
+        // A pointer back to the start of its own name is a loop.
+        let mut reader = Reader::from_bytes(&[0x01, b'a', 0xc0, 0x00]);
+        assert!(reader.read_name().is_err());
+
+        // A name longer than 255 octets is rejected.
+        let mut data = Vec::new();
+        for _ in 0..5 {
+            data.push(63);
+            data.extend_from_slice(&[b'a'; 63]);
+        }
+        data.push(0x00);
+        let mut reader = Reader::from_bytes(&data);
+        assert!(reader.read_name().is_err());
+    }
+
+    #[test]
+    fn reader_read_looping_name() {
+        let mut data = vec![
+            0x00, 0x00, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        ];
+        data.extend_from_slice(&[0x01, b'a', 0xc0, 0x0c, 0x00, 0x01, 0x00, 0x01]);
+        assert!(Message::from_bytes(&data).is_err());
+    }
 }
@@ -13,11 +13,42 @@
 // limitations under the License.
 
 use std::fmt;
+use std::sync::OnceLock;
 
+use crate::dns::a_record::ARecord;
+use crate::dns::aaaa_record::AAAARecord;
 use crate::dns::class::*;
 use crate::dns::error::Result;
+use crate::dns::ptr_record::PTRRecord;
 use crate::dns::reader::Reader;
+use crate::dns::srv_record::SRVRecord;
+use crate::dns::txt_record::TXTRecord;
 use crate::dns::typ::*;
+use crate::dns::writer::Writer;
+
+// RecordView represents the typed data of a record, which is parsed once when it is first accessed.
+#[allow(clippy::upper_case_acronyms)]
+enum RecordView {
+    A(ARecord),
+    AAAA(AAAARecord),
+    PTR(PTRRecord),
+    SRV(SRVRecord),
+    TXT(TXTRecord),
+}
+
+impl RecordView {
+    // from_record returns the typed data of the specified record, or None if the type has no view or the data is malformed.
+    fn from_record(record: &Record) -> Option<RecordView> {
+        match record.typ() {
+            Type::A => ARecord::from_record(record).ok().map(RecordView::A),
+            Type::AAAA => AAAARecord::from_record(record).ok().map(RecordView::AAAA),
+            Type::PTR => PTRRecord::from_record(record).ok().map(RecordView::PTR),
+            Type::SRV => SRVRecord::from_record(record).ok().map(RecordView::SRV),
+            Type::TXT => TXTRecord::from_record(record).ok().map(RecordView::TXT),
+            _ => None,
+        }
+    }
+}
 
 /// A structure representing a DNS record.
 pub struct Record {
@@ -27,6 +58,8 @@ pub struct Record {
     cls: Class,
     unicast_response: bool,
     ttl: u32,
+    udp_payload_size: u16,
+    view: OnceLock<Option<RecordView>>,
 }
 
 impl Record {
@@ -39,12 +72,15 @@ impl Record {
             cls: Class::NONE,
             unicast_response: false,
             ttl: 0,
+            udp_payload_size: 0,
+            view: OnceLock::new(),
         }
     }
 
     /// set_name sets the name of the record.
     pub fn set_name(&mut self, name: &str) {
         self.name = name.to_string();
+        self.view.take();
     }
 
     /// name returns the name of the record.
@@ -55,6 +91,7 @@ impl Record {
     /// set_typ sets the type of the record.
     pub fn set_typ(&mut self, typ: Type) {
         self.typ = typ;
+        self.view.take();
     }
 
     /// typ returns the type of the record.
@@ -65,6 +102,7 @@ impl Record {
     /// set_class sets the class of the record.
     pub fn set_class(&mut self, class: Class) {
         self.cls = class;
+        self.view.take();
     }
 
     /// class returns the class of the record.
@@ -75,6 +113,7 @@ impl Record {
     /// set_data sets the data of the record.
     pub fn set_data(&mut self, data: Vec<u8>) {
         self.data = data;
+        self.view.take();
     }
 
     /// data returns the data of the record.
@@ -92,9 +131,21 @@ impl Record {
         self.unicast_response
     }
 
+    /// set_cache_flush sets the cache-flush bit of the resource record, which shares the bit with the unicast response flag.
+    /// RFC 6762: 10.2. Announcements to Flush Outdated Cache Entries
+    pub fn set_cache_flush(&mut self, cache_flush: bool) {
+        self.unicast_response = cache_flush;
+    }
+
+    /// cache_flush returns the cache-flush bit of the resource record.
+    pub fn cache_flush(&self) -> bool {
+        self.unicast_response
+    }
+
     /// set_ttl sets the TTL of the record.
     pub fn set_ttl(&mut self, ttl: u32) {
         self.ttl = ttl;
+        self.view.take();
     }
 
     /// ttl returns the TTL of the record.
@@ -102,6 +153,18 @@ impl Record {
         self.ttl
     }
 
+    /// set_udp_payload_size sets the UDP payload size of an OPT record, which is written in place of the class.
+    /// RFC 6891: 6.1.2. Wire Format
+    /// CLASS: requestor's UDP payload size.
+    pub fn set_udp_payload_size(&mut self, size: u16) {
+        self.udp_payload_size = size;
+    }
+
+    /// udp_payload_size returns the UDP payload size of an OPT record.
+    pub fn udp_payload_size(&self) -> u16 {
+        self.udp_payload_size
+    }
+
     /// parse_request_record parses a request record.
     pub fn parse_request_record(&mut self, reader: &mut Reader) -> Result<()> {
         self.parse_section(reader)?;
@@ -118,15 +181,21 @@ impl Record {
         // Parse data length.
         let data_len = reader.read_u16()?;
         if 0 < data_len {
+            let data_offset = reader.offset();
             let mut data = vec![0; data_len as usize];
             reader.read_bytes(&mut data)?;
-            self.data = data;
+            self.data = match expand_names(reader, self.typ, data_offset, &data) {
+                Some(expanded) => expanded,
+                None => data,
+            };
         }
 
         Ok(())
     }
 
     fn parse_section(&mut self, reader: &mut Reader) -> Result<()> {
+        self.view.take();
+
         // Parse domain name.
         self.name = reader.read_name()?;
 
@@ -135,11 +204,96 @@ impl Record {
 
         // Parse class.
         let cls = reader.read_u16()?;
+        if self.typ == Type::OPT {
+            self.udp_payload_size = cls;
+            return Ok(());
+        }
         self.cls = Class::from_value(cls & CLASS_MASK);
         self.unicast_response = (cls & UNICAST_RESPONSE_MASK) != 0;
 
         Ok(())
     }
+
+    // view returns the typed data of the record, which is parsed on the first access and kept until the record is modified.
+    fn view(&self) -> Option<&RecordView> {
+        self.view
+            .get_or_init(|| RecordView::from_record(self))
+            .as_ref()
+    }
+
+    /// as_a returns the A record view of the record, or None if the record is not a well-formed A record.
+    pub fn as_a(&self) -> Option<&ARecord> {
+        match self.view()? {
+            RecordView::A(a) => Some(a),
+            _ => None,
+        }
+    }
+
+    /// as_aaaa returns the AAAA record view of the record, or None if the record is not a well-formed AAAA record.
+    pub fn as_aaaa(&self) -> Option<&AAAARecord> {
+        match self.view()? {
+            RecordView::AAAA(aaaa) => Some(aaaa),
+            _ => None,
+        }
+    }
+
+    /// as_ptr returns the PTR record view of the record, or None if the record is not a well-formed PTR record.
+    pub fn as_ptr(&self) -> Option<&PTRRecord> {
+        match self.view()? {
+            RecordView::PTR(ptr) => Some(ptr),
+            _ => None,
+        }
+    }
+
+    /// as_srv returns the SRV record view of the record, or None if the record is not a well-formed SRV record.
+    pub fn as_srv(&self) -> Option<&SRVRecord> {
+        match self.view()? {
+            RecordView::SRV(srv) => Some(srv),
+            _ => None,
+        }
+    }
+
+    /// as_txt returns the TXT record view of the record, or None if the record is not a well-formed TXT record.
+    pub fn as_txt(&self) -> Option<&TXTRecord> {
+        match self.view()? {
+            RecordView::TXT(txt) => Some(txt),
+            _ => None,
+        }
+    }
+}
+
+// The parsed view is a cache of the data, so a clone parses again and the equality ignores it.
+impl Clone for Record {
+    fn clone(&self) -> Self {
+        Record {
+            name: self.name.clone(),
+            data: self.data.clone(),
+            typ: self.typ,
+            cls: self.cls,
+            unicast_response: self.unicast_response,
+            ttl: self.ttl,
+            udp_payload_size: self.udp_payload_size,
+            view: OnceLock::new(),
+        }
+    }
+}
+
+impl PartialEq for Record {
+    fn eq(&self, other: &Self) -> bool {
+        self.name == other.name
+            && self.data == other.data
+            && self.typ == other.typ
+            && self.cls == other.cls
+            && self.unicast_response == other.unicast_response
+            && self.ttl == other.ttl
+            && self.udp_payload_size == other.udp_payload_size
+    }
+}
+
+impl Default for Record {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl fmt::Display for Record {
@@ -147,3 +301,45 @@ impl fmt::Display for Record {
         Ok(())
     }
 }
+
+// embedded_names returns the length of the data before the embedded names of the specified type, and the number of the names,
+// or None if the type has no name which may be compressed.
+// RFC 3597: 4. Domain Name Compression
+pub(crate) fn embedded_names(typ: Type) -> Option<(usize, usize)> {
+    match typ {
+        Type::PTR | Type::CNAME | Type::NS | Type::NSEC => Some((0, 1)),
+        Type::SRV => Some((6, 1)),
+        // RFC 1035: 3.3.9. MX RDATA format
+        Type::MX => Some((2, 1)),
+        // RFC 1035: 3.3.13. SOA RDATA format
+        Type::SOA => Some((0, 2)),
+        _ => None,
+    }
+}
+
+// expand_names returns the data of the specified type whose embedded names are decompressed against the enclosing message,
+// so the data can be parsed without the message. It returns None if the data has no name or a name is invalid.
+pub(crate) fn expand_names(
+    reader: &Reader,
+    typ: Type,
+    data_offset: usize,
+    data: &[u8],
+) -> Option<Vec<u8>> {
+    let (prefix_len, names) = embedded_names(typ)?;
+    if data.len() < prefix_len {
+        return None;
+    }
+    let mut name_reader = reader.reader_at(data_offset + prefix_len);
+    let mut w = Writer::new();
+    w.write_bytes(&data[..prefix_len]).ok()?;
+    for _ in 0..names {
+        let name = name_reader.read_name().ok()?;
+        w.write_name(&name).ok()?;
+    }
+    let names_len = name_reader.offset() - data_offset;
+    if data.len() < names_len {
+        return None;
+    }
+    w.write_bytes(&data[names_len..]).ok()?;
+    Some(w.to_bytes())
+}
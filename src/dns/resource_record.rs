@@ -14,13 +14,21 @@
 
 use crate::dns::a_record::ARecord;
 use crate::dns::aaaa_record::AAAARecord;
-use crate::dns::error::{Error, Result};
+use crate::dns::error::{Error, ErrorKind, Result};
+use crate::dns::loc_record::LOCRecord;
+use crate::dns::mx_record::MXRecord;
+use crate::dns::naptr_record::NAPTRRecord;
+use crate::dns::ns_record::NSRecord;
 use crate::dns::nsec_record::NSECRecord;
+use crate::dns::opt_record::OPTRecord;
 use crate::dns::ptr_record::PTRRecord;
 use crate::dns::record::Record;
+use crate::dns::soa_record::SOARecord;
 use crate::dns::srv_record::SRVRecord;
+use crate::dns::svcb_record::SVCBRecord;
 use crate::dns::txt_record::TXTRecord;
 use crate::dns::typ::Type;
+use crate::dns::uri_record::URIRecord;
 
 /// ResourceRecord represents a DNS resource record.
 pub trait ResourceRecord: Send {
@@ -41,10 +49,18 @@ impl Record {
             Type::SRV => Ok(Box::new(SRVRecord::from_record(self)?)),
             Type::PTR => Ok(Box::new(PTRRecord::from_record(self)?)),
             Type::NSEC => Ok(Box::new(NSECRecord::from_record(self)?)),
-            _ => Err(Error::from_str(&format!(
-                "Unsupported record type: {:?}",
-                self.typ().to_string()
-            ))),
+            Type::OPT => Ok(Box::new(OPTRecord::from_record(self)?)),
+            Type::SOA => Ok(Box::new(SOARecord::from_record(self)?)),
+            Type::MX => Ok(Box::new(MXRecord::from_record(self)?)),
+            Type::NS => Ok(Box::new(NSRecord::from_record(self)?)),
+            Type::LOC => Ok(Box::new(LOCRecord::from_record(self)?)),
+            Type::NAPTR => Ok(Box::new(NAPTRRecord::from_record(self)?)),
+            Type::URI => Ok(Box::new(URIRecord::from_record(self)?)),
+            Type::SVCB | Type::HTTPS => Ok(Box::new(SVCBRecord::from_record(self)?)),
+            _ => Err(Error::new(
+                ErrorKind::UnsupportedType,
+                &format!("Unsupported record type: {:?}", self.typ().to_string()),
+            )),
         }
     }
 }
@@ -0,0 +1,173 @@
+// Copyright (C) 2024 Satoshi Konno All rights reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//    http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::dns::class::Class;
+use crate::dns::error::{Error, Result};
+use crate::dns::presentation;
+use crate::dns::reader::Reader;
+use crate::dns::record::Record;
+use crate::dns::resource_record::ResourceRecord;
+use crate::dns::typ::Type;
+use crate::dns::writer::Writer;
+use std::fmt;
+use std::str::FromStr;
+
+/// SOARecord represents a SOA record, which marks the start of a zone of authority.
+/// RFC 1035: 3.3.13. SOA RDATA format
+pub struct SOARecord {
+    name: String,
+    mname: String,
+    rname: String,
+    serial: u32,
+    refresh: u32,
+    retry: u32,
+    expire: u32,
+    minimum: u32,
+    content: String,
+}
+
+impl SOARecord {
+    /// from_record creates a new SOA record from the specified record.
+    pub fn from_record(record: &Record) -> Result<SOARecord> {
+        let mut reader = Reader::from_bytes(record.data());
+        let mut soa = SOARecord {
+            name: record.name().to_string(),
+            mname: reader.read_name()?,
+            rname: reader.read_name()?,
+            serial: reader.read_u32()?,
+            refresh: reader.read_u32()?,
+            retry: reader.read_u32()?,
+            expire: reader.read_u32()?,
+            minimum: reader.read_u32()?,
+            content: "".to_string(),
+        };
+        soa.content = soa.to_string();
+        Ok(soa)
+    }
+
+    /// mname returns the name of the primary name server of the zone.
+    pub fn mname(&self) -> &str {
+        &self.mname
+    }
+
+    /// rname returns the mailbox of the person responsible for the zone, encoded as a domain name.
+    pub fn rname(&self) -> &str {
+        &self.rname
+    }
+
+    /// serial returns the version number of the zone.
+    pub fn serial(&self) -> u32 {
+        self.serial
+    }
+
+    /// refresh returns the interval in seconds before the zone should be refreshed.
+    pub fn refresh(&self) -> u32 {
+        self.refresh
+    }
+
+    /// retry returns the interval in seconds before a failed refresh should be retried.
+    pub fn retry(&self) -> u32 {
+        self.retry
+    }
+
+    /// expire returns the time in seconds after which the zone is no longer authoritative.
+    pub fn expire(&self) -> u32 {
+        self.expire
+    }
+
+    /// minimum returns the TTL of the negative responses of the zone.
+    /// RFC 2308: 4. SOA Minimum Field
+    pub fn minimum(&self) -> u32 {
+        self.minimum
+    }
+
+    /// set_name sets the owner name of the SOA record.
+    pub fn set_name(&mut self, name: &str) {
+        self.name = name.to_string();
+    }
+
+    /// to_record returns the record of the SOA record.
+    pub fn to_record(&self) -> Result<Record> {
+        let mut w = Writer::new();
+        w.write_name(&self.mname)?;
+        w.write_name(&self.rname)?;
+        for value in [
+            self.serial,
+            self.refresh,
+            self.retry,
+            self.expire,
+            self.minimum,
+        ] {
+            w.write_u32(value)?;
+        }
+        let mut record = Record::new();
+        record.set_name(&self.name);
+        record.set_typ(Type::SOA);
+        record.set_class(Class::IN);
+        record.set_data(w.to_bytes());
+        Ok(record)
+    }
+}
+
+impl FromStr for SOARecord {
+    type Err = Error;
+
+    /// from_str parses the RDATA of the zone file format such as `ns.example.com. admin.example.com. 1 3600 600 86400 60`, and the owner name is empty.
+    fn from_str(rdata: &str) -> Result<SOARecord> {
+        let fields = presentation::parse_fields(rdata, 7, "SOA")?;
+        let mut soa = SOARecord {
+            name: "".to_string(),
+            mname: presentation::parse_name(&fields[0])?,
+            rname: presentation::parse_name(&fields[1])?,
+            serial: presentation::parse_u32(&fields[2])?,
+            refresh: presentation::parse_u32(&fields[3])?,
+            retry: presentation::parse_u32(&fields[4])?,
+            expire: presentation::parse_u32(&fields[5])?,
+            minimum: presentation::parse_u32(&fields[6])?,
+            content: "".to_string(),
+        };
+        soa.content = soa.to_string();
+        Ok(soa)
+    }
+}
+
+impl ResourceRecord for SOARecord {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn typ(&self) -> Type {
+        Type::SOA
+    }
+
+    fn content(&self) -> &str {
+        &self.content
+    }
+}
+
+impl fmt::Display for SOARecord {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{}. {}. {} {} {} {} {}",
+            self.mname,
+            self.rname,
+            self.serial,
+            self.refresh,
+            self.retry,
+            self.expire,
+            self.minimum
+        )
+    }
+}
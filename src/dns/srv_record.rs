@@ -12,12 +12,17 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use crate::dns::error::Result;
+use crate::dns::class::Class;
+use crate::dns::error::{Error, Result};
+use crate::dns::presentation;
 use crate::dns::reader::Reader;
 use crate::dns::record::Record;
 use crate::dns::resource_record::ResourceRecord;
+use crate::dns::ttl::DEFAULT_HOST_RECORD_TTL;
 use crate::dns::typ::Type;
+use crate::dns::writer::Writer;
 use std::fmt;
+use std::str::FromStr;
 
 /// SRVRecord represents a SRV record.
 pub struct SRVRecord {
@@ -28,22 +33,33 @@ pub struct SRVRecord {
     weight: u16,
     port: u16,
     target: String,
+    ttl: u32,
 }
 
 impl SRVRecord {
-    /// from_record creates a new SRV record from the specified record.
-    pub fn from_record(record: &Record) -> Result<SRVRecord> {
+    /// new creates a new SRV record of the specified owner name such as `My Printer._ipp._tcp.local`, priority, weight, port and target host
+    /// with the default TTL of the host records.
+    pub fn new(name: &str, priority: u16, weight: u16, port: u16, target: &str) -> SRVRecord {
         let mut srv = SRVRecord {
             service: "".to_string(),
             proto: "".to_string(),
-            name: record.name().to_string(),
-            priority: 0,
-            weight: 0,
-            port: 0,
-            target: "".to_string(),
+            name: "".to_string(),
+            priority,
+            weight,
+            port,
+            target: target.to_string(),
+            ttl: DEFAULT_HOST_RECORD_TTL,
         };
+        srv.set_name(name);
+        srv
+    }
+
+    /// from_record creates a new SRV record from the specified record.
+    pub fn from_record(record: &Record) -> Result<SRVRecord> {
+        let mut srv = SRVRecord::new(record.name(), 0, 0, 0, "");
+        srv.ttl = record.ttl();
         let data = record.data();
-        if data.len() == 0 {
+        if data.is_empty() {
             return Ok(srv);
         }
         let mut reader = Reader::from_bytes(data);
@@ -59,12 +75,13 @@ impl SRVRecord {
         Type::SRV
     }
 
-    /// service returns the service of the SRV record.
+    /// service returns the service label of the owner name such as `_ipp`, or an empty string if the name has no service labels.
+    /// RFC 2782: The format of the SRV RR
     pub fn service(&self) -> &str {
         &self.service
     }
 
-    /// proto returns the protocol of the SRV record.
+    /// proto returns the protocol label of the owner name such as `_tcp`, or an empty string if the name has no service labels.
     pub fn proto(&self) -> &str {
         &self.proto
     }
@@ -93,6 +110,73 @@ impl SRVRecord {
     pub fn target(&self) -> &str {
         &self.target
     }
+
+    /// ttl returns the TTL of the SRV record.
+    pub fn ttl(&self) -> u32 {
+        self.ttl
+    }
+
+    /// set_name sets the owner name of the SRV record, and the service and protocol labels of the name.
+    pub fn set_name(&mut self, name: &str) {
+        self.name = name.to_string();
+        let (service, proto) = service_labels(name);
+        self.service = service.to_string();
+        self.proto = proto.to_string();
+    }
+
+    /// set_ttl sets the TTL of the SRV record.
+    pub fn set_ttl(&mut self, ttl: u32) {
+        self.ttl = ttl;
+    }
+
+    /// to_record returns the record of the SRV record, or an error if the target is not a valid host name
+    /// such as with a label longer than 63 bytes.
+    pub fn to_record(&self) -> Result<Record> {
+        let mut w = Writer::new();
+        w.write_u16(self.priority)?;
+        w.write_u16(self.weight)?;
+        w.write_u16(self.port)?;
+        w.write_name(&self.target)?;
+        let mut record = Record::new();
+        record.set_name(&self.name);
+        record.set_typ(Type::SRV);
+        record.set_class(Class::IN);
+        record.set_ttl(self.ttl);
+        record.set_data(w.to_bytes());
+        Ok(record)
+    }
+}
+
+impl FromStr for SRVRecord {
+    type Err = Error;
+
+    /// from_str parses the RDATA of the zone file format such as `0 0 80 host.local.`, and the owner name is empty.
+    fn from_str(rdata: &str) -> Result<SRVRecord> {
+        let fields = presentation::parse_fields(rdata, 4, "SRV")?;
+        Ok(SRVRecord::new(
+            "",
+            presentation::parse_u16(&fields[0])?,
+            presentation::parse_u16(&fields[1])?,
+            presentation::parse_u16(&fields[2])?,
+            &presentation::parse_name(&fields[3])?,
+        ))
+    }
+}
+
+// service_labels returns the service and protocol labels of the specified owner name, which are the first `_tcp` or `_udp` label
+// and the underscored label before it, so an instance label may hold dots or begin with an underscore.
+// RFC 6763: 7. Service Names
+fn service_labels(name: &str) -> (&str, &str) {
+    let labels: Vec<&str> = name.split('.').collect();
+    for n in 1..labels.len() {
+        let proto = labels[n];
+        if (proto.eq_ignore_ascii_case("_tcp") || proto.eq_ignore_ascii_case("_udp"))
+            && labels[n - 1].starts_with('_')
+        {
+            return (labels[n - 1], proto);
+        }
+    }
+    ("", "")
 }
 
 impl ResourceRecord for SRVRecord {
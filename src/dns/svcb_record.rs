@@ -0,0 +1,333 @@
+// Copyright (C) 2024 Satoshi Konno All rights reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//    http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::dns::class::Class;
+use crate::dns::error::{Error, ErrorKind, Result};
+use crate::dns::reader::Reader;
+use crate::dns::record::Record;
+use crate::dns::resource_record::ResourceRecord;
+use crate::dns::typ::Type;
+use crate::dns::writer::Writer;
+use std::fmt;
+use std::net::{Ipv4Addr, Ipv6Addr};
+
+/// SVC_PARAM_KEY_ALPN is the key of the supported protocol identifiers.
+/// RFC 9460: 7.1. "alpn" and "no-default-alpn"
+pub const SVC_PARAM_KEY_ALPN: u16 = 1;
+/// SVC_PARAM_KEY_PORT is the key of the alternative port.
+/// RFC 9460: 7.2. "port"
+pub const SVC_PARAM_KEY_PORT: u16 = 3;
+/// SVC_PARAM_KEY_IPV4HINT is the key of the IPv4 address hints.
+/// RFC 9460: 7.3. "ipv4hint" and "ipv6hint"
+pub const SVC_PARAM_KEY_IPV4HINT: u16 = 4;
+/// SVC_PARAM_KEY_IPV6HINT is the key of the IPv6 address hints.
+/// RFC 9460: 7.3. "ipv4hint" and "ipv6hint"
+pub const SVC_PARAM_KEY_IPV6HINT: u16 = 6;
+
+/// SVCBRecord represents a SVCB record, or a HTTPS record which shares its format.
+/// RFC 9460: 2.2. RDATA Wire Format
+pub struct SVCBRecord {
+    name: String,
+    typ: Type,
+    ttl: u32,
+    priority: u16,
+    target: String,
+    params: Vec<(u16, Vec<u8>)>,
+    content: String,
+}
+
+impl SVCBRecord {
+    /// new creates a new record of the specified type, SVCB or HTTPS, without any SvcParams.
+    pub fn new(typ: Type, name: &str, priority: u16, target: &str) -> SVCBRecord {
+        let mut svcb = SVCBRecord {
+            name: name.to_string(),
+            typ,
+            ttl: 0,
+            priority,
+            target: target.to_string(),
+            params: Vec::new(),
+            content: "".to_string(),
+        };
+        svcb.content = svcb.to_string();
+        svcb
+    }
+
+    /// from_record creates a new SVCB or HTTPS record from the specified record.
+    pub fn from_record(record: &Record) -> Result<SVCBRecord> {
+        let mut svcb = SVCBRecord::new(record.typ(), record.name(), 0, "");
+        svcb.ttl = record.ttl();
+        let data = record.data();
+        if data.is_empty() {
+            return Ok(svcb);
+        }
+        let mut reader = Reader::from_bytes(data);
+        svcb.priority = reader.read_u16()?;
+        svcb.target = reader.read_name()?;
+        while !reader.is_empty() {
+            let key = reader.read_u16()?;
+            // RFC 9460: 2.2. RDATA Wire Format
+            // SvcParamKeys SHALL appear in increasing numeric order.
+            if svcb.params.last().is_some_and(|(last, _)| key <= *last) {
+                return Err(Error::new(
+                    ErrorKind::InvalidRdata,
+                    &format!("Unordered SvcParamKey: {}", key),
+                ));
+            }
+            let mut value = vec![0; reader.read_u16()? as usize];
+            reader.read_bytes(&mut value)?;
+            validate_param(key, &value)?;
+            svcb.params.push((key, value));
+        }
+        svcb.content = svcb.to_string();
+        Ok(svcb)
+    }
+
+    /// name returns the owner name of the record.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// typ returns the type of the record, SVCB or HTTPS.
+    pub fn typ(&self) -> Type {
+        self.typ
+    }
+
+    /// ttl returns the TTL of the record.
+    pub fn ttl(&self) -> u32 {
+        self.ttl
+    }
+
+    /// priority returns the SvcPriority of the record.
+    pub fn priority(&self) -> u16 {
+        self.priority
+    }
+
+    /// is_alias returns true if the record is in the AliasMode whose priority is zero.
+    /// RFC 9460: 2.4.2. AliasMode
+    pub fn is_alias(&self) -> bool {
+        self.priority == 0
+    }
+
+    /// target returns the TargetName of the record, which is empty for the root name denoting the owner name.
+    pub fn target(&self) -> &str {
+        &self.target
+    }
+
+    /// param returns the value of the SvcParam of the specified key.
+    pub fn param(&self, key: u16) -> Option<&[u8]> {
+        self.params
+            .iter()
+            .find(|(k, _)| *k == key)
+            .map(|(_, value)| value.as_slice())
+    }
+
+    /// params returns the keys and values of all SvcParams in the increasing order of the keys.
+    pub fn params(&self) -> &[(u16, Vec<u8>)] {
+        &self.params
+    }
+
+    /// alpn returns the protocol identifiers of the alpn SvcParam.
+    pub fn alpn(&self) -> Vec<String> {
+        self.param(SVC_PARAM_KEY_ALPN)
+            .and_then(parse_alpn)
+            .unwrap_or_default()
+    }
+
+    /// port returns the alternative port of the port SvcParam.
+    pub fn port(&self) -> Option<u16> {
+        let value = self.param(SVC_PARAM_KEY_PORT)?;
+        Some(u16::from_be_bytes([value[0], value[1]]))
+    }
+
+    /// ipv4hint returns the addresses of the ipv4hint SvcParam.
+    pub fn ipv4hint(&self) -> Vec<Ipv4Addr> {
+        self.param(SVC_PARAM_KEY_IPV4HINT)
+            .unwrap_or_default()
+            .chunks_exact(4)
+            .map(|octets| Ipv4Addr::new(octets[0], octets[1], octets[2], octets[3]))
+            .collect()
+    }
+
+    /// ipv6hint returns the addresses of the ipv6hint SvcParam.
+    pub fn ipv6hint(&self) -> Vec<Ipv6Addr> {
+        self.param(SVC_PARAM_KEY_IPV6HINT)
+            .unwrap_or_default()
+            .chunks_exact(16)
+            .map(|octets| {
+                let octets: [u8; 16] = octets.try_into().unwrap_or_default();
+                Ipv6Addr::from(octets)
+            })
+            .collect()
+    }
+
+    /// set_name sets the owner name of the record.
+    pub fn set_name(&mut self, name: &str) {
+        self.name = name.to_string();
+    }
+
+    /// set_ttl sets the TTL of the record.
+    pub fn set_ttl(&mut self, ttl: u32) {
+        self.ttl = ttl;
+    }
+
+    /// set_param sets the value of the SvcParam of the specified key, keeping the keys in the increasing order.
+    pub fn set_param(&mut self, key: u16, value: &[u8]) -> Result<()> {
+        validate_param(key, value)?;
+        match self.params.binary_search_by_key(&key, |(k, _)| *k) {
+            Ok(n) => self.params[n].1 = value.to_vec(),
+            Err(n) => self.params.insert(n, (key, value.to_vec())),
+        }
+        self.content = self.to_string();
+        Ok(())
+    }
+
+    /// set_alpn sets the protocol identifiers of the alpn SvcParam.
+    pub fn set_alpn(&mut self, protocols: &[&str]) -> Result<()> {
+        let mut value = Vec::new();
+        for protocol in protocols {
+            if protocol.is_empty() || 255 < protocol.len() {
+                return Err(Error::new(
+                    ErrorKind::InvalidRdata,
+                    &format!("Invalid alpn: {:?}", protocol),
+                ));
+            }
+            value.push(protocol.len() as u8);
+            value.extend_from_slice(protocol.as_bytes());
+        }
+        self.set_param(SVC_PARAM_KEY_ALPN, &value)
+    }
+
+    /// set_port sets the alternative port of the port SvcParam.
+    pub fn set_port(&mut self, port: u16) -> Result<()> {
+        self.set_param(SVC_PARAM_KEY_PORT, &port.to_be_bytes())
+    }
+
+    /// set_ipv4hint sets the addresses of the ipv4hint SvcParam.
+    pub fn set_ipv4hint(&mut self, addrs: &[Ipv4Addr]) -> Result<()> {
+        let value: Vec<u8> = addrs.iter().flat_map(|addr| addr.octets()).collect();
+        self.set_param(SVC_PARAM_KEY_IPV4HINT, &value)
+    }
+
+    /// set_ipv6hint sets the addresses of the ipv6hint SvcParam.
+    pub fn set_ipv6hint(&mut self, addrs: &[Ipv6Addr]) -> Result<()> {
+        let value: Vec<u8> = addrs.iter().flat_map(|addr| addr.octets()).collect();
+        self.set_param(SVC_PARAM_KEY_IPV6HINT, &value)
+    }
+
+    /// to_record returns the record of the SVCB or HTTPS record, whose target name is never compressed.
+    /// RFC 9460: 2.2. RDATA Wire Format
+    pub fn to_record(&self) -> Result<Record> {
+        let mut w = Writer::new();
+        w.write_u16(self.priority)?;
+        w.write_name(&self.target)?;
+        for (key, value) in &self.params {
+            w.write_u16(*key)?;
+            w.write_u16(value.len() as u16)?;
+            w.write_bytes(value)?;
+        }
+        let mut record = Record::new();
+        record.set_name(&self.name);
+        record.set_typ(self.typ);
+        record.set_class(Class::IN);
+        record.set_ttl(self.ttl);
+        record.set_data(w.to_bytes());
+        Ok(record)
+    }
+}
+
+// parse_alpn returns the protocol identifiers of the specified alpn value, or None if an identifier is empty or truncated.
+fn parse_alpn(value: &[u8]) -> Option<Vec<String>> {
+    let mut protocols = Vec::new();
+    let mut rest = value;
+    while let Some((len, tail)) = rest.split_first() {
+        let len = *len as usize;
+        if len == 0 || tail.len() < len {
+            return None;
+        }
+        protocols.push(String::from_utf8_lossy(&tail[..len]).to_string());
+        rest = &tail[len..];
+    }
+    if protocols.is_empty() {
+        return None;
+    }
+    Some(protocols)
+}
+
+// validate_param returns an error if the value is malformed for the known keys.
+fn validate_param(key: u16, value: &[u8]) -> Result<()> {
+    let valid = match key {
+        SVC_PARAM_KEY_ALPN => parse_alpn(value).is_some(),
+        SVC_PARAM_KEY_PORT => value.len() == 2,
+        SVC_PARAM_KEY_IPV4HINT => !value.is_empty() && value.len().is_multiple_of(4),
+        SVC_PARAM_KEY_IPV6HINT => !value.is_empty() && value.len().is_multiple_of(16),
+        _ => value.len() <= u16::MAX as usize,
+    };
+    if !valid {
+        return Err(Error::new(
+            ErrorKind::InvalidRdata,
+            &format!("Invalid SvcParam: key{} ({} bytes)", key, value.len()),
+        ));
+    }
+    Ok(())
+}
+
+impl ResourceRecord for SVCBRecord {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn typ(&self) -> Type {
+        self.typ
+    }
+
+    fn content(&self) -> &str {
+        &self.content
+    }
+}
+
+// RFC 9460: 2.1. Zone-File Presentation Format
+impl fmt::Display for SVCBRecord {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} {}.", self.priority, self.target)?;
+        for (key, value) in &self.params {
+            match *key {
+                SVC_PARAM_KEY_ALPN => write!(f, " alpn={}", self.alpn().join(","))?,
+                SVC_PARAM_KEY_PORT => write!(f, " port={}", self.port().unwrap_or_default())?,
+                SVC_PARAM_KEY_IPV4HINT => {
+                    let addrs: Vec<String> =
+                        self.ipv4hint().iter().map(|a| a.to_string()).collect();
+                    write!(f, " ipv4hint={}", addrs.join(","))?
+                }
+                SVC_PARAM_KEY_IPV6HINT => {
+                    let addrs: Vec<String> =
+                        self.ipv6hint().iter().map(|a| a.to_string()).collect();
+                    write!(f, " ipv6hint={}", addrs.join(","))?
+                }
+                key if value.is_empty() => write!(f, " key{}", key)?,
+                key => {
+                    write!(f, " key{}=\"", key)?;
+                    for b in value {
+                        match b {
+                            b'"' | b'\\' => write!(f, "\\{}", *b as char)?,
+                            0x20..=0x7e => write!(f, "{}", *b as char)?,
+                            _ => write!(f, "\\{:03}", b)?,
+                        }
+                    }
+                    write!(f, "\"")?
+                }
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,21 @@
+// Copyright (C) 2024 Satoshi Konno All rights reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//    http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+/// DEFAULT_HOST_RECORD_TTL is the default TTL of the records bound to the host name such as SRV, TXT and A/AAAA.
+/// RFC 6762: 10. Resource Record TTL Values and Cache Coherency
+pub const DEFAULT_HOST_RECORD_TTL: u32 = 120;
+
+/// DEFAULT_OTHER_RECORD_TTL is the default TTL of the other records such as PTR.
+/// RFC 6762: 10. Resource Record TTL Values and Cache Coherency
+pub const DEFAULT_OTHER_RECORD_TTL: u32 = 4500;
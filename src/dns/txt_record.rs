@@ -12,18 +12,25 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use crate::dns::error::Result;
+use crate::dns::class::Class;
+use crate::dns::error::{Error, ErrorKind, Result};
+use crate::dns::presentation;
 use crate::dns::reader::Reader;
 use crate::dns::record::Record;
 use crate::dns::resource_record::ResourceRecord;
 use crate::dns::typ::Type;
+use crate::dns::writer::Writer;
+use crate::txt;
 use std::collections::HashMap;
 use std::fmt;
+use std::str::FromStr;
 
 pub struct TXTRecord {
     name: String,
     strs: Vec<String>,
+    byte_strs: Vec<Vec<u8>>,
     attrs: HashMap<String, String>,
+    attr_bytes: HashMap<String, Vec<u8>>,
 }
 
 impl TXTRecord {
@@ -31,20 +38,55 @@ impl TXTRecord {
     pub fn from_record(record: &Record) -> Result<TXTRecord> {
         let data = record.data();
         let mut reader = Reader::from_bytes(data);
-        let strs = reader.read_strings()?;
+        let strs = reader.read_byte_strings()?;
+        Ok(TXTRecord::from_strings(record.name(), strs))
+    }
+
+    /// with_attributes creates a new TXT record of the specified attributes in their iteration order,
+    /// so a Vec of pairs keeps the insertion order, and `&HashMap<String, String>` is accepted as well.
+    /// The attributes of an empty key and the later ones of a duplicate key are ignored.
+    pub fn with_attributes<I, K, V>(name: &str, attrs: I) -> TXTRecord
+    where
+        I: IntoIterator<Item = (K, V)>,
+        K: AsRef<str>,
+        V: AsRef<[u8]>,
+    {
+        let mut keys: Vec<String> = Vec::new();
+        let mut strs = Vec::new();
+        for (key, value) in attrs {
+            let key = key.as_ref();
+            if key.is_empty() || keys.iter().any(|k| txt::key_eq(k, key)) {
+                continue;
+            }
+            keys.push(key.to_string());
+            strs.push(txt::format_attribute_bytes(key, value.as_ref()));
+        }
+        TXTRecord::from_strings(name, strs)
+    }
+
+    // from_strings creates a new TXT record of the specified strings, where the first one of the duplicate keys is used.
+    // The text attributes have the values which are not UTF-8 replaced, and the byte attributes keep them as they are.
+    fn from_strings(name: &str, byte_strs: Vec<Vec<u8>>) -> TXTRecord {
         let mut attrs = HashMap::new();
-        for s in &strs {
-            let mut kv = s.splitn(2, '=');
-            let key = kv.next().unwrap().to_string();
-            let value = kv.next().unwrap_or("").to_string();
-            attrs.insert(key, value);
+        let mut attr_bytes = HashMap::new();
+        for s in &byte_strs {
+            let (key, value) = txt::parse_attribute_bytes(s);
+            if key.is_empty() || txt::attribute(&attr_bytes, &key).is_some() {
+                continue;
+            }
+            attrs.insert(key.clone(), String::from_utf8_lossy(&value).to_string());
+            attr_bytes.insert(key, value);
+        }
+        TXTRecord {
+            name: name.to_string(),
+            strs: byte_strs
+                .iter()
+                .map(|s| String::from_utf8_lossy(s).to_string())
+                .collect(),
+            byte_strs,
+            attrs,
+            attr_bytes,
         }
-        let txt = TXTRecord {
-            name: record.name().to_string(),
-            strs: strs,
-            attrs: attrs,
-        };
-        Ok(txt)
     }
 
     /// name returns the name of the TXT record.
@@ -64,7 +106,60 @@ impl TXTRecord {
 
     /// attribute returns the attribute of the TXT record.
     pub fn attribute(&self, key: &str) -> Option<&String> {
-        self.attrs.get(key)
+        txt::attribute(&self.attrs, key)
+    }
+
+    /// attributes_bytes returns the attributes of the TXT record whose values are kept as bytes.
+    pub fn attributes_bytes(&self) -> &HashMap<String, Vec<u8>> {
+        &self.attr_bytes
+    }
+
+    /// attribute_bytes returns the value of the specified key as bytes, such as the binary values some protocols pack into TXT.
+    pub fn attribute_bytes(&self, key: &str) -> Option<&[u8]> {
+        txt::attribute(&self.attr_bytes, key).map(|value| value.as_slice())
+    }
+
+    /// set_name sets the owner name of the TXT record.
+    pub fn set_name(&mut self, name: &str) {
+        self.name = name.to_string();
+    }
+
+    /// to_record returns the record of the TXT record, or an error if a string is longer than 255 bytes.
+    pub fn to_record(&self) -> Result<Record> {
+        let mut w = Writer::new();
+        for s in &self.byte_strs {
+            if (u8::MAX as usize) < s.len() {
+                return Err(Error::new(
+                    ErrorKind::InvalidRdata,
+                    &format!("TXT string too long: {}", String::from_utf8_lossy(s)),
+                ));
+            }
+            w.write_u8(s.len() as u8)?;
+            w.write_bytes(s)?;
+        }
+        // RFC 6763: 6.1. General Format Rules for DNS TXT Records
+        if self.byte_strs.is_empty() {
+            w.write_u8(0)?;
+        }
+        let mut record = Record::new();
+        record.set_name(&self.name);
+        record.set_typ(Type::TXT);
+        record.set_class(Class::IN);
+        record.set_data(w.to_bytes());
+        Ok(record)
+    }
+}
+
+impl FromStr for TXTRecord {
+    type Err = Error;
+
+    /// from_str parses the RDATA of the zone file format such as `"path=/" "version=1"`, and the owner name is empty.
+    fn from_str(rdata: &str) -> Result<TXTRecord> {
+        let strs = presentation::tokenize(rdata)?
+            .into_iter()
+            .map(|s| s.into_bytes())
+            .collect();
+        Ok(TXTRecord::from_strings("", strs))
     }
 }
 
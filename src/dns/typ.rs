@@ -16,22 +16,31 @@ use std::fmt::*;
 
 pub const TYPE_MASK: u16 = 0x7fff;
 
-#[derive(Debug, Copy, Clone, PartialEq)]
+#[derive(Debug, Copy, Clone, PartialEq, Default)]
 pub enum Type {
-    NONE = 0x0000,
-    A = 0x0001,
-    NS = 0x0002,
-    CNAME = 0x0005,
-    SOA = 0x0006,
-    PTR = 0x000c,
-    MX = 0x000f,
-    TXT = 0x0010,
-    AAAA = 0x001c,
-    SRV = 0x0021,
-    NAPTR = 0x0023,
-    OPT = 0x0029,
-    ANY = 0x00ff,
-    NSEC = 0x0027,
+    #[default]
+    NONE,
+    A,
+    NS,
+    CNAME,
+    SOA,
+    PTR,
+    MX,
+    TXT,
+    AAAA,
+    LOC,
+    SRV,
+    NAPTR,
+    OPT,
+    ANY,
+    NSEC,
+    RRSIG,
+    DNSKEY,
+    SVCB,
+    HTTPS,
+    URI,
+    /// Unknown is a type without a dedicated variant, which keeps its value so the record can be written again.
+    Unknown(u16),
 }
 
 impl Type {
@@ -46,12 +55,19 @@ impl Type {
             0x000f => Type::MX,
             0x0010 => Type::TXT,
             0x001c => Type::AAAA,
+            0x001d => Type::LOC,
             0x0021 => Type::SRV,
             0x0023 => Type::NAPTR,
             0x0029 => Type::OPT,
             0x00ff => Type::ANY,
-            0x0027 => Type::NSEC,
-            _ => Type::NONE,
+            0x002f => Type::NSEC,
+            0x002e => Type::RRSIG,
+            0x0030 => Type::DNSKEY,
+            0x0040 => Type::SVCB,
+            0x0041 => Type::HTTPS,
+            0x0100 => Type::URI,
+            0x0000 => Type::NONE,
+            value => Type::Unknown(value),
         }
     }
 
@@ -66,44 +82,49 @@ impl Type {
             Type::MX => 0x000f,
             Type::TXT => 0x0010,
             Type::AAAA => 0x001c,
+            Type::LOC => 0x001d,
             Type::SRV => 0x0021,
             Type::NAPTR => 0x0023,
             Type::OPT => 0x0029,
             Type::ANY => 0x00ff,
             Type::NONE => 0x0000,
-            Type::NSEC => 0x0027,
+            Type::NSEC => 0x002f,
+            Type::RRSIG => 0x002e,
+            Type::DNSKEY => 0x0030,
+            Type::SVCB => 0x0040,
+            Type::HTTPS => 0x0041,
+            Type::URI => 0x0100,
+            Type::Unknown(value) => *value,
         }
     }
-
-    /// to_string returns the string representation of the type.
-    pub fn to_string(&self) -> String {
-        match self {
-            Type::A => "A".to_string(),
-            Type::NS => "NS".to_string(),
-            Type::CNAME => "CNAME".to_string(),
-            Type::SOA => "SOA".to_string(),
-            Type::PTR => "PTR".to_string(),
-            Type::MX => "MX".to_string(),
-            Type::TXT => "TXT".to_string(),
-            Type::AAAA => "AAAA".to_string(),
-            Type::SRV => "SRV".to_string(),
-            Type::NAPTR => "NAPTR".to_string(),
-            Type::OPT => "OPT".to_string(),
-            Type::ANY => "ANY".to_string(),
-            Type::NONE => "NONE".to_string(),
-            Type::NSEC => "NSEC".to_string(),
-        }
-    }
-}
-
-impl Default for Type {
-    fn default() -> Type {
-        Type::NONE
-    }
 }
 
 impl Display for Type {
     fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
-        write!(f, "{}", self.to_string())
+        let s = match self {
+            Type::A => "A",
+            Type::NS => "NS",
+            Type::CNAME => "CNAME",
+            Type::SOA => "SOA",
+            Type::PTR => "PTR",
+            Type::MX => "MX",
+            Type::TXT => "TXT",
+            Type::AAAA => "AAAA",
+            Type::LOC => "LOC",
+            Type::SRV => "SRV",
+            Type::NAPTR => "NAPTR",
+            Type::OPT => "OPT",
+            Type::ANY => "ANY",
+            Type::NONE => "NONE",
+            Type::NSEC => "NSEC",
+            Type::RRSIG => "RRSIG",
+            Type::DNSKEY => "DNSKEY",
+            Type::SVCB => "SVCB",
+            Type::HTTPS => "HTTPS",
+            Type::URI => "URI",
+            // RFC 3597: 5. Text Representation
+            Type::Unknown(value) => return write!(f, "TYPE{}", value),
+        };
+        write!(f, "{}", s)
     }
 }
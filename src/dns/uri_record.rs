@@ -0,0 +1,153 @@
+// Copyright (C) 2024 Satoshi Konno All rights reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//    http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::dns::class::Class;
+use crate::dns::error::{Error, ErrorKind, Result};
+use crate::dns::presentation;
+use crate::dns::reader::Reader;
+use crate::dns::record::Record;
+use crate::dns::resource_record::ResourceRecord;
+use crate::dns::typ::Type;
+use crate::dns::writer::Writer;
+use std::fmt;
+use std::str::FromStr;
+
+/// URIRecord represents a URI record, which publishes an endpoint of the owner name directly as a URI.
+/// RFC 7553: 4.5. URI RDATA Wire Format
+pub struct URIRecord {
+    name: String,
+    priority: u16,
+    weight: u16,
+    target: String,
+    content: String,
+}
+
+impl URIRecord {
+    /// new creates a new URI record of the specified owner name, priority, weight and target URI.
+    pub fn new(name: &str, priority: u16, weight: u16, target: &str) -> URIRecord {
+        let mut uri = URIRecord {
+            name: name.to_string(),
+            priority,
+            weight,
+            target: target.to_string(),
+            content: "".to_string(),
+        };
+        uri.content = uri.to_string();
+        uri
+    }
+
+    /// from_record creates a new URI record from the specified record.
+    pub fn from_record(record: &Record) -> Result<URIRecord> {
+        let data = record.data();
+        let mut reader = Reader::from_bytes(data);
+        let priority = reader.read_u16()?;
+        let weight = reader.read_u16()?;
+        // RFC 7553: 4.4. Target
+        // The target is not a character-string, and fills the rest of the data.
+        let target = std::str::from_utf8(&data[4..])
+            .map_err(|_| Error::from_bytes_with_kind(ErrorKind::InvalidRdata, data, 4))?;
+        if target.is_empty() {
+            return Err(Error::from_bytes_with_kind(
+                ErrorKind::InvalidRdata,
+                data,
+                4,
+            ));
+        }
+        Ok(URIRecord::new(record.name(), priority, weight, target))
+    }
+
+    /// priority returns the priority of the target, where the lower values are preferred.
+    pub fn priority(&self) -> u16 {
+        self.priority
+    }
+
+    /// weight returns the relative weight among the targets of the same priority.
+    pub fn weight(&self) -> u16 {
+        self.weight
+    }
+
+    /// target returns the target URI.
+    pub fn target(&self) -> &str {
+        &self.target
+    }
+
+    /// set_name sets the owner name of the URI record.
+    pub fn set_name(&mut self, name: &str) {
+        self.name = name.to_string();
+    }
+
+    /// to_record returns the record of the URI record.
+    pub fn to_record(&self) -> Result<Record> {
+        let mut w = Writer::new();
+        w.write_u16(self.priority)?;
+        w.write_u16(self.weight)?;
+        w.write_bytes(self.target.as_bytes())?;
+        let mut record = Record::new();
+        record.set_name(&self.name);
+        record.set_typ(Type::URI);
+        record.set_class(Class::IN);
+        record.set_data(w.to_bytes());
+        Ok(record)
+    }
+}
+
+impl FromStr for URIRecord {
+    type Err = Error;
+
+    /// from_str parses the RDATA of the zone file format such as `10 1 "ftp://ftp1.example.com/public"`, and the owner name is empty.
+    fn from_str(rdata: &str) -> Result<URIRecord> {
+        let fields = presentation::parse_fields(rdata, 3, "URI")?;
+        if fields[2].is_empty() {
+            return Err(Error::new(
+                ErrorKind::InvalidText,
+                &format!("Invalid URI RDATA: {:?}", rdata),
+            ));
+        }
+        Ok(URIRecord::new(
+            "",
+            presentation::parse_u16(&fields[0])?,
+            presentation::parse_u16(&fields[1])?,
+            &fields[2],
+        ))
+    }
+}
+
+impl ResourceRecord for URIRecord {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn typ(&self) -> Type {
+        Type::URI
+    }
+
+    fn content(&self) -> &str {
+        &self.content
+    }
+}
+
+// RFC 7553: 4.5. URI RDATA Wire Format
+// The target is presented as a quoted string.
+impl fmt::Display for URIRecord {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} {} \"", self.priority, self.weight)?;
+        for c in self.target.chars() {
+            if c == '"' || c == '\\' {
+                write!(f, "\\")?;
+            }
+            write!(f, "{}", c)?;
+        }
+        write!(f, "\"")
+    }
+}
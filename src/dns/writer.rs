@@ -12,21 +12,57 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::collections::HashMap;
+
 use crate::dns::class::Class;
 use crate::dns::class::UNICAST_RESPONSE_MASK;
-use crate::dns::error::Result;
+use crate::dns::error::{Error, ErrorKind, Result};
+use crate::dns::question::Question;
+use crate::dns::reader::Reader;
 use crate::dns::record::Record;
 use crate::dns::typ::Type;
 
+// RFC 1035: 4.1.4. Message compression
+const POINTER_MASK: u16 = 0xc000;
+const POINTER_MAX_OFFSET: usize = 0x3fff;
+const MAX_LABEL_LEN: usize = 63;
+
 /// Writer represents a DNS writer.
 pub struct Writer {
     buffer: Vec<u8>,
+    names: HashMap<String, usize>,
+    name_compression: bool,
+    srv_compression: bool,
 }
 
 impl Writer {
     /// new creates a new writer.
     pub fn new() -> Writer {
-        Writer { buffer: Vec::new() }
+        Writer {
+            buffer: Vec::new(),
+            names: HashMap::new(),
+            name_compression: true,
+            srv_compression: false,
+        }
+    }
+
+    /// set_name_compression sets whether the owner names and the names embedded in the records are compressed,
+    /// which is enabled by default. The previously written names are still remembered while it is disabled.
+    /// RFC 1035: 4.1.4. Message compression
+    pub fn set_name_compression(&mut self, enabled: bool) {
+        self.name_compression = enabled;
+    }
+
+    /// name_compression returns true if the names of the records are compressed.
+    pub fn name_compression(&self) -> bool {
+        self.name_compression
+    }
+
+    /// set_srv_compression sets whether the SRV targets are compressed.
+    /// RFC 2782 forbids compressing the target, but RFC 6762: 18.14. Name Compression allows it in Multicast DNS,
+    /// so it can be enabled for the peers which are known to accept it.
+    pub fn set_srv_compression(&mut self, enabled: bool) {
+        self.srv_compression = enabled;
     }
 
     /// write_u8 writes a u8 value.
@@ -66,7 +102,7 @@ impl Writer {
 
     /// write_type writes a type.
     pub fn write_type(&mut self, typ: Type) -> Result<()> {
-        self.write_u16(typ as u16)
+        self.write_u16(typ.to_value())
     }
 
     /// write_class writes a class.
@@ -86,24 +122,54 @@ impl Writer {
         self.write_bytes(data)
     }
 
-    /// write_name writes a domain name.
+    /// write_name writes a domain name without compression.
+    /// The empty labels are skipped, so the root name and a trailing dot are written as the terminating zero label.
     pub fn write_name(&mut self, name: &str) -> Result<()> {
-        let labels = name.split('.');
-        for label in labels {
-            let len = label.len();
-            self.write_u8(len as u8)?;
-            for c in label.chars() {
-                self.write_u8(c as u8)?;
+        self.write_name_with(name, false)
+    }
+
+    /// write_compressed_name writes a domain name, replacing the longest suffix written before with a pointer.
+    /// RFC 1035: 4.1.4. Message compression
+    pub fn write_compressed_name(&mut self, name: &str) -> Result<()> {
+        self.write_name_with(name, true)
+    }
+
+    // write_name_with writes a domain name, and remembers its suffixes as the targets of the later pointers.
+    fn write_name_with(&mut self, name: &str, compress: bool) -> Result<()> {
+        let labels: Vec<&str> = name.split('.').filter(|label| !label.is_empty()).collect();
+        for n in 0..labels.len() {
+            let suffix = labels[n..].join(".");
+            if compress {
+                if let Some(offset) = self.names.get(&suffix) {
+                    return self.write_u16(POINTER_MASK | *offset as u16);
+                }
+            }
+            if self.buffer.len() <= POINTER_MAX_OFFSET {
+                self.names.entry(suffix).or_insert(self.buffer.len());
+            }
+            let label = labels[n].as_bytes();
+            // RFC 1035: 2.3.4. Size limits
+            if MAX_LABEL_LEN < label.len() {
+                return Err(Error::new(
+                    ErrorKind::InvalidLabel,
+                    &format!("Label too long: {}", labels[n]),
+                ));
             }
+            self.write_u8(label.len() as u8)?;
+            self.write_bytes(label)?;
         }
         self.write_u8(0)?;
         Ok(())
     }
 
-    /// write_request_record writes a request record.
+    /// write_request_record writes a request record, whose owner name is compressed unless the name compression is disabled.
     pub fn write_request_record(&mut self, record: &Record) -> Result<()> {
-        self.write_name(record.name())?;
+        self.write_name_with(record.name(), self.name_compression)?;
         self.write_type(record.typ())?;
+        if record.typ() == Type::OPT {
+            self.write_u16(record.udp_payload_size())?;
+            return Ok(());
+        }
         let mut cls = record.class() as u16;
         if record.unicast_response() {
             cls |= UNICAST_RESPONSE_MASK;
@@ -112,11 +178,55 @@ impl Writer {
         Ok(())
     }
 
+    /// write_question writes a question, whose name is compressed unless the name compression is disabled.
+    pub fn write_question(&mut self, question: &Question) -> Result<()> {
+        self.write_name_with(question.name(), self.name_compression)?;
+        self.write_type(question.typ())?;
+        let mut cls = question.class() as u16;
+        if question.unicast_response() {
+            cls |= UNICAST_RESPONSE_MASK;
+        }
+        self.write_u16(cls)?;
+        Ok(())
+    }
+
     /// write_response_record writes a response record.
     pub fn write_response_record(&mut self, record: &Record) -> Result<()> {
         self.write_request_record(record)?;
         self.write_ttl(record.ttl())?;
-        self.write_data(record.data())?;
+        self.write_rdata(record)?;
+        Ok(())
+    }
+
+    // write_rdata writes the data of the specified record, compressing the embedded names where it is legal.
+    // The names which are never compressed are still remembered, so the later names can point to them.
+    fn write_rdata(&mut self, record: &Record) -> Result<()> {
+        let data = record.data();
+        let (prefix_len, compress) = match record.typ() {
+            Type::PTR | Type::CNAME | Type::NS => (0, true),
+            Type::SRV => (6, self.srv_compression),
+            // RFC 4034: 4.1.1. The Next Domain Field
+            Type::NSEC => (0, false),
+            _ => return self.write_data(data),
+        };
+        if data.len() < prefix_len {
+            return self.write_data(data);
+        }
+        let mut reader = Reader::from_bytes(data);
+        reader.set_offset(prefix_len);
+        let name = match reader.read_name() {
+            Ok(name) => name,
+            Err(_) => return self.write_data(data),
+        };
+        let suffix = &data[reader.offset()..];
+
+        let len_offset = self.buffer.len();
+        self.write_u16(0)?;
+        self.write_bytes(&data[..prefix_len])?;
+        self.write_name_with(&name, compress && self.name_compression)?;
+        self.write_bytes(suffix)?;
+        let len = (self.buffer.len() - len_offset - 2) as u16;
+        self.buffer[len_offset..len_offset + 2].copy_from_slice(&len.to_be_bytes());
         Ok(())
     }
 
@@ -125,3 +235,9 @@ impl Writer {
         self.buffer.clone()
     }
 }
+
+impl Default for Writer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,148 @@
+// Copyright (C) 2024 Satoshi Konno All rights reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//    http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+#[cfg(test)]
+mod tests {
+
+    use crate::dns::message::Message;
+    use crate::dns::{PTRRecord, Record, SRVRecord, Type, Writer};
+    use crate::fixture_test::{name_data, record, srv_data};
+
+    fn contains(bytes: &[u8], sub: &[u8]) -> usize {
+        bytes.windows(sub.len()).filter(|w| *w == sub).count()
+    }
+
+    #[test]
+    fn write_rdata_names() {
+        let mut msg = Message::new();
+        msg.add_answer(record(
+            "_http._tcp.local",
+            Type::PTR,
+            name_data("web._http._tcp.local"),
+        ));
+        msg.add_answer(record(
+            "web._http._tcp.local",
+            Type::SRV,
+            srv_data(80, "host.local"),
+        ));
+        msg.add_answer(record("host.local", Type::A, vec![192, 168, 1, 10]));
+        let bytes = msg.to_bytes().unwrap();
+
+        // The PTR target is compressed against the owner name, and the later owner names against the earlier names,
+        // but the SRV target is not.
+        assert_eq!(contains(&bytes, b"\x03web"), 1);
+        assert_eq!(contains(&bytes, b"\x03web\xc0\x0c"), 1);
+        assert_eq!(contains(&bytes, b"\x04host\x05local\x00"), 1);
+
+        let parsed = Message::from_bytes(&bytes).unwrap();
+        let ptr = PTRRecord::from_record(&parsed.answers()[0]).unwrap();
+        assert_eq!(ptr.domain_name(), "web._http._tcp.local");
+        let srv = SRVRecord::from_record(&parsed.answers()[1]).unwrap();
+        assert_eq!(srv.target(), "host.local");
+        assert_eq!(srv.port(), 80);
+        assert_eq!(parsed.answers()[1].data(), msg.answers()[1].data());
+    }
+
+    #[test]
+    fn write_srv_compression() {
+        let srv = record(
+            "web._http._tcp.local",
+            Type::SRV,
+            srv_data(80, "web._http._tcp.local"),
+        );
+        let mut w = Writer::new();
+        assert!(w.write_response_record(&srv).is_ok());
+        let uncompressed = w.to_bytes();
+
+        let mut w = Writer::new();
+        w.set_srv_compression(true);
+        assert!(w.write_response_record(&srv).is_ok());
+        let compressed = w.to_bytes();
+        assert_eq!(uncompressed.len() - compressed.len(), 20);
+        assert!(compressed.ends_with(&[0, 80, 0xc0, 0x00]));
+    }
+
+    #[test]
+    fn rdata_names_of_captures() {
+        let captures = vec![
+            include_bytes!("log/matter-spec-120-4.3.1.13-dns-sd.bin").to_vec(),
+            include_bytes!("log/matter-spec-120-4.3.1.13-avahi01.bin").to_vec(),
+            include_bytes!("log/matter-spec-120-4.3.1.13-avahi02.bin").to_vec(),
+        ];
+        for capture in captures {
+            let msg = Message::from_bytes(&capture).unwrap();
+            let records: Vec<&Record> = msg.answers().iter().chain(msg.additionals()).collect();
+            for record in &records {
+                match record.typ() {
+                    Type::PTR => assert!(PTRRecord::from_record(record).is_ok()),
+                    Type::SRV => assert!(SRVRecord::from_record(record).is_ok()),
+                    _ => {}
+                }
+            }
+
+            // The expanded names are compressed again, and read back into the same records.
+            let bytes = msg.to_bytes().unwrap();
+            let reparsed = Message::from_bytes(&bytes).unwrap();
+            let reparsed_records: Vec<&Record> = reparsed
+                .answers()
+                .iter()
+                .chain(reparsed.additionals())
+                .collect();
+            assert_eq!(records.len(), reparsed_records.len());
+            for (record, reparsed) in records.iter().zip(reparsed_records) {
+                assert_eq!(record.name(), reparsed.name());
+                assert_eq!(record.typ(), reparsed.typ());
+                assert_eq!(record.data(), reparsed.data());
+            }
+        }
+    }
+
+    #[test]
+    fn write_name_compression() {
+        let mut msg = Message::new();
+        for instance in ["a", "b", "c"] {
+            let name = format!("{}._http._tcp.local", instance);
+            msg.add_answer(record("_http._tcp.local", Type::PTR, name_data(&name)));
+            msg.add_additional(record(&name, Type::SRV, srv_data(80, "host.local")));
+        }
+        msg.add_additional(record("host.local", Type::A, vec![192, 168, 1, 10]));
+
+        let compressed = msg.to_bytes().unwrap();
+        let uncompressed = msg.to_bytes_with_compression(false).unwrap();
+        assert!(compressed.len() < uncompressed.len());
+        assert_eq!(contains(&compressed, b"\x05_http\x04_tcp\x05local\x00"), 1);
+        assert_eq!(
+            contains(&uncompressed, b"\x05_http\x04_tcp\x05local\x00"),
+            9
+        );
+        assert!(!uncompressed
+            .windows(2)
+            .any(|w| w[0] & 0xc0 == 0xc0 && w[1] == 0x0c));
+
+        for bytes in [compressed, uncompressed] {
+            let parsed = Message::from_bytes(&bytes).unwrap();
+            let records: Vec<&Record> = parsed
+                .answers()
+                .iter()
+                .chain(parsed.additionals())
+                .collect();
+            let expected: Vec<&Record> = msg.answers().iter().chain(msg.additionals()).collect();
+            assert_eq!(records.len(), expected.len());
+            for (record, expected) in records.iter().zip(expected) {
+                assert_eq!(record.name(), expected.name());
+                assert_eq!(record.data(), expected.data());
+            }
+        }
+    }
+}
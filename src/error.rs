@@ -21,6 +21,7 @@ pub struct Error {
 
 impl Error {
     /// from_str creates a new Error with the specified string.
+    #[allow(clippy::should_implement_trait)]
     pub fn from_str(str: &str) -> Error {
         Error {
             msg: str.to_string(),
@@ -28,8 +29,10 @@ impl Error {
     }
 
     /// from_string creates a new Error with the specified string.
-    pub fn from_string(str: &String) -> Error {
-        Error { msg: str.clone() }
+    pub fn from_string(str: &str) -> Error {
+        Error {
+            msg: str.to_string(),
+        }
     }
 
     /// message returns the error message.
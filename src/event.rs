@@ -0,0 +1,127 @@
+// Copyright (C) 2024 Satoshi Konno All rights reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//    http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::mpsc;
+
+use crate::conflict::Conflict;
+use crate::service::Service;
+
+/// ServiceEvent represents a change of the discovered services.
+#[derive(Clone)]
+pub enum ServiceEvent {
+    /// Added is delivered when a service is discovered for the first time.
+    Added(Service),
+    /// Updated is delivered when a known service is announced again.
+    Updated(Service),
+    /// Removed is delivered when a service is no longer available.
+    Removed(Service),
+    /// ConflictDetected is delivered when two hosts assert conflicting unique records of the same name.
+    ConflictDetected(Conflict),
+}
+
+impl ServiceEvent {
+    /// service returns the service of the event, or None if the event is not about a service.
+    pub fn service(&self) -> Option<&Service> {
+        match self {
+            ServiceEvent::Added(service) => Some(service),
+            ServiceEvent::Updated(service) => Some(service),
+            ServiceEvent::Removed(service) => Some(service),
+            ServiceEvent::ConflictDetected(_) => None,
+        }
+    }
+}
+
+/// ServiceCallback is called with the service of each event it is registered for.
+pub type ServiceCallback = Box<dyn Fn(&Service) + Send>;
+
+/// EventSender represents a sending half of a subscribed event channel, or a callback of a kind of events.
+pub(crate) enum EventSender {
+    Std(mpsc::Sender<ServiceEvent>),
+    Added(ServiceCallback),
+    Updated(ServiceCallback),
+    Removed(ServiceCallback),
+    #[cfg(feature = "crossbeam")]
+    Crossbeam(crossbeam_channel::Sender<ServiceEvent>),
+    #[cfg(feature = "flume")]
+    Flume(flume::Sender<ServiceEvent>),
+    #[cfg(feature = "tokio")]
+    Tokio(tokio::sync::mpsc::UnboundedSender<ServiceEvent>),
+}
+
+impl EventSender {
+    /// send sends the specified event, and returns false if the receiver has been dropped.
+    pub(crate) fn send(&self, event: ServiceEvent) -> bool {
+        match self {
+            EventSender::Std(tx) => tx.send(event).is_ok(),
+            EventSender::Added(callback) => {
+                if let ServiceEvent::Added(service) = &event {
+                    callback(service);
+                }
+                true
+            }
+            EventSender::Updated(callback) => {
+                if let ServiceEvent::Updated(service) = &event {
+                    callback(service);
+                }
+                true
+            }
+            EventSender::Removed(callback) => {
+                if let ServiceEvent::Removed(service) = &event {
+                    callback(service);
+                }
+                true
+            }
+            #[cfg(feature = "crossbeam")]
+            EventSender::Crossbeam(tx) => tx.send(event).is_ok(),
+            #[cfg(feature = "flume")]
+            EventSender::Flume(tx) => tx.send(event).is_ok(),
+            #[cfg(feature = "tokio")]
+            EventSender::Tokio(tx) => tx.send(event).is_ok(),
+        }
+    }
+}
+
+/// EventSenders represents the subscribers of the service events.
+pub(crate) struct EventSenders {
+    senders: Vec<EventSender>,
+}
+
+impl EventSenders {
+    /// new creates an empty subscriber list.
+    pub(crate) fn new() -> EventSenders {
+        EventSenders {
+            senders: Vec::new(),
+        }
+    }
+
+    /// add adds the specified subscriber.
+    pub(crate) fn add(&mut self, sender: EventSender) {
+        self.senders.push(sender);
+    }
+
+    /// len returns the number of the subscribers.
+    pub(crate) fn len(&self) -> usize {
+        self.senders.len()
+    }
+
+    /// clear drops all subscribers, which disconnects their receivers.
+    pub(crate) fn clear(&mut self) {
+        self.senders.clear();
+    }
+
+    /// publish sends the specified event to all subscribers, and drops the disconnected ones.
+    pub(crate) fn publish(&mut self, event: &ServiceEvent) {
+        self.senders.retain(|sender| sender.send(event.clone()));
+    }
+}
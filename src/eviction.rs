@@ -0,0 +1,79 @@
+// Copyright (C) 2024 Satoshi Konno All rights reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//    http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::fmt;
+
+use crate::cache::CachedRecord;
+
+/// EvictionReason represents why a record left the cache.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum EvictionReason {
+    /// Expired is the reason of a record whose TTL has elapsed.
+    Expired,
+    /// Goodbye is the reason of a record withdrawn by a goodbye packet with zero TTL.
+    /// RFC 6762: 10.1. Goodbye Packets
+    Goodbye,
+    /// CacheFlush is the reason of a record replaced by a newer record with the cache-flush bit.
+    /// RFC 6762: 10.2. Announcements to Flush Outdated Cache Entries
+    CacheFlush,
+    /// Poof is the reason of a record flushed by the passive observation of failures.
+    /// RFC 6762: 10.5. Passive Observation Of Failures (POOF)
+    Poof,
+    /// Flush is the reason of a record removed by an explicit flush of the cache.
+    Flush,
+    /// Limit is the reason of a record evicted to keep the cache within its capacity.
+    Limit,
+    /// Conflict is the reason of a record replaced by a conflicting record from another host,
+    /// since its owner did not announce it again while it was reconfirmed.
+    /// RFC 6762: 9. Conflict Resolution
+    Conflict,
+}
+
+impl fmt::Display for EvictionReason {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let reason = match self {
+            EvictionReason::Expired => "expired",
+            EvictionReason::Goodbye => "goodbye",
+            EvictionReason::CacheFlush => "cache-flush",
+            EvictionReason::Poof => "poof",
+            EvictionReason::Flush => "flush",
+            EvictionReason::Limit => "limit",
+            EvictionReason::Conflict => "conflict",
+        };
+        write!(f, "{}", reason)
+    }
+}
+
+/// Eviction represents a record which left the cache with the reason.
+#[derive(Clone)]
+pub struct Eviction {
+    record: CachedRecord,
+    reason: EvictionReason,
+}
+
+impl Eviction {
+    pub(crate) fn new(record: CachedRecord, reason: EvictionReason) -> Eviction {
+        Eviction { record, reason }
+    }
+
+    /// record returns the evicted record.
+    pub fn record(&self) -> &CachedRecord {
+        &self.record
+    }
+
+    /// reason returns why the record was evicted.
+    pub fn reason(&self) -> EvictionReason {
+        self.reason
+    }
+}
@@ -0,0 +1,50 @@
+// Copyright (C) 2024 Satoshi Konno All rights reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//    http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::fmt;
+use std::net::{IpAddr, SocketAddr};
+
+/// AddressFamily represents an address family of the transports.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum AddressFamily {
+    IPv4,
+    IPv6,
+}
+
+impl AddressFamily {
+    /// from_ipaddr returns the family of the specified IP address.
+    pub fn from_ipaddr(addr: &IpAddr) -> AddressFamily {
+        match addr {
+            IpAddr::V4(_) => AddressFamily::IPv4,
+            IpAddr::V6(v6) => match v6.to_ipv4_mapped() {
+                Some(_) => AddressFamily::IPv4,
+                None => AddressFamily::IPv6,
+            },
+        }
+    }
+
+    /// from_socketaddr returns the family of the specified socket address.
+    pub fn from_socketaddr(addr: &SocketAddr) -> AddressFamily {
+        AddressFamily::from_ipaddr(&addr.ip())
+    }
+}
+
+impl fmt::Display for AddressFamily {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            AddressFamily::IPv4 => write!(f, "IPv4"),
+            AddressFamily::IPv6 => write!(f, "IPv6"),
+        }
+    }
+}
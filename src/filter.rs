@@ -0,0 +1,112 @@
+// Copyright (C) 2024 Satoshi Konno All rights reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//    http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::ops::Range;
+
+use crate::service::Service;
+
+/// Filter represents a condition of the services which generate events, built by chaining the conditions such as
+/// `Filter::service_type("_http._tcp").txt_eq("path", "/admin").port_range(8000..9000)`.
+#[derive(Debug, Clone, Default)]
+pub struct Filter {
+    service_type: Option<String>,
+    domain: Option<String>,
+    txt_eqs: Vec<(String, String)>,
+    txt_keys: Vec<String>,
+    port_range: Option<Range<u16>>,
+    txtvers_range: Option<Range<u32>>,
+}
+
+impl Filter {
+    /// new creates a new filter which matches all services.
+    pub fn new() -> Filter {
+        Filter::default()
+    }
+
+    /// service_type creates a new filter which matches the services of the specified type such as `_http._tcp`.
+    pub fn service_type(service_type: &str) -> Filter {
+        Filter {
+            service_type: Some(service_type.trim_end_matches('.').to_string()),
+            ..Filter::default()
+        }
+    }
+
+    /// domain adds the condition of the specified domain such as `local`.
+    pub fn domain(mut self, domain: &str) -> Filter {
+        self.domain = Some(domain.trim_end_matches('.').to_string());
+        self
+    }
+
+    /// txt_eq adds the condition that the TXT attribute of the specified key equals the specified value.
+    pub fn txt_eq(mut self, key: &str, value: &str) -> Filter {
+        self.txt_eqs.push((key.to_string(), value.to_string()));
+        self
+    }
+
+    /// txt_has adds the condition that the TXT attribute of the specified key exists.
+    pub fn txt_has(mut self, key: &str) -> Filter {
+        self.txt_keys.push(key.to_string());
+        self
+    }
+
+    /// port_range adds the condition that the port is in the specified range.
+    pub fn port_range(mut self, range: Range<u16>) -> Filter {
+        self.port_range = Some(range);
+        self
+    }
+
+    /// txtvers_range adds the condition that the TXT record format version is in the specified range,
+    /// which a service without the txtvers attribute never satisfies.
+    /// RFC 6763: 6.7. Version Tag
+    pub fn txtvers_range(mut self, range: Range<u32>) -> Filter {
+        self.txtvers_range = Some(range);
+        self
+    }
+
+    /// matches returns true if the specified service satisfies all conditions.
+    /// The service types, domains and TXT keys are compared case-insensitively.
+    pub fn matches(&self, service: &Service) -> bool {
+        if let Some(service_type) = &self.service_type {
+            if !service.service_type().eq_ignore_ascii_case(service_type) {
+                return false;
+            }
+        }
+        if let Some(domain) = &self.domain {
+            if !service.domain().eq_ignore_ascii_case(domain) {
+                return false;
+            }
+        }
+        for (key, value) in &self.txt_eqs {
+            if service.attribute(key) != Some(value) {
+                return false;
+            }
+        }
+        for key in &self.txt_keys {
+            if service.attribute(key).is_none() {
+                return false;
+            }
+        }
+        if let Some(range) = &self.port_range {
+            if !range.contains(&service.port()) {
+                return false;
+            }
+        }
+        if let Some(range) = &self.txtvers_range {
+            if !service.txtvers().is_some_and(|v| range.contains(&v)) {
+                return false;
+            }
+        }
+        true
+    }
+}
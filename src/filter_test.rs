@@ -0,0 +1,120 @@
+// Copyright (C) 2024 Satoshi Konno All rights reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//    http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+#[cfg(test)]
+mod tests {
+
+    use crate::dns::{Message, Type};
+    use crate::fixture_test::{record, srv_record};
+    use crate::{Filter, Service};
+
+    fn test_service(name: &str, port: u16, txt: &str) -> Service {
+        let mut data = vec![txt.len() as u8];
+        data.extend_from_slice(txt.as_bytes());
+        let mut msg = Message::new();
+        msg.add_answer(srv_record(name, port, "host.local"));
+        msg.add_answer(record(name, Type::TXT, data));
+        Service::from_message(&msg)
+    }
+
+    #[test]
+    fn filter_matches() {
+        struct Test {
+            filter: Filter,
+            expected: bool,
+        }
+
+        let service = test_service("admin._http._tcp.local", 8080, "path=/admin");
+        let tests = vec![
+            Test {
+                filter: Filter::new(),
+                expected: true,
+            },
+            Test {
+                filter: Filter::service_type("_HTTP._tcp")
+                    .txt_eq("Path", "/admin")
+                    .port_range(8000..9000),
+                expected: true,
+            },
+            Test {
+                filter: Filter::service_type("_ipp._tcp"),
+                expected: false,
+            },
+            Test {
+                filter: Filter::service_type("_http._tcp").txt_eq("path", "/"),
+                expected: false,
+            },
+            Test {
+                filter: Filter::service_type("_http._tcp").port_range(80..8080),
+                expected: false,
+            },
+            Test {
+                filter: Filter::new().domain("local.").txt_has("path"),
+                expected: true,
+            },
+            Test {
+                filter: Filter::new().txt_has("version"),
+                expected: false,
+            },
+            Test {
+                filter: Filter::new().txtvers_range(1..3),
+                expected: false,
+            },
+        ];
+
+        for test in tests {
+            assert_eq!(
+                test.filter.matches(&service),
+                test.expected,
+                "{:?}",
+                test.filter
+            );
+        }
+    }
+
+    #[test]
+    fn filter_txtvers() {
+        struct Test {
+            txt: &'static str,
+            expected: bool,
+        }
+
+        let tests = vec![
+            Test {
+                txt: "txtvers=1",
+                expected: true,
+            },
+            Test {
+                txt: "TxtVers=2",
+                expected: true,
+            },
+            Test {
+                txt: "txtvers=3",
+                expected: false,
+            },
+            Test {
+                txt: "txtvers=x",
+                expected: false,
+            },
+        ];
+
+        let filter = Filter::service_type("_http._tcp").txtvers_range(1..3);
+        for test in tests {
+            let service = test_service("printer._http._tcp.local", 80, test.txt);
+            assert_eq!(filter.matches(&service), test.expected, "{}", test.txt);
+        }
+        let service = test_service("printer._http._tcp.local", 80, "txtvers=2");
+        assert_eq!(service.txtvers(), Some(2));
+    }
+}
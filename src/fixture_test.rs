@@ -0,0 +1,83 @@
+// Copyright (C) 2024 Satoshi Konno All rights reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//    http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+// The fixtures shared by the tests of the modules.
+#![cfg(test)]
+
+use cybergarage::net::Packet;
+
+use crate::dns::{Class, Message, Record, SRVRecord, Type, Writer};
+
+/// record returns an IN record of the specified name, type and data with the TTL of the host records.
+pub(crate) fn record(name: &str, typ: Type, data: Vec<u8>) -> Record {
+    let mut record = Record::new();
+    record.set_name(name);
+    record.set_typ(typ);
+    record.set_class(Class::IN);
+    record.set_ttl(120);
+    record.set_data(data);
+    record
+}
+
+/// name_data returns the data of a PTR record to the specified name.
+pub(crate) fn name_data(name: &str) -> Vec<u8> {
+    let mut w = Writer::new();
+    assert!(w.write_name(name).is_ok());
+    w.to_bytes()
+}
+
+/// srv_data returns the data of a SRV record to the specified port and target.
+pub(crate) fn srv_data(port: u16, target: &str) -> Vec<u8> {
+    let mut w = Writer::new();
+    assert!(w.write_u16(0).is_ok());
+    assert!(w.write_u16(0).is_ok());
+    assert!(w.write_u16(port).is_ok());
+    assert!(w.write_name(target).is_ok());
+    w.to_bytes()
+}
+
+/// a_record returns an A record of the specified name and address.
+pub(crate) fn a_record(name: &str, addr: [u8; 4]) -> Record {
+    record(name, Type::A, addr.to_vec())
+}
+
+/// ptr_record returns a PTR record of the specified name to the specified instance.
+pub(crate) fn ptr_record(name: &str, instance: &str) -> Record {
+    record(name, Type::PTR, name_data(instance))
+}
+
+/// srv_record returns a SRV record of the specified name to the specified port and target.
+pub(crate) fn srv_record(name: &str, port: u16, target: &str) -> Record {
+    record(name, Type::SRV, srv_data(port, target))
+}
+
+/// with_ttl returns the specified record with the specified TTL, such as zero for a goodbye.
+pub(crate) fn with_ttl(mut record: Record, ttl: u32) -> Record {
+    record.set_ttl(ttl);
+    record
+}
+
+/// unique returns the specified record with the cache-flush bit of a unique record set.
+pub(crate) fn unique(mut record: Record) -> Record {
+    record.set_cache_flush(true);
+    record
+}
+
+/// srv_response_packet returns a response packet of a SRV record of the specified instance name and port on `host.local`.
+pub(crate) fn srv_response_packet(name: &str, port: u16) -> Packet {
+    let srv = SRVRecord::new(name, 0, 0, port, "host.local");
+    let mut msg = Message::new();
+    msg.add_answer(srv.to_record().unwrap());
+    Packet::from_bytes(&msg.to_bytes().unwrap())
+}
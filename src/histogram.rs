@@ -0,0 +1,120 @@
+// Copyright (C) 2024 Satoshi Konno All rights reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//    http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+/// Histogram represents a distribution of the observed values counted in the buckets of the upper bounds.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Histogram {
+    bounds: Vec<u64>,
+    counts: Vec<u64>,
+    count: u64,
+    sum: u64,
+    min: u64,
+    max: u64,
+}
+
+impl Histogram {
+    /// new creates a new histogram of the specified ascending upper bounds, and the values above the last bound are counted in an overflow bucket.
+    pub fn new(bounds: &[u64]) -> Histogram {
+        let mut bounds = bounds.to_vec();
+        bounds.sort_unstable();
+        bounds.dedup();
+        Histogram {
+            counts: vec![0; bounds.len() + 1],
+            bounds,
+            count: 0,
+            sum: 0,
+            min: 0,
+            max: 0,
+        }
+    }
+
+    /// record adds the specified value to the histogram.
+    pub fn record(&mut self, value: u64) {
+        let idx = self.bounds.partition_point(|&bound| bound < value);
+        self.counts[idx] += 1;
+        if self.count == 0 || value < self.min {
+            self.min = value;
+        }
+        self.max = self.max.max(value);
+        self.count += 1;
+        self.sum = self.sum.saturating_add(value);
+    }
+
+    /// count returns the number of the recorded values.
+    pub fn count(&self) -> u64 {
+        self.count
+    }
+
+    /// sum returns the sum of the recorded values.
+    pub fn sum(&self) -> u64 {
+        self.sum
+    }
+
+    /// min returns the smallest recorded value, or 0 if nothing has been recorded.
+    pub fn min(&self) -> u64 {
+        self.min
+    }
+
+    /// max returns the largest recorded value, or 0 if nothing has been recorded.
+    pub fn max(&self) -> u64 {
+        self.max
+    }
+
+    /// mean returns the average of the recorded values, or 0 if nothing has been recorded.
+    pub fn mean(&self) -> f64 {
+        if self.count == 0 {
+            return 0.0;
+        }
+        self.sum as f64 / self.count as f64
+    }
+
+    /// buckets returns the upper bounds and the counts of the buckets, where the overflow bucket has no bound.
+    pub fn buckets(&self) -> Vec<(Option<u64>, u64)> {
+        self.counts
+            .iter()
+            .enumerate()
+            .map(|(i, &count)| (self.bounds.get(i).copied(), count))
+            .collect()
+    }
+
+    /// percentile returns the upper bound of the bucket which the specified percentile such as 99.0 falls in,
+    /// which is the largest recorded value for the overflow bucket, or 0 if nothing has been recorded.
+    pub fn percentile(&self, percentile: f64) -> u64 {
+        if self.count == 0 {
+            return 0;
+        }
+        let rank = ((percentile.clamp(0.0, 100.0) / 100.0) * self.count as f64).ceil() as u64;
+        let rank = rank.max(1);
+        let mut seen = 0;
+        for (i, &count) in self.counts.iter().enumerate() {
+            seen += count;
+            if rank <= seen {
+                return self
+                    .bounds
+                    .get(i)
+                    .map_or(self.max, |&bound| bound.min(self.max));
+            }
+        }
+        self.max
+    }
+
+    /// clear removes all recorded values.
+    pub fn clear(&mut self) {
+        self.counts.iter_mut().for_each(|count| *count = 0);
+        self.count = 0;
+        self.sum = 0;
+        self.min = 0;
+        self.max = 0;
+    }
+}
@@ -0,0 +1,66 @@
+// Copyright (C) 2024 Satoshi Konno All rights reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//    http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+#[cfg(test)]
+mod tests {
+
+    use std::time::Duration;
+
+    use crate::histogram::Histogram;
+    use crate::stats::{Stats, PACKET_SIZE_BOUNDS};
+
+    #[test]
+    fn histogram() {
+        let mut histogram = Histogram::new(&[10, 100, 1000]);
+        assert_eq!(histogram.count(), 0);
+        assert_eq!(histogram.mean(), 0.0);
+        assert_eq!(histogram.percentile(50.0), 0);
+
+        for value in [5, 10, 50, 80, 500, 2000] {
+            histogram.record(value);
+        }
+        assert_eq!(histogram.count(), 6);
+        assert_eq!(histogram.sum(), 2645);
+        assert_eq!(histogram.min(), 5);
+        assert_eq!(histogram.max(), 2000);
+        assert_eq!(
+            histogram.buckets(),
+            vec![(Some(10), 2), (Some(100), 2), (Some(1000), 1), (None, 1)]
+        );
+        assert_eq!(histogram.percentile(0.0), 10);
+        assert_eq!(histogram.percentile(50.0), 100);
+        assert_eq!(histogram.percentile(80.0), 1000);
+        assert_eq!(histogram.percentile(99.0), 2000);
+
+        histogram.clear();
+        assert_eq!(histogram.count(), 0);
+        assert_eq!(histogram.buckets().iter().map(|(_, n)| n).sum::<u64>(), 0);
+    }
+
+    #[test]
+    fn histogram_stats() {
+        let mut stats = Stats::new();
+        stats.add_packet_size(300);
+        stats.add_packet_size(1400);
+        stats.add_parse_time(Duration::from_micros(7));
+        stats.add_answers_per_response(3);
+
+        let packet_size = stats.packet_size();
+        assert_eq!(packet_size.count(), 2);
+        assert_eq!(packet_size.buckets().len(), PACKET_SIZE_BOUNDS.len() + 1);
+        assert_eq!(packet_size.percentile(50.0), 512);
+        assert_eq!(stats.parse_time().max(), 7);
+        assert_eq!(stats.answers_per_response().percentile(100.0), 3);
+    }
+}
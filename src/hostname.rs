@@ -0,0 +1,208 @@
+// Copyright (C) 2024 Satoshi Konno All rights reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//    http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use crate::error::{Error, Result};
+use crate::sanitize::NameSanitizer;
+
+/// LOCAL_DOMAIN is the domain of the multicast DNS host names.
+pub const LOCAL_DOMAIN: &str = "local";
+
+const AUTO_INCREMENT_FIRST: u32 = 2;
+
+// DEFAULT_HOSTNAME is the host name used when the system host name is unavailable.
+const DEFAULT_HOSTNAME: &str = "mdns";
+
+/// HostnameCallback returns the name to try next for the conflicted name and the number of conflicts so far, or None to give up.
+pub type HostnameCallback = Box<dyn Fn(&str, u32) -> Option<String> + Send>;
+
+/// HostnamePolicy represents how a responder chooses a new host name when the current one conflicts.
+pub enum HostnamePolicy {
+    /// Fixed never renames the host, and a conflict is reported as an error.
+    Fixed(String),
+    /// AutoIncrement renames the host by appending an increasing number such as `name-2`.
+    AutoIncrement(String),
+    /// Callback renames the host by the name returned from the callback.
+    Callback(String, HostnameCallback),
+}
+
+impl HostnamePolicy {
+    /// name returns the initial host name of the policy.
+    pub fn name(&self) -> &str {
+        match self {
+            HostnamePolicy::Fixed(name) => name,
+            HostnamePolicy::AutoIncrement(name) => name,
+            HostnamePolicy::Callback(name, _) => name,
+        }
+    }
+
+    /// next_name returns the name to try after the specified name conflicted for the specified number of times.
+    pub fn next_name(&self, name: &str, conflicts: u32) -> Option<String> {
+        match self {
+            HostnamePolicy::Fixed(_) => None,
+            HostnamePolicy::AutoIncrement(_) => Some(increment_name(name)),
+            HostnamePolicy::Callback(_, callback) => callback(name, conflicts),
+        }
+    }
+}
+
+// increment_name returns `name-2` for `name`, and `name-3` for `name-2`.
+fn increment_name(name: &str) -> String {
+    if let Some((base, n)) = name.rsplit_once('-') {
+        if let Ok(n) = n.parse::<u32>() {
+            if AUTO_INCREMENT_FIRST <= n {
+                return format!("{}-{}", base, n + 1);
+            }
+        }
+    }
+    format!("{}-{}", name, AUTO_INCREMENT_FIRST)
+}
+
+/// system_hostname returns the host name of the system without its domain, which a responder advertises by default.
+pub fn system_hostname() -> String {
+    let name = platform_hostname().unwrap_or_default();
+    let name = name.split('.').next().unwrap_or_default();
+    // The system host names may have characters such as underscores which some stacks reject.
+    let mut sanitizer = NameSanitizer::new();
+    sanitizer.set_fallback(DEFAULT_HOSTNAME);
+    sanitizer.hostname(name)
+}
+
+#[cfg(unix)]
+fn platform_hostname() -> Option<String> {
+    let mut buf = [0u8; 256];
+    let ret = unsafe { libc::gethostname(buf.as_mut_ptr() as *mut libc::c_char, buf.len()) };
+    if ret != 0 {
+        return None;
+    }
+    let len = buf.iter().position(|&b| b == 0).unwrap_or(buf.len());
+    String::from_utf8(buf[..len].to_vec()).ok()
+}
+
+#[cfg(not(unix))]
+fn platform_hostname() -> Option<String> {
+    std::env::var("COMPUTERNAME").ok()
+}
+
+/// HostnameStore represents a file which keeps the last successfully defended host name.
+pub struct HostnameStore {
+    path: PathBuf,
+}
+
+impl HostnameStore {
+    /// new creates a new store of the specified file path.
+    pub fn new<P: AsRef<Path>>(path: P) -> HostnameStore {
+        HostnameStore {
+            path: path.as_ref().to_path_buf(),
+        }
+    }
+
+    /// path returns the file path of the store.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// load returns the stored host name, or None if no name has been stored.
+    pub fn load(&self) -> Option<String> {
+        let name = fs::read_to_string(&self.path).ok()?;
+        let name = name.trim();
+        if name.is_empty() {
+            return None;
+        }
+        Some(name.to_string())
+    }
+
+    /// save stores the specified host name.
+    pub fn save(&self, name: &str) -> io::Result<()> {
+        fs::write(&self.path, format!("{}\n", name))
+    }
+}
+
+/// Hostname represents the host name of a responder, which is renamed by the policy on conflicts.
+pub struct Hostname {
+    policy: HostnamePolicy,
+    store: Option<HostnameStore>,
+    name: String,
+    conflicts: u32,
+}
+
+impl Hostname {
+    /// new creates a new host name of the specified policy.
+    pub fn new(policy: HostnamePolicy) -> Hostname {
+        let name = policy.name().to_string();
+        Hostname {
+            policy,
+            store: None,
+            name,
+            conflicts: 0,
+        }
+    }
+
+    /// with_store creates a new host name of the specified policy, which starts from the stored name if any,
+    /// so a renamed device keeps its name across restarts. A fixed policy always uses its own name.
+    pub fn with_store(policy: HostnamePolicy, store: HostnameStore) -> Hostname {
+        let mut hostname = Hostname::new(policy);
+        if !matches!(hostname.policy, HostnamePolicy::Fixed(_)) {
+            if let Some(name) = store.load() {
+                hostname.name = name;
+            }
+        }
+        hostname.store = Some(store);
+        hostname
+    }
+
+    /// name returns the current host name without the domain.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// fqdn returns the current host name in the local domain.
+    pub fn fqdn(&self) -> String {
+        format!("{}.{}", self.name, LOCAL_DOMAIN)
+    }
+
+    /// conflicts returns the number of conflicts since the name was last defended.
+    pub fn conflicts(&self) -> u32 {
+        self.conflicts
+    }
+
+    /// conflict renames the host by the policy after the current name conflicted, and returns the new name.
+    /// RFC 6762: 9. Conflict Resolution
+    pub fn conflict(&mut self) -> Result<&str> {
+        self.conflicts += 1;
+        match self.policy.next_name(&self.name, self.conflicts) {
+            Some(name) if !name.is_empty() && name != self.name => {
+                log::info!("host name {} conflicted, renamed to {}", self.name, name);
+                self.name = name;
+                Ok(&self.name)
+            }
+            _ => Err(Error::from_string(&format!(
+                "host name {} conflicted and could not be renamed",
+                self.name
+            ))),
+        }
+    }
+
+    /// defended records that the current name was probed and announced successfully, and stores it if a store is set.
+    pub fn defended(&mut self) -> io::Result<()> {
+        self.conflicts = 0;
+        match &self.store {
+            Some(store) => store.save(&self.name),
+            None => Ok(()),
+        }
+    }
+}
@@ -0,0 +1,95 @@
+// Copyright (C) 2024 Satoshi Konno All rights reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//    http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+#[cfg(test)]
+mod tests {
+
+    use std::fs;
+
+    use crate::hostname::{Hostname, HostnamePolicy, HostnameStore};
+
+    #[test]
+    fn hostname_auto_increment() {
+        struct Test {
+            name: &'static str,
+            expected: &'static str,
+        }
+
+        let tests = vec![
+            Test {
+                name: "device",
+                expected: "device-2",
+            },
+            Test {
+                name: "device-2",
+                expected: "device-3",
+            },
+            Test {
+                name: "device-1",
+                expected: "device-1-2",
+            },
+            Test {
+                name: "my-device",
+                expected: "my-device-2",
+            },
+        ];
+
+        let policy = HostnamePolicy::AutoIncrement("device".to_string());
+        for test in tests {
+            assert_eq!(policy.next_name(test.name, 1).unwrap(), test.expected);
+        }
+    }
+
+    #[test]
+    fn hostname_conflict() {
+        let mut hostname = Hostname::new(HostnamePolicy::Fixed("device".to_string()));
+        assert!(hostname.conflict().is_err());
+        assert_eq!(hostname.fqdn(), "device.local");
+
+        let policy = HostnamePolicy::Callback(
+            "device".to_string(),
+            Box::new(|_, conflicts| match conflicts {
+                1 => Some("device-backup".to_string()),
+                _ => None,
+            }),
+        );
+        let mut hostname = Hostname::new(policy);
+        assert_eq!(hostname.conflict().unwrap(), "device-backup");
+        assert!(hostname.conflict().is_err());
+        assert_eq!(hostname.name(), "device-backup");
+    }
+
+    #[test]
+    fn hostname_store() {
+        let path = std::env::temp_dir().join(format!("mdns-hostname-{}", std::process::id()));
+        let _ = fs::remove_file(&path);
+
+        let policy = HostnamePolicy::AutoIncrement("device".to_string());
+        let mut hostname = Hostname::with_store(policy, HostnameStore::new(&path));
+        assert_eq!(hostname.name(), "device");
+        assert_eq!(hostname.conflict().unwrap(), "device-2");
+        assert!(hostname.defended().is_ok());
+        assert_eq!(hostname.conflicts(), 0);
+
+        let policy = HostnamePolicy::AutoIncrement("device".to_string());
+        let hostname = Hostname::with_store(policy, HostnameStore::new(&path));
+        assert_eq!(hostname.name(), "device-2");
+
+        let policy = HostnamePolicy::Fixed("device".to_string());
+        let hostname = Hostname::with_store(policy, HostnameStore::new(&path));
+        assert_eq!(hostname.name(), "device");
+
+        let _ = fs::remove_file(&path);
+    }
+}
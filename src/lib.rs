@@ -12,20 +12,163 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+pub use self::advertiser::{Advertiser, RegistrationState};
+#[cfg(feature = "tokio")]
+pub use self::async_client::AsyncClient;
+pub use self::browse_format::{BrowseFormat, BrowseFormatter};
+pub use self::browse_session::BrowseSession;
+pub use self::cache::{Cache, CachedRecord};
+#[cfg(feature = "capture")]
+pub use self::capture::{HexWriter, PacketSink, PcapWriter};
 pub use self::client::Client;
+pub use self::coalesce::EventCoalescer;
+pub use self::conflict::{Conflict, Reconfirmation, ReconfirmationOutcome};
+pub use self::device_info::DeviceInfo;
 pub use self::discoverer::Discoverer;
 pub use self::error::{Error, Result};
+pub use self::event::{ServiceCallback, ServiceEvent};
+pub use self::eviction::{Eviction, EvictionReason};
+pub use self::family::AddressFamily;
+pub use self::filter::Filter;
+pub use self::histogram::Histogram;
+pub use self::hostname::{system_hostname, Hostname, HostnamePolicy, HostnameStore, LOCAL_DOMAIN};
+pub use self::llq::LlqClient;
+pub use self::loopback::LoopbackFilter;
+pub use self::name_conflict::{ConflictCallback, ConflictDecision, NameConflict};
+pub use self::normalize::NameComparison;
+pub use self::outgoing::{OutgoingHook, OutgoingKind, OutgoingPacket};
+pub use self::provenance::Provenance;
+pub use self::proxy::ProxyAnswerBuilder;
 pub use self::query::Query;
+pub use self::query_history::{ObservedQuestion, QueryHistory};
+pub use self::query_pacing::QueryPacing;
+pub use self::received_message::{ReceivedMessage, ReceivedRecord};
+pub use self::record_index::{RecordIndex, RegistrationId};
+pub use self::record_ttls::RecordTtls;
+pub use self::responder::Responder;
+pub use self::responder_config::ResponderConfig;
+pub use self::response_strategy::{
+    DefaultResponseStrategy, QuestionContext, ResponseDecision, ResponseStrategy,
+    SubnetResponseStrategy,
+};
+pub use self::sanitize::NameSanitizer;
 pub use self::service::Service;
+pub use self::service_builder::{ServiceBuilder, ServiceInfo, SERVICE_TYPE_ENUMERATION};
+pub use self::service_record::{ServiceRecord, ServiceRecordData};
+pub use self::shutdown::ShutdownReport;
+pub use self::signer::RecordSigner;
+pub use self::stats::Stats;
+pub use self::summary::ServiceSummary;
+pub use self::supervisor::{Supervisor, TaskHealth, TaskStatus};
+pub use self::transport::{MulticastTransport, Route};
+pub use self::trust_policy::TrustPolicy;
+pub use self::txt_schema::{TxtSchema, TxtSchemas, TxtViolation};
+pub use self::unicast::UnicastResolver;
+pub use self::validate::{validate_multicast_response, Violation};
+pub use self::virtual_network::{VirtualLink, VirtualNetwork};
 
+pub mod additional;
+pub mod advertiser;
+#[cfg(feature = "tokio")]
+pub mod async_client;
+pub mod avahi_service;
+pub mod browse_format;
+pub mod browse_session;
+pub mod cache;
+#[cfg(feature = "capture")]
+pub mod capture;
 pub mod client;
+pub mod coalesce;
+pub mod conflict;
+pub mod dedup;
 pub mod default;
+pub mod device_info;
 pub mod discoverer;
 pub mod dns;
 pub mod error;
+pub mod event;
+pub mod eviction;
+pub mod family;
+pub mod filter;
+pub mod histogram;
+pub mod hostname;
+pub mod llq;
+pub mod loopback;
 pub mod message;
+pub mod name_conflict;
+pub mod net;
+pub mod normalize;
+pub mod outgoing;
+pub mod packing;
+pub mod provenance;
+pub mod proxy;
 pub mod query;
+pub mod query_history;
+pub mod query_pacing;
+pub mod rate_limit;
+pub mod received_message;
+pub mod record_index;
+pub mod record_ttls;
+pub mod responder;
+pub mod responder_config;
+pub mod response_strategy;
+pub mod sanitize;
 pub mod service;
+pub mod service_builder;
+pub mod service_record;
+pub mod shutdown;
+pub mod signer;
+pub mod stats;
+pub mod summary;
+pub mod supervisor;
+pub mod transport;
+pub mod trust_policy;
+pub mod txt;
+pub mod txt_schema;
+pub mod unicast;
+pub mod validate;
+pub mod virtual_network;
 
+mod additional_test;
+mod advertiser_test;
+#[cfg(feature = "tokio")]
+mod async_client_test;
+mod avahi_service_test;
+mod browse_format_test;
+mod browse_session_test;
+mod cache_test;
+#[cfg(feature = "capture")]
+mod capture_test;
 mod client_test;
+mod coalesce_test;
+mod dedup_test;
+mod device_info_test;
+mod discoverer_test;
+mod filter_test;
+mod fixture_test;
+mod histogram_test;
+mod hostname_test;
+mod llq_test;
+mod loopback_test;
 mod message_test;
+mod normalize_test;
+mod packing_test;
+mod proxy_test;
+mod query_history_test;
+mod rate_limit_test;
+mod received_message_test;
+mod record_index_test;
+mod responder_config_test;
+mod response_strategy_test;
+mod sanitize_test;
+mod service_record_test;
+mod stats_test;
+mod summary_test;
+mod supervisor_test;
+mod transport_test;
+mod trust_policy_test;
+mod txt_schema_test;
+mod txt_test;
+mod unicast_test;
+mod validate_test;
+mod virtual_network_test;
@@ -0,0 +1,476 @@
+// Copyright (C) 2024 Satoshi Konno All rights reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//    http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::io::{self, ErrorKind};
+use std::net::{SocketAddr, UdpSocket};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use cybergarage::net::Packet;
+
+use crate::discoverer::Discoverer;
+use crate::dns::reader::Reader;
+use crate::dns::{Class, Message, QuestionRecord, Record, Type, Writer, QR};
+use crate::shutdown::join_until;
+
+/// LLQ_OPTION_CODE is the EDNS0 option code of the long-lived query.
+/// RFC 8764: 3.2. LLQ Extension
+pub const LLQ_OPTION_CODE: u16 = 1;
+/// LLQ_VERSION is the version of the long-lived query protocol.
+pub const LLQ_VERSION: u16 = 1;
+/// DEFAULT_LLQ_LEASE is the default lease life requested in seconds.
+pub const DEFAULT_LLQ_LEASE: u32 = 7200;
+/// LLQ_NO_ERROR is the error code of the successful operations.
+pub const LLQ_NO_ERROR: u16 = 0;
+/// LLQ_EVENT_REMOVED_TTL is the TTL of the removed answers in the events.
+/// RFC 8764: 5.1. Server Sends Events
+pub const LLQ_EVENT_REMOVED_TTL: u32 = 0xFFFFFFFF;
+
+const LLQ_OPTION_LEN: u16 = 18;
+const RECV_BUFFER_SIZE: usize = 9000;
+const POLL_INTERVAL: Duration = Duration::from_millis(200);
+
+/// LlqOpcode represents an operation of the long-lived query.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum LlqOpcode {
+    Setup = 1,
+    Refresh = 2,
+    Event = 3,
+}
+
+impl LlqOpcode {
+    /// from_value returns the opcode of the value.
+    pub fn from_value(value: u16) -> Option<LlqOpcode> {
+        match value {
+            1 => Some(LlqOpcode::Setup),
+            2 => Some(LlqOpcode::Refresh),
+            3 => Some(LlqOpcode::Event),
+            _ => None,
+        }
+    }
+}
+
+/// LlqOption represents the LLQ option of an OPT record.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct LlqOption {
+    version: u16,
+    opcode: LlqOpcode,
+    error: u16,
+    id: u64,
+    lease: u32,
+}
+
+impl LlqOption {
+    /// new creates a new option of the specified operation.
+    pub fn new(opcode: LlqOpcode, id: u64, lease: u32) -> LlqOption {
+        LlqOption {
+            version: LLQ_VERSION,
+            opcode,
+            error: LLQ_NO_ERROR,
+            id,
+            lease,
+        }
+    }
+
+    /// set_error sets the error code of the option.
+    pub fn set_error(&mut self, error: u16) {
+        self.error = error;
+    }
+
+    /// version returns the protocol version of the option.
+    pub fn version(&self) -> u16 {
+        self.version
+    }
+
+    /// opcode returns the operation of the option.
+    pub fn opcode(&self) -> LlqOpcode {
+        self.opcode
+    }
+
+    /// error returns the error code of the option.
+    pub fn error(&self) -> u16 {
+        self.error
+    }
+
+    /// id returns the identifier of the long-lived query.
+    pub fn id(&self) -> u64 {
+        self.id
+    }
+
+    /// lease returns the lease life in seconds.
+    pub fn lease(&self) -> u32 {
+        self.lease
+    }
+
+    /// to_record returns the OPT record which carries the option.
+    pub fn to_record(&self) -> Record {
+        let mut w = Writer::new();
+        let _ = w.write_u16(LLQ_OPTION_CODE);
+        let _ = w.write_u16(LLQ_OPTION_LEN);
+        let _ = w.write_u16(self.version);
+        let _ = w.write_u16(self.opcode as u16);
+        let _ = w.write_u16(self.error);
+        let _ = w.write_u32((self.id >> 32) as u32);
+        let _ = w.write_u32(self.id as u32);
+        let _ = w.write_u32(self.lease);
+
+        let mut record = Record::new();
+        record.set_name("");
+        record.set_typ(Type::OPT);
+        record.set_class(Class::NONE);
+        record.set_data(w.to_bytes());
+        record
+    }
+
+    /// from_message returns the LLQ option in the additional section of the specified message.
+    pub fn from_message(msg: &Message) -> Option<LlqOption> {
+        msg.additionals()
+            .iter()
+            .filter(|record| record.typ() == Type::OPT)
+            .find_map(LlqOption::from_record)
+    }
+
+    /// from_record returns the LLQ option in the specified OPT record.
+    pub fn from_record(record: &Record) -> Option<LlqOption> {
+        let mut reader = Reader::from_bytes(record.data());
+        while !reader.is_empty() {
+            let code = reader.read_u16().ok()?;
+            let len = reader.read_u16().ok()?;
+            if code != LLQ_OPTION_CODE || len != LLQ_OPTION_LEN {
+                let mut skipped = vec![0; len as usize];
+                reader.read_bytes(&mut skipped).ok()?;
+                continue;
+            }
+            let version = reader.read_u16().ok()?;
+            let opcode = LlqOpcode::from_value(reader.read_u16().ok()?)?;
+            let error = reader.read_u16().ok()?;
+            let id = ((reader.read_u32().ok()? as u64) << 32) | reader.read_u32().ok()? as u64;
+            let lease = reader.read_u32().ok()?;
+            return Some(LlqOption {
+                version,
+                opcode,
+                error,
+                id,
+                lease,
+            });
+        }
+        None
+    }
+}
+
+/// LlqClient represents a client of Apple's long-lived query protocol,
+/// which receives the changes of a wide-area browse pushed from a unicast DNS server.
+/// The answers are merged into the discoverer, so they are delivered as the standard service events.
+pub struct LlqClient {
+    server: SocketAddr,
+    name: String,
+    typ: Type,
+    lease: u32,
+    id: u64,
+    running: Arc<AtomicBool>,
+    worker: Option<JoinHandle<()>>,
+}
+
+impl LlqClient {
+    /// new creates a new client of the PTR records of the specified name at the specified server.
+    pub fn new(server: SocketAddr, name: &str) -> LlqClient {
+        LlqClient {
+            server,
+            name: name.to_string(),
+            typ: Type::PTR,
+            lease: DEFAULT_LLQ_LEASE,
+            id: 0,
+            running: Arc::new(AtomicBool::new(false)),
+            worker: None,
+        }
+    }
+
+    /// set_lease sets the lease life to request in seconds.
+    pub fn set_lease(&mut self, lease: u32) {
+        self.lease = lease;
+    }
+
+    /// server returns the address of the server.
+    pub fn server(&self) -> SocketAddr {
+        self.server
+    }
+
+    /// name returns the queried name.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// id returns the identifier granted by the server, or zero before the setup.
+    pub fn id(&self) -> u64 {
+        self.id
+    }
+
+    /// lease returns the lease life granted by the server in seconds.
+    pub fn lease(&self) -> u32 {
+        self.lease
+    }
+
+    /// is_running returns true if the client is receiving the events.
+    pub fn is_running(&self) -> bool {
+        self.running.load(Ordering::SeqCst)
+    }
+
+    /// start sets up the long-lived query within the specified timeout, and starts receiving the events into the specified discoverer.
+    /// RFC 8764: 4. LLQ Setup
+    pub fn start(
+        &mut self,
+        discoverer: Arc<Mutex<Discoverer>>,
+        timeout: Duration,
+    ) -> Result<(), io::Error> {
+        if self.is_running() {
+            return Ok(());
+        }
+        let bind_addr: SocketAddr = match self.server {
+            SocketAddr::V4(_) => "0.0.0.0:0".parse().unwrap(),
+            SocketAddr::V6(_) => "[::]:0".parse().unwrap(),
+        };
+        let socket = UdpSocket::bind(bind_addr)?;
+        socket.connect(self.server)?;
+        let mut session = Session {
+            socket,
+            server: self.server,
+            name: self.name.clone(),
+            typ: self.typ,
+            id: 0,
+            lease: self.lease,
+        };
+        let deadline = Instant::now() + timeout;
+
+        // Initial request, and the challenge from the server.
+        session.send(LlqOpcode::Setup, session.lease)?;
+        let (_, challenge) = session.recv_until(deadline, LlqOpcode::Setup)?;
+        session.id = challenge.id();
+        session.lease = challenge.lease();
+
+        // Challenge response, and the acknowledgement with the initial answers.
+        session.send(LlqOpcode::Setup, session.lease)?;
+        let (msg, ack) = session.recv_until(deadline, LlqOpcode::Setup)?;
+        if ack.id() != session.id {
+            return Err(io::Error::new(
+                ErrorKind::InvalidData,
+                format!("unexpected LLQ id {:x}", ack.id()),
+            ));
+        }
+        session.feed(&discoverer, &msg);
+
+        self.id = session.id;
+        self.lease = session.lease;
+        self.running.store(true, Ordering::SeqCst);
+        let running = self.running.clone();
+        self.worker = Some(thread::spawn(move || session.run(discoverer, running)));
+        Ok(())
+    }
+
+    /// stop cancels the long-lived query, and waits for the worker at most the specified timeout.
+    /// It returns false if the worker did not finish in time.
+    pub fn stop(&mut self, timeout: Duration) -> bool {
+        self.running.store(false, Ordering::SeqCst);
+        let worker = match self.worker.take() {
+            Some(worker) => worker,
+            None => return true,
+        };
+        join_until(worker, Instant::now() + timeout)
+    }
+}
+
+impl Drop for LlqClient {
+    fn drop(&mut self) {
+        self.stop(POLL_INTERVAL * 2);
+    }
+}
+
+// Session represents an established long-lived query owned by the worker.
+struct Session {
+    socket: UdpSocket,
+    server: SocketAddr,
+    name: String,
+    typ: Type,
+    id: u64,
+    lease: u32,
+}
+
+impl Session {
+    fn send(&self, opcode: LlqOpcode, lease: u32) -> Result<(), io::Error> {
+        let mut msg = Message::new();
+        msg.set_id(message_id());
+        let mut question = QuestionRecord::new();
+        question.set_name(&self.name);
+        question.set_typ(self.typ);
+        msg.add_question(question);
+        msg.add_additional(LlqOption::new(opcode, self.id, lease).to_record());
+        self.send_message(&msg)
+    }
+
+    fn send_message(&self, msg: &Message) -> Result<(), io::Error> {
+        let bytes = msg
+            .to_bytes()
+            .map_err(|e| io::Error::other(e.message().to_string()))?;
+        self.socket.send(&bytes)?;
+        Ok(())
+    }
+
+    // ack acknowledges the specified event message.
+    // RFC 8764: 5.2. Client Acknowledges Events
+    fn ack(&self, event: &Message, option: &LlqOption) -> Result<(), io::Error> {
+        let mut msg = Message::new();
+        msg.set_id(event.id());
+        msg.set_qr(QR::Response);
+        for question in event.questions() {
+            msg.add_question(question.clone());
+        }
+        msg.add_additional(
+            LlqOption::new(LlqOpcode::Event, option.id(), option.lease()).to_record(),
+        );
+        self.send_message(&msg)
+    }
+
+    fn recv(&self) -> Result<Option<(Message, LlqOption)>, io::Error> {
+        let mut buf = vec![0; RECV_BUFFER_SIZE];
+        let n = match self.socket.recv(&mut buf) {
+            Ok(n) => n,
+            Err(e) if matches!(e.kind(), ErrorKind::WouldBlock | ErrorKind::TimedOut) => {
+                return Ok(None)
+            }
+            Err(e) => return Err(e),
+        };
+        let msg = match Message::from_bytes(&buf[..n]) {
+            Ok(msg) => msg,
+            Err(e) => {
+                log::debug!("invalid LLQ message: {}", e.message());
+                return Ok(None);
+            }
+        };
+        match LlqOption::from_message(&msg) {
+            Some(option) if msg.is_response() => Ok(Some((msg, option))),
+            _ => Ok(None),
+        }
+    }
+
+    fn recv_until(
+        &self,
+        deadline: Instant,
+        opcode: LlqOpcode,
+    ) -> Result<(Message, LlqOption), io::Error> {
+        loop {
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            if remaining.is_zero() {
+                return Err(io::Error::new(
+                    ErrorKind::TimedOut,
+                    format!("LLQ setup of {} timed out", self.name),
+                ));
+            }
+            self.socket.set_read_timeout(Some(remaining))?;
+            if let Some((msg, option)) = self.recv()? {
+                if option.opcode() != opcode {
+                    continue;
+                }
+                if option.error() != LLQ_NO_ERROR {
+                    return Err(io::Error::other(format!(
+                        "LLQ setup of {} failed ({})",
+                        self.name,
+                        option.error()
+                    )));
+                }
+                return Ok((msg, option));
+            }
+        }
+    }
+
+    // feed merges the answers of the specified message into the discoverer, converting the removal events into goodbyes.
+    fn feed(&self, discoverer: &Arc<Mutex<Discoverer>>, msg: &Message) {
+        if msg.answers().is_empty() {
+            return;
+        }
+        let mut answers = Message::new();
+        answers.set_qr(QR::Response);
+        for answer in msg.answers() {
+            let mut answer = answer.clone();
+            if answer.ttl() == LLQ_EVENT_REMOVED_TTL {
+                answer.set_ttl(0);
+            }
+            answers.add_answer(answer);
+        }
+        for additional in msg.additionals() {
+            if additional.typ() != Type::OPT {
+                answers.add_additional(additional.clone());
+            }
+        }
+        if let Ok(bytes) = answers.to_bytes() {
+            let mut pkt = Packet::from_bytes(&bytes);
+            pkt.set_from(self.server);
+            discoverer.lock().unwrap().unicast_received(&pkt);
+        }
+    }
+
+    // run receives the events and refreshes the lease until the client is stopped.
+    // RFC 8764: 7. LLQ Lease-Life Expiration
+    fn run(mut self, discoverer: Arc<Mutex<Discoverer>>, running: Arc<AtomicBool>) {
+        let mut refresh_at = Instant::now() + refresh_interval(self.lease);
+        if let Err(e) = self.socket.set_read_timeout(Some(POLL_INTERVAL)) {
+            log::warn!("LLQ worker of {} failed: {}", self.name, e);
+            return;
+        }
+        while running.load(Ordering::SeqCst) {
+            if refresh_at <= Instant::now() {
+                if let Err(e) = self.send(LlqOpcode::Refresh, self.lease) {
+                    log::warn!("LLQ refresh of {} failed: {}", self.name, e);
+                }
+                refresh_at = Instant::now() + refresh_interval(self.lease);
+            }
+            match self.recv() {
+                Ok(Some((msg, option))) if option.id() == self.id => match option.opcode() {
+                    LlqOpcode::Event => {
+                        self.feed(&discoverer, &msg);
+                        if let Err(e) = self.ack(&msg, &option) {
+                            log::warn!("LLQ ack of {} failed: {}", self.name, e);
+                        }
+                    }
+                    LlqOpcode::Refresh => {
+                        self.lease = option.lease();
+                        refresh_at = Instant::now() + refresh_interval(self.lease);
+                    }
+                    LlqOpcode::Setup => {}
+                },
+                Ok(_) => {}
+                Err(e) => {
+                    log::warn!("LLQ worker of {} failed: {}", self.name, e);
+                    break;
+                }
+            }
+        }
+        // A refresh with the zero lease cancels the long-lived query.
+        let _ = self.send(LlqOpcode::Refresh, 0);
+        running.store(false, Ordering::SeqCst);
+    }
+}
+
+// refresh_interval returns the interval to refresh the lease, which is the half of the lease life.
+fn refresh_interval(lease: u32) -> Duration {
+    Duration::from_secs((lease / 2).max(1) as u64)
+}
+
+pub(crate) fn message_id() -> u16 {
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .subsec_nanos();
+    (nanos ^ (nanos >> 16)) as u16
+}
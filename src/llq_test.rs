@@ -0,0 +1,143 @@
+// Copyright (C) 2024 Satoshi Konno All rights reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//    http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+#[cfg(test)]
+mod tests {
+
+    use std::net::{SocketAddr, UdpSocket};
+    use std::thread;
+    use std::time::Duration;
+
+    use crate::dns::{Message, Record, QR};
+    use crate::fixture_test::{srv_record, with_ttl};
+    use crate::llq::{LlqClient, LlqOpcode, LlqOption, LLQ_EVENT_REMOVED_TTL};
+    use crate::{Discoverer, ServiceEvent};
+
+    fn recv_option(server: &UdpSocket) -> (Message, LlqOption, SocketAddr) {
+        let mut buf = vec![0; 1500];
+        let (n, from) = server.recv_from(&mut buf).unwrap();
+        let msg = Message::from_bytes(&buf[..n]).unwrap();
+        let option = LlqOption::from_message(&msg).unwrap();
+        (msg, option, from)
+    }
+
+    fn reply(
+        server: &UdpSocket,
+        to: SocketAddr,
+        req: &Message,
+        option: LlqOption,
+        answers: Vec<Record>,
+    ) {
+        let mut msg = Message::new();
+        msg.set_id(req.id());
+        msg.set_qr(QR::Response);
+        for question in req.questions() {
+            msg.add_question(question.clone());
+        }
+        for answer in answers {
+            msg.add_answer(answer);
+        }
+        msg.add_additional(option.to_record());
+        server.send_to(&msg.to_bytes().unwrap(), to).unwrap();
+    }
+
+    #[test]
+    fn llq_option() {
+        let option = LlqOption::new(LlqOpcode::Refresh, 0x0123456789abcdef, 3600);
+        let mut msg = Message::new();
+        msg.add_additional(option.to_record());
+        let msg = Message::from_bytes(&msg.to_bytes().unwrap()).unwrap();
+        assert_eq!(msg.additionals()[0].name(), "");
+        assert_eq!(LlqOption::from_message(&msg), Some(option));
+    }
+
+    #[test]
+    fn llq_client() {
+        let server = UdpSocket::bind("127.0.0.1:0").unwrap();
+        server
+            .set_read_timeout(Some(Duration::from_secs(5)))
+            .unwrap();
+        let server_addr = server.local_addr().unwrap();
+
+        let server_thread = thread::spawn(move || {
+            // Initial request and challenge
+            let (req, option, from) = recv_option(&server);
+            assert_eq!(option.opcode(), LlqOpcode::Setup);
+            assert_eq!(option.id(), 0);
+            reply(
+                &server,
+                from,
+                &req,
+                LlqOption::new(LlqOpcode::Setup, 42, 3600),
+                vec![],
+            );
+
+            // Challenge response and acknowledgement with the initial answers
+            let (req, option, from) = recv_option(&server);
+            assert_eq!(option.id(), 42);
+            let answers = vec![srv_record(
+                "first._http._tcp.example.com",
+                80,
+                "host.example.com",
+            )];
+            reply(
+                &server,
+                from,
+                &req,
+                LlqOption::new(LlqOpcode::Setup, 42, 3600),
+                answers,
+            );
+
+            // Event and its acknowledgement
+            let answers = vec![with_ttl(
+                srv_record("first._http._tcp.example.com", 80, "host.example.com"),
+                LLQ_EVENT_REMOVED_TTL,
+            )];
+            reply(
+                &server,
+                from,
+                &req,
+                LlqOption::new(LlqOpcode::Event, 42, 3600),
+                answers,
+            );
+            let (_, option, _) = recv_option(&server);
+            assert_eq!(option.opcode(), LlqOpcode::Event);
+
+            // Cancellation
+            let (_, option, _) = recv_option(&server);
+            assert_eq!(option.opcode(), LlqOpcode::Refresh);
+            assert_eq!(option.lease(), 0);
+        });
+
+        let discoverer = Discoverer::new();
+        let events = discoverer.lock().unwrap().subscribe();
+        let mut llq = LlqClient::new(server_addr, "_http._tcp.example.com");
+        llq.start(discoverer.clone(), Duration::from_secs(5))
+            .unwrap();
+        assert_eq!(llq.id(), 42);
+        assert_eq!(llq.lease(), 3600);
+
+        match events.recv_timeout(Duration::from_secs(5)) {
+            Ok(ServiceEvent::Added(service)) => {
+                assert_eq!(service.name(), "first._http._tcp.example.com");
+                assert_eq!(service.source(), Some(server_addr));
+            }
+            _ => panic!("expected an added event"),
+        }
+        assert!(events.recv_timeout(Duration::from_secs(5)).is_ok());
+
+        assert!(llq.stop(Duration::from_secs(5)));
+        server_thread.join().unwrap();
+    }
+}
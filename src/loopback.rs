@@ -0,0 +1,128 @@
+// Copyright (C) 2024 Satoshi Konno All rights reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//    http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+use std::net::{IpAddr, SocketAddr};
+use std::time::{Duration, Instant};
+
+use crate::default::PORT;
+
+/// DEFAULT_LOOPBACK_WINDOW is the default time a transmitted message is remembered to recognize its looped back copy.
+pub const DEFAULT_LOOPBACK_WINDOW: Duration = Duration::from_secs(2);
+
+/// LoopbackFilter represents the fingerprints of the recently transmitted multicast messages,
+/// which recognizes the copies looped back by the kernel. A filter shared by a discoverer and a responder
+/// keeps the discoverer from caching the announcements of its own responder as discovered services.
+pub struct LoopbackFilter {
+    window: Duration,
+    sent: HashMap<u64, Instant>,
+    local_addrs: Vec<IpAddr>,
+}
+
+impl LoopbackFilter {
+    /// new creates a new filter of the default window.
+    pub fn new() -> LoopbackFilter {
+        LoopbackFilter::with_window(DEFAULT_LOOPBACK_WINDOW)
+    }
+
+    /// with_window creates a new filter of the specified window.
+    pub fn with_window(window: Duration) -> LoopbackFilter {
+        LoopbackFilter {
+            window,
+            sent: HashMap::new(),
+            local_addrs: Vec::new(),
+        }
+    }
+
+    /// window returns the window of the filter.
+    pub fn window(&self) -> Duration {
+        self.window
+    }
+
+    /// add_local_addrs adds the specified addresses of the local interfaces, which the looped back messages come from.
+    pub fn add_local_addrs(&mut self, addrs: &[IpAddr]) {
+        for addr in addrs {
+            if !self.local_addrs.contains(addr) {
+                self.local_addrs.push(*addr);
+            }
+        }
+    }
+
+    /// local_addrs returns the addresses of the local interfaces.
+    pub fn local_addrs(&self) -> &[IpAddr] {
+        &self.local_addrs
+    }
+
+    /// is_local_addr returns true if the specified address is a loopback address or an address of the local interfaces.
+    pub fn is_local_addr(&self, addr: &IpAddr) -> bool {
+        addr.is_loopback() || self.local_addrs.contains(addr)
+    }
+
+    /// add_sent remembers the specified message transmitted now.
+    pub fn add_sent(&mut self, msg_bytes: &[u8]) {
+        self.add_sent_at(msg_bytes, Instant::now());
+    }
+
+    /// add_sent_at remembers the specified message transmitted at the specified time.
+    pub fn add_sent_at(&mut self, msg_bytes: &[u8], now: Instant) {
+        self.remove_expired_at(now);
+        self.sent.insert(fingerprint(msg_bytes), now);
+    }
+
+    /// is_own returns true if the specified message received now is a looped back copy of a message transmitted within the window.
+    pub fn is_own(&mut self, msg_bytes: &[u8], from: SocketAddr) -> bool {
+        self.is_own_at(msg_bytes, from, Instant::now())
+    }
+
+    /// is_own_at returns true if the specified message received at the specified time is a looped back copy,
+    /// which is identical to a transmitted message and comes from the Multicast DNS port of a local address.
+    /// Another host may send an identical query, so the message fingerprint alone is not regarded as our own.
+    pub fn is_own_at(&mut self, msg_bytes: &[u8], from: SocketAddr, now: Instant) -> bool {
+        self.remove_expired_at(now);
+        if from.port() != PORT || !self.is_local_addr(&from.ip()) {
+            return false;
+        }
+        self.sent.contains_key(&fingerprint(msg_bytes))
+    }
+
+    /// len returns the number of the remembered messages.
+    pub fn len(&self) -> usize {
+        self.sent.len()
+    }
+
+    /// is_empty returns true if no message is remembered.
+    pub fn is_empty(&self) -> bool {
+        self.sent.is_empty()
+    }
+
+    fn remove_expired_at(&mut self, now: Instant) {
+        let window = self.window;
+        self.sent
+            .retain(|_, sent| now.saturating_duration_since(*sent) < window);
+    }
+}
+
+impl Default for LoopbackFilter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn fingerprint(msg_bytes: &[u8]) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    msg_bytes.hash(&mut hasher);
+    hasher.finish()
+}
@@ -0,0 +1,75 @@
+// Copyright (C) 2024 Satoshi Konno All rights reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//    http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+#[cfg(test)]
+mod tests {
+
+    use std::net::IpAddr;
+    use std::time::{Duration, Instant};
+
+    use cybergarage::net::Observer;
+
+    use crate::fixture_test::srv_response_packet;
+    use crate::{Discoverer, LoopbackFilter, ServiceEvent};
+
+    #[test]
+    fn loopback_filter() {
+        let now = Instant::now();
+        let mut filter = LoopbackFilter::new();
+        assert_eq!(filter.window(), Duration::from_secs(2));
+        let local: IpAddr = "192.168.1.2".parse().unwrap();
+        filter.add_local_addrs(&[local, local]);
+        assert_eq!(filter.local_addrs(), &[local]);
+
+        filter.add_sent_at(&[1, 2, 3], now);
+        assert_eq!(filter.len(), 1);
+        let later = now + Duration::from_millis(100);
+        assert!(filter.is_own_at(&[1, 2, 3], "192.168.1.2:5353".parse().unwrap(), later));
+        assert!(filter.is_own_at(&[1, 2, 3], "127.0.0.1:5353".parse().unwrap(), later));
+        // An identical message of another host, or from another port, is not our own.
+        assert!(!filter.is_own_at(&[1, 2, 3], "192.168.1.10:5353".parse().unwrap(), later));
+        assert!(!filter.is_own_at(&[1, 2, 3], "192.168.1.2:40000".parse().unwrap(), later));
+        assert!(!filter.is_own_at(&[1, 2, 4], "192.168.1.2:5353".parse().unwrap(), later));
+        // The fingerprints are forgotten after the window.
+        let expired = now + Duration::from_secs(2);
+        assert!(!filter.is_own_at(&[1, 2, 3], "192.168.1.2:5353".parse().unwrap(), expired));
+        assert!(filter.is_empty());
+    }
+
+    #[test]
+    fn discoverer_self_suppression() {
+        let discoverer = Discoverer::new();
+        let events = discoverer.lock().unwrap().subscribe();
+        let mut pkt = srv_response_packet("My Web._http._tcp.local", 80);
+        pkt.set_from("192.168.1.2:5353".parse().unwrap());
+
+        // The announcement of our own responder sharing the filter is ignored.
+        let filter = discoverer.lock().unwrap().loopback_filter();
+        {
+            let mut filter = filter.lock().unwrap();
+            filter.add_local_addrs(&["192.168.1.2".parse().unwrap()]);
+            filter.add_sent(pkt.bytes());
+        }
+        discoverer.lock().unwrap().packet_received(&pkt);
+        assert!(events.try_recv().is_err());
+        assert!(discoverer.lock().unwrap().services().is_empty());
+        assert_eq!(discoverer.lock().unwrap().stats().parse_time().count(), 0);
+
+        // It is discovered when the suppression is disabled.
+        assert!(discoverer.lock().unwrap().is_self_suppression());
+        discoverer.lock().unwrap().set_self_suppression(false);
+        discoverer.lock().unwrap().packet_received(&pkt);
+        assert!(matches!(events.try_recv(), Ok(ServiceEvent::Added(_))));
+    }
+}
@@ -12,14 +12,18 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use crate::dns::{Message, QuestionRecord};
+use crate::additional::additional_records;
+use crate::cache::Cache;
+use crate::dns::{Message, QuestionRecord, Record, Result, Type};
 use crate::query::Query;
+use crate::service::Service;
 
 /// QueryMessage represents a DNS-SD query message.
 pub struct QueryMessage {}
 
 impl QueryMessage {
     /// Create a new query message.
+    #[allow(clippy::new_ret_no_self)]
     pub fn new(q: &Query) -> Message {
         let mut msg = Message::new();
         let mut qr = QuestionRecord::new();
@@ -27,4 +31,77 @@ impl QueryMessage {
         msg.add_question(qr);
         msg
     }
+
+    /// with_known_answers creates a new query message, whose answer section carries the specified records answering the query
+    /// such as the PTR records with their remaining TTLs. The records of the other names or types, and expired ones, are skipped.
+    /// RFC 6762: 7.1. Known-Answer Suppression
+    pub fn with_known_answers(q: &Query, known_answers: &[Record]) -> Message {
+        let mut msg = QueryMessage::new(q);
+        let question = msg.questions()[0].clone();
+        for record in known_answers {
+            if record.ttl() == 0 || !question.matches(record) {
+                continue;
+            }
+            let mut record = record.clone();
+            record.set_cache_flush(false);
+            msg.add_answer(record);
+        }
+        msg
+    }
+
+    /// from_cache creates a new query message with the known answers in the specified cache, which are the cached records
+    /// answering the query whose remaining TTLs are more than half of their original TTLs.
+    pub fn from_cache(q: &Query, cache: &Cache) -> Message {
+        let msg = QueryMessage::new(q);
+        let known_answers = cache.known_answers(msg.questions());
+        QueryMessage::with_known_answers(q, &known_answers)
+    }
+}
+
+/// ResponseMessage represents a DNS-SD response message.
+pub struct ResponseMessage {}
+
+impl ResponseMessage {
+    /// from_service creates a new response of the PTR, SRV, TXT, A and AAAA records of the specified service.
+    pub fn from_service(service: &Service) -> Result<Message> {
+        let records: Vec<Record> = service
+            .records()
+            .iter()
+            .map(|record| record.record().clone())
+            .collect();
+        ResponseMessage::from_records(&records)
+    }
+
+    /// from_records creates a new authoritative response of the specified records whose identifier is zero.
+    /// The PTR records are the answers, or the SRV and TXT records if there is no PTR record, or the other records
+    /// if there is neither, and the rest of the records follow as the additional records.
+    /// RFC 6762: 18.1. ID (Query Identifier)
+    /// In multicast responses, including unsolicited multicast responses, the Query Identifier MUST be set to zero on transmission.
+    /// RFC 6763: 12. Populating the DNS Additional Record Section
+    pub fn from_records(records: &[Record]) -> Result<Message> {
+        let answer_types = [vec![Type::PTR], vec![Type::SRV, Type::TXT]]
+            .into_iter()
+            .find(|types| records.iter().any(|r| types.contains(&r.typ())));
+        let (answers, rest): (Vec<Record>, Vec<Record>) =
+            records.iter().cloned().partition(|r| match &answer_types {
+                Some(types) => types.contains(&r.typ()),
+                None => true,
+            });
+        let mut additionals = additional_records(&answers, &rest);
+        for record in rest {
+            if !additionals.iter().any(|r| is_same_record(r, &record)) {
+                additionals.push(record);
+            }
+        }
+        Message::builder()
+            .response()
+            .id(0)
+            .answers(answers)
+            .additionals(additionals)
+            .build()
+    }
+}
+
+fn is_same_record(a: &Record, b: &Record) -> bool {
+    a.typ() == b.typ() && a.name().eq_ignore_ascii_case(b.name()) && a.data() == b.data()
 }
@@ -18,8 +18,11 @@ mod tests {
     use crate::dns::class::Class;
     use crate::dns::message::Message;
     use crate::dns::typ::Type;
-    use crate::message::QueryMessage;
+    use crate::dns::{AAAARecord, ARecord};
+    use crate::fixture_test::{ptr_record, unique, with_ttl};
+    use crate::message::{QueryMessage, ResponseMessage};
     use crate::query::Query;
+    use crate::{Cache, Provenance, RecordTtls, Service, ServiceBuilder};
 
     #[test]
     fn query_message() {
@@ -61,4 +64,82 @@ mod tests {
             assert_eq!(question.class(), Class::IN);
         }
     }
+
+    #[test]
+    fn response_message() {
+        let info = ServiceBuilder::new("web", "_http._tcp")
+            .port(8080)
+            .attribute("path", "/")
+            .build()
+            .unwrap();
+        let mut records = info.records("host.local", &RecordTtls::new());
+        let mut a = "192.168.1.10".parse::<ARecord>().unwrap();
+        a.set_name("host.local");
+        records.push(a.to_record());
+        let mut aaaa = "fe80::10".parse::<AAAARecord>().unwrap();
+        aaaa.set_name("host.local");
+        records.push(aaaa.to_record());
+        let mut announcement = Message::new();
+        for record in records {
+            announcement.add_answer(record);
+        }
+        let service = Service::from_message(&announcement);
+
+        let msg = ResponseMessage::from_service(&service).unwrap();
+        assert!(msg.is_response());
+        assert!(msg.aa());
+        assert_eq!(msg.id(), 0);
+        assert_eq!(msg.qd_count(), 0);
+        assert_eq!(msg.an_count(), 1);
+        assert_eq!(msg.answers()[0].typ(), Type::PTR);
+        let types: Vec<Type> = msg.additionals().iter().map(|r| r.typ()).collect();
+        assert_eq!(types, vec![Type::TXT, Type::SRV, Type::A, Type::AAAA]);
+        let parsed = Message::from_bytes(&msg.to_bytes().unwrap()).unwrap();
+        assert!(parsed == msg);
+
+        // Without PTR records, the SRV and TXT records are the answers.
+        let records: Vec<_> = msg.additionals().to_vec();
+        let msg = ResponseMessage::from_records(&records).unwrap();
+        assert_eq!(msg.an_count(), 2);
+        assert_eq!(msg.ar_count(), 2);
+        let msg = ResponseMessage::from_records(&records[2..]).unwrap();
+        assert_eq!(msg.an_count(), 2);
+        assert_eq!(msg.ar_count(), 0);
+    }
+
+    #[test]
+    fn query_message_known_answers() {
+        let query = Query::with("_http._tcp", "local");
+        let http = |instance: &str, ttl: u32| {
+            let instance = format!("{}._http._tcp.local", instance);
+            unique(with_ttl(ptr_record("_http._tcp.local", &instance), ttl))
+        };
+        let known_answers = vec![
+            http("web", 4500),
+            http("gone", 0),
+            with_ttl(
+                ptr_record("_ipp._tcp.local", "printer._ipp._tcp.local"),
+                4500,
+            ),
+            http("blog", 3000),
+        ];
+        let msg = QueryMessage::with_known_answers(&query, &known_answers);
+        assert!(msg.is_query());
+        assert_eq!(msg.qd_count(), 1);
+        assert_eq!(msg.an_count(), 2);
+        for answer in msg.answers() {
+            assert_eq!(answer.name(), "_http._tcp.local");
+            assert!(!answer.cache_flush());
+        }
+        let parsed = Message::from_bytes(&msg.to_bytes().unwrap()).unwrap();
+        assert_eq!(parsed.an_count(), 2);
+
+        let mut cache = Cache::new();
+        let provenance = Provenance::new("192.168.1.10:5353".parse().unwrap());
+        for record in &known_answers {
+            cache.add_record(record, &provenance);
+        }
+        let msg = QueryMessage::from_cache(&query, &cache);
+        assert_eq!(msg.an_count(), 2);
+    }
 }
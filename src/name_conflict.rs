@@ -0,0 +1,87 @@
+// Copyright (C) 2024 Satoshi Konno All rights reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//    http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::fmt;
+use std::net::SocketAddr;
+
+use crate::record_index::RegistrationId;
+
+/// NameConflict represents a registered service instance name lost to another host, and the name it is renamed to.
+/// RFC 6762: 9. Conflict Resolution
+#[derive(Debug, Clone, PartialEq)]
+pub struct NameConflict {
+    id: RegistrationId,
+    old_name: String,
+    new_name: String,
+    source: SocketAddr,
+}
+
+impl NameConflict {
+    /// new creates a new conflict of the specified registration with the host of the specified address.
+    pub fn new(
+        id: RegistrationId,
+        old_name: &str,
+        new_name: &str,
+        source: SocketAddr,
+    ) -> NameConflict {
+        NameConflict {
+            id,
+            old_name: old_name.to_string(),
+            new_name: new_name.to_string(),
+            source,
+        }
+    }
+
+    /// id returns the registration of the conflicted service.
+    pub fn id(&self) -> RegistrationId {
+        self.id
+    }
+
+    /// old_name returns the instance name which conflicted.
+    pub fn old_name(&self) -> &str {
+        &self.old_name
+    }
+
+    /// new_name returns the instance name the service is renamed to unless the rename is vetoed.
+    pub fn new_name(&self) -> &str {
+        &self.new_name
+    }
+
+    /// source returns the address of the host which asserted the conflicting records.
+    pub fn source(&self) -> SocketAddr {
+        self.source
+    }
+}
+
+impl fmt::Display for NameConflict {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{} conflicted with {}, renamed to {}",
+            self.old_name, self.source, self.new_name
+        )
+    }
+}
+
+/// ConflictDecision represents how a responder resolves a lost name conflict.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum ConflictDecision {
+    /// Rename probes the service again with the new name.
+    Rename,
+    /// Deregister withdraws the service instead, for the products which must keep a fixed provisioned name.
+    Deregister,
+}
+
+/// ConflictCallback is called with each lost name conflict of a registered service, and decides how it is resolved.
+pub type ConflictCallback = Box<dyn Fn(&NameConflict) -> ConflictDecision + Send>;
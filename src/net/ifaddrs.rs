@@ -0,0 +1,406 @@
+// Copyright (C) 2024 Satoshi Konno All rights reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//    http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::io;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+
+/// InterfaceAddr represents a unicast address of an interface with the prefix length of its network.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct InterfaceAddr {
+    addr: IpAddr,
+    prefix_len: u8,
+}
+
+impl InterfaceAddr {
+    /// new creates a new address of the specified prefix length.
+    pub fn new(addr: IpAddr, prefix_len: u8) -> InterfaceAddr {
+        let max_len = match addr {
+            IpAddr::V4(_) => 32,
+            IpAddr::V6(_) => 128,
+        };
+        InterfaceAddr {
+            addr,
+            prefix_len: prefix_len.min(max_len),
+        }
+    }
+
+    /// addr returns the address.
+    pub fn addr(&self) -> IpAddr {
+        self.addr
+    }
+
+    /// prefix_len returns the prefix length of the network of the address.
+    pub fn prefix_len(&self) -> u8 {
+        self.prefix_len
+    }
+
+    /// netmask returns the netmask of the network of the address.
+    pub fn netmask(&self) -> IpAddr {
+        match self.addr {
+            IpAddr::V4(_) => IpAddr::V4(Ipv4Addr::from(mask_bits(self.prefix_len, 32) as u32)),
+            IpAddr::V6(_) => IpAddr::V6(Ipv6Addr::from(mask_bits(self.prefix_len, 128))),
+        }
+    }
+
+    /// contains returns true if the specified address is in the network of the address.
+    pub fn contains(&self, addr: &IpAddr) -> bool {
+        match (self.addr, addr) {
+            (IpAddr::V4(net), IpAddr::V4(addr)) => {
+                let mask = mask_bits(self.prefix_len, 32) as u32;
+                u32::from(net) & mask == u32::from(*addr) & mask
+            }
+            (IpAddr::V6(net), IpAddr::V6(addr)) => {
+                let mask = mask_bits(self.prefix_len, 128);
+                u128::from(net) & mask == u128::from(*addr) & mask
+            }
+            _ => false,
+        }
+    }
+}
+
+// mask_bits returns the mask of the specified prefix length in the lower bits of the specified width.
+fn mask_bits(prefix_len: u8, width: u32) -> u128 {
+    if prefix_len == 0 {
+        return 0;
+    }
+    let ones = u128::MAX >> (128 - width);
+    (ones << (width - prefix_len as u32)) & ones
+}
+
+/// Interface represents a local network interface.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Interface {
+    name: String,
+    index: u32,
+    up: bool,
+    loopback: bool,
+    multicast: bool,
+    point_to_point: bool,
+    addrs: Vec<InterfaceAddr>,
+}
+
+impl Interface {
+    /// new creates a new interface of the specified name, index and addresses, which is up and supports multicast,
+    /// such as to describe the interfaces of a fixed configuration instead of enumerating them.
+    pub fn new(name: &str, index: u32, addrs: &[InterfaceAddr]) -> Interface {
+        let mut iface = Interface {
+            name: name.to_string(),
+            index,
+            up: true,
+            loopback: false,
+            multicast: true,
+            point_to_point: false,
+            addrs: Vec::new(),
+        };
+        for addr in addrs {
+            iface.add_addr(*addr);
+        }
+        iface
+    }
+
+    /// name returns the name of the interface such as `eth0` or `en0`.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// index returns the index of the interface, which is also the scope ID of the IPv6 link-local addresses.
+    pub fn index(&self) -> u32 {
+        self.index
+    }
+
+    /// is_up returns true if the interface is up.
+    pub fn is_up(&self) -> bool {
+        self.up
+    }
+
+    /// is_loopback returns true if the interface is a loopback interface.
+    pub fn is_loopback(&self) -> bool {
+        self.loopback
+    }
+
+    /// is_multicast returns true if the interface supports multicast.
+    pub fn is_multicast(&self) -> bool {
+        self.multicast
+    }
+
+    /// is_point_to_point returns true if the interface is a point-to-point link such as a VPN tunnel.
+    pub fn is_point_to_point(&self) -> bool {
+        self.point_to_point
+    }
+
+    /// addrs returns the unicast addresses of the interface.
+    pub fn addrs(&self) -> &Vec<InterfaceAddr> {
+        &self.addrs
+    }
+
+    /// ipaddrs returns the unicast addresses of the interface without the prefix lengths.
+    pub fn ipaddrs(&self) -> Vec<IpAddr> {
+        self.addrs.iter().map(|a| a.addr()).collect()
+    }
+
+    /// contains returns true if the specified address is on a network of the interface.
+    pub fn contains(&self, addr: &IpAddr) -> bool {
+        self.addrs.iter().any(|a| a.contains(addr))
+    }
+
+    /// is_mdns_capable returns true if multicast DNS can run on the interface,
+    /// which is up, supports multicast and has an address.
+    pub fn is_mdns_capable(&self) -> bool {
+        self.up && self.multicast && !self.addrs.is_empty()
+    }
+
+    fn add_addr(&mut self, addr: InterfaceAddr) {
+        if !self.addrs.contains(&addr) {
+            self.addrs.push(addr);
+        }
+    }
+}
+
+/// interfaces returns the local network interfaces with their unicast addresses.
+pub fn interfaces() -> io::Result<Vec<Interface>> {
+    platform_interfaces()
+}
+
+/// mdns_interfaces returns the interfaces which multicast DNS can run on, excluding the loopback interfaces.
+pub fn mdns_interfaces() -> io::Result<Vec<Interface>> {
+    Ok(interfaces()?
+        .into_iter()
+        .filter(|i| i.is_mdns_capable() && !i.is_loopback())
+        .collect())
+}
+
+/// unicast_addrs returns the addresses of the interfaces which multicast DNS can run on, such as to generate the A/AAAA records.
+pub fn unicast_addrs() -> io::Result<Vec<IpAddr>> {
+    Ok(mdns_interfaces()?
+        .iter()
+        .flat_map(|i| i.ipaddrs())
+        .collect())
+}
+
+/// interface_of returns the interface on whose network the specified address is.
+pub fn interface_of<'a>(ifaces: &'a [Interface], addr: &IpAddr) -> Option<&'a Interface> {
+    if let IpAddr::V6(v6) = addr {
+        if let Some(v4) = v6.to_ipv4_mapped() {
+            return interface_of(ifaces, &IpAddr::V4(v4));
+        }
+    }
+    ifaces
+        .iter()
+        .filter(|i| !i.is_loopback() || addr.is_loopback())
+        .find(|i| i.contains(addr))
+}
+
+/// is_on_link returns true if the specified address is link-local, loopback or on a directly attached network of the interfaces.
+/// RFC 6762: 11. Source Address Check
+pub fn is_on_link(ifaces: &[Interface], addr: &IpAddr) -> bool {
+    match addr {
+        IpAddr::V4(v4) if v4.is_link_local() || v4.is_loopback() => true,
+        IpAddr::V6(v6) if v6.is_unicast_link_local() || v6.is_loopback() => true,
+        IpAddr::V6(v6) => match v6.to_ipv4_mapped() {
+            Some(v4) => is_on_link(ifaces, &IpAddr::V4(v4)),
+            None => interface_of(ifaces, addr).is_some(),
+        },
+        IpAddr::V4(_) => interface_of(ifaces, addr).is_some(),
+    }
+}
+
+fn find_or_insert<'a>(ifaces: &'a mut Vec<Interface>, name: &str) -> &'a mut Interface {
+    let n = match ifaces.iter().position(|i| i.name == name) {
+        Some(n) => n,
+        None => {
+            ifaces.push(Interface {
+                name: name.to_string(),
+                index: 0,
+                up: false,
+                loopback: false,
+                multicast: false,
+                point_to_point: false,
+                addrs: Vec::new(),
+            });
+            ifaces.len() - 1
+        }
+    };
+    &mut ifaces[n]
+}
+
+#[cfg(unix)]
+fn platform_interfaces() -> io::Result<Vec<Interface>> {
+    use std::ffi::CStr;
+
+    let mut ifap: *mut libc::ifaddrs = std::ptr::null_mut();
+    // SAFETY: getifaddrs initializes the list on success, which is freed below.
+    if unsafe { libc::getifaddrs(&mut ifap) } != 0 {
+        return Err(io::Error::last_os_error());
+    }
+    let mut ifaces: Vec<Interface> = Vec::new();
+    let mut cur = ifap;
+    while !cur.is_null() {
+        // SAFETY: cur is a node of the list returned by getifaddrs.
+        let ifa = unsafe { &*cur };
+        cur = ifa.ifa_next;
+        if ifa.ifa_name.is_null() {
+            continue;
+        }
+        // SAFETY: ifa_name is a NUL terminated string of the node.
+        let name = unsafe { CStr::from_ptr(ifa.ifa_name) }.to_string_lossy();
+        let flags = ifa.ifa_flags as libc::c_int;
+        let iface = find_or_insert(&mut ifaces, &name);
+        // SAFETY: ifa_name is a NUL terminated string of the node.
+        iface.index = unsafe { libc::if_nametoindex(ifa.ifa_name) };
+        iface.up = flags & libc::IFF_UP != 0;
+        iface.loopback = flags & libc::IFF_LOOPBACK != 0;
+        iface.multicast = flags & libc::IFF_MULTICAST != 0;
+        iface.point_to_point = flags & libc::IFF_POINTOPOINT != 0;
+        // SAFETY: ifa_addr and ifa_netmask are null or socket addresses of the family of sa_family.
+        let (addr, netmask) = unsafe {
+            (
+                sockaddr_to_ipaddr(ifa.ifa_addr),
+                sockaddr_to_ipaddr(ifa.ifa_netmask),
+            )
+        };
+        if let Some(addr) = addr {
+            let prefix_len = match netmask {
+                Some(IpAddr::V4(mask)) => u32::from(mask).count_ones() as u8,
+                Some(IpAddr::V6(mask)) => u128::from(mask).count_ones() as u8,
+                None => 0,
+            };
+            iface.add_addr(InterfaceAddr::new(addr, prefix_len));
+        }
+    }
+    // SAFETY: ifap was returned by getifaddrs and is not used after this.
+    unsafe { libc::freeifaddrs(ifap) };
+    Ok(ifaces)
+}
+
+#[cfg(unix)]
+unsafe fn sockaddr_to_ipaddr(sa: *const libc::sockaddr) -> Option<IpAddr> {
+    if sa.is_null() {
+        return None;
+    }
+    match (*sa).sa_family as libc::c_int {
+        libc::AF_INET => {
+            let sin = &*(sa as *const libc::sockaddr_in);
+            Some(IpAddr::V4(Ipv4Addr::from(u32::from_be(
+                sin.sin_addr.s_addr,
+            ))))
+        }
+        libc::AF_INET6 => {
+            let sin6 = &*(sa as *const libc::sockaddr_in6);
+            Some(IpAddr::V6(Ipv6Addr::from(sin6.sin6_addr.s6_addr)))
+        }
+        _ => None,
+    }
+}
+
+#[cfg(windows)]
+fn platform_interfaces() -> io::Result<Vec<Interface>> {
+    use windows_sys::Win32::Foundation::{ERROR_BUFFER_OVERFLOW, NO_ERROR};
+    use windows_sys::Win32::NetworkManagement::IpHelper::{
+        GetAdaptersAddresses, GAA_FLAG_SKIP_ANYCAST, GAA_FLAG_SKIP_DNS_SERVER,
+        GAA_FLAG_SKIP_MULTICAST, IF_TYPE_PPP, IF_TYPE_SOFTWARE_LOOPBACK, IP_ADAPTER_ADDRESSES_LH,
+        IP_ADAPTER_NO_MULTICAST,
+    };
+    use windows_sys::Win32::NetworkManagement::Ndis::IfOperStatusUp;
+    use windows_sys::Win32::Networking::WinSock::{
+        AF_INET, AF_INET6, AF_UNSPEC, SOCKADDR, SOCKADDR_IN, SOCKADDR_IN6,
+    };
+
+    let flags = GAA_FLAG_SKIP_ANYCAST | GAA_FLAG_SKIP_MULTICAST | GAA_FLAG_SKIP_DNS_SERVER;
+    let mut size: u32 = 16 * 1024;
+    let mut buf: Vec<u64>;
+    loop {
+        buf = vec![0; (size as usize).div_ceil(8)];
+        // SAFETY: buf is a writable and aligned buffer of size bytes.
+        let ret = unsafe {
+            GetAdaptersAddresses(
+                AF_UNSPEC as u32,
+                flags,
+                std::ptr::null(),
+                buf.as_mut_ptr() as *mut IP_ADAPTER_ADDRESSES_LH,
+                &mut size,
+            )
+        };
+        if ret == ERROR_BUFFER_OVERFLOW {
+            continue;
+        }
+        if ret != NO_ERROR {
+            return Err(io::Error::from_raw_os_error(ret as i32));
+        }
+        break;
+    }
+
+    let mut ifaces: Vec<Interface> = Vec::new();
+    let mut cur = buf.as_ptr() as *const IP_ADAPTER_ADDRESSES_LH;
+    while !cur.is_null() {
+        // SAFETY: cur is a node of the list written by GetAdaptersAddresses into buf.
+        let adapter = unsafe { &*cur };
+        cur = adapter.Next;
+        // SAFETY: FriendlyName is a NUL terminated wide string of the node.
+        let name = unsafe { wide_to_string(adapter.FriendlyName) };
+        let iface = find_or_insert(&mut ifaces, &name);
+        // SAFETY: IfIndex is the interpretation of the first union field for the adapters.
+        iface.index = unsafe { adapter.Anonymous1.Anonymous.IfIndex };
+        iface.up = adapter.OperStatus == IfOperStatusUp;
+        iface.loopback = adapter.IfType == IF_TYPE_SOFTWARE_LOOPBACK;
+        // SAFETY: Flags is the interpretation of the second union field for the adapters.
+        iface.multicast = unsafe { adapter.Anonymous2.Flags } & IP_ADAPTER_NO_MULTICAST == 0;
+        iface.point_to_point = adapter.IfType == IF_TYPE_PPP;
+        let mut unicast = adapter.FirstUnicastAddress;
+        while !unicast.is_null() {
+            // SAFETY: unicast is a node of the address list of the adapter.
+            let ua = unsafe { &*unicast };
+            unicast = ua.Next;
+            let sa = ua.Address.lpSockaddr as *const SOCKADDR;
+            if sa.is_null() {
+                continue;
+            }
+            // SAFETY: sa is a socket address of the family of sa_family.
+            let addr = unsafe {
+                match (*sa).sa_family {
+                    AF_INET => {
+                        let sin = &*(sa as *const SOCKADDR_IN);
+                        IpAddr::V4(Ipv4Addr::from(u32::from_be(sin.sin_addr.S_un.S_addr)))
+                    }
+                    AF_INET6 => {
+                        let sin6 = &*(sa as *const SOCKADDR_IN6);
+                        IpAddr::V6(Ipv6Addr::from(sin6.sin6_addr.u.Byte))
+                    }
+                    _ => continue,
+                }
+            };
+            iface.add_addr(InterfaceAddr::new(addr, ua.OnLinkPrefixLength));
+        }
+    }
+    Ok(ifaces)
+}
+
+#[cfg(windows)]
+unsafe fn wide_to_string(s: *const u16) -> String {
+    if s.is_null() {
+        return String::new();
+    }
+    let mut len = 0;
+    while *s.add(len) != 0 {
+        len += 1;
+    }
+    String::from_utf16_lossy(std::slice::from_raw_parts(s, len))
+}
+
+#[cfg(not(any(unix, windows)))]
+fn platform_interfaces() -> io::Result<Vec<Interface>> {
+    Err(io::Error::new(
+        io::ErrorKind::Unsupported,
+        "interface enumeration is not supported on this platform",
+    ))
+}
@@ -0,0 +1,145 @@
+// Copyright (C) 2024 Satoshi Konno All rights reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//    http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+#[cfg(test)]
+mod tests {
+
+    use std::net::IpAddr;
+
+    use cybergarage::net::{Observer, Packet};
+
+    use crate::dns::{Message, Record, Type};
+    use crate::net::{interface_of, interfaces, is_on_link, Interface, InterfaceAddr};
+    use crate::Discoverer;
+
+    #[test]
+    fn interface_addrs() {
+        struct Test {
+            net: &'static str,
+            prefix_len: u8,
+            addr: &'static str,
+            netmask: &'static str,
+            expected: bool,
+        }
+
+        let tests = vec![
+            Test {
+                net: "192.168.1.10",
+                prefix_len: 24,
+                addr: "192.168.1.20",
+                netmask: "255.255.255.0",
+                expected: true,
+            },
+            Test {
+                net: "192.168.1.10",
+                prefix_len: 24,
+                addr: "192.168.2.20",
+                netmask: "255.255.255.0",
+                expected: false,
+            },
+            Test {
+                net: "fe80::10",
+                prefix_len: 64,
+                addr: "fe80::20",
+                netmask: "ffff:ffff:ffff:ffff::",
+                expected: true,
+            },
+            Test {
+                net: "fe80::10",
+                prefix_len: 64,
+                addr: "192.168.1.20",
+                netmask: "ffff:ffff:ffff:ffff::",
+                expected: false,
+            },
+        ];
+        for test in tests {
+            let addr = InterfaceAddr::new(test.net.parse().unwrap(), test.prefix_len);
+            assert_eq!(addr.netmask(), test.netmask.parse::<IpAddr>().unwrap());
+            assert_eq!(
+                addr.contains(&test.addr.parse().unwrap()),
+                test.expected,
+                "{}/{} {}",
+                test.net,
+                test.prefix_len,
+                test.addr
+            );
+        }
+    }
+
+    #[test]
+    fn interface_enumeration() {
+        let ifaces = interfaces().unwrap();
+        let loopback: IpAddr = "127.0.0.1".parse().unwrap();
+        let iface = match interface_of(&ifaces, &loopback) {
+            Some(iface) => iface,
+            None => return,
+        };
+        assert!(iface.is_loopback());
+        assert!(iface.ipaddrs().contains(&loopback));
+
+        let discoverer = Discoverer::new();
+        discoverer.lock().unwrap().refresh_interfaces().unwrap();
+        let mut a = Record::new();
+        a.set_name("host.local");
+        a.set_typ(Type::A);
+        a.set_ttl(120);
+        a.set_data(vec![127, 0, 0, 1]);
+        let mut msg = Message::new();
+        msg.add_answer(a);
+        let mut pkt = Packet::from_bytes(&msg.to_bytes().unwrap());
+        pkt.set_from("127.0.0.1:5353".parse().unwrap());
+        discoverer.lock().unwrap().packet_received(&pkt);
+        let discoverer = discoverer.lock().unwrap();
+        let records = discoverer.cache().records("host.local");
+        assert_eq!(records[0].provenance().interface(), Some(iface.name()));
+    }
+
+    #[test]
+    fn interface_on_link() {
+        let ifaces = vec![Interface::new(
+            "eth0",
+            2,
+            &[
+                InterfaceAddr::new("192.168.1.10".parse().unwrap(), 24),
+                InterfaceAddr::new("2001:db8::10".parse().unwrap(), 64),
+            ],
+        )];
+        assert!(ifaces[0].is_mdns_capable());
+        assert_eq!(
+            interface_of(&ifaces, &"192.168.1.20".parse().unwrap()),
+            Some(&ifaces[0])
+        );
+
+        let tests = vec![
+            ("192.168.1.20", true),
+            ("::ffff:192.168.1.20", true),
+            ("2001:db8::20", true),
+            ("169.254.1.1", true),
+            ("fe80::1", true),
+            ("127.0.0.1", true),
+            ("::1", true),
+            ("192.168.2.20", false),
+            ("::ffff:10.0.0.1", false),
+            ("2001:db8:1::20", false),
+        ];
+        for (addr, expected) in tests {
+            assert_eq!(
+                is_on_link(&ifaces, &addr.parse().unwrap()),
+                expected,
+                "{}",
+                addr
+            );
+        }
+    }
+}
@@ -0,0 +1,54 @@
+// Copyright (C) 2024 Satoshi Konno All rights reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//    http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use unicode_normalization::UnicodeNormalization;
+
+/// NameComparison represents how the names are compared by the probing and conflict logic.
+/// RFC 6763: 4.1.3. Instance Names
+/// The instance names SHOULD be in Normalization Form C, but some hosts announce the decomposed form (NFD),
+/// so the same name may arrive as different byte sequences.
+#[derive(Debug, Copy, Clone, PartialEq, Default)]
+pub enum NameComparison {
+    /// Normalized compares the names in Normalization Form C ignoring case.
+    #[default]
+    Normalized,
+    /// StrictBytes compares the bytes of the names ignoring only the ASCII case, as RFC 1035 does.
+    StrictBytes,
+}
+
+impl NameComparison {
+    /// key returns the comparison key of the specified name, which is equal for the names regarded as the same.
+    pub fn key(&self, name: &str) -> String {
+        match self {
+            NameComparison::Normalized => name.nfc().collect::<String>().to_lowercase(),
+            NameComparison::StrictBytes => name.to_ascii_lowercase(),
+        }
+    }
+
+    /// eq returns true if the specified names are regarded as the same.
+    pub fn eq(&self, a: &str, b: &str) -> bool {
+        if a.eq_ignore_ascii_case(b) {
+            return true;
+        }
+        match self {
+            NameComparison::Normalized => self.key(a) == self.key(b),
+            NameComparison::StrictBytes => false,
+        }
+    }
+}
+
+/// to_nfc returns the specified name in Normalization Form C, which should be used for the announced names.
+pub fn to_nfc(name: &str) -> String {
+    name.nfc().collect()
+}
@@ -0,0 +1,99 @@
+// Copyright (C) 2024 Satoshi Konno All rights reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//    http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+#[cfg(test)]
+mod tests {
+
+    use std::time::Instant;
+
+    use crate::dns::Type;
+    use crate::fixture_test::{record, unique};
+    use crate::normalize::to_nfc;
+    use crate::{Cache, NameComparison, Provenance};
+
+    // "Café" in the composed form (NFC) and the decomposed form (NFD).
+    const CAFE_NFC: &str = "Caf\u{e9}._http._tcp.local";
+    const CAFE_NFD: &str = "Cafe\u{301}._http._tcp.local";
+
+    #[test]
+    fn name_comparison() {
+        struct Test {
+            a: &'static str,
+            b: &'static str,
+            normalized: bool,
+            strict: bool,
+        }
+
+        let tests = vec![
+            Test {
+                a: CAFE_NFC,
+                b: CAFE_NFD,
+                normalized: true,
+                strict: false,
+            },
+            Test {
+                a: "Test._http._tcp.local",
+                b: "test._HTTP._tcp.local",
+                normalized: true,
+                strict: true,
+            },
+            Test {
+                a: CAFE_NFC,
+                b: "cafe._http._tcp.local",
+                normalized: false,
+                strict: false,
+            },
+        ];
+        for test in tests {
+            assert_eq!(
+                NameComparison::Normalized.eq(test.a, test.b),
+                test.normalized,
+                "{} {}",
+                test.a,
+                test.b
+            );
+            assert_eq!(NameComparison::StrictBytes.eq(test.a, test.b), test.strict);
+        }
+        assert_eq!(to_nfc(CAFE_NFD), CAFE_NFC);
+    }
+
+    #[test]
+    fn name_comparison_conflict() {
+        let now = Instant::now();
+        let host1 = Provenance::new("192.168.1.10:5353".parse().unwrap());
+        let host2 = Provenance::new("192.168.1.20:5353".parse().unwrap());
+        let srv =
+            |name: &str, data: u8| unique(record(name, Type::SRV, vec![0, 0, 0, 0, 0, 80, data]));
+
+        let mut cache = Cache::new();
+        assert!(cache
+            .add_record_at(&srv(CAFE_NFC, 1), &host1, now)
+            .is_none());
+        assert!(cache
+            .add_record_at(&srv(CAFE_NFD, 2), &host2, now)
+            .is_some());
+        assert_eq!(cache.records_at(CAFE_NFD, now).len(), 1);
+        assert_eq!(cache.pending_conflicts().len(), 1);
+
+        let mut cache = Cache::new();
+        cache.set_name_comparison(NameComparison::StrictBytes);
+        assert!(cache
+            .add_record_at(&srv(CAFE_NFC, 1), &host1, now)
+            .is_none());
+        assert!(cache
+            .add_record_at(&srv(CAFE_NFD, 2), &host2, now)
+            .is_none());
+        assert_eq!(cache.records_at(CAFE_NFD, now).len(), 1);
+    }
+}
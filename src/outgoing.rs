@@ -0,0 +1,113 @@
+// Copyright (C) 2024 Satoshi Konno All rights reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//    http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::fmt;
+
+use crate::dns::{Message, Result};
+use crate::family::AddressFamily;
+use crate::transport::Route;
+
+/// OutgoingKind represents the role of a packet about to be transmitted.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum OutgoingKind {
+    /// Query is a query for the records of other hosts.
+    /// RFC 6762: 5. Querying
+    Query,
+    /// Probe is a query probing the uniqueness of the records to announce.
+    /// RFC 6762: 8.1. Probing
+    Probe,
+    /// Announcement is an unsolicited response announcing the records.
+    /// RFC 6762: 8.3. Announcing
+    Announcement,
+    /// Response is a response to a received query.
+    /// RFC 6762: 6. Responding
+    Response,
+}
+
+impl fmt::Display for OutgoingKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let kind = match self {
+            OutgoingKind::Query => "query",
+            OutgoingKind::Probe => "probe",
+            OutgoingKind::Announcement => "announcement",
+            OutgoingKind::Response => "response",
+        };
+        write!(f, "{}", kind)
+    }
+}
+
+/// OutgoingPacket represents a packet about to be transmitted, which the outgoing hook may inspect or rewrite.
+pub struct OutgoingPacket {
+    kind: OutgoingKind,
+    bytes: Vec<u8>,
+    route: Option<Route>,
+}
+
+impl OutgoingPacket {
+    /// new creates a new packet of the specified kind and bytes.
+    pub fn new(kind: OutgoingKind, bytes: Vec<u8>) -> OutgoingPacket {
+        OutgoingPacket {
+            kind,
+            bytes,
+            route: None,
+        }
+    }
+
+    /// kind returns the role of the packet.
+    pub fn kind(&self) -> OutgoingKind {
+        self.kind
+    }
+
+    /// family returns the address family the packet is multicast over, or None if it is sent over every family.
+    pub fn family(&self) -> Option<AddressFamily> {
+        self.route.as_ref().map(|route| route.family())
+    }
+
+    /// interface returns the interface the packet is multicast on, or None if it is sent on every interface.
+    pub fn interface(&self) -> Option<&str> {
+        self.route.as_ref().and_then(|route| route.interface())
+    }
+
+    pub(crate) fn set_route(&mut self, route: Option<Route>) {
+        self.route = route;
+    }
+
+    /// bytes returns the bytes to be transmitted.
+    pub fn bytes(&self) -> &[u8] {
+        &self.bytes
+    }
+
+    /// set_bytes replaces the bytes to be transmitted.
+    pub fn set_bytes(&mut self, bytes: Vec<u8>) {
+        self.bytes = bytes;
+    }
+
+    /// message parses the bytes to be transmitted.
+    pub fn message(&self) -> Result<Message> {
+        Message::from_bytes(&self.bytes)
+    }
+
+    /// set_message replaces the bytes to be transmitted with the specified message.
+    pub fn set_message(&mut self, msg: &Message) -> Result<()> {
+        self.bytes = msg.to_bytes()?;
+        Ok(())
+    }
+
+    pub(crate) fn into_bytes(self) -> Vec<u8> {
+        self.bytes
+    }
+}
+
+/// OutgoingHook is invoked with every packet about to be transmitted, and returns false to veto the transmission.
+pub type OutgoingHook = Box<dyn Fn(&mut OutgoingPacket) -> bool + Send>;
@@ -0,0 +1,198 @@
+// Copyright (C) 2024 Satoshi Konno All rights reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//    http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::additional;
+use crate::dns::{Message, Record, Type, QR};
+use crate::error::{Error, Result};
+
+/// DEFAULT_MAX_MESSAGE_SIZE is the default maximum size of a response, which fits in an Ethernet MTU with the IPv6 and UDP headers.
+/// RFC 6762: 17. Multicast DNS Message Size
+pub const DEFAULT_MAX_MESSAGE_SIZE: usize = 1500 - 40 - 8;
+
+/// MAX_MESSAGE_SIZE is the maximum size of a Multicast DNS message excluding the IPv6 and UDP headers.
+/// RFC 6762: 17. Multicast DNS Message Size
+/// Even when fragmentation is used, a Multicast DNS packet, including IP and UDP headers, MUST NOT exceed 9000 bytes.
+pub const MAX_MESSAGE_SIZE: usize = 9000 - 40 - 8;
+
+/// rrsets groups the specified records into the record sets of the same name, type and class in the order of their first records.
+/// A RRSIG record belongs to the record set of the type it covers, so the signature is never separated from the records.
+pub fn rrsets(records: &[Record]) -> Vec<Vec<Record>> {
+    let mut rrsets: Vec<Vec<Record>> = Vec::new();
+    for record in records {
+        let typ = match record.typ() {
+            Type::RRSIG if 2 <= record.data().len() => {
+                Type::from_value(u16::from_be_bytes([record.data()[0], record.data()[1]]))
+            }
+            typ => typ,
+        };
+        let rrset = rrsets.iter_mut().find(|rrset| {
+            let first = &rrset[0];
+            first.name().eq_ignore_ascii_case(record.name())
+                && first.typ() == typ
+                && first.class() == record.class()
+        });
+        match rrset {
+            Some(rrset) => rrset.push(record.clone()),
+            None => rrsets.push(vec![record.clone()]),
+        }
+    }
+    rrsets
+}
+
+/// pack_response returns the responses carrying the specified answers, each of which is no larger than the specified size.
+/// The answers are split only between the record sets, and a record set which alone exceeds the size is sent in its own response
+/// as the multicast responses have no truncation semantics (RFC 6762: 18.5. TC (Truncated) Bit).
+/// Each response carries the specified additional records related to its answers as far as they fit.
+pub fn pack_response(
+    answers: &[Record],
+    additionals: &[Record],
+    max_size: usize,
+) -> Result<Vec<Message>> {
+    let mut packets: Vec<Vec<Record>> = Vec::new();
+    let mut packet: Vec<Record> = Vec::new();
+    for rrset in rrsets(answers) {
+        let mut candidate = packet.clone();
+        candidate.extend(rrset.iter().cloned());
+        if packet.is_empty() || message_size(&candidate, &[])? <= max_size {
+            packet = candidate;
+            continue;
+        }
+        packets.push(packet);
+        packet = rrset;
+    }
+    if !packet.is_empty() {
+        packets.push(packet);
+    }
+
+    let mut responses = Vec::new();
+    for answers in packets {
+        let size = message_size(&answers, &[])?;
+        if MAX_MESSAGE_SIZE < size {
+            return Err(Error::from_string(&format!(
+                "Record set of {} too large for a response ({} bytes)",
+                answers[0].name(),
+                size
+            )));
+        }
+        if max_size < size {
+            log::warn!(
+                "record set of {} exceeds the response size ({} > {} bytes)",
+                answers[0].name(),
+                size,
+                max_size
+            );
+        }
+        let mut packed_additionals: Vec<Record> = Vec::new();
+        for rrset in rrsets(&additional::additional_records(&answers, additionals)) {
+            let mut candidate = packed_additionals.clone();
+            candidate.extend(rrset);
+            if message_size(&answers, &candidate)? <= max_size {
+                packed_additionals = candidate;
+            }
+        }
+        responses.push(response(&answers, &packed_additionals));
+    }
+    Ok(responses)
+}
+
+/// pack_query returns the queries carrying the questions and the known answers of the specified query,
+/// each of which is no larger than the specified size. The first query carries the questions, and the known answers
+/// which do not fit follow in the subsequent queries without questions.
+/// RFC 6762: 7.2. Multipacket Known-Answer Suppression
+/// If the Known-Answer list is too large to fit in a query packet, the querier sets the TC bit on every packet but the last,
+/// so the responders wait for the rest of the Known-Answer list before answering.
+pub fn pack_query(query: &Message, max_size: usize) -> Result<Vec<Message>> {
+    let mut queries = Vec::new();
+    let mut packet = query_packet(query, true);
+    for answer in query.answers() {
+        let mut candidate = packet.clone();
+        candidate.add_answer(answer.clone());
+        if message_bytes(&candidate)?.len() <= max_size {
+            packet = candidate;
+            continue;
+        }
+        // A known answer which alone exceeds the size is sent in its own packet.
+        queries.push(packet);
+        packet = query_packet(query, false);
+        packet.add_answer(answer.clone());
+    }
+    queries.push(packet);
+
+    let last = queries.len() - 1;
+    for (n, packet) in queries.iter_mut().enumerate() {
+        let size = message_bytes(packet)?.len();
+        if MAX_MESSAGE_SIZE < size {
+            return Err(Error::from_string(&format!(
+                "Query packet too large ({} bytes)",
+                size
+            )));
+        }
+        if max_size < size {
+            log::warn!(
+                "query packet exceeds the size ({} > {} bytes)",
+                size,
+                max_size
+            );
+        }
+        if n < last {
+            packet.set_tc(true);
+        }
+    }
+    Ok(queries)
+}
+
+// query_packet returns an empty query of the header of the specified query, which carries its questions and the other sections
+// if it is the first packet.
+fn query_packet(query: &Message, first: bool) -> Message {
+    let mut msg = Message::new();
+    msg.set_id(query.id());
+    msg.set_flags(query.flags());
+    if !first {
+        return msg;
+    }
+    for question in query.questions() {
+        msg.add_question(question.clone());
+    }
+    for authority in query.authorities() {
+        msg.add_authority(authority.clone());
+    }
+    for additional in query.additionals() {
+        msg.add_additional(additional.clone());
+    }
+    msg
+}
+
+fn message_bytes(msg: &Message) -> Result<Vec<u8>> {
+    msg.to_bytes().map_err(|e| Error::from_string(e.message()))
+}
+
+fn response(answers: &[Record], additionals: &[Record]) -> Message {
+    let mut msg = Message::new();
+    msg.set_qr(QR::Response);
+    msg.set_aa(true);
+    for answer in answers {
+        msg.add_answer(answer.clone());
+    }
+    for additional in additionals {
+        msg.add_additional(additional.clone());
+    }
+    msg
+}
+
+fn message_size(answers: &[Record], additionals: &[Record]) -> Result<usize> {
+    let bytes = response(answers, additionals)
+        .to_bytes()
+        .map_err(|e| Error::from_string(e.message()))?;
+    Ok(bytes.len())
+}
@@ -0,0 +1,136 @@
+// Copyright (C) 2024 Satoshi Konno All rights reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//    http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+#[cfg(test)]
+mod tests {
+
+    use crate::dns::{Message, Question, Record, Type, Writer};
+    use crate::fixture_test::{record, srv_data};
+    use crate::packing::{pack_query, pack_response, rrsets, DEFAULT_MAX_MESSAGE_SIZE};
+
+    fn txt(name: &str, size: usize) -> Record {
+        let mut data = Vec::new();
+        while data.len() < size {
+            let n = (size - data.len() - 1).min(255);
+            data.push(n as u8);
+            data.extend(std::iter::repeat_n(b'x', n));
+        }
+        record(name, Type::TXT, data)
+    }
+
+    #[test]
+    fn packing_rrsets() {
+        let records = vec![
+            record("host.local", Type::A, vec![192, 168, 1, 10]),
+            txt("a._http._tcp.local", 10),
+            record("HOST.local", Type::A, vec![192, 168, 1, 11]),
+            record("host.local", Type::RRSIG, vec![0, 1, 0, 0]),
+            record("host.local", Type::AAAA, vec![0; 16]),
+        ];
+        let sets = rrsets(&records);
+        assert_eq!(sets.len(), 3);
+        assert_eq!(sets[0].len(), 3);
+        assert_eq!(sets[0][2].typ(), Type::RRSIG);
+        assert_eq!(sets[1][0].typ(), Type::TXT);
+        assert_eq!(sets[2][0].typ(), Type::AAAA);
+    }
+
+    #[test]
+    fn packing_large_txt_records() {
+        let names = [
+            "a._http._tcp.local",
+            "b._http._tcp.local",
+            "c._http._tcp.local",
+        ];
+        let mut answers = Vec::new();
+        let mut known = Vec::new();
+        for name in names {
+            answers.push(txt(name, 700));
+            known.push(record(name, Type::SRV, srv_data(80, "host.local")));
+        }
+        // The addresses of a host are one RRSet which must not be split.
+        for n in 0..40 {
+            answers.push(record("host.local", Type::A, vec![10, 0, 0, n]));
+        }
+        known.push(record("host.local", Type::AAAA, vec![0; 16]));
+
+        let responses = pack_response(&answers, &known, DEFAULT_MAX_MESSAGE_SIZE).unwrap();
+        assert_eq!(responses.len(), 3);
+        let mut answered = 0;
+        for res in &responses {
+            assert!(res.is_response());
+            assert!(res.aa());
+            assert!(!res.tc());
+            assert!(res.to_bytes().unwrap().len() <= DEFAULT_MAX_MESSAGE_SIZE);
+            let addrs = res.answers().iter().filter(|r| r.typ() == Type::A).count();
+            assert!(addrs == 0 || addrs == 40);
+            answered += res.answers().len();
+        }
+        assert_eq!(answered, answers.len());
+        assert!(responses
+            .iter()
+            .all(|res| res.additionals().iter().all(|r| r.typ() != Type::TXT)));
+        assert!(responses[2]
+            .additionals()
+            .iter()
+            .any(|r| r.typ() == Type::AAAA));
+        assert!(responses[0].additionals().is_empty());
+
+        // A record set larger than the size is sent alone, but never beyond the Multicast DNS limit.
+        let responses = pack_response(
+            &[txt(names[0], 2000), txt(names[1], 100)],
+            &[],
+            DEFAULT_MAX_MESSAGE_SIZE,
+        )
+        .unwrap();
+        assert_eq!(responses.len(), 2);
+        assert_eq!(responses[0].answers().len(), 1);
+        assert!(pack_response(&[txt(names[0], 9000)], &[], DEFAULT_MAX_MESSAGE_SIZE).is_err());
+    }
+
+    #[test]
+    fn packing_known_answers() {
+        let mut query = Message::new();
+        query.add_question(Question::new("_http._tcp.local", Type::PTR));
+        let packets = pack_query(&query, 512).unwrap();
+        assert_eq!(packets.len(), 1);
+        assert!(!packets[0].tc());
+
+        for n in 0..40 {
+            let mut w = Writer::new();
+            assert!(w
+                .write_name(&format!("instance-{}._http._tcp.local", n))
+                .is_ok());
+            query.add_answer(record("_http._tcp.local", Type::PTR, w.to_bytes()));
+        }
+        let packets = pack_query(&query, 512).unwrap();
+        assert!(2 <= packets.len());
+        let last = packets.len() - 1;
+        let mut answers = 0;
+        for (n, packet) in packets.iter().enumerate() {
+            assert!(packet.is_query());
+            assert!(packet.to_bytes().unwrap().len() <= 512);
+            assert_eq!(packet.tc(), n < last);
+            assert_eq!(packet.qd_count(), if n == 0 { 1 } else { 0 });
+            answers += packet.an_count();
+        }
+        assert_eq!(answers, 40);
+
+        // A packet too large for any Multicast DNS message is an error.
+        let mut query = Message::new();
+        query.add_question(Question::new("a._http._tcp.local", Type::TXT));
+        query.add_answer(txt("a._http._tcp.local", 9000));
+        assert!(pack_query(&query, 512).is_err());
+    }
+}
@@ -0,0 +1,69 @@
+// Copyright (C) 2024 Satoshi Konno All rights reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//    http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::net::SocketAddr;
+use std::time::SystemTime;
+
+use crate::family::AddressFamily;
+
+/// Provenance represents where and when a message was received.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Provenance {
+    source: SocketAddr,
+    interface: Option<String>,
+    family: AddressFamily,
+    timestamp: SystemTime,
+}
+
+impl Provenance {
+    /// new creates a new provenance of the specified source address received now.
+    pub fn new(source: SocketAddr) -> Provenance {
+        Provenance::with_timestamp(source, SystemTime::now())
+    }
+
+    /// with_timestamp creates a new provenance of the specified source address received at the specified time.
+    pub fn with_timestamp(source: SocketAddr, timestamp: SystemTime) -> Provenance {
+        Provenance {
+            source,
+            interface: None,
+            family: AddressFamily::from_socketaddr(&source),
+            timestamp,
+        }
+    }
+
+    /// set_interface sets the name of the interface the message was received on.
+    pub fn set_interface(&mut self, interface: &str) {
+        self.interface = Some(interface.to_string());
+    }
+
+    /// source returns the address of the sender.
+    pub fn source(&self) -> SocketAddr {
+        self.source
+    }
+
+    /// interface returns the name of the interface the message was received on, or None if the transport does not report it.
+    pub fn interface(&self) -> Option<&str> {
+        self.interface.as_deref()
+    }
+
+    /// family returns the address family of the transport the message was received over.
+    pub fn family(&self) -> AddressFamily {
+        self.family
+    }
+
+    /// timestamp returns the time the message was received.
+    pub fn timestamp(&self) -> SystemTime {
+        self.timestamp
+    }
+}
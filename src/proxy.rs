@@ -0,0 +1,151 @@
+// Copyright (C) 2024 Satoshi Konno All rights reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//    http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::time::Instant;
+
+use crate::additional::additional_records;
+use crate::cache::Cache;
+use crate::dns::{Message, PTRRecord, Record, Result, SRVRecord, Type};
+
+/// DEFAULT_PROXY_MIN_TTL is the default floor of the TTLs of the proxied answers in seconds.
+pub const DEFAULT_PROXY_MIN_TTL: u32 = 1;
+/// DEFAULT_PROXY_MAX_TTL is the default cap of the TTLs of the proxied answers in seconds.
+/// RFC 8766: 5.5.1. DNS TTL Limiting
+pub const DEFAULT_PROXY_MAX_TTL: u32 = 10;
+
+/// ProxyAnswerBuilder represents a builder of the unicast DNS responses of a Discovery Proxy, which answers the questions
+/// of unicast DNS clients from the records cached by Multicast DNS.
+/// The TTLs of the answers are the remaining lifetimes of the cached records bounded by the floor and the cap,
+/// so an answer never outlives the record in the cache by more than the floor.
+pub struct ProxyAnswerBuilder {
+    min_ttl: u32,
+    max_ttl: u32,
+    authoritative: bool,
+}
+
+impl ProxyAnswerBuilder {
+    /// new creates a new builder of the default TTL bounds, whose answers are not authoritative.
+    pub fn new() -> ProxyAnswerBuilder {
+        ProxyAnswerBuilder {
+            min_ttl: DEFAULT_PROXY_MIN_TTL,
+            max_ttl: DEFAULT_PROXY_MAX_TTL,
+            authoritative: false,
+        }
+    }
+
+    /// set_ttl_bounds sets the floor and the cap of the TTLs of the answers in seconds.
+    pub fn set_ttl_bounds(&mut self, min_ttl: u32, max_ttl: u32) {
+        self.min_ttl = min_ttl.min(max_ttl);
+        self.max_ttl = max_ttl;
+    }
+
+    /// min_ttl returns the floor of the TTLs of the answers.
+    pub fn min_ttl(&self) -> u32 {
+        self.min_ttl
+    }
+
+    /// max_ttl returns the cap of the TTLs of the answers.
+    pub fn max_ttl(&self) -> u32 {
+        self.max_ttl
+    }
+
+    /// set_authoritative sets whether the answers are authoritative, such as for a zone delegated to the proxy.
+    /// The answers from the cache are not authoritative by default.
+    pub fn set_authoritative(&mut self, authoritative: bool) {
+        self.authoritative = authoritative;
+    }
+
+    /// is_authoritative returns true if the answers are authoritative.
+    pub fn is_authoritative(&self) -> bool {
+        self.authoritative
+    }
+
+    /// answer returns the response to the specified unicast query from the records in the specified cache.
+    pub fn answer(&self, query: &Message, cache: &Cache) -> Result<Message> {
+        self.answer_at(query, cache, Instant::now())
+    }
+
+    /// answer_at returns the response to the specified unicast query from the records in the specified cache at the specified time.
+    /// The response echoes the identifier, the questions and the recursion desired bit of the query, and the records carry
+    /// no cache-flush bit since it has no meaning in unicast DNS.
+    pub fn answer_at(&self, query: &Message, cache: &Cache, now: Instant) -> Result<Message> {
+        let mut answers: Vec<Record> = Vec::new();
+        for question in query.questions() {
+            for entry in cache.records_at(question.name(), now) {
+                if entry.is_goodbye() || !question.matches(entry.record()) {
+                    continue;
+                }
+                answers.push(self.proxied_record(entry.record(), entry.remaining_ttl_at(now)));
+            }
+        }
+
+        // The additional records are looked up by the names the answers point to.
+        let mut names: Vec<String> = Vec::new();
+        for answer in &answers {
+            match answer.typ() {
+                Type::PTR => {
+                    if let Ok(ptr) = PTRRecord::from_record(answer) {
+                        names.push(ptr.domain_name().to_string());
+                    }
+                }
+                Type::A | Type::AAAA => names.push(answer.name().to_string()),
+                _ => {}
+            }
+        }
+        let mut known: Vec<Record> = Vec::new();
+        let mut n = 0;
+        while n < names.len() {
+            for entry in cache.records_at(&names[n], now) {
+                if entry.is_goodbye() {
+                    continue;
+                }
+                let record = self.proxied_record(entry.record(), entry.remaining_ttl_at(now));
+                if record.typ() == Type::SRV {
+                    if let Ok(srv) = SRVRecord::from_record(&record) {
+                        if !names.iter().any(|n| n.eq_ignore_ascii_case(srv.target())) {
+                            names.push(srv.target().to_string());
+                        }
+                    }
+                }
+                known.push(record);
+            }
+            n += 1;
+        }
+        let additionals = additional_records(&answers, &known);
+
+        Message::builder()
+            .response()
+            .authoritative(self.authoritative)
+            .id(query.id())
+            .recursion_desired(query.rd())
+            .questions(query.questions().iter().cloned())
+            .answers(answers)
+            .additionals(additionals)
+            .build()
+    }
+
+    // proxied_record returns the specified cached record with the bounded remaining TTL and without the cache-flush bit.
+    fn proxied_record(&self, record: &Record, remaining_ttl: u32) -> Record {
+        let mut record = record.clone();
+        record.set_ttl(remaining_ttl.clamp(self.min_ttl, self.max_ttl));
+        record.set_cache_flush(false);
+        record
+    }
+}
+
+impl Default for ProxyAnswerBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,81 @@
+// Copyright (C) 2024 Satoshi Konno All rights reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//    http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+#[cfg(test)]
+mod tests {
+
+    use std::time::{Duration, Instant};
+
+    use crate::dns::{ARecord, Message, PTRRecord, Question, SRVRecord, Type};
+    use crate::{Cache, Provenance, ProxyAnswerBuilder};
+
+    #[test]
+    fn proxy_answers() {
+        let provenance = Provenance::new("192.168.1.10:5353".parse().unwrap());
+        let now = Instant::now();
+        let mut cache = Cache::new();
+        let mut ptr = PTRRecord::new("_http._tcp.local", "web");
+        ptr.set_ttl(4500);
+        cache.add_record_at(&ptr.to_record().unwrap(), &provenance, now);
+        let mut srv = "0 0 80 host.local".parse::<SRVRecord>().unwrap();
+        srv.set_name("web._http._tcp.local");
+        let mut record = srv.to_record().unwrap();
+        record.set_ttl(120);
+        record.set_cache_flush(true);
+        cache.add_record_at(&record, &provenance, now);
+        let mut a = "192.168.1.10".parse::<ARecord>().unwrap();
+        a.set_name("host.local");
+        let mut record = a.to_record();
+        record.set_ttl(120);
+        record.set_cache_flush(true);
+        cache.add_record_at(&record, &provenance, now);
+
+        let mut query = Message::new();
+        query.set_id(0x1234);
+        query.set_rd(true);
+        query.add_question(Question::new("_http._tcp.local", Type::PTR));
+
+        let mut builder = ProxyAnswerBuilder::new();
+        builder.set_ttl_bounds(30, 600);
+        assert_eq!(builder.min_ttl(), 30);
+        assert_eq!(builder.max_ttl(), 600);
+        let later = now + Duration::from_secs(100);
+        let res = builder.answer_at(&query, &cache, later).unwrap();
+        assert!(res.is_response());
+        assert!(!res.aa());
+        assert!(res.rd());
+        assert_eq!(res.id(), 0x1234);
+        assert_eq!(res.qd_count(), 1);
+        assert_eq!(res.an_count(), 1);
+        // The cap bounds the long remaining TTL of the PTR record.
+        assert_eq!(res.answers()[0].ttl(), 600);
+        // The SRV record and the address of its target are the additional records, and the floor bounds their short remaining TTLs.
+        let additionals = res.additionals();
+        assert_eq!(additionals.len(), 2);
+        assert_eq!(additionals[0].typ(), Type::SRV);
+        assert_eq!(additionals[1].typ(), Type::A);
+        for record in additionals {
+            assert_eq!(record.ttl(), 30);
+            assert!(!record.cache_flush());
+        }
+
+        builder.set_authoritative(true);
+        assert!(builder.is_authoritative());
+        let res = builder
+            .answer_at(&query, &cache, now + Duration::from_secs(5000))
+            .unwrap();
+        assert!(res.aa());
+        assert_eq!(res.an_count(), 0);
+    }
+}
@@ -12,7 +12,13 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::fmt;
+
+use crate::hostname::LOCAL_DOMAIN;
+use crate::service_builder::SERVICE_TYPE_ENUMERATION;
+
 /// Query represents a DNS-SD query.
+#[derive(Debug, Clone, PartialEq)]
 pub struct Query {
     service: String,
     domain: String,
@@ -35,6 +41,20 @@ impl Query {
         }
     }
 
+    /// service_enumeration creates a new query of the service types in the local domain, whose answers are the PTR records
+    /// of the service types instead of the service instances.
+    /// RFC 6763: 9. Service Type Enumeration
+    pub fn service_enumeration() -> Query {
+        Query::with(SERVICE_TYPE_ENUMERATION, LOCAL_DOMAIN)
+    }
+
+    /// is_service_enumeration returns true if the query is a query of the service types.
+    pub fn is_service_enumeration(&self) -> bool {
+        self.service
+            .trim_end_matches('.')
+            .eq_ignore_ascii_case(SERVICE_TYPE_ENUMERATION)
+    }
+
     /// set_service sets the service of the query.
     pub fn set_service(&mut self, service: &str) {
         self.service = service.to_string();
@@ -54,9 +74,16 @@ impl Query {
     pub fn domain(&self) -> &str {
         &self.domain
     }
+}
+
+impl Default for Query {
+    fn default() -> Self {
+        Self::new()
+    }
+}
 
-    /// to_string returns the string representation of the query.
-    pub fn to_string(&self) -> String {
-        format!("{}.{}", self.service, self.domain)
+impl fmt::Display for Query {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}.{}", self.service, self.domain)
     }
 }
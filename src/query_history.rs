@@ -0,0 +1,171 @@
+// Copyright (C) 2024 Satoshi Konno All rights reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//    http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::VecDeque;
+use std::fmt;
+use std::net::{IpAddr, SocketAddr};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::dns::{Message, Type};
+
+/// DEFAULT_QUERY_HISTORY_CAPACITY is the default number of the questions kept in a query history.
+pub const DEFAULT_QUERY_HISTORY_CAPACITY: usize = 1000;
+
+/// ObservedQuestion represents a question asked on the network, with who asked it and when.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ObservedQuestion {
+    source: SocketAddr,
+    name: String,
+    typ: Type,
+    unicast_response: bool,
+    known_answers: usize,
+    timestamp: SystemTime,
+}
+
+impl ObservedQuestion {
+    /// source returns the address of the querier.
+    pub fn source(&self) -> SocketAddr {
+        self.source
+    }
+
+    /// name returns the name of the question.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// typ returns the type of the question.
+    pub fn typ(&self) -> Type {
+        self.typ
+    }
+
+    /// unicast_response returns true if the querier asked for a unicast response.
+    pub fn unicast_response(&self) -> bool {
+        self.unicast_response
+    }
+
+    /// known_answers returns the number of the known answers the query carried.
+    pub fn known_answers(&self) -> usize {
+        self.known_answers
+    }
+
+    /// timestamp returns when the question was received.
+    pub fn timestamp(&self) -> SystemTime {
+        self.timestamp
+    }
+}
+
+impl fmt::Display for ObservedQuestion {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let since_epoch = self
+            .timestamp
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default();
+        write!(
+            f,
+            "{}.{:06} {} {} {:?} {}",
+            since_epoch.as_secs(),
+            since_epoch.subsec_micros(),
+            self.source,
+            if self.unicast_response { "QU" } else { "QM" },
+            self.typ,
+            self.name
+        )
+    }
+}
+
+/// QueryHistory represents a bounded history of the questions seen on the network, where the oldest questions are dropped first,
+/// such as to audit what other devices are probing for.
+pub struct QueryHistory {
+    capacity: usize,
+    questions: VecDeque<ObservedQuestion>,
+}
+
+impl QueryHistory {
+    /// new creates an empty history of the default capacity.
+    pub fn new() -> QueryHistory {
+        QueryHistory::with_capacity(DEFAULT_QUERY_HISTORY_CAPACITY)
+    }
+
+    /// with_capacity creates an empty history keeping at most the specified number of questions.
+    pub fn with_capacity(capacity: usize) -> QueryHistory {
+        QueryHistory {
+            capacity,
+            questions: VecDeque::new(),
+        }
+    }
+
+    /// capacity returns the maximum number of the kept questions.
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// len returns the number of the kept questions.
+    pub fn len(&self) -> usize {
+        self.questions.len()
+    }
+
+    /// is_empty returns true if no question is kept.
+    pub fn is_empty(&self) -> bool {
+        self.questions.is_empty()
+    }
+
+    /// add_query adds the questions of the specified query received from the specified address now.
+    pub fn add_query(&mut self, msg: &Message, source: SocketAddr) {
+        self.add_query_at(msg, source, SystemTime::now());
+    }
+
+    /// add_query_at adds the questions of the specified query received from the specified address at the specified time.
+    pub fn add_query_at(&mut self, msg: &Message, source: SocketAddr, timestamp: SystemTime) {
+        if self.capacity == 0 {
+            return;
+        }
+        for question in msg.questions() {
+            while self.capacity <= self.questions.len() {
+                self.questions.pop_front();
+            }
+            self.questions.push_back(ObservedQuestion {
+                source,
+                name: question.name().to_string(),
+                typ: question.typ(),
+                unicast_response: question.unicast_response(),
+                known_answers: msg.answers().len(),
+                timestamp,
+            });
+        }
+    }
+
+    /// questions returns the kept questions from the oldest.
+    pub fn questions(&self) -> Vec<&ObservedQuestion> {
+        self.questions.iter().collect()
+    }
+
+    /// questions_from returns the kept questions asked by the specified host from the oldest.
+    pub fn questions_from(&self, addr: &IpAddr) -> Vec<&ObservedQuestion> {
+        self.questions
+            .iter()
+            .filter(|q| q.source.ip() == *addr)
+            .collect()
+    }
+
+    /// clear drops all kept questions.
+    pub fn clear(&mut self) {
+        self.questions.clear();
+    }
+}
+
+impl Default for QueryHistory {
+    fn default() -> Self {
+        Self::new()
+    }
+}
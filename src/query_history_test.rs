@@ -0,0 +1,130 @@
+// Copyright (C) 2024 Satoshi Konno All rights reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//    http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+#[cfg(test)]
+mod tests {
+
+    use std::net::{IpAddr, SocketAddr};
+    use std::time::{Duration, SystemTime};
+
+    use cybergarage::net::{Observer, Packet};
+
+    use crate::dns::{Message, Question, Type};
+    use crate::{Discoverer, QueryHistory};
+
+    fn query(names: &[&str], unicast_response: bool) -> Message {
+        let mut msg = Message::new();
+        for name in names {
+            let mut question = Question::new(name, Type::PTR);
+            question.set_unicast_response(unicast_response);
+            msg.add_question(question);
+        }
+        msg
+    }
+
+    #[test]
+    fn query_history() {
+        let a: SocketAddr = "192.168.1.10:5353".parse().unwrap();
+        let b: SocketAddr = "192.168.1.20:5353".parse().unwrap();
+        let t0 = SystemTime::UNIX_EPOCH + Duration::from_secs(1);
+
+        let mut history = QueryHistory::with_capacity(3);
+        assert!(history.is_empty());
+        history.add_query_at(&query(&["_http._tcp.local"], true), a, t0);
+        history.add_query_at(
+            &query(&["_ipp._tcp.local", "_printer._tcp.local"], false),
+            b,
+            t0 + Duration::from_secs(1),
+        );
+        assert_eq!(history.len(), 3);
+
+        let questions = history.questions();
+        assert_eq!(questions[0].source(), a);
+        assert_eq!(questions[0].name(), "_http._tcp.local");
+        assert_eq!(questions[0].typ(), Type::PTR);
+        assert!(questions[0].unicast_response());
+        assert_eq!(questions[0].timestamp(), t0);
+        assert!(!questions[1].unicast_response());
+        assert_eq!(history.questions_from(&a.ip()).len(), 1);
+        assert_eq!(history.questions_from(&b.ip()).len(), 2);
+
+        // The oldest questions are dropped first.
+        history.add_query_at(&query(&["_smb._tcp.local"], false), a, t0);
+        assert_eq!(history.len(), 3);
+        assert_eq!(history.questions()[0].name(), "_ipp._tcp.local");
+        assert_eq!(history.questions()[2].name(), "_smb._tcp.local");
+
+        let unknown: IpAddr = "192.168.1.30".parse().unwrap();
+        assert!(history.questions_from(&unknown).is_empty());
+        history.clear();
+        assert!(history.is_empty());
+
+        let mut history = QueryHistory::with_capacity(0);
+        history.add_query_at(&query(&["_http._tcp.local"], false), a, t0);
+        assert!(history.is_empty());
+    }
+
+    #[test]
+    fn discoverer_query_history() {
+        let discoverer = Discoverer::new();
+        assert!(discoverer.lock().unwrap().query_history().is_none());
+        discoverer
+            .lock()
+            .unwrap()
+            .set_query_history(QueryHistory::new());
+
+        let mut pkt = Packet::from_bytes(&query(&["_http._tcp.local"], false).to_bytes().unwrap());
+        pkt.set_from("192.168.1.10:5353".parse().unwrap());
+        discoverer.lock().unwrap().packet_received(&pkt);
+
+        let d = discoverer.lock().unwrap();
+        let questions = d.query_history().unwrap().questions();
+        assert_eq!(questions.len(), 1);
+        assert_eq!(questions[0].name(), "_http._tcp.local");
+        assert_eq!(
+            questions[0].source(),
+            "192.168.1.10:5353".parse::<SocketAddr>().unwrap()
+        );
+    }
+
+    #[test]
+    fn discoverer_query_history_askers() {
+        let discoverer = Discoverer::new();
+        discoverer
+            .lock()
+            .unwrap()
+            .set_query_history(QueryHistory::new());
+
+        // The identical queries of the different hosts are not suppressed as the duplicates,
+        // and the repeated one is kept in the history before it is suppressed.
+        let mut pkt = Packet::from_bytes(&query(&["_http._tcp.local"], false).to_bytes().unwrap());
+        for from in [
+            "192.168.1.10:5353",
+            "192.168.1.20:5353",
+            "192.168.1.10:5353",
+        ] {
+            pkt.set_from(from.parse().unwrap());
+            discoverer.lock().unwrap().packet_received(&pkt);
+        }
+
+        let d = discoverer.lock().unwrap();
+        let questions = d.query_history().unwrap().questions();
+        assert_eq!(questions.len(), 3);
+        assert_eq!(
+            questions[1].source(),
+            "192.168.1.20:5353".parse::<SocketAddr>().unwrap()
+        );
+        assert_eq!(d.stats().duplicates_suppressed(), 1);
+    }
+}
@@ -0,0 +1,53 @@
+// Copyright (C) 2024 Satoshi Konno All rights reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//    http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::time::Duration;
+
+use crate::rate_limit::MIN_QUERY_INTERVAL;
+
+/// LOW_POWER_QUERY_INTERVAL is the minimum interval between the same queries of the low-power pacing.
+pub const LOW_POWER_QUERY_INTERVAL: Duration = Duration::from_secs(60);
+
+/// QueryPacing represents how often the queries of a browse are sent,
+/// such as the low-power pacing for devices where every radio wakeup is costly.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+pub enum QueryPacing {
+    /// Standard sends every search at once, and queries the cached records again before they expire.
+    #[default]
+    Standard,
+    /// LowPower sends the same query at most once per LOW_POWER_QUERY_INTERVAL, never queries the cached records again
+    /// before they expire, and batches the questions of the searches into a single query.
+    LowPower,
+}
+
+impl QueryPacing {
+    /// min_interval returns the minimum interval between the same queries.
+    pub fn min_interval(&self) -> Duration {
+        match self {
+            QueryPacing::Standard => MIN_QUERY_INTERVAL,
+            QueryPacing::LowPower => LOW_POWER_QUERY_INTERVAL,
+        }
+    }
+
+    /// refreshes_cache returns true if the cached records are queried again before they expire.
+    /// RFC 6762: 5.2. Continuous Multicast DNS Querying
+    pub fn refreshes_cache(&self) -> bool {
+        *self == QueryPacing::Standard
+    }
+
+    /// batches_questions returns true if the questions of the searches are sent in a single query.
+    pub fn batches_questions(&self) -> bool {
+        *self == QueryPacing::LowPower
+    }
+}
@@ -0,0 +1,263 @@
+// Copyright (C) 2024 Satoshi Konno All rights reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//    http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+use crate::dns::Message;
+
+/// MIN_QUERY_INTERVAL is the minimum interval between the same queries.
+/// RFC 6762: 5.2. Continuous Multicast DNS Querying
+/// The interval between the first two queries MUST be at least one second.
+pub const MIN_QUERY_INTERVAL: Duration = Duration::from_secs(1);
+/// MIN_RESPONSE_INTERVAL is the minimum interval between the same multicast responses.
+/// RFC 6762: 6. Responding
+/// A Multicast DNS responder MUST NOT multicast a given resource record on a given interface
+/// until at least one second has elapsed since the last time that resource record was multicast on that particular interface.
+pub const MIN_RESPONSE_INTERVAL: Duration = Duration::from_secs(1);
+/// DEFAULT_BURST is the default number of packets which can be sent at once.
+pub const DEFAULT_BURST: u32 = 20;
+/// DEFAULT_RATE is the default number of packets which can be sent per second.
+pub const DEFAULT_RATE: u32 = 10;
+/// DEFAULT_STORM_THRESHOLD is the default number of the same inbound questions within the storm window.
+pub const DEFAULT_STORM_THRESHOLD: u32 = 20;
+/// DEFAULT_STORM_WINDOW is the default window to count the same inbound questions.
+pub const DEFAULT_STORM_WINDOW: Duration = Duration::from_secs(1);
+/// DEFAULT_STORM_SUPPRESSION is the default time to ignore a storming question.
+pub const DEFAULT_STORM_SUPPRESSION: Duration = Duration::from_secs(10);
+
+/// TokenBucket represents a token bucket which limits the rate of sent packets.
+#[derive(Debug, Clone)]
+pub struct TokenBucket {
+    capacity: f64,
+    rate: f64,
+    tokens: f64,
+    last: Instant,
+}
+
+impl TokenBucket {
+    /// new creates a new full bucket of the specified capacity, which is refilled at the specified rate per second.
+    pub fn new(capacity: u32, rate: u32) -> TokenBucket {
+        TokenBucket {
+            capacity: capacity as f64,
+            rate: rate as f64,
+            tokens: capacity as f64,
+            last: Instant::now(),
+        }
+    }
+
+    /// try_acquire takes a token now, and returns false if the bucket is empty.
+    pub fn try_acquire(&mut self) -> bool {
+        self.try_acquire_at(Instant::now())
+    }
+
+    /// try_acquire_at takes a token at the specified time, and returns false if the bucket is empty.
+    pub fn try_acquire_at(&mut self, now: Instant) -> bool {
+        let elapsed = now.saturating_duration_since(self.last).as_secs_f64();
+        self.tokens = (self.tokens + elapsed * self.rate).min(self.capacity);
+        self.last = now;
+        if self.tokens < 1.0 {
+            return false;
+        }
+        self.tokens -= 1.0;
+        true
+    }
+}
+
+/// RateLimiter represents a limiter of the outgoing queries and responses,
+/// which combines a global token bucket with the per-record minimum intervals of RFC 6762.
+#[derive(Debug, Clone)]
+pub struct RateLimiter {
+    queries: TokenBucket,
+    responses: TokenBucket,
+    last_queries: HashMap<String, Instant>,
+    last_responses: HashMap<String, Instant>,
+}
+
+impl RateLimiter {
+    /// new creates a new limiter of the default rates.
+    pub fn new() -> RateLimiter {
+        RateLimiter::with_rate(DEFAULT_BURST, DEFAULT_RATE)
+    }
+
+    /// with_rate creates a new limiter of the specified burst and rate per second for each of the queries and responses.
+    pub fn with_rate(burst: u32, rate: u32) -> RateLimiter {
+        RateLimiter {
+            queries: TokenBucket::new(burst, rate),
+            responses: TokenBucket::new(burst, rate),
+            last_queries: HashMap::new(),
+            last_responses: HashMap::new(),
+        }
+    }
+
+    /// allow_query returns true if the query of the specified key can be sent now.
+    pub fn allow_query(&mut self, key: &str) -> bool {
+        self.allow_query_at(key, Instant::now())
+    }
+
+    /// allow_query_at returns true if the query of the specified key can be sent at the specified time.
+    pub fn allow_query_at(&mut self, key: &str, now: Instant) -> bool {
+        allow(
+            &mut self.queries,
+            &mut self.last_queries,
+            MIN_QUERY_INTERVAL,
+            key,
+            now,
+        )
+    }
+
+    /// allow_response returns true if the response of the specified key can be multicast now.
+    pub fn allow_response(&mut self, key: &str) -> bool {
+        self.allow_response_at(key, Instant::now())
+    }
+
+    /// allow_response_at returns true if the response of the specified key can be multicast at the specified time.
+    pub fn allow_response_at(&mut self, key: &str, now: Instant) -> bool {
+        allow(
+            &mut self.responses,
+            &mut self.last_responses,
+            MIN_RESPONSE_INTERVAL,
+            key,
+            now,
+        )
+    }
+}
+
+impl Default for RateLimiter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn allow(
+    bucket: &mut TokenBucket,
+    last_sent: &mut HashMap<String, Instant>,
+    min_interval: Duration,
+    key: &str,
+    now: Instant,
+) -> bool {
+    if let Some(last) = last_sent.get(key) {
+        if now.saturating_duration_since(*last) < min_interval {
+            return false;
+        }
+    }
+    if !bucket.try_acquire_at(now) {
+        return false;
+    }
+    last_sent.retain(|_, last| now.saturating_duration_since(*last) < min_interval);
+    last_sent.insert(key.to_string(), now);
+    true
+}
+
+/// StormDetector represents a detector of the inbound query storms,
+/// which suppresses a question temporarily when it is repeated excessively.
+#[derive(Debug, Clone)]
+pub struct StormDetector {
+    threshold: u32,
+    window: Duration,
+    suppression: Duration,
+    counts: HashMap<String, (Instant, u32)>,
+    suppressed: HashMap<String, Instant>,
+}
+
+impl StormDetector {
+    /// new creates a new detector of the default threshold.
+    pub fn new() -> StormDetector {
+        StormDetector::with_threshold(
+            DEFAULT_STORM_THRESHOLD,
+            DEFAULT_STORM_WINDOW,
+            DEFAULT_STORM_SUPPRESSION,
+        )
+    }
+
+    /// with_threshold creates a new detector which suppresses a question for the specified suppression
+    /// when it is received more than the specified threshold within the specified window.
+    pub fn with_threshold(
+        threshold: u32,
+        window: Duration,
+        suppression: Duration,
+    ) -> StormDetector {
+        StormDetector {
+            threshold,
+            window,
+            suppression,
+            counts: HashMap::new(),
+            suppressed: HashMap::new(),
+        }
+    }
+
+    /// observe records the question of the specified key received now, and returns false if it should be ignored.
+    pub fn observe(&mut self, key: &str) -> bool {
+        self.observe_at(key, Instant::now())
+    }
+
+    /// observe_at records the question of the specified key received at the specified time, and returns false if it should be ignored.
+    pub fn observe_at(&mut self, key: &str, now: Instant) -> bool {
+        if self.is_suppressed_at(key, now) {
+            return false;
+        }
+        // The expired suppressions of all questions are dropped, so distinct names can not grow the map.
+        self.suppressed.retain(|_, until| now < *until);
+        let window = self.window;
+        self.counts
+            .retain(|_, (start, _)| now.saturating_duration_since(*start) < window);
+        let (_, count) = self.counts.entry(key.to_string()).or_insert((now, 0));
+        *count += 1;
+        if *count <= self.threshold {
+            return true;
+        }
+        log::warn!(
+            "query storm detected ({}), suppressed for {:?}",
+            key,
+            self.suppression
+        );
+        self.counts.remove(key);
+        self.suppressed
+            .insert(key.to_string(), now + self.suppression);
+        false
+    }
+
+    /// suppressed_len returns the number of the suppressed questions, including the expired ones not dropped yet.
+    pub fn suppressed_len(&self) -> usize {
+        self.suppressed.len()
+    }
+
+    /// is_suppressed returns true if the question of the specified key is suppressed now.
+    pub fn is_suppressed(&self, key: &str) -> bool {
+        self.is_suppressed_at(key, Instant::now())
+    }
+
+    /// is_suppressed_at returns true if the question of the specified key is suppressed at the specified time.
+    pub fn is_suppressed_at(&self, key: &str, now: Instant) -> bool {
+        match self.suppressed.get(key) {
+            Some(until) => now < *until,
+            None => false,
+        }
+    }
+}
+
+impl Default for StormDetector {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// question_key returns the key of the questions in the specified message for the rate limiting and the storm detection.
+pub(crate) fn question_key(msg: &Message) -> String {
+    msg.questions()
+        .iter()
+        .map(|q| format!("{}/{}", q.name().to_lowercase(), q.typ()))
+        .collect::<Vec<String>>()
+        .join(",")
+}
@@ -0,0 +1,85 @@
+// Copyright (C) 2024 Satoshi Konno All rights reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//    http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+#[cfg(test)]
+mod tests {
+
+    use std::time::{Duration, Instant};
+
+    use crate::rate_limit::{RateLimiter, StormDetector, TokenBucket};
+
+    #[test]
+    fn token_bucket() {
+        let now = Instant::now();
+        let mut bucket = TokenBucket::new(2, 1);
+        assert!(bucket.try_acquire_at(now));
+        assert!(bucket.try_acquire_at(now));
+        assert!(!bucket.try_acquire_at(now));
+        assert!(!bucket.try_acquire_at(now + Duration::from_millis(500)));
+        assert!(bucket.try_acquire_at(now + Duration::from_millis(1000)));
+    }
+
+    #[test]
+    fn rate_limiter_min_interval() {
+        let now = Instant::now();
+        let mut limiter = RateLimiter::new();
+        assert!(limiter.allow_query_at("_http._tcp.local", now));
+        assert!(!limiter.allow_query_at("_http._tcp.local", now + Duration::from_millis(999)));
+        assert!(limiter.allow_query_at("_ipp._tcp.local", now));
+        assert!(limiter.allow_query_at("_http._tcp.local", now + Duration::from_secs(1)));
+        assert!(limiter.allow_response_at("_http._tcp.local", now));
+        assert!(!limiter.allow_response_at("_http._tcp.local", now));
+    }
+
+    #[test]
+    fn rate_limiter_burst() {
+        let now = Instant::now();
+        let mut limiter = RateLimiter::with_rate(3, 1);
+        for n in 0..3 {
+            assert!(limiter.allow_query_at(&format!("q{}", n), now));
+        }
+        assert!(!limiter.allow_query_at("q3", now));
+    }
+
+    #[test]
+    fn storm_detector() {
+        let now = Instant::now();
+        let mut detector =
+            StormDetector::with_threshold(3, Duration::from_secs(1), Duration::from_secs(10));
+        for _ in 0..3 {
+            assert!(detector.observe_at("_http._tcp.local", now));
+        }
+        assert!(!detector.observe_at("_http._tcp.local", now));
+        assert!(detector.is_suppressed_at("_http._tcp.local", now + Duration::from_secs(9)));
+        assert!(detector.observe_at("_ipp._tcp.local", now));
+        assert!(detector.observe_at("_http._tcp.local", now + Duration::from_secs(10)));
+    }
+
+    #[test]
+    fn storm_detector_expired_suppressions() {
+        let now = Instant::now();
+        let mut detector =
+            StormDetector::with_threshold(1, Duration::from_secs(1), Duration::from_secs(10));
+        for n in 0..100 {
+            let key = format!("q{}.local", n);
+            assert!(detector.observe_at(&key, now));
+            assert!(!detector.observe_at(&key, now));
+        }
+        assert_eq!(detector.suppressed_len(), 100);
+
+        // Observing any question drops the expired suppressions of the others.
+        assert!(detector.observe_at("other.local", now + Duration::from_secs(10)));
+        assert_eq!(detector.suppressed_len(), 0);
+    }
+}
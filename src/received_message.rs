@@ -0,0 +1,135 @@
+// Copyright (C) 2024 Satoshi Konno All rights reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//    http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::net::SocketAddr;
+use std::time::SystemTime;
+
+use cybergarage::net::Packet;
+
+use crate::dns::{Message, Record, Result};
+use crate::family::AddressFamily;
+use crate::provenance::Provenance;
+
+/// ReceivedMessage represents a message parsed from a received packet with where and when it was received,
+/// so the provenance is not lost once the bytes are parsed.
+#[derive(Clone)]
+pub struct ReceivedMessage {
+    message: Message,
+    provenance: Provenance,
+}
+
+impl ReceivedMessage {
+    /// new creates a new received message of the specified message and provenance.
+    pub fn new(message: Message, provenance: Provenance) -> ReceivedMessage {
+        ReceivedMessage {
+            message,
+            provenance,
+        }
+    }
+
+    /// from_packet parses the specified packet received now on the specified interface, if the transport reports it.
+    pub fn from_packet(pkt: &Packet, interface: Option<&str>) -> Result<ReceivedMessage> {
+        let message = Message::from_bytes(pkt.bytes())?;
+        let mut provenance = Provenance::new(pkt.from());
+        if let Some(interface) = interface {
+            provenance.set_interface(interface);
+        }
+        Ok(ReceivedMessage::new(message, provenance))
+    }
+
+    /// message returns the parsed message.
+    pub fn message(&self) -> &Message {
+        &self.message
+    }
+
+    /// message_mut returns the parsed message to be sanitized, such as by a TrustPolicy.
+    pub fn message_mut(&mut self) -> &mut Message {
+        &mut self.message
+    }
+
+    /// provenance returns where and when the message was received.
+    pub fn provenance(&self) -> &Provenance {
+        &self.provenance
+    }
+
+    /// source returns the address of the sender.
+    pub fn source(&self) -> SocketAddr {
+        self.provenance.source()
+    }
+
+    /// interface returns the name of the interface the message was received on, if known.
+    pub fn interface(&self) -> Option<&str> {
+        self.provenance.interface()
+    }
+
+    /// family returns the address family of the transport the message was received over.
+    pub fn family(&self) -> AddressFamily {
+        self.provenance.family()
+    }
+
+    /// timestamp returns the time the message was received.
+    pub fn timestamp(&self) -> SystemTime {
+        self.provenance.timestamp()
+    }
+
+    /// answers returns the answer records, each with the provenance of the message.
+    pub fn answers(&self) -> impl Iterator<Item = ReceivedRecord<'_>> {
+        self.with_provenance(self.message.answers())
+    }
+
+    /// records returns the answer and additional records, each with the provenance of the message.
+    pub fn records(&self) -> impl Iterator<Item = ReceivedRecord<'_>> {
+        self.answers()
+            .chain(self.with_provenance(self.message.additionals()))
+    }
+
+    /// into_message returns the parsed message, dropping the provenance.
+    pub fn into_message(self) -> Message {
+        self.message
+    }
+
+    fn with_provenance<'a>(
+        &'a self,
+        records: &'a [Record],
+    ) -> impl Iterator<Item = ReceivedRecord<'a>> {
+        records.iter().map(move |record| ReceivedRecord {
+            record,
+            provenance: &self.provenance,
+        })
+    }
+}
+
+/// ReceivedRecord represents a record of a received message with the provenance of the message.
+#[derive(Clone, Copy)]
+pub struct ReceivedRecord<'a> {
+    record: &'a Record,
+    provenance: &'a Provenance,
+}
+
+impl<'a> ReceivedRecord<'a> {
+    /// record returns the record.
+    pub fn record(&self) -> &'a Record {
+        self.record
+    }
+
+    /// provenance returns where and when the record was received.
+    pub fn provenance(&self) -> &'a Provenance {
+        self.provenance
+    }
+
+    /// source returns the address of the sender of the record.
+    pub fn source(&self) -> SocketAddr {
+        self.provenance.source()
+    }
+}
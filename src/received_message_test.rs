@@ -0,0 +1,65 @@
+// Copyright (C) 2024 Satoshi Konno All rights reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//    http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+#[cfg(test)]
+mod tests {
+
+    use std::time::{Duration, UNIX_EPOCH};
+
+    use cybergarage::net::Packet;
+
+    use crate::dns::Message;
+    use crate::fixture_test::srv_record;
+    use crate::{AddressFamily, Provenance, ReceivedMessage};
+
+    #[test]
+    fn received_message_from_packet() {
+        let mut msg = Message::new();
+        msg.add_answer(srv_record("a._http._tcp.local", 80, "host.local"));
+        msg.add_additional(srv_record("b._http._tcp.local", 80, "host.local"));
+        let mut pkt = Packet::from_bytes(&msg.to_bytes().unwrap());
+        pkt.set_from("192.168.1.10:5353".parse().unwrap());
+
+        let received = ReceivedMessage::from_packet(&pkt, Some("eth0")).unwrap();
+        assert_eq!(received.source(), "192.168.1.10:5353".parse().unwrap());
+        assert_eq!(received.interface(), Some("eth0"));
+        assert_eq!(received.family(), AddressFamily::IPv4);
+        assert_eq!(received.message().answers().len(), 1);
+
+        let answers: Vec<_> = received.answers().map(|r| r.record().name()).collect();
+        assert_eq!(answers, vec!["a._http._tcp.local"]);
+        let records: Vec<_> = received.records().collect();
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[1].record().name(), "b._http._tcp.local");
+        for record in records {
+            assert_eq!(record.source(), received.source());
+            assert_eq!(record.provenance().interface(), Some("eth0"));
+        }
+
+        let pkt = Packet::from_bytes(&vec![0x00, 0x01]);
+        assert!(ReceivedMessage::from_packet(&pkt, None).is_err());
+    }
+
+    #[test]
+    fn received_message_provenance() {
+        let timestamp = UNIX_EPOCH + Duration::from_secs(1000);
+        let provenance = Provenance::with_timestamp("[fe80::1]:5353".parse().unwrap(), timestamp);
+        let received = ReceivedMessage::new(Message::new(), provenance);
+        assert_eq!(received.interface(), None);
+        assert_eq!(received.family(), AddressFamily::IPv6);
+        assert_eq!(received.timestamp(), timestamp);
+        assert_eq!(received.records().count(), 0);
+        assert!(received.into_message().answers().is_empty());
+    }
+}
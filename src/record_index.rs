@@ -0,0 +1,140 @@
+// Copyright (C) 2024 Satoshi Konno All rights reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//    http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::HashMap;
+
+use crate::dns::{Question, Record, Type};
+use crate::normalize::NameComparison;
+
+/// RegistrationId identifies the records registered together, such as the records of a service.
+pub type RegistrationId = u64;
+
+/// RecordIndex indexes the registered records by their names, so the records answering a question are found
+/// without scanning all registrations.
+pub struct RecordIndex {
+    records: HashMap<String, Vec<(RegistrationId, Record)>>,
+    names: HashMap<RegistrationId, Vec<String>>,
+    name_comparison: NameComparison,
+}
+
+impl RecordIndex {
+    /// new creates an empty index.
+    pub fn new() -> RecordIndex {
+        RecordIndex {
+            records: HashMap::new(),
+            names: HashMap::new(),
+            name_comparison: NameComparison::default(),
+        }
+    }
+
+    /// set_name_comparison sets how the question names are matched, and indexes the registered records again.
+    pub fn set_name_comparison(&mut self, name_comparison: NameComparison) {
+        self.name_comparison = name_comparison;
+        let records: Vec<(RegistrationId, Record)> = self
+            .records
+            .drain()
+            .flat_map(|(_, records)| records)
+            .collect();
+        self.names.clear();
+        for (id, record) in records {
+            self.insert(id, record);
+        }
+    }
+
+    /// add indexes the specified records under the specified registration.
+    pub fn add(&mut self, id: RegistrationId, records: &[Record]) {
+        for record in records {
+            self.insert(id, record.clone());
+        }
+    }
+
+    fn insert(&mut self, id: RegistrationId, record: Record) {
+        let key = self.name_comparison.key(record.name());
+        let names = self.names.entry(id).or_default();
+        if !names.contains(&key) {
+            names.push(key.clone());
+        }
+        self.records.entry(key).or_default().push((id, record));
+    }
+
+    /// remove removes the records of the specified registration, and returns them.
+    pub fn remove(&mut self, id: RegistrationId) -> Vec<Record> {
+        let mut removed = Vec::new();
+        for key in self.names.remove(&id).unwrap_or_default() {
+            if let Some(entries) = self.records.get_mut(&key) {
+                let (matched, kept): (Vec<_>, Vec<_>) =
+                    entries.drain(..).partition(|(i, _)| *i == id);
+                *entries = kept;
+                removed.extend(matched.into_iter().map(|(_, record)| record));
+                if entries.is_empty() {
+                    self.records.remove(&key);
+                }
+            }
+        }
+        removed
+    }
+
+    /// contains returns true if any record is registered under the specified registration.
+    pub fn contains(&self, id: RegistrationId) -> bool {
+        self.names.contains_key(&id)
+    }
+
+    /// len returns the number of the indexed names.
+    pub fn len(&self) -> usize {
+        self.records.len()
+    }
+
+    /// is_empty returns true if no record is indexed.
+    pub fn is_empty(&self) -> bool {
+        self.records.is_empty()
+    }
+
+    /// records returns the registered records of the specified name.
+    pub fn records(&self, name: &str) -> Vec<&Record> {
+        match self.records.get(&self.name_comparison.key(name)) {
+            Some(entries) => entries.iter().map(|(_, record)| record).collect(),
+            None => Vec::new(),
+        }
+    }
+
+    /// answers returns the registered records answering the specified question,
+    /// where the identical records shared by several registrations such as the PTR records of a service type enumeration are answered once.
+    pub fn answers(&self, question: &Question) -> Vec<Record> {
+        let mut answers: Vec<Record> = Vec::new();
+        for record in self.records(question.name()) {
+            if question.typ() != Type::ANY && question.typ() != record.typ() {
+                continue;
+            }
+            if answers
+                .iter()
+                .any(|a| a.typ() == record.typ() && a.data() == record.data())
+            {
+                continue;
+            }
+            answers.push(record.clone());
+        }
+        answers
+    }
+
+    /// has_name returns true if any record of the specified name is registered, which makes the responder authoritative for the name.
+    pub fn has_name(&self, name: &str) -> bool {
+        self.records.contains_key(&self.name_comparison.key(name))
+    }
+}
+
+impl Default for RecordIndex {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,106 @@
+// Copyright (C) 2024 Satoshi Konno All rights reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//    http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+#[cfg(test)]
+mod tests {
+
+    use crate::dns::{PTRRecord, Question, SRVRecord, Type};
+    use crate::{RecordIndex, RecordTtls, ServiceBuilder};
+
+    #[test]
+    fn record_index_answers() {
+        let mut index = RecordIndex::new();
+        let ttls = RecordTtls::new();
+        for n in 0..300 {
+            let service_type = if n % 2 == 0 {
+                "_http._tcp"
+            } else {
+                "_ipp._tcp"
+            };
+            let service = ServiceBuilder::new(&format!("Service {}", n), service_type)
+                .port(8000 + n as u16)
+                .subtype("_printer")
+                .build()
+                .unwrap();
+            index.add(n, &service.records("host.local", &ttls));
+        }
+
+        struct Test {
+            name: &'static str,
+            typ: Type,
+            expected: usize,
+        }
+
+        let tests = vec![
+            Test {
+                name: "_http._tcp.local",
+                typ: Type::PTR,
+                expected: 150,
+            },
+            Test {
+                name: "_PRINTER._sub._ipp._tcp.local",
+                typ: Type::PTR,
+                expected: 150,
+            },
+            Test {
+                name: "_services._dns-sd._udp.local",
+                typ: Type::PTR,
+                expected: 2,
+            },
+            Test {
+                name: "Service 7._ipp._tcp.local",
+                typ: Type::SRV,
+                expected: 1,
+            },
+            Test {
+                name: "service 7._ipp._tcp.local",
+                typ: Type::ANY,
+                expected: 2,
+            },
+            Test {
+                name: "Service 7._http._tcp.local",
+                typ: Type::ANY,
+                expected: 0,
+            },
+        ];
+        for test in &tests {
+            let answers = index.answers(&Question::new(test.name, test.typ));
+            assert_eq!(answers.len(), test.expected, "{} {}", test.name, test.typ);
+        }
+
+        let srv = &index.answers(&Question::new("Service 7._ipp._tcp.local", Type::SRV))[0];
+        assert!(srv.cache_flush());
+        let srv = SRVRecord::from_record(srv).unwrap();
+        assert_eq!(srv.port(), 8007);
+        assert_eq!(srv.target(), "host.local");
+        let ptr = &index.answers(&Question::new("_services._dns-sd._udp.local", Type::PTR))[0];
+        assert!(!ptr.cache_flush());
+        assert_eq!(ptr.ttl(), 4500);
+        assert_eq!(
+            PTRRecord::from_record(ptr).unwrap().domain_name(),
+            "_http._tcp.local"
+        );
+
+        assert_eq!(index.remove(7).len(), 5);
+        assert!(!index.contains(7));
+        assert!(!index.has_name("Service 7._ipp._tcp.local"));
+        assert_eq!(
+            index
+                .answers(&Question::new("_ipp._tcp.local", Type::PTR))
+                .len(),
+            149
+        );
+        assert!(index.remove(7).is_empty());
+    }
+}
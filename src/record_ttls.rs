@@ -0,0 +1,99 @@
+// Copyright (C) 2024 Satoshi Konno All rights reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//    http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+pub use crate::dns::ttl::{DEFAULT_HOST_RECORD_TTL, DEFAULT_OTHER_RECORD_TTL};
+use crate::dns::{Record, Type};
+
+/// RecordTtls represents the TTLs of the advertised records.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RecordTtls {
+    host_ttl: u32,
+    other_ttl: u32,
+    overrides: Vec<(Type, u32)>,
+}
+
+impl RecordTtls {
+    /// new creates the TTLs of the RFC 6762 defaults.
+    pub fn new() -> RecordTtls {
+        RecordTtls {
+            host_ttl: DEFAULT_HOST_RECORD_TTL,
+            other_ttl: DEFAULT_OTHER_RECORD_TTL,
+            overrides: Vec::new(),
+        }
+    }
+
+    /// set_host_ttl sets the TTL of the records bound to the host name.
+    pub fn set_host_ttl(&mut self, ttl: u32) -> &mut Self {
+        self.host_ttl = ttl;
+        self
+    }
+
+    /// host_ttl returns the TTL of the records bound to the host name.
+    pub fn host_ttl(&self) -> u32 {
+        self.host_ttl
+    }
+
+    /// set_other_ttl sets the TTL of the records not bound to the host name.
+    pub fn set_other_ttl(&mut self, ttl: u32) -> &mut Self {
+        self.other_ttl = ttl;
+        self
+    }
+
+    /// other_ttl returns the TTL of the records not bound to the host name.
+    pub fn other_ttl(&self) -> u32 {
+        self.other_ttl
+    }
+
+    /// set_ttl overrides the TTL of the records of the specified type.
+    pub fn set_ttl(&mut self, typ: Type, ttl: u32) -> &mut Self {
+        match self.overrides.iter_mut().find(|(t, _)| *t == typ) {
+            Some(entry) => entry.1 = ttl,
+            None => self.overrides.push((typ, ttl)),
+        }
+        self
+    }
+
+    /// clear_ttl removes the override of the specified type.
+    pub fn clear_ttl(&mut self, typ: Type) -> &mut Self {
+        self.overrides.retain(|(t, _)| *t != typ);
+        self
+    }
+
+    /// ttl returns the TTL of the records of the specified type.
+    pub fn ttl(&self, typ: Type) -> u32 {
+        if let Some((_, ttl)) = self.overrides.iter().find(|(t, _)| *t == typ) {
+            return *ttl;
+        }
+        if is_host_bound(typ) {
+            return self.host_ttl;
+        }
+        self.other_ttl
+    }
+
+    /// apply sets the TTL of the type to the specified record.
+    pub fn apply(&self, record: &mut Record) {
+        record.set_ttl(self.ttl(record.typ()));
+    }
+}
+
+impl Default for RecordTtls {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// is_host_bound returns true if the records of the specified type are bound to the host name, and become stale when the host changes.
+fn is_host_bound(typ: Type) -> bool {
+    matches!(typ, Type::A | Type::AAAA | Type::SRV | Type::TXT)
+}
@@ -0,0 +1,151 @@
+// Copyright (C) 2024 Satoshi Konno All rights reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//    http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::Arc;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use crate::advertiser::{Advertiser, RegistrationState};
+use crate::error::Result;
+use crate::hostname::Hostname;
+use crate::loopback::LoopbackFilter;
+use crate::name_conflict::ConflictCallback;
+use crate::outgoing::OutgoingHook;
+use crate::record_index::RegistrationId;
+use crate::responder_config::ResponderConfig;
+use crate::service_builder::ServiceInfo;
+use crate::shutdown::{ShutdownReport, DEFAULT_SHUTDOWN_TIMEOUT};
+use crate::stats::Stats;
+use crate::supervisor::TaskStatus;
+
+/// Responder represents a responder, which advertises the registered services and answers the questions for their records.
+pub struct Responder {
+    advertiser: Arc<Mutex<Advertiser>>,
+}
+
+impl Responder {
+    /// new creates a new responder of the system host name.
+    pub fn new() -> Responder {
+        Responder {
+            advertiser: Advertiser::new(),
+        }
+    }
+
+    /// with_config creates a new responder of the specified configuration.
+    pub fn with_config(config: ResponderConfig) -> Responder {
+        let responder = Responder::new();
+        responder.advertiser.lock().unwrap().set_config(config);
+        responder
+    }
+
+    /// set_config sets the configuration of the responses.
+    pub fn set_config(&mut self, config: ResponderConfig) {
+        self.advertiser.lock().unwrap().set_config(config);
+    }
+
+    /// set_hostname sets the host name which the services are advertised on.
+    pub fn set_hostname(&mut self, hostname: Hostname) {
+        self.advertiser.lock().unwrap().set_hostname(hostname);
+    }
+
+    /// hostname returns the current host name in the local domain.
+    pub fn hostname(&self) -> String {
+        self.advertiser.lock().unwrap().hostname()
+    }
+
+    /// register registers the specified service, which is probed and then announced.
+    pub fn register(&mut self, service: ServiceInfo) -> Result<RegistrationId> {
+        self.advertiser.lock().unwrap().register(service)
+    }
+
+    /// unregister sends the goodbye of the specified service.
+    pub fn unregister(&mut self, id: RegistrationId) -> Result<()> {
+        self.advertiser.lock().unwrap().unregister(id)
+    }
+
+    /// services returns the registered services, which may have been renamed on conflicts.
+    pub fn services(&self) -> Vec<ServiceInfo> {
+        self.advertiser.lock().unwrap().services()
+    }
+
+    /// state returns the state of the specified registration.
+    pub fn state(&self, id: RegistrationId) -> Option<RegistrationState> {
+        self.advertiser.lock().unwrap().state(id)
+    }
+
+    /// set_outgoing_hook sets the hook invoked with every packet about to be transmitted, which may rewrite or veto the packet.
+    pub fn set_outgoing_hook(&mut self, hook: OutgoingHook) {
+        self.advertiser.lock().unwrap().set_outgoing_hook(hook);
+    }
+
+    /// on_name_conflict registers the callback called when a registered service loses a name conflict,
+    /// which may veto the rename and deregister the service instead.
+    pub fn on_name_conflict(&mut self, callback: ConflictCallback) {
+        self.advertiser.lock().unwrap().on_name_conflict(callback);
+    }
+
+    /// set_loopback_filter sets the filter which the multicast transmissions are remembered in,
+    /// such as `Client::loopback_filter` so the client does not discover the services of this responder.
+    pub fn set_loopback_filter(&mut self, filter: Arc<Mutex<LoopbackFilter>>) {
+        self.advertiser.lock().unwrap().set_loopback_filter(filter);
+    }
+
+    /// set_on_link_check sets whether the packets from the sources off the directly attached networks are dropped.
+    pub fn set_on_link_check(&mut self, enabled: bool) {
+        self.advertiser.lock().unwrap().set_on_link_check(enabled);
+    }
+
+    /// stats returns the statistics of the responder.
+    pub fn stats(&self) -> Stats {
+        self.advertiser.lock().unwrap().stats().clone()
+    }
+
+    /// tasks returns the statuses of the background tasks of the responder.
+    pub fn tasks(&self) -> Vec<TaskStatus> {
+        self.advertiser.lock().unwrap().tasks()
+    }
+
+    /// start starts the responder.
+    pub fn start(&mut self) -> std::result::Result<(), std::io::Error> {
+        self.advertiser.lock().unwrap().start()
+    }
+
+    /// stop stops the responder without sending the goodbyes.
+    pub fn stop(&mut self) -> std::result::Result<(), std::io::Error> {
+        self.advertiser.lock().unwrap().stop()
+    }
+
+    /// shutdown sends the goodbyes of the announced services, stops the responder and reports what was cleaned up.
+    pub fn shutdown(
+        &mut self,
+        timeout: Duration,
+    ) -> std::result::Result<ShutdownReport, std::io::Error> {
+        let started = Instant::now();
+        let mut report = self.advertiser.lock().unwrap().shutdown(timeout)?;
+        report.set_elapsed(started.elapsed());
+        Ok(report)
+    }
+}
+
+impl Default for Responder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Drop for Responder {
+    fn drop(&mut self) {
+        let _ = self.shutdown(DEFAULT_SHUTDOWN_TIMEOUT);
+    }
+}
@@ -0,0 +1,304 @@
+// Copyright (C) 2024 Satoshi Konno All rights reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//    http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::HashMap;
+use std::net::IpAddr;
+
+use crate::additional;
+use crate::dns::{AAAARecord, ARecord, Message, Question, Record};
+use crate::error::{Error, Result};
+use crate::family::AddressFamily;
+use crate::packing::{self, DEFAULT_MAX_MESSAGE_SIZE};
+use crate::record_ttls::RecordTtls;
+use crate::response_strategy::{
+    DefaultResponseStrategy, QuestionContext, ResponseDecision, ResponseStrategy,
+};
+use crate::signer::{self, RecordSigner};
+use crate::txt_schema::TxtSchemas;
+use crate::validate::validate_multicast_response;
+
+/// ResponderConfig represents a configuration of a responder.
+pub struct ResponderConfig {
+    advertised_addrs: Vec<IpAddr>,
+    additional_records: bool,
+    strict: bool,
+    families: Vec<AddressFamily>,
+    txt_schemas: TxtSchemas,
+    record_ttls: RecordTtls,
+    response_strategy: Box<dyn ResponseStrategy>,
+    signer: Option<Box<dyn RecordSigner>>,
+    max_message_size: usize,
+}
+
+impl ResponderConfig {
+    /// new creates a new configuration which advertises the detected interface addresses.
+    pub fn new() -> ResponderConfig {
+        ResponderConfig {
+            advertised_addrs: Vec::new(),
+            additional_records: true,
+            strict: false,
+            families: vec![AddressFamily::IPv4, AddressFamily::IPv6],
+            txt_schemas: TxtSchemas::new(),
+            record_ttls: RecordTtls::new(),
+            response_strategy: Box::new(DefaultResponseStrategy::new()),
+            signer: None,
+            max_message_size: DEFAULT_MAX_MESSAGE_SIZE,
+        }
+    }
+
+    /// set_advertised_addrs sets the addresses advertised in the A/AAAA records instead of the detected interface addresses,
+    /// such as the host address of a container with port mappings or a virtual IP.
+    pub fn set_advertised_addrs(&mut self, addrs: &[IpAddr]) {
+        self.advertised_addrs = addrs.to_vec();
+    }
+
+    /// add_advertised_addr adds the specified address to the advertised addresses.
+    pub fn add_advertised_addr(&mut self, addr: IpAddr) {
+        if !self.advertised_addrs.contains(&addr) {
+            self.advertised_addrs.push(addr);
+        }
+    }
+
+    /// advertised_addrs returns the overridden advertised addresses.
+    pub fn advertised_addrs(&self) -> &Vec<IpAddr> {
+        &self.advertised_addrs
+    }
+
+    /// clear_advertised_addrs removes the override, and the detected interface addresses are advertised again.
+    pub fn clear_advertised_addrs(&mut self) {
+        self.advertised_addrs.clear();
+    }
+
+    /// has_advertised_addrs returns true if the advertised addresses are overridden.
+    pub fn has_advertised_addrs(&self) -> bool {
+        !self.advertised_addrs.is_empty()
+    }
+
+    /// addrs returns the addresses to advertise, which are the overridden addresses if any, or the specified detected addresses.
+    pub fn addrs(&self, detected_addrs: &[IpAddr]) -> Vec<IpAddr> {
+        if self.has_advertised_addrs() {
+            return self.advertised_addrs.clone();
+        }
+        detected_addrs.to_vec()
+    }
+
+    /// set_strict sets whether the responder refuses to send the responses which violate the section placement rules.
+    pub fn set_strict(&mut self, strict: bool) {
+        self.strict = strict;
+    }
+
+    /// is_strict returns true if the responder refuses to send the invalid responses.
+    pub fn is_strict(&self) -> bool {
+        self.strict
+    }
+
+    /// set_family_enabled sets whether the responder joins the multicast group of the specified family and answers over it,
+    /// which are both enabled by default.
+    pub fn set_family_enabled(&mut self, family: AddressFamily, enabled: bool) {
+        self.families.retain(|f| *f != family);
+        if enabled {
+            self.families.push(family);
+        }
+    }
+
+    /// is_family_enabled returns true if the responder answers over the specified family.
+    pub fn is_family_enabled(&self, family: AddressFamily) -> bool {
+        self.families.contains(&family)
+    }
+
+    /// families returns the families the responder answers over.
+    pub fn families(&self) -> &Vec<AddressFamily> {
+        &self.families
+    }
+
+    /// check_response returns an error listing the violations of the specified response in the strict mode,
+    /// and only logs them otherwise.
+    pub fn check_response(&self, msg: &Message) -> Result<()> {
+        let violations = validate_multicast_response(msg);
+        if violations.is_empty() {
+            return Ok(());
+        }
+        let violations = violations
+            .iter()
+            .map(|v| v.to_string())
+            .collect::<Vec<String>>()
+            .join(", ");
+        if !self.strict {
+            log::debug!("invalid response: {}", violations);
+            return Ok(());
+        }
+        Err(Error::from_string(&format!(
+            "Invalid response: {}",
+            violations
+        )))
+    }
+
+    /// set_response_strategy sets the strategy deciding how each received question is answered.
+    pub fn set_response_strategy(&mut self, strategy: Box<dyn ResponseStrategy>) {
+        self.response_strategy = strategy;
+    }
+
+    /// decide returns how the question of the specified context is answered by the response strategy.
+    pub fn decide(&self, ctx: &QuestionContext) -> ResponseDecision {
+        self.response_strategy.decide(ctx)
+    }
+
+    /// set_max_message_size sets the maximum size of a response, over which the answers are split across responses.
+    pub fn set_max_message_size(&mut self, size: usize) {
+        self.max_message_size = size;
+    }
+
+    /// max_message_size returns the maximum size of a response.
+    pub fn max_message_size(&self) -> usize {
+        self.max_message_size
+    }
+
+    /// pack_response returns the responses carrying the specified answers within the maximum size without splitting a record set,
+    /// each with the additional records of its answers selected from the specified known records if the additional section is enabled.
+    pub fn pack_response(
+        &self,
+        answers: &[Record],
+        known_records: &[Record],
+    ) -> Result<Vec<Message>> {
+        let known_records = if self.additional_records {
+            known_records
+        } else {
+            &[]
+        };
+        packing::pack_response(answers, known_records, self.max_message_size)
+    }
+
+    /// set_signer sets the signer which appends the RRSIG records to the answered record sets.
+    pub fn set_signer(&mut self, signer: Box<dyn RecordSigner>) {
+        self.signer = Some(signer);
+    }
+
+    /// clear_signer removes the signer, and the answers are sent unsigned again.
+    pub fn clear_signer(&mut self) {
+        self.signer = None;
+    }
+
+    /// has_signer returns true if the answers are signed.
+    pub fn has_signer(&self) -> bool {
+        self.signer.is_some()
+    }
+
+    /// sign_answers returns the specified answers followed by their RRSIG records, or the answers as is without a signer.
+    pub fn sign_answers(&self, answers: &[Record]) -> Result<Vec<Record>> {
+        match &self.signer {
+            Some(signer) => signer::sign_records(signer.as_ref(), answers),
+            None => Ok(answers.to_vec()),
+        }
+    }
+
+    /// dnskey_answers returns the DNSKEY records of the signer answering the specified question,
+    /// or no records without a signer.
+    pub fn dnskey_answers(&self, question: &Question) -> Vec<Record> {
+        match &self.signer {
+            Some(signer) => signer::dnskey_records(signer.as_ref(), question),
+            None => Vec::new(),
+        }
+    }
+
+    /// set_record_ttls sets the TTLs of the advertised records, which a registered service may override.
+    pub fn set_record_ttls(&mut self, ttls: RecordTtls) {
+        self.record_ttls = ttls;
+    }
+
+    /// record_ttls returns the TTLs of the advertised records.
+    pub fn record_ttls(&self) -> &RecordTtls {
+        &self.record_ttls
+    }
+
+    /// set_txt_schemas sets the TXT schemas which the attributes of the advertised services must satisfy.
+    pub fn set_txt_schemas(&mut self, schemas: TxtSchemas) {
+        self.txt_schemas = schemas;
+    }
+
+    /// txt_schemas returns the TXT schemas of the advertised services.
+    pub fn txt_schemas(&self) -> &TxtSchemas {
+        &self.txt_schemas
+    }
+
+    /// check_attributes returns an error listing the violations of the specified attributes of a service type to advertise.
+    pub fn check_attributes(
+        &self,
+        service_type: &str,
+        attrs: &HashMap<String, String>,
+    ) -> Result<()> {
+        let violations = self.txt_schemas.validate(service_type, attrs);
+        if violations.is_empty() {
+            return Ok(());
+        }
+        let violations = violations
+            .iter()
+            .map(|v| v.to_string())
+            .collect::<Vec<String>>()
+            .join(", ");
+        Err(Error::from_string(&format!(
+            "Invalid TXT attributes of {}: {}",
+            service_type, violations
+        )))
+    }
+
+    /// set_additional_records_enabled sets whether the additional section of the responses is populated,
+    /// which can be disabled for size-constrained links.
+    pub fn set_additional_records_enabled(&mut self, enabled: bool) {
+        self.additional_records = enabled;
+    }
+
+    /// additional_records_enabled returns true if the additional section of the responses is populated.
+    pub fn additional_records_enabled(&self) -> bool {
+        self.additional_records
+    }
+
+    /// additional_records returns the additional records of the specified answers selected from the specified known records,
+    /// or no records if the additional section is disabled.
+    pub fn additional_records(&self, answers: &[Record], known_records: &[Record]) -> Vec<Record> {
+        if !self.additional_records {
+            return Vec::new();
+        }
+        additional::additional_records(answers, known_records)
+    }
+
+    /// address_records returns the A/AAAA records of the specified host for the addresses to advertise,
+    /// which are unique records sent with the cache-flush bit.
+    pub fn address_records(&self, host: &str, ttl: u32, detected_addrs: &[IpAddr]) -> Vec<Record> {
+        self.addrs(detected_addrs)
+            .iter()
+            .map(|addr| {
+                let mut record = match addr {
+                    IpAddr::V4(v4) => {
+                        let mut a = ARecord::new(host, *v4);
+                        a.set_ttl(ttl);
+                        a.to_record()
+                    }
+                    IpAddr::V6(v6) => {
+                        let mut aaaa = AAAARecord::new(host, *v6);
+                        aaaa.set_ttl(ttl);
+                        aaaa.to_record()
+                    }
+                };
+                record.set_cache_flush(true);
+                record
+            })
+            .collect()
+    }
+}
+
+impl Default for ResponderConfig {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,167 @@
+// Copyright (C) 2024 Satoshi Konno All rights reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//    http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+#[cfg(test)]
+mod tests {
+
+    use std::net::IpAddr;
+    use std::sync::{Arc, Mutex};
+
+    use crate::dns::{AAAARecord, ARecord, Question, Record, Type};
+    use crate::{RecordSigner, RecordTtls, ResponderConfig, Result};
+
+    #[test]
+    fn responder_config_advertised_addrs() {
+        let detected: Vec<IpAddr> = vec!["172.17.0.2".parse().unwrap()];
+        let advertised: Vec<IpAddr> =
+            vec!["192.168.1.10".parse().unwrap(), "fd00::10".parse().unwrap()];
+
+        let mut config = ResponderConfig::new();
+        assert_eq!(config.addrs(&detected), detected);
+
+        config.set_advertised_addrs(&advertised);
+        assert_eq!(config.addrs(&detected), advertised);
+
+        let records = config.address_records("host.local", 120, &detected);
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[0].typ(), Type::A);
+        assert_eq!(records[0].name(), "host.local");
+        assert_eq!(records[0].ttl(), 120);
+        assert!(records.iter().all(|r| r.cache_flush()));
+        assert_eq!(
+            ARecord::from_record(&records[0]).unwrap().ipaddr(),
+            &advertised[0]
+        );
+        assert_eq!(records[1].typ(), Type::AAAA);
+        assert_eq!(
+            AAAARecord::from_record(&records[1]).unwrap().ipaddr(),
+            &advertised[1]
+        );
+
+        config.clear_advertised_addrs();
+        assert_eq!(config.addrs(&detected), detected);
+    }
+
+    #[test]
+    fn responder_config_record_ttls() {
+        struct Test {
+            typ: Type,
+            expected: u32,
+        }
+
+        let mut config = ResponderConfig::new();
+        assert_eq!(config.record_ttls().ttl(Type::SRV), 120);
+        assert_eq!(config.record_ttls().ttl(Type::PTR), 4500);
+
+        let mut ttls = RecordTtls::new();
+        ttls.set_host_ttl(60).set_ttl(Type::TXT, 4500);
+        config.set_record_ttls(ttls);
+
+        let tests = vec![
+            Test {
+                typ: Type::A,
+                expected: 60,
+            },
+            Test {
+                typ: Type::SRV,
+                expected: 60,
+            },
+            Test {
+                typ: Type::TXT,
+                expected: 4500,
+            },
+            Test {
+                typ: Type::PTR,
+                expected: 4500,
+            },
+        ];
+        for test in tests {
+            assert_eq!(
+                config.record_ttls().ttl(test.typ),
+                test.expected,
+                "{}",
+                test.typ
+            );
+        }
+
+        let mut record = Record::new();
+        record.set_typ(Type::AAAA);
+        config.record_ttls().apply(&mut record);
+        assert_eq!(record.ttl(), 60);
+    }
+
+    struct TestSigner {
+        signed: Arc<Mutex<Vec<usize>>>,
+    }
+
+    impl RecordSigner for TestSigner {
+        fn sign(&self, rrset: &[Record]) -> Result<Vec<Record>> {
+            self.signed.lock().unwrap().push(rrset.len());
+            let mut rrsig = Record::new();
+            rrsig.set_name(rrset[0].name());
+            rrsig.set_typ(Type::RRSIG);
+            rrsig.set_ttl(rrset[0].ttl());
+            rrsig.set_data(rrset[0].typ().to_value().to_be_bytes().to_vec());
+            Ok(vec![rrsig])
+        }
+
+        fn dnskeys(&self, name: &str) -> Vec<Record> {
+            let mut dnskey = Record::new();
+            dnskey.set_name(name);
+            dnskey.set_typ(Type::DNSKEY);
+            vec![dnskey]
+        }
+    }
+
+    #[test]
+    fn responder_config_signer() {
+        let detected: Vec<IpAddr> =
+            vec!["192.168.1.10".parse().unwrap(), "fd00::10".parse().unwrap()];
+        let mut config = ResponderConfig::new();
+        let mut answers = config.address_records("host.local", 120, &detected);
+        answers.extend(config.address_records(
+            "HOST.local",
+            120,
+            &["192.168.1.11".parse().unwrap()],
+        ));
+        assert_eq!(config.sign_answers(&answers).unwrap().len(), 3);
+        assert!(config
+            .dnskey_answers(&Question::new("local", Type::DNSKEY))
+            .is_empty());
+
+        let signed = Arc::new(Mutex::new(Vec::new()));
+        config.set_signer(Box::new(TestSigner {
+            signed: signed.clone(),
+        }));
+        let records = config.sign_answers(&answers).unwrap();
+        assert_eq!(records.len(), 5);
+        assert_eq!(records[3].typ(), Type::RRSIG);
+        assert_eq!(records[3].data(), Type::A.to_value().to_be_bytes());
+        assert_eq!(records[4].data(), Type::AAAA.to_value().to_be_bytes());
+        assert_eq!(*signed.lock().unwrap(), vec![2, 1]);
+
+        assert!(config.sign_answers(&records).is_ok());
+        assert_eq!(signed.lock().unwrap().len(), 4);
+
+        let keys = config.dnskey_answers(&Question::new("local", Type::DNSKEY));
+        assert_eq!(keys.len(), 1);
+        assert_eq!(keys[0].typ(), Type::DNSKEY);
+        assert!(config
+            .dnskey_answers(&Question::new("local", Type::A))
+            .is_empty());
+
+        config.clear_signer();
+        assert!(!config.has_signer());
+    }
+}
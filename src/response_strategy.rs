@@ -0,0 +1,217 @@
+// Copyright (C) 2024 Satoshi Konno All rights reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//    http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::hash_map::RandomState;
+use std::hash::{BuildHasher, Hasher};
+use std::net::SocketAddr;
+use std::time::{Duration, Instant};
+
+use crate::dns::{Question, ResponseMode};
+use crate::net::InterfaceAddr;
+
+/// SHARED_RESPONSE_DELAY is the range of the random delay of the responses of shared records.
+/// RFC 6762: 6. Responding
+pub const SHARED_RESPONSE_DELAY: (Duration, Duration) =
+    (Duration::from_millis(20), Duration::from_millis(120));
+
+/// TRUNCATED_RESPONSE_DELAY is the range of the random delay of the responses to truncated queries,
+/// which waits for the rest of the known answers.
+/// RFC 6762: 7.2. Multipacket Known-Answer Suppression
+pub const TRUNCATED_RESPONSE_DELAY: (Duration, Duration) =
+    (Duration::from_millis(400), Duration::from_millis(500));
+
+/// ResponseDecision represents how a responder answers a question.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum ResponseDecision {
+    /// Immediate multicasts the answer without delay.
+    Immediate,
+    /// Delayed multicasts the answer after the delay, which may be aggregated with other answers.
+    Delayed(Duration),
+    /// Unicast answers directly to the querier.
+    Unicast,
+    /// Ignore does not answer the question.
+    Ignore,
+}
+
+/// QuestionContext represents a question received by a responder with what the decision depends on.
+#[derive(Debug, Clone)]
+pub struct QuestionContext {
+    question: Question,
+    source: SocketAddr,
+    unique: bool,
+    truncated: bool,
+    known_answer: bool,
+    ttl: u32,
+    last_multicast: Option<Instant>,
+    now: Instant,
+}
+
+impl QuestionContext {
+    /// new creates a new context of the specified question received from the specified address now.
+    pub fn new(question: &Question, source: SocketAddr) -> QuestionContext {
+        QuestionContext {
+            question: question.clone(),
+            source,
+            unique: false,
+            truncated: false,
+            known_answer: false,
+            ttl: 0,
+            last_multicast: None,
+            now: Instant::now(),
+        }
+    }
+
+    /// question returns the received question.
+    pub fn question(&self) -> &Question {
+        &self.question
+    }
+
+    /// source returns the address of the querier.
+    pub fn source(&self) -> SocketAddr {
+        self.source
+    }
+
+    /// set_unique sets whether the answer is a unique record set, which the responder is the only one to answer.
+    pub fn set_unique(&mut self, unique: bool) -> &mut Self {
+        self.unique = unique;
+        self
+    }
+
+    /// is_unique returns true if the answer is a unique record set.
+    pub fn is_unique(&self) -> bool {
+        self.unique
+    }
+
+    /// set_truncated sets whether the query has the TC bit, and more known answers follow.
+    pub fn set_truncated(&mut self, truncated: bool) -> &mut Self {
+        self.truncated = truncated;
+        self
+    }
+
+    /// is_truncated returns true if the query has the TC bit.
+    pub fn is_truncated(&self) -> bool {
+        self.truncated
+    }
+
+    /// set_known_answer sets whether the query carries the answer as a known answer with at least half of its TTL.
+    pub fn set_known_answer(&mut self, known_answer: bool) -> &mut Self {
+        self.known_answer = known_answer;
+        self
+    }
+
+    /// is_known_answer returns true if the querier already knows the answer.
+    pub fn is_known_answer(&self) -> bool {
+        self.known_answer
+    }
+
+    /// set_last_multicast sets the TTL of the answer and when it was last multicast.
+    pub fn set_last_multicast(&mut self, ttl: u32, last_multicast: Option<Instant>) -> &mut Self {
+        self.ttl = ttl;
+        self.last_multicast = last_multicast;
+        self
+    }
+
+    /// set_now sets the time the question was received.
+    pub fn set_now(&mut self, now: Instant) -> &mut Self {
+        self.now = now;
+        self
+    }
+
+    /// response_mode returns how the question should be answered by the QU/QM bit and the source port.
+    pub fn response_mode(&self) -> ResponseMode {
+        self.question
+            .response_mode_at(self.source.port(), self.ttl, self.last_multicast, self.now)
+    }
+}
+
+/// ResponseStrategy decides how a responder answers each received question.
+pub trait ResponseStrategy: Send {
+    /// decide returns how the question of the specified context is answered.
+    fn decide(&self, ctx: &QuestionContext) -> ResponseDecision;
+}
+
+/// DefaultResponseStrategy implements the response rules of RFC 6762.
+/// A question answered by a known answer is ignored, a unicast response is sent if requested and allowed,
+/// a unique record set is answered immediately, and a shared record set is answered after a random delay.
+#[derive(Debug, Copy, Clone, Default)]
+pub struct DefaultResponseStrategy {}
+
+impl DefaultResponseStrategy {
+    /// new creates a new strategy.
+    pub fn new() -> DefaultResponseStrategy {
+        DefaultResponseStrategy {}
+    }
+}
+
+impl ResponseStrategy for DefaultResponseStrategy {
+    fn decide(&self, ctx: &QuestionContext) -> ResponseDecision {
+        // RFC 6762: 7.1. Known-Answer Suppression
+        if ctx.is_known_answer() {
+            return ResponseDecision::Ignore;
+        }
+        if ctx.response_mode() == ResponseMode::Unicast {
+            return ResponseDecision::Unicast;
+        }
+        if ctx.is_truncated() {
+            return ResponseDecision::Delayed(random_delay(TRUNCATED_RESPONSE_DELAY));
+        }
+        if ctx.is_unique() {
+            return ResponseDecision::Immediate;
+        }
+        ResponseDecision::Delayed(random_delay(SHARED_RESPONSE_DELAY))
+    }
+}
+
+/// SubnetResponseStrategy answers only the queries from the specified subnets, and decides the others by the inner strategy.
+pub struct SubnetResponseStrategy {
+    subnets: Vec<InterfaceAddr>,
+    inner: Box<dyn ResponseStrategy>,
+}
+
+impl SubnetResponseStrategy {
+    /// new creates a new strategy of the specified subnets over the default strategy.
+    pub fn new(subnets: &[InterfaceAddr]) -> SubnetResponseStrategy {
+        SubnetResponseStrategy::with_strategy(subnets, Box::new(DefaultResponseStrategy::new()))
+    }
+
+    /// with_strategy creates a new strategy of the specified subnets over the specified strategy.
+    pub fn with_strategy(
+        subnets: &[InterfaceAddr],
+        inner: Box<dyn ResponseStrategy>,
+    ) -> SubnetResponseStrategy {
+        SubnetResponseStrategy {
+            subnets: subnets.to_vec(),
+            inner,
+        }
+    }
+}
+
+impl ResponseStrategy for SubnetResponseStrategy {
+    fn decide(&self, ctx: &QuestionContext) -> ResponseDecision {
+        let source = ctx.source().ip();
+        if !self.subnets.iter().any(|subnet| subnet.contains(&source)) {
+            return ResponseDecision::Ignore;
+        }
+        self.inner.decide(ctx)
+    }
+}
+
+// random_delay returns a random delay in the specified range.
+fn random_delay(range: (Duration, Duration)) -> Duration {
+    let (min, max) = range;
+    let span = (max - min).as_millis() as u64;
+    let mut hasher = RandomState::new().build_hasher();
+    hasher.write_u64(span);
+    min + Duration::from_millis(hasher.finish() % (span + 1))
+}
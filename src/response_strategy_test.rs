@@ -0,0 +1,76 @@
+// Copyright (C) 2024 Satoshi Konno All rights reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//    http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+#[cfg(test)]
+mod tests {
+
+    use std::time::{Duration, Instant};
+
+    use crate::dns::{Question, Type};
+    use crate::net::InterfaceAddr;
+    use crate::response_strategy::SHARED_RESPONSE_DELAY;
+    use crate::{
+        DefaultResponseStrategy, QuestionContext, ResponderConfig, ResponseDecision,
+        ResponseStrategy, SubnetResponseStrategy,
+    };
+
+    #[test]
+    fn default_response_strategy() {
+        let now = Instant::now();
+        let strategy = DefaultResponseStrategy::new();
+        let mut question = Question::new("host.local", Type::A);
+        let source = "192.168.1.20:5353".parse().unwrap();
+
+        let mut ctx = QuestionContext::new(&question, source);
+        ctx.set_now(now).set_unique(true);
+        assert_eq!(strategy.decide(&ctx), ResponseDecision::Immediate);
+
+        ctx.set_unique(false);
+        match strategy.decide(&ctx) {
+            ResponseDecision::Delayed(delay) => {
+                assert!(SHARED_RESPONSE_DELAY.0 <= delay && delay <= SHARED_RESPONSE_DELAY.1)
+            }
+            decision => panic!("unexpected decision {:?}", decision),
+        }
+
+        ctx.set_known_answer(true);
+        assert_eq!(strategy.decide(&ctx), ResponseDecision::Ignore);
+
+        // A QU question is answered by unicast only if the answer was multicast recently.
+        question.set_unicast_response(true);
+        let mut ctx = QuestionContext::new(&question, source);
+        ctx.set_now(now).set_unique(true);
+        assert_eq!(strategy.decide(&ctx), ResponseDecision::Immediate);
+        ctx.set_last_multicast(120, Some(now - Duration::from_secs(10)));
+        assert_eq!(strategy.decide(&ctx), ResponseDecision::Unicast);
+
+        let legacy = QuestionContext::new(&question, "192.168.1.20:49152".parse().unwrap());
+        assert_eq!(strategy.decide(&legacy), ResponseDecision::Unicast);
+    }
+
+    #[test]
+    fn subnet_response_strategy() {
+        let subnet = InterfaceAddr::new("192.168.1.0".parse().unwrap(), 24);
+        let mut config = ResponderConfig::new();
+        config.set_response_strategy(Box::new(SubnetResponseStrategy::new(&[subnet])));
+
+        let question = Question::new("host.local", Type::A);
+        let mut ctx = QuestionContext::new(&question, "192.168.1.20:5353".parse().unwrap());
+        ctx.set_unique(true);
+        assert_eq!(config.decide(&ctx), ResponseDecision::Immediate);
+        let mut ctx = QuestionContext::new(&question, "10.0.0.20:5353".parse().unwrap());
+        ctx.set_unique(true);
+        assert_eq!(config.decide(&ctx), ResponseDecision::Ignore);
+    }
+}
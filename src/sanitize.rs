@@ -0,0 +1,152 @@
+// Copyright (C) 2024 Satoshi Konno All rights reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//    http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use unicode_normalization::char::is_combining_mark;
+use unicode_normalization::UnicodeNormalization;
+
+/// MAX_LABEL_LEN is the maximum length in bytes of an instance name or a host name label.
+/// RFC 6763: 4.1.1. Instance Names
+/// RFC 1035: 2.3.4. Size limits
+pub const MAX_LABEL_LEN: usize = 63;
+
+/// DEFAULT_SANITIZED_NAME is the name used when nothing of the specified name remains after sanitization.
+pub const DEFAULT_SANITIZED_NAME: &str = "mdns";
+
+const UNIQUE_SUFFIX_FIRST: u32 = 2;
+
+/// NameSanitizer sanitizes arbitrary user-supplied device names such as `Living Room TV\n` into
+/// the instance names and host names which the other stacks accept.
+#[derive(Debug, Clone, PartialEq)]
+pub struct NameSanitizer {
+    max_len: usize,
+    fallback: String,
+}
+
+impl NameSanitizer {
+    /// new creates a new sanitizer clamping the names to 63 bytes.
+    pub fn new() -> NameSanitizer {
+        NameSanitizer {
+            max_len: MAX_LABEL_LEN,
+            fallback: DEFAULT_SANITIZED_NAME.to_string(),
+        }
+    }
+
+    /// set_max_len sets the maximum length in bytes of the sanitized names, which is at most 63 bytes.
+    pub fn set_max_len(&mut self, max_len: usize) {
+        self.max_len = max_len.clamp(1, MAX_LABEL_LEN);
+    }
+
+    /// max_len returns the maximum length in bytes of the sanitized names.
+    pub fn max_len(&self) -> usize {
+        self.max_len
+    }
+
+    /// set_fallback sets the name used when nothing of the specified name remains after sanitization.
+    pub fn set_fallback(&mut self, fallback: &str) {
+        self.fallback = fallback.to_string();
+    }
+
+    /// fallback returns the name used when nothing of the specified name remains after sanitization.
+    pub fn fallback(&self) -> &str {
+        &self.fallback
+    }
+
+    /// instance_name returns the specified name as an instance name in Normalization Form C,
+    /// whose control characters are replaced with spaces, whose spaces are collapsed, and which is clamped to the maximum length.
+    /// RFC 6763: 4.1.3. Instance Names
+    pub fn instance_name(&self, name: &str) -> String {
+        let name: String = name
+            .nfc()
+            .map(|c| if c.is_control() { ' ' } else { c })
+            .collect();
+        let name = name.split_whitespace().collect::<Vec<_>>().join(" ");
+        let name = clamp(&name, self.max_len).trim_end();
+        if name.is_empty() {
+            return self.fallback.clone();
+        }
+        name.to_string()
+    }
+
+    /// hostname returns the specified name as a host name label of the letters, digits and hyphens, such as `Cafe-Printer` for `Café Printer`.
+    /// The accents are removed, the other characters are replaced with hyphens, and the name is clamped to the maximum length.
+    /// RFC 1123: 2.1. Host Names and Numbers
+    pub fn hostname(&self, name: &str) -> String {
+        let mut label = String::new();
+        for c in name.nfkd().filter(|c| !is_combining_mark(*c)) {
+            if c.is_ascii_alphanumeric() {
+                label.push(c);
+            } else if !label.is_empty() && !label.ends_with('-') {
+                label.push('-');
+            }
+        }
+        let label = clamp(&label, self.max_len).trim_end_matches('-');
+        if label.is_empty() {
+            return self.fallback.clone();
+        }
+        label.to_string()
+    }
+
+    /// unique_instance_name returns the sanitized instance name, suffixed as `name (2)` unless the name is not taken yet.
+    pub fn unique_instance_name<F>(&self, name: &str, is_taken: F) -> String
+    where
+        F: Fn(&str) -> bool,
+    {
+        let name = self.instance_name(name);
+        self.unique(&name, |n| format!(" ({})", n), is_taken)
+    }
+
+    /// unique_hostname returns the sanitized host name, suffixed as `name-2` unless the name is not taken yet.
+    pub fn unique_hostname<F>(&self, name: &str, is_taken: F) -> String
+    where
+        F: Fn(&str) -> bool,
+    {
+        let name = self.hostname(name);
+        self.unique(&name, |n| format!("-{}", n), is_taken)
+    }
+
+    // unique returns the first name which is not taken, clamping the base name so the suffix fits in the maximum length.
+    fn unique<S, F>(&self, name: &str, suffix: S, is_taken: F) -> String
+    where
+        S: Fn(u32) -> String,
+        F: Fn(&str) -> bool,
+    {
+        let mut candidate = name.to_string();
+        let mut n = UNIQUE_SUFFIX_FIRST;
+        while is_taken(&candidate) && n < u32::MAX {
+            let suffix = suffix(n);
+            let base = clamp(name, self.max_len.saturating_sub(suffix.len()));
+            candidate = format!("{}{}", base.trim_end_matches([' ', '-']), suffix);
+            n += 1;
+        }
+        candidate
+    }
+}
+
+impl Default for NameSanitizer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// clamp returns the longest prefix of the specified name within the specified bytes, without splitting a character.
+fn clamp(name: &str, max_len: usize) -> &str {
+    if name.len() <= max_len {
+        return name;
+    }
+    let mut end = max_len;
+    while !name.is_char_boundary(end) {
+        end -= 1;
+    }
+    &name[..end]
+}
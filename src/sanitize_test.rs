@@ -0,0 +1,139 @@
+// Copyright (C) 2024 Satoshi Konno All rights reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//    http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+#[cfg(test)]
+mod tests {
+
+    use crate::sanitize::{NameSanitizer, MAX_LABEL_LEN};
+    use crate::ServiceBuilder;
+
+    #[test]
+    fn sanitize_instance_name() {
+        struct Test {
+            name: &'static str,
+            expected: &'static str,
+        }
+
+        let tests = vec![
+            Test {
+                name: "Living Room TV",
+                expected: "Living Room TV",
+            },
+            Test {
+                name: "  Living\tRoom\n\nTV ",
+                expected: "Living Room TV",
+            },
+            Test {
+                name: "Cafe\u{0301}.Printer",
+                expected: "Caf\u{e9}.Printer",
+            },
+            Test {
+                name: "\u{7}\n",
+                expected: "mdns",
+            },
+        ];
+
+        let sanitizer = NameSanitizer::new();
+        for test in tests {
+            assert_eq!(sanitizer.instance_name(test.name), test.expected);
+        }
+
+        let long = "\u{e9}".repeat(40);
+        let name = sanitizer.instance_name(&long);
+        assert_eq!(name.len(), 62);
+        assert_eq!(name, "\u{e9}".repeat(31));
+    }
+
+    #[test]
+    fn sanitize_hostname() {
+        struct Test {
+            name: &'static str,
+            expected: &'static str,
+        }
+
+        let tests = vec![
+            Test {
+                name: "Café Printer",
+                expected: "Cafe-Printer",
+            },
+            Test {
+                name: "my_host.example",
+                expected: "my-host-example",
+            },
+            Test {
+                name: "--Living Room (2)--",
+                expected: "Living-Room-2",
+            },
+            Test {
+                name: "テレビ",
+                expected: "mdns",
+            },
+        ];
+
+        let mut sanitizer = NameSanitizer::new();
+        for test in tests {
+            assert_eq!(sanitizer.hostname(test.name), test.expected);
+        }
+
+        sanitizer.set_max_len(8);
+        sanitizer.set_fallback("device");
+        assert_eq!(sanitizer.hostname("living room"), "living-r");
+        assert_eq!(sanitizer.hostname("kitchen tv"), "kitchen");
+        assert_eq!(sanitizer.hostname("_"), "device");
+        sanitizer.set_max_len(100);
+        assert_eq!(sanitizer.max_len(), MAX_LABEL_LEN);
+    }
+
+    #[test]
+    fn sanitize_unique_name() {
+        let mut sanitizer = NameSanitizer::new();
+        let taken = ["TV", "TV (2)", "tv", "tv-2"];
+        let is_taken = |name: &str| taken.contains(&name);
+        assert_eq!(sanitizer.unique_instance_name("TV", is_taken), "TV (3)");
+        assert_eq!(sanitizer.unique_instance_name("Radio", is_taken), "Radio");
+        assert_eq!(sanitizer.unique_hostname("tv", is_taken), "tv-3");
+
+        sanitizer.set_max_len(8);
+        let taken = ["living-r", "Living R"];
+        let is_taken = |name: &str| taken.contains(&name);
+        assert_eq!(
+            sanitizer.unique_hostname("living room", is_taken),
+            "living-2"
+        );
+        assert_eq!(
+            sanitizer.unique_instance_name("Living Room", is_taken),
+            "Livi (2)"
+        );
+    }
+
+    #[test]
+    fn sanitize_service_builder() {
+        assert!(ServiceBuilder::new("Living\nRoom", "_http._tcp")
+            .build()
+            .is_ok());
+        assert!(ServiceBuilder::new(&"a".repeat(64), "_http._tcp")
+            .build()
+            .is_err());
+        let service = ServiceBuilder::new(&"a".repeat(64), "_http._tcp")
+            .sanitize(NameSanitizer::new())
+            .build()
+            .unwrap();
+        assert_eq!(service.instance_name(), "a".repeat(63));
+        let service = ServiceBuilder::new(" Living\nRoom ", "_http._tcp")
+            .sanitize(NameSanitizer::new())
+            .build()
+            .unwrap();
+        assert_eq!(service.instance_name(), "Living Room");
+    }
+}
@@ -12,12 +12,17 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use crate::dns::{AAAARecord, ARecord, Message, Record, ResourceRecords, Type};
+use crate::dns::{Message, Record, ResourceRecords, Type};
+use crate::family::AddressFamily;
+use crate::provenance::Provenance;
+use crate::service_record::ServiceRecord;
+use crate::txt;
 use std::collections::HashMap;
 use std::fmt;
-use std::net::IpAddr;
+use std::net::{IpAddr, SocketAddr};
 
 /// Service represents a DNS-SD service.
+#[derive(Clone)]
 pub struct Service {
     msg: Message,
     name: String,
@@ -26,6 +31,10 @@ pub struct Service {
     ipaddrs: Vec<IpAddr>,
     port: u16,
     attrs: HashMap<String, String>,
+    attr_bytes: HashMap<String, Vec<u8>>,
+    source: Option<SocketAddr>,
+    families: Vec<AddressFamily>,
+    provenance: Option<Provenance>,
 }
 
 impl Service {
@@ -39,6 +48,10 @@ impl Service {
             port: 0,
             ipaddrs: Vec::new(),
             attrs: HashMap::new(),
+            attr_bytes: HashMap::new(),
+            source: None,
+            families: Vec::new(),
+            provenance: None,
         };
         srv.parse_message(msg);
         srv
@@ -54,11 +67,46 @@ impl Service {
         self.msg.resource_records()
     }
 
+    /// records returns the PTR, SRV, TXT, A and AAAA records of the message which produced the service,
+    /// which are the PTR records pointing to the service, its SRV and TXT records, and the address records of its host.
+    pub fn records(&self) -> Vec<ServiceRecord> {
+        self.msg
+            .answers()
+            .iter()
+            .chain(self.msg.authorities())
+            .chain(self.msg.additionals())
+            .filter(|record| self.produced_by(record))
+            .filter_map(ServiceRecord::from_record)
+            .collect()
+    }
+
+    // produced_by returns true if the specified record is about the service or its host.
+    fn produced_by(&self, record: &Record) -> bool {
+        match record.typ() {
+            Type::PTR => record
+                .as_ptr()
+                .is_some_and(|ptr| ptr.domain_name().eq_ignore_ascii_case(&self.name)),
+            Type::SRV | Type::TXT => record.name().eq_ignore_ascii_case(&self.name),
+            Type::A | Type::AAAA => record.name().eq_ignore_ascii_case(&self.host),
+            _ => false,
+        }
+    }
+
     /// name returns the name of the service.
     pub fn name(&self) -> &str {
         &self.name
     }
 
+    /// instance_name returns the instance part of the service name.
+    pub fn instance_name(&self) -> &str {
+        split_service_name(&self.name).0
+    }
+
+    /// service_type returns the service type part of the service name such as `_http._tcp`.
+    pub fn service_type(&self) -> &str {
+        split_service_name(&self.name).1
+    }
+
     /// domain returns the domain of the service.
     pub fn domain(&self) -> &str {
         &self.domain
@@ -79,6 +127,62 @@ impl Service {
         self.port
     }
 
+    /// set_source sets the address of the responder which sent the service.
+    pub fn set_source(&mut self, source: SocketAddr) {
+        self.source = Some(source);
+        self.add_family(AddressFamily::from_socketaddr(&source));
+    }
+
+    /// source returns the address of the responder which sent the service.
+    pub fn source(&self) -> Option<SocketAddr> {
+        self.source
+    }
+
+    /// set_provenance sets where and when the service was received, which also sets the source address.
+    pub fn set_provenance(&mut self, provenance: Provenance) {
+        self.set_source(provenance.source());
+        self.provenance = Some(provenance);
+    }
+
+    /// provenance returns where and when the service was last received.
+    pub fn provenance(&self) -> Option<&Provenance> {
+        self.provenance.as_ref()
+    }
+
+    /// interface returns the name of the interface the service was last received on, if known.
+    pub fn interface(&self) -> Option<&str> {
+        self.provenance.as_ref()?.interface()
+    }
+
+    // merge adds the addresses and families of the specified service received on another interface.
+    pub(crate) fn merge(&mut self, other: &Service) {
+        for ipaddr in &other.ipaddrs {
+            if !self.ipaddrs.contains(ipaddr) {
+                self.ipaddrs.push(*ipaddr);
+            }
+        }
+        for family in &other.families {
+            self.add_family(*family);
+        }
+    }
+
+    /// add_family adds the specified family to the families the service was received over.
+    pub fn add_family(&mut self, family: AddressFamily) {
+        if !self.families.contains(&family) {
+            self.families.push(family);
+        }
+    }
+
+    /// families returns the address families the service was received over.
+    pub fn families(&self) -> &Vec<AddressFamily> {
+        &self.families
+    }
+
+    /// is_reachable_over returns true if the service was received over the specified family.
+    pub fn is_reachable_over(&self, family: AddressFamily) -> bool {
+        self.families.contains(&family)
+    }
+
     /// attributes returns the attributes of the service.
     pub fn attributes(&self) -> &HashMap<String, String> {
         &self.attrs
@@ -86,13 +190,26 @@ impl Service {
 
     /// attribute returns the attribute of the service.
     pub fn attribute(&self, key: &str) -> Option<&String> {
-        self.attrs.get(key)
+        txt::attribute(&self.attrs, key)
+    }
+
+    /// attributes_bytes returns the attributes of the service whose values are kept as bytes.
+    pub fn attributes_bytes(&self) -> &HashMap<String, Vec<u8>> {
+        &self.attr_bytes
+    }
+
+    /// attribute_bytes returns the attribute of the service as bytes, so the binary values are returned losslessly.
+    pub fn attribute_bytes(&self, key: &str) -> Option<&[u8]> {
+        txt::attribute(&self.attr_bytes, key).map(|value| value.as_slice())
+    }
+
+    /// txtvers returns the TXT record format version of the service, or None if the service has no valid txtvers attribute.
+    /// RFC 6763: 6.7. Version Tag
+    pub fn txtvers(&self) -> Option<u32> {
+        txt::txtvers(&self.attrs)
     }
 
     fn parse_message(&mut self, msg: &Message) {
-        for record in msg.questions() {
-            self.parse_record(record);
-        }
         for record in msg.answers() {
             self.parse_record(record);
         }
@@ -107,32 +224,35 @@ impl Service {
     fn parse_record(&mut self, record: &Record) {
         match record.typ() {
             Type::SRV => {
-                let srv = crate::dns::SRVRecord::from_record(record).unwrap();
-                self.name = srv.name().to_string();
-                self.domain = srv.proto().to_string();
-                self.host = srv.target().to_string();
-                self.port = srv.port();
+                if let Some(srv) = record.as_srv() {
+                    self.name = srv.name().to_string();
+                    self.domain = split_service_name(srv.name()).2.to_string();
+                    self.host = srv.target().to_string();
+                    self.port = srv.port();
+                }
             }
             Type::TXT => {
-                let txt = crate::dns::TXTRecord::from_record(record).unwrap();
-                self.attrs = txt.attributes().clone();
+                if let Some(txt) = record.as_txt() {
+                    self.attrs = txt.attributes().clone();
+                    self.attr_bytes = txt.attributes_bytes().clone();
+                }
             }
-            Type::A => match ARecord::from_record(record) {
-                Ok(a) => {
-                    self.ipaddrs.push(a.ipaddr().clone());
+            Type::A => {
+                if let Some(a) = record.as_a() {
+                    self.ipaddrs.push(*a.ipaddr());
                 }
-                _ => {}
-            },
-            Type::AAAA => match AAAARecord::from_record(record) {
-                Ok(a) => {
-                    self.ipaddrs.push(a.ipaddr().clone());
+            }
+            Type::AAAA => {
+                if let Some(a) = record.as_aaaa() {
+                    self.ipaddrs.push(*a.ipaddr());
                 }
-                _ => {}
-            },
+            }
             _ => {}
         }
     }
 
+    /// to_string returns the string representation of the service.
+    #[allow(clippy::inherent_to_string_shadow_display)]
     pub fn to_string(&self) -> String {
         let mut s = String::new();
         s.push_str(&format!("name: {}\n", self.name));
@@ -149,14 +269,29 @@ impl Service {
     }
 }
 
-impl Clone for Service {
-    fn clone(&self) -> Service {
-        Service::from_message(&self.msg.clone())
+impl fmt::Display for Service {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.msg)
     }
 }
 
-impl fmt::Display for Service {
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "{}", self.msg.to_string())
+/// split_service_name splits the specified service instance name into the instance, the service type and the domain.
+/// RFC 6763: 4.1. Structured Service Instance Names
+/// The service type is the two labels of `_service._tcp` or `_service._udp` preceding the domain.
+pub(crate) fn split_service_name(name: &str) -> (&str, &str, &str) {
+    let mut offset = 0;
+    for label in name.split('.') {
+        if label == "_tcp" || label == "_udp" {
+            let proto_end = offset + label.len();
+            let service_start = name[..offset.saturating_sub(1)]
+                .rfind('.')
+                .map(|n| n + 1)
+                .unwrap_or(0);
+            let instance = name[..service_start].trim_end_matches('.');
+            let domain = name[proto_end..].trim_start_matches('.');
+            return (instance, &name[service_start..proto_end], domain);
+        }
+        offset += label.len() + 1;
     }
+    (name, "", "")
 }
@@ -0,0 +1,361 @@
+// Copyright (C) 2024 Satoshi Konno All rights reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//    http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::dns::{self, Class, PTRRecord, Record, SRVRecord, Type};
+use crate::error::{Error, Result};
+use crate::family::AddressFamily;
+use crate::hostname::LOCAL_DOMAIN;
+use crate::record_ttls::RecordTtls;
+use crate::sanitize::{NameSanitizer, MAX_LABEL_LEN};
+use crate::txt;
+
+/// SERVICE_TYPE_ENUMERATION is the name under which the service types in a domain are enumerated.
+/// RFC 6763: 9. Service Type Enumeration
+pub const SERVICE_TYPE_ENUMERATION: &str = "_services._dns-sd._udp";
+
+/// ServiceBuilder builds a service to advertise by chaining the properties such as
+/// `ServiceBuilder::new("My Web", "_http._tcp").port(80).attribute("path", "/")`.
+#[derive(Debug, Clone)]
+pub struct ServiceBuilder {
+    instance_name: String,
+    service_type: String,
+    domain: String,
+    host: Option<String>,
+    port: u16,
+    subtypes: Vec<String>,
+    attrs: Vec<(String, Vec<u8>)>,
+    family: Option<AddressFamily>,
+    sanitizer: Option<NameSanitizer>,
+}
+
+impl ServiceBuilder {
+    /// new creates a new builder of the specified instance name and service type such as `_http._tcp`.
+    pub fn new(instance_name: &str, service_type: &str) -> ServiceBuilder {
+        ServiceBuilder {
+            instance_name: instance_name.to_string(),
+            service_type: service_type.trim_end_matches('.').to_string(),
+            domain: LOCAL_DOMAIN.to_string(),
+            host: None,
+            port: 0,
+            subtypes: Vec::new(),
+            attrs: Vec::new(),
+            family: None,
+            sanitizer: None,
+        }
+    }
+
+    /// domain sets the domain of the service, which is `local` by default.
+    pub fn domain(mut self, domain: &str) -> ServiceBuilder {
+        self.domain = domain.trim_end_matches('.').to_string();
+        self
+    }
+
+    /// host sets the target host of the service instead of the local host name.
+    pub fn host(mut self, host: &str) -> ServiceBuilder {
+        self.host = Some(host.trim_end_matches('.').to_string());
+        self
+    }
+
+    /// port sets the port of the service.
+    pub fn port(mut self, port: u16) -> ServiceBuilder {
+        self.port = port;
+        self
+    }
+
+    /// subtype adds the specified subtype such as `_printer`, or `_printer._sub._http._tcp`.
+    /// RFC 6763: 7.1. Selective Instance Enumeration (Subtypes)
+    pub fn subtype(mut self, subtype: &str) -> ServiceBuilder {
+        let subtype = subtype.trim_end_matches('.');
+        let subtype = match subtype.split_once("._sub.") {
+            Some((label, _)) => label,
+            None => subtype,
+        };
+        self.subtypes.push(subtype.to_string());
+        self
+    }
+
+    /// attribute adds the TXT attribute of the specified key and value, where an empty value is a boolean attribute.
+    pub fn attribute(mut self, key: &str, value: &str) -> ServiceBuilder {
+        self.attrs
+            .push((key.to_string(), value.as_bytes().to_vec()));
+        self
+    }
+
+    /// attribute_bytes adds the TXT attribute of the specified key and binary value, which is serialized as it is.
+    /// RFC 6763: 6.5. Rules for Values in DNS-SD Key/Value Pairs
+    pub fn attribute_bytes(mut self, key: &str, value: &[u8]) -> ServiceBuilder {
+        self.attrs.push((key.to_string(), value.to_vec()));
+        self
+    }
+
+    /// txtvers sets the TXT record format version, which is always serialized first and replaces any txtvers attribute added before.
+    /// RFC 6763: 6.7. Version Tag
+    pub fn txtvers(mut self, version: u32) -> ServiceBuilder {
+        self.attrs
+            .retain(|(k, _)| !txt::key_eq(k, txt::TXTVERS_KEY));
+        self.attrs.insert(
+            0,
+            (
+                txt::TXTVERS_KEY.to_string(),
+                version.to_string().into_bytes(),
+            ),
+        );
+        self
+    }
+
+    /// family restricts the service to the specified address family, which is advertised over both by default.
+    pub fn family(mut self, family: AddressFamily) -> ServiceBuilder {
+        self.family = Some(family);
+        self
+    }
+
+    /// sanitize sets the sanitizer of the instance name, so a user-supplied device name is published as a valid instance name
+    /// instead of being rejected by the build.
+    pub fn sanitize(mut self, sanitizer: NameSanitizer) -> ServiceBuilder {
+        self.sanitizer = Some(sanitizer);
+        self
+    }
+
+    /// build returns the service, or an error if the instance name, service type or TXT attributes are invalid.
+    /// A TXT record larger than RFC 6763 recommends is only warned.
+    pub fn build(mut self) -> Result<ServiceInfo> {
+        if let Some(sanitizer) = &self.sanitizer {
+            self.instance_name = sanitizer.instance_name(&self.instance_name);
+        }
+        if self.instance_name.is_empty() || MAX_LABEL_LEN < self.instance_name.len() {
+            return Err(Error::from_string(&format!(
+                "Invalid instance name: {:?}",
+                self.instance_name
+            )));
+        }
+        if !is_valid_service_type(&self.service_type) {
+            return Err(Error::from_string(&format!(
+                "Invalid service type: {:?}",
+                self.service_type
+            )));
+        }
+        for (key, _) in &self.attrs {
+            txt::validate_key(key)?;
+        }
+        let keys = txt::canonical_order(self.attrs.iter().map(|(k, _)| k.as_str()));
+        let attr_bytes: Vec<(String, Vec<u8>)> = keys
+            .iter()
+            .filter_map(|key| self.attrs.iter().find(|(k, _)| k == key).cloned())
+            .collect();
+        let attrs = attr_bytes
+            .iter()
+            .map(|(key, value)| (key.clone(), String::from_utf8_lossy(value).to_string()))
+            .collect();
+        let txt_size = txt::encode_attribute_bytes(&attr_bytes)?.len();
+        if txt::RECORD_RECOMMENDED_MAX_SIZE < txt_size {
+            log::warn!(
+                "TXT record of {} is {} bytes, and its responses may not fit in a packet",
+                self.instance_name,
+                txt_size
+            );
+        } else if txt::RECORD_PREFERRED_MAX_SIZE < txt_size {
+            log::info!(
+                "TXT record of {} is {} bytes, larger than the typical {} bytes",
+                self.instance_name,
+                txt_size,
+                txt::RECORD_PREFERRED_MAX_SIZE
+            );
+        }
+        Ok(ServiceInfo {
+            instance_name: self.instance_name,
+            service_type: self.service_type,
+            domain: self.domain,
+            host: self.host,
+            port: self.port,
+            subtypes: self.subtypes,
+            attrs,
+            attr_bytes,
+            family: self.family,
+        })
+    }
+}
+
+/// ServiceInfo represents a service to advertise built by a ServiceBuilder.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ServiceInfo {
+    instance_name: String,
+    service_type: String,
+    domain: String,
+    host: Option<String>,
+    port: u16,
+    subtypes: Vec<String>,
+    attrs: Vec<(String, String)>,
+    attr_bytes: Vec<(String, Vec<u8>)>,
+    family: Option<AddressFamily>,
+}
+
+impl ServiceInfo {
+    /// instance_name returns the instance name of the service such as `My Web`.
+    pub fn instance_name(&self) -> &str {
+        &self.instance_name
+    }
+
+    // set_instance_name renames the service, such as after its name conflicted.
+    pub(crate) fn set_instance_name(&mut self, instance_name: &str) {
+        self.instance_name = instance_name.to_string();
+    }
+
+    /// service_type returns the service type such as `_http._tcp`.
+    pub fn service_type(&self) -> &str {
+        &self.service_type
+    }
+
+    /// domain returns the domain of the service such as `local`.
+    pub fn domain(&self) -> &str {
+        &self.domain
+    }
+
+    /// name returns the full service instance name such as `My Web._http._tcp.local`.
+    pub fn name(&self) -> String {
+        format!("{}.{}", self.instance_name, self.type_name())
+    }
+
+    /// type_name returns the service type name in the domain such as `_http._tcp.local`.
+    pub fn type_name(&self) -> String {
+        format!("{}.{}", self.service_type, self.domain)
+    }
+
+    /// host returns the target host of the service, or None if the local host name is used.
+    pub fn host(&self) -> Option<&str> {
+        self.host.as_deref()
+    }
+
+    /// port returns the port of the service.
+    pub fn port(&self) -> u16 {
+        self.port
+    }
+
+    /// subtypes returns the subtype labels of the service such as `_printer`.
+    pub fn subtypes(&self) -> &Vec<String> {
+        &self.subtypes
+    }
+
+    /// subtype_names returns the subtype names to browse such as `_printer._sub._http._tcp.local`.
+    pub fn subtype_names(&self) -> Vec<String> {
+        self.subtypes
+            .iter()
+            .map(|subtype| format!("{}._sub.{}", subtype, self.type_name()))
+            .collect()
+    }
+
+    /// attributes returns the TXT attributes of the service in the serialization order.
+    pub fn attributes(&self) -> &Vec<(String, String)> {
+        &self.attrs
+    }
+
+    /// attribute returns the value of the specified TXT key, ignoring case.
+    pub fn attribute(&self, key: &str) -> Option<&str> {
+        self.attrs
+            .iter()
+            .find(|(k, _)| txt::key_eq(k, key))
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// attribute_bytes returns the value of the specified TXT key as bytes, ignoring case,
+    /// where the value added as text is returned as its UTF-8 bytes.
+    pub fn attribute_bytes(&self, key: &str) -> Option<&[u8]> {
+        self.attr_bytes
+            .iter()
+            .find(|(k, _)| txt::key_eq(k, key))
+            .map(|(_, value)| value.as_slice())
+    }
+
+    /// txtvers returns the TXT record format version of the service, or None if it has no valid txtvers attribute.
+    pub fn txtvers(&self) -> Option<u32> {
+        self.attribute(txt::TXTVERS_KEY)
+            .and_then(|v| v.parse::<u32>().ok())
+    }
+
+    /// txt_data returns the TXT record data of the attributes.
+    pub fn txt_data(&self) -> Vec<u8> {
+        // The lengths of the strings were checked by the builder.
+        txt::encode_attribute_bytes(&self.attr_bytes).unwrap_or_else(|_| vec![0])
+    }
+
+    /// records returns the PTR, SRV and TXT records of the service whose SRV target is the specified host
+    /// unless the service has its own host, with the TTLs of the specified TTLs.
+    /// The unique SRV and TXT records are sent with the cache-flush bit, and the shared PTR records without it.
+    pub fn records(&self, host: &str, ttls: &RecordTtls) -> Vec<Record> {
+        let name = self.name();
+        let mut records = Vec::new();
+        let mut ptr_names = vec![self.type_name()];
+        ptr_names.extend(self.subtype_names());
+        for ptr_name in ptr_names {
+            records.push(ptr_record(&ptr_name, &name));
+        }
+        records.push(ptr_record(
+            &format!("{}.{}", SERVICE_TYPE_ENUMERATION, self.domain),
+            &self.type_name(),
+        ));
+
+        let target = self.host.as_deref().unwrap_or(host);
+        let srv = SRVRecord::new(&name, 0, 0, self.port, target);
+        records.push(or_empty(srv.to_record(), &name, Type::SRV));
+        let mut txt = Record::new();
+        txt.set_name(&name);
+        txt.set_typ(Type::TXT);
+        txt.set_data(self.txt_data());
+        records.push(txt);
+
+        for record in &mut records {
+            record.set_class(Class::IN);
+            record.set_cache_flush(matches!(record.typ(), Type::SRV | Type::TXT));
+            ttls.apply(record);
+        }
+        records
+    }
+
+    /// family returns the address family which the service is restricted to, or None if it is advertised over both.
+    pub fn family(&self) -> Option<AddressFamily> {
+        self.family
+    }
+}
+
+fn ptr_record(name: &str, target: &str) -> Record {
+    let mut ptr = PTRRecord::new(name, target);
+    ptr.set_domain_name(target);
+    or_empty(ptr.to_record(), name, Type::PTR)
+}
+
+// or_empty returns the specified record, or a record of empty data if the target name could not be written.
+// The names were checked by the builder, so this only happens with an invalid host name.
+fn or_empty(record: dns::Result<Record>, name: &str, typ: Type) -> Record {
+    record.unwrap_or_else(|_| {
+        let mut record = Record::new();
+        record.set_name(name);
+        record.set_typ(typ);
+        record
+    })
+}
+
+// is_valid_service_type returns true if the specified service type is `_<service>._tcp` or `_<service>._udp`.
+// RFC 6763: 7. Service Names
+fn is_valid_service_type(service_type: &str) -> bool {
+    let (service, proto) = match service_type.rsplit_once('.') {
+        Some(labels) => labels,
+        None => return false,
+    };
+    if !proto.eq_ignore_ascii_case("_tcp") && !proto.eq_ignore_ascii_case("_udp") {
+        return false;
+    }
+    match service.strip_prefix('_') {
+        Some(label) => !label.is_empty() && !label.contains('.'),
+        None => false,
+    }
+}
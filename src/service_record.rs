@@ -0,0 +1,83 @@
+// Copyright (C) 2024 Satoshi Konno All rights reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//    http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::dns::{AAAARecord, ARecord, PTRRecord, Record, SRVRecord, TXTRecord, Type};
+
+/// ServiceRecordData represents the typed data of a record which produced a service.
+pub enum ServiceRecordData {
+    /// PTR is the data of a PTR record pointing to the service instance.
+    PTR(PTRRecord),
+    /// SRV is the data of the SRV record of the service instance.
+    SRV(SRVRecord),
+    /// TXT is the data of the TXT record of the service instance.
+    TXT(TXTRecord),
+    /// A is the data of an A record of the host.
+    A(ARecord),
+    /// AAAA is the data of an AAAA record of the host.
+    AAAA(AAAARecord),
+}
+
+/// ServiceRecord represents a record as it was announced for a service, with its TTL and cache-flush bit.
+pub struct ServiceRecord {
+    record: Record,
+    data: ServiceRecordData,
+}
+
+impl ServiceRecord {
+    /// from_record creates a new service record from the specified record, or returns None if the record is not a PTR, SRV, TXT, A or AAAA record.
+    pub fn from_record(record: &Record) -> Option<ServiceRecord> {
+        let data = match record.typ() {
+            Type::PTR => ServiceRecordData::PTR(PTRRecord::from_record(record).ok()?),
+            Type::SRV => ServiceRecordData::SRV(SRVRecord::from_record(record).ok()?),
+            Type::TXT => ServiceRecordData::TXT(TXTRecord::from_record(record).ok()?),
+            Type::A => ServiceRecordData::A(ARecord::from_record(record).ok()?),
+            Type::AAAA => ServiceRecordData::AAAA(AAAARecord::from_record(record).ok()?),
+            _ => return None,
+        };
+        Some(ServiceRecord {
+            record: record.clone(),
+            data,
+        })
+    }
+
+    /// name returns the owner name of the record.
+    pub fn name(&self) -> &str {
+        self.record.name()
+    }
+
+    /// typ returns the type of the record.
+    pub fn typ(&self) -> Type {
+        self.record.typ()
+    }
+
+    /// ttl returns the TTL of the record as it was announced.
+    pub fn ttl(&self) -> u32 {
+        self.record.ttl()
+    }
+
+    /// cache_flush returns the cache-flush bit of the record.
+    pub fn cache_flush(&self) -> bool {
+        self.record.cache_flush()
+    }
+
+    /// data returns the typed data of the record.
+    pub fn data(&self) -> &ServiceRecordData {
+        &self.data
+    }
+
+    /// record returns the raw record.
+    pub fn record(&self) -> &Record {
+        &self.record
+    }
+}
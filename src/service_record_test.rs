@@ -0,0 +1,63 @@
+// Copyright (C) 2024 Satoshi Konno All rights reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//    http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+#[cfg(test)]
+mod tests {
+
+    use crate::dns::{Message, TXTRecord, Type};
+    use crate::fixture_test::{a_record, ptr_record, srv_record, unique, with_ttl};
+    use crate::{Service, ServiceRecordData};
+
+    #[test]
+    fn service_records() {
+        let name = "test._http._tcp.local";
+        let mut msg = Message::new();
+        msg.add_answer(with_ttl(ptr_record("_http._tcp.local", name), 4500));
+        msg.add_answer(unique(srv_record(
+            "other._http._tcp.local",
+            8080,
+            "other.local",
+        )));
+        msg.add_answer(unique(srv_record(name, 80, "host.local")));
+        let txt = TXTRecord::with_attributes(name, [("path", "/")]);
+        msg.add_additional(unique(with_ttl(txt.to_record().unwrap(), 4500)));
+        msg.add_additional(unique(a_record("host.local", [192, 168, 1, 10])));
+        msg.add_additional(unique(a_record("other.local", [192, 168, 1, 20])));
+
+        let service = Service::from_message(&msg);
+        assert_eq!(service.name(), name);
+        let records = service.records();
+        assert_eq!(records.len(), 4);
+
+        assert_eq!(records[0].typ(), Type::PTR);
+        assert_eq!(records[0].ttl(), 4500);
+        assert!(!records[0].cache_flush());
+        assert!(
+            matches!(records[0].data(), ServiceRecordData::PTR(ptr) if ptr.domain_name() == name)
+        );
+
+        assert_eq!(records[1].name(), name);
+        assert!(records[1].cache_flush());
+        match records[1].data() {
+            ServiceRecordData::SRV(srv) => assert_eq!(srv.port(), 80),
+            _ => panic!("expected an SRV record"),
+        }
+        assert!(matches!(records[2].data(), ServiceRecordData::TXT(_)));
+        match records[3].data() {
+            ServiceRecordData::A(a) => assert_eq!(a.ipaddr().to_string(), "192.168.1.10"),
+            _ => panic!("expected an A record"),
+        }
+        assert_eq!(records[3].record().name(), "host.local");
+    }
+}
@@ -0,0 +1,165 @@
+// Copyright (C) 2024 Satoshi Konno All rights reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//    http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// DEFAULT_SHUTDOWN_TIMEOUT is the default time limit to wait for background workers.
+pub const DEFAULT_SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(3);
+
+/// ShutdownReport represents what was cleaned up by a shutdown sequence.
+#[derive(Debug, Clone, Default)]
+pub struct ShutdownReport {
+    schedulers_cancelled: usize,
+    goodbyes_sent: usize,
+    replies_flushed: usize,
+    workers_joined: usize,
+    workers_timed_out: usize,
+    subscribers_closed: usize,
+    transport_stopped: bool,
+    elapsed: Duration,
+}
+
+impl ShutdownReport {
+    /// new creates an empty report.
+    pub fn new() -> ShutdownReport {
+        ShutdownReport::default()
+    }
+
+    /// schedulers_cancelled returns the number of cancelled timers and scheduled queries.
+    pub fn schedulers_cancelled(&self) -> usize {
+        self.schedulers_cancelled
+    }
+
+    /// goodbyes_sent returns the number of goodbye packets sent for published services.
+    pub fn goodbyes_sent(&self) -> usize {
+        self.goodbyes_sent
+    }
+
+    /// replies_flushed returns the number of pending delayed replies flushed from the queue without being sent.
+    pub fn replies_flushed(&self) -> usize {
+        self.replies_flushed
+    }
+
+    /// workers_joined returns the number of worker threads joined.
+    pub fn workers_joined(&self) -> usize {
+        self.workers_joined
+    }
+
+    /// workers_timed_out returns the number of worker threads which did not finish in time.
+    pub fn workers_timed_out(&self) -> usize {
+        self.workers_timed_out
+    }
+
+    /// subscribers_closed returns the number of closed event subscribers.
+    pub fn subscribers_closed(&self) -> usize {
+        self.subscribers_closed
+    }
+
+    /// transport_stopped returns true if the transport was stopped.
+    pub fn transport_stopped(&self) -> bool {
+        self.transport_stopped
+    }
+
+    /// elapsed returns the time taken by the shutdown sequence.
+    pub fn elapsed(&self) -> Duration {
+        self.elapsed
+    }
+
+    /// is_complete returns true if every worker finished before the timeout.
+    pub fn is_complete(&self) -> bool {
+        self.workers_timed_out == 0
+    }
+
+    pub(crate) fn add_worker(&mut self, joined: bool) {
+        if joined {
+            self.workers_joined += 1;
+        } else {
+            self.workers_timed_out += 1;
+        }
+    }
+
+    pub(crate) fn merge(&mut self, other: &ShutdownReport) {
+        self.schedulers_cancelled += other.schedulers_cancelled;
+        self.goodbyes_sent += other.goodbyes_sent;
+        self.replies_flushed += other.replies_flushed;
+        self.workers_joined += other.workers_joined;
+        self.workers_timed_out += other.workers_timed_out;
+        self.subscribers_closed += other.subscribers_closed;
+        self.transport_stopped |= other.transport_stopped;
+    }
+
+    pub(crate) fn add_schedulers_cancelled(&mut self, n: usize) {
+        self.schedulers_cancelled += n;
+    }
+
+    pub(crate) fn add_replies_flushed(&mut self, n: usize) {
+        self.replies_flushed += n;
+    }
+
+    pub(crate) fn add_goodbyes_sent(&mut self, n: usize) {
+        self.goodbyes_sent += n;
+    }
+
+    pub(crate) fn add_subscribers_closed(&mut self, n: usize) {
+        self.subscribers_closed += n;
+    }
+
+    pub(crate) fn set_transport_stopped(&mut self, stopped: bool) {
+        self.transport_stopped = stopped;
+    }
+
+    pub(crate) fn set_elapsed(&mut self, elapsed: Duration) {
+        self.elapsed = elapsed;
+    }
+}
+
+/// join_until waits for the specified worker to finish until the deadline, and returns false if it did not finish in time.
+pub(crate) fn join_until(worker: thread::JoinHandle<()>, deadline: Instant) -> bool {
+    while !worker.is_finished() {
+        if deadline <= Instant::now() {
+            return false;
+        }
+        thread::sleep(Duration::from_millis(10));
+    }
+    worker.join().is_ok()
+}
+
+/// TimerWorker represents a running timer thread, which can be cancelled without taking the lock of its owner.
+pub(crate) struct TimerWorker {
+    cancelled: Arc<AtomicBool>,
+    worker: thread::JoinHandle<()>,
+}
+
+impl TimerWorker {
+    /// new creates a timer of the specified thread, which waits with the specified flag.
+    pub(crate) fn new(cancelled: Arc<AtomicBool>, worker: thread::JoinHandle<()>) -> TimerWorker {
+        TimerWorker { cancelled, worker }
+    }
+
+    /// cancel wakes the timer up to end it, and returns false if it did not finish until the deadline.
+    pub(crate) fn cancel(self, deadline: Instant) -> bool {
+        self.cancelled.store(true, Ordering::SeqCst);
+        self.worker.thread().unpark();
+        join_until(self.worker, deadline)
+    }
+}
+
+/// wait_tick waits for the specified tick on a timer thread, and returns false if the timer was cancelled meanwhile.
+pub(crate) fn wait_tick(cancelled: &AtomicBool, tick: Duration) -> bool {
+    thread::park_timeout(tick);
+    !cancelled.load(Ordering::SeqCst)
+}
@@ -0,0 +1,55 @@
+// Copyright (C) 2024 Satoshi Konno All rights reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//    http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::dns::{Question, Record, Type};
+use crate::error::Result;
+use crate::packing::rrsets;
+
+/// RecordSigner represents a signer of the advertised record sets, such as an experimental DNSSEC signer for DNS-SD.
+/// RFC 4034: 3. The RRSIG Resource Record
+pub trait RecordSigner: Send {
+    /// sign returns the RRSIG records covering the specified record set, whose records share the name, type and class.
+    fn sign(&self, rrset: &[Record]) -> Result<Vec<Record>>;
+
+    /// dnskeys returns the DNSKEY records which the responder answers for the specified name.
+    /// RFC 4034: 2. The DNSKEY Resource Record
+    fn dnskeys(&self, name: &str) -> Vec<Record>;
+}
+
+/// sign_records returns the specified records followed by the RRSIG records of each record set signed by the specified signer.
+pub fn sign_records(signer: &dyn RecordSigner, records: &[Record]) -> Result<Vec<Record>> {
+    let mut signed = records.to_vec();
+    // The signatures are not record sets themselves, and are never signed again.
+    let unsigned: Vec<Record> = records
+        .iter()
+        .filter(|r| r.typ() != Type::RRSIG)
+        .cloned()
+        .collect();
+    for rrset in rrsets(&unsigned) {
+        signed.extend(signer.sign(&rrset)?);
+    }
+    Ok(signed)
+}
+
+/// dnskey_records returns the DNSKEY records of the specified signer answering the specified question.
+pub fn dnskey_records(signer: &dyn RecordSigner, question: &Question) -> Vec<Record> {
+    if !matches!(question.typ(), Type::DNSKEY | Type::ANY) {
+        return Vec::new();
+    }
+    signer
+        .dnskeys(question.name())
+        .into_iter()
+        .filter(|record| question.matches(record))
+        .collect()
+}
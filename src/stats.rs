@@ -0,0 +1,265 @@
+// Copyright (C) 2024 Satoshi Konno All rights reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//    http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::time::{Duration, Instant};
+
+use crate::histogram::Histogram;
+
+/// METRIC_PACKETS_RECEIVED is the counter name of the received packets.
+pub const METRIC_PACKETS_RECEIVED: &str = "mdns_packets_received_total";
+/// METRIC_PACKETS_PER_SEC is the gauge name of the received packet rate.
+pub const METRIC_PACKETS_PER_SEC: &str = "mdns_packets_per_second";
+/// METRIC_PARSE_FAILURES is the counter name of the packets which could not be parsed.
+pub const METRIC_PARSE_FAILURES: &str = "mdns_parse_failures_total";
+/// METRIC_DUPLICATES_SUPPRESSED is the counter name of the suppressed duplicate packets.
+pub const METRIC_DUPLICATES_SUPPRESSED: &str = "mdns_duplicates_suppressed_total";
+/// METRIC_OFF_LINK_DROPPED is the counter name of the packets dropped since their sources are not on the local links.
+pub const METRIC_OFF_LINK_DROPPED: &str = "mdns_off_link_dropped_total";
+/// METRIC_QUERIES_SENT is the counter name of the sent queries.
+pub const METRIC_QUERIES_SENT: &str = "mdns_queries_sent_total";
+/// METRIC_QUERIES_SUPPRESSED is the counter name of the queries answered from the cache instead of the network.
+pub const METRIC_QUERIES_SUPPRESSED: &str = "mdns_queries_suppressed_total";
+/// METRIC_ANNOUNCEMENTS_SENT is the counter name of the sent announcements.
+pub const METRIC_ANNOUNCEMENTS_SENT: &str = "mdns_announcements_sent_total";
+/// METRIC_ADDITIONALS_REJECTED is the counter name of the additional records rejected by the trust policy.
+pub const METRIC_ADDITIONALS_REJECTED: &str = "mdns_additionals_rejected_total";
+/// METRIC_SERVICES_CACHED is the gauge name of the cached services.
+pub const METRIC_SERVICES_CACHED: &str = "mdns_services_cached";
+/// METRIC_PARSE_TIME is the histogram name of the parse time per packet in microseconds.
+pub const METRIC_PARSE_TIME: &str = "mdns_parse_time_microseconds";
+/// METRIC_PACKET_SIZE is the histogram name of the received packet sizes in bytes.
+pub const METRIC_PACKET_SIZE: &str = "mdns_packet_size_bytes";
+/// METRIC_ANSWERS_PER_RESPONSE is the histogram name of the answer records per received response.
+pub const METRIC_ANSWERS_PER_RESPONSE: &str = "mdns_answers_per_response";
+
+/// PARSE_TIME_BOUNDS are the bucket upper bounds of the parse time histogram in microseconds.
+pub const PARSE_TIME_BOUNDS: [u64; 10] = [1, 2, 5, 10, 20, 50, 100, 200, 500, 1000];
+/// PACKET_SIZE_BOUNDS are the bucket upper bounds of the packet size histogram in bytes.
+pub const PACKET_SIZE_BOUNDS: [u64; 8] = [64, 128, 256, 512, 1024, 1500, 4096, 9000];
+/// ANSWERS_PER_RESPONSE_BOUNDS are the bucket upper bounds of the answers per response histogram.
+pub const ANSWERS_PER_RESPONSE_BOUNDS: [u64; 7] = [0, 1, 2, 4, 8, 16, 32];
+
+const RATE_WINDOW: Duration = Duration::from_secs(1);
+
+/// Stats represents statistics of the discovery state.
+/// When the `metrics` feature is enabled, every update is also emitted through the metrics crate facade.
+#[derive(Debug, Clone)]
+pub struct Stats {
+    packets_received: u64,
+    parse_failures: u64,
+    duplicates_suppressed: u64,
+    off_link_dropped: u64,
+    queries_sent: u64,
+    queries_suppressed: u64,
+    announcements_sent: u64,
+    additionals_rejected: u64,
+    services_cached: usize,
+    parse_time: Histogram,
+    packet_size: Histogram,
+    answers_per_response: Histogram,
+    packets_per_sec: f64,
+    rate_window_start: Instant,
+    rate_window_packets: u64,
+}
+
+impl Stats {
+    /// new creates a new statistics.
+    pub fn new() -> Stats {
+        Stats {
+            packets_received: 0,
+            parse_failures: 0,
+            duplicates_suppressed: 0,
+            off_link_dropped: 0,
+            queries_sent: 0,
+            queries_suppressed: 0,
+            announcements_sent: 0,
+            additionals_rejected: 0,
+            services_cached: 0,
+            parse_time: Histogram::new(&PARSE_TIME_BOUNDS),
+            packet_size: Histogram::new(&PACKET_SIZE_BOUNDS),
+            answers_per_response: Histogram::new(&ANSWERS_PER_RESPONSE_BOUNDS),
+            packets_per_sec: 0.0,
+            rate_window_start: Instant::now(),
+            rate_window_packets: 0,
+        }
+    }
+
+    /// packets_received returns the number of received packets.
+    pub fn packets_received(&self) -> u64 {
+        self.packets_received
+    }
+
+    /// packets_per_sec returns the received packet rate measured over the last window.
+    pub fn packets_per_sec(&self) -> f64 {
+        self.packets_per_sec
+    }
+
+    /// parse_failures returns the number of packets which could not be parsed.
+    pub fn parse_failures(&self) -> u64 {
+        self.parse_failures
+    }
+
+    /// duplicates_suppressed returns the number of byte-identical packets suppressed before parsing.
+    pub fn duplicates_suppressed(&self) -> u64 {
+        self.duplicates_suppressed
+    }
+
+    /// off_link_dropped returns the number of packets dropped since their sources are not on the local links.
+    pub fn off_link_dropped(&self) -> u64 {
+        self.off_link_dropped
+    }
+
+    /// queries_sent returns the number of sent queries.
+    pub fn queries_sent(&self) -> u64 {
+        self.queries_sent
+    }
+
+    /// queries_suppressed returns the number of queries answered from the cache instead of the network.
+    pub fn queries_suppressed(&self) -> u64 {
+        self.queries_suppressed
+    }
+
+    /// announcements_sent returns the number of sent announcements.
+    pub fn announcements_sent(&self) -> u64 {
+        self.announcements_sent
+    }
+
+    /// additionals_rejected returns the number of the additional records rejected by the trust policy before caching.
+    pub fn additionals_rejected(&self) -> u64 {
+        self.additionals_rejected
+    }
+
+    /// services_cached returns the number of cached services.
+    pub fn services_cached(&self) -> usize {
+        self.services_cached
+    }
+
+    /// parse_time returns the histogram of the parse time per packet in microseconds.
+    pub fn parse_time(&self) -> &Histogram {
+        &self.parse_time
+    }
+
+    /// packet_size returns the histogram of the received packet sizes in bytes.
+    pub fn packet_size(&self) -> &Histogram {
+        &self.packet_size
+    }
+
+    /// answers_per_response returns the histogram of the answer records per received response.
+    pub fn answers_per_response(&self) -> &Histogram {
+        &self.answers_per_response
+    }
+
+    /// reset clears the counters and the histograms, and restarts the rate window.
+    /// The number of the cached services is kept since it is the current state rather than a count.
+    pub fn reset(&mut self) {
+        let services_cached = self.services_cached;
+        *self = Stats::new();
+        self.services_cached = services_cached;
+    }
+
+    pub(crate) fn add_packet_received(&mut self) {
+        self.add_packet_received_at(Instant::now());
+    }
+
+    pub(crate) fn add_packet_received_at(&mut self, now: Instant) {
+        self.packets_received += 1;
+        self.rate_window_packets += 1;
+        let elapsed = now.saturating_duration_since(self.rate_window_start);
+        if RATE_WINDOW <= elapsed {
+            self.packets_per_sec = self.rate_window_packets as f64 / elapsed.as_secs_f64();
+            self.rate_window_start = now;
+            self.rate_window_packets = 0;
+            #[cfg(feature = "metrics")]
+            metrics::gauge!(METRIC_PACKETS_PER_SEC).set(self.packets_per_sec);
+        }
+        #[cfg(feature = "metrics")]
+        metrics::counter!(METRIC_PACKETS_RECEIVED).increment(1);
+    }
+
+    pub(crate) fn add_parse_failure(&mut self) {
+        self.parse_failures += 1;
+        #[cfg(feature = "metrics")]
+        metrics::counter!(METRIC_PARSE_FAILURES).increment(1);
+    }
+
+    pub(crate) fn add_duplicate_suppressed(&mut self) {
+        self.duplicates_suppressed += 1;
+        #[cfg(feature = "metrics")]
+        metrics::counter!(METRIC_DUPLICATES_SUPPRESSED).increment(1);
+    }
+
+    pub(crate) fn add_off_link_dropped(&mut self) {
+        self.off_link_dropped += 1;
+        #[cfg(feature = "metrics")]
+        metrics::counter!(METRIC_OFF_LINK_DROPPED).increment(1);
+    }
+
+    pub(crate) fn add_query_sent(&mut self) {
+        self.queries_sent += 1;
+        #[cfg(feature = "metrics")]
+        metrics::counter!(METRIC_QUERIES_SENT).increment(1);
+    }
+
+    pub(crate) fn add_query_suppressed(&mut self) {
+        self.queries_suppressed += 1;
+        #[cfg(feature = "metrics")]
+        metrics::counter!(METRIC_QUERIES_SUPPRESSED).increment(1);
+    }
+
+    pub(crate) fn add_announcement_sent(&mut self) {
+        self.announcements_sent += 1;
+        #[cfg(feature = "metrics")]
+        metrics::counter!(METRIC_ANNOUNCEMENTS_SENT).increment(1);
+    }
+
+    pub(crate) fn add_additionals_rejected(&mut self, n: usize) {
+        if n == 0 {
+            return;
+        }
+        self.additionals_rejected += n as u64;
+        #[cfg(feature = "metrics")]
+        metrics::counter!(METRIC_ADDITIONALS_REJECTED).increment(n as u64);
+    }
+
+    pub(crate) fn add_packet_size(&mut self, size: usize) {
+        self.packet_size.record(size as u64);
+        #[cfg(feature = "metrics")]
+        metrics::histogram!(METRIC_PACKET_SIZE).record(size as f64);
+    }
+
+    pub(crate) fn add_parse_time(&mut self, elapsed: Duration) {
+        let micros = elapsed.as_micros().min(u64::MAX as u128) as u64;
+        self.parse_time.record(micros);
+        #[cfg(feature = "metrics")]
+        metrics::histogram!(METRIC_PARSE_TIME).record(micros as f64);
+    }
+
+    pub(crate) fn add_answers_per_response(&mut self, answers: usize) {
+        self.answers_per_response.record(answers as u64);
+        #[cfg(feature = "metrics")]
+        metrics::histogram!(METRIC_ANSWERS_PER_RESPONSE).record(answers as f64);
+    }
+
+    pub(crate) fn set_services_cached(&mut self, n: usize) {
+        self.services_cached = n;
+        #[cfg(feature = "metrics")]
+        metrics::gauge!(METRIC_SERVICES_CACHED).set(n as f64);
+    }
+}
+
+impl Default for Stats {
+    fn default() -> Self {
+        Self::new()
+    }
+}
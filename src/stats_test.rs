@@ -0,0 +1,82 @@
+// Copyright (C) 2024 Satoshi Konno All rights reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//    http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+#[cfg(test)]
+mod tests {
+
+    use std::time::{Duration, Instant};
+
+    use crate::stats::Stats;
+
+    #[test]
+    fn stats_packets_per_sec() {
+        let now = Instant::now();
+        let mut stats = Stats::new();
+        for _ in 0..4 {
+            stats.add_packet_received_at(now);
+        }
+        // The rate is measured only once the window has passed.
+        assert_eq!(stats.packets_received(), 4);
+        assert_eq!(stats.packets_per_sec(), 0.0);
+
+        stats.add_packet_received_at(now + Duration::from_secs(5));
+        assert_eq!(stats.packets_received(), 5);
+        assert!((stats.packets_per_sec() - 1.0).abs() < 0.1);
+
+        // The next window counts only the packets received after it started.
+        stats.add_packet_received_at(now + Duration::from_secs(6));
+        assert_eq!(stats.packets_per_sec(), 1.0);
+        stats.add_packet_received_at(now + Duration::from_millis(6500));
+        stats.add_packet_received_at(now + Duration::from_secs(7));
+        assert_eq!(stats.packets_per_sec(), 2.0);
+    }
+
+    #[test]
+    fn stats_counters() {
+        let mut stats = Stats::new();
+        stats.add_parse_failure();
+        stats.add_parse_failure();
+        stats.add_query_sent();
+        stats.add_announcement_sent();
+        stats.add_announcement_sent();
+        stats.add_announcement_sent();
+        stats.add_additionals_rejected(0);
+        stats.add_additionals_rejected(2);
+        assert_eq!(stats.parse_failures(), 2);
+        assert_eq!(stats.queries_sent(), 1);
+        assert_eq!(stats.announcements_sent(), 3);
+        assert_eq!(stats.additionals_rejected(), 2);
+        assert_eq!(stats.queries_suppressed(), 0);
+    }
+
+    #[test]
+    fn stats_reset() {
+        let mut stats = Stats::new();
+        stats.add_packet_received();
+        stats.add_packet_size(512);
+        stats.add_parse_failure();
+        stats.add_query_sent();
+        stats.add_announcement_sent();
+        stats.set_services_cached(3);
+
+        stats.reset();
+        assert_eq!(stats.packets_received(), 0);
+        assert_eq!(stats.parse_failures(), 0);
+        assert_eq!(stats.queries_sent(), 0);
+        assert_eq!(stats.announcements_sent(), 0);
+        assert_eq!(stats.packet_size().count(), 0);
+        assert_eq!(stats.packets_per_sec(), 0.0);
+        assert_eq!(stats.services_cached(), 3);
+    }
+}
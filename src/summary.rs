@@ -0,0 +1,115 @@
+// Copyright (C) 2024 Satoshi Konno All rights reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//    http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::BTreeMap;
+use std::fmt;
+
+use crate::service::Service;
+
+/// ServiceSummary represents the counts of the discovered services grouped by the service type and the interface.
+/// A service seen on several interfaces is counted on each of them when the services are interface scoped.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ServiceSummary {
+    total: usize,
+    types: BTreeMap<String, usize>,
+    interfaces: BTreeMap<String, usize>,
+    type_interfaces: BTreeMap<(String, String), usize>,
+}
+
+impl ServiceSummary {
+    /// new creates an empty summary.
+    pub fn new() -> ServiceSummary {
+        ServiceSummary::default()
+    }
+
+    /// from_services creates a new summary of the specified services.
+    pub fn from_services<'a, I>(services: I) -> ServiceSummary
+    where
+        I: IntoIterator<Item = &'a Service>,
+    {
+        let mut summary = ServiceSummary::new();
+        for service in services {
+            summary.add(service);
+        }
+        summary
+    }
+
+    /// add counts the specified service.
+    pub fn add(&mut self, service: &Service) {
+        let typ = service.service_type().to_lowercase();
+        self.total += 1;
+        *self.types.entry(typ.clone()).or_insert(0) += 1;
+        if let Some(interface) = service.interface() {
+            *self.interfaces.entry(interface.to_string()).or_insert(0) += 1;
+            *self
+                .type_interfaces
+                .entry((typ, interface.to_string()))
+                .or_insert(0) += 1;
+        }
+    }
+
+    /// total returns the number of the services.
+    pub fn total(&self) -> usize {
+        self.total
+    }
+
+    /// types returns the numbers of the services by the lowercased service type such as `_http._tcp`.
+    pub fn types(&self) -> &BTreeMap<String, usize> {
+        &self.types
+    }
+
+    /// interfaces returns the numbers of the services by the interface they were received on.
+    pub fn interfaces(&self) -> &BTreeMap<String, usize> {
+        &self.interfaces
+    }
+
+    /// count_of_type returns the number of the services of the specified service type.
+    pub fn count_of_type(&self, service_type: &str) -> usize {
+        self.types
+            .get(&service_type.to_lowercase())
+            .copied()
+            .unwrap_or(0)
+    }
+
+    /// count_on_interface returns the number of the services received on the specified interface.
+    pub fn count_on_interface(&self, interface: &str) -> usize {
+        self.interfaces.get(interface).copied().unwrap_or(0)
+    }
+
+    /// count_of_type_on_interface returns the number of the services of the specified service type received on the specified interface.
+    pub fn count_of_type_on_interface(&self, service_type: &str, interface: &str) -> usize {
+        self.type_interfaces
+            .get(&(service_type.to_lowercase(), interface.to_string()))
+            .copied()
+            .unwrap_or(0)
+    }
+
+    /// unattributed returns the number of the services which could not be attributed to any interface.
+    pub fn unattributed(&self) -> usize {
+        self.total - self.interfaces.values().sum::<usize>()
+    }
+}
+
+impl fmt::Display for ServiceSummary {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "Services : {}", self.total)?;
+        for (typ, n) in &self.types {
+            writeln!(f, "Type : {} {}", typ, n)?;
+        }
+        for (interface, n) in &self.interfaces {
+            writeln!(f, "Interface : {} {}", interface, n)?;
+        }
+        Ok(())
+    }
+}
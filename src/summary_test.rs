@@ -0,0 +1,62 @@
+// Copyright (C) 2024 Satoshi Konno All rights reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//    http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+#[cfg(test)]
+mod tests {
+
+    use crate::dns::{Message, SRVRecord};
+    use crate::{Provenance, Service, ServiceSummary};
+
+    fn service(name: &str, interface: Option<&str>) -> Service {
+        let mut msg = Message::new();
+        msg.add_answer(
+            SRVRecord::new(name, 0, 0, 80, "host.local")
+                .to_record()
+                .unwrap(),
+        );
+        let mut service = Service::from_message(&msg);
+        let mut provenance = Provenance::new("192.168.1.10:5353".parse().unwrap());
+        if let Some(interface) = interface {
+            provenance.set_interface(interface);
+        }
+        service.set_provenance(provenance);
+        service
+    }
+
+    #[test]
+    fn service_summary() {
+        let services = vec![
+            service("a._http._tcp.local", Some("eth0")),
+            service("b._HTTP._tcp.local", Some("eth1")),
+            service("a._ipp._tcp.local", Some("eth0")),
+            service("b._ipp._tcp.local", None),
+        ];
+        let summary = ServiceSummary::from_services(&services);
+        assert_eq!(summary.total(), 4);
+        assert_eq!(summary.types().len(), 2);
+        assert_eq!(summary.count_of_type("_http._tcp"), 2);
+        assert_eq!(summary.count_of_type("_IPP._tcp"), 2);
+        assert_eq!(summary.count_of_type("_printer._tcp"), 0);
+        assert_eq!(summary.count_on_interface("eth0"), 2);
+        assert_eq!(summary.count_on_interface("eth1"), 1);
+        assert_eq!(summary.count_of_type_on_interface("_http._tcp", "eth1"), 1);
+        assert_eq!(summary.count_of_type_on_interface("_ipp._tcp", "eth1"), 0);
+        assert_eq!(summary.unattributed(), 1);
+        assert_eq!(
+            summary.to_string(),
+            "Services : 4\nType : _http._tcp 2\nType : _ipp._tcp 2\nInterface : eth0 2\nInterface : eth1 1\n"
+        );
+        assert_eq!(ServiceSummary::new().total(), 0);
+    }
+}
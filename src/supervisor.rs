@@ -0,0 +1,243 @@
+// Copyright (C) 2024 Satoshi Konno All rights reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//    http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::any::Any;
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+use std::thread;
+
+/// DEFAULT_MAX_RESTARTS is the default number of times a panicked task is restarted before it is given up.
+pub const DEFAULT_MAX_RESTARTS: u32 = 5;
+
+/// TaskHealth represents the health of a supervised task.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum TaskHealth {
+    /// Running is the health of a running task.
+    Running,
+    /// Finished is the health of a task which returned or was cancelled.
+    Finished,
+    /// Failed is the health of a task which panicked more than the restart limit.
+    Failed,
+}
+
+/// TaskStatus represents the status of a supervised task.
+#[derive(Debug, Clone)]
+pub struct TaskStatus {
+    name: String,
+    health: TaskHealth,
+    restarts: u32,
+    last_panic: Option<String>,
+}
+
+impl TaskStatus {
+    /// name returns the name of the task.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// health returns the health of the task.
+    pub fn health(&self) -> TaskHealth {
+        self.health
+    }
+
+    /// restarts returns how many times the task was restarted after a panic.
+    pub fn restarts(&self) -> u32 {
+        self.restarts
+    }
+
+    /// last_panic returns the message of the last panic of the task, if any.
+    pub fn last_panic(&self) -> Option<&str> {
+        self.last_panic.as_deref()
+    }
+}
+
+/// Supervisor represents the owner of the background tasks, which restarts the panicked tasks and reports their health.
+/// The tasks are threads, or tokio tasks with the `tokio` feature.
+#[derive(Clone)]
+pub struct Supervisor {
+    statuses: Arc<Mutex<Vec<TaskStatus>>>,
+    max_restarts: u32,
+}
+
+impl Supervisor {
+    /// new creates a new supervisor without tasks.
+    pub fn new() -> Supervisor {
+        Supervisor {
+            statuses: Arc::new(Mutex::new(Vec::new())),
+            max_restarts: DEFAULT_MAX_RESTARTS,
+        }
+    }
+
+    /// set_max_restarts sets how many times a panicked task is restarted before it is given up.
+    pub fn set_max_restarts(&mut self, max_restarts: u32) {
+        self.max_restarts = max_restarts;
+    }
+
+    /// max_restarts returns how many times a panicked task is restarted before it is given up.
+    pub fn max_restarts(&self) -> u32 {
+        self.max_restarts
+    }
+
+    /// tasks returns the statuses of the tasks which have been spawned.
+    pub fn tasks(&self) -> Vec<TaskStatus> {
+        lock(&self.statuses).clone()
+    }
+
+    /// task returns the status of the specified task.
+    pub fn task(&self, name: &str) -> Option<TaskStatus> {
+        lock(&self.statuses)
+            .iter()
+            .find(|s| s.name == name)
+            .cloned()
+    }
+
+    /// spawn starts the specified task on a thread, and runs it again when it panics.
+    /// A task which was spawned before under the same name is reported as the same task.
+    pub fn spawn<F>(&self, name: &str, task: F) -> thread::JoinHandle<()>
+    where
+        F: Fn() + Send + 'static,
+    {
+        self.set_running(name);
+        let supervisor = self.clone();
+        let name = name.to_string();
+        thread::spawn(move || loop {
+            match panic::catch_unwind(AssertUnwindSafe(&task)) {
+                Ok(()) => {
+                    supervisor.set_health(&name, TaskHealth::Finished);
+                    return;
+                }
+                Err(cause) => {
+                    if !supervisor.restart(&name, cause.as_ref()) {
+                        return;
+                    }
+                }
+            }
+        })
+    }
+
+    /// spawn_async starts the future made by the specified factory as a tokio task, and makes and runs a new future when it panics.
+    /// Aborting the returned handle cancels the task.
+    #[cfg(feature = "tokio")]
+    pub fn spawn_async<F, Fut>(&self, name: &str, factory: F) -> tokio::task::JoinHandle<()>
+    where
+        F: Fn() -> Fut + Send + 'static,
+        Fut: std::future::Future<Output = ()> + Send + 'static,
+    {
+        self.set_running(name);
+        let supervisor = self.clone();
+        let name = name.to_string();
+        tokio::spawn(async move {
+            // The running task is aborted when the supervising task is cancelled.
+            struct AbortOnDrop(tokio::task::JoinHandle<()>);
+            impl Drop for AbortOnDrop {
+                fn drop(&mut self) {
+                    self.0.abort();
+                }
+            }
+            loop {
+                let mut task = AbortOnDrop(tokio::spawn(factory()));
+                match (&mut task.0).await {
+                    Ok(()) => {
+                        supervisor.set_health(&name, TaskHealth::Finished);
+                        return;
+                    }
+                    Err(e) if e.is_panic() => {
+                        if !supervisor.restart(&name, e.into_panic().as_ref()) {
+                            return;
+                        }
+                    }
+                    Err(_) => {
+                        supervisor.set_health(&name, TaskHealth::Finished);
+                        return;
+                    }
+                }
+            }
+        })
+    }
+
+    /// set_finished reports the specified task as finished, such as when it was cancelled from outside.
+    #[cfg(feature = "tokio")]
+    pub(crate) fn set_finished(&self, name: &str) {
+        self.set_health(name, TaskHealth::Finished);
+    }
+
+    fn set_running(&self, name: &str) {
+        let mut statuses = lock(&self.statuses);
+        match statuses.iter_mut().find(|s| s.name == name) {
+            Some(status) => status.health = TaskHealth::Running,
+            None => statuses.push(TaskStatus {
+                name: name.to_string(),
+                health: TaskHealth::Running,
+                restarts: 0,
+                last_panic: None,
+            }),
+        }
+    }
+
+    fn set_health(&self, name: &str, health: TaskHealth) {
+        if let Some(status) = lock(&self.statuses).iter_mut().find(|s| s.name == name) {
+            status.health = health;
+        }
+    }
+
+    // restart records the panic of the specified task, and returns false if the task has used up the restarts.
+    fn restart(&self, name: &str, cause: &(dyn Any + Send)) -> bool {
+        let message = panic_message(cause);
+        let mut statuses = lock(&self.statuses);
+        let status = match statuses.iter_mut().find(|s| s.name == name) {
+            Some(status) => status,
+            None => return false,
+        };
+        status.last_panic = Some(message.clone());
+        if self.max_restarts <= status.restarts {
+            log::error!("task {} failed: {}", name, message);
+            status.health = TaskHealth::Failed;
+            return false;
+        }
+        log::warn!("task {} panicked and is restarted: {}", name, message);
+        status.restarts += 1;
+        true
+    }
+}
+
+impl Default for Supervisor {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// lock_recovered locks the specified mutex even if a panicked task poisoned it, so the restarted task can continue.
+pub(crate) fn lock_recovered<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    match mutex.lock() {
+        Ok(guard) => guard,
+        Err(poisoned) => {
+            mutex.clear_poison();
+            poisoned.into_inner()
+        }
+    }
+}
+
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+fn panic_message(cause: &(dyn Any + Send)) -> String {
+    if let Some(message) = cause.downcast_ref::<&str>() {
+        return message.to_string();
+    }
+    if let Some(message) = cause.downcast_ref::<String>() {
+        return message.clone();
+    }
+    "unknown panic".to_string()
+}
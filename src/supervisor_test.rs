@@ -0,0 +1,83 @@
+// Copyright (C) 2024 Satoshi Konno All rights reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//    http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+#[cfg(test)]
+mod tests {
+
+    use std::sync::atomic::{AtomicU32, Ordering};
+    use std::sync::Arc;
+
+    use crate::{Supervisor, TaskHealth};
+
+    #[test]
+    fn supervisor_restart() {
+        let supervisor = Supervisor::new();
+        let runs = Arc::new(AtomicU32::new(0));
+        let task_runs = runs.clone();
+        let worker = supervisor.spawn("flaky", move || {
+            if task_runs.fetch_add(1, Ordering::SeqCst) < 2 {
+                panic!("flaky failure");
+            }
+        });
+        assert!(worker.join().is_ok());
+        assert_eq!(runs.load(Ordering::SeqCst), 3);
+
+        let status = supervisor.task("flaky").unwrap();
+        assert_eq!(status.health(), TaskHealth::Finished);
+        assert_eq!(status.restarts(), 2);
+        assert_eq!(status.last_panic(), Some("flaky failure"));
+        assert!(supervisor.task("unknown").is_none());
+    }
+
+    #[test]
+    fn supervisor_failure() {
+        let mut supervisor = Supervisor::new();
+        supervisor.set_max_restarts(1);
+        let worker = supervisor.spawn("broken", || panic!("broken {}", 42));
+        assert!(worker.join().is_ok());
+
+        let tasks = supervisor.tasks();
+        assert_eq!(tasks.len(), 1);
+        assert_eq!(tasks[0].name(), "broken");
+        assert_eq!(tasks[0].health(), TaskHealth::Failed);
+        assert_eq!(tasks[0].restarts(), 1);
+        assert_eq!(tasks[0].last_panic(), Some("broken 42"));
+    }
+
+    #[cfg(feature = "tokio")]
+    #[test]
+    fn supervisor_async() {
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .build()
+            .unwrap();
+        let supervisor = Supervisor::new();
+        let runs = Arc::new(AtomicU32::new(0));
+        let task_runs = runs.clone();
+        runtime.block_on(async {
+            let task = supervisor.spawn_async("flaky", move || {
+                let runs = task_runs.clone();
+                async move {
+                    if runs.fetch_add(1, Ordering::SeqCst) < 1 {
+                        panic!("async failure");
+                    }
+                }
+            });
+            assert!(task.await.is_ok());
+        });
+        assert_eq!(runs.load(Ordering::SeqCst), 2);
+        let status = supervisor.task("flaky").unwrap();
+        assert_eq!(status.health(), TaskHealth::Finished);
+        assert_eq!(status.restarts(), 1);
+    }
+}
@@ -0,0 +1,202 @@
+// Copyright (C) 2024 Satoshi Konno All rights reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//    http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::io;
+use std::net::IpAddr;
+
+use cybergarage::net::{MulticastServer, ObserverObject, Packet};
+
+use crate::default::{MULTICAST_V4_ADDR, MULTICAST_V6_ADDR};
+use crate::family::AddressFamily;
+use crate::net::Interface;
+
+/// Route represents where a packet is sent, which is the multicast group of an address family on an interface,
+/// or on all interfaces of the family if the interface is not known.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Route {
+    family: AddressFamily,
+    interface: Option<String>,
+}
+
+impl Route {
+    /// new creates a new route of the specified family and interface.
+    pub fn new(family: AddressFamily, interface: Option<&str>) -> Route {
+        Route {
+            family,
+            interface: interface.map(|name| name.to_string()),
+        }
+    }
+
+    /// family returns the address family of the route.
+    pub fn family(&self) -> AddressFamily {
+        self.family
+    }
+
+    /// interface returns the interface name of the route, or None if the route is over all interfaces of the family.
+    pub fn interface(&self) -> Option<&str> {
+        self.interface.as_deref()
+    }
+}
+
+// TransportServer represents a multicast server joined to the group of a family on an interface.
+struct TransportServer {
+    interface: String,
+    family: AddressFamily,
+    server: MulticastServer,
+}
+
+/// MulticastTransport represents the multicast servers per interface and address family,
+/// so a response can be sent on the family and interface its query arrived on instead of on every server.
+pub struct MulticastTransport {
+    servers: Vec<TransportServer>,
+}
+
+impl MulticastTransport {
+    /// new creates a new transport which has no server until it is started.
+    pub fn new() -> MulticastTransport {
+        MulticastTransport {
+            servers: Vec::new(),
+        }
+    }
+
+    /// start joins the multicast group of each specified family on each of the specified interfaces which can run multicast DNS,
+    /// and delivers the received packets to the specified observer.
+    /// An interface which cannot join a group is skipped, and an error is returned only if no group is joined.
+    pub fn start(
+        &mut self,
+        ifaces: &[Interface],
+        families: &[AddressFamily],
+        port: u16,
+        observer: ObserverObject,
+    ) -> io::Result<()> {
+        if self.is_running() {
+            return Ok(());
+        }
+        for iface in ifaces
+            .iter()
+            .filter(|i| i.is_mdns_capable() && !i.is_loopback())
+        {
+            for family in families {
+                let ifaddr = match iface
+                    .ipaddrs()
+                    .into_iter()
+                    .find(|addr| AddressFamily::from_ipaddr(addr) == *family)
+                {
+                    Some(ifaddr) => ifaddr,
+                    None => continue,
+                };
+                let mut server = MulticastServer::new();
+                if let Err(e) = server.bind(group_addr(*family), port, ifaddr) {
+                    log::warn!("{} group not joined on {}: {:?}", family, iface.name(), e);
+                    continue;
+                }
+                if let Err(e) = server.start() {
+                    log::warn!("{} server not started on {}: {:?}", family, iface.name(), e);
+                    let _ = server.stop();
+                    continue;
+                }
+                server.add_observer(observer.clone());
+                self.servers.push(TransportServer {
+                    interface: iface.name().to_string(),
+                    family: *family,
+                    server,
+                });
+            }
+        }
+        if !self.is_running() {
+            return Err(io::Error::new(
+                io::ErrorKind::AddrNotAvailable,
+                "no multicast group joined",
+            ));
+        }
+        Ok(())
+    }
+
+    /// is_running returns true if any multicast group is joined.
+    pub fn is_running(&self) -> bool {
+        !self.servers.is_empty()
+    }
+
+    /// routes returns the routes of the joined multicast groups.
+    pub fn routes(&self) -> Vec<Route> {
+        self.servers
+            .iter()
+            .map(|s| Route::new(s.family, Some(&s.interface)))
+            .collect()
+    }
+
+    /// notify sends the specified packet to the multicast groups of every family on every interface, such as an announcement.
+    pub fn notify(&self, pkt: &Packet) -> io::Result<()> {
+        for server in &self.servers {
+            server.server.notify(pkt)?;
+        }
+        Ok(())
+    }
+
+    /// notify_route sends the specified packet to the multicast group of the family of the specified route
+    /// on its interface, or on every interface of the family if the route has no interface or the interface has no server.
+    pub fn notify_route(&self, pkt: &Packet, route: &Route) -> io::Result<()> {
+        let routes = self.routes();
+        for n in select_routes(&routes, route) {
+            self.servers[n].server.notify(pkt)?;
+        }
+        Ok(())
+    }
+
+    /// stop leaves all multicast groups.
+    pub fn stop(&mut self) -> io::Result<()> {
+        for server in &self.servers {
+            server.server.stop()?;
+        }
+        self.servers.clear();
+        Ok(())
+    }
+}
+
+impl Default for MulticastTransport {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Drop for MulticastTransport {
+    fn drop(&mut self) {
+        let _ = self.stop();
+    }
+}
+
+/// select_routes returns the indices of the specified routes which a packet of the specified route is sent on,
+/// which are the routes of the same family on the same interface, or all routes of the family if none is on the interface.
+pub fn select_routes(routes: &[Route], route: &Route) -> Vec<usize> {
+    let family: Vec<usize> = (0..routes.len())
+        .filter(|n| routes[*n].family == route.family)
+        .collect();
+    let on_interface: Vec<usize> = family
+        .iter()
+        .copied()
+        .filter(|n| route.interface.is_some() && routes[*n].interface == route.interface)
+        .collect();
+    if on_interface.is_empty() {
+        family
+    } else {
+        on_interface
+    }
+}
+
+fn group_addr(family: AddressFamily) -> IpAddr {
+    match family {
+        AddressFamily::IPv4 => MULTICAST_V4_ADDR,
+        AddressFamily::IPv6 => MULTICAST_V6_ADDR,
+    }
+}
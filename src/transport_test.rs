@@ -0,0 +1,63 @@
+// Copyright (C) 2024 Satoshi Konno All rights reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//    http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+#[cfg(test)]
+mod tests {
+
+    use crate::transport::select_routes;
+    use crate::{AddressFamily, MulticastTransport, Route};
+
+    #[test]
+    fn transport_select_routes() {
+        let routes = vec![
+            Route::new(AddressFamily::IPv4, Some("eth0")),
+            Route::new(AddressFamily::IPv6, Some("eth0")),
+            Route::new(AddressFamily::IPv4, Some("wlan0")),
+            Route::new(AddressFamily::IPv6, Some("wlan0")),
+        ];
+        struct Test {
+            route: Route,
+            expected: Vec<usize>,
+        }
+        let tests = vec![
+            Test {
+                route: Route::new(AddressFamily::IPv4, Some("eth0")),
+                expected: vec![0],
+            },
+            Test {
+                route: Route::new(AddressFamily::IPv6, Some("wlan0")),
+                expected: vec![3],
+            },
+            Test {
+                route: Route::new(AddressFamily::IPv6, None),
+                expected: vec![1, 3],
+            },
+            Test {
+                route: Route::new(AddressFamily::IPv4, Some("tun0")),
+                expected: vec![0, 2],
+            },
+        ];
+        for test in tests {
+            assert_eq!(select_routes(&routes, &test.route), test.expected);
+        }
+        assert!(select_routes(&routes[..1], &Route::new(AddressFamily::IPv6, None)).is_empty());
+
+        let transport = MulticastTransport::new();
+        assert!(!transport.is_running());
+        assert!(transport.routes().is_empty());
+        let route = Route::new(AddressFamily::IPv4, Some("eth0"));
+        assert_eq!(route.family(), AddressFamily::IPv4);
+        assert_eq!(route.interface(), Some("eth0"));
+    }
+}
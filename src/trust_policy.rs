@@ -0,0 +1,134 @@
+// Copyright (C) 2024 Satoshi Konno All rights reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//    http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::dns::{Message, PTRRecord, Record, SRVRecord, Type};
+
+/// TrustPolicy represents the sanity checks which the additional records of a received message must pass before they are cached.
+/// RFC 6762: 11. Source Address Check
+/// The additional records are unsolicited, so only the records relevant to the answers and from an on-link source are trusted.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TrustPolicy {
+    relevant_only: bool,
+    on_link_only: bool,
+}
+
+impl TrustPolicy {
+    /// new creates a new policy which trusts only the relevant additional records from the on-link sources.
+    pub fn new() -> TrustPolicy {
+        TrustPolicy {
+            relevant_only: true,
+            on_link_only: true,
+        }
+    }
+
+    /// permissive creates a new policy which trusts all additional records.
+    pub fn permissive() -> TrustPolicy {
+        TrustPolicy {
+            relevant_only: false,
+            on_link_only: false,
+        }
+    }
+
+    /// set_relevant_only sets whether only the additional records relevant to the answers are trusted.
+    pub fn set_relevant_only(&mut self, enabled: bool) {
+        self.relevant_only = enabled;
+    }
+
+    /// relevant_only returns true if only the additional records relevant to the answers are trusted.
+    pub fn relevant_only(&self) -> bool {
+        self.relevant_only
+    }
+
+    /// set_on_link_only sets whether the additional records from the sources off the local links are rejected.
+    pub fn set_on_link_only(&mut self, enabled: bool) {
+        self.on_link_only = enabled;
+    }
+
+    /// on_link_only returns true if the additional records from the sources off the local links are rejected.
+    pub fn on_link_only(&self) -> bool {
+        self.on_link_only
+    }
+
+    /// is_relevant returns true if the specified additional record is about a name of the answers of the specified message,
+    /// or a name they point to such as the SRV and TXT records of a PTR answer and the addresses of the SRV targets.
+    /// RFC 6763: 12. Populating the DNS Additional Record Section
+    pub fn is_relevant(&self, msg: &Message, additional: &Record) -> bool {
+        is_relevant_name(&relevant_names(msg), additional.name())
+    }
+
+    /// apply removes the untrusted additional records of the specified message received from an on-link source or not,
+    /// and returns the number of the removed records. The OPT record is not a resource record and is always kept.
+    pub fn apply(&self, msg: &mut Message, on_link: bool) -> usize {
+        if !self.relevant_only && (on_link || !self.on_link_only) {
+            return 0;
+        }
+        let names = relevant_names(msg);
+        let before = msg.additionals().len();
+        msg.retain_additionals(|additional| {
+            if additional.typ() == Type::OPT {
+                return true;
+            }
+            if self.on_link_only && !on_link {
+                return false;
+            }
+            !self.relevant_only || is_relevant_name(&names, additional.name())
+        });
+        before - msg.additionals().len()
+    }
+}
+
+impl Default for TrustPolicy {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// relevant_names returns the names of the answers of the specified message, and the names their data point to.
+fn relevant_names(msg: &Message) -> Vec<String> {
+    let mut names: Vec<String> = Vec::new();
+    for answer in msg.answers() {
+        names.push(answer.name().to_string());
+        if let Some(target) = target_name(answer) {
+            names.push(target);
+        }
+    }
+    // The SRV records of the PTR answers are in the additional section, and their targets are relevant too.
+    for additional in msg.additionals() {
+        if additional.typ() == Type::SRV && is_relevant_name(&names, additional.name()) {
+            if let Some(target) = target_name(additional) {
+                names.push(target);
+            }
+        }
+    }
+    names
+}
+
+fn target_name(record: &Record) -> Option<String> {
+    match record.typ() {
+        Type::PTR => PTRRecord::from_record(record)
+            .ok()
+            .map(|ptr| ptr.domain_name().to_string()),
+        Type::SRV => SRVRecord::from_record(record)
+            .ok()
+            .map(|srv| srv.target().to_string()),
+        _ => None,
+    }
+}
+
+fn is_relevant_name(names: &[String], name: &str) -> bool {
+    let name = name.trim_end_matches('.');
+    names
+        .iter()
+        .any(|n| n.trim_end_matches('.').eq_ignore_ascii_case(name))
+}
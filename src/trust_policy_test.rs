@@ -0,0 +1,80 @@
+// Copyright (C) 2024 Satoshi Konno All rights reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//    http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+#[cfg(test)]
+mod tests {
+
+    use crate::dns::{Message, OPTRecord};
+    use crate::fixture_test::{a_record, ptr_record, srv_record};
+    use crate::TrustPolicy;
+
+    fn response() -> Message {
+        let mut msg = Message::new();
+        msg.add_answer(ptr_record("_http._tcp.local", "a._http._tcp.local"));
+        msg.add_additional(srv_record("a._http._tcp.local", 80, "host.local."));
+        msg.add_additional(a_record("HOST.local.", [192, 168, 1, 10]));
+        msg.add_additional(a_record("victim.local", [192, 168, 1, 10]));
+        msg.add_additional(srv_record("b._http._tcp.local", 80, "other.local"));
+        msg.add_additional(a_record("other.local", [192, 168, 1, 10]));
+        msg.set_opt(&OPTRecord::new(1440)).unwrap();
+        msg
+    }
+
+    #[test]
+    fn trust_policy_relevant_only() {
+        let policy = TrustPolicy::new();
+        let msg = response();
+        assert!(policy.is_relevant(&msg, &msg.additionals()[0]));
+        assert!(policy.is_relevant(&msg, &msg.additionals()[1]));
+        assert!(!policy.is_relevant(&msg, &msg.additionals()[2]));
+
+        let mut msg = response();
+        assert_eq!(policy.apply(&mut msg, true), 3);
+        let names: Vec<_> = msg.additionals().iter().map(|r| r.name()).collect();
+        assert_eq!(names, vec!["a._http._tcp.local", "HOST.local.", ""]);
+        assert_eq!(msg.ar_count(), 3);
+        assert!(msg.opt().is_some());
+    }
+
+    #[test]
+    fn trust_policy_on_link_only() {
+        let policy = TrustPolicy::new();
+        let mut msg = response();
+        assert_eq!(policy.apply(&mut msg, false), 5);
+        assert_eq!(msg.additionals().len(), 1);
+        assert!(msg.opt().is_some());
+        assert_eq!(msg.answers().len(), 1);
+
+        let mut policy = TrustPolicy::new();
+        policy.set_relevant_only(false);
+        let mut msg = response();
+        assert_eq!(policy.apply(&mut msg, true), 0);
+        assert_eq!(policy.apply(&mut msg, false), 5);
+
+        let mut policy = TrustPolicy::new();
+        policy.set_on_link_only(false);
+        let mut msg = response();
+        assert_eq!(policy.apply(&mut msg, false), 3);
+    }
+
+    #[test]
+    fn trust_policy_permissive() {
+        let policy = TrustPolicy::permissive();
+        assert!(!policy.relevant_only());
+        assert!(!policy.on_link_only());
+        let mut msg = response();
+        assert_eq!(policy.apply(&mut msg, false), 0);
+        assert_eq!(msg.additionals().len(), 6);
+    }
+}
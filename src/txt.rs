@@ -0,0 +1,181 @@
+// Copyright (C) 2024 Satoshi Konno All rights reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//    http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::HashMap;
+
+use crate::error::{Error, Result};
+
+/// TXTVERS_KEY is the key of the TXT record format version.
+/// RFC 6763: 6.7. Version Tag
+pub const TXTVERS_KEY: &str = "txtvers";
+
+/// KEY_VALUE_SEPARATOR is the separator between a key and its value.
+/// RFC 6763: 6.3. DNS TXT Record Format Rules for use in DNS-SD
+pub const KEY_VALUE_SEPARATOR: char = '=';
+
+/// KEY_RECOMMENDED_MAX_LEN is the recommended maximum length of a key.
+/// RFC 6763: 6.4. Rules for Keys in DNS-SD Key/Value Pairs
+/// The key SHOULD be no more than nine characters long.
+pub const KEY_RECOMMENDED_MAX_LEN: usize = 9;
+
+/// STRING_MAX_LEN is the maximum length of a single TXT string.
+pub const STRING_MAX_LEN: usize = 255;
+
+/// RECORD_PREFERRED_MAX_SIZE is the size which a typical TXT record is intended to stay within.
+/// RFC 6763: 6.2. DNS-SD TXT Record Size
+pub const RECORD_PREFERRED_MAX_SIZE: usize = 400;
+
+/// RECORD_RECOMMENDED_MAX_SIZE is the size above which a TXT record is NOT RECOMMENDED,
+/// because the response no longer fits in a single Ethernet packet with the other records.
+/// RFC 6763: 6.2. DNS-SD TXT Record Size
+pub const RECORD_RECOMMENDED_MAX_SIZE: usize = 1300;
+
+/// is_valid_key returns true if the specified key is a valid DNS-SD key.
+/// RFC 6763: 6.4. Rules for Keys in DNS-SD Key/Value Pairs
+/// The key MUST be at least one character and consists of printable US-ASCII characters (0x20-0x7E), excluding '=' (0x3D).
+pub fn is_valid_key(key: &str) -> bool {
+    if key.is_empty() {
+        return false;
+    }
+    key.bytes()
+        .all(|b| (0x20..=0x7E).contains(&b) && b != KEY_VALUE_SEPARATOR as u8)
+}
+
+/// is_recommended_key returns true if the specified key is valid and no longer than the recommended length.
+pub fn is_recommended_key(key: &str) -> bool {
+    is_valid_key(key) && key.len() <= KEY_RECOMMENDED_MAX_LEN
+}
+
+/// validate_key returns an error if the specified key is not a valid DNS-SD key.
+pub fn validate_key(key: &str) -> Result<()> {
+    if !is_valid_key(key) {
+        return Err(Error::from_string(&format!("Invalid TXT key: {:?}", key)));
+    }
+    Ok(())
+}
+
+/// key_eq returns true if the specified keys are equal.
+/// RFC 6763: 6.4. Rules for Keys in DNS-SD Key/Value Pairs
+/// Case is ignored when interpreting a key.
+pub fn key_eq(a: &str, b: &str) -> bool {
+    a.eq_ignore_ascii_case(b)
+}
+
+/// parse_attribute splits the specified TXT string into a key and a value.
+/// A string without the separator is a boolean attribute and has an empty value.
+pub fn parse_attribute(s: &str) -> (String, String) {
+    let mut kv = s.splitn(2, KEY_VALUE_SEPARATOR);
+    let key = kv.next().unwrap_or("").to_string();
+    let value = kv.next().unwrap_or("").to_string();
+    (key, value)
+}
+
+/// parse_attribute_bytes splits the specified TXT string into a key and a value of any bytes.
+/// RFC 6763: 6.5. Rules for Values in DNS-SD Key/Value Pairs
+/// The value is opaque binary data, and only the key is text.
+pub fn parse_attribute_bytes(s: &[u8]) -> (String, Vec<u8>) {
+    let sep = KEY_VALUE_SEPARATOR as u8;
+    match s.iter().position(|b| *b == sep) {
+        Some(n) => (
+            String::from_utf8_lossy(&s[..n]).to_string(),
+            s[n + 1..].to_vec(),
+        ),
+        None => (String::from_utf8_lossy(s).to_string(), Vec::new()),
+    }
+}
+
+/// format_attribute returns the TXT string of the specified key and value.
+pub fn format_attribute(key: &str, value: &str) -> String {
+    if value.is_empty() {
+        return key.to_string();
+    }
+    format!("{}{}{}", key, KEY_VALUE_SEPARATOR, value)
+}
+
+/// format_attribute_bytes returns the TXT string of the specified key and value of any bytes.
+pub fn format_attribute_bytes(key: &str, value: &[u8]) -> Vec<u8> {
+    if value.is_empty() {
+        return key.as_bytes().to_vec();
+    }
+    [key.as_bytes(), &[KEY_VALUE_SEPARATOR as u8], value].concat()
+}
+
+/// attribute returns the value of the specified key, ignoring case.
+pub fn attribute<'a, V>(attrs: &'a HashMap<String, V>, key: &str) -> Option<&'a V> {
+    if let Some(value) = attrs.get(key) {
+        return Some(value);
+    }
+    attrs
+        .iter()
+        .find(|(k, _)| key_eq(k, key))
+        .map(|(_, value)| value)
+}
+
+/// txtvers returns the TXT record format version of the specified attributes.
+pub fn txtvers(attrs: &HashMap<String, String>) -> Option<u32> {
+    attribute(attrs, TXTVERS_KEY).and_then(|v| v.parse::<u32>().ok())
+}
+
+/// canonical_order returns the specified keys in the serialization order.
+/// The txtvers key is placed first as RFC 6763 6.7 requires, the other keys keep their order,
+/// and duplicated keys are dropped because only the first occurrence is meaningful (RFC 6763 6.4).
+pub fn canonical_order<'a, I>(keys: I) -> Vec<&'a str>
+where
+    I: IntoIterator<Item = &'a str>,
+{
+    let mut ordered: Vec<&'a str> = Vec::new();
+    for key in keys {
+        if ordered.iter().any(|k| key_eq(k, key)) {
+            continue;
+        }
+        if key_eq(key, TXTVERS_KEY) {
+            ordered.insert(0, key);
+        } else {
+            ordered.push(key);
+        }
+    }
+    ordered
+}
+
+/// encode_attributes returns the TXT record data of the specified attributes in the specified order.
+/// RFC 6763: 6.1. General Format Rules for DNS TXT Records
+/// An empty TXT record contains a single zero byte.
+pub fn encode_attributes(attrs: &[(String, String)]) -> Result<Vec<u8>> {
+    let attrs: Vec<(String, Vec<u8>)> = attrs
+        .iter()
+        .map(|(key, value)| (key.clone(), value.as_bytes().to_vec()))
+        .collect();
+    encode_attribute_bytes(&attrs)
+}
+
+/// encode_attribute_bytes returns the TXT record data of the specified attributes of any bytes in the specified order.
+pub fn encode_attribute_bytes(attrs: &[(String, Vec<u8>)]) -> Result<Vec<u8>> {
+    let mut data = Vec::new();
+    for (key, value) in attrs {
+        let s = format_attribute_bytes(key, value);
+        if STRING_MAX_LEN < s.len() {
+            return Err(Error::from_string(&format!(
+                "TXT string too long ({} bytes): {}",
+                s.len(),
+                key
+            )));
+        }
+        data.push(s.len() as u8);
+        data.extend_from_slice(&s);
+    }
+    if data.is_empty() {
+        data.push(0);
+    }
+    Ok(data)
+}
@@ -0,0 +1,236 @@
+// Copyright (C) 2024 Satoshi Konno All rights reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//    http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::HashMap;
+use std::fmt;
+
+use crate::txt;
+
+/// ValueValidator returns true if the specified value of a TXT key is valid.
+pub type ValueValidator = fn(&str) -> bool;
+
+/// TxtViolation represents a TXT attribute which does not satisfy the schema of the service type.
+#[derive(Debug, Clone, PartialEq)]
+pub enum TxtViolation {
+    /// Missing is a required key which is not present.
+    Missing(String),
+    /// Invalid is a key whose value is rejected by the validator of the key.
+    Invalid(String, String),
+}
+
+impl fmt::Display for TxtViolation {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            TxtViolation::Missing(key) => write!(f, "missing key {}", key),
+            TxtViolation::Invalid(key, value) => write!(f, "invalid value of {}: {:?}", key, value),
+        }
+    }
+}
+
+// KeyRule represents the rule of a TXT key.
+#[derive(Clone)]
+struct KeyRule {
+    key: String,
+    required: bool,
+    validator: Option<ValueValidator>,
+}
+
+/// TxtSchema represents the TXT attributes expected for a service type.
+#[derive(Clone)]
+pub struct TxtSchema {
+    service_type: String,
+    rules: Vec<KeyRule>,
+}
+
+impl TxtSchema {
+    /// new creates an empty schema of the specified service type such as `_http._tcp`.
+    pub fn new(service_type: &str) -> TxtSchema {
+        TxtSchema {
+            service_type: service_type.to_string(),
+            rules: Vec::new(),
+        }
+    }
+
+    /// service_type returns the service type of the schema.
+    pub fn service_type(&self) -> &str {
+        &self.service_type
+    }
+
+    /// required adds the specified key which must be present.
+    pub fn required(self, key: &str) -> TxtSchema {
+        self.rule(key, true, None)
+    }
+
+    /// required_with adds the specified key which must be present with a value accepted by the validator.
+    pub fn required_with(self, key: &str, validator: ValueValidator) -> TxtSchema {
+        self.rule(key, true, Some(validator))
+    }
+
+    /// optional_with adds the specified key whose value must be accepted by the validator if present.
+    pub fn optional_with(self, key: &str, validator: ValueValidator) -> TxtSchema {
+        self.rule(key, false, Some(validator))
+    }
+
+    fn rule(mut self, key: &str, required: bool, validator: Option<ValueValidator>) -> TxtSchema {
+        self.rules.push(KeyRule {
+            key: key.to_string(),
+            required,
+            validator,
+        });
+        self
+    }
+
+    /// validate returns the violations of the specified attributes, or an empty list if they satisfy the schema.
+    pub fn validate(&self, attrs: &HashMap<String, String>) -> Vec<TxtViolation> {
+        let mut violations = Vec::new();
+        for rule in &self.rules {
+            match txt::attribute(attrs, &rule.key) {
+                Some(value) => {
+                    if let Some(validator) = rule.validator {
+                        if !validator(value) {
+                            violations.push(TxtViolation::Invalid(rule.key.clone(), value.clone()));
+                        }
+                    }
+                }
+                None => {
+                    if rule.required {
+                        violations.push(TxtViolation::Missing(rule.key.clone()));
+                    }
+                }
+            }
+        }
+        violations
+    }
+
+    /// http returns the schema of `_http._tcp`.
+    /// The optional path key is the path of the resource on the server, which starts with a slash.
+    pub fn http() -> TxtSchema {
+        TxtSchema::new("_http._tcp").optional_with("path", |v| v.starts_with('/'))
+    }
+
+    /// ipp returns the schema of `_ipp._tcp` of the PWG 5100.14 IPP Everywhere.
+    pub fn ipp() -> TxtSchema {
+        TxtSchema::new("_ipp._tcp")
+            .required_with(txt::TXTVERS_KEY, |v| v == "1")
+            .required("rp")
+            .optional_with("qtotal", is_u32)
+            .optional_with("priority", |v| {
+                v.parse::<u8>().map(|n| n <= 99).unwrap_or(false)
+            })
+    }
+
+    /// hap returns the schema of `_hap._tcp` of the HomeKit Accessory Protocol.
+    pub fn hap() -> TxtSchema {
+        TxtSchema::new("_hap._tcp")
+            .required_with("c#", |v| v.parse::<u32>().map(|n| 1 <= n).unwrap_or(false))
+            .required_with("id", is_device_id)
+            .required("md")
+            .required_with("s#", |v| v == "1")
+            .required_with("sf", |v| v == "0" || v == "1")
+            .required_with("ci", |v| v.parse::<u16>().map(|n| 1 <= n).unwrap_or(false))
+            .optional_with("ff", is_u32)
+    }
+
+    /// matterc returns the schema of `_matterc._udp` of the Matter commissionable nodes.
+    pub fn matterc() -> TxtSchema {
+        TxtSchema::new("_matterc._udp")
+            .required_with("D", |v| {
+                v.parse::<u16>().map(|n| n <= 0xFFF).unwrap_or(false)
+            })
+            .optional_with("CM", |v| matches!(v, "0" | "1" | "2"))
+            .optional_with("VP", is_vendor_product)
+            .optional_with("DT", is_u32)
+            .optional_with("DN", |v| v.len() <= 32)
+            .optional_with("SII", is_u32)
+            .optional_with("SAI", is_u32)
+            .optional_with("PH", is_u32)
+            .optional_with("PI", |v| v.len() <= 128)
+    }
+}
+
+/// TxtSchemas represents the registered schemas of the service types.
+#[derive(Clone)]
+pub struct TxtSchemas {
+    schemas: HashMap<String, TxtSchema>,
+}
+
+impl TxtSchemas {
+    /// new creates an empty registry.
+    pub fn new() -> TxtSchemas {
+        TxtSchemas {
+            schemas: HashMap::new(),
+        }
+    }
+
+    /// with_defaults creates a new registry of the shipped schemas for `_http`, `_ipp`, `_hap` and `_matterc`.
+    pub fn with_defaults() -> TxtSchemas {
+        let mut schemas = TxtSchemas::new();
+        for schema in [
+            TxtSchema::http(),
+            TxtSchema::ipp(),
+            TxtSchema::hap(),
+            TxtSchema::matterc(),
+        ] {
+            schemas.register(schema);
+        }
+        schemas
+    }
+
+    /// register registers the specified schema, replacing the schema of the same service type.
+    pub fn register(&mut self, schema: TxtSchema) {
+        self.schemas
+            .insert(schema.service_type().to_lowercase(), schema);
+    }
+
+    /// schema returns the schema of the specified service type.
+    pub fn schema(&self, service_type: &str) -> Option<&TxtSchema> {
+        self.schemas.get(&service_type.to_lowercase())
+    }
+
+    /// validate returns the violations of the specified attributes of the service type, which has none without a schema.
+    pub fn validate(
+        &self,
+        service_type: &str,
+        attrs: &HashMap<String, String>,
+    ) -> Vec<TxtViolation> {
+        match self.schema(service_type) {
+            Some(schema) => schema.validate(attrs),
+            None => Vec::new(),
+        }
+    }
+}
+
+impl Default for TxtSchemas {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn is_u32(v: &str) -> bool {
+    v.parse::<u32>().is_ok()
+}
+
+// is_device_id returns true for a device ID formatted like a MAC address such as `AA:BB:CC:DD:EE:FF`.
+fn is_device_id(v: &str) -> bool {
+    let octets: Vec<&str> = v.split(':').collect();
+    octets.len() == 6
+        && octets
+            .iter()
+            .all(|o| o.len() == 2 && o.chars().all(|c| c.is_ascii_hexdigit()))
+}
+
+// is_vendor_product returns true for `vendor+product` or `vendor` of decimal IDs.
+fn is_vendor_product(v: &str) -> bool {
+    v.split('+').count() <= 2 && v.split('+').all(|id| id.parse::<u16>().is_ok())
+}
@@ -0,0 +1,106 @@
+// Copyright (C) 2024 Satoshi Konno All rights reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//    http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+#[cfg(test)]
+mod tests {
+
+    use std::collections::HashMap;
+
+    use crate::{ResponderConfig, TxtSchema, TxtSchemas, TxtViolation};
+
+    fn attrs(kvs: &[(&str, &str)]) -> HashMap<String, String> {
+        kvs.iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn txt_schema_validate() {
+        struct Test {
+            service_type: &'static str,
+            attrs: Vec<(&'static str, &'static str)>,
+            expected: Vec<TxtViolation>,
+        }
+
+        let schemas = TxtSchemas::with_defaults();
+        let tests = vec![
+            Test {
+                service_type: "_http._tcp",
+                attrs: vec![("path", "/index.html")],
+                expected: vec![],
+            },
+            Test {
+                service_type: "_http._tcp",
+                attrs: vec![("path", "index.html")],
+                expected: vec![TxtViolation::Invalid(
+                    "path".to_string(),
+                    "index.html".to_string(),
+                )],
+            },
+            Test {
+                service_type: "_ipp._tcp",
+                attrs: vec![("txtvers", "1"), ("qtotal", "x")],
+                expected: vec![
+                    TxtViolation::Missing("rp".to_string()),
+                    TxtViolation::Invalid("qtotal".to_string(), "x".to_string()),
+                ],
+            },
+            Test {
+                service_type: "_hap._tcp",
+                attrs: vec![
+                    ("c#", "2"),
+                    ("id", "AA:BB:CC:DD:EE:FF"),
+                    ("md", "Bridge"),
+                    ("s#", "1"),
+                    ("sf", "0"),
+                    ("ci", "2"),
+                ],
+                expected: vec![],
+            },
+            Test {
+                service_type: "_matterc._udp",
+                attrs: vec![("D", "4096"), ("VP", "65521+32769")],
+                expected: vec![TxtViolation::Invalid("D".to_string(), "4096".to_string())],
+            },
+            Test {
+                service_type: "_unknown._tcp",
+                attrs: vec![],
+                expected: vec![],
+            },
+        ];
+
+        for test in tests {
+            assert_eq!(
+                schemas.validate(test.service_type, &attrs(&test.attrs)),
+                test.expected,
+                "{}",
+                test.service_type
+            );
+        }
+    }
+
+    #[test]
+    fn txt_schema_register() {
+        let mut schemas = TxtSchemas::new();
+        schemas.register(TxtSchema::new("_Test._tcp").required("key"));
+        assert!(schemas.schema("_test._tcp").is_some());
+
+        let mut config = ResponderConfig::new();
+        config.set_txt_schemas(schemas);
+        assert!(config
+            .check_attributes("_test._tcp", &attrs(&[("KEY", "")]))
+            .is_ok());
+        assert!(config.check_attributes("_test._tcp", &attrs(&[])).is_err());
+    }
+}
@@ -0,0 +1,147 @@
+// Copyright (C) 2024 Satoshi Konno All rights reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//    http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+#[cfg(test)]
+mod tests {
+
+    use std::collections::HashMap;
+
+    use crate::dns::{Message, QR};
+    use crate::txt;
+    use crate::{RecordTtls, Service, ServiceBuilder};
+
+    #[test]
+    fn txt_keys() {
+        struct Test {
+            key: &'static str,
+            valid: bool,
+            recommended: bool,
+        }
+
+        let tests = vec![
+            Test {
+                key: "path",
+                valid: true,
+                recommended: true,
+            },
+            Test {
+                key: "verylongkey",
+                valid: true,
+                recommended: false,
+            },
+            Test {
+                key: "",
+                valid: false,
+                recommended: false,
+            },
+            Test {
+                key: "a=b",
+                valid: false,
+                recommended: false,
+            },
+            Test {
+                key: "caf\u{e9}",
+                valid: false,
+                recommended: false,
+            },
+        ];
+
+        for test in tests {
+            assert_eq!(txt::is_valid_key(test.key), test.valid, "{}", test.key);
+            assert_eq!(txt::validate_key(test.key).is_ok(), test.valid);
+            assert_eq!(
+                txt::is_recommended_key(test.key),
+                test.recommended,
+                "{}",
+                test.key
+            );
+        }
+    }
+
+    #[test]
+    fn txt_attributes() {
+        assert_eq!(
+            txt::parse_attribute("path=/a=b"),
+            ("path".to_string(), "/a=b".to_string())
+        );
+        assert_eq!(
+            txt::parse_attribute("flag"),
+            ("flag".to_string(), "".to_string())
+        );
+        assert_eq!(txt::format_attribute("path", "/"), "path=/");
+        assert_eq!(txt::format_attribute("flag", ""), "flag");
+
+        let mut attrs = HashMap::new();
+        attrs.insert("TxtVers".to_string(), "1".to_string());
+        attrs.insert("path".to_string(), "/".to_string());
+        assert_eq!(txt::txtvers(&attrs), Some(1));
+        assert_eq!(txt::attribute(&attrs, "PATH"), Some(&"/".to_string()));
+    }
+
+    #[test]
+    fn txt_canonical_order() {
+        let keys = vec!["path", "txtvers", "PATH", "u"];
+        assert_eq!(txt::canonical_order(keys), vec!["txtvers", "path", "u"]);
+    }
+
+    #[test]
+    fn txt_builder_txtvers() {
+        let service = ServiceBuilder::new("Printer", "_ipp._tcp")
+            .attribute("rp", "ipp/print")
+            .attribute("TXTVERS", "2")
+            .txtvers(1)
+            .build()
+            .unwrap();
+        assert_eq!(
+            service.attributes()[0],
+            ("txtvers".to_string(), "1".to_string())
+        );
+        assert_eq!(service.attributes().len(), 2);
+        assert_eq!(service.txtvers(), Some(1));
+        assert_eq!(service.txt_data(), b"\x09txtvers=1\x0crp=ipp/print");
+    }
+
+    #[test]
+    fn txt_binary_attributes() {
+        assert_eq!(
+            txt::parse_attribute_bytes(b"id=\x00\xff="),
+            ("id".to_string(), vec![0x00, 0xff, b'='])
+        );
+        assert_eq!(txt::format_attribute_bytes("id", &[0xfe]), b"id=\xfe");
+        assert_eq!(txt::format_attribute_bytes("flag", &[]), b"flag");
+
+        let id = [0x00, 0x80, 0xff, b'=', 0xc3];
+        let info = ServiceBuilder::new("Light", "_matter._tcp")
+            .attribute("path", "/")
+            .attribute_bytes("id", &id)
+            .build()
+            .unwrap();
+        assert_eq!(info.attribute_bytes("ID"), Some(&id[..]));
+        assert_eq!(info.attribute_bytes("path"), Some(&b"/"[..]));
+        assert!(info.txt_data().windows(id.len()).any(|w| w == id));
+
+        let mut msg = Message::new();
+        msg.set_qr(QR::Response);
+        for record in info.records("light.local", &RecordTtls::new()) {
+            msg.add_answer(record);
+        }
+        let msg = Message::from_bytes(&msg.to_bytes().unwrap()).unwrap();
+        let service = Service::from_message(&msg);
+        assert_eq!(service.attribute_bytes("id"), Some(&id[..]));
+        assert_eq!(service.attribute_bytes("path"), Some(&b"/"[..]));
+        assert_eq!(service.attribute("path"), Some(&"/".to_string()));
+        assert!(service.attribute("id").is_some());
+        assert_eq!(service.attribute_bytes("none"), None);
+    }
+}
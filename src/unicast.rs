@@ -0,0 +1,267 @@
+// Copyright (C) 2024 Satoshi Konno All rights reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//    http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::io::{self, ErrorKind, Read, Write};
+use std::net::{SocketAddr, TcpStream, UdpSocket};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use cybergarage::net::{Observer, Packet};
+
+use crate::discoverer::Discoverer;
+use crate::dns::{
+    Answer, Message, OPTRecord, PTRRecord, Question, QuestionRecord, ResponseCode, Type,
+    EXTENDED_RCODE_BADVERS, QR,
+};
+use crate::llq::message_id;
+use crate::query::Query;
+
+/// DEFAULT_UNICAST_UDP_PAYLOAD_SIZE is the default UDP payload size advertised to the unicast DNS servers.
+pub const DEFAULT_UNICAST_UDP_PAYLOAD_SIZE: u16 = 4096;
+/// DEFAULT_UNICAST_TIMEOUT is the default time limit of a unicast query.
+pub const DEFAULT_UNICAST_TIMEOUT: Duration = Duration::from_secs(3);
+
+/// UnicastResolver represents a resolver of a unicast DNS server, such as a wide-area DNS-SD server or a discovery proxy.
+/// The queries advertise a large UDP payload size with an OPT record, and a truncated response is queried again over TCP.
+pub struct UnicastResolver {
+    server: SocketAddr,
+    udp_payload_size: u16,
+    timeout: Duration,
+}
+
+impl UnicastResolver {
+    /// new creates a new resolver of the specified server.
+    pub fn new(server: SocketAddr) -> UnicastResolver {
+        UnicastResolver {
+            server,
+            udp_payload_size: DEFAULT_UNICAST_UDP_PAYLOAD_SIZE,
+            timeout: DEFAULT_UNICAST_TIMEOUT,
+        }
+    }
+
+    /// server returns the address of the server.
+    pub fn server(&self) -> SocketAddr {
+        self.server
+    }
+
+    /// set_udp_payload_size sets the UDP payload size to advertise.
+    pub fn set_udp_payload_size(&mut self, size: u16) {
+        self.udp_payload_size = size;
+    }
+
+    /// udp_payload_size returns the UDP payload size to advertise.
+    pub fn udp_payload_size(&self) -> u16 {
+        self.udp_payload_size
+    }
+
+    /// set_timeout sets the time limit of each exchange with the server.
+    pub fn set_timeout(&mut self, timeout: Duration) {
+        self.timeout = timeout;
+    }
+
+    /// timeout returns the time limit of each exchange with the server.
+    pub fn timeout(&self) -> Duration {
+        self.timeout
+    }
+
+    /// query queries the records of the specified name and type, and returns the response.
+    /// A name error is returned as the response, and the other response codes including the extended ones are returned as errors.
+    pub fn query(&self, name: &str, typ: Type) -> Result<Message, io::Error> {
+        let mut req = self.query_message(name, typ, true);
+        let mut res = self.exchange_udp(&req)?;
+        // RFC 6891: 7. Transport Considerations
+        // A server without EDNS support responds with FORMERR or NOTIMP and no OPT record, so the query is repeated without it.
+        if OPTRecord::from_message(&res).is_none()
+            && matches!(
+                res.response_code(),
+                ResponseCode::FormatError | ResponseCode::NotImplemented
+            )
+        {
+            req = self.query_message(name, typ, false);
+            res = self.exchange_udp(&req)?;
+        }
+        if res.tc() {
+            res = self.exchange_tcp(&req)?;
+        }
+        match res.extended_response_code() {
+            code if code == ResponseCode::NoError as u16
+                || code == ResponseCode::NameError as u16 => {}
+            EXTENDED_RCODE_BADVERS => {
+                return Err(io::Error::other(format!(
+                    "{} does not support the EDNS version of the query of {}",
+                    self.server, name
+                )))
+            }
+            code => {
+                return Err(io::Error::other(format!(
+                    "query of {} at {} failed ({})",
+                    name, self.server, code
+                )))
+            }
+        }
+        // The response is checked against the question, so the records of another question are not merged.
+        if let Some(question) = req.questions().first() {
+            Answer::from_response(question, &res).map_err(|e| {
+                io::Error::new(
+                    ErrorKind::InvalidData,
+                    format!("{} from {}", e.message(), self.server),
+                )
+            })?;
+        }
+        Ok(res)
+    }
+
+    /// search browses the specified query at the server, and merges the answers into the specified discoverer,
+    /// so they are delivered as the standard service events. The SRV and TXT records of the instances are queried
+    /// unless the server already returned them as the additional records.
+    pub fn search(
+        &self,
+        discoverer: &Arc<Mutex<Discoverer>>,
+        query: &Query,
+    ) -> Result<(), io::Error> {
+        let name = query.to_string();
+        let res = self.query(&name, Type::PTR)?;
+        self.feed(discoverer, &res);
+        let question = Question::new(&name, Type::PTR);
+        let answer = Answer::from_response(&question, &res)
+            .map_err(|e| io::Error::new(ErrorKind::InvalidData, e.message().to_string()))?;
+        let instances: Vec<String> = answer
+            .records()
+            .iter()
+            .filter_map(|record| PTRRecord::from_record(record).ok())
+            .map(|ptr| ptr.domain_name().to_string())
+            .collect();
+        for instance in instances {
+            let resolved = res
+                .additionals()
+                .iter()
+                .any(|record| record.typ() == Type::SRV && record.name() == instance);
+            if resolved {
+                continue;
+            }
+            for typ in [Type::SRV, Type::TXT] {
+                let res = self.query(&instance, typ)?;
+                self.feed(discoverer, &res);
+            }
+        }
+        Ok(())
+    }
+
+    fn query_message(&self, name: &str, typ: Type, edns: bool) -> Message {
+        let mut msg = Message::new();
+        msg.set_id(message_id());
+        msg.set_rd(true);
+        let mut question = QuestionRecord::new();
+        question.set_name(name);
+        question.set_typ(typ);
+        msg.add_question(question);
+        if edns {
+            if let Err(e) = msg.set_opt(&OPTRecord::new(self.udp_payload_size)) {
+                log::warn!("OPT record not attached: {}", e.message());
+            }
+        }
+        msg
+    }
+
+    fn exchange_udp(&self, req: &Message) -> Result<Message, io::Error> {
+        let bind_addr: SocketAddr = match self.server {
+            SocketAddr::V4(_) => "0.0.0.0:0".parse().unwrap(),
+            SocketAddr::V6(_) => "[::]:0".parse().unwrap(),
+        };
+        let socket = UdpSocket::bind(bind_addr)?;
+        socket.connect(self.server)?;
+        socket.send(&to_bytes(req)?)?;
+        let deadline = Instant::now() + self.timeout;
+        let mut buf = vec![0; self.udp_payload_size.max(512) as usize];
+        loop {
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            if remaining.is_zero() {
+                return Err(self.timed_out(req));
+            }
+            socket.set_read_timeout(Some(remaining))?;
+            let n = match socket.recv(&mut buf) {
+                Ok(n) => n,
+                Err(e) if matches!(e.kind(), ErrorKind::WouldBlock | ErrorKind::TimedOut) => {
+                    return Err(self.timed_out(req))
+                }
+                Err(e) => return Err(e),
+            };
+            match Message::from_bytes(&buf[..n]) {
+                Ok(res) if res.is_response() && res.id() == req.id() => return Ok(res),
+                Ok(_) => {}
+                Err(e) => log::debug!("invalid unicast response: {}", e.message()),
+            }
+        }
+    }
+
+    // exchange_tcp sends the query again over TCP, where each message is prefixed with its length.
+    // RFC 7766: 8. Connection Handling
+    fn exchange_tcp(&self, req: &Message) -> Result<Message, io::Error> {
+        let mut stream = TcpStream::connect_timeout(&self.server, self.timeout)?;
+        stream.set_read_timeout(Some(self.timeout))?;
+        stream.set_write_timeout(Some(self.timeout))?;
+        let bytes = to_bytes(req)?;
+        let mut frame = (bytes.len() as u16).to_be_bytes().to_vec();
+        frame.extend_from_slice(&bytes);
+        stream.write_all(&frame)?;
+        let mut len = [0; 2];
+        stream.read_exact(&mut len)?;
+        let mut buf = vec![0; u16::from_be_bytes(len) as usize];
+        stream.read_exact(&mut buf)?;
+        let res = Message::from_bytes(&buf)
+            .map_err(|e| io::Error::new(ErrorKind::InvalidData, e.message().to_string()))?;
+        if res.id() != req.id() {
+            return Err(io::Error::new(
+                ErrorKind::InvalidData,
+                format!("unexpected response id {:x}", res.id()),
+            ));
+        }
+        Ok(res)
+    }
+
+    // feed merges the answers and the additional records of the specified response into the discoverer.
+    fn feed(&self, discoverer: &Arc<Mutex<Discoverer>>, res: &Message) {
+        if res.answers().is_empty() {
+            return;
+        }
+        let mut msg = Message::new();
+        msg.set_qr(QR::Response);
+        for answer in res.answers() {
+            msg.add_answer(answer.clone());
+        }
+        for additional in res.additionals() {
+            if additional.typ() != Type::OPT {
+                msg.add_additional(additional.clone());
+            }
+        }
+        if let Ok(bytes) = msg.to_bytes() {
+            let mut pkt = Packet::from_bytes(&bytes);
+            pkt.set_from(self.server);
+            discoverer.lock().unwrap().packet_received(&pkt);
+        }
+    }
+
+    fn timed_out(&self, req: &Message) -> io::Error {
+        let name = req.questions().first().map(|q| q.name()).unwrap_or("");
+        io::Error::new(
+            ErrorKind::TimedOut,
+            format!("query of {} at {} timed out", name, self.server),
+        )
+    }
+}
+
+fn to_bytes(msg: &Message) -> Result<Vec<u8>, io::Error> {
+    msg.to_bytes()
+        .map_err(|e| io::Error::other(e.message().to_string()))
+}
@@ -0,0 +1,163 @@
+// Copyright (C) 2024 Satoshi Konno All rights reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//    http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+#[cfg(test)]
+mod tests {
+
+    use std::io::{Read, Write};
+    use std::net::{SocketAddr, TcpListener, UdpSocket};
+    use std::thread;
+    use std::time::Duration;
+
+    use crate::dns::{
+        Message, OPTRecord, Question, ResponseCode, Type, EXTENDED_RCODE_BADVERS, QR,
+    };
+    use crate::fixture_test::{ptr_record, srv_record};
+    use crate::{Discoverer, Query, ServiceEvent, UnicastResolver};
+
+    fn response(req: &Message, opt: Option<OPTRecord>) -> Message {
+        let mut msg = Message::new();
+        msg.set_id(req.id());
+        msg.set_qr(QR::Response);
+        for question in req.questions() {
+            msg.add_question(question.clone());
+        }
+        if let Some(opt) = opt {
+            msg.add_additional(opt.to_record().unwrap());
+        }
+        msg
+    }
+
+    fn recv_query(server: &UdpSocket) -> (Message, SocketAddr) {
+        let mut buf = vec![0; 1500];
+        let (n, from) = server.recv_from(&mut buf).unwrap();
+        (Message::from_bytes(&buf[..n]).unwrap(), from)
+    }
+
+    #[test]
+    fn unicast_tcp_fallback() {
+        let server = UdpSocket::bind("127.0.0.1:0").unwrap();
+        server
+            .set_read_timeout(Some(Duration::from_secs(5)))
+            .unwrap();
+        let server_addr = server.local_addr().unwrap();
+        let listener = TcpListener::bind(server_addr).unwrap();
+
+        let server_thread = thread::spawn(move || {
+            // The query advertises the payload size, and the response is truncated.
+            let (req, from) = recv_query(&server);
+            assert!(req.rd());
+            let opt = OPTRecord::from_message(&req).unwrap();
+            assert_eq!(opt.udp_payload_size(), 4096);
+            let mut res = response(&req, Some(OPTRecord::new(1232)));
+            res.set_tc(true);
+            server.send_to(&res.to_bytes().unwrap(), from).unwrap();
+
+            // The same query over TCP, framed with the length.
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut len = [0; 2];
+            stream.read_exact(&mut len).unwrap();
+            let mut buf = vec![0; u16::from_be_bytes(len) as usize];
+            stream.read_exact(&mut buf).unwrap();
+            let req = Message::from_bytes(&buf).unwrap();
+            let mut res = response(&req, Some(OPTRecord::new(1232)));
+            res.add_answer(ptr_record(
+                "_http._tcp.example.com",
+                "first._http._tcp.example.com",
+            ));
+            res.add_additional(srv_record(
+                "first._http._tcp.example.com",
+                80,
+                "host.example.com",
+            ));
+            let bytes = res.to_bytes().unwrap();
+            stream
+                .write_all(&(bytes.len() as u16).to_be_bytes())
+                .unwrap();
+            stream.write_all(&bytes).unwrap();
+        });
+
+        let discoverer = Discoverer::new();
+        let events = discoverer.lock().unwrap().subscribe();
+        let resolver = UnicastResolver::new(server_addr);
+        resolver
+            .search(&discoverer, &Query::with("_http._tcp", "example.com"))
+            .unwrap();
+        match events.try_recv() {
+            Ok(ServiceEvent::Added(service)) => {
+                assert_eq!(service.name(), "first._http._tcp.example.com");
+                assert_eq!(service.port(), 80);
+                assert_eq!(service.source(), Some(server_addr));
+            }
+            _ => panic!("expected an added event"),
+        }
+        server_thread.join().unwrap();
+    }
+
+    #[test]
+    fn unicast_response_codes() {
+        let server = UdpSocket::bind("127.0.0.1:0").unwrap();
+        server
+            .set_read_timeout(Some(Duration::from_secs(5)))
+            .unwrap();
+        let server_addr = server.local_addr().unwrap();
+
+        let server_thread = thread::spawn(move || {
+            // A server without EDNS rejects the OPT record, and answers the query without it.
+            let (req, from) = recv_query(&server);
+            assert!(OPTRecord::from_message(&req).is_some());
+            let mut res = response(&req, None);
+            res.set_response_code(ResponseCode::FormatError);
+            server.send_to(&res.to_bytes().unwrap(), from).unwrap();
+            let (req, from) = recv_query(&server);
+            assert!(OPTRecord::from_message(&req).is_none());
+            let res = response(&req, None);
+            server.send_to(&res.to_bytes().unwrap(), from).unwrap();
+
+            // An extended response code is carried by the OPT record.
+            let (req, from) = recv_query(&server);
+            let mut opt = OPTRecord::new(1232);
+            opt.set_extended_rcode((EXTENDED_RCODE_BADVERS >> 4) as u8);
+            let res = response(&req, Some(opt));
+            server.send_to(&res.to_bytes().unwrap(), from).unwrap();
+
+            // A name error is a negative answer.
+            let (req, from) = recv_query(&server);
+            let mut res = response(&req, Some(OPTRecord::new(1232)));
+            res.set_response_code(ResponseCode::NameError);
+            server.send_to(&res.to_bytes().unwrap(), from).unwrap();
+
+            // A response echoing another question does not answer the query.
+            let (req, from) = recv_query(&server);
+            let mut res = Message::new();
+            res.set_id(req.id());
+            res.set_qr(QR::Response);
+            res.add_question(Question::new("_http._tcp.example.com", Type::SRV));
+            server.send_to(&res.to_bytes().unwrap(), from).unwrap();
+        });
+
+        let mut resolver = UnicastResolver::new(server_addr);
+        resolver.set_timeout(Duration::from_secs(5));
+        let res = resolver.query("_http._tcp.example.com", Type::PTR).unwrap();
+        assert_eq!(res.extended_response_code(), 0);
+        assert!(resolver.query("_http._tcp.example.com", Type::PTR).is_err());
+        let res = resolver.query("_none._tcp.example.com", Type::PTR).unwrap();
+        assert_eq!(res.extended_response_code(), ResponseCode::NameError as u16);
+        match resolver.query("_http._tcp.example.com", Type::PTR) {
+            Ok(_) => panic!("a response of another question was accepted"),
+            Err(e) => assert_eq!(e.kind(), std::io::ErrorKind::InvalidData),
+        }
+        server_thread.join().unwrap();
+    }
+}
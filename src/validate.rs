@@ -0,0 +1,199 @@
+// Copyright (C) 2024 Satoshi Konno All rights reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//    http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::fmt;
+
+use crate::dns::{Message, Opcode, PTRRecord, Record, ResponseCode, SRVRecord, Type, QR};
+
+/// Violation represents a rule of the multicast DNS responses which a message breaks.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Violation {
+    /// NonZeroId is a response whose query identifier is not zero.
+    /// RFC 6762: 18.1. ID (Query Identifier)
+    NonZeroId(u16),
+    /// NotResponse is a message whose QR bit is not set.
+    /// RFC 6762: 18.2. QR (Query/Response) Bit
+    NotResponse,
+    /// NonZeroOpcode is a response whose OPCODE is not zero.
+    /// RFC 6762: 18.3. OPCODE
+    NonZeroOpcode,
+    /// NotAuthoritative is a response whose AA bit is not set.
+    /// RFC 6762: 18.4. AA (Authoritative Answer) Bit
+    NotAuthoritative,
+    /// Truncated is a response whose TC bit is set.
+    /// RFC 6762: 18.5. TC (Truncated) Bit
+    Truncated,
+    /// NonZeroResponseCode is a response whose RCODE is not zero.
+    /// RFC 6762: 18.11. RCODE (Response Code)
+    NonZeroResponseCode,
+    /// QuestionInResponse is a question carried in a response.
+    /// RFC 6762: 6. Responding
+    /// Multicast DNS responses MUST NOT contain any questions in the Question Section.
+    QuestionInResponse(String, Type),
+    /// DuplicateAdditional is an additional record which is already in the answer section.
+    /// RFC 6762: 6. Responding
+    DuplicateAdditional(String, Type),
+    /// UnrelatedAdditional is an additional record which is not related to any answer.
+    /// RFC 6763: 12. DNS Additional Record Generation
+    UnrelatedAdditional(String, Type),
+}
+
+impl fmt::Display for Violation {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Violation::NonZeroId(id) => write!(f, "response ID is not zero ({})", id),
+            Violation::NotResponse => write!(f, "QR bit is not set"),
+            Violation::NonZeroOpcode => write!(f, "OPCODE is not zero"),
+            Violation::NotAuthoritative => write!(f, "AA bit is not set"),
+            Violation::Truncated => write!(f, "TC bit is set"),
+            Violation::NonZeroResponseCode => write!(f, "RCODE is not zero"),
+            Violation::QuestionInResponse(name, typ) => {
+                write!(f, "question in response ({} {})", name, typ)
+            }
+            Violation::DuplicateAdditional(name, typ) => {
+                write!(
+                    f,
+                    "additional record duplicates an answer ({} {})",
+                    name, typ
+                )
+            }
+            Violation::UnrelatedAdditional(name, typ) => {
+                write!(
+                    f,
+                    "additional record is unrelated to the answers ({} {})",
+                    name, typ
+                )
+            }
+        }
+    }
+}
+
+/// validate_multicast_response returns the violations of the specified multicast response, or an empty list if it is valid.
+pub fn validate_multicast_response(msg: &Message) -> Vec<Violation> {
+    let mut violations = Vec::new();
+    if msg.id() != 0 {
+        violations.push(Violation::NonZeroId(msg.id()));
+    }
+    if msg.qr() != QR::Response {
+        violations.push(Violation::NotResponse);
+    }
+    if !matches!(msg.opcode(), Opcode::Query) {
+        violations.push(Violation::NonZeroOpcode);
+    }
+    if !msg.aa() {
+        violations.push(Violation::NotAuthoritative);
+    }
+    if msg.tc() {
+        violations.push(Violation::Truncated);
+    }
+    if !matches!(msg.response_code(), ResponseCode::NoError) {
+        violations.push(Violation::NonZeroResponseCode);
+    }
+    for question in msg.questions() {
+        violations.push(Violation::QuestionInResponse(
+            question.name().to_string(),
+            question.typ(),
+        ));
+    }
+    let related = related_names(msg.answers(), msg.additionals());
+    for record in msg.additionals() {
+        if msg.answers().iter().any(|a| is_same_record(a, record)) {
+            violations.push(Violation::DuplicateAdditional(
+                record.name().to_string(),
+                record.typ(),
+            ));
+        } else if !related
+            .iter()
+            .any(|n| n.eq_ignore_ascii_case(record.name()))
+        {
+            violations.push(Violation::UnrelatedAdditional(
+                record.name().to_string(),
+                record.typ(),
+            ));
+        }
+    }
+    violations
+}
+
+/// reorder_response returns a valid multicast response of the records of the specified message.
+/// The questions are dropped, the additional records already answered or unrelated to the answers are dropped,
+/// and the additional records are ordered after the records they are related to.
+pub fn reorder_response(msg: &Message) -> Message {
+    let mut res = Message::new();
+    res.set_qr(QR::Response);
+    res.set_aa(true);
+    for answer in msg.answers() {
+        res.add_answer(answer.clone());
+    }
+    let mut additionals: Vec<&Record> = Vec::new();
+    for record in msg.additionals() {
+        if msg.answers().iter().any(|a| is_same_record(a, record))
+            || additionals.iter().any(|a| is_same_record(a, record))
+        {
+            continue;
+        }
+        additionals.push(record);
+    }
+    let related = related_names(msg.answers(), msg.additionals());
+    for name in &related {
+        for record in additionals
+            .iter()
+            .filter(|r| r.name().eq_ignore_ascii_case(name))
+        {
+            res.add_additional((*record).clone());
+        }
+    }
+    res
+}
+
+// related_names returns the names of the answers, and the names the answers refer to through the PTR and SRV records in order.
+fn related_names(answers: &[Record], additionals: &[Record]) -> Vec<String> {
+    let mut names: Vec<String> = Vec::new();
+    let add_name = |names: &mut Vec<String>, name: &str| {
+        if !names.iter().any(|n| n.eq_ignore_ascii_case(name)) {
+            names.push(name.to_string());
+        }
+    };
+    for answer in answers {
+        add_name(&mut names, answer.name());
+    }
+    let mut n = 0;
+    while n < names.len() {
+        let name = names[n].clone();
+        for record in answers.iter().chain(additionals.iter()) {
+            if !record.name().eq_ignore_ascii_case(&name) {
+                continue;
+            }
+            match record.typ() {
+                Type::PTR => {
+                    if let Ok(ptr) = PTRRecord::from_record(record) {
+                        add_name(&mut names, ptr.domain_name());
+                    }
+                }
+                Type::SRV => {
+                    if let Ok(srv) = SRVRecord::from_record(record) {
+                        add_name(&mut names, srv.target());
+                    }
+                }
+                _ => {}
+            }
+        }
+        n += 1;
+    }
+    names
+}
+
+fn is_same_record(a: &Record, b: &Record) -> bool {
+    a.typ() == b.typ() && a.name().eq_ignore_ascii_case(b.name()) && a.data() == b.data()
+}
@@ -0,0 +1,80 @@
+// Copyright (C) 2024 Satoshi Konno All rights reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//    http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+#[cfg(test)]
+mod tests {
+
+    use crate::dns::{Message, Question, Type, QR};
+    use crate::fixture_test::{name_data, record, srv_data};
+    use crate::validate::{reorder_response, validate_multicast_response, Violation};
+    use crate::ResponderConfig;
+
+    fn response() -> Message {
+        let mut msg = Message::new();
+        msg.set_qr(QR::Response);
+        msg.set_aa(true);
+        msg.add_answer(record(
+            "_http._tcp.local",
+            Type::PTR,
+            name_data("web._http._tcp.local"),
+        ));
+        msg.add_additional(record(
+            "web._http._tcp.local",
+            Type::SRV,
+            srv_data(80, "host.local"),
+        ));
+        msg.add_additional(record("host.local", Type::A, vec![192, 168, 1, 10]));
+        msg
+    }
+
+    #[test]
+    fn validate_response() {
+        let msg = Message::from_bytes(&response().to_bytes().unwrap()).unwrap();
+        assert!(validate_multicast_response(&msg).is_empty());
+
+        let mut msg = response();
+        msg.set_id(1);
+        msg.set_aa(false);
+        msg.add_question(Question::new("_http._tcp.local", Type::PTR));
+        msg.add_additional(record("other.local", Type::A, vec![192, 168, 1, 20]));
+        msg.add_additional(record(
+            "_http._tcp.local",
+            Type::PTR,
+            name_data("web._http._tcp.local"),
+        ));
+        let msg = Message::from_bytes(&msg.to_bytes().unwrap()).unwrap();
+        assert_eq!(
+            validate_multicast_response(&msg),
+            vec![
+                Violation::NonZeroId(1),
+                Violation::NotAuthoritative,
+                Violation::QuestionInResponse("_http._tcp.local".to_string(), Type::PTR),
+                Violation::UnrelatedAdditional("other.local".to_string(), Type::A),
+                Violation::DuplicateAdditional("_http._tcp.local".to_string(), Type::PTR),
+            ]
+        );
+
+        let mut config = ResponderConfig::new();
+        assert!(config.check_response(&msg).is_ok());
+        config.set_strict(true);
+        assert!(config.check_response(&msg).is_err());
+
+        let res = reorder_response(&msg);
+        assert!(validate_multicast_response(&res).is_empty());
+        assert_eq!(res.answers().len(), 1);
+        let additionals: Vec<Type> = res.additionals().iter().map(|r| r.typ()).collect();
+        assert_eq!(additionals, vec![Type::SRV, Type::A]);
+        assert!(config.check_response(&res).is_ok());
+    }
+}
@@ -0,0 +1,260 @@
+// Copyright (C) 2024 Satoshi Konno All rights reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//    http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::net::SocketAddr;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use cybergarage::net::{ObserverObject, Packet};
+
+use crate::discoverer::Discoverer;
+
+/// HostId identifies a host on the virtual network.
+pub type HostId = usize;
+
+const IDLE_STEP_LIMIT: usize = 10000;
+
+/// VirtualLink represents the link attaching a host to the virtual network.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct VirtualLink {
+    loss: f64,
+    latency: Duration,
+}
+
+impl VirtualLink {
+    /// new creates a lossless link without latency.
+    pub fn new() -> VirtualLink {
+        VirtualLink::with(0.0, Duration::ZERO)
+    }
+
+    /// with creates a link of the specified packet loss ratio in 0.0..=1.0 and latency.
+    pub fn with(loss: f64, latency: Duration) -> VirtualLink {
+        VirtualLink {
+            loss: loss.clamp(0.0, 1.0),
+            latency,
+        }
+    }
+
+    /// loss returns the packet loss ratio of the link.
+    pub fn loss(&self) -> f64 {
+        self.loss
+    }
+
+    /// latency returns the latency of the link.
+    pub fn latency(&self) -> Duration {
+        self.latency
+    }
+}
+
+impl Default for VirtualLink {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// Host represents a host attached to the virtual network.
+struct Host {
+    addr: SocketAddr,
+    observer: Option<ObserverObject>,
+    link: VirtualLink,
+    outbox: Arc<Mutex<Vec<Vec<u8>>>>,
+}
+
+// InFlight represents a packet on the way to a host.
+struct InFlight {
+    deliver_at: Duration,
+    seq: u64,
+    to: HostId,
+    from: SocketAddr,
+    bytes: Vec<u8>,
+}
+
+/// VirtualNetwork represents a simulated multicast link of multiple hosts with per-link packet loss and latency,
+/// driven by a virtual clock and a seeded random generator, so the multi-host behaviors are reproducible in tests.
+/// A packet sent by a host is delivered to every other host after the latencies of both links,
+/// unless it is lost on either link.
+pub struct VirtualNetwork {
+    hosts: Vec<Host>,
+    in_flight: Vec<InFlight>,
+    now: Duration,
+    seq: u64,
+    rng: u64,
+    delivered: u64,
+    dropped: u64,
+}
+
+impl VirtualNetwork {
+    /// new creates an empty network of the specified random seed.
+    pub fn new(seed: u64) -> VirtualNetwork {
+        VirtualNetwork {
+            hosts: Vec::new(),
+            in_flight: Vec::new(),
+            now: Duration::ZERO,
+            seq: 0,
+            rng: seed.max(1),
+            delivered: 0,
+            dropped: 0,
+        }
+    }
+
+    /// add_host adds a host of the specified address, which delivers the received packets to the observer if any.
+    pub fn add_host(&mut self, addr: SocketAddr, observer: Option<ObserverObject>) -> HostId {
+        self.hosts.push(Host {
+            addr,
+            observer,
+            link: VirtualLink::new(),
+            outbox: Arc::new(Mutex::new(Vec::new())),
+        });
+        self.hosts.len() - 1
+    }
+
+    /// add_discoverer adds a host running the specified discoverer, whose outgoing packets are sent on the network
+    /// through the outgoing hook instead of the transport, which should not be started.
+    pub fn add_discoverer(
+        &mut self,
+        addr: SocketAddr,
+        discoverer: &Arc<Mutex<Discoverer>>,
+    ) -> HostId {
+        let observer: ObserverObject = discoverer.clone();
+        let id = self.add_host(addr, Some(observer));
+        let outbox = self.hosts[id].outbox.clone();
+        discoverer
+            .lock()
+            .unwrap()
+            .set_outgoing_hook(Box::new(move |pkt| {
+                outbox.lock().unwrap().push(pkt.bytes().to_vec());
+                true
+            }));
+        id
+    }
+
+    /// set_link sets the link of the specified host.
+    pub fn set_link(&mut self, host: HostId, link: VirtualLink) {
+        self.hosts[host].link = link;
+    }
+
+    /// addr returns the address of the specified host.
+    pub fn addr(&self, host: HostId) -> SocketAddr {
+        self.hosts[host].addr
+    }
+
+    /// send multicasts the specified packet bytes from the specified host now.
+    pub fn send(&mut self, from: HostId, bytes: &[u8]) {
+        for to in 0..self.hosts.len() {
+            if to == from {
+                continue;
+            }
+            let sender = self.hosts[from].link;
+            let receiver = self.hosts[to].link;
+            if self.next_random() < sender.loss || self.next_random() < receiver.loss {
+                self.dropped += 1;
+                continue;
+            }
+            self.seq += 1;
+            self.in_flight.push(InFlight {
+                deliver_at: self.now + sender.latency + receiver.latency,
+                seq: self.seq,
+                to,
+                from: self.hosts[from].addr,
+                bytes: bytes.to_vec(),
+            });
+        }
+    }
+
+    /// now returns the virtual time elapsed since the network was created.
+    pub fn now(&self) -> Duration {
+        self.now
+    }
+
+    /// in_flight returns the number of the packets not delivered yet.
+    pub fn in_flight(&self) -> usize {
+        self.in_flight.len()
+    }
+
+    /// delivered returns the number of the delivered packets.
+    pub fn delivered(&self) -> u64 {
+        self.delivered
+    }
+
+    /// dropped returns the number of the packets lost on the links.
+    pub fn dropped(&self) -> u64 {
+        self.dropped
+    }
+
+    /// advance advances the virtual clock by the specified duration, and delivers the packets due in order,
+    /// including the packets the hosts send in response.
+    pub fn advance(&mut self, duration: Duration) {
+        let until = self.now + duration;
+        self.flush_outboxes();
+        while let Some(n) = self.next_due(until) {
+            let pkt = self.in_flight.remove(n);
+            self.now = pkt.deliver_at;
+            self.deliver(pkt);
+            self.flush_outboxes();
+        }
+        self.now = until;
+    }
+
+    /// run_until_idle delivers the packets until none is in flight, and returns false if the network never became idle.
+    pub fn run_until_idle(&mut self) -> bool {
+        self.flush_outboxes();
+        for _ in 0..IDLE_STEP_LIMIT {
+            let deliver_at = match self.in_flight.iter().map(|p| p.deliver_at).min() {
+                Some(deliver_at) => deliver_at,
+                None => return true,
+            };
+            self.advance(deliver_at.saturating_sub(self.now));
+        }
+        self.in_flight.is_empty()
+    }
+
+    fn next_due(&self, until: Duration) -> Option<usize> {
+        self.in_flight
+            .iter()
+            .enumerate()
+            .filter(|(_, p)| p.deliver_at <= until)
+            .min_by_key(|(_, p)| (p.deliver_at, p.seq))
+            .map(|(n, _)| n)
+    }
+
+    fn deliver(&mut self, in_flight: InFlight) {
+        let observer = match &self.hosts[in_flight.to].observer {
+            Some(observer) => observer.clone(),
+            None => return,
+        };
+        let mut pkt = Packet::from_bytes(&in_flight.bytes);
+        pkt.set_from(in_flight.from);
+        observer.lock().unwrap().packet_received(&pkt);
+        self.delivered += 1;
+    }
+
+    // flush_outboxes sends the packets which the hosts have sent since the last flush.
+    fn flush_outboxes(&mut self) {
+        for from in 0..self.hosts.len() {
+            let sent: Vec<Vec<u8>> = self.hosts[from].outbox.lock().unwrap().drain(..).collect();
+            for bytes in sent {
+                self.send(from, &bytes);
+            }
+        }
+    }
+
+    // next_random returns the next pseudo random number in 0.0..1.0 by xorshift64*.
+    fn next_random(&mut self) -> f64 {
+        self.rng ^= self.rng >> 12;
+        self.rng ^= self.rng << 25;
+        self.rng ^= self.rng >> 27;
+        let n = self.rng.wrapping_mul(0x2545_F491_4F6C_DD1D);
+        (n >> 11) as f64 / (1u64 << 53) as f64
+    }
+}
@@ -0,0 +1,109 @@
+// Copyright (C) 2024 Satoshi Konno All rights reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//    http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+#[cfg(test)]
+mod tests {
+
+    use std::time::Duration;
+
+    use crate::dns::Message;
+    use crate::fixture_test::{srv_record, unique};
+    use crate::{Discoverer, Query, ServiceEvent, VirtualLink, VirtualNetwork};
+
+    fn srv_response_bytes(name: &str, target: &str) -> Vec<u8> {
+        let mut msg = Message::new();
+        msg.add_answer(unique(srv_record(name, 80, target)));
+        msg.to_bytes().unwrap()
+    }
+
+    #[test]
+    fn virtual_network_latency_and_loss() {
+        let mut network = VirtualNetwork::new(1);
+        let discoverer = Discoverer::new();
+        let events = discoverer.lock().unwrap().subscribe();
+        let browser = network.add_discoverer("192.168.1.1:5353".parse().unwrap(), &discoverer);
+        let host = network.add_host("192.168.1.10:5353".parse().unwrap(), None);
+        let lossy = network.add_host("192.168.1.20:5353".parse().unwrap(), None);
+        network.set_link(host, VirtualLink::with(0.0, Duration::from_millis(100)));
+        network.set_link(lossy, VirtualLink::with(1.0, Duration::ZERO));
+
+        network.send(
+            host,
+            &srv_response_bytes("web._http._tcp.local", "host.local"),
+        );
+        network.send(
+            lossy,
+            &srv_response_bytes("ipp._ipp._tcp.local", "lossy.local"),
+        );
+        // The lossy host loses both its own packet and the one sent to it.
+        assert_eq!(network.dropped(), 3);
+
+        network.advance(Duration::from_millis(50));
+        assert!(events.try_recv().is_err());
+        network.advance(Duration::from_millis(50));
+        match events.try_recv() {
+            Ok(ServiceEvent::Added(service)) => assert_eq!(service.host(), "host.local"),
+            _ => panic!("expected an added event"),
+        }
+        assert_eq!(network.now(), Duration::from_millis(100));
+        // The host itself does not receive its own packet, and the other hosts have no observers.
+        assert_eq!(network.delivered(), 1);
+        assert_eq!(network.addr(browser).port(), 5353);
+    }
+
+    #[test]
+    fn virtual_network_multi_host() {
+        let mut network = VirtualNetwork::new(1);
+        let discoverer = Discoverer::new();
+        let events = discoverer.lock().unwrap().subscribe();
+        let other = Discoverer::new();
+        network.add_discoverer("192.168.1.1:5353".parse().unwrap(), &discoverer);
+        network.add_discoverer("192.168.1.2:5353".parse().unwrap(), &other);
+        let host1 = network.add_host("192.168.1.10:5353".parse().unwrap(), None);
+        let host2 = network.add_host("192.168.1.20:5353".parse().unwrap(), None);
+
+        // Conflict resolution
+        network.send(
+            host1,
+            &srv_response_bytes("web._http._tcp.local", "host1.local"),
+        );
+        network.send(
+            host2,
+            &srv_response_bytes("web._http._tcp.local", "host2.local"),
+        );
+        assert!(network.run_until_idle());
+        assert!(matches!(events.try_recv(), Ok(ServiceEvent::Added(_))));
+        assert!(matches!(
+            events.try_recv(),
+            Ok(ServiceEvent::ConflictDetected(_))
+        ));
+
+        // Duplicate suppression
+        let bytes = srv_response_bytes("ipp._ipp._tcp.local", "host1.local");
+        network.send(host1, &bytes);
+        network.send(host2, &bytes);
+        assert!(network.run_until_idle());
+        assert_eq!(
+            discoverer.lock().unwrap().stats().duplicates_suppressed(),
+            1
+        );
+
+        // The queries of a discoverer are sent on the network,
+        // including the query reconfirming the conflicted record.
+        let query = Query::with("_http._tcp", "local");
+        assert!(discoverer.lock().unwrap().search(&query).is_ok());
+        assert!(network.run_until_idle());
+        assert_eq!(other.lock().unwrap().stats().packets_received(), 6);
+    }
+}